@@ -2,19 +2,30 @@
 
 const DEFAULT_WARMUP = 10;
 const DEFAULT_ITERATIONS = 100;
+// Growth across a session above which the memory timeline is flagged as a
+// possible leak. Matches `memory::DEFAULT_GROWTH_THRESHOLD_BYTES` in the core.
+const MEMORY_GROWTH_THRESHOLD_BYTES = 64 * 1024 * 1024;
 
 const state = {
     benchmarks: [],
     results: new Map(),
+    failures: new Map(),         // id -> error message for benchmarks that failed (e.g. GPU timeout)
+    memoryTimeline: [],          // memory samples taken between benchmarks, if enabled
+    runStartedMs: null,
+    runFinishedMs: null,
+    runSettings: null,           // timing settings and filter of the last run, for exports
     selectedBenchmarks: [],
     queuedBenchmarks: new Set(),
     runningBenchmark: null,
     runningPhase: null,
+    runningProgress: null,       // iterations of the running phase, e.g. '40/100'
     activeTab: 'micro', // 'micro' or 'scene'
     currentCategory: 'all',
+    filterIds: null,             // IDs matching the filter expression, or null for no filter
     expandedCategories: new Set(),
     isRunning: false,
     abortRequested: false,
+    cancelHandle: null,          // JsCancellationHandle of the running main-thread benchmark
     isTauri: false,
     wasmWorker: null,
     wasmSimdLevel: 'scalar',
@@ -30,6 +41,7 @@ const state = {
     mainThreadWasm: null,
     hybridCanvas: null,
     hybridInitialized: false,
+    health: null,                // health report of this browser, checked on load
 };
 
 // Returns true if the given category belongs to the "scene" tab.
@@ -108,6 +120,9 @@ function createWasmWorker() {
         if (!state.pendingWasmResolve) return;
 
         switch (type) {
+            case 'progress':
+                showProgress(data.id, data.event);
+                break;
             case 'result':
                 state.pendingWasmResolve(data.result);
                 state.pendingWasmResolve = null;
@@ -121,6 +136,10 @@ function createWasmWorker() {
                 state.pendingWasmResolve(data.benchmarks);
                 state.pendingWasmResolve = null;
                 break;
+            case 'memory':
+                state.pendingWasmResolve(data.sample);
+                state.pendingWasmResolve = null;
+                break;
         }
     };
 
@@ -201,9 +220,35 @@ function initHybridRenderer() {
     }
 }
 
+// Check whether this browser is fit for benchmarking and flag it in the header
+// if a check warns or fails. Runs after `initHybridRenderer`, whose outcome
+// the GPU check reports.
+function checkHealth() {
+    try {
+        state.health = state.mainThreadWasm.health_check([]);
+    } catch (e) {
+        console.error('Health check failed:', e);
+        return;
+    }
+
+    const problems = state.health.checks.filter(
+        c => c.status === 'warn' || c.status === 'fail'
+    );
+    const span = document.getElementById('bench-health');
+    if (problems.length === 0) {
+        span.style.display = 'none';
+        return;
+    }
+    const failed = problems.some(c => c.status === 'fail');
+    span.textContent = failed ? 'health checks failed' : 'health warnings';
+    span.title = problems.map(c => `${c.kind}: ${c.status} (${c.message})`).join('\n');
+    span.style.display = '';
+}
+
 // Check if a benchmark ID is a hybrid scene benchmark (needs WebGL on main thread)
 function isHybridBenchmark(id) {
-    return id.startsWith('scene_hybrid/') || id.startsWith('vello_hybrid/');
+    return id.startsWith('scene_hybrid/') || id.startsWith('vello_hybrid/') ||
+        id.startsWith('hybrid_internals/');
 }
 
 async function switchWasmSimdLevel(level) {
@@ -252,6 +297,7 @@ async function init() {
     const mainLoaded = await loadMainThreadWasm();
     if (mainLoaded) {
         initHybridRenderer();
+        checkHealth();
     }
 
     await loadSimdLevels();
@@ -259,6 +305,8 @@ async function init() {
     await loadReferencesList();
     setupEventListeners();
     setupScreenshotDialogListeners();
+    setupCanvasesDialogListeners();
+    setupAboutDialogListeners();
     updateSkiaBadge();
     updateColumnHeaders();
 }
@@ -293,10 +341,31 @@ async function loadBenchmarks() {
         if (state.executionMode === 'native' && state.isTauri) {
             state.benchmarks = await invoke('list_benchmarks');
         } else if (state.wasmWorker) {
+            // Show the names right away, then fill in `measures` and
+            // availability one category at a time. Both steps are timed in
+            // the console, to see what the fast list saves on startup.
+            const listStart = performance.now();
             state.benchmarks = await new Promise((resolve) => {
                 state.pendingWasmResolve = resolve;
-                state.wasmWorker.postMessage({ type: 'list' });
-            });
+                state.wasmWorker.postMessage({ type: 'list', fast: true });
+            }) ?? [];
+            renderCategories(Array.from(getCategorySet()));
+            renderBenchmarks();
+            const listMs = performance.now() - listStart;
+
+            const details = new Map();
+            for (const category of new Set(state.benchmarks.map(b => b.category))) {
+                const entries = await new Promise((resolve) => {
+                    state.pendingWasmResolve = resolve;
+                    state.wasmWorker.postMessage({ type: 'category-details', category });
+                });
+                for (const entry of entries ?? []) details.set(entry.id, entry);
+            }
+            state.benchmarks = state.benchmarks.map(b => details.get(b.id) ?? b);
+            console.info(
+                `Benchmark list shown after ${listMs.toFixed(1)} ms, ` +
+                `details complete after ${(performance.now() - listStart).toFixed(1)} ms`
+            );
         } else {
             state.benchmarks = [];
         }
@@ -399,13 +468,41 @@ function getFilteredBenchmarks() {
         return state.activeTab === 'scene' ? scene : !scene;
     });
 
-    if (state.currentCategory === 'all') return tabFiltered;
-    return tabFiltered.filter(b =>
+    const exprFiltered = state.filterIds
+        ? tabFiltered.filter(b => state.filterIds.has(b.id))
+        : tabFiltered;
+
+    if (state.currentCategory === 'all') return exprFiltered;
+    return exprFiltered.filter(b =>
         b.category === state.currentCategory ||
         b.category.startsWith(state.currentCategory + '/')
     );
 }
 
+// Apply a filter expression (see vello_bench_core::Filter) to the table. The
+// expression is parsed by the WASM module so it behaves exactly like
+// `vello_bench list --filter`. Invalid expressions leave the table unfiltered
+// and show the parse error on the input.
+function applyFilterExpression(expr) {
+    const input = document.getElementById('filter-expr');
+    if (!expr.trim() || !state.mainThreadWasm) {
+        state.filterIds = null;
+        input.classList.remove('invalid');
+        input.title = 'Same syntax as vello_bench list --filter';
+    } else {
+        try {
+            state.filterIds = new Set(state.mainThreadWasm.filter_benchmark_ids(expr));
+            input.classList.remove('invalid');
+            input.title = `${state.filterIds.size} matching benchmarks`;
+        } catch (e) {
+            state.filterIds = null;
+            input.classList.add('invalid');
+            input.title = String(e);
+        }
+    }
+    renderBenchmarks();
+}
+
 function renderBenchmarks() {
     const tbody = document.getElementById('benchmark-tbody');
     const filtered = getFilteredBenchmarks();
@@ -417,7 +514,7 @@ function renderBenchmarks() {
     }
 
     if (filtered.length === 0) {
-        tbody.innerHTML = '<tr><td colspan="8" class="no-results">No benchmarks available.</td></tr>';
+        tbody.innerHTML = '<tr><td colspan="9" class="no-results">No benchmarks available.</td></tr>';
         return;
     }
 
@@ -433,30 +530,56 @@ function renderBenchmarks() {
         let status = 'idle';
         let statusText = 'idle';
         if (state.runningBenchmark === bench.id) {
-            status = state.runningPhase === 'warming up' ? 'calibrating' : 'running';
-            statusText = state.runningPhase;
+            status = state.runningPhase === 'measuring' ? 'running' : 'calibrating';
+            statusText = state.runningProgress
+                ? `${state.runningPhase} ${state.runningProgress}`
+                : state.runningPhase;
+        } else if (state.queuedBenchmarks.has(bench.id) && isEstimate(currentResult)) {
+            status = 'queued';
+            statusText = 'estimated';
         } else if (state.queuedBenchmarks.has(bench.id)) {
             status = 'queued';
             statusText = 'queued';
+        } else if (state.failures.has(bench.id)) {
+            status = 'failed';
+            statusText = 'failed';
         } else if (currentResult) {
             status = 'completed';
-            statusText = 'done';
+            statusText = currentResult.cancelled ? 'cancelled' : 'done';
+        } else if (bench.unavailable_reason) {
+            status = 'unavailable';
+            statusText = 'unavailable';
         }
+        const statusTitle = status === 'failed' ? state.failures.get(bench.id)
+            : status === 'unavailable' ? bench.unavailable_reason
+            : null;
+
+        // Single-shot estimates are prefixed with "~".
+        const formatResult = (result) => {
+            const { mean, unit } = formatTime(result.statistics.mean_ns);
+            return `${isEstimate(result) ? '~' : ''}${mean.toFixed(3)} ${unit}`;
+        };
 
-        const baseStr = baseResult
-            ? (() => { const { mean, unit } = formatTime(baseResult.statistics.mean_ns); return `${mean.toFixed(3)} ${unit}`; })()
-            : '-';
+        const baseStr = baseResult ? formatResult(baseResult) : '-';
+        const baseSpread = baseResult
+            ? [formatSpread(baseResult), formatWarmup(baseResult)].filter(Boolean).join('\n') || null
+            : null;
 
         let compareStr = '-';
         let changeStr = '-';
         let changeClass = '';
 
         if (compareResult) {
-            const { mean, unit } = formatTime(compareResult.statistics.mean_ns);
-            compareStr = `${mean.toFixed(3)} ${unit}`;
+            compareStr = formatResult(compareResult);
         }
 
-        if (baseResult && compareResult) {
+        // Estimates are too noisy to serve as a baseline, and GPU frames
+        // synced differently aren't comparable.
+        const syncDiffers = baseResult && compareResult &&
+            (baseResult.sync_mode ?? 'Finish') !== (compareResult.sync_mode ?? 'Finish');
+        if (syncDiffers) {
+            changeStr = 'sync differs';
+        } else if (baseResult && compareResult && !isEstimate(baseResult)) {
             const comparison = calculateComparison(compareResult.statistics.mean_ns, baseResult.statistics.mean_ns);
             if (comparison) {
                 const sign = comparison.percentChange > 0 ? '+' : '';
@@ -474,6 +597,11 @@ function renderBenchmarks() {
             }
         }
 
+        // Memory after this benchmark, if it was sampled in the last run.
+        const memorySample = state.memoryTimeline.findLast(s => s.after === bench.id);
+        const memoryTotalBytes = memorySample ? memoryTotal(memorySample) : null;
+        const memoryStr = memoryTotalBytes != null ? formatBytes(memoryTotalBytes) : '-';
+
         const rowClasses = [status];
         if (isSelected) rowClasses.push('selected');
 
@@ -484,14 +612,17 @@ function renderBenchmarks() {
                 <td class="col-select">
                     <input type="checkbox" class="row-checkbox" ${isSelected ? 'checked' : ''} ${state.isRunning ? 'disabled' : ''}>
                 </td>
-                <td class="col-name">${bench.name}</td>
+                <td class="col-name"${bench.measures ? ` title="Measures: ${bench.measures}"` : ''}>${bench.name}</td>
                 <td class="col-category">${bench.category}</td>
-                <td class="col-status"><span class="status-badge ${status}">${statusText}</span></td>
-                <td class="col-mean"><span class="result-mean">${baseStr}</span></td>
+                <td class="col-status"><span class="status-badge ${status}"${statusTitle ? ` title="${statusTitle}"` : ''}>${statusText}</span></td>
+                <td class="col-mean"><span class="result-mean"${baseSpread ? ` title="${baseSpread}"` : ''}>${baseStr}</span></td>
                 <td class="col-ref"><span class="result-ref">${compareStr}</span></td>
                 <td class="col-change"><span class="result-change ${changeClass}">${changeStr}</span></td>
+                <td class="col-memory">${memoryStr}</td>
                 <td class="col-actions">${isScene
                     ? `<button class="screenshot-btn" data-screenshot="${bench.id}" title="Capture screenshot">&#128247;</button>`
+                    : ''}${canMeasureCanvases(bench.id)
+                    ? `<button class="screenshot-btn" data-canvases="${bench.id}" title="Measure multi-canvas overhead">&#8862;</button>`
                     : ''}</td>
             </tr>
         `;
@@ -510,6 +641,45 @@ function formatTime(meanNs) {
     }
 }
 
+// Spread of the iteration times of a result, or null if it has none (no
+// per-iteration samples, or from before the spread was recorded), followed by
+// the outliers left out of it, if any.
+function formatSpread(result) {
+    const { median_ns, p95_ns, min_ns, max_ns, std_dev_ns } = result.statistics;
+    if (median_ns == null || (min_ns === max_ns && !std_dev_ns)) {
+        return null;
+    }
+    const fmt = (ns) => {
+        const { mean, unit } = formatTime(ns);
+        return `${mean.toFixed(3)} ${unit}`;
+    };
+    let spread = `median ${fmt(median_ns)}, p95 ${fmt(p95_ns)}, min ${fmt(min_ns)}, `
+        + `max ${fmt(max_ns)}, std dev ${fmt(std_dev_ns)}`;
+    if (result.outliers_removed) {
+        spread += `; ${result.outliers_removed} outliers removed `
+            + `(mean with them ${fmt(result.raw_statistics.mean_ns)})`;
+    }
+    return spread;
+}
+
+// Times of the warm-up of a result, with its first and last iteration if they
+// were timed on their own, or null if it recorded none.
+function formatWarmup(result) {
+    const warmup = result.warmup;
+    if (!warmup) {
+        return null;
+    }
+    const fmt = (ns) => {
+        const { mean, unit } = formatTime(ns);
+        return `${mean.toFixed(3)} ${unit}`;
+    };
+    let text = `warm-up: ${warmup.iterations} iterations, mean ${fmt(warmup.mean_ns)}`;
+    if (warmup.samples?.length > 1) {
+        text += `, first ${fmt(warmup.samples[0])}, last ${fmt(warmup.samples[warmup.samples.length - 1])}`;
+    }
+    return text;
+}
+
 function updateStats() {
     const tabFiltered = state.benchmarks.filter(b => {
         const scene = isSceneCategory(b.category);
@@ -520,6 +690,13 @@ function updateStats() {
         `${tabFiltered.length} benchmarks`;
     document.getElementById('bench-completed').textContent =
         `${completedCount} completed`;
+
+    const memory = document.getElementById('bench-memory');
+    const growth = state.isRunning ? null : detectMemoryGrowth(state.memoryTimeline);
+    memory.textContent = growth
+        ? `memory grew ${formatBytes(growth.growth)} over ${growth.samples} samples`
+        : '';
+    memory.style.display = growth ? '' : 'none';
 }
 
 function getTimingConfig() {
@@ -528,11 +705,46 @@ function getTimingConfig() {
     return { warmup, iterations };
 }
 
-async function runSingleBenchmark(id) {
+function isEstimate(result) {
+    return result?.measurement_mode === 'SingleShot';
+}
+
+// Show the progress event of a running benchmark in its status cell.
+function showProgress(id, event) {
+    if (state.runningBenchmark !== id) return;
+    state.runningPhase = event.phase === 'warmup' ? 'warming up' : 'measuring';
+    state.runningProgress = event.total != null
+        ? `${event.completed}/${event.total}`
+        : `${event.completed}`;
+    renderBenchmarks();
+}
+
+// Store the outcome of a benchmark run. Failed outcomes (e.g. a GPU timeout)
+// carry `status: 'failed'` and an `error` message instead of statistics.
+function recordOutcome(id, result) {
+    if (!result) return;
+    if (result.status === 'failed') {
+        console.error(`Benchmark ${id} failed: ${result.error}`);
+        state.results.delete(id);
+        state.failures.set(id, result.error);
+        return;
+    }
+    state.failures.delete(id);
+    state.results.set(id, result);
+}
+
+// Run one benchmark. With `singleShot`, warm-up is skipped and a single
+// iteration is timed; the result is marked as an estimate.
+async function runSingleBenchmark(id, { singleShot = false } = {}) {
     const simdLevel = document.getElementById('simd-level').value;
-    const { warmup, iterations } = getTimingConfig();
+    const { warmup, iterations } = singleShot
+        ? { warmup: 0, iterations: 1 }
+        : getTimingConfig();
 
     if (state.executionMode === 'native' && state.isTauri) {
+        if (singleShot) {
+            return await invoke('estimate_benchmark', { id, simdLevel });
+        }
         return await invoke('run_benchmark', { id, simdLevel, warmup, iterations });
     }
 
@@ -540,12 +752,30 @@ async function runSingleBenchmark(id) {
     if (isHybridBenchmark(id) && state.hybridInitialized && state.mainThreadWasm) {
         // Yield to let the UI update before blocking the main thread
         await new Promise(resolve => setTimeout(resolve, 0));
-        // Programmatic vello scenes use a different entry point
-        if (id.startsWith('vello_hybrid/')) {
-            const result = state.mainThreadWasm.run_vello_hybrid_benchmark(id, warmup, iterations);
-            return result;
+        let result;
+        const cancel = new state.mainThreadWasm.JsCancellationHandle();
+        const syncMode = document.getElementById('gpu-sync').value;
+        state.cancelHandle = cancel;
+        try {
+            // Programmatic vello scenes use a different entry point. Fences
+            // are only signaled between tasks, so they need the async one.
+            if (id.startsWith('vello_hybrid/') && syncMode === 'fence') {
+                result = await state.mainThreadWasm.run_vello_hybrid_benchmark_async(id, warmup, iterations, cancel, syncMode);
+            } else if (id.startsWith('vello_hybrid/')) {
+                result = state.mainThreadWasm.run_vello_hybrid_benchmark(id, warmup, iterations, cancel);
+            } else if (id.startsWith('hybrid_internals/')) {
+                result = state.mainThreadWasm.run_hybrid_internals_benchmark(id, warmup, iterations);
+            } else {
+                // Yields to the browser between frames, so Abort works mid-run.
+                result = await state.mainThreadWasm.run_hybrid_benchmark_async(id, warmup, iterations, cancel, syncMode);
+            }
+        } finally {
+            state.cancelHandle = null;
+            cancel.free();
+        }
+        if (result && singleShot) {
+            result.measurement_mode = 'SingleShot';
         }
-        const result = state.mainThreadWasm.run_hybrid_benchmark(id, warmup, iterations);
         return result;
     }
 
@@ -553,32 +783,122 @@ async function runSingleBenchmark(id) {
     if (state.wasmWorker) {
         return new Promise((resolve) => {
             state.pendingWasmResolve = resolve;
-            state.wasmWorker.postMessage({ type: 'run', id, warmup, iterations });
+            state.wasmWorker.postMessage({ type: 'run', id, warmup, iterations, singleShot });
         });
     }
     return null;
 }
 
+// Sample memory usage after benchmark `after` finished. In the browser, the
+// worker and the main-thread module each have their own linear memory; both
+// are added up. Must only be called between benchmarks.
+async function sampleMemory(after) {
+    if (state.executionMode === 'native' && state.isTauri) {
+        return await invoke('sample_memory', { after });
+    }
+
+    const samples = [];
+    if (state.wasmWorker) {
+        samples.push(await new Promise((resolve) => {
+            state.pendingWasmResolve = resolve;
+            state.wasmWorker.postMessage({ type: 'sample-memory', after });
+        }));
+    }
+    if (state.mainThreadWasm) {
+        samples.push(state.mainThreadWasm.sample_memory(after));
+    }
+    const valid = samples.filter(Boolean);
+    if (valid.length === 0) return null;
+
+    const sum = (key) => valid.some(s => s[key] != null)
+        ? valid.reduce((acc, s) => acc + (s[key] ?? 0), 0)
+        : undefined;
+    return {
+        after,
+        timestamp_ms: Date.now(),
+        wasm_memory_bytes: sum('wasm_memory_bytes'),
+        // The JS heap is per agent; take the largest reading rather than summing.
+        js_heap_bytes: valid.some(s => s.js_heap_bytes != null)
+            ? Math.max(...valid.map(s => s.js_heap_bytes ?? 0))
+            : undefined,
+    };
+}
+
+// The figure tracked for growth: RSS on native, linear memory plus JS heap in
+// the browser. Mirrors `MemorySample::total_bytes` in the core.
+function memoryTotal(sample) {
+    if (sample.rss_bytes != null) return sample.rss_bytes;
+    if (sample.wasm_memory_bytes == null && sample.js_heap_bytes == null) return null;
+    return (sample.wasm_memory_bytes ?? 0) + (sample.js_heap_bytes ?? 0);
+}
+
+// Flag a timeline whose memory usage never decreases and grows by more than
+// the threshold overall. Mirrors `memory::detect_growth` in the core.
+function detectMemoryGrowth(timeline) {
+    const totals = timeline.map(memoryTotal).filter(t => t != null);
+    if (totals.length < 2) return null;
+    for (let i = 1; i < totals.length; i++) {
+        if (totals[i] < totals[i - 1]) return null;
+    }
+    const growth = totals[totals.length - 1] - totals[0];
+    return growth > MEMORY_GROWTH_THRESHOLD_BYTES ? { growth, samples: totals.length } : null;
+}
+
 function abortBenchmarks() {
     if (state.isRunning) {
         state.abortRequested = true;
+        state.cancelHandle?.cancel();
     }
 }
 
 async function runBenchmarks(ids) {
+    // The support matrix says these can't run here; don't queue them.
+    const unavailable = new Set(state.benchmarks.filter(b => b.unavailable_reason).map(b => b.id));
+    ids = ids.filter(id => !unavailable.has(id));
     if (state.isRunning || ids.length === 0) return;
 
     state.isRunning = true;
     state.abortRequested = false;
+    state.runStartedMs = Date.now();
+    state.runFinishedMs = null;
+    const filter = document.getElementById('filter-expr').value.trim();
+    state.runSettings = { ...getTimingConfig(), filter: filter || null };
+    const sampling = document.getElementById('sample-memory').checked;
+    state.memoryTimeline = [];
 
     for (const id of ids) {
         state.results.delete(id);
+        state.failures.delete(id);
         state.queuedBenchmarks.add(id);
     }
     renderBenchmarks();
     updateStats();
     updateRunButtons();
 
+    // Optional fast pass: a single timed iteration per benchmark without warm-up, so
+    // rough numbers for every benchmark show up before the full run starts.
+    if (document.getElementById('quick-pass').checked) {
+        for (const id of ids) {
+            if (state.abortRequested) break;
+
+            state.runningBenchmark = id;
+            state.runningPhase = 'estimating';
+            renderBenchmarks();
+
+            try {
+                const result = await runSingleBenchmark(id, { singleShot: true });
+                recordOutcome(id, result);
+            } catch (e) {
+                console.error(`Failed to estimate benchmark ${id}:`, e);
+            }
+        }
+        state.runningBenchmark = null;
+        state.runningPhase = null;
+        state.runningProgress = null;
+        renderBenchmarks();
+        updateStats();
+    }
+
     for (const id of ids) {
         if (state.abortRequested) break;
 
@@ -600,24 +920,36 @@ async function runBenchmarks(ids) {
 
         try {
             const result = await runSingleBenchmark(id);
-            if (result) {
-                state.results.set(id, result);
-            }
+            recordOutcome(id, result);
         } catch (e) {
             console.error(`Failed to run benchmark ${id}:`, e);
         }
 
         clearTimeout(phaseTimer);
+
+        // Outside the timed region: the benchmark has fully returned.
+        if (sampling) {
+            try {
+                const sample = await sampleMemory(id);
+                if (sample) state.memoryTimeline.push(sample);
+            } catch (e) {
+                console.error(`Failed to sample memory after ${id}:`, e);
+            }
+        }
+
         state.runningBenchmark = null;
         state.runningPhase = null;
+        state.runningProgress = null;
         renderBenchmarks();
         updateStats();
     }
 
     state.isRunning = false;
     state.abortRequested = false;
+    state.runFinishedMs = Date.now();
     state.queuedBenchmarks.clear();
     renderBenchmarks();
+    updateStats();
     updateRunButtons();
 }
 
@@ -635,9 +967,53 @@ function updateRunButtons() {
     }
 }
 
-function exportResults() {
-    const results = Array.from(state.results.values());
-    const json = JSON.stringify(results, null, 2);
+// Environment for exported result files, matching `EnvironmentInfo` in the core.
+async function getEnvironment() {
+    if (state.executionMode === 'native' && state.isTauri) {
+        const simdLevel = document.getElementById('simd-level').value;
+        return await invoke('get_environment', { simdLevel });
+    }
+    // The main-thread module also reports the crate version, which
+    // fingerprints the environment. The SIMD level may have been switched
+    // since it loaded.
+    const environment = state.mainThreadWasm?.capture_environment('') ?? {
+        target_arch: 'wasm32',
+        user_agent: navigator.userAgent,
+        core_count: navigator.hardwareConcurrency,
+    };
+    return { ...environment, simd_level: state.wasmSimdLevel };
+}
+
+// Settings of the last run for exported result files, matching `RunConfig` in
+// the core. Natively this also records the app's `VELLO_BENCH_*` variables.
+async function getRunConfig() {
+    const { warmup, iterations, filter } = state.runSettings ?? { ...getTimingConfig(), filter: null };
+    if (state.executionMode === 'native' && state.isTauri) {
+        return await invoke('get_run_config', { warmup, iterations, filter });
+    }
+    const config = { warmup, iterations };
+    if (filter) config.filter = filter;
+    return config;
+}
+
+// Export the current results as a `ResultFile`.
+async function exportResults() {
+    const now = Date.now();
+    const file = {
+        schema_version: 1,
+        started_ms: state.runStartedMs ?? now,
+        finished_ms: state.runFinishedMs ?? now,
+        environment: await getEnvironment(),
+        results: Array.from(state.results.values()),
+        config: await getRunConfig(),
+    };
+    if (state.memoryTimeline.length > 0) {
+        file.memory_timeline = state.memoryTimeline;
+    }
+    if (state.health) {
+        file.health = state.health;
+    }
+    const json = JSON.stringify(file, null, 2);
     const blob = new Blob([json], { type: 'application/json' });
     const url = URL.createObjectURL(blob);
 
@@ -734,11 +1110,18 @@ async function saveReference() {
         return;
     }
 
+    // Estimates must never end up in a reference, since references are used
+    // as comparison baselines.
+    const results = Array.from(state.results.values()).filter(r => !isEstimate(r));
+    if (results.length === 0) {
+        alert('Only single-shot estimates are available; run a full measurement first.');
+        return;
+    }
+
     const name = await showSaveDialog();
     if (!name) return;
 
     try {
-        const results = Array.from(state.results.values());
         if (state.isTauri) {
             await invoke('save_reference', { name, results });
         } else {
@@ -935,7 +1318,8 @@ async function captureScreenshot(benchId) {
     // Extract the scene name and category from the benchmark ID
     let sceneName, category;
     if (benchId.startsWith('scene_cpu/')) {
-        sceneName = benchId.slice('scene_cpu/'.length);
+        // Phase benchmarks (`scene_cpu/<name>/encode`, ...) show the scene.
+        sceneName = benchId.slice('scene_cpu/'.length).replace(/\/(encode|flush|rasterize)$/, '');
         category = 'scene_cpu';
     } else if (benchId.startsWith('scene_hybrid/')) {
         sceneName = benchId.slice('scene_hybrid/'.length);
@@ -971,15 +1355,15 @@ async function captureScreenshot(benchId) {
             const rgba = Uint8ClampedArray.from(atob(result.rgba_base64), c => c.charCodeAt(0));
             dataUrl = rgbaToDataUrl(rgba, result.width, result.height);
         } else if (category === 'scene_hybrid' && state.hybridInitialized && state.mainThreadWasm) {
-            // Hybrid WebGL: render once to canvas, then grab its content
-            const success = state.mainThreadWasm.render_hybrid_once(sceneName);
-            if (!success) throw new Error('Hybrid render failed');
-            dataUrl = state.hybridCanvas.toDataURL('image/png');
+            // Hybrid WebGL: render once, then read back straight-alpha pixels
+            const result = state.mainThreadWasm.screenshot_hybrid(sceneName);
+            if (!result) throw new Error('Hybrid render failed');
+            dataUrl = rgbaToDataUrl(result.data, result.width, result.height);
         } else if (category === 'vello_hybrid' && state.hybridInitialized && state.mainThreadWasm) {
-            // Vello Hybrid WebGL: render once to canvas, then grab its content
-            const success = state.mainThreadWasm.render_vello_hybrid_once(sceneName);
-            if (!success) throw new Error('Vello Hybrid render failed');
-            dataUrl = state.hybridCanvas.toDataURL('image/png');
+            // Vello Hybrid WebGL: render once, then read back straight-alpha pixels
+            const result = state.mainThreadWasm.screenshot_vello_hybrid(sceneName);
+            if (!result) throw new Error('Vello Hybrid render failed');
+            dataUrl = rgbaToDataUrl(result.data, result.width, result.height);
         } else if (category === 'scene_cpu' && state.mainThreadWasm) {
             // CPU: render via WASM and get raw pixel data
             const result = state.mainThreadWasm.screenshot_cpu(sceneName);
@@ -1020,6 +1404,111 @@ async function captureScreenshot(benchId) {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Multi-canvas overhead
+// ---------------------------------------------------------------------------
+
+const MAX_OVERHEAD_CANVASES = 4;
+
+// Whether the multi-canvas overhead of a benchmark can be measured: archive
+// scenes on the main-thread WebGL renderer.
+function canMeasureCanvases(id) {
+    return id.startsWith('scene_hybrid/') && state.executionMode === 'wasm' &&
+        state.hybridInitialized && state.mainThreadWasm != null;
+}
+
+// Render the scene of a `scene_hybrid/` benchmark to 1 to 4 canvases in turn
+// and show how the time per canvas grows beyond that of a single canvas.
+// The extra canvases are created for the measurement; afterwards the hybrid
+// renderer is back on its own canvas alone.
+async function measureCanvasOverhead(benchId) {
+    const dialog = document.getElementById('canvases-dialog');
+    const title = document.getElementById('canvases-dialog-title');
+    const body = document.getElementById('canvases-dialog-body');
+    title.textContent = `Multi-canvas overhead: ${benchId.slice('scene_hybrid/'.length)}`;
+    body.innerHTML = '<p class="screenshot-loading">Measuring...</p>';
+    dialog.style.display = 'flex';
+    if (state.isRunning) {
+        body.innerHTML = '<p class="screenshot-loading">Wait for the running benchmarks to finish.</p>';
+        return;
+    }
+
+    const wasm = state.mainThreadWasm;
+    const { warmup, iterations } = getTimingConfig();
+    // The same size as the primary canvas, so that none is ever resized.
+    const extra = Array.from({ length: MAX_OVERHEAD_CANVASES - 1 }, () => {
+        const canvas = document.createElement('canvas');
+        canvas.width = state.hybridCanvas.width;
+        canvas.height = state.hybridCanvas.height;
+        canvas.style.display = 'none';
+        document.body.appendChild(canvas);
+        return canvas;
+    });
+
+    const rows = [];
+    state.isRunning = true;
+    state.abortRequested = false;
+    updateRunButtons();
+    try {
+        for (let count = 1; count <= MAX_OVERHEAD_CANVASES; count++) {
+            if (state.abortRequested) break;
+            body.innerHTML = `<p class="screenshot-loading">Measuring ${count} of ${MAX_OVERHEAD_CANVASES} canvases...</p>`;
+            // Yield to let the dialog update before blocking the main thread.
+            await new Promise(resolve => setTimeout(resolve, 0));
+            wasm.init_hybrid_multi([state.hybridCanvas, ...extra.slice(0, count - 1)]);
+            const result = wasm.run_hybrid_multi_benchmark(benchId, warmup, iterations);
+            if (!result) throw new Error('Scene not found');
+            if (result.status === 'failed') throw new Error(result.error);
+            if (!result.canvas_overhead) throw new Error('No single-canvas baseline');
+            rows.push({ meanNs: result.statistics.mean_ns, ...result.canvas_overhead });
+        }
+        renderCanvasOverhead(body, rows);
+    } catch (err) {
+        body.innerHTML = `<p class="screenshot-loading" style="color: var(--danger);">Error: ${err.message}</p>`;
+        console.error('Multi-canvas measurement failed:', err);
+    } finally {
+        wasm.init_hybrid(state.hybridCanvas);
+        extra.forEach(canvas => canvas.remove());
+        state.isRunning = false;
+        state.abortRequested = false;
+        updateRunButtons();
+    }
+}
+
+function renderCanvasOverhead(body, rows) {
+    const fmt = (ns) => {
+        const { mean, unit } = formatTime(ns);
+        return `${mean.toFixed(3)} ${unit}`;
+    };
+    const maxOverhead = Math.max(...rows.map(row => Math.abs(row.overhead_ns_per_canvas)), 1);
+    const tableRows = rows.map(row => {
+        const width = Math.round(Math.abs(row.overhead_ns_per_canvas) / maxOverhead * 100);
+        const sign = row.overhead_ns_per_canvas < 0 ? '-' : '+';
+        const pctSign = row.overhead_pct > 0 ? '+' : '';
+        return `
+        <tr>
+            <td>${row.canvases}</td>
+            <td class="about-size">${fmt(row.meanNs)}</td>
+            <td class="about-size">${fmt(row.expected_ns)}</td>
+            <td class="about-size">${fmt(row.meanNs / row.canvases)}</td>
+            <td><span class="overhead-bar${row.overhead_ns_per_canvas < 0 ? ' negative' : ''}" style="width: ${width}px"></span>${sign}${fmt(Math.abs(row.overhead_ns_per_canvas))}</td>
+            <td class="about-size">${pctSign}${row.overhead_pct.toFixed(1)}%</td>
+        </tr>`;
+    }).join('');
+    body.innerHTML = `
+        <table class="benchmark-table about-table">
+            <thead><tr>
+                <th>Canvases</th>
+                <th class="about-size">Per iteration</th>
+                <th class="about-size" title="Canvas count times one frame on a single canvas">Expected</th>
+                <th class="about-size">Per canvas</th>
+                <th title="Time per canvas beyond a single-canvas frame">Overhead per canvas</th>
+                <th class="about-size">Overhead</th>
+            </tr></thead>
+            <tbody>${tableRows}</tbody>
+        </table>`;
+}
+
 // Convert raw RGBA pixel data to a PNG data URL via an off-screen canvas.
 function rgbaToDataUrl(rgbaBytes, width, height) {
     const canvas = document.createElement('canvas');
@@ -1031,6 +1520,73 @@ function rgbaToDataUrl(rgbaBytes, width, height) {
     return canvas.toDataURL('image/png');
 }
 
+// ---------------------------------------------------------------------------
+// About dialog
+// ---------------------------------------------------------------------------
+
+function formatBytes(bytes) {
+    if (bytes >= 1024 * 1024) return `${(bytes / (1024 * 1024)).toFixed(2)} MiB`;
+    if (bytes >= 1024) return `${(bytes / 1024).toFixed(1)} KiB`;
+    return `${bytes} B`;
+}
+
+async function showAboutDialog() {
+    const dialog = document.getElementById('about-dialog');
+    const body = document.getElementById('about-dialog-body');
+    dialog.style.display = 'flex';
+
+    let manifest = null;
+    try {
+        if (state.isTauri && state.executionMode === 'native') {
+            manifest = await invoke('get_asset_manifest');
+        } else if (state.mainThreadWasm) {
+            manifest = state.mainThreadWasm.get_asset_manifest();
+        }
+    } catch (e) {
+        console.error('Failed to load asset manifest:', e);
+    }
+
+    if (!manifest) {
+        body.innerHTML = '<p class="screenshot-loading">Asset manifest unavailable.</p>';
+        return;
+    }
+
+    const total = manifest.reduce((sum, asset) => sum + asset.bytes, 0);
+    const rows = manifest.map(asset => `
+        <tr>
+            <td>${asset.name}</td>
+            <td>${asset.kind}</td>
+            <td class="about-size">${formatBytes(asset.bytes)}</td>
+        </tr>`).join('');
+    body.innerHTML = `
+        <table class="benchmark-table about-table">
+            <thead><tr><th>Asset</th><th>Kind</th><th class="about-size">Size</th></tr></thead>
+            <tbody>${rows}</tbody>
+            <tfoot><tr><td>Total</td><td></td><td class="about-size">${formatBytes(total)}</td></tr></tfoot>
+        </table>`;
+}
+
+function setupAboutDialogListeners() {
+    const dialog = document.getElementById('about-dialog');
+    const close = () => { dialog.style.display = 'none'; };
+
+    document.getElementById('about-btn').addEventListener('click', showAboutDialog);
+    document.getElementById('about-dialog-close').addEventListener('click', close);
+    dialog.addEventListener('click', (e) => {
+        if (e.target === dialog) close();
+    });
+}
+
+function setupCanvasesDialogListeners() {
+    const dialog = document.getElementById('canvases-dialog');
+    const close = () => { dialog.style.display = 'none'; };
+
+    document.getElementById('canvases-dialog-close').addEventListener('click', close);
+    dialog.addEventListener('click', (e) => {
+        if (e.target === dialog) close();
+    });
+}
+
 function setupScreenshotDialogListeners() {
     const dialog = document.getElementById('screenshot-dialog');
     const closeBtn = document.getElementById('screenshot-dialog-close');
@@ -1051,12 +1607,16 @@ function updateSkiaBadge() {
 
     if (state.activeTab === 'scene') {
         badge.style.display = 'inline-block';
-        if (state.executionMode === 'native' && state.isTauri) {
+        // Availability comes from the support matrix via the benchmark list.
+        const skia = state.benchmarks.find(b => b.category === 'scene_skia');
+        if (skia && !skia.unavailable_reason) {
             badge.textContent = 'Skia: available';
+            badge.title = '';
             badge.classList.add('skia-available');
             badge.classList.remove('skia-unavailable');
         } else {
             badge.textContent = 'Skia: native only';
+            badge.title = skia?.unavailable_reason ?? '';
             badge.classList.add('skia-unavailable');
             badge.classList.remove('skia-available');
         }
@@ -1090,6 +1650,10 @@ function setupEventListeners() {
         btn.addEventListener('click', () => switchTab(btn.dataset.tab));
     });
 
+    document.getElementById('filter-expr').addEventListener('input', (e) => {
+        applyFilterExpression(e.target.value);
+    });
+
     document.getElementById('exec-mode').addEventListener('change', async (e) => {
         state.executionMode = e.target.value;
         await loadSimdLevels();
@@ -1134,7 +1698,14 @@ function setupEventListeners() {
 
     document.getElementById('benchmark-tbody').addEventListener('click', (e) => {
         // Handle screenshot button clicks
-        const screenshotBtn = e.target.closest('.screenshot-btn');
+        const canvasesBtn = e.target.closest('[data-canvases]');
+        if (canvasesBtn) {
+            e.stopPropagation();
+            measureCanvasOverhead(canvasesBtn.dataset.canvases);
+            return;
+        }
+
+        const screenshotBtn = e.target.closest('[data-screenshot]');
         if (screenshotBtn) {
             e.stopPropagation();
             const benchId = screenshotBtn.dataset.screenshot;
@@ -30,20 +30,53 @@ self.onmessage = async function(e) {
             }
 
             try {
-                const result = wasmModule.run_benchmark(data.id, data.warmup, data.iterations);
+                const onProgress = (event) =>
+                    self.postMessage({ type: 'progress', id: data.id, event });
+                const result = data.singleShot
+                    ? wasmModule.estimate_benchmark(data.id)
+                    : wasmModule.run_benchmark_with_progress(
+                        data.id, data.warmup, data.iterations, data.renderMode, data.maxMs, onProgress);
                 self.postMessage({ type: 'result', id: data.id, result });
             } catch (e) {
                 self.postMessage({ type: 'error', id: data.id, error: e.message });
             }
             break;
 
+        case 'sample-memory':
+            if (!wasmModule) {
+                self.postMessage({ type: 'memory', sample: null });
+                return;
+            }
+            try {
+                const sample = wasmModule.sample_memory(data.after);
+                self.postMessage({ type: 'memory', sample });
+            } catch (e) {
+                self.postMessage({ type: 'error', error: e.message });
+            }
+            break;
+
         case 'list':
             if (!wasmModule) {
                 self.postMessage({ type: 'benchmarks', benchmarks: [] });
                 return;
             }
             try {
-                const benchmarks = wasmModule.list_benchmarks();
+                const benchmarks = data.fast
+                    ? wasmModule.list_benchmarks_fast()
+                    : wasmModule.list_benchmarks();
+                self.postMessage({ type: 'benchmarks', benchmarks });
+            } catch (e) {
+                self.postMessage({ type: 'error', error: e.message });
+            }
+            break;
+
+        case 'category-details':
+            if (!wasmModule) {
+                self.postMessage({ type: 'benchmarks', benchmarks: [] });
+                return;
+            }
+            try {
+                const benchmarks = wasmModule.get_category_details(data.category);
                 self.postMessage({ type: 'benchmarks', benchmarks });
             } catch (e) {
                 self.postMessage({ type: 'error', error: e.message });
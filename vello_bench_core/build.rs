@@ -2,10 +2,19 @@
 //! and generates Rust source with `include_bytes!` for each file.
 //!
 //! Scene deserialization happens at runtime using `anyrender_serialize`.
+//!
+//! It also embeds the reference result files in `references/`, and generates
+//! the embedded asset manifest (scene archives, reference results and every
+//! file in `assets/`) and fails the build if the total size exceeds the asset
+//! budget, so that large assets can't silently bloat the WASM download.
 
 use std::fs;
 use std::path::Path;
 
+/// Default budget for all embedded assets, in MiB. Override with the
+/// `VELLO_BENCH_ASSET_BUDGET_MB` environment variable.
+const DEFAULT_ASSET_BUDGET_MB: u64 = 32;
+
 fn main() {
     let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
     let scenes_dir = Path::new(&manifest_dir).join("../scenes");
@@ -77,4 +86,127 @@ fn main() {
         "cargo:warning=Generated scene_list.rs with {} scene(s)",
         entries.len()
     );
+
+    let references = write_reference_list(&manifest_dir, &out_dir);
+    write_asset_manifest(&manifest_dir, &out_dir, &entries, &references);
+}
+
+/// Generate `reference_list.rs` embedding every `references/*.json` file, and
+/// return the `(name, absolute path)` of each.
+fn write_reference_list(manifest_dir: &str, out_dir: &str) -> Vec<(String, String)> {
+    let references_dir = Path::new(manifest_dir).join("references");
+    println!("cargo:rerun-if-changed=references");
+
+    let mut references = Vec::new();
+    if references_dir.is_dir() {
+        let mut files: Vec<_> = fs::read_dir(&references_dir)
+            .expect("Failed to read references directory")
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        files.sort();
+
+        for path in files {
+            println!("cargo:rerun-if-changed={}", path.display());
+            let name = path.file_stem().unwrap().to_string_lossy().to_string();
+            let abs_path = fs::canonicalize(&path)
+                .unwrap_or_else(|e| panic!("Failed to canonicalize {}: {e}", path.display()));
+            references.push((name, abs_path.display().to_string()));
+        }
+    }
+
+    let mut code = String::from(
+        "/// Auto-generated list of reference result files.\n\
+         /// Each entry is (reference_name, json).\n\
+         pub static REFERENCE_FILES: &[(&str, &str)] = &[\n",
+    );
+    for (name, abs_path) in &references {
+        code.push_str(&format!(
+            "    (\"{name}\", include_str!(\"{abs_path}\")),\n"
+        ));
+    }
+    code.push_str("];\n");
+    fs::write(Path::new(out_dir).join("reference_list.rs"), &code).unwrap();
+
+    references
+}
+
+/// Generate `asset_manifest.rs` listing every embedded asset with its size,
+/// and enforce the asset budget.
+fn write_asset_manifest(
+    manifest_dir: &str,
+    out_dir: &str,
+    scenes: &[(String, String)],
+    references: &[(String, String)],
+) {
+    let assets_dir = Path::new(manifest_dir).join("assets");
+    println!("cargo:rerun-if-changed=assets");
+    println!("cargo:rerun-if-env-changed=VELLO_BENCH_ASSET_BUDGET_MB");
+
+    // (name, kind, bytes)
+    let mut assets: Vec<(String, &str, u64)> = Vec::new();
+
+    for (name, abs_path) in scenes {
+        let bytes = fs::metadata(abs_path).map(|m| m.len()).unwrap_or(0);
+        assets.push((format!("scenes/{name}.anyrender.zip"), "Scene", bytes));
+    }
+    for (name, abs_path) in references {
+        let bytes = fs::metadata(abs_path).map(|m| m.len()).unwrap_or(0);
+        assets.push((format!("references/{name}.json"), "Reference", bytes));
+    }
+
+    let mut asset_files: Vec<_> = fs::read_dir(&assets_dir)
+        .expect("Failed to read assets directory")
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file())
+        .collect();
+    asset_files.sort_by_key(|e| e.file_name());
+
+    for entry in asset_files {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let extension = file_name.rsplit('.').next().unwrap_or_default();
+        let kind = match extension.to_ascii_lowercase().as_str() {
+            "png" | "jpg" | "jpeg" => "Image",
+            "svg" => "Svg",
+            "ttf" | "otf" | "woff" | "woff2" => "Font",
+            _ => "Other",
+        };
+        let bytes = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        assets.push((format!("assets/{file_name}"), kind, bytes));
+    }
+
+    let mut code = String::from(
+        "/// Auto-generated list of embedded assets.\n\
+         pub static ASSET_MANIFEST: &[AssetEntry] = &[\n",
+    );
+    for (name, kind, bytes) in &assets {
+        code.push_str(&format!(
+            "    AssetEntry {{ name: \"{name}\", kind: AssetKind::{kind}, bytes: {bytes} }},\n"
+        ));
+    }
+    code.push_str("];\n");
+    fs::write(Path::new(out_dir).join("asset_manifest.rs"), &code).unwrap();
+
+    let budget_mb = match std::env::var("VELLO_BENCH_ASSET_BUDGET_MB") {
+        Ok(value) => value
+            .parse()
+            .unwrap_or_else(|e| panic!("Invalid VELLO_BENCH_ASSET_BUDGET_MB {value:?}: {e}")),
+        Err(_) => DEFAULT_ASSET_BUDGET_MB,
+    };
+    let total: u64 = assets.iter().map(|(_, _, bytes)| bytes).sum();
+
+    if total > budget_mb * 1024 * 1024 {
+        assets.sort_by_key(|(_, _, bytes)| std::cmp::Reverse(*bytes));
+        let largest: String = assets
+            .iter()
+            .take(5)
+            .map(|(name, _, bytes)| format!("\n  {name}: {bytes} bytes"))
+            .collect();
+        panic!(
+            "Embedded assets total {total} bytes, over the {budget_mb} MiB budget. \
+             Largest assets:{largest}\n\
+             Shrink or drop assets, or set VELLO_BENCH_ASSET_BUDGET_MB to experiment locally."
+        );
+    }
 }
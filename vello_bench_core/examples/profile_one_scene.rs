@@ -0,0 +1,128 @@
+//! Render one scene repeatedly without any timing, to attach a profiler to.
+//!
+//! ```text
+//! cargo run --release -p vello_bench_core --example profile_one_scene -- \
+//!     [--backend cpu|hybrid|skia] [--frames N] [--test-scenes] SCENE
+//! ```
+//!
+//! `SCENE` is an embedded archive scene or, on the CPU and hybrid backends, a
+//! programmatic vello scene such as `filled_rects`. With `--test-scenes`,
+//! archive scenes come from [`TestSceneSource`] instead (`test_rect`,
+//! `test_layer`), which render in microseconds and need no assets. The scene
+//! is rendered `N` times (default 100) through [`vello_bench_core::harness`],
+//! the same code path the benchmarks time.
+
+use std::hint::black_box;
+use std::process::ExitCode;
+
+use vello_bench_core::harness::{
+    self, CpuSceneRenderer, EmbeddedScenes, FrameKind, HybridRenderer, HybridSceneRenderer, Level,
+    Pixmap, RenderContext, RenderMode, Renderer, SceneSource, SkiaSceneRenderer, TestSceneSource,
+};
+
+const USAGE: &str = "Usage: profile_one_scene [--backend cpu|hybrid|skia] [--frames N] \
+                     [--test-scenes] SCENE";
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), String> {
+    let mut backend = "cpu".to_string();
+    let mut frames = 100_u32;
+    let mut test_scenes = false;
+    let mut scene = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--backend" => backend = args.next().ok_or(USAGE)?,
+            "--frames" => {
+                frames = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .ok_or("--frames needs a number")?;
+            }
+            "--test-scenes" => test_scenes = true,
+            _ if arg.starts_with("--") => return Err(USAGE.to_string()),
+            _ => scene = Some(arg),
+        }
+    }
+    let scene = scene.ok_or(USAGE)?;
+
+    let test_source = TestSceneSource::new();
+    let source: &dyn SceneSource = if test_scenes {
+        &test_source
+    } else {
+        &EmbeddedScenes
+    };
+    let level = Level::new();
+
+    match (backend.as_str(), source.get(&scene)) {
+        ("cpu", Some(item)) => {
+            let mut renderer = CpuSceneRenderer::new(item, level);
+            for _ in 0..frames {
+                renderer.render_frame();
+            }
+            black_box(renderer);
+        }
+        ("cpu", None) => {
+            let registration = harness::vello_scene::<RenderContext>(&scene)?;
+            let info = &registration.info;
+            let mut ctx: RenderContext =
+                Renderer::new(info.width, info.height, 0, level, RenderMode::default());
+            let mut pixmap = Pixmap::new(info.width, info.height);
+            let state = registration.setup(&mut ctx);
+            for frame in 0..frames {
+                registration.draw_frame(state.as_ref(), &mut ctx, FrameKind::Full, frame.into());
+                ctx.flush();
+                ctx.render_to_pixmap(&mut pixmap);
+            }
+            black_box(pixmap);
+        }
+        ("hybrid", Some(item)) => {
+            let mut renderer = HybridSceneRenderer::new(item);
+            for _ in 0..frames {
+                renderer.render_frame();
+            }
+        }
+        ("hybrid", None) => {
+            let registration = harness::vello_scene::<HybridRenderer>(&scene)?;
+            let info = &registration.info;
+            let mut renderer: HybridRenderer =
+                Renderer::new(info.width, info.height, 0, level, RenderMode::default());
+            let state = registration.setup(&mut renderer);
+            for frame in 0..frames {
+                registration.draw_frame(
+                    state.as_ref(),
+                    &mut renderer,
+                    FrameKind::Full,
+                    frame.into(),
+                );
+                renderer.render_and_sync();
+            }
+        }
+        ("skia", Some(item)) => {
+            let mut renderer = SkiaSceneRenderer::new(item);
+            for _ in 0..frames {
+                renderer.render_frame();
+            }
+            black_box(renderer.into_rgba());
+        }
+        ("skia", None) => {
+            return Err(format!(
+                "Skia only renders archive scenes, '{scene}' is none"
+            ));
+        }
+        _ => return Err(format!("Unknown backend '{backend}'\n{USAGE}")),
+    }
+
+    eprintln!("Rendered {scene} {frames} times on {backend}");
+    Ok(())
+}
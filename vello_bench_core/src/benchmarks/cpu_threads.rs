@@ -0,0 +1,195 @@
+//! Thread scaling of the Vello CPU backend.
+//!
+//! Each benchmark renders one scene with a fixed number of worker threads,
+//! e.g. `cpu_threads/tiled_flowers_1000_t4`. `_t0` renders on the calling
+//! thread and is the baseline the speedups in the report are relative to (see
+//! [`thread_scaling_markdown`](crate::result::report::thread_scaling_markdown)).
+//!
+//! The scene is drawn before every iteration (untimed), so the time covers
+//! only `flush()` and rasterisation, which is where the worker threads do
+//! their work. `tiny_rects` is small enough that dispatching to the workers
+//! costs more than it saves.
+//!
+//! Only thread counts up to [`std::thread::available_parallelism`] are
+//! listed; oversubscribed runs measure the scheduler rather than the
+//! renderer. They can still be run by ID.
+
+use crate::registry::BenchmarkInfo;
+use crate::result::BenchmarkResult;
+use crate::runner::BenchRunner;
+use fearless_simd::Level;
+
+const CATEGORY: &str = "cpu_threads";
+const MEASURES: &str = "flush + rasterize to pixmap with the given number of worker threads; \
+    excludes drawing the scene and image uploads";
+
+/// Scenes run at every thread count: vector-only, image-heavy and mixed,
+/// plus [`TINY_SCENE`].
+const SCENES: &[&str] = &[
+    "filled_rects",
+    "tiled_flowers_1000",
+    "paths_and_images_100",
+    TINY_SCENE,
+];
+
+/// A few rects on a small canvas, where threading is a net loss.
+const TINY_SCENE: &str = "tiny_rects";
+
+/// Canvas size of [`TINY_SCENE`].
+const TINY_SIZE: u16 = 64;
+
+/// Worker thread counts, in the order their variants are listed.
+const THREAD_COUNTS: &[u16] = &[0, 2, 4, 8];
+
+/// Split a benchmark name into scene name and thread count.
+///
+/// `"filled_rects_t4"` yields `Some(("filled_rects", 4))`.
+pub fn split_threads(name: &str) -> Option<(&str, u16)> {
+    let (scene, threads) = name.rsplit_once("_t")?;
+    let threads = threads.parse().ok()?;
+    SCENES.contains(&scene).then_some((scene, threads))
+}
+
+/// The programmatic scenes of [`SCENES`], leaving out [`TINY_SCENE`], which
+/// is drawn here.
+pub(crate) fn registered_scenes() -> Vec<&'static str> {
+    SCENES
+        .iter()
+        .copied()
+        .filter(|scene| *scene != TINY_SCENE)
+        .collect()
+}
+
+/// Number of threads the machine can run in parallel, or 1 if unknown.
+pub(crate) fn max_threads() -> u16 {
+    std::thread::available_parallelism()
+        .map(|n| u16::try_from(n.get()).unwrap_or(u16::MAX))
+        .unwrap_or(1)
+}
+
+pub fn list() -> Vec<BenchmarkInfo> {
+    let max = max_threads();
+    let names: Vec<String> = SCENES
+        .iter()
+        .flat_map(|scene| {
+            THREAD_COUNTS
+                .iter()
+                .filter(|threads| **threads <= max)
+                .map(move |threads| format!("{scene}_t{threads}"))
+        })
+        .collect();
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    BenchmarkInfo::from_names(CATEGORY, &names, MEASURES)
+}
+
+/// Run a thread scaling benchmark. On WASM this always returns `None`:
+/// the WASM build renders on a single thread.
+pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        run_native(name, runner, level)
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = (name, runner, level);
+        None
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn run_native(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
+    use crate::vello_scenes::find_scene;
+    use vello_cpu::RenderContext;
+
+    let (scene, threads) = split_threads(name)?;
+
+    let mut result = if scene == TINY_SCENE {
+        measure(
+            name,
+            runner,
+            level,
+            threads,
+            (TINY_SIZE, TINY_SIZE),
+            |_| (),
+            |_, ctx| draw_tiny_rects(ctx),
+        )
+    } else {
+        let registration = find_scene::<RenderContext>(scene)?;
+        let size = (registration.info.width, registration.info.height);
+        measure(
+            name,
+            runner,
+            level,
+            threads,
+            size,
+            |ctx| registration.setup(ctx),
+            |state, ctx| registration.draw(state.as_ref(), ctx),
+        )
+    };
+    result.num_threads = Some(threads);
+
+    Some(result)
+}
+
+/// Time `flush()` + `render_to_pixmap()` of whatever `draw` draws, with
+/// `threads` worker threads. `setup` runs once, untimed.
+#[cfg(not(target_arch = "wasm32"))]
+fn measure<S>(
+    name: &str,
+    runner: &BenchRunner,
+    level: Level,
+    threads: u16,
+    (width, height): (u16, u16),
+    setup: impl FnOnce(&mut vello_cpu::RenderContext) -> S,
+    draw: impl Fn(&S, &mut vello_cpu::RenderContext),
+) -> BenchmarkResult {
+    use crate::renderer::Renderer;
+    use crate::simd::level_suffix;
+    use vello_cpu::{Pixmap, RenderContext, RenderMode};
+
+    let mut ctx: RenderContext =
+        Renderer::new(width, height, threads, level, RenderMode::default());
+    let pixmap = Pixmap::new(width, height);
+
+    // Setup phase — image uploads etc. (not timed).
+    let state = setup(&mut ctx);
+
+    runner.run_with_setup(
+        &format!("{CATEGORY}/{name}"),
+        CATEGORY,
+        name,
+        level_suffix(level),
+        &mut (ctx, pixmap),
+        |(ctx, _)| {
+            ctx.reset();
+            draw(&state, ctx);
+        },
+        #[inline(always)]
+        |(ctx, pixmap)| {
+            ctx.flush();
+            ctx.render_to_pixmap(pixmap);
+            std::hint::black_box(&pixmap);
+        },
+    )
+}
+
+/// Four rects on a [`TINY_SIZE`] canvas.
+#[cfg(not(target_arch = "wasm32"))]
+fn draw_tiny_rects(r: &mut impl crate::renderer::Renderer) {
+    use vello_common::kurbo::Rect;
+    use vello_common::peniko::color::palette;
+
+    let half = f64::from(TINY_SIZE) / 2.0;
+    let colors = [
+        palette::css::RED,
+        palette::css::GREEN,
+        palette::css::BLUE,
+        palette::css::YELLOW,
+    ];
+    for (i, color) in colors.into_iter().enumerate() {
+        let x = (i % 2) as f64 * half;
+        let y = (i / 2) as f64 * half;
+        r.set_paint(color);
+        r.fill_rect(&Rect::new(x, y, x + half, y + half));
+    }
+}
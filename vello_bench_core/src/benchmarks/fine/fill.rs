@@ -10,9 +10,10 @@ use vello_cpu::fine::{Fine, U8Kernel};
 
 const NAMES: &[&str] = &["opaque_short", "opaque_long", "transparent_short", "transparent_long"];
 const CATEGORY: &str = "fine/fill";
+const MEASURES: &str = "one `Fine::fill` of a span with a solid paint; excludes packing";
 
 pub fn list() -> Vec<BenchmarkInfo> {
-    BenchmarkInfo::from_names(CATEGORY, NAMES)
+    BenchmarkInfo::from_names(CATEGORY, NAMES, MEASURES)
 }
 
 pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
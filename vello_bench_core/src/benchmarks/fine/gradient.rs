@@ -30,10 +30,12 @@ const NAMES: &[&str] = &[
     "transparent",
 ];
 const CATEGORY: &str = "fine/gradient";
+const MEASURES: &str =
+    "one `Fine::fill` of a wide tile row with a gradient paint; excludes encoding and packing";
 const SEED: [u8; 32] = [0; 32];
 
 pub fn list() -> Vec<BenchmarkInfo> {
-    BenchmarkInfo::from_names(CATEGORY, NAMES)
+    BenchmarkInfo::from_names(CATEGORY, NAMES, MEASURES)
 }
 
 pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
@@ -26,12 +26,14 @@ const NAMES: &[&str] = &[
     "extend_reflect",
 ];
 const CATEGORY: &str = "fine/image";
+const MEASURES: &str =
+    "one `Fine::fill` of a wide tile row with an image paint; excludes encoding and packing";
 
 static COLR_DATA: &[u8] = include_bytes!("../../../assets/big_colr.png");
 static SMALL_DATA: &[u8] = include_bytes!("../../../assets/rgb_image_2x2.png");
 
 pub fn list() -> Vec<BenchmarkInfo> {
-    BenchmarkInfo::from_names(CATEGORY, NAMES)
+    BenchmarkInfo::from_names(CATEGORY, NAMES, MEASURES)
 }
 
 pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
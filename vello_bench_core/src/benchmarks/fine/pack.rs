@@ -10,9 +10,10 @@ use vello_cpu::region::Regions;
 
 const NAMES: &[&str] = &["block", "regular"];
 const CATEGORY: &str = "fine/pack";
+const MEASURES: &str = "`Fine::pack` of the scratch buffer into a region buffer; excludes filling";
 
 pub fn list() -> Vec<BenchmarkInfo> {
-    BenchmarkInfo::from_names(CATEGORY, NAMES)
+    BenchmarkInfo::from_names(CATEGORY, NAMES, MEASURES)
 }
 
 pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
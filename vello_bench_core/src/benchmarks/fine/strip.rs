@@ -14,10 +14,12 @@ use vello_cpu::fine::{Fine, U8Kernel};
 
 const NAMES: &[&str] = &["solid_short", "solid_long"];
 const CATEGORY: &str = "fine/strip";
+const MEASURES: &str =
+    "one `Fine::fill` of a span with a solid paint masked by strip alphas; excludes packing";
 const SEED: [u8; 32] = [0; 32];
 
 pub fn list() -> Vec<BenchmarkInfo> {
-    BenchmarkInfo::from_names(CATEGORY, NAMES)
+    BenchmarkInfo::from_names(CATEGORY, NAMES, MEASURES)
 }
 
 pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
@@ -1,48 +1,84 @@
-use crate::data::get_data_items;
+use crate::data::{DataItem, get_data_items, seed_offset};
 use crate::registry::BenchmarkInfo;
 use crate::result::BenchmarkResult;
-use crate::runner::BenchRunner;
+use crate::runner::{BenchRunner, MicroBench};
 use crate::simd::level_suffix;
 use fearless_simd::Level;
 use vello_common::flatten::{self, FlattenCtx, Line};
-use vello_common::kurbo::Affine;
+use vello_common::kurbo::{Affine, BezPath};
 
 const CATEGORY: &str = "flatten";
+const MEASURES: &str =
+    "flattening the fills and pre-expanded strokes of an SVG; excludes parsing and stroking";
 
 pub fn list() -> Vec<BenchmarkInfo> {
-    BenchmarkInfo::from_data_items(CATEGORY)
+    BenchmarkInfo::from_data_items(CATEGORY, MEASURES)
 }
 
 pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
     let items = get_data_items();
     let item = items.iter().find(|i| i.name == name)?;
-    let expanded_strokes = item.expanded_strokes();
-    let simd_variant = level_suffix(level);
 
-    let mut line_buf: Vec<Line> = vec![];
-    let mut temp_buf: Vec<Line> = vec![];
-    let mut flatten_ctx = FlattenCtx::default();
-
-    Some(runner.run(
+    let mut bench = Flatten {
+        level,
+        item,
+        expanded_strokes: item.expanded_strokes(),
+        line_buf: vec![],
+        temp_buf: vec![],
+        flatten_ctx: FlattenCtx::default(),
+    };
+    Some(runner.run_micro(
         &format!("{CATEGORY}/{name}"),
         CATEGORY,
         name,
-        simd_variant,
-        #[inline(always)]
-        || {
-            line_buf.clear();
-
-            for path in &item.fills {
-                flatten::fill(level, &path.path, path.transform, &mut temp_buf, &mut flatten_ctx);
-                line_buf.extend(&temp_buf);
-            }
-
-            for stroke in &expanded_strokes {
-                flatten::fill(level, stroke, Affine::IDENTITY, &mut temp_buf, &mut flatten_ctx);
-                line_buf.extend(&temp_buf);
-            }
-
-            std::hint::black_box(&line_buf);
-        },
+        level_suffix(level),
+        &mut bench,
     ))
 }
+
+/// Flattens a data item shifted by the input's [`seed_offset`].
+struct Flatten<'a> {
+    level: Level,
+    item: &'a DataItem,
+    expanded_strokes: Vec<BezPath>,
+    line_buf: Vec<Line>,
+    temp_buf: Vec<Line>,
+    flatten_ctx: FlattenCtx,
+}
+
+impl MicroBench for Flatten<'_> {
+    type Input = Affine;
+
+    fn make_input(&self, seed: u64) -> Affine {
+        seed_offset(seed)
+    }
+
+    #[inline(always)]
+    fn run(&mut self, offset: &Affine) {
+        self.line_buf.clear();
+
+        for path in &self.item.fills {
+            flatten::fill(
+                self.level,
+                &path.path,
+                *offset * path.transform,
+                &mut self.temp_buf,
+                &mut self.flatten_ctx,
+            );
+            self.line_buf.extend(&self.temp_buf);
+        }
+
+        for stroke in &self.expanded_strokes {
+            flatten::fill(
+                self.level,
+                stroke,
+                *offset,
+                &mut self.temp_buf,
+                &mut self.flatten_ctx,
+            );
+            self.line_buf.extend(&self.temp_buf);
+        }
+
+        std::hint::black_box(&self.line_buf);
+    }
+}
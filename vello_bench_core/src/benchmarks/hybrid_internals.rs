@@ -0,0 +1,92 @@
+//! Micro-benchmarks for Vello Hybrid internals whose cost is normally folded
+//! into the per-frame time of the scene benchmarks.
+//!
+//! On native (non-WASM): uses the wgpu [`HybridRenderer`](crate::renderer::HybridRenderer).
+//! On WASM: handled by the `vello_bench_wasm` crate on the main thread using
+//! WebGL (not available in this core crate).
+//!
+//! The `reset_*` benchmarks build a large hybrid scene before every iteration
+//! (untimed) and time only `Scene::reset()`. Scenes are built with the
+//! overlapping-images draw function, so every command is one image-filled
+//! rect. Results carry the command count as throughput.
+
+use crate::registry::BenchmarkInfo;
+use crate::result::BenchmarkResult;
+use crate::runner::BenchRunner;
+use fearless_simd::Level;
+
+const CATEGORY: &str = "hybrid_internals";
+const MEASURES: &str =
+    "`Scene::reset()` of a scene with the given number of commands; excludes building it";
+
+/// Canvas width used to build the scenes.
+pub const WIDTH: u16 = 1920;
+/// Canvas height used to build the scenes.
+pub const HEIGHT: u16 = 1080;
+
+/// `reset_*` benchmark names and the number of draw commands each builds.
+const RESET_VARIANTS: &[(&str, u32)] = &[
+    ("reset_1k", 1_000),
+    ("reset_10k", 10_000),
+    ("reset_100k", 100_000),
+];
+
+/// Number of draw commands built before each `reset_*` iteration, or `None`
+/// if `name` is not a reset benchmark.
+pub fn reset_command_count(name: &str) -> Option<u32> {
+    RESET_VARIANTS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, count)| *count)
+}
+
+pub fn list() -> Vec<BenchmarkInfo> {
+    let names: Vec<&str> = RESET_VARIANTS.iter().map(|(name, _)| *name).collect();
+    BenchmarkInfo::from_names(CATEGORY, &names, MEASURES)
+}
+
+/// Run a hybrid internals benchmark. On WASM this always returns `None`
+/// because hybrid WASM benchmarks are driven from JS via the
+/// `vello_bench_wasm` crate.
+pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        run_native(name, runner, level)
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = (name, runner, level);
+        None
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn run_native(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
+    use crate::renderer::{HybridRenderer, Renderer};
+    use crate::result::Throughput;
+    use crate::simd::level_suffix;
+    use crate::vello_scenes::images::{draw_overlapping_images, setup_image_grid};
+    use vello_cpu::RenderMode;
+
+    let count = reset_command_count(name)?;
+    let simd_variant = level_suffix(level);
+
+    let mut hybrid: HybridRenderer = Renderer::new(WIDTH, HEIGHT, 0, level, RenderMode::default());
+
+    // Setup phase — image upload (not timed).
+    let image_state = setup_image_grid(&mut hybrid);
+
+    let mut result = runner.run_with_setup(
+        &format!("{CATEGORY}/{name}"),
+        CATEGORY,
+        name,
+        simd_variant,
+        &mut hybrid,
+        |hybrid| draw_overlapping_images(&image_state, hybrid, count),
+        #[inline(always)]
+        |hybrid| hybrid.reset(),
+    );
+    result.throughput = Some(Throughput::Elements(count.into()));
+
+    Some(result)
+}
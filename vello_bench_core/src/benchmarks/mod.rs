@@ -1,10 +1,15 @@
+pub mod cpu_threads;
 pub mod fine;
 pub mod flatten;
+pub mod hybrid_internals;
+pub mod recording;
 pub mod render_strips;
 pub mod scene_cpu;
 pub mod scene_hybrid;
 pub mod scene_skia;
+pub mod set_paint;
 pub mod strokes;
 pub mod tile;
+pub mod transform;
 pub mod vello_cpu;
 pub mod vello_hybrid;
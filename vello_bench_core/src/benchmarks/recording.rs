@@ -0,0 +1,260 @@
+//! Recording and replaying the programmatic vello scenes.
+//!
+//! Downstream users cache static content by recording it once into a
+//! [`Recording`] and executing that on every frame. Each scene registered in
+//! `vello_scenes` gets two benchmarks per backend:
+//!
+//! - `<scene>_record` records the scene into a fresh [`Recording`] every
+//!   iteration, i.e. the cost of a cache miss, without preparing it.
+//! - `<scene>_replay` records and prepares the scene once in setup, so its
+//!   strips are generated and its images uploaded, and times executing the
+//!   recording and rendering the frame, i.e. the cost of a cache hit.
+//!
+//! `vello_recording` runs them on Vello CPU (rendering to a `Pixmap`),
+//! `vello_recording_hybrid` on native Vello Hybrid (rendering on the GPU and
+//! syncing). A recording holds one frame, so the replay of an animated scene
+//! shows its first frame throughout, while `_record` draws the frames in turn.
+//!
+//! Scenes that make recordings themselves or use what the recorder can't
+//! hold are left out (see
+//! [`VelloScene::RECORDABLE`](crate::vello_scenes::VelloScene::RECORDABLE)),
+//! as are scenes only Vello CPU can render from `vello_recording_hybrid`.
+
+use std::any::Any;
+use std::hint::black_box;
+
+use crate::registry::BenchmarkInfo;
+use crate::renderer::{RecorderRenderer, Renderer};
+use crate::result::{BenchmarkResult, Throughput};
+use crate::runner::BenchRunner;
+use crate::simd::level_suffix;
+use crate::vello_scenes::{
+    RecordFrameFn, SceneRegistration, find_scene, find_scene_recorder, get_vello_scenes,
+    is_cpu_only, is_recordable,
+};
+use fearless_simd::Level;
+use vello_common::recording::Recording;
+use vello_cpu::{Pixmap, RenderContext, RenderMode};
+
+const CATEGORY_CPU: &str = "vello_recording";
+const CATEGORY_HYBRID: &str = "vello_recording_hybrid";
+const MEASURES_RECORD: &str = "scene draw into a new recording; excludes preparing it, \
+    image uploads and rendering";
+const MEASURES_REPLAY_CPU: &str = "execute a prepared recording + flush + rasterize to pixmap; \
+    excludes recording, preparing, image uploads and readback";
+const MEASURES_REPLAY_HYBRID: &str = "execute a prepared recording + GPU render + GPU sync; \
+    excludes recording, preparing, image uploads and readback";
+
+/// What a benchmark of the category times.
+#[derive(Debug, Clone, Copy)]
+enum Variant {
+    Record,
+    Replay,
+}
+
+impl Variant {
+    const ALL: [Self; 2] = [Self::Record, Self::Replay];
+
+    fn suffix(self) -> &'static str {
+        match self {
+            Self::Record => "_record",
+            Self::Replay => "_replay",
+        }
+    }
+}
+
+/// Split a benchmark name into scene name and variant, or `None` if it has
+/// no variant suffix or names an unrecordable scene.
+fn split_variant(name: &str) -> Option<(&str, Variant)> {
+    Variant::ALL.into_iter().find_map(|variant| {
+        let scene = name.strip_suffix(variant.suffix())?;
+        is_recordable(scene).then_some((scene, variant))
+    })
+}
+
+pub fn list() -> Vec<BenchmarkInfo> {
+    let names: Vec<String> = get_vello_scenes()
+        .iter()
+        .filter(|scene| is_recordable(scene.name))
+        .flat_map(|scene| {
+            Variant::ALL
+                .into_iter()
+                .map(move |variant| format!("{}{}", scene.name, variant.suffix()))
+        })
+        .collect();
+
+    let hybrid_names: Vec<String> = names
+        .iter()
+        .filter(|name| split_variant(name).is_some_and(|(scene, _)| !is_cpu_only(scene)))
+        .cloned()
+        .collect();
+
+    let mut benchmarks = infos(CATEGORY_CPU, &names, MEASURES_REPLAY_CPU);
+    benchmarks.extend(infos(
+        CATEGORY_HYBRID,
+        &hybrid_names,
+        MEASURES_REPLAY_HYBRID,
+    ));
+    benchmarks
+}
+
+fn infos(category: &str, names: &[String], measures_replay: &'static str) -> Vec<BenchmarkInfo> {
+    names
+        .iter()
+        .map(|name| match split_variant(name) {
+            Some((scene, variant)) => {
+                let measures = match variant {
+                    Variant::Replay => measures_replay,
+                    Variant::Record => MEASURES_RECORD,
+                };
+                BenchmarkInfo::new(category, name.clone(), measures).with_scene_tags(scene)
+            }
+            None => BenchmarkInfo::new(category, name.clone(), MEASURES_RECORD),
+        })
+        .collect()
+}
+
+/// Run a `vello_recording` benchmark.
+pub fn run_cpu(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
+    let (scene, variant) = split_variant(name)?;
+    let registration = find_scene::<RenderContext>(scene)?;
+    let draw = find_scene_recorder(scene)?;
+    let info = &registration.info;
+
+    let mut ctx: RenderContext =
+        Renderer::new(info.width, info.height, 0, level, RenderMode::default());
+
+    // Setup phase — image uploads etc. (not timed).
+    let state = registration.setup(&mut ctx);
+
+    let result = match variant {
+        Variant::Record => {
+            let frame = record_frames(draw, state.as_ref(), &mut ctx);
+            let mut result = time(CATEGORY_CPU, name, runner, level, &registration, frame);
+            result.animated = registration.animated;
+            result
+        }
+        Variant::Replay => {
+            let recording = record_once(draw, state.as_ref(), &mut ctx);
+            let mut pixmap = Pixmap::new(info.width, info.height);
+            time(CATEGORY_CPU, name, runner, level, &registration, || {
+                ctx.execute_recording(&recording);
+                ctx.flush();
+                ctx.render_to_pixmap(&mut pixmap);
+                black_box(&pixmap);
+            })
+        }
+    };
+
+    Some(result)
+}
+
+/// Run a `vello_recording_hybrid` benchmark. On WASM this always returns
+/// `None`: the core crate only links Vello Hybrid natively.
+pub fn run_hybrid(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        run_hybrid_native(name, runner, level)
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = (name, runner, level);
+        None
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn run_hybrid_native(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
+    use crate::renderer::HybridRenderer;
+
+    let (scene, variant) = split_variant(name)?;
+    let registration = find_scene::<HybridRenderer>(scene).filter(|s| !s.cpu_only)?;
+    let draw = find_scene_recorder(scene)?;
+    let info = &registration.info;
+
+    let mut hybrid: HybridRenderer =
+        Renderer::new(info.width, info.height, 0, level, RenderMode::default());
+
+    // Setup phase — image uploads etc. (not timed).
+    let state = registration.setup(&mut hybrid);
+
+    let result = match variant {
+        Variant::Record => {
+            let frame = record_frames(draw, state.as_ref(), &mut hybrid);
+            let mut result = time(CATEGORY_HYBRID, name, runner, level, &registration, frame);
+            result.animated = registration.animated;
+            result
+        }
+        Variant::Replay => {
+            let recording = record_once(draw, state.as_ref(), &mut hybrid);
+            let mut result = time(CATEGORY_HYBRID, name, runner, level, &registration, || {
+                hybrid.execute_recording(&recording);
+                hybrid.render_and_sync();
+            });
+            // Recording doesn't touch the GPU, so only replays sync.
+            result.sync_mode = Some(hybrid.sync_mode());
+            result
+        }
+    };
+
+    Some(result)
+}
+
+/// Record frame 0 of the scene and prepare the recording on `r`.
+fn record_once<R: Renderer>(draw: RecordFrameFn, state: &dyn Any, r: &mut R) -> Recording {
+    let (width, height) = (r.width(), r.height());
+    let mut recording = Recording::new();
+    r.record(&mut recording, |recorder| {
+        draw(
+            state,
+            &mut RecorderRenderer::wrap(recorder, width, height),
+            0,
+        );
+    });
+    r.prepare_recording(&mut recording);
+    recording
+}
+
+/// A frame of the `_record` variants: record the next frame of the scene on
+/// `r` into a new recording.
+fn record_frames<'a, R: Renderer>(
+    draw: RecordFrameFn,
+    state: &'a dyn Any,
+    r: &'a mut R,
+) -> impl FnMut() + 'a {
+    let (width, height) = (r.width(), r.height());
+    let mut frame_index = 0;
+    move || {
+        let mut recording = Recording::new();
+        r.record(&mut recording, |recorder| {
+            draw(
+                state,
+                &mut RecorderRenderer::wrap(recorder, width, height),
+                frame_index,
+            );
+        });
+        frame_index += 1;
+        black_box(&recording);
+    }
+}
+
+/// Time `frame`, per iteration for scenes that ask for it, and set the
+/// scene's throughput.
+fn time<R>(
+    category: &str,
+    name: &str,
+    runner: &BenchRunner,
+    level: Level,
+    registration: &SceneRegistration<R>,
+    frame: impl FnMut(),
+) -> BenchmarkResult {
+    let id = format!("{category}/{name}");
+    let simd_variant = level_suffix(level);
+    let mut result = if registration.per_iteration {
+        runner.run_with_frame_wait(&id, category, name, simd_variant, frame)
+    } else {
+        runner.run(&id, category, name, simd_variant, frame)
+    };
+    result.throughput = registration.elements.map(Throughput::Elements);
+    result
+}
@@ -8,9 +8,11 @@ use vello_common::peniko::Fill;
 use vello_common::strip::Strip;
 
 const CATEGORY: &str = "render_strips";
+const MEASURES: &str =
+    "strip rendering from the sorted tiles of an SVG; excludes parsing, flattening and tiling";
 
 pub fn list() -> Vec<BenchmarkInfo> {
-    BenchmarkInfo::from_data_items(CATEGORY)
+    BenchmarkInfo::from_data_items(CATEGORY, MEASURES)
 }
 
 pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
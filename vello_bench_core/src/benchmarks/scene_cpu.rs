@@ -3,24 +3,123 @@
 //! Each scene in the `scenes/` directory becomes a benchmark under the
 //! `scene_cpu` category. The benchmark measures the full rendering pipeline:
 //! scene replay (via `VelloCpuScenePainter`) + rasterization to a `Pixmap`.
+//!
+//! `scene_cpu/<name>/encode`, `scene_cpu/<name>/flush` and
+//! `scene_cpu/<name>/rasterize` time one [`ScenePhase`] of that pipeline
+//! each, with the phases before it run untimed, so a regression in scene
+//! encoding can be told apart from one in rasterization. The three add up
+//! to roughly the full frame but are measured in separate runs.
+//!
+//! [`run_with_mode_from`] renders a scene in an explicit [`RenderMode`]
+//! instead of `RenderMode::default()`, e.g. `scene_cpu/demo_scene_quality`
+//! for the optimize-quality fine rasterizer. These variants aren't listed
+//! but run by ID like the plain ones.
+//!
+//! `scene_cpu_mt` runs the same frame with worker threads, e.g.
+//! `scene_cpu_mt/demo_scene_t4`, to measure the multi-threaded rasterizer
+//! applications use. `_t0` renders on the calling thread like `scene_cpu`
+//! and is the baseline of the thread scaling report. As for `cpu_threads`,
+//! only counts up to the machine's parallelism are listed, and the WASM build
+//! lists none since it renders on a single thread.
+//!
+//! `scene_cpu_cold` times the first frame instead: building a fresh
+//! [`CpuSceneRenderer`] from the archive and rendering once, repeated a few
+//! times (see [`BenchRunner::measure_first_iteration`]).
+//!
+//! # Per-frame state
+//!
+//! All archive replay renderers (`scene_cpu`, `scene_hybrid`, `scene_skia`
+//! and the WASM WebGL path) follow the same rule: every frame starts with an
+//! explicit reset of the painter's per-frame state (paint, transform, layer
+//! and clip stacks, recorded draw commands) before the scene is appended.
+//! What persists across frames is only what was created during setup: the
+//! deserialized scene, the resources registered with the render context
+//! (images, fonts) and the render target. No backend reuses work from a
+//! previous frame, so frame N measures the same thing as frame 1.
+//!
+//! The CPU, Skia and Hybrid renderers also keep the deserialized scene and
+//! its context across runs, in the [scene cache](crate::scene_cache), so setup
+//! is cheaper when a benchmark runs again. The cold benchmarks bypass it.
 
+use crate::benchmarks::vello_cpu::{RENDER_MODES, render_mode_suffix};
+use crate::harness::scene_item;
+use crate::readback::check_pixmap_size;
 use crate::registry::BenchmarkInfo;
 use crate::result::BenchmarkResult;
 use crate::runner::BenchRunner;
-use crate::scenes::{SceneItem, get_scenes};
+use crate::scene_cache;
+use crate::scenes::{EmbeddedScenes, SceneItem, SceneSource};
+use crate::screenshot::{AlphaMode, unpremultiplied_rgba_into};
 use crate::simd::level_suffix;
 use anyrender::PaintScene;
 use fearless_simd::Level;
 use vello_common::kurbo::Affine;
-use vello_cpu::{Pixmap, RenderContext as VelloCpuRenderCtx, RenderSettings};
+use vello_cpu::{Pixmap, RenderContext as VelloCpuRenderCtx, RenderMode, RenderSettings};
 
 const CATEGORY: &str = "scene_cpu";
+const MEASURES: &str =
+    "scene replay via anyrender + flush + rasterize to pixmap; excludes parsing and readback";
+const ENCODE_MEASURES: &str =
+    "scene replay via anyrender into the render context; excludes flush and rasterize";
+const FLUSH_MEASURES: &str = "flush after an untimed scene replay; excludes rasterize";
+const RASTERIZE_MEASURES: &str =
+    "rasterize to pixmap after an untimed scene replay + flush; excludes readback";
+const THREADED_CATEGORY: &str = "scene_cpu_mt";
+const THREADED_MEASURES: &str = "scene replay via anyrender + flush + rasterize to pixmap with the \
+    given number of worker threads; excludes parsing and readback";
+
+/// Worker thread counts of `scene_cpu_mt`, besides one per core.
+const THREAD_COUNTS: &[u16] = &[0, 1, 2, 4, 8];
+
+const COLD_CATEGORY: &str = "scene_cpu_cold";
+const COLD_MEASURES: &str =
+    "first frame: scene parsing + render context setup + replay + rasterize; excludes readback";
+
+/// One phase of [`CpuSceneRenderer::render_frame`], benchmarked on its own
+/// as `scene_cpu/<name>/<phase>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScenePhase {
+    /// [`CpuSceneRenderer::encode_frame`].
+    Encode,
+    /// [`CpuSceneRenderer::flush_frame`].
+    Flush,
+    /// [`CpuSceneRenderer::rasterize_frame`].
+    Rasterize,
+}
+
+impl ScenePhase {
+    /// All phases, in pipeline order.
+    pub const ALL: [Self; 3] = [Self::Encode, Self::Flush, Self::Rasterize];
+
+    /// Suffix of the benchmark ID, e.g. `"encode"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Encode => "encode",
+            Self::Flush => "flush",
+            Self::Rasterize => "rasterize",
+        }
+    }
+
+    /// The phase called `name`, see [`Self::name`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|phase| phase.name() == name)
+    }
+
+    fn measures(self) -> &'static str {
+        match self {
+            Self::Encode => ENCODE_MEASURES,
+            Self::Flush => FLUSH_MEASURES,
+            Self::Rasterize => RASTERIZE_MEASURES,
+        }
+    }
+}
 
 /// Encapsulates all state needed to render a scene with the Vello CPU backend.
 ///
 /// Used by both benchmarks (hot loop) and screenshots (single render) to
 /// ensure the exact same codepath.
 pub struct CpuSceneRenderer {
+    name: String,
     anyrender_ctx: anyrender_vello_cpu::VelloCpuRenderContext,
     render_ctx: VelloCpuRenderCtx,
     pixmap: Pixmap,
@@ -28,22 +127,93 @@ pub struct CpuSceneRenderer {
 }
 
 impl CpuSceneRenderer {
+    /// Alpha convention of [`Self::into_rgba`].
+    pub const ALPHA_MODE: AlphaMode = AlphaMode::Straight;
+
     /// Set up a CPU renderer for the given scene and SIMD level.
+    ///
+    /// The deserialized scene is taken from the
+    /// [scene cache](crate::scene_cache) if [`Self::into_cache`] left it
+    /// there.
     pub fn new(item: &SceneItem, level: Level) -> Self {
+        Self::with_pixmap(item, level, Pixmap::new(item.width, item.height))
+    }
+
+    /// Like [`Self::new`], always deserializing the scene anew.
+    pub fn uncached(item: &SceneItem, level: Level) -> Self {
+        let settings = RenderSettings {
+            level,
+            ..Default::default()
+        };
+        Self::with_settings(item, settings, Pixmap::new(item.width, item.height), false)
+    }
+
+    /// Set up a CPU renderer for the embedded scene called `name`.
+    pub fn from_name(name: &str, level: Level) -> Result<Self, String> {
+        Ok(Self::new(scene_item(&EmbeddedScenes, name)?, level))
+    }
+
+    /// Like [`Self::new`], rendering into `pixmap` instead of a new one, e.g.
+    /// one from a [`PixmapPool`](crate::screenshot::PixmapPool).
+    ///
+    /// Panics if `pixmap` isn't the size of the scene.
+    pub fn with_pixmap(item: &SceneItem, level: Level, pixmap: Pixmap) -> Self {
         let settings = RenderSettings {
             level,
             ..Default::default()
         };
+        Self::with_settings(item, settings, pixmap, true)
+    }
+
+    /// Like [`Self::new`], rendering with `num_threads` worker threads; 0
+    /// renders on the calling thread.
+    pub fn with_threads(item: &SceneItem, level: Level, num_threads: u16) -> Self {
+        let settings = RenderSettings {
+            level,
+            num_threads,
+            ..Default::default()
+        };
+        Self::with_settings(item, settings, Pixmap::new(item.width, item.height), true)
+    }
+
+    /// Like [`Self::new`], rendering in `mode` instead of
+    /// `RenderMode::default()`.
+    pub fn with_mode(item: &SceneItem, level: Level, mode: RenderMode) -> Self {
+        let settings = RenderSettings {
+            level,
+            render_mode: mode,
+            ..Default::default()
+        };
+        Self::with_settings(item, settings, Pixmap::new(item.width, item.height), true)
+    }
+
+    fn with_settings(
+        item: &SceneItem,
+        settings: RenderSettings,
+        pixmap: Pixmap,
+        cached: bool,
+    ) -> Self {
+        if let Err(e) = check_pixmap_size(&pixmap, item.width, item.height) {
+            panic!("{e}");
+        }
         let render_ctx = VelloCpuRenderCtx::new_with(item.width, item.height, settings);
-        let pixmap = Pixmap::new(item.width, item.height);
 
-        let mut anyrender_ctx = anyrender_vello_cpu::VelloCpuRenderContext::new();
-        let scene = item
-            .archive
-            .to_scene(&mut anyrender_ctx)
-            .expect("Failed to deserialize scene for CPU backend");
+        let deserialize = || {
+            let mut anyrender_ctx = anyrender_vello_cpu::VelloCpuRenderContext::new();
+            let scene = item
+                .archive
+                .to_scene(&mut anyrender_ctx)
+                .expect("Failed to deserialize scene for CPU backend");
+            (anyrender_ctx, scene)
+        };
+        let (anyrender_ctx, scene) = if cached {
+            scene_cache::take_or_deserialize(&item.name, deserialize)
+        } else {
+            deserialize()
+        };
 
         Self {
+            name: item.name.clone(),
             anyrender_ctx,
             render_ctx,
             pixmap,
@@ -51,50 +221,160 @@ impl CpuSceneRenderer {
         }
     }
 
-    /// Render one frame. This is the benchmarked operation.
+    /// Render one frame. This is the benchmarked operation: the three
+    /// phases [`Self::encode_frame`], [`Self::flush_frame`] and
+    /// [`Self::rasterize_frame`] in order.
     #[inline(always)]
     pub fn render_frame(&mut self) {
-        {
-            let mut painter = anyrender_vello_cpu::VelloCpuScenePainter::new(
-                &self.anyrender_ctx,
-                &mut self.render_ctx,
-            );
-            painter.reset();
-            painter.append_scene(self.scene.clone(), Affine::IDENTITY);
-        }
+        self.encode_frame();
+        self.flush_frame();
+        self.rasterize_frame();
+    }
+
+    /// Reset the render context and replay the scene into it.
+    ///
+    /// See the module docs for what persists across frames.
+    #[inline(always)]
+    pub fn encode_frame(&mut self) {
+        let mut painter = anyrender_vello_cpu::VelloCpuScenePainter::new(
+            &self.anyrender_ctx,
+            &mut self.render_ctx,
+        );
+        painter.reset();
+        painter.append_scene(self.scene.clone(), Affine::IDENTITY);
+    }
+
+    /// Flush the scene encoded by [`Self::encode_frame`].
+    #[inline(always)]
+    pub fn flush_frame(&mut self) {
         self.render_ctx.flush();
+    }
+
+    /// Rasterize the flushed scene to the pixmap.
+    #[inline(always)]
+    pub fn rasterize_frame(&mut self) {
         self.render_ctx.render_to_pixmap(&mut self.pixmap);
     }
 
+    /// Consume the renderer, leaving the deserialized scene in the
+    /// [scene cache](crate::scene_cache) for the next renderer of the same
+    /// scene.
+    pub fn into_cache(self) {
+        scene_cache::put_back(&self.name, self.anyrender_ctx, self.scene);
+    }
+
     /// Consume the renderer and extract non-premultiplied RGBA8 pixel data.
     pub fn into_rgba(self) -> Vec<u8> {
-        self.pixmap
-            .take_unpremultiplied()
-            .into_iter()
-            .flat_map(|p| [p.r, p.g, p.b, p.a])
-            .collect()
+        let mut rgba = Vec::new();
+        self.into_rgba_into(&mut rgba);
+        rgba
+    }
+
+    /// Like [`Self::into_rgba`], writing the pixel data to `out` (replacing
+    /// its content) and returning the pixmap for reuse. Its pixels are
+    /// unspecified.
+    pub fn into_rgba_into(self, out: &mut Vec<u8>) -> Pixmap {
+        unpremultiplied_rgba_into(self.pixmap, out)
     }
 }
 
 pub fn list() -> Vec<BenchmarkInfo> {
-    get_scenes()
-        .iter()
-        .map(|item| BenchmarkInfo {
-            id: format!("{CATEGORY}/{}", item.name),
-            category: CATEGORY.into(),
-            name: item.name.clone(),
+    list_from(&EmbeddedScenes)
+}
+
+/// Benchmarks of the scenes in `source`.
+pub fn list_from(source: &dyn SceneSource) -> Vec<BenchmarkInfo> {
+    BenchmarkInfo::from_names(CATEGORY, &source.names(), MEASURES)
+}
+
+/// Phase benchmarks of the scenes in `source`, see [`ScenePhase`].
+pub fn list_phases_from(source: &dyn SceneSource) -> Vec<BenchmarkInfo> {
+    source
+        .names()
+        .into_iter()
+        .flat_map(|name| {
+            ScenePhase::ALL.into_iter().map(move |phase| {
+                BenchmarkInfo::new(
+                    CATEGORY,
+                    format!("{name}/{}", phase.name()),
+                    phase.measures(),
+                )
+            })
         })
         .collect()
 }
 
+/// Thread count benchmarks of the scenes in `source`. Empty on WASM.
+pub fn list_threaded_from(source: &dyn SceneSource) -> Vec<BenchmarkInfo> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let cores = crate::benchmarks::cpu_threads::max_threads();
+        let mut counts: Vec<u16> = THREAD_COUNTS
+            .iter()
+            .copied()
+            .chain([cores])
+            .filter(|&threads| threads <= cores)
+            .collect();
+        counts.sort_unstable();
+        counts.dedup();
+
+        let names: Vec<String> = source
+            .names()
+            .into_iter()
+            .flat_map(|scene| {
+                counts
+                    .iter()
+                    .map(move |threads| format!("{scene}_t{threads}"))
+            })
+            .collect();
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        BenchmarkInfo::from_names(THREADED_CATEGORY, &names, THREADED_MEASURES)
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = source;
+        Vec::new()
+    }
+}
+
+/// Split a `scene_cpu_mt` benchmark name into scene name and thread count,
+/// e.g. `"demo_scene_t4"` into `("demo_scene", 4)`.
+pub fn split_threads(name: &str) -> Option<(&str, u16)> {
+    let (scene, threads) = name.rsplit_once("_t")?;
+    Some((scene, threads.parse().ok()?))
+}
+
+/// Cold first-frame benchmarks of the scenes in `source`.
+pub fn list_cold_from(source: &dyn SceneSource) -> Vec<BenchmarkInfo> {
+    BenchmarkInfo::from_names(COLD_CATEGORY, &source.names(), COLD_MEASURES)
+}
+
 pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
-    let scenes = get_scenes();
-    let item = scenes.iter().find(|s| s.name == name)?;
+    run_from(&EmbeddedScenes, name, runner, level)
+}
+
+/// Run the benchmark of scene `name` from `source`, of one phase of it if
+/// `name` ends in `/<phase>`, or in an explicit render mode if it ends in
+/// `_speed` or `_quality`.
+pub fn run_from(
+    source: &dyn SceneSource,
+    name: &str,
+    runner: &BenchRunner,
+    level: Level,
+) -> Option<BenchmarkResult> {
+    if let Some((scene, phase)) = name.rsplit_once('/') {
+        return run_phase_from(source, scene, ScenePhase::from_name(phase)?, runner, level);
+    }
+    if let (scene, Some(mode)) = split_render_mode_from(source, name) {
+        return run_with_mode_from(source, scene, runner, level, mode);
+    }
+
+    let item = source.get(name)?;
     let simd_variant = level_suffix(level);
 
     let mut renderer = CpuSceneRenderer::new(item, level);
 
-    Some(runner.run(
+    let result = runner.run(
         &format!("{CATEGORY}/{name}"),
         CATEGORY,
         name,
@@ -104,5 +384,162 @@ pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkRe
             renderer.render_frame();
             std::hint::black_box(&renderer);
         },
+    );
+    renderer.into_cache();
+    Some(result)
+}
+
+/// Split a benchmark name into scene name and explicit render mode, like
+/// [`split_render_mode`](crate::benchmarks::vello_cpu::split_render_mode)
+/// but for any scene in `source`. A scene whose own name ends in a mode
+/// suffix is returned unchanged.
+pub fn split_render_mode_from<'a>(
+    source: &dyn SceneSource,
+    name: &'a str,
+) -> (&'a str, Option<RenderMode>) {
+    if source.get(name).is_some() {
+        return (name, None);
+    }
+    RENDER_MODES
+        .into_iter()
+        .find_map(|mode| {
+            let scene = name
+                .strip_suffix(render_mode_suffix(mode))?
+                .strip_suffix('_')?;
+            source.get(scene).map(|_| (scene, Some(mode)))
+        })
+        .unwrap_or((name, None))
+}
+
+/// Run scene `name` from `source` in render mode `mode`. The result ID
+/// carries the mode suffix, e.g. `scene_cpu/demo_scene_quality`, and the
+/// result records the mode.
+pub fn run_with_mode_from(
+    source: &dyn SceneSource,
+    name: &str,
+    runner: &BenchRunner,
+    level: Level,
+    mode: RenderMode,
+) -> Option<BenchmarkResult> {
+    let item = source.get(name)?;
+    let name = format!("{name}_{}", render_mode_suffix(mode));
+
+    let mut renderer = CpuSceneRenderer::with_mode(item, level, mode);
+
+    let mut result = runner.run(
+        &format!("{CATEGORY}/{name}"),
+        CATEGORY,
+        &name,
+        level_suffix(level),
+        #[inline(always)]
+        || {
+            renderer.render_frame();
+            std::hint::black_box(&renderer);
+        },
+    );
+    renderer.into_cache();
+    result.render_mode = Some(render_mode_suffix(mode).to_string());
+    Some(result)
+}
+
+/// Run the benchmark of `phase` of scene `name` from `source`. The phases
+/// before it run untimed before every iteration.
+pub fn run_phase_from(
+    source: &dyn SceneSource,
+    name: &str,
+    phase: ScenePhase,
+    runner: &BenchRunner,
+    level: Level,
+) -> Option<BenchmarkResult> {
+    let item = source.get(name)?;
+    let simd_variant = level_suffix(level);
+
+    let mut renderer = CpuSceneRenderer::new(item, level);
+
+    let result = runner.run_with_setup(
+        &format!("{CATEGORY}/{name}/{}", phase.name()),
+        CATEGORY,
+        &format!("{name}/{}", phase.name()),
+        simd_variant,
+        &mut renderer,
+        |renderer| match phase {
+            ScenePhase::Encode => {}
+            ScenePhase::Flush => renderer.encode_frame(),
+            ScenePhase::Rasterize => {
+                renderer.encode_frame();
+                renderer.flush_frame();
+            }
+        },
+        |renderer| {
+            match phase {
+                ScenePhase::Encode => renderer.encode_frame(),
+                ScenePhase::Flush => renderer.flush_frame(),
+                ScenePhase::Rasterize => renderer.rasterize_frame(),
+            }
+            std::hint::black_box(&renderer);
+        },
+    );
+    renderer.into_cache();
+    Some(result)
+}
+
+/// Run the `scene_cpu_mt` benchmark `name` (see [`split_threads`]) with
+/// scenes from `source`. Always `None` on WASM, which renders on a single
+/// thread.
+pub fn run_threaded_from(
+    source: &dyn SceneSource,
+    name: &str,
+    runner: &BenchRunner,
+    level: Level,
+) -> Option<BenchmarkResult> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let (scene, threads) = split_threads(name)?;
+        let item = source.get(scene)?;
+
+        let mut renderer = CpuSceneRenderer::with_threads(item, level, threads);
+
+        let mut result = runner.run(
+            &format!("{THREADED_CATEGORY}/{name}"),
+            THREADED_CATEGORY,
+            name,
+            level_suffix(level),
+            #[inline(always)]
+            || {
+                renderer.render_frame();
+                std::hint::black_box(&renderer);
+            },
+        );
+        renderer.into_cache();
+        result.num_threads = Some(threads);
+        Some(result)
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = (source, name, runner, level);
+        None
+    }
+}
+
+/// Run the cold first-frame benchmark of scene `name` from `source`.
+pub fn run_cold_from(
+    source: &dyn SceneSource,
+    name: &str,
+    runner: &BenchRunner,
+    level: Level,
+) -> Option<BenchmarkResult> {
+    let item = source.get(name)?;
+    let simd_variant = level_suffix(level);
+
+    Some(runner.measure_first_iteration(
+        &format!("{COLD_CATEGORY}/{name}"),
+        COLD_CATEGORY,
+        name,
+        simd_variant,
+        || {
+            let mut renderer = CpuSceneRenderer::uncached(item, level);
+            renderer.render_frame();
+            std::hint::black_box(renderer)
+        },
     ))
 }
@@ -7,14 +7,50 @@
 //! Each scene in the `scenes/` directory becomes a benchmark under the
 //! `scene_hybrid` category. The benchmark measures the full hybrid
 //! rendering pipeline: scene replay + GPU rendering + GPU sync.
+//!
+//! The deserialized scene is kept in the [scene cache](crate::scene_cache)
+//! between runs, together with its context and the renderer its images were
+//! uploaded to, as long as the wgpu device stays the same.
+//!
+//! `scene_hybrid_cold` times the first frame instead, natively only:
+//! building a fresh `HybridSceneRenderer` (render target, renderer, parsed
+//! scene, and the GPU device if `keep_gpu_devices` is off) and rendering
+//! once, repeated a few times (see [`BenchRunner::measure_first_iteration`]).
+//!
+//! Where the adapter supports timestamp queries, the GPU time of the measured
+//! frames is recorded in [`Statistics::gpu_time_ns`](crate::result::Statistics::gpu_time_ns)
+//! alongside the wall-clock time; see [`crate::gpu_timestamps`].
+//!
+//! GPU syncs are bounded by [`crate::gpu_watchdog`]; a hung GPU aborts the
+//! benchmark instead of blocking forever. They wait for the whole device, so
+//! results record [`SyncMode::Finish`](crate::result::SyncMode::Finish).
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::gpu_timestamps::GpuTimestamps;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::gpu_watchdog::wait_for_gpu;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::readback::{copy_unpadded_rows, padded_bytes_per_row};
 use crate::registry::BenchmarkInfo;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::renderer::{GpuDevice, gpu_device, gpu_devices_kept};
 use crate::result::BenchmarkResult;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::result::SyncMode;
 use crate::runner::BenchRunner;
-use crate::scenes::get_scenes;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::scene_cache;
+use crate::scenes::{EmbeddedScenes, SceneSource};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::screenshot::AlphaMode;
 use fearless_simd::Level;
 
 const CATEGORY: &str = "scene_hybrid";
+const MEASURES: &str =
+    "scene replay via anyrender + GPU render + GPU sync; excludes parsing and readback";
+const COLD_CATEGORY: &str = "scene_hybrid_cold";
+const COLD_MEASURES: &str =
+    "first frame: GPU and renderer setup + scene parsing + render + GPU sync; excludes readback";
 
 /// Encapsulates all state needed to render a scene with the Vello Hybrid
 /// (wgpu) backend.
@@ -24,50 +60,111 @@ const CATEGORY: &str = "scene_hybrid";
 /// handled by `vello_bench_wasm`.
 #[cfg(not(target_arch = "wasm32"))]
 pub struct HybridSceneRenderer {
+    name: String,
     gpu: GpuContext,
     renderer: vello_hybrid::Renderer,
     hybrid_scene: vello_hybrid::Scene,
     render_size: vello_hybrid::RenderSize,
     ctx: anyrender_vello_hybrid::VelloHybridRenderContext,
     scene: anyrender::Scene,
+    timestamps: Option<GpuTimestamps>,
+}
+
+/// What the [scene cache](crate::scene_cache) keeps of a
+/// [`HybridSceneRenderer`] besides the scene: the context holding the
+/// scene's images and the renderer they were uploaded to, which only works
+/// on the device it was created on.
+#[cfg(not(target_arch = "wasm32"))]
+struct HybridSceneContext {
+    ctx: anyrender_vello_hybrid::VelloHybridRenderContext,
+    renderer: vello_hybrid::Renderer,
+    device: wgpu::Device,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 impl HybridSceneRenderer {
+    /// Alpha convention of [`Self::into_rgba`].
+    pub const ALPHA_MODE: AlphaMode = AlphaMode::Premultiplied;
+
     /// Set up a Hybrid renderer for the given scene (initialises wgpu).
+    ///
+    /// The deserialized scene and the renderer holding its images are taken
+    /// from the [scene cache](crate::scene_cache) if [`Self::into_cache`]
+    /// left them there for the same device.
     pub fn new(item: &crate::scenes::SceneItem) -> Self {
+        Self::with_cache(item, true)
+    }
+
+    /// Like [`Self::new`], always deserializing the scene anew.
+    pub fn uncached(item: &crate::scenes::SceneItem) -> Self {
+        Self::with_cache(item, false)
+    }
+
+    fn with_cache(item: &crate::scenes::SceneItem, cached: bool) -> Self {
         let width = item.width as u32;
         let height = item.height as u32;
 
-        let gpu = pollster::block_on(init_gpu(width, height));
+        let gpu = init_gpu(width, height);
+        let timestamps = GpuTimestamps::new(&gpu.device, &gpu.queue);
 
         let render_target_config = vello_hybrid::RenderTargetConfig {
             format: wgpu::TextureFormat::Rgba8Unorm,
             width,
             height,
         };
-
-        let renderer = vello_hybrid::Renderer::new(&gpu.device, &render_target_config);
         let hybrid_scene = vello_hybrid::Scene::new(item.width, item.height);
         let render_size = vello_hybrid::RenderSize { width, height };
 
-        let mut ctx = anyrender_vello_hybrid::VelloHybridRenderContext::new();
-        let scene = item
-            .archive
-            .to_scene(&mut ctx)
-            .expect("Failed to deserialize scene for Hybrid backend");
+        let deserialize = || {
+            let mut ctx = anyrender_vello_hybrid::VelloHybridRenderContext::new();
+            let scene = item
+                .archive
+                .to_scene(&mut ctx)
+                .expect("Failed to deserialize scene for Hybrid backend");
+            let context = HybridSceneContext {
+                ctx,
+                renderer: vello_hybrid::Renderer::new(&gpu.device, &render_target_config),
+                device: gpu.device.clone(),
+            };
+            (context, scene)
+        };
+        let (context, scene) = if cached {
+            let (context, scene) = scene_cache::take_or_deserialize(&item.name, &deserialize);
+            if context.device == gpu.device {
+                (context, scene)
+            } else {
+                // Cached on a device dropped since, e.g. after a GPU timeout.
+                // Taking it removed it, so this deserializes.
+                scene_cache::take_or_deserialize(&item.name, &deserialize)
+            }
+        } else {
+            deserialize()
+        };
+        let HybridSceneContext { ctx, renderer, .. } = context;
 
         Self {
+            name: item.name.clone(),
             gpu,
             renderer,
             hybrid_scene,
             render_size,
             ctx,
             scene,
+            timestamps,
         }
     }
 
+    /// Set up a Hybrid renderer for the embedded scene called `name`.
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        let item = crate::harness::scene_item(&EmbeddedScenes, name)?;
+        Ok(Self::new(item))
+    }
+
     /// Render one frame. This is the benchmarked operation.
+    ///
+    /// Resets the hybrid scene before replaying the scene, like the other
+    /// backends; see the `scene_cpu` module docs for what persists across
+    /// frames.
     #[inline(always)]
     pub fn render_frame(&mut self) {
         use anyrender::PaintScene;
@@ -92,9 +189,13 @@ impl HybridSceneRenderer {
                 &self.gpu.queue,
                 &mut self.hybrid_scene,
             );
+            painter.reset();
             painter.append_scene(self.scene.clone(), Affine::IDENTITY);
         }
 
+        if let Some(timestamps) = &self.timestamps {
+            timestamps.begin(&mut encoder);
+        }
         self.renderer
             .render(
                 &self.hybrid_scene,
@@ -105,26 +206,64 @@ impl HybridSceneRenderer {
                 &texture_view,
             )
             .expect("Hybrid render failed");
+        if let Some(timestamps) = &self.timestamps {
+            timestamps.end(&mut encoder);
+        }
 
         self.gpu.queue.submit(Some(encoder.finish()));
-        self.gpu
-            .device
-            .poll(wgpu::PollType::wait_indefinitely())
-            .unwrap();
+        if let Some(timestamps) = &self.timestamps {
+            timestamps.start_readback();
+        }
+        wait_for_gpu(&self.gpu.device, &self.gpu.adapter);
+        if let Some(timestamps) = &mut self.timestamps {
+            timestamps.finish_readback();
+        }
+    }
+
+    /// Mean GPU time of the last `frames` frames rendered by
+    /// [`Self::render_frame`] in nanoseconds, or `None` if the adapter has no
+    /// timestamp queries or no frame was timed.
+    pub fn gpu_time_ns(&self, frames: usize) -> Option<f64> {
+        let times = self.timestamps.as_ref()?.times_ns();
+        let recent = &times[times.len().saturating_sub(frames)..];
+        (!recent.is_empty()).then(|| recent.iter().sum::<f64>() / recent.len() as f64)
+    }
 
-        self.hybrid_scene.reset();
+    /// Consume the renderer, leaving the deserialized scene and the renderer
+    /// holding its images in the [scene cache](crate::scene_cache) for the
+    /// next renderer of the same scene. Drops them instead if devices aren't
+    /// kept (see [`keep_gpu_devices`](crate::renderer::keep_gpu_devices)),
+    /// as the next renderer gets another device.
+    pub fn into_cache(self) {
+        if !gpu_devices_kept() {
+            return;
+        }
+        let context = HybridSceneContext {
+            ctx: self.ctx,
+            renderer: self.renderer,
+            device: self.gpu.device,
+        };
+        scene_cache::put_back(&self.name, context, self.scene);
     }
 
     /// Consume the renderer, do one final render, and read the GPU texture
-    /// back to a CPU buffer as non-premultiplied RGBA8.
-    pub fn into_rgba(mut self) -> Vec<u8> {
+    /// back to a CPU buffer as premultiplied RGBA8.
+    pub fn into_rgba(self) -> Vec<u8> {
+        let mut rgba = Vec::new();
+        self.into_rgba_into(&mut rgba);
+        rgba
+    }
+
+    /// Like [`Self::into_rgba`], writing the pixel data to `out` and
+    /// replacing its content.
+    pub fn into_rgba_into(mut self, out: &mut Vec<u8>) {
         // Ensure there is a rendered frame on the texture.
         self.render_frame();
 
         let width = self.render_size.width;
         let height = self.render_size.height;
 
-        let bytes_per_row = align_to(width * 4, 256);
+        let bytes_per_row = padded_bytes_per_row(width);
         let readback_buffer = self.gpu.device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("screenshot_readback"),
             size: (bytes_per_row * height) as u64,
@@ -160,77 +299,85 @@ impl HybridSceneRenderer {
         );
 
         self.gpu.queue.submit(Some(encoder.finish()));
-        self.gpu
-            .device
-            .poll(wgpu::PollType::wait_indefinitely())
-            .unwrap();
+        wait_for_gpu(&self.gpu.device, &self.gpu.adapter);
 
         let buffer_slice = readback_buffer.slice(..);
         let (tx, rx) = std::sync::mpsc::channel();
         buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
             tx.send(result).unwrap();
         });
-        self.gpu
-            .device
-            .poll(wgpu::PollType::wait_indefinitely())
-            .unwrap();
+        wait_for_gpu(&self.gpu.device, &self.gpu.adapter);
         rx.recv().unwrap().expect("Failed to map buffer");
 
         let data = buffer_slice.get_mapped_range();
 
-        // Strip row padding (bytes_per_row may be larger than width * 4).
-        let row_bytes = (width * 4) as usize;
-        let mut rgba = Vec::with_capacity((width * height * 4) as usize);
-        for row in 0..height as usize {
-            let start = row * bytes_per_row as usize;
-            rgba.extend_from_slice(&data[start..start + row_bytes]);
-        }
+        // vello_hybrid writes premultiplied colors; the Rgba8Unorm texture
+        // format stores them as-is. Strip row padding (bytes_per_row may be
+        // larger than width * 4).
+        out.clear();
+        out.resize((width * height * 4) as usize, 0);
+        copy_unpadded_rows(&data, bytes_per_row as usize, width, height, out)
+            .expect("readback buffer is sized for the render target");
 
         drop(data);
         readback_buffer.unmap();
-
-        // Rgba8Unorm is already non-premultiplied — no conversion needed.
-        rgba
     }
 }
 
 pub fn list() -> Vec<BenchmarkInfo> {
-    get_scenes()
-        .iter()
-        .map(|item| BenchmarkInfo {
-            id: format!("{CATEGORY}/{}", item.name),
-            category: CATEGORY.into(),
-            name: item.name.clone(),
-        })
-        .collect()
+    list_from(&EmbeddedScenes)
+}
+
+/// Benchmarks of the scenes in `source`.
+pub fn list_from(source: &dyn SceneSource) -> Vec<BenchmarkInfo> {
+    BenchmarkInfo::from_names(CATEGORY, &source.names(), MEASURES)
+}
+
+/// Cold first-frame benchmarks of the scenes in `source`.
+pub fn list_cold_from(source: &dyn SceneSource) -> Vec<BenchmarkInfo> {
+    BenchmarkInfo::from_names(COLD_CATEGORY, &source.names(), COLD_MEASURES)
 }
 
 /// Run a hybrid benchmark. On WASM this always returns `None` because
 /// hybrid WASM benchmarks are driven from JS via the `vello_bench_wasm` crate.
 pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
+    run_from(&EmbeddedScenes, name, runner, level)
+}
+
+/// [`run`] for scene `name` from `source`.
+pub fn run_from(
+    source: &dyn SceneSource,
+    name: &str,
+    runner: &BenchRunner,
+    level: Level,
+) -> Option<BenchmarkResult> {
     #[cfg(not(target_arch = "wasm32"))]
     {
-        run_native(name, runner, level)
+        run_native(source, name, runner, level)
     }
     #[cfg(target_arch = "wasm32")]
     {
-        let _ = (name, runner, level);
+        let _ = (source, name, runner, level);
         // Hybrid WASM benchmarks are handled by vello_bench_wasm on the main thread.
         None
     }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-fn run_native(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
+fn run_native(
+    source: &dyn SceneSource,
+    name: &str,
+    runner: &BenchRunner,
+    level: Level,
+) -> Option<BenchmarkResult> {
     use crate::simd::level_suffix;
 
-    let scenes = get_scenes();
-    let item = scenes.iter().find(|s| s.name == name)?;
+    let item = source.get(name)?;
     let simd_variant = level_suffix(level);
 
     let mut renderer = HybridSceneRenderer::new(item);
 
-    Some(runner.run(
+    let mut result = runner.run(
         &format!("{CATEGORY}/{name}"),
         CATEGORY,
         name,
@@ -239,31 +386,63 @@ fn run_native(name: &str, runner: &BenchRunner, level: Level) -> Option<Benchmar
         || {
             renderer.render_frame();
         },
-    ))
+    );
+    result.sync_mode = Some(SyncMode::Finish);
+    // The warm-up frames come first, so the last ones are those measured.
+    result.statistics.gpu_time_ns = renderer.gpu_time_ns(result.statistics.iterations);
+    renderer.into_cache();
+    Some(result)
+}
+
+/// Run the cold first-frame benchmark of scene `name` from `source`. Always
+/// `None` on WASM, where the WebGL renderer belongs to the page's canvas.
+pub fn run_cold_from(
+    source: &dyn SceneSource,
+    name: &str,
+    runner: &BenchRunner,
+    level: Level,
+) -> Option<BenchmarkResult> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let item = source.get(name)?;
+        let simd_variant = crate::simd::level_suffix(level);
+
+        let mut result = runner.measure_first_iteration(
+            &format!("{COLD_CATEGORY}/{name}"),
+            COLD_CATEGORY,
+            name,
+            simd_variant,
+            || {
+                let mut renderer = HybridSceneRenderer::uncached(item);
+                renderer.render_frame();
+                renderer
+            },
+        );
+        result.sync_mode = Some(SyncMode::Finish);
+        Some(result)
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = (source, name, runner, level);
+        None
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 struct GpuContext {
+    adapter: wgpu::AdapterInfo,
     device: wgpu::Device,
     queue: wgpu::Queue,
     texture: wgpu::Texture,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-async fn init_gpu(width: u32, height: u32) -> GpuContext {
-    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
-    let adapter = instance
-        .request_adapter(&wgpu::RequestAdapterOptions {
-            power_preference: wgpu::PowerPreference::HighPerformance,
-            ..Default::default()
-        })
-        .await
-        .expect("Failed to find a suitable GPU adapter");
-
-    let (device, queue) = adapter
-        .request_device(&wgpu::DeviceDescriptor::default())
-        .await
-        .expect("Failed to create GPU device");
+fn init_gpu(width: u32, height: u32) -> GpuContext {
+    let GpuDevice {
+        adapter,
+        device,
+        queue,
+    } = gpu_device(wgpu::PowerPreference::HighPerformance);
 
     let texture = device.create_texture(&wgpu::TextureDescriptor {
         label: Some("bench_render_target"),
@@ -281,14 +460,93 @@ async fn init_gpu(width: u32, height: u32) -> GpuContext {
     });
 
     GpuContext {
+        adapter,
         device,
         queue,
         texture,
     }
 }
 
-/// Round `value` up to the next multiple of `alignment`.
-#[cfg(not(target_arch = "wasm32"))]
-fn align_to(value: u32, alignment: u32) -> u32 {
-    (value + alignment - 1) / alignment * alignment
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::renderer::gpu_adapter_info;
+    use crate::scenes::TestSceneSource;
+    use anyrender::PaintScene;
+    use vello_common::kurbo::{Affine, Rect};
+    use vello_common::peniko::Fill;
+    use vello_common::peniko::color::palette;
+
+    #[test]
+    fn repeated_runs_deserialize_once() {
+        if gpu_adapter_info().is_none() {
+            eprintln!("Skipped: no GPU adapter");
+            return;
+        }
+        let source = TestSceneSource::new();
+        let runner = BenchRunner::new(0, 2);
+        let before = scene_cache::deserialized_scene_count();
+        for _ in 0..2 {
+            run_from(&source, "test_layer", &runner, Level::new()).expect("the scene exists");
+        }
+        assert_eq!(scene_cache::deserialized_scene_count() - before, 1);
+
+        // The cold benchmark deserializes every time, bypassing the cache.
+        run_cold_from(&source, "test_layer", &runner, Level::new()).expect("the scene exists");
+        assert_eq!(scene_cache::deserialized_scene_count() - before, 1);
+    }
+
+    #[test]
+    fn renderers_of_different_sizes_share_one_device() {
+        if gpu_adapter_info().is_none() {
+            eprintln!("Skipped: no GPU adapter");
+            return;
+        }
+        let source = TestSceneSource::new().with_scene("wide", 256, 32, |_| {});
+        let small = HybridSceneRenderer::new(source.get("test_rect").unwrap());
+        let wide = HybridSceneRenderer::new(source.get("wide").unwrap());
+
+        assert!(small.gpu.device == wide.gpu.device);
+        assert!(small.gpu.queue == wide.gpu.queue);
+        let size = |renderer: &HybridSceneRenderer| {
+            let texture = &renderer.gpu.texture;
+            (texture.width(), texture.height())
+        };
+        let test_size = u32::from(TestSceneSource::SIZE);
+        assert_eq!(size(&small), (test_size, test_size));
+        assert_eq!(size(&wide), (256, 32));
+    }
+
+    #[test]
+    fn gpu_time_fits_in_the_wall_time() {
+        if gpu_adapter_info().is_none() {
+            eprintln!("Skipped: no GPU adapter");
+            return;
+        }
+        // 4000 overlapping rects, enough for the GPU to take measurable time.
+        let source = TestSceneSource::empty().with_scene("rects", 512, 512, |scene| {
+            for i in 0..4000 {
+                let x = f64::from(i % 64) * 8.0;
+                let y = f64::from(i / 64) * 8.0;
+                let rect = Rect::new(x, y, x + 12.0, y + 12.0);
+                let color = palette::css::STEEL_BLUE.with_alpha(0.5);
+                scene.fill(Fill::NonZero, Affine::IDENTITY, color, None, &rect);
+            }
+        });
+
+        let result = run_from(&source, "rects", &BenchRunner::new(3, 20), Level::new())
+            .expect("the scene exists");
+        let statistics = &result.statistics;
+        let Some(gpu_time_ns) = statistics.gpu_time_ns else {
+            eprintln!("Skipped: no timestamp queries on this adapter");
+            return;
+        };
+        assert!(gpu_time_ns > 0.0, "GPU time {gpu_time_ns} ns");
+        assert!(statistics.mean_ns > 0.0);
+        assert!(
+            gpu_time_ns <= statistics.mean_ns,
+            "GPU time {gpu_time_ns} ns exceeds the wall time {} ns",
+            statistics.mean_ns
+        );
+    }
 }
@@ -10,10 +10,14 @@
 use crate::registry::BenchmarkInfo;
 use crate::result::BenchmarkResult;
 use crate::runner::BenchRunner;
-use crate::scenes::get_scenes;
+use crate::scenes::{EmbeddedScenes, SceneSource};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::screenshot::AlphaMode;
 use fearless_simd::Level;
 
 const CATEGORY: &str = "scene_skia";
+const MEASURES: &str =
+    "scene replay via anyrender + Skia render into an RGBA buffer; excludes parsing";
 
 /// Encapsulates all state needed to render a scene with the Skia backend.
 ///
@@ -21,6 +25,7 @@ const CATEGORY: &str = "scene_skia";
 /// ensure the exact same codepath. Native-only — Skia is not available on WASM.
 #[cfg(not(target_arch = "wasm32"))]
 pub struct SkiaSceneRenderer {
+    name: String,
     ctx: anyrender_skia::SkiaRenderContext,
     renderer: anyrender_skia::SkiaImageRenderer,
     buffer: Vec<u8>,
@@ -29,22 +34,47 @@ pub struct SkiaSceneRenderer {
 
 #[cfg(not(target_arch = "wasm32"))]
 impl SkiaSceneRenderer {
+    /// Alpha convention of [`Self::into_rgba`].
+    pub const ALPHA_MODE: AlphaMode = AlphaMode::Premultiplied;
+
     /// Set up a Skia renderer for the given scene.
+    ///
+    /// The deserialized scene is taken from the
+    /// [scene cache](crate::scene_cache) if [`Self::into_cache`] left it
+    /// there.
     pub fn new(item: &crate::scenes::SceneItem) -> Self {
+        Self::with_buffer(item, Vec::new())
+    }
+
+    /// Set up a Skia renderer for the embedded scene called `name`.
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        let item = crate::harness::scene_item(&EmbeddedScenes, name)?;
+        Ok(Self::new(item))
+    }
+
+    /// Like [`Self::new`], rendering into `buffer` instead of a new one, e.g.
+    /// one from a [`PixmapPool`](crate::screenshot::PixmapPool). Its content
+    /// is discarded.
+    pub fn with_buffer(item: &crate::scenes::SceneItem, mut buffer: Vec<u8>) -> Self {
         use anyrender::ImageRenderer;
 
         let width = item.width as u32;
         let height = item.height as u32;
-        let buffer = vec![0u8; (width * height * 4) as usize];
+        buffer.clear();
+        buffer.resize((width * height * 4) as usize, 0);
         let renderer = anyrender_skia::SkiaImageRenderer::new(width, height);
 
-        let mut ctx = anyrender_skia::SkiaRenderContext::new();
-        let scene = item
-            .archive
-            .to_scene(&mut ctx)
-            .expect("Failed to deserialize scene for Skia backend");
+        let (ctx, scene) = crate::scene_cache::take_or_deserialize(&item.name, || {
+            let mut ctx = anyrender_skia::SkiaRenderContext::new();
+            let scene = item
+                .archive
+                .to_scene(&mut ctx)
+                .expect("Failed to deserialize scene for Skia backend");
+            (ctx, scene)
+        });
 
         Self {
+            name: item.name.clone(),
             ctx,
             renderer,
             buffer,
@@ -53,6 +83,10 @@ impl SkiaSceneRenderer {
     }
 
     /// Render one frame. This is the benchmarked operation.
+    ///
+    /// Resets the painter before replaying the scene, like the other
+    /// backends; see the `scene_cpu` module docs for what persists across
+    /// frames. The output buffer is reused and fully overwritten.
     #[inline(always)]
     pub fn render_frame(&mut self) {
         use anyrender::ImageRenderer;
@@ -62,54 +96,74 @@ impl SkiaSceneRenderer {
         self.renderer.render(
             &mut self.ctx,
             |painter| {
+                painter.reset();
                 painter.append_scene(self.scene.clone(), Affine::IDENTITY);
             },
             &mut self.buffer,
         );
     }
 
-    /// Consume the renderer and return the RGBA8 pixel data.
+    /// Consume the renderer, leaving the deserialized scene in the
+    /// [scene cache](crate::scene_cache) for the next renderer of the same
+    /// scene.
+    pub fn into_cache(self) {
+        crate::scene_cache::put_back(&self.name, self.ctx, self.scene);
+    }
+
+    /// Consume the renderer and return the RGBA8 pixel data, premultiplied
+    /// as rasterized by Skia.
     pub fn into_rgba(self) -> Vec<u8> {
         self.buffer
     }
 }
 
 pub fn list() -> Vec<BenchmarkInfo> {
-    get_scenes()
-        .iter()
-        .map(|item| BenchmarkInfo {
-            id: format!("{CATEGORY}/{}", item.name),
-            category: CATEGORY.into(),
-            name: item.name.clone(),
-        })
-        .collect()
+    list_from(&EmbeddedScenes)
+}
+
+/// Benchmarks of the scenes in `source`.
+pub fn list_from(source: &dyn SceneSource) -> Vec<BenchmarkInfo> {
+    BenchmarkInfo::from_names(CATEGORY, &source.names(), MEASURES)
 }
 
 /// Run a Skia benchmark. On WASM this always returns `None` because
 /// Skia (skia-safe) is not available on the WASM target.
-pub fn run(name: &str, runner: &BenchRunner, _level: Level) -> Option<BenchmarkResult> {
+pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
+    run_from(&EmbeddedScenes, name, runner, level)
+}
+
+/// [`run`] for scene `name` from `source`.
+pub fn run_from(
+    source: &dyn SceneSource,
+    name: &str,
+    runner: &BenchRunner,
+    _level: Level,
+) -> Option<BenchmarkResult> {
     #[cfg(not(target_arch = "wasm32"))]
     {
-        run_native(name, runner)
+        run_native(source, name, runner)
     }
     #[cfg(target_arch = "wasm32")]
     {
-        let _ = (name, runner);
+        let _ = (source, name, runner);
         None
     }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-fn run_native(name: &str, runner: &BenchRunner) -> Option<BenchmarkResult> {
-    let scenes = get_scenes();
-    let item = scenes.iter().find(|s| s.name == name)?;
+fn run_native(
+    source: &dyn SceneSource,
+    name: &str,
+    runner: &BenchRunner,
+) -> Option<BenchmarkResult> {
+    let item = source.get(name)?;
 
     // Skia does not use SIMD level selection — always report "n/a".
     let simd_variant = "n/a";
 
     let mut renderer = SkiaSceneRenderer::new(item);
 
-    Some(runner.run(
+    let result = runner.run(
         &format!("{CATEGORY}/{name}"),
         CATEGORY,
         name,
@@ -119,5 +173,7 @@ fn run_native(name: &str, runner: &BenchRunner) -> Option<BenchmarkResult> {
             renderer.render_frame();
             std::hint::black_box(&renderer);
         },
-    ))
+    );
+    renderer.into_cache();
+    Some(result)
 }
@@ -0,0 +1,145 @@
+//! Cost of `set_paint` for the color types scenes typically pass.
+//!
+//! `set_paint(impl Into<PaintType>)` accepts anything that converts into a
+//! [`PaintType`], so it is easy to pass unconverted colors in a hot loop.
+//! Each benchmark times [`CALLS`] calls with one kind of argument:
+//!
+//! - `preconverted`: a [`PaintType`] built once, cloned per call.
+//! - `palette`: an sRGB [`AlphaColor`] such as a CSS palette constant.
+//! - `premul`: a [`PremulColor`], un-premultiplied per call.
+//! - `display_p3`: an [`AlphaColor`] in Display P3, converted to sRGB per
+//!   call.
+//!
+//! `set_paint_cpu` calls it on a Vello CPU `RenderContext`, `set_paint_hybrid`
+//! on a Vello Hybrid `Scene`, which needs no GPU. Results carry the call
+//! count as throughput, so the per-element time is the cost of one call.
+
+use crate::registry::BenchmarkInfo;
+use crate::result::{BenchmarkResult, Throughput};
+use crate::runner::BenchRunner;
+use crate::simd::level_suffix;
+use fearless_simd::Level;
+use std::hint::black_box;
+use vello_common::color::{AlphaColor, DisplayP3, PremulColor, Srgb};
+use vello_common::paint::PaintType;
+use vello_common::peniko::color::palette;
+
+const CATEGORY_CPU: &str = "set_paint_cpu";
+const CATEGORY_HYBRID: &str = "set_paint_hybrid";
+const MEASURES: &str = "1,000,000 `set_paint` calls, including converting the argument; \
+    excludes creating the context";
+
+/// `set_paint` calls per iteration.
+pub const CALLS: u32 = 1_000_000;
+
+/// Argument kinds, in listing order.
+const VARIANTS: &[&str] = &["preconverted", "palette", "premul", "display_p3"];
+
+/// Canvas size of the context; nothing is drawn.
+const SIZE: u16 = 64;
+
+pub fn list() -> Vec<BenchmarkInfo> {
+    let mut benchmarks = BenchmarkInfo::from_names(CATEGORY_CPU, VARIANTS, MEASURES);
+    benchmarks.extend(BenchmarkInfo::from_names(
+        CATEGORY_HYBRID,
+        VARIANTS,
+        MEASURES,
+    ));
+    benchmarks
+}
+
+/// Run a `set_paint_cpu` benchmark.
+pub fn run_cpu(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
+    use crate::renderer::Renderer;
+    use vello_cpu::{RenderContext, RenderMode};
+
+    let mut ctx: RenderContext = Renderer::new(SIZE, SIZE, 0, level, RenderMode::default());
+    run_variant(CATEGORY_CPU, name, runner, level, |paint| {
+        ctx.set_paint(paint)
+    })
+}
+
+/// Run a `set_paint_hybrid` benchmark. On WASM this always returns `None`:
+/// the core crate only links Vello Hybrid natively.
+pub fn run_hybrid(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let mut scene = vello_hybrid::Scene::new(SIZE, SIZE);
+        run_variant(CATEGORY_HYBRID, name, runner, level, |paint| {
+            scene.set_paint(paint);
+        })
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = (name, runner, level);
+        None
+    }
+}
+
+/// The argument a variant passes to `set_paint`, before conversion.
+enum Argument {
+    Preconverted(PaintType),
+    Palette(AlphaColor<Srgb>),
+    Premul(PremulColor<Srgb>),
+    DisplayP3(AlphaColor<DisplayP3>),
+}
+
+impl Argument {
+    fn for_variant(name: &str) -> Option<Self> {
+        let color = palette::css::ROYAL_BLUE.with_alpha(0.8);
+        Some(match name {
+            "preconverted" => Self::Preconverted(color.into()),
+            "palette" => Self::Palette(color),
+            "premul" => Self::Premul(color.premultiply()),
+            "display_p3" => Self::DisplayP3(color.convert()),
+            _ => return None,
+        })
+    }
+}
+
+/// Time [`CALLS`] calls of `set_paint` with the argument of variant `name`.
+///
+/// The argument goes through [`black_box`] on every call, so the conversion
+/// can't be hoisted out of the loop.
+fn run_variant(
+    category: &str,
+    name: &str,
+    runner: &BenchRunner,
+    level: Level,
+    mut set_paint: impl FnMut(PaintType),
+) -> Option<BenchmarkResult> {
+    let argument = Argument::for_variant(name)?;
+
+    let mut result = runner.run(
+        &format!("{category}/{name}"),
+        category,
+        name,
+        level_suffix(level),
+        #[inline(always)]
+        || match &argument {
+            Argument::Preconverted(paint) => {
+                for _ in 0..CALLS {
+                    set_paint(black_box(paint).clone());
+                }
+            }
+            Argument::Palette(color) => {
+                for _ in 0..CALLS {
+                    set_paint((*black_box(color)).into());
+                }
+            }
+            Argument::Premul(color) => {
+                for _ in 0..CALLS {
+                    set_paint(black_box(color).un_premultiply().into());
+                }
+            }
+            Argument::DisplayP3(color) => {
+                for _ in 0..CALLS {
+                    set_paint(black_box(color).convert::<Srgb>().into());
+                }
+            }
+        },
+    );
+    result.throughput = Some(Throughput::Elements(CALLS.into()));
+
+    Some(result)
+}
@@ -8,9 +8,11 @@ use vello_common::flatten;
 use vello_common::kurbo::{Stroke, StrokeCtx};
 
 const CATEGORY: &str = "strokes";
+const MEASURES: &str =
+    "expanding all strokes of an SVG to fill paths; excludes parsing and flattening";
 
 pub fn list() -> Vec<BenchmarkInfo> {
-    BenchmarkInfo::from_data_items(CATEGORY)
+    BenchmarkInfo::from_data_items(CATEGORY, MEASURES)
 }
 
 pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
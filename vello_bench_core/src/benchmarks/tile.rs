@@ -1,34 +1,54 @@
-use crate::data::get_data_items;
+use crate::data::{DataItem, get_data_items, seed_offset};
 use crate::registry::BenchmarkInfo;
 use crate::result::BenchmarkResult;
-use crate::runner::BenchRunner;
+use crate::runner::{BenchRunner, MicroBench};
 use crate::simd::level_suffix;
 use fearless_simd::Level;
+use vello_common::flatten::Line;
 use vello_common::tile::Tiles;
 
 const CATEGORY: &str = "tile";
+const MEASURES: &str =
+    "tile generation from the flattened lines of an SVG; excludes parsing, flattening and sorting";
 
 pub fn list() -> Vec<BenchmarkInfo> {
-    BenchmarkInfo::from_data_items(CATEGORY)
+    BenchmarkInfo::from_data_items(CATEGORY, MEASURES)
 }
 
 pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
     let items = get_data_items();
     let item = items.iter().find(|i| i.name == name)?;
-    let lines = item.lines();
-    let simd_variant = level_suffix(level);
 
-    let mut tiles = Tiles::new(level);
-
-    Some(runner.run(
+    let mut bench = Tile {
+        item,
+        tiles: Tiles::new(level),
+    };
+    Some(runner.run_micro(
         &format!("{CATEGORY}/{name}"),
         CATEGORY,
         name,
-        simd_variant,
-        #[inline(always)]
-        || {
-            tiles.make_tiles_analytic_aa(&lines, item.width, item.height);
-            std::hint::black_box(&tiles);
-        },
+        level_suffix(level),
+        &mut bench,
     ))
 }
+
+/// Tiles the lines of a data item shifted by the input's [`seed_offset`].
+struct Tile<'a> {
+    item: &'a DataItem,
+    tiles: Tiles,
+}
+
+impl MicroBench for Tile<'_> {
+    type Input = Vec<Line>;
+
+    fn make_input(&self, seed: u64) -> Vec<Line> {
+        self.item.lines_with_transform(seed_offset(seed))
+    }
+
+    #[inline(always)]
+    fn run(&mut self, lines: &Vec<Line>) {
+        self.tiles
+            .make_tiles_analytic_aa(lines, self.item.width, self.item.height);
+        std::hint::black_box(&self.tiles);
+    }
+}
@@ -0,0 +1,163 @@
+//! Cost of transform bookkeeping in the scene layer, apart from rasterizing.
+//!
+//! Scenes compose several [`Affine`]s per primitive and call `set_transform`
+//! before most draws. Each benchmark times [`CALLS`] iterations of one of:
+//!
+//! - `compose`: multiplying three affines, as a scene does for a primitive
+//!   inside a transformed group.
+//! - `set_changing`: `set_transform` with a different matrix than the last
+//!   call.
+//! - `set_unchanged`: `set_transform` with the matrix already set. Compared
+//!   with `set_changing`, this shows whether the backend skips redundant
+//!   calls, and thus whether scenes should skip them themselves.
+//!
+//! `transform_cpu` calls `set_transform` on a Vello CPU `RenderContext` and
+//! also holds `compose`, which involves no backend. `transform_hybrid` calls
+//! it on a Vello Hybrid `Scene`, which needs no GPU. Results carry the call
+//! count as throughput, so the per-element time is the cost of one call.
+
+use crate::registry::BenchmarkInfo;
+use crate::result::{BenchmarkResult, Throughput};
+use crate::runner::BenchRunner;
+use crate::simd::level_suffix;
+use fearless_simd::Level;
+use std::hint::black_box;
+use vello_common::kurbo::Affine;
+
+const CATEGORY_CPU: &str = "transform_cpu";
+const CATEGORY_HYBRID: &str = "transform_hybrid";
+const COMPOSE_MEASURES: &str = "1,000,000 products of three `Affine`s";
+const SET_MEASURES: &str = "1,000,000 `set_transform` calls; excludes creating the context";
+
+/// Iterations per benchmark iteration.
+pub const CALLS: u32 = 1_000_000;
+
+/// `set_transform` variants, in listing order.
+const SET_VARIANTS: &[&str] = &["set_changing", "set_unchanged"];
+
+/// Distinct matrices `set_changing` cycles through. A power of two, so that
+/// picking the next one is a mask.
+const CHANGING_COUNT: usize = 16;
+
+/// Canvas size of the context; nothing is drawn.
+const SIZE: u16 = 64;
+
+pub fn list() -> Vec<BenchmarkInfo> {
+    let mut benchmarks = BenchmarkInfo::from_names(CATEGORY_CPU, &["compose"], COMPOSE_MEASURES);
+    benchmarks.extend(BenchmarkInfo::from_names(
+        CATEGORY_CPU,
+        SET_VARIANTS,
+        SET_MEASURES,
+    ));
+    benchmarks.extend(BenchmarkInfo::from_names(
+        CATEGORY_HYBRID,
+        SET_VARIANTS,
+        SET_MEASURES,
+    ));
+    benchmarks
+}
+
+/// Run a `transform_cpu` benchmark.
+pub fn run_cpu(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
+    use crate::renderer::Renderer;
+    use vello_cpu::{RenderContext, RenderMode};
+
+    if name == "compose" {
+        return Some(run_compose(runner, level));
+    }
+    let mut ctx: RenderContext = Renderer::new(SIZE, SIZE, 0, level, RenderMode::default());
+    run_set_variant(CATEGORY_CPU, name, runner, level, |transform| {
+        ctx.set_transform(transform);
+    })
+}
+
+/// Run a `transform_hybrid` benchmark. On WASM this always returns `None`:
+/// the core crate only links Vello Hybrid natively.
+pub fn run_hybrid(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let mut scene = vello_hybrid::Scene::new(SIZE, SIZE);
+        run_set_variant(CATEGORY_HYBRID, name, runner, level, |transform| {
+            scene.set_transform(transform);
+        })
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = (name, runner, level);
+        None
+    }
+}
+
+/// A group transform, a primitive's local transform and a paint offset, as a
+/// scene would compose them.
+fn compose_operands() -> [Affine; 3] {
+    [
+        Affine::translate((120.0, 80.0)) * Affine::rotate(0.3),
+        Affine::scale_non_uniform(1.5, 0.75),
+        Affine::translate((-4.0, 2.5)),
+    ]
+}
+
+/// Time [`CALLS`] products of the [`compose_operands`]. The operands go
+/// through [`black_box`] every iteration and so does the product, so the
+/// multiplication can neither be hoisted nor dropped.
+fn run_compose(runner: &BenchRunner, level: Level) -> BenchmarkResult {
+    let [group, local, paint] = compose_operands();
+
+    let mut result = runner.run(
+        &format!("{CATEGORY_CPU}/compose"),
+        CATEGORY_CPU,
+        "compose",
+        level_suffix(level),
+        #[inline(always)]
+        || {
+            for _ in 0..CALLS {
+                black_box(*black_box(&group) * *black_box(&local) * *black_box(&paint));
+            }
+        },
+    );
+    result.throughput = Some(Throughput::Elements(CALLS.into()));
+    result
+}
+
+/// Time [`CALLS`] calls of `set_transform` for variant `name`.
+///
+/// The matrix goes through [`black_box`] on every call, so the backend can't
+/// tell an unchanged matrix from the call site alone.
+fn run_set_variant(
+    category: &str,
+    name: &str,
+    runner: &BenchRunner,
+    level: Level,
+    mut set_transform: impl FnMut(Affine),
+) -> Option<BenchmarkResult> {
+    let changing = match name {
+        "set_changing" => true,
+        "set_unchanged" => false,
+        _ => return None,
+    };
+    let transforms: [Affine; CHANGING_COUNT] =
+        std::array::from_fn(|i| Affine::translate((i as f64, 0.5 * i as f64)));
+
+    let mut result = runner.run(
+        &format!("{category}/{name}"),
+        category,
+        name,
+        level_suffix(level),
+        #[inline(always)]
+        || {
+            if changing {
+                for i in 0..CALLS as usize {
+                    set_transform(*black_box(&transforms[i & (CHANGING_COUNT - 1)]));
+                }
+            } else {
+                for _ in 0..CALLS {
+                    set_transform(*black_box(&transforms[0]));
+                }
+            }
+        },
+    );
+    result.throughput = Some(Throughput::Elements(CALLS.into()));
+
+    Some(result)
+}
@@ -3,52 +3,309 @@
 //! Each scene registered in `vello_scenes` becomes a benchmark under the
 //! `vello_cpu` category. The benchmark measures: scene draw + flush +
 //! rasterisation to a `Pixmap`. Image uploads happen during setup (not timed).
+//!
+//! A representative subset of scenes additionally gets `_speed` and
+//! `_quality` variants (e.g. `vello_cpu/rotated_images_1000_quality`) that
+//! pin the [`RenderMode`]. The plain IDs use `RenderMode::default()`.
+//!
+//! Another subset gets `_strided` variants (e.g.
+//! `vello_cpu/filled_rects_strided`) that render through
+//! [`Renderer::render_to_buffer`] into a caller-owned buffer whose rows are
+//! padded by [`STRIDE_PADDING_BYTES`], as embedders rendering into shared
+//! memory or canvas backing stores do.
+//!
+//! Scenes with a warm-up proxy (the `_10000` variants) draw it for all but
+//! the last warm-up frame. Scenes that ask for per-iteration timing (the
+//! `filters_` scenes) are timed one frame at a time.
 
 use crate::registry::BenchmarkInfo;
-use crate::renderer::Renderer;
-use crate::result::BenchmarkResult;
+use crate::renderer::{Renderer, strided_buffer_len};
+use crate::result::{BenchmarkResult, Throughput};
 use crate::runner::BenchRunner;
 use crate::simd::level_suffix;
-use crate::vello_scenes::{draw_scene, get_vello_scenes, setup_scene};
+use crate::vello_scenes::{SceneRegistration, find_scene, get_vello_scenes, times_per_iteration};
 use fearless_simd::Level;
 use vello_cpu::{Pixmap, RenderContext, RenderMode};
 
 const CATEGORY: &str = "vello_cpu";
+const MEASURES: &str =
+    "scene draw + flush + rasterize to pixmap; excludes image uploads and readback";
+const MEASURES_PER_ITERATION: &str = "scene draw + flush + rasterize to pixmap, timed per frame; \
+    excludes image uploads and readback";
+const MEASURES_STRIDED: &str = "scene draw + flush + rasterize + row copy into a strided caller \
+    buffer; excludes image uploads";
+
+/// Scenes that get explicit render mode variants: vector paths, image
+/// sampling with and without transforms, and long path runs.
+pub(crate) const RENDER_MODE_SCENES: &[&str] = &[
+    "filled_rects",
+    "tiled_flowers_1000",
+    "overlapping_images_1000",
+    "rotated_images_1000",
+    "mixed_image_and_vector_1000",
+    "paths_and_images_100",
+];
+
+/// Scenes that get a `_strided` variant: vector-only, image-heavy and mixed.
+pub(crate) const STRIDED_SCENES: &[&str] = &[
+    "filled_rects",
+    "tiled_flowers_1000",
+    "mixed_image_and_vector_1000",
+];
+
+/// Suffix of the strided variants.
+const STRIDED_SUFFIX: &str = "_strided";
+
+/// Bytes of padding after each row in the `_strided` variants, so the stride
+/// is `width * 4 + STRIDE_PADDING_BYTES`.
+pub const STRIDE_PADDING_BYTES: usize = 64;
+
+/// All render modes, in the order their variants are listed.
+pub const RENDER_MODES: [RenderMode; 2] = [RenderMode::OptimizeSpeed, RenderMode::OptimizeQuality];
+
+/// Short name of a render mode, used as benchmark ID suffix and recorded in
+/// [`BenchmarkResult::render_mode`].
+pub fn render_mode_suffix(mode: RenderMode) -> &'static str {
+    match mode {
+        RenderMode::OptimizeSpeed => "speed",
+        RenderMode::OptimizeQuality => "quality",
+    }
+}
+
+/// Parse a render mode from its suffix (`"speed"` or `"quality"`).
+pub fn render_mode_from_suffix(suffix: &str) -> Option<RenderMode> {
+    RENDER_MODES
+        .into_iter()
+        .find(|mode| render_mode_suffix(*mode) == suffix)
+}
+
+/// Split a benchmark name into scene name and explicit render mode.
+///
+/// `"rotated_images_1000_quality"` yields `("rotated_images_1000",
+/// Some(RenderMode::OptimizeQuality))`; names without a recognised suffix are
+/// returned unchanged with `None`.
+pub fn split_render_mode(name: &str) -> (&str, Option<RenderMode>) {
+    RENDER_MODES
+        .into_iter()
+        .find_map(|mode| {
+            let scene = name
+                .strip_suffix(render_mode_suffix(mode))?
+                .strip_suffix('_')?;
+            RENDER_MODE_SCENES
+                .contains(&scene)
+                .then_some((scene, Some(mode)))
+        })
+        .unwrap_or((name, None))
+}
 
 pub fn list() -> Vec<BenchmarkInfo> {
-    get_vello_scenes()
+    let scenes = get_vello_scenes();
+
+    let plain = scenes.iter().map(|scene| scene.name.to_string());
+    let variants = RENDER_MODE_SCENES.iter().flat_map(|scene| {
+        RENDER_MODES
+            .iter()
+            .map(move |mode| format!("{scene}_{}", render_mode_suffix(*mode)))
+    });
+
+    let strided = STRIDED_SCENES
         .iter()
-        .map(|scene| BenchmarkInfo {
-            id: format!("{CATEGORY}/{}", scene.name),
-            category: CATEGORY.into(),
-            name: scene.name.to_string(),
+        .map(|scene| format!("{scene}{STRIDED_SUFFIX}"));
+
+    plain.chain(variants).chain(strided).map(info).collect()
+}
+
+/// Info for the benchmark `name`, which doesn't have to be listed, e.g. an
+/// ad hoc generated scene.
+pub(crate) fn info(name: String) -> BenchmarkInfo {
+    let scene = split_strided(&name).unwrap_or(split_render_mode(&name).0);
+    let measures = if split_strided(&name).is_some() {
+        MEASURES_STRIDED
+    } else if times_per_iteration(scene) {
+        MEASURES_PER_ITERATION
+    } else {
+        MEASURES
+    };
+    BenchmarkInfo::new(CATEGORY, name.clone(), measures).with_scene_tags(scene)
+}
+
+/// The scene of a `_strided` variant name, or `None` if `name` is not one.
+pub(crate) fn split_strided(name: &str) -> Option<&str> {
+    name.strip_suffix(STRIDED_SUFFIX)
+        .filter(|scene| STRIDED_SCENES.contains(scene))
+}
+
+pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
+    let (scene, mode, strided) = split_variant(name);
+    run_scene(scene, name, runner, level, mode, strided)
+}
+
+/// Run every benchmark of [`list`], in order, sharing one render context and
+/// pixmap between all benchmarks of the same canvas size and render mode
+/// instead of creating them per benchmark. Each scene starts from a reset
+/// context; the results are the ones [`run`] gives.
+pub fn run_category(runner: &BenchRunner, level: Level) -> Vec<BenchmarkResult> {
+    run_listed(&list(), runner, level)
+}
+
+/// [`run_category`] for the benchmarks of `list`, which must be of this
+/// category. Unknown scenes are skipped.
+pub(crate) fn run_listed(
+    list: &[BenchmarkInfo],
+    runner: &BenchRunner,
+    level: Level,
+) -> Vec<BenchmarkResult> {
+    let mut targets: Vec<((u16, u16, &str), Target)> = Vec::new();
+    list.iter()
+        .filter_map(|info| {
+            let (scene, mode, strided) = split_variant(&info.name);
+            let registration = find_scene::<RenderContext>(scene)?;
+            let key = (
+                registration.info.width,
+                registration.info.height,
+                render_mode_suffix(mode),
+            );
+            let index = match targets.iter().position(|(k, _)| *k == key) {
+                Some(index) => index,
+                None => {
+                    targets.push((key, Target::new(key.0, key.1, level, mode)));
+                    targets.len() - 1
+                }
+            };
+            let target = &mut targets[index].1;
+            target.ctx.reset();
+            Some(run_registration(
+                &registration,
+                &info.name,
+                runner,
+                level,
+                mode,
+                strided,
+                target,
+            ))
         })
         .collect()
 }
 
-pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
-    let scenes = get_vello_scenes();
-    let info = scenes.iter().find(|s| s.name == name)?;
+/// The scene, render mode and whether it is a `_strided` variant of the
+/// benchmark `name`.
+fn split_variant(name: &str) -> (&str, RenderMode, bool) {
+    if let Some(scene) = split_strided(name) {
+        return (scene, RenderMode::default(), true);
+    }
+    let (scene, mode) = split_render_mode(name);
+    (scene, mode.unwrap_or_default(), false)
+}
+
+/// Run a scene with an explicit render mode. The result ID carries the mode
+/// suffix, e.g. `vello_cpu/filled_rects_quality`.
+///
+/// Unlike [`run`], this accepts any scene, not just the ones that have
+/// registered render mode variants.
+pub fn run_with_mode(
+    scene: &str,
+    runner: &BenchRunner,
+    level: Level,
+    mode: RenderMode,
+) -> Option<BenchmarkResult> {
+    let name = format!("{scene}_{}", render_mode_suffix(mode));
+    run_scene(scene, &name, runner, level, mode, false)
+}
+
+fn run_scene(
+    scene: &str,
+    name: &str,
+    runner: &BenchRunner,
+    level: Level,
+    mode: RenderMode,
+    strided: bool,
+) -> Option<BenchmarkResult> {
+    let registration = find_scene::<RenderContext>(scene)?;
+    let info = &registration.info;
+    let mut target = Target::new(info.width, info.height, level, mode);
+    Some(run_registration(
+        &registration,
+        name,
+        runner,
+        level,
+        mode,
+        strided,
+        &mut target,
+    ))
+}
+
+/// What a benchmark renders with.
+struct Target {
+    ctx: RenderContext,
+    pixmap: Pixmap,
+}
+
+impl Target {
+    fn new(width: u16, height: u16, level: Level, mode: RenderMode) -> Self {
+        Self {
+            ctx: Renderer::new(width, height, 0, level, mode),
+            pixmap: Pixmap::new(width, height),
+        }
+    }
+}
+
+fn run_registration(
+    registration: &SceneRegistration<RenderContext>,
+    name: &str,
+    runner: &BenchRunner,
+    level: Level,
+    mode: RenderMode,
+    strided: bool,
+    target: &mut Target,
+) -> BenchmarkResult {
+    let info = &registration.info;
     let simd_variant = level_suffix(level);
+    let Target { ctx, pixmap } = target;
 
-    let mut ctx: RenderContext =
-        Renderer::new(info.width, info.height, 0, level, RenderMode::default());
-    let mut pixmap = Pixmap::new(info.width, info.height);
+    // Destination of the strided variants, 4-byte aligned like the pixel
+    // buffers embedders hand out.
+    let stride_bytes = usize::from(info.width) * 4 + STRIDE_PADDING_BYTES;
+    let buf_len = strided_buffer_len(info.width, info.height, stride_bytes);
+    let mut storage = vec![0_u8; if strided { buf_len + 3 } else { 0 }];
+    let offset = storage.as_ptr().align_offset(4).min(storage.len());
+    let buf = &mut storage[offset..];
 
     // Setup phase — image uploads etc. (not timed).
-    let state = setup_scene(name, &mut ctx).expect("scene not found in setup");
+    let state = registration.setup(ctx);
 
-    Some(runner.run(
-        &format!("{CATEGORY}/{name}"),
-        CATEGORY,
-        name,
-        simd_variant,
-        #[inline(always)]
-        || {
-            draw_scene(name, state.as_ref(), &mut ctx);
-            ctx.flush();
-            ctx.render_to_pixmap(&mut pixmap);
+    let mut frame_index = 0;
+    let mut draw = |frame| {
+        registration.draw_frame(state.as_ref(), ctx, frame, frame_index);
+        frame_index += 1;
+        ctx.flush();
+        if strided {
+            // Qualified: `RenderContext` has an unstrided inherent method of
+            // the same name.
+            Renderer::render_to_buffer(&*ctx, &mut buf[..buf_len], stride_bytes);
+            std::hint::black_box(&buf);
+        } else {
+            ctx.render_to_pixmap(pixmap);
             std::hint::black_box(&pixmap);
-        },
-    ))
+        }
+    };
+
+    let id = format!("{CATEGORY}/{name}");
+    let proxy = registration.has_warmup_proxy;
+    let mut result = if registration.per_iteration {
+        runner.run_with_frame_wait_and_warmup_proxy(
+            &id,
+            CATEGORY,
+            name,
+            simd_variant,
+            proxy,
+            &mut draw,
+        )
+    } else {
+        runner.run_with_warmup_proxy(&id, CATEGORY, name, simd_variant, proxy, &mut draw)
+    };
+    result.render_mode = Some(render_mode_suffix(mode).to_string());
+    result.throughput = registration.elements.map(Throughput::Elements);
+    result.animated = registration.animated;
+
+    result
 }
@@ -6,27 +6,41 @@
 //!
 //! Each scene registered in `vello_scenes` becomes a benchmark under the
 //! `vello_hybrid` category. The benchmark measures: scene draw + GPU render +
-//! GPU sync. Image uploads happen during setup (not timed).
+//! GPU sync. Image uploads happen during setup (not timed). Scenes with a
+//! warm-up proxy draw it for all but the last warm-up frame, and scenes that
+//! ask for per-iteration timing are timed one frame at a time. Scenes that
+//! only Vello CPU can render are left out.
 
 use crate::registry::BenchmarkInfo;
 use crate::result::BenchmarkResult;
 use crate::runner::BenchRunner;
-use crate::vello_scenes::get_vello_scenes;
+use crate::vello_scenes::{get_vello_scenes, is_cpu_only, times_per_iteration};
 use fearless_simd::Level;
 
 const CATEGORY: &str = "vello_hybrid";
+const MEASURES: &str = "scene draw + GPU render + GPU sync; excludes image uploads and readback";
+const MEASURES_PER_ITERATION: &str = "scene draw + GPU render + GPU sync, timed per frame; \
+    excludes image uploads and readback";
 
 pub fn list() -> Vec<BenchmarkInfo> {
     get_vello_scenes()
         .iter()
-        .map(|scene| BenchmarkInfo {
-            id: format!("{CATEGORY}/{}", scene.name),
-            category: CATEGORY.into(),
-            name: scene.name.to_string(),
-        })
+        .filter(|scene| !is_cpu_only(scene.name))
+        .map(|scene| info(scene.name.to_string()))
         .collect()
 }
 
+/// Info for the benchmark of scene `name`, which doesn't have to be
+/// registered, e.g. an ad hoc generated scene.
+pub(crate) fn info(name: String) -> BenchmarkInfo {
+    let measures = if times_per_iteration(&name) {
+        MEASURES_PER_ITERATION
+    } else {
+        MEASURES
+    };
+    BenchmarkInfo::new(CATEGORY, name.clone(), measures).with_scene_tags(&name)
+}
+
 /// Run a hybrid benchmark. On WASM this always returns `None` because
 /// hybrid WASM benchmarks are driven from JS via the `vello_bench_wasm` crate.
 pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
@@ -41,32 +55,111 @@ pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkRe
     }
 }
 
+/// Run every benchmark of [`list`], in order, sharing one renderer between
+/// all scenes of the same canvas size instead of creating one per benchmark.
+/// Each scene starts from a reset scene, but images uploaded by earlier
+/// scenes stay in the renderer's atlas. The results are the ones [`run`]
+/// gives. Empty on WASM, like [`run`].
+pub fn run_category(runner: &BenchRunner, level: Level) -> Vec<BenchmarkResult> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::renderer::{HybridRenderer, Renderer};
+        use crate::vello_scenes::find_scene;
+        use vello_cpu::RenderMode;
+
+        let mut renderers: Vec<((u16, u16), HybridRenderer)> = Vec::new();
+        list()
+            .into_iter()
+            .filter_map(|info| {
+                let registration = find_scene::<HybridRenderer>(&info.name)?;
+                let key = (registration.info.width, registration.info.height);
+                let index = match renderers.iter().position(|(k, _)| *k == key) {
+                    Some(index) => index,
+                    None => {
+                        let hybrid = Renderer::new(key.0, key.1, 0, level, RenderMode::default());
+                        renderers.push((key, hybrid));
+                        renderers.len() - 1
+                    }
+                };
+                let hybrid = &mut renderers[index].1;
+                hybrid.reset();
+                Some(run_registration(
+                    &registration,
+                    &info.name,
+                    runner,
+                    level,
+                    hybrid,
+                ))
+            })
+            .collect()
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = (runner, level);
+        Vec::new()
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 fn run_native(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
     use crate::renderer::{HybridRenderer, Renderer};
-    use crate::simd::level_suffix;
-    use crate::vello_scenes::{draw_scene, setup_scene};
+    use crate::vello_scenes::find_scene;
     use vello_cpu::RenderMode;
 
-    let scenes = get_vello_scenes();
-    let info = scenes.iter().find(|s| s.name == name)?;
-    let simd_variant = level_suffix(level);
-
+    let registration = find_scene::<HybridRenderer>(name).filter(|s| !s.cpu_only)?;
+    let info = &registration.info;
     let mut hybrid: HybridRenderer =
         Renderer::new(info.width, info.height, 0, level, RenderMode::default());
+    Some(run_registration(
+        &registration,
+        name,
+        runner,
+        level,
+        &mut hybrid,
+    ))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn run_registration(
+    registration: &crate::vello_scenes::SceneRegistration<crate::renderer::HybridRenderer>,
+    name: &str,
+    runner: &BenchRunner,
+    level: Level,
+    hybrid: &mut crate::renderer::HybridRenderer,
+) -> BenchmarkResult {
+    use crate::simd::level_suffix;
+
+    let simd_variant = level_suffix(level);
 
     // Setup phase — image uploads etc. (not timed).
-    let state = setup_scene(name, &mut hybrid).expect("scene not found in setup");
+    let state = registration.setup(hybrid);
 
-    Some(runner.run(
-        &format!("{CATEGORY}/{name}"),
-        CATEGORY,
-        name,
-        simd_variant,
-        #[inline(always)]
-        || {
-            draw_scene(name, state.as_ref(), &mut hybrid);
-            hybrid.render_and_sync();
-        },
-    ))
+    let mut frame_index = 0;
+    let mut draw = |frame| {
+        registration.draw_frame(state.as_ref(), hybrid, frame, frame_index);
+        frame_index += 1;
+        hybrid.render_and_sync();
+    };
+
+    let id = format!("{CATEGORY}/{name}");
+    let proxy = registration.has_warmup_proxy;
+    let mut result = if registration.per_iteration {
+        runner.run_with_frame_wait_and_warmup_proxy(
+            &id,
+            CATEGORY,
+            name,
+            simd_variant,
+            proxy,
+            &mut draw,
+        )
+    } else {
+        runner.run_with_warmup_proxy(&id, CATEGORY, name, simd_variant, proxy, &mut draw)
+    };
+    result.throughput = registration
+        .elements
+        .map(crate::result::Throughput::Elements);
+    result.animated = registration.animated;
+    result.sync_mode = Some(hybrid.sync_mode());
+
+    result
 }
@@ -0,0 +1,360 @@
+//! Manage golden screenshot hashes.
+//!
+//! ```text
+//! golden check  [--store PATH] [--backend NAME] [--scenes GLOB] [--dump-dir DIR]
+//! golden update [--store PATH] [--backend NAME] [--scenes GLOB]
+//! golden repeat [--backend NAME] [--scenes GLOB] [--frames N]
+//! golden aliasing [--scenes GLOB]
+//! golden strided [--scenes GLOB]
+//! golden parity [--scenes GLOB] [--dump-dir DIR]
+//! golden winding [--dump-dir DIR]
+//! golden effects [--backend NAME]
+//! golden coverage [--backend NAME]
+//! golden params [--backend NAME]
+//! golden perceptual [--references DIR] [--backend NAME] [--scenes GLOB] [--dump-dir DIR]
+//! golden approve [--backend NAME] --note TEXT [--references DIR] SCENE
+//! golden fingerprint [--store PATH] [--scenes GLOB]
+//! golden fingerprint-update [--store PATH] [--scenes GLOB]
+//! ```
+//!
+//! `--backend` may be repeated. `check` and `update` use the deterministic
+//! backends by default (see [`Backend::HASHED`]), since GPU output differs
+//! between drivers; `perceptual` and `approve` use the GPU backends, and
+//! `repeat` uses all of them. `check` exits with status 1 if any render
+//! differs from the store. `repeat` renders each archive scene `N` times
+//! (default 50) on one renderer and exits with status 1 if the last frame
+//! differs from the first. `aliasing` prints an
+//! aliasing threshold report for each matching vello scene (by default only
+//! `isolated_shapes`, the one scene without touching shapes) and exits with
+//! status 1 if full aliasing changed any fully covered pixel. `strided`
+//! renders each matching vello scene on the CPU into a buffer with padded
+//! rows and exits with status 1 if it differs from the normal render once the
+//! padding is stripped, or if the padding was written to. `parity` compares
+//! the CPU and hybrid renders of each matching scene and exits with status 1
+//! if any exceeds its tolerance (see [`golden::PARITY_TOLERANCES`]); for each
+//! failing scene it lists the rows that differ most and writes them side by
+//! side to `DIR/<scene>.parity_rows.png`. `winding` does the same for the
+//! fill-rule, long-path and generated scenes of
+//! [`golden::WINDING_PARITY_SCENES`]. Both exit successfully with a note if
+//! there is no GPU adapter to render the hybrid side on. `effects` renders each
+//! effect scene of [`golden::EFFECT_SANITY_PAIRS`] and its baseline without
+//! the effect and exits with status 1 if a backend renders them (nearly) the
+//! same, i.e. ignores the blend mode, filter, aliasing threshold or image
+//! quality; without a GPU adapter it skips the hybrid backend. `coverage`
+//! renders the extreme-zoom scenes of [`golden::COVERAGE_SCENES`] and exits
+//! with status 1 if a backend covers none or all of the canvas with one, and
+//! `params` renders the parameterized scenes of
+//! [`golden::PARAMETERIZED_PAIRS`] and exits with status 1 if one differs
+//! from the registered variant with the same count, both skipping the hybrid
+//! backend the same way.
+//!
+//! `perceptual` compares the GPU backends' renders with the reference PNGs in
+//! `--references` (default `golden_references`), printing the SSIM of every
+//! scene, and exits with status 1 if any is below the minimum in the
+//! reference's sidecar or has no reference; without a GPU adapter it reports
+//! the backends as skipped. `approve` renders `SCENE` as its new reference
+//! and appends the note to `APPROVALS.md` in the reference directory.
+//!
+//! `fingerprint` compares the content fingerprints of the programmatic scenes
+//! (see [`fingerprint`](vello_bench_core::fingerprint)) with the store
+//! (default `vello_bench_core/fingerprints.toml`) and exits with status 1 if
+//! any differs, is missing, or differs between two setups of the same scene.
+//! An empty or missing store is an error rather than a list of every scene.
+//! `fingerprint-update` writes the current fingerprints to the store and drops
+//! the entries of scenes that no longer exist.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use vello_bench_core::Level;
+use vello_bench_core::fingerprint::{self, FingerprintStore};
+use vello_bench_core::golden::{
+    self, Backend, CoverageResult, EffectSanityResult, GoldenStore, ParameterizedResult,
+    ParityResult,
+};
+use vello_bench_core::renderer::gpu_adapter_available;
+use vello_bench_core::screenshot::{aliasing_report, check_strided_render};
+use vello_bench_core::vello_scenes::get_vello_scenes;
+
+const USAGE: &str = "Usage: golden \
+                     <check|update|repeat|aliasing|strided|parity|winding|effects|coverage|\
+                     params|perceptual|approve|fingerprint|fingerprint-update> \
+                     [--store PATH] [--backend cpu|cpu_quality|hybrid|skia] [--scenes GLOB] \
+                     [--dump-dir DIR] [--frames N] [--references DIR] [--note TEXT] [SCENE]";
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<ExitCode, String> {
+    let mut args = std::env::args().skip(1);
+    let command = args.next().ok_or(USAGE)?;
+
+    let mut store_path = None;
+    let mut backends = Vec::new();
+    let mut scenes = None;
+    let mut dump_dir = PathBuf::from("target/golden");
+    let mut frames = 50;
+    let mut references = PathBuf::from(golden::DEFAULT_REFERENCE_DIR);
+    let mut note = None;
+    let mut scene = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{arg} requires a value"));
+        match arg.as_str() {
+            "--store" => store_path = Some(PathBuf::from(value()?)),
+            "--backend" => {
+                let name = value()?;
+                backends.push(
+                    Backend::from_name(&name).ok_or_else(|| format!("Unknown backend: {name}"))?,
+                );
+            }
+            "--scenes" => scenes = Some(value()?),
+            "--dump-dir" => dump_dir = value()?.into(),
+            "--frames" => {
+                frames = value()?
+                    .parse()
+                    .map_err(|e| format!("Invalid --frames: {e}"))?;
+            }
+            "--references" => references = value()?.into(),
+            "--note" => note = Some(value()?),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Ok(ExitCode::SUCCESS);
+            }
+            _ if command == "approve" && !arg.starts_with('-') && scene.is_none() => {
+                scene = Some(arg);
+            }
+            _ => return Err(format!("Unknown argument: {arg}\n{USAGE}")),
+        }
+    }
+    if backends.is_empty() {
+        backends = match command.as_str() {
+            "check" | "update" => Backend::HASHED.to_vec(),
+            "perceptual" | "approve" => Backend::GPU.to_vec(),
+            _ => Backend::ALL.to_vec(),
+        };
+    }
+
+    // Doesn't use the store.
+    if command == "aliasing" {
+        let scenes = scenes.as_deref().unwrap_or("isolated_shapes");
+        return Ok(check_aliasing(scenes));
+    }
+    if command == "strided" {
+        return Ok(check_strided(scenes.as_deref().unwrap_or("*")));
+    }
+    if command == "parity" || command == "winding" {
+        if !gpu_adapter_available() {
+            println!("No GPU adapter available; skipping the {command} check.");
+            return Ok(ExitCode::SUCCESS);
+        }
+        let results = if command == "parity" {
+            golden::check_parity(scenes.as_deref().unwrap_or("*"), Some(&dump_dir))
+        } else {
+            golden::check_winding_parity(Some(&dump_dir))
+        };
+        return Ok(report_parity(&results));
+    }
+    if command == "effects" || command == "coverage" || command == "params" {
+        if backends.contains(&Backend::Hybrid) && !gpu_adapter_available() {
+            println!("No GPU adapter available; skipping the hybrid backend.");
+            backends.retain(|&backend| backend != Backend::Hybrid);
+        }
+        return Ok(match command.as_str() {
+            "effects" => report_effect_sanity(&golden::check_effect_sanity(&backends)),
+            "coverage" => report_coverage(&golden::check_coverage(&backends)),
+            _ => report_parameterized(&golden::check_parameterized(&backends)),
+        });
+    }
+    if command == "perceptual" {
+        let scenes = scenes.as_deref().unwrap_or("*");
+        let report = golden::check_perceptual(&references, &backends, scenes, Some(&dump_dir))?;
+        print!("{}", report.summary());
+        return Ok(if report.is_ok() {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        });
+    }
+    if command == "approve" {
+        let scene = scene.ok_or_else(|| format!("approve requires a scene\n{USAGE}"))?;
+        let [backend] = backends[..] else {
+            return Err("approve requires exactly one --backend".to_string());
+        };
+        let note = note.ok_or("approve requires --note saying why the reference changed")?;
+        let line = golden::approve(&references, backend, &scene, &note)?;
+        println!("{line}");
+        return Ok(ExitCode::SUCCESS);
+    }
+    let scenes = scenes.unwrap_or_else(|| "*".to_string());
+
+    if command == "fingerprint" || command == "fingerprint-update" {
+        let store_path = store_path.unwrap_or_else(|| fingerprint::DEFAULT_STORE.into());
+        let mut store = FingerprintStore::load(&store_path)?;
+        if command == "fingerprint" {
+            if store.is_empty() {
+                return Err(format!(
+                    "No fingerprints in {}; record them with `golden fingerprint-update`",
+                    store_path.display()
+                ));
+            }
+            let report = fingerprint::check(&store, &scenes);
+            print!("{}", report.summary());
+            return Ok(if report.is_ok() {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            });
+        }
+        let count = fingerprint::update(&mut store, &scenes)?;
+        for scene in store.retain_registered() {
+            println!("Removed {scene}, which is no longer registered");
+        }
+        store.save(&store_path)?;
+        println!("Updated {count} fingerprint(s) in {}", store_path.display());
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let store_path = store_path.unwrap_or_else(|| PathBuf::from("golden.toml"));
+    let mut store = GoldenStore::load(&store_path)?;
+
+    match command.as_str() {
+        "check" => {
+            let report = golden::check(&store, &backends, &scenes, Some(&dump_dir));
+            print!("{}", report.summary());
+            Ok(if report.is_ok() {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            })
+        }
+        "repeat" => {
+            let report = golden::check_repeatability(&backends, &scenes, frames);
+            print!("{}", report.summary());
+            Ok(if report.is_ok() {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            })
+        }
+        "update" => {
+            let count = golden::update(&mut store, &backends, &scenes)?;
+            store.save(&store_path)?;
+            println!(
+                "Updated {count} golden hash(es) in {}",
+                store_path.display()
+            );
+            Ok(ExitCode::SUCCESS)
+        }
+        _ => Err(format!("Unknown command: {command}\n{USAGE}")),
+    }
+}
+
+fn check_aliasing(scene_glob: &str) -> ExitCode {
+    let mut failed = false;
+    for info in get_vello_scenes() {
+        if !golden::glob_match(scene_glob, info.name) {
+            continue;
+        }
+        let Some(report) = aliasing_report(info.name) else {
+            continue;
+        };
+        print!("{}", report.summary());
+
+        let changed = report.full_coverage_changed();
+        if changed > 0 {
+            println!("  FAIL: {changed} fully covered pixel(s) changed when fully aliased");
+            failed = true;
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn check_strided(scene_glob: &str) -> ExitCode {
+    let mut failed = false;
+    for info in get_vello_scenes() {
+        if !golden::glob_match(scene_glob, info.name) {
+            continue;
+        }
+        let Some(problems) = check_strided_render(info.name, Level::new()) else {
+            continue;
+        };
+
+        if problems.is_empty() {
+            println!("{}: ok", info.name);
+        } else {
+            println!("{}: FAIL", info.name);
+            for problem in problems {
+                println!("  {problem}");
+            }
+            failed = true;
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn report_parity(results: &[ParityResult]) -> ExitCode {
+    for result in results {
+        println!("{}", result.summary());
+        print!("{}", result.failure_details());
+    }
+
+    if results.iter().all(|r| r.is_ok()) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn report_coverage(results: &[CoverageResult]) -> ExitCode {
+    for result in results {
+        println!("{}", result.summary());
+    }
+
+    if results.iter().all(|r| r.is_ok()) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn report_parameterized(results: &[ParameterizedResult]) -> ExitCode {
+    for result in results {
+        println!("{}", result.summary());
+    }
+
+    if results.iter().all(|r| r.is_ok()) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn report_effect_sanity(results: &[EffectSanityResult]) -> ExitCode {
+    for result in results {
+        println!("{}", result.summary());
+    }
+
+    if results.iter().all(|r| r.is_ok()) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
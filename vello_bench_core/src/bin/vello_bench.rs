@@ -0,0 +1,1091 @@
+//! Native command-line interface to the benchmark suite.
+//!
+//! ```text
+//! vello_bench list [--verbose] [--filter EXPR]
+//! vello_bench support
+//! vello_bench validate-scenes
+//! vello_bench doctor [--skip CHECK]...
+//! vello_bench run [--filter EXPR] [--warmup N] [--iterations N] [--simd LEVEL]
+//!                 [--gpu-timeout SECS] [--isolate [--timeout SECS]] [--output PATH]
+//!                 [--generated SEED:BUDGET]... [--warmup-input same|distinct|both]
+//!                 [--skip CHECK]... [--fresh-gpu-device]
+//!                 [--baseline PATH [--fail-on-regression PCT]] [--shard INDEX/COUNT]
+//! vello_bench matrix --out DIR [--filter EXPR] [--shard INDEX/COUNT] [--warmup N]
+//!                    [--iterations N] [--gpu-timeout SECS] [--dry-run] [--fresh-gpu-device]
+//! vello_bench recover PATH
+//! vello_bench report PATH [--html]
+//! vello_bench reproduce PATH [--exec]
+//! vello_bench update-reference --from PATH --name NAME [--label TEXT]
+//! vello_bench serve --socket PATH [--warmup N] [--iterations N] [--simd LEVEL]
+//!                   [--gpu-timeout SECS]
+//! vello_bench client <list [--filter EXPR] | run ID [--warmup N] [--iterations N]
+//!                    [--simd LEVEL] | shutdown> --socket PATH
+//! ```
+//!
+//! `list` prints the ID of every registered benchmark, with `--verbose`
+//! followed by what its timed span measures. `--filter` restricts it to the
+//! benchmarks matching a filter expression, e.g.
+//! `"category:scene_cpu AND NOT name:*10000"` (see
+//! [`Filter`](vello_bench_core::Filter) for the syntax). It exits with status 1
+//! if a benchmark has no description or no tags, the registry is invalid (see
+//! [`validate`](vello_bench_core::registry::validate)), or an embedded
+//! reference result file is invalid.
+//!
+//! `support` prints the support matrix as a markdown table and exits with
+//! status 1 if a registered benchmark category is missing from it.
+//!
+//! `validate-scenes` loads every embedded scene archive and prints its format
+//! version, or why it failed to load, e.g. because it was written by a newer
+//! `anyrender_serialize` (see [`scene_load_errors`]). It exits with status 1
+//! if any scene failed.
+//!
+//! `doctor` checks whether this machine is fit for benchmarking: scenes,
+//! benchmark availability, GPU adapter, timer resolution, a runner selftest
+//! and SIMD detection (see [`health`](vello_bench_core::health)). It prints
+//! one line per check and exits with status 1 if any check failed. `--skip`
+//! leaves out a check, e.g. `--skip gpu` on a machine without a GPU; it can be
+//! repeated.
+//!
+//! `run` runs the selected benchmarks one after another and writes a
+//! [`ResultFile`] to stdout or `--output`, with progress and failures on
+//! stderr. It exits with status 1 if any benchmark failed. `--output` is
+//! written incrementally (see [`JsonLinesObserver`]), so an interrupted run
+//! keeps its results; ctrl-C stops after the current benchmark and writes a
+//! file marked partial, a second ctrl-C exits immediately. With `--isolate`,
+//! every benchmark runs in its own child process (see
+//! [`isolate`](vello_bench_core::isolate)), so a crash fails only that
+//! benchmark; `--timeout` bounds each child. `--generated` adds the CPU and
+//! hybrid benchmarks of a generated scene with any seed and budget (see
+//! [`GeneratedParams`]), subject to `--filter`; it can be repeated. The hidden
+//! `--include-panicking-benchmark` adds a benchmark that always panics, to
+//! check that isolation contains it. `--warmup-input distinct` warms the
+//! micro-benchmarks of
+//! [`MICRO_BENCH_CATEGORIES`](vello_bench_core::registry::MICRO_BENCH_CATEGORIES)
+//! up on different data than they measure (see [`WarmupInput`]); `both` runs
+//! each of them both ways, one result after the other, to see whether it
+//! matters. Before the first benchmark, `run` records the checks of `doctor`
+//! in the result file, leaving out those given with `--skip`.
+//!
+//! With `--baseline`, `run` compares its results with the result file at
+//! that path, e.g. of a run on `main`, and prints the comparison to stderr
+//! (see [`compare_reports`]). Changes beyond 5% count, or
+//! beyond the percentage given with `--fail-on-regression`, which also makes
+//! `run` exit with status 1 if any benchmark regressed by more than that.
+//!
+//! `--list`, `--level` and `--json` are accepted as aliases of `list`,
+//! `--simd` and `--output`, e.g. `vello_bench --list --filter tag:images`.
+//!
+//! `--shard` splits `run` and `matrix` into `COUNT` shards and runs only the
+//! benchmarks of shard `INDEX` (zero-based, see [`shard`]), e.g. to spread a
+//! run over several machines. The result file records the shard, so that
+//! [`compare_reports`] can tell benchmarks missing from a sharded baseline
+//! from new ones, and [`ResultFile::merge`] combines the shards of a run.
+//!
+//! By default `run` and `matrix` create one wgpu device per adapter
+//! preference and reuse it for all hybrid benchmarks (see
+//! [`keep_gpu_devices`](vello_bench_core::renderer::keep_gpu_devices)), which
+//! saves seconds per GPU benchmark on some drivers. A failed benchmark drops
+//! the kept device, in case it was lost. With `--fresh-gpu-device`, every
+//! hybrid benchmark creates its own instead. With `--isolate`, every child
+//! process creates its own anyway.
+//!
+//! `matrix` runs the full matrix for a nightly job: every benchmark that runs
+//! natively, at every SIMD level, render mode and thread count it supports
+//! (see [`full_matrix_plan`]). Results stream to `DIR/results.json`, or with
+//! `--shard` to a file per shard, and a rerun with the same arguments resumes
+//! where an interrupted run stopped, skipping the runs already in the file.
+//! Once done, the result files in `DIR` are merged into `report.json`,
+//! `report.md` and `report.html`, which compare backends and SIMD levels side
+//! by side. `--dry-run` prints the planned runs instead.
+//!
+//! `recover` prints the [`ResultFile`] recovered from the output file of a run
+//! that was killed before it could finish, see [`recover_partial`].
+//!
+//! `report` prints a markdown summary of a result file, or with `--html` a
+//! standalone HTML page (see [`report`](vello_bench_core::result::report)).
+//!
+//! `run` and `matrix` record their command line, settings and `VELLO_BENCH_*`
+//! environment variables in the result file (see [`RunConfig`]). `reproduce`
+//! prints the shell command that repeats the run of a result file, or with
+//! `--exec` runs it. For files from the UI, which have no command line, it is
+//! a `run` command with the same settings.
+//!
+//! `update-reference` writes the result file at `--from` to the source tree
+//! as the embedded reference `NAME` (see [`reference`]), after checking that
+//! it can serve as one. `--label` sets the environment label, which a
+//! reference must have, e.g. "desktop reference: Ryzen 9 7950X, Chrome 131".
+//!
+//! `serve` stays running and answers benchmark runs and screenshots sent as
+//! JSON lines to the Unix domain socket at `--socket`, with devices and
+//! scenes kept warm between them (see [`serve`](vello_bench_core::serve)).
+//! Its `--warmup`, `--iterations` and `--simd` are the defaults for runs that
+//! don't set their own. `client` sends one request to such a server: `run`
+//! prints the outcome as a JSON line and exits with status 1 if the
+//! benchmark failed, `list` prints the matching IDs, `shutdown` stops the
+//! server.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use vello_bench_core::gpu_watchdog::{DEFAULT_GPU_TIMEOUT, set_gpu_timeout};
+use vello_bench_core::health::HealthCheckKind;
+use vello_bench_core::isolate::{
+    DEFAULT_PROCESS_TIMEOUT, IsolationConfig, RUN_SINGLE_FLAG, run_isolated, single_outcome_json,
+};
+use vello_bench_core::reference::{self, check_reference, reference_problems};
+use vello_bench_core::registry::{
+    CostClass, Platform, full_matrix_plan, health_check, parse_shard_spec, shard, shard_plan,
+    support_matrix_markdown, supports_distinct_warmup_input, undeclared_categories,
+    undocumented_benchmarks, untagged_benchmarks, validate,
+};
+use vello_bench_core::renderer::{discard_kept_gpu_devices, keep_gpu_devices};
+use vello_bench_core::result::compare::compare_reports;
+use vello_bench_core::result::{
+    ENV_PREFIX, JsonLinesObserver, RunConfig, ShardSet, recover_partial, report,
+};
+use vello_bench_core::scenes::{
+    SCENE_FILES, SUPPORTED_ARCHIVE_VERSIONS, archive_version, scene_load_errors,
+};
+use vello_bench_core::serve::{self, Request, Response, RunDefaults, Server};
+use vello_bench_core::simd::available_level_from_suffix;
+use vello_bench_core::vello_scenes::{GeneratedParams, scene_registration_problems};
+use vello_bench_core::{
+    BenchRunner, BenchmarkInfo, BenchmarkOutcome, EnvironmentInfo, Filter, Level, ResultFile,
+    WarmupInput, generated_benchmarks, get_benchmark_list_filtered, try_run_benchmark_by_id,
+};
+
+const USAGE: &str = "Usage: vello_bench <list [--verbose] [--filter EXPR] | support | \
+                     validate-scenes | doctor [--skip CHECK]... | \
+                     run [--filter EXPR] [--warmup N] [--iterations N] [--simd LEVEL] \
+                     [--gpu-timeout SECS] [--isolate [--timeout SECS]] [--output PATH] \
+                     [--generated SEED:BUDGET]... [--warmup-input same|distinct|both] \
+                     [--skip CHECK]... [--fresh-gpu-device] \
+                     [--baseline PATH [--fail-on-regression PCT]] [--shard INDEX/COUNT] | \
+                     matrix --out DIR [--filter EXPR] \
+                     [--shard INDEX/COUNT] [--warmup N] [--iterations N] [--gpu-timeout SECS] \
+                     [--dry-run] [--fresh-gpu-device] | recover PATH | report PATH [--html] | \
+                     reproduce PATH [--exec] | \
+                     update-reference --from PATH --name NAME [--label TEXT] | \
+                     serve --socket PATH [--warmup N] [--iterations N] [--simd LEVEL] \
+                     [--gpu-timeout SECS] | client <list [--filter EXPR] | run ID [--warmup N] \
+                     [--iterations N] [--simd LEVEL] | shutdown> --socket PATH>\n\
+                     --list, --level and --json are aliases of list, --simd and --output.";
+
+/// Change in percent beyond which `run --baseline` reports a benchmark as
+/// regressed or improved, unless `--fail-on-regression` sets one.
+const DEFAULT_REGRESSION_THRESHOLD_PCT: f64 = 5.0;
+
+/// ID of the benchmark added by `--include-panicking-benchmark`.
+const PANICKING_BENCHMARK_ID: &str = "debug/panic";
+
+/// Settings shared by `run` and the single-benchmark child mode.
+struct RunOptions {
+    filter: String,
+    warmup: u64,
+    iterations: u64,
+    level: Level,
+    gpu_timeout: Duration,
+    isolate: bool,
+    process_timeout: Duration,
+    output: Option<PathBuf>,
+    generated: Vec<GeneratedParams>,
+    include_panicking: bool,
+    /// What micro-benchmarks warm up on; with more than one, each of them
+    /// runs once per entry.
+    warmup_inputs: Vec<WarmupInput>,
+    /// Health checks left out of `doctor` and the health report of `run`.
+    skip_checks: Vec<HealthCheckKind>,
+    /// Create a wgpu device per benchmark instead of reusing one, see
+    /// [`keep_gpu_devices`].
+    fresh_gpu_device: bool,
+    /// Result file to compare the results of `run` with.
+    baseline: Option<PathBuf>,
+    /// Regression threshold in percent that fails `run`.
+    fail_on_regression: Option<f64>,
+    /// Index and count of the shard to run, see [`shard`].
+    shard: Option<(u32, u32)>,
+}
+
+/// Settings of `client` that are sent only if given, so that the server's
+/// defaults apply otherwise.
+#[derive(Default)]
+struct ClientOptions {
+    warmup: Option<u64>,
+    iterations: Option<u64>,
+    simd: Option<String>,
+}
+
+/// Settings of `matrix` on top of [`RunOptions`].
+#[derive(Default)]
+struct MatrixOptions {
+    out_dir: Option<PathBuf>,
+    dry_run: bool,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            filter: String::new(),
+            warmup: 10,
+            iterations: 100,
+            level: Level::new(),
+            gpu_timeout: DEFAULT_GPU_TIMEOUT,
+            isolate: false,
+            process_timeout: DEFAULT_PROCESS_TIMEOUT,
+            output: None,
+            generated: Vec::new(),
+            include_panicking: false,
+            warmup_inputs: vec![WarmupInput::Same],
+            skip_checks: Vec::new(),
+            fresh_gpu_device: false,
+            baseline: None,
+            fail_on_regression: None,
+            shard: None,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<ExitCode, String> {
+    let mut args = std::env::args().skip(1);
+    let command = match args.next().ok_or(USAGE)? {
+        // `--list` is accepted in place of the `list` command.
+        command if command == "--list" => "list".to_string(),
+        command => command,
+    };
+    let mut verbose = false;
+    let mut options = RunOptions::default();
+    // Benchmark ID for the hidden single-benchmark mode, see `isolate`.
+    let single_id = if command == RUN_SINGLE_FLAG {
+        Some(args.next().ok_or("Missing benchmark ID")?)
+    } else {
+        None
+    };
+    let recover_path = if command == "recover" {
+        Some(args.next().ok_or("Missing result file path")?)
+    } else {
+        None
+    };
+    let report_path = if command == "report" {
+        Some(args.next().ok_or("Missing result file path")?)
+    } else {
+        None
+    };
+    let reproduce_path = if command == "reproduce" {
+        Some(args.next().ok_or("Missing result file path")?)
+    } else {
+        None
+    };
+    // Request and benchmark ID of `client`.
+    let client_request = if command == "client" {
+        let request = args.next().ok_or("Missing client request")?;
+        let id = if request == "run" {
+            Some(args.next().ok_or("Missing benchmark ID")?)
+        } else {
+            None
+        };
+        Some((request, id))
+    } else {
+        None
+    };
+    let mut client = ClientOptions::default();
+    let mut socket: Option<PathBuf> = None;
+    let mut html = false;
+    let mut exec = false;
+    // Source file, name and label of `update-reference`.
+    let mut reference_from: Option<PathBuf> = None;
+    let mut reference_name: Option<String> = None;
+    let mut reference_label: Option<String> = None;
+    let mut matrix = MatrixOptions::default();
+    let runs = command == "run" || single_id.is_some();
+    let measures = runs || command == "matrix" || command == "serve";
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Ok(ExitCode::SUCCESS);
+            }
+            "-v" | "--verbose" if command == "list" => verbose = true,
+            "--html" if command == "report" => html = true,
+            "--exec" if command == "reproduce" => exec = true,
+            "--filter" if matches!(command.as_str(), "list" | "run" | "matrix" | "client") => {
+                options.filter = args.next().ok_or("--filter needs an expression")?;
+            }
+            "--warmup" if measures => options.warmup = parse_value(&arg, args.next())?,
+            "--iterations" if measures => options.iterations = parse_value(&arg, args.next())?,
+            "--simd" | "--level" if runs || command == "serve" => {
+                let suffix = args.next().ok_or(format!("{arg} needs a level"))?;
+                options.level = available_level_from_suffix(&suffix)?;
+            }
+            "--gpu-timeout" if measures => {
+                options.gpu_timeout = Duration::from_secs_f64(parse_value(&arg, args.next())?);
+            }
+            "--isolate" if command == "run" => options.isolate = true,
+            "--timeout" if command == "run" => {
+                options.process_timeout = Duration::from_secs_f64(parse_value(&arg, args.next())?);
+            }
+            "--output" | "--json" if command == "run" => {
+                options.output = Some(args.next().ok_or(format!("{arg} needs a path"))?.into());
+            }
+            "--generated" if command == "run" => {
+                let spec = args.next().ok_or("--generated needs SEED:BUDGET")?;
+                options.generated.push(GeneratedParams::from_spec(&spec)?);
+            }
+            "--include-panicking-benchmark" if command == "run" => options.include_panicking = true,
+            "--baseline" if command == "run" => {
+                options.baseline = Some(args.next().ok_or("--baseline needs a path")?.into());
+            }
+            "--fail-on-regression" if command == "run" => {
+                options.fail_on_regression = Some(parse_value(&arg, args.next())?);
+            }
+            "--warmup-input" if runs => {
+                let name = args
+                    .next()
+                    .ok_or("--warmup-input needs same, distinct or both")?;
+                options.warmup_inputs = match name.as_str() {
+                    "both" => vec![WarmupInput::Same, WarmupInput::Distinct],
+                    _ => vec![
+                        WarmupInput::from_name(&name)
+                            .ok_or(format!("Invalid --warmup-input: {name}"))?,
+                    ],
+                };
+            }
+            "--skip" if command == "doctor" || command == "run" => {
+                let name = args.next().ok_or("--skip needs a check")?;
+                options.skip_checks.push(
+                    HealthCheckKind::from_name(&name).ok_or(format!("Unknown check: {name}"))?,
+                );
+            }
+            "--out" if command == "matrix" => {
+                matrix.out_dir = Some(args.next().ok_or("--out needs a directory")?.into());
+            }
+            "--shard" if command == "run" || command == "matrix" => {
+                let spec = args.next().ok_or("--shard needs INDEX/COUNT")?;
+                options.shard =
+                    Some(parse_shard_spec(&spec).ok_or(format!("Invalid shard: {spec}"))?);
+            }
+            "--dry-run" if command == "matrix" => matrix.dry_run = true,
+            "--fresh-gpu-device" if command == "run" || command == "matrix" => {
+                options.fresh_gpu_device = true;
+            }
+            "--socket" if command == "serve" || command == "client" => {
+                socket = Some(args.next().ok_or("--socket needs a path")?.into());
+            }
+            "--warmup" if command == "client" => {
+                client.warmup = Some(parse_value(&arg, args.next())?);
+            }
+            "--iterations" if command == "client" => {
+                client.iterations = Some(parse_value(&arg, args.next())?);
+            }
+            "--simd" | "--level" if command == "client" => {
+                client.simd = Some(args.next().ok_or(format!("{arg} needs a level"))?);
+            }
+            "--from" if command == "update-reference" => {
+                reference_from = Some(args.next().ok_or("--from needs a path")?.into());
+            }
+            "--name" if command == "update-reference" => {
+                reference_name = Some(args.next().ok_or("--name needs a name")?);
+            }
+            "--label" if command == "update-reference" => {
+                reference_label = Some(args.next().ok_or("--label needs a text")?);
+            }
+            _ => return Err(format!("Unknown argument: {arg}\n{USAGE}")),
+        }
+    }
+
+    if let Some(id) = single_id {
+        return run_single(&id, &options);
+    }
+    if let Some(path) = recover_path {
+        return recover(Path::new(&path));
+    }
+    if let Some(path) = report_path {
+        return print_report(Path::new(&path), html);
+    }
+    if let Some(path) = reproduce_path {
+        return reproduce(Path::new(&path), exec);
+    }
+    if let Some((request, id)) = client_request {
+        let socket = socket.ok_or("client needs --socket PATH")?;
+        return send_request(&socket, &request, id, &options.filter, client);
+    }
+
+    match command.as_str() {
+        "list" => list(verbose, &options.filter),
+        "support" => Ok(support()),
+        "validate-scenes" => Ok(validate_scenes()),
+        "doctor" => Ok(doctor(&options.skip_checks)),
+        "run" => run_benchmarks(&options),
+        "matrix" => run_matrix(&options, &matrix),
+        "serve" => serve_socket(
+            socket.as_deref().ok_or("serve needs --socket PATH")?,
+            &options,
+        ),
+        "update-reference" => update_reference(
+            reference_from
+                .as_deref()
+                .ok_or("update-reference needs --from PATH")?,
+            reference_name
+                .as_deref()
+                .ok_or("update-reference needs --name NAME")?,
+            reference_label,
+        ),
+        "-h" | "--help" => {
+            println!("{USAGE}");
+            Ok(ExitCode::SUCCESS)
+        }
+        _ => Err(format!("Unknown command: {command}\n{USAGE}")),
+    }
+}
+
+fn list(verbose: bool, filter: &str) -> Result<ExitCode, String> {
+    for info in get_benchmark_list_filtered(filter)? {
+        if verbose {
+            println!("{}\t{}", info.id, info.measures);
+        } else {
+            println!("{}", info.id);
+        }
+    }
+
+    let mut ok = true;
+    let undocumented = undocumented_benchmarks();
+    if !undocumented.is_empty() {
+        eprintln!(
+            "Benchmarks without a description of what they measure: {}",
+            undocumented.join(", ")
+        );
+        ok = false;
+    }
+    let untagged = untagged_benchmarks();
+    if !untagged.is_empty() {
+        eprintln!("Benchmarks without tags: {}", untagged.join(", "));
+        ok = false;
+    }
+    for problem in scene_registration_problems() {
+        eprintln!("Scene registration: {problem}");
+        ok = false;
+    }
+    if let Err(errors) = validate() {
+        for error in errors {
+            eprintln!("Registry: {error}");
+        }
+        ok = false;
+    }
+    for problem in reference_problems() {
+        eprintln!("Reference results: {problem}");
+        ok = false;
+    }
+
+    Ok(if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+fn support() -> ExitCode {
+    print!("{}", support_matrix_markdown());
+
+    let missing = undeclared_categories();
+    if missing.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        eprintln!(
+            "Categories missing from the support matrix: {}",
+            missing.join(", ")
+        );
+        ExitCode::FAILURE
+    }
+}
+
+fn validate_scenes() -> ExitCode {
+    let errors = scene_load_errors();
+    for (name, zip_bytes) in SCENE_FILES {
+        match errors.iter().find(|error| error.name == *name) {
+            Some(error) => println!("{name}: FAIL\n  {}", error.message),
+            None => match archive_version(zip_bytes) {
+                Ok(version) => println!("{name}: ok (format version {version})"),
+                Err(e) => println!("{name}: ok (unknown format version: {e})"),
+            },
+        }
+    }
+    println!(
+        "Supported archive format versions: {}..={}",
+        SUPPORTED_ARCHIVE_VERSIONS.start(),
+        SUPPORTED_ARCHIVE_VERSIONS.end()
+    );
+
+    if errors.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn doctor(skip: &[HealthCheckKind]) -> ExitCode {
+    let report = health_check(&[Platform::Native], skip);
+    print!("{}", report.summary());
+
+    if report.is_ok() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn parse_value<T: FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{flag} needs a value"))?;
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value for {flag}: {value}"))
+}
+
+/// The benchmark added by `--include-panicking-benchmark`.
+fn panicking_benchmark() -> ! {
+    panic!("{PANICKING_BENCHMARK_ID} always panics");
+}
+
+fn run_in_process(runner: &BenchRunner, id: &str, level: Level) -> Option<BenchmarkOutcome> {
+    if id == PANICKING_BENCHMARK_ID {
+        panicking_benchmark();
+    }
+    try_run_benchmark_by_id(runner, id, level)
+}
+
+/// Child side of `run --isolate`: run `id` and print its outcome as JSON.
+fn run_single(id: &str, options: &RunOptions) -> Result<ExitCode, String> {
+    set_gpu_timeout(options.gpu_timeout);
+    let mut runner = BenchRunner::new(options.warmup, options.iterations);
+    runner.warmup_input = options.warmup_inputs[0];
+    if id == PANICKING_BENCHMARK_ID {
+        panicking_benchmark();
+    }
+    println!("{}", single_outcome_json(&runner, id, options.level)?);
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run_benchmarks(options: &RunOptions) -> Result<ExitCode, String> {
+    set_gpu_timeout(options.gpu_timeout);
+    let mut list = get_benchmark_list_filtered(&options.filter)?;
+    let filter = Filter::parse(&options.filter)?;
+    for &params in &options.generated {
+        for info in filter.apply(&generated_benchmarks(params)) {
+            // Registered seeds are listed already.
+            if !list.iter().any(|listed| listed.id == info.id) {
+                list.push(info);
+            }
+        }
+    }
+    if let Some((index, count)) = options.shard {
+        list = shard(&list, index, count);
+    }
+    if options.include_panicking {
+        list.push(BenchmarkInfo {
+            id: PANICKING_BENCHMARK_ID.to_string(),
+            category: "debug".to_string(),
+            name: "panic".to_string(),
+            measures: "nothing; always panics",
+            tags: vec!["debug"],
+            estimated_cost: CostClass::Micro,
+        });
+    }
+
+    // Load the baseline up front, so that a bad path fails before the run.
+    let baseline = match &options.baseline {
+        Some(path) => Some(ResultFile::load(path)?),
+        None if options.fail_on_regression.is_some() => {
+            return Err("--fail-on-regression needs --baseline PATH".to_string());
+        }
+        None => None,
+    };
+
+    let runner = BenchRunner::new(options.warmup, options.iterations);
+    keep_gpu_devices(!options.fresh_gpu_device);
+    let isolation = if options.isolate {
+        let exe = std::env::current_exe()
+            .map_err(|e| format!("Failed to locate the vello_bench executable: {e}"))?;
+        Some(IsolationConfig {
+            exe,
+            warmup: options.warmup,
+            iterations: options.iterations,
+            level: options.level,
+            warmup_input: options.warmup_inputs[0],
+            gpu_timeout: options.gpu_timeout,
+            process_timeout: options.process_timeout,
+        })
+    } else {
+        None
+    };
+
+    let stop = stop_on_ctrlc()?;
+    let environment = EnvironmentInfo::capture(options.level);
+    let config = run_config(options, &runner);
+    let health = health_check(&[Platform::Native], &options.skip_checks);
+    if !health.is_ok() {
+        eprint!(
+            "Health checks failed, results may be unreliable:\n{}",
+            health.summary()
+        );
+    }
+    let mut observer = match &options.output {
+        Some(path) => Some(
+            JsonLinesObserver::create(path, environment.clone(), config.clone())?
+                .with_health(health.clone()),
+        ),
+        None => None,
+    };
+    let started_ms = now_ms();
+
+    // Micro-benchmarks run once per warm-up input, everything else once.
+    let runs: Vec<(&BenchmarkInfo, WarmupInput)> = list
+        .iter()
+        .flat_map(|info| {
+            let inputs = if supports_distinct_warmup_input(&info.category) {
+                &options.warmup_inputs[..]
+            } else {
+                &[WarmupInput::Same][..]
+            };
+            inputs.iter().map(move |&input| (info, input))
+        })
+        .collect();
+
+    let mut outcomes = Vec::new();
+    let mut partial = false;
+    for (i, &(info, warmup_input)) in runs.iter().enumerate() {
+        if stop.load(Ordering::Relaxed) {
+            partial = true;
+            break;
+        }
+        let note = match warmup_input {
+            WarmupInput::Same => "",
+            WarmupInput::Distinct => " (distinct warm-up input)",
+        };
+        eprint!("[{}/{}] {}{note} ... ", i + 1, runs.len(), info.id);
+        let runner = BenchRunner {
+            warmup_input,
+            ..runner.clone()
+        };
+        let outcome = match &isolation {
+            Some(config) => run_isolated(
+                &IsolationConfig {
+                    warmup_input,
+                    ..config.clone()
+                },
+                &info.id,
+            ),
+            None => match run_in_process(&runner, &info.id, options.level) {
+                Some(outcome) => outcome,
+                None => {
+                    eprintln!("skipped (unknown)");
+                    continue;
+                }
+            },
+        };
+        print_outcome(&outcome);
+        if outcome.result().is_none() {
+            // A GPU timeout may have lost a kept device.
+            discard_kept_gpu_devices();
+        }
+        if let Some(observer) = &mut observer {
+            observer.push(&outcome)?;
+        }
+        outcomes.push(outcome);
+    }
+
+    let file = match observer {
+        Some(observer) => observer.finish(partial)?,
+        None => {
+            let file = ResultFile {
+                schema_version: ResultFile::SCHEMA_VERSION,
+                started_ms,
+                finished_ms: now_ms(),
+                environment,
+                results: outcomes
+                    .iter()
+                    .filter_map(|o| o.result().cloned())
+                    .collect(),
+                memory_timeline: Vec::new(),
+                partial,
+                config: Some(config),
+                health: Some(health),
+            };
+            print_json(&file)?;
+            file
+        }
+    };
+
+    let mut ok = true;
+    let failed = outcomes
+        .iter()
+        .filter(|outcome| outcome.result().is_none())
+        .count();
+    if failed > 0 {
+        eprintln!("{failed} of {} benchmarks failed", outcomes.len());
+        ok = false;
+    }
+    if let Some(baseline) = baseline {
+        let threshold = options
+            .fail_on_regression
+            .unwrap_or(DEFAULT_REGRESSION_THRESHOLD_PCT);
+        let comparison = compare_reports(&baseline, &file, threshold);
+        eprint!("{}", comparison.to_text());
+        let regressions = comparison.regressions().count();
+        if options.fail_on_regression.is_some() && regressions > 0 {
+            eprintln!("{regressions} benchmarks regressed by more than {threshold}%");
+            ok = false;
+        }
+    }
+
+    Ok(if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+/// A flag set by the first ctrl-C, after which the caller stops once the
+/// current benchmark is done. A second ctrl-C exits immediately.
+fn stop_on_ctrlc() -> Result<Arc<AtomicBool>, String> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let handler_stop = stop.clone();
+    ctrlc::set_handler(move || {
+        if handler_stop.swap(true, Ordering::Relaxed) {
+            // Second ctrl-C: give up on finishing the current benchmark. A
+            // streamed output file can still be read with `recover`.
+            std::process::exit(130);
+        }
+        eprintln!("\nInterrupted; stopping after the current benchmark (ctrl-C again to abort)");
+    })
+    .map_err(|e| format!("Failed to install the ctrl-C handler: {e}"))?;
+    Ok(stop)
+}
+
+/// Finish the progress line of a benchmark.
+fn print_outcome(outcome: &BenchmarkOutcome) {
+    match outcome {
+        BenchmarkOutcome::Completed(result) => {
+            eprintln!("{:.3} ms", result.statistics.mean_ns / 1e6);
+        }
+        BenchmarkOutcome::Failed { error, .. } => eprintln!("FAILED\n{error}"),
+    }
+}
+
+fn run_matrix(options: &RunOptions, matrix: &MatrixOptions) -> Result<ExitCode, String> {
+    let out_dir = matrix.out_dir.as_deref().ok_or("matrix needs --out DIR")?;
+    let mut plan = full_matrix_plan(&get_benchmark_list_filtered(&options.filter)?);
+    let results_path = match options.shard {
+        Some((index, count)) => {
+            plan = shard_plan(&plan, index, count);
+            out_dir.join(format!("results.shard-{index}-of-{count}.json"))
+        }
+        None => out_dir.join("results.json"),
+    };
+
+    if matrix.dry_run {
+        for entry in &plan {
+            println!("{}\t{}", entry.result_id(), entry.simd_level);
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    set_gpu_timeout(options.gpu_timeout);
+    keep_gpu_devices(!options.fresh_gpu_device);
+    std::fs::create_dir_all(out_dir)
+        .map_err(|e| format!("Failed to create {}: {e}", out_dir.display()))?;
+    let stop = stop_on_ctrlc()?;
+    let runner = BenchRunner::new(options.warmup, options.iterations);
+    let mut observer = JsonLinesObserver::resume(
+        &results_path,
+        EnvironmentInfo::capture(Level::new()),
+        run_config(options, &runner),
+    )?;
+    let todo: Vec<_> = plan
+        .iter()
+        .filter(|entry| !observer.results().iter().any(|r| entry.is_done_by(r)))
+        .collect();
+    if todo.len() < plan.len() {
+        eprintln!(
+            "Resuming {}: {} of {} runs already done",
+            results_path.display(),
+            plan.len() - todo.len(),
+            plan.len()
+        );
+    }
+
+    let mut failed = 0;
+    let mut partial = false;
+    for (i, entry) in todo.iter().enumerate() {
+        if stop.load(Ordering::Relaxed) {
+            partial = true;
+            break;
+        }
+        eprint!(
+            "[{}/{}] {} ({}) ... ",
+            i + 1,
+            todo.len(),
+            entry.result_id(),
+            entry.simd_level
+        );
+        let Some(outcome) = entry.run(&runner) else {
+            eprintln!("skipped (unknown)");
+            continue;
+        };
+        print_outcome(&outcome);
+        if outcome.result().is_none() {
+            failed += 1;
+            discard_kept_gpu_devices();
+        }
+        observer.push(&outcome)?;
+    }
+    observer.finish(partial)?;
+
+    let report = write_matrix_report(out_dir)?;
+    eprintln!(
+        "Wrote {} results to {}",
+        report.results.len(),
+        out_dir.join("report.json").display()
+    );
+
+    if failed == 0 {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        eprintln!(
+            "{failed} of {} runs failed; rerun to retry them",
+            todo.len()
+        );
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+fn serve_socket(socket: &Path, options: &RunOptions) -> Result<ExitCode, String> {
+    set_gpu_timeout(options.gpu_timeout);
+    let mut server = Server::new(RunDefaults {
+        warmup: options.warmup,
+        iterations: options.iterations,
+        level: options.level,
+    });
+    serve::listen(&mut server, socket)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Send the `client` request named `request` and print the response.
+fn send_request(
+    socket: &Path,
+    request: &str,
+    id: Option<String>,
+    filter: &str,
+    options: ClientOptions,
+) -> Result<ExitCode, String> {
+    let request = match (request, id) {
+        ("run", Some(id)) => Request::Run {
+            id,
+            warmup: options.warmup,
+            iterations: options.iterations,
+            simd: options.simd,
+        },
+        ("list", _) => Request::List {
+            filter: filter.to_string(),
+        },
+        ("shutdown", _) => Request::Shutdown,
+        (request, _) => return Err(format!("Unknown client request: {request}\n{USAGE}")),
+    };
+
+    match serve::request(socket, &request)? {
+        Response::Outcome { outcome } => {
+            let json = serde_json::to_string(&outcome)
+                .map_err(|e| format!("Failed to serialize outcome: {e}"))?;
+            println!("{json}");
+            Ok(if outcome.result().is_some() {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            })
+        }
+        Response::Benchmarks { benchmarks } => {
+            for info in benchmarks {
+                println!("{}", info.id);
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Response::ShuttingDown => {
+            eprintln!("The server is shutting down");
+            Ok(ExitCode::SUCCESS)
+        }
+        Response::Error { message } => Err(message),
+        response => Err(format!("Unexpected response: {response:?}")),
+    }
+}
+
+/// Merge the `results*.json` files of a matrix run in `out_dir`, including
+/// those of shards still running, into `report.json`, `report.md` and
+/// `report.html`.
+fn write_matrix_report(out_dir: &Path) -> Result<ResultFile, String> {
+    let entries = std::fs::read_dir(out_dir)
+        .map_err(|e| format!("Failed to read {}: {e}", out_dir.display()))?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("results") && name.ends_with(".json"))
+        })
+        .collect();
+    paths.sort();
+
+    let files = paths
+        .iter()
+        .map(|path| recover_partial(path))
+        .collect::<Result<Vec<_>, _>>()?;
+    let file = ResultFile::merge(files)
+        .ok_or_else(|| format!("No result files in {}", out_dir.display()))?;
+
+    let json = file.to_json()?;
+    for (name, content) in [
+        ("report.json", json + "\n"),
+        ("report.md", report::markdown_report(&file)),
+        ("report.html", report::html_report(&file)),
+    ] {
+        let path = out_dir.join(name);
+        std::fs::write(&path, content)
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    }
+    Ok(file)
+}
+
+fn recover(path: &Path) -> Result<ExitCode, String> {
+    let file = recover_partial(path)?;
+    if file.partial {
+        eprintln!(
+            "Recovered {} results from a partial run",
+            file.results.len()
+        );
+    }
+    print_json(&file)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn print_report(path: &Path, html: bool) -> Result<ExitCode, String> {
+    let file = ResultFile::load(path)?;
+    if html {
+        print!("{}", report::html_report(&file));
+    } else {
+        print!("{}", report::markdown_report(&file));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn reproduce(path: &Path, exec: bool) -> Result<ExitCode, String> {
+    let file = ResultFile::load(path)?;
+    let config = file
+        .config
+        .ok_or_else(|| format!("{} has no recorded configuration", path.display()))?;
+    if config.args.is_empty() {
+        eprintln!(
+            "{} has no recorded command line; using a run with the same settings",
+            path.display()
+        );
+    }
+    if file.environment.target_arch != std::env::consts::ARCH {
+        eprintln!(
+            "The run was on {}, this is {}",
+            file.environment.target_arch,
+            std::env::consts::ARCH
+        );
+    }
+    if !exec {
+        println!("{}", config.shell_command("vello_bench"));
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let exe = std::env::current_exe()
+        .map_err(|e| format!("Failed to locate the vello_bench executable: {e}"))?;
+    let mut command = std::process::Command::new(exe);
+    command.args(config.command_args()).envs(&config.env);
+    // Variables the run didn't have must not leak in from this process.
+    for (name, _) in std::env::vars_os() {
+        let recorded = name
+            .to_str()
+            .is_some_and(|name| config.env.contains_key(name));
+        if name.to_string_lossy().starts_with(ENV_PREFIX) && !recorded {
+            command.env_remove(&name);
+        }
+    }
+    let status = command
+        .status()
+        .map_err(|e| format!("Failed to run vello_bench: {e}"))?;
+    Ok(match status.code() {
+        Some(0) => ExitCode::SUCCESS,
+        Some(code) => ExitCode::from(u8::try_from(code).unwrap_or(1)),
+        None => ExitCode::FAILURE,
+    })
+}
+
+fn update_reference(from: &Path, name: &str, label: Option<String>) -> Result<ExitCode, String> {
+    let valid_name = !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_');
+    if !valid_name {
+        return Err(format!(
+            "Invalid reference name {name:?}; use lowercase letters, digits and underscores"
+        ));
+    }
+
+    let mut file = ResultFile::load(from)?;
+    if label.is_some() {
+        file.environment.label = label;
+    }
+    check_reference(&file).map_err(|e| format!("{} can't be a reference: {e}", from.display()))?;
+
+    let dir = Path::new(reference::REFERENCE_DIR);
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    let path = dir.join(format!("{name}.json"));
+    let json = file.to_json()?;
+    std::fs::write(&path, json + "\n")
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    eprintln!(
+        "Wrote {} results to {}; rebuild to embed them",
+        file.results.len(),
+        path.display()
+    );
+    Ok(ExitCode::SUCCESS)
+}
+
+fn print_json(file: &ResultFile) -> Result<(), String> {
+    let json = file.to_json()?;
+    println!("{json}");
+    Ok(())
+}
+
+/// Record how `runner` was configured from `options`, for the result file.
+fn run_config(options: &RunOptions, runner: &BenchRunner) -> RunConfig {
+    RunConfig {
+        filter: (!options.filter.is_empty()).then(|| options.filter.clone()),
+        gpu_timeout_ms: Some(options.gpu_timeout.as_millis() as u64),
+        shards: options
+            .shard
+            .map(|(index, count)| ShardSet::single(index, count)),
+        ..RunConfig::capture(runner)
+    }
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
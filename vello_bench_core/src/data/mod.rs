@@ -1,3 +1,5 @@
+pub mod paths;
+
 use std::sync::OnceLock;
 use usvg::tiny_skia_path::PathSegment;
 use usvg::{Group, Node};
@@ -11,7 +13,20 @@ use vello_common::{flatten, strip};
 
 static DATA: OnceLock<Vec<DataItem>> = OnceLock::new();
 
-const TIGER_SVG: &[u8] = include_bytes!("../assets/Ghostscript_Tiger.svg");
+const TIGER_SVG: &[u8] = include_bytes!("../../assets/Ghostscript_Tiger.svg");
+
+/// A subpixel translation that differs for every seed, and is the identity
+/// for seed 0. Shifting a data item by it keeps the amount of geometry but
+/// moves every coordinate, and with it where lines cross pixel and tile
+/// boundaries. Used for the inputs of [`MicroBench`](crate::MicroBench)es.
+pub fn seed_offset(seed: u64) -> Affine {
+    // Additive recurrence with the plastic number, which spreads consecutive
+    // seeds evenly over the unit square.
+    const A1: f64 = 0.754_877_666_246_692_7;
+    const A2: f64 = 0.569_840_290_998_053_3;
+    let seed = seed as f64;
+    Affine::translate(((seed * A1).fract(), (seed * A2).fract()))
+}
 
 pub fn get_data_items() -> &'static [DataItem] {
     DATA.get_or_init(|| {
@@ -19,6 +34,51 @@ pub fn get_data_items() -> &'static [DataItem] {
     })
 }
 
+// Include the auto-generated asset manifest from the build script.
+include!(concat!(env!("OUT_DIR"), "/asset_manifest.rs"));
+
+/// Kind of an embedded asset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+pub enum AssetKind {
+    /// An AnyRender scene archive from `scenes/`.
+    Scene,
+    /// A raster image.
+    Image,
+    /// An SVG document.
+    Svg,
+    /// A font file.
+    Font,
+    /// A reference result file from `references/`, see
+    /// [`reference`](crate::reference).
+    Reference,
+    /// Anything else in `assets/`.
+    Other,
+}
+
+/// An asset embedded in the binary, as listed in [`asset_manifest`].
+#[derive(Clone, Copy, Debug, serde::Serialize)]
+pub struct AssetEntry {
+    /// Path relative to the workspace (e.g. `"assets/splash-flower.jpg"`).
+    pub name: &'static str,
+    /// Kind of asset.
+    pub kind: AssetKind,
+    /// Size in bytes.
+    pub bytes: u64,
+}
+
+/// All assets embedded in the binary with their sizes.
+///
+/// The build fails if their total exceeds the asset budget (32 MiB by
+/// default, overridable with `VELLO_BENCH_ASSET_BUDGET_MB`).
+pub fn asset_manifest() -> &'static [AssetEntry] {
+    ASSET_MANIFEST
+}
+
+/// Total size of all embedded assets in bytes.
+pub fn total_asset_bytes() -> u64 {
+    ASSET_MANIFEST.iter().map(|asset| asset.bytes).sum()
+}
+
 #[derive(Clone, Debug)]
 pub struct DataItem {
     pub name: String,
@@ -54,6 +114,12 @@ impl DataItem {
 
     /// Get the raw flattened lines of both fills and strokes.
     pub fn lines(&self) -> Vec<Line> {
+        self.lines_with_transform(Affine::IDENTITY)
+    }
+
+    /// Get the raw flattened lines of both fills and strokes, with
+    /// `transform` applied on top of each path's own transform.
+    pub fn lines_with_transform(&self, transform: Affine) -> Vec<Line> {
         let mut line_buf = vec![];
         let mut temp_buf = vec![];
 
@@ -61,7 +127,7 @@ impl DataItem {
             flatten::fill(
                 Level::new(),
                 &path.path,
-                path.transform,
+                transform * path.transform,
                 &mut temp_buf,
                 &mut FlattenCtx::default(),
             );
@@ -77,7 +143,7 @@ impl DataItem {
                 Level::new(),
                 &path.path,
                 &stroke,
-                path.transform,
+                transform * path.transform,
                 &mut temp_buf,
                 &mut FlattenCtx::default(),
                 &mut StrokeCtx::default(),
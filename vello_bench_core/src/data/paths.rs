@@ -0,0 +1,462 @@
+//! A corpus of paths in three tiers of complexity, for scenes and
+//! micro-benchmarks that need realistic geometry of a known difficulty.
+//!
+//! Every tier is deterministic and returns the same paths in the same order
+//! on every call and platform. Each path is scaled uniformly and centered so
+//! that its bounding box lies in the unit square and spans it along its
+//! longer side; draw it with a transform to place and size it.
+//!
+//! | Tier          | Paths  | Segments per path | Contents                         |
+//! |---------------|--------|-------------------|----------------------------------|
+//! | [`simple`]    | 24     | 4 to 8            | rects, ellipses, rounded rects   |
+//! | [`medium`]    | 20     | 10 to 24          | blobs, stars, glyph outlines     |
+//! | [`complex`]   | 7      | 500 to 5000       | runs of tiger fills, squiggles   |
+//!
+//! Segments are counted with [`segment_count`]. The shapes are built by hand
+//! rather than with kurbo's shape conversions, so their segment counts don't
+//! depend on a flattening tolerance or the kurbo version.
+
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+use rand::prelude::StdRng;
+use rand::{Rng, SeedableRng};
+use vello_common::kurbo::{Affine, BezPath, Point, Shape, Vec2};
+
+use super::get_data_items;
+
+/// Fewest segments of a path in [`complex`].
+pub const COMPLEX_MIN_SEGMENTS: usize = 500;
+
+/// Width to height ratios of the shapes in [`simple`].
+const ASPECTS: [f64; 4] = [1.0, 1.618, 3.0, 0.4];
+
+/// Segment counts of the squiggles in [`complex`].
+const SQUIGGLE_SEGMENTS: [usize; 4] = [500, 1000, 2000, 5000];
+
+/// Number of segments of `path`, with a closing line counted where a
+/// subpath is closed away from its start.
+pub fn segment_count(path: &BezPath) -> usize {
+    path.segments().count()
+}
+
+/// Rects, ellipses and rounded rects in four aspect ratios, each once
+/// axis-aligned and once rotated (24 paths):
+///
+/// - 8 rects, 4 line segments each
+/// - 8 ellipses, 8 cubic segments each
+/// - 8 rounded rects with corner radii of 10% to 40% of the shorter side, 4
+///   line and 4 cubic segments each
+pub fn simple() -> Vec<BezPath> {
+    let rects = in_aspects(|_, w, h| {
+        let mut path = BezPath::new();
+        polygon(&mut path, &[(0.0, 0.0), (w, 0.0), (w, h), (0.0, h)]);
+        path
+    });
+    let ellipses = in_aspects(|_, w, h| {
+        let mut path = BezPath::new();
+        ellipse_arcs(&mut path, Point::ZERO, Vec2::new(w / 2.0, h / 2.0), true);
+        path
+    });
+    let rounded_rects = in_aspects(|i, w, h| {
+        let radius = 0.1 * (i + 1) as f64 * w.min(h);
+        rounded_rect(w, h, radius)
+    });
+
+    rects
+        .into_iter()
+        .chain(ellipses)
+        .chain(rounded_rects)
+        .map(normalized)
+        .collect()
+}
+
+/// Blobs, stars and glyph-like outlines (20 paths):
+///
+/// - 8 blobs, smooth closed curves with 10 to 17 cubic segments
+/// - 8 stars with 5 to 12 points, 10 to 24 line segments
+/// - the letters O, A, D and B as outlines with counters of opposite
+///   orientation, 16, 11, 10 and 17 segments
+pub fn medium() -> Vec<BezPath> {
+    let mut rng = StdRng::seed_from_u64(0x6d65_6469_756d);
+    let mut paths: Vec<BezPath> = (10..18).map(|points| blob(&mut rng, points)).collect();
+    paths.extend((5..13).map(|points| star(points, rng.random_range(0.35..0.65))));
+    paths.extend([glyph_o(), glyph_a(), glyph_d(), glyph_b()]);
+    paths.into_iter().map(normalized).collect()
+}
+
+/// Paths of at least [`COMPLEX_MIN_SEGMENTS`] segments each:
+///
+/// - 3 runs of the fills of the Ghostscript tiger in document order, with their
+///   transforms applied, cut into runs of at least [`COMPLEX_MIN_SEGMENTS`]
+///   segments; each run is one path of many subpaths (the number of runs
+///   follows the embedded tiger)
+/// - 4 open squiggles of 500, 1000, 2000 and 5000 cubic segments that cross
+///   themselves many times
+pub fn complex() -> Vec<BezPath> {
+    let mut paths = tiger_runs();
+    let mut rng = StdRng::seed_from_u64(0x0063_6f6d_706c_6578);
+    paths.extend(SQUIGGLE_SEGMENTS.map(|segments| squiggle(&mut rng, segments)));
+    paths.into_iter().map(normalized).collect()
+}
+
+/// Scale `path` uniformly and move it so that its bounding box lies centered
+/// in the unit square, touching two opposite sides.
+fn normalized(mut path: BezPath) -> BezPath {
+    let bbox = path.bounding_box();
+    let scale = 1.0 / bbox.width().max(bbox.height());
+    let margin = Vec2::new(
+        (1.0 - bbox.width() * scale) / 2.0,
+        (1.0 - bbox.height() * scale) / 2.0,
+    );
+    path.apply_affine(
+        Affine::translate(margin)
+            * Affine::scale(scale)
+            * Affine::translate(-bbox.origin().to_vec2()),
+    );
+    path
+}
+
+/// Two paths per entry of [`ASPECTS`], made by `make(index, width, height)`
+/// and then once left as is and once rotated by 30 degrees.
+fn in_aspects(make: impl Fn(usize, f64, f64) -> BezPath) -> Vec<BezPath> {
+    ASPECTS
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &aspect)| {
+            let (w, h) = (100.0 * aspect.min(1.0), 100.0 / aspect.max(1.0));
+            let path = make(i, w, h);
+            let mut rotated = path.clone();
+            rotated.apply_affine(Affine::rotate(PI / 6.0));
+            [path, rotated]
+        })
+        .collect()
+}
+
+/// A `w` by `h` rect with corners rounded by quarter ellipse arcs of
+/// `radius`, which ends exactly where it starts.
+fn rounded_rect(w: f64, h: f64, radius: f64) -> BezPath {
+    // Control point distance of a cubic approximating a quarter circle.
+    let k = radius * 0.552_284_749_830_793_4;
+    let mut path = BezPath::new();
+    path.move_to((radius, 0.0));
+    path.line_to((w - radius, 0.0));
+    path.curve_to((w - radius + k, 0.0), (w, radius - k), (w, radius));
+    path.line_to((w, h - radius));
+    path.curve_to((w, h - radius + k), (w - radius + k, h), (w - radius, h));
+    path.line_to((radius, h));
+    path.curve_to((radius - k, h), (0.0, h - radius + k), (0.0, h - radius));
+    path.line_to((0.0, radius));
+    path.curve_to((0.0, radius - k), (radius - k, 0.0), (radius, 0.0));
+    path.close_path();
+    path
+}
+
+/// A smooth closed curve through `points` points at jittered radii, one
+/// cubic segment per point (a closed Catmull-Rom spline).
+fn blob(rng: &mut StdRng, points: u32) -> BezPath {
+    let vertices: Vec<Point> = (0..points)
+        .map(|i| {
+            let angle = TAU * f64::from(i) / f64::from(points);
+            let radius = rng.random_range(60.0..100.0);
+            Point::new(radius * angle.cos(), radius * angle.sin())
+        })
+        .collect();
+    let n = vertices.len();
+    let at = |i: usize| vertices[i % n];
+
+    let mut path = BezPath::new();
+    path.move_to(at(0));
+    for i in 0..n {
+        let (p0, p1, p2, p3) = (at(i + n - 1), at(i), at(i + 1), at(i + 2));
+        path.curve_to(p1 + (p2 - p0) / 6.0, p2 - (p3 - p1) / 6.0, p2);
+    }
+    path.close_path();
+    path
+}
+
+/// A star of `points` points with inner vertices at `inner` times the outer
+/// radius.
+fn star(points: u32, inner: f64) -> BezPath {
+    let mut path = BezPath::new();
+    for i in 0..2 * points {
+        let angle = PI * f64::from(i) / f64::from(points) - FRAC_PI_2;
+        let radius = if i % 2 == 0 { 100.0 } else { 100.0 * inner };
+        let vertex = Point::new(radius * angle.cos(), radius * angle.sin());
+        if i == 0 {
+            path.move_to(vertex);
+        } else {
+            path.line_to(vertex);
+        }
+    }
+    path.close_path();
+    path
+}
+
+/// Append an ellipse around `center` out of 8 cubic arcs, clockwise (in
+/// y-down coordinates) or counter-clockwise.
+fn ellipse_arcs(path: &mut BezPath, center: Point, radii: Vec2, clockwise: bool) {
+    let step = if clockwise { TAU / 8.0 } else { -TAU / 8.0 };
+    // Control point distance of a cubic approximating an arc of `step`.
+    let k = 4.0 / 3.0 * (step / 4.0).tan();
+    let point = |angle: f64| center + Vec2::new(radii.x * angle.cos(), radii.y * angle.sin());
+    let tangent = |angle: f64| Vec2::new(-radii.x * angle.sin(), radii.y * angle.cos());
+
+    path.move_to(point(0.0));
+    for i in 0..8 {
+        // The last arc ends at angle 0 rather than a full turn, so it ends
+        // exactly at the start and closing adds no segment.
+        let (a0, a1) = (step * f64::from(i), step * f64::from((i + 1) % 8));
+        path.curve_to(
+            point(a0) + k * tangent(a0),
+            point(a1) - k * tangent(a1),
+            point(a1),
+        );
+    }
+    path.close_path();
+}
+
+/// Append a closed polygon through `vertices`.
+fn polygon(path: &mut BezPath, vertices: &[(f64, f64)]) {
+    path.move_to(vertices[0]);
+    for &vertex in &vertices[1..] {
+        path.line_to(vertex);
+    }
+    path.close_path();
+}
+
+/// An O: two rings of 8 arcs (16 segments).
+fn glyph_o() -> BezPath {
+    let mut path = BezPath::new();
+    let center = Point::new(40.0, 50.0);
+    ellipse_arcs(&mut path, center, Vec2::new(40.0, 50.0), true);
+    ellipse_arcs(&mut path, center, Vec2::new(24.0, 34.0), false);
+    path
+}
+
+/// An A: an outline of 8 lines around a triangular counter (11 segments).
+fn glyph_a() -> BezPath {
+    let mut path = BezPath::new();
+    polygon(
+        &mut path,
+        &[
+            (0.0, 100.0),
+            (40.0, 0.0),
+            (60.0, 0.0),
+            (100.0, 100.0),
+            (78.0, 100.0),
+            (70.0, 75.0),
+            (30.0, 75.0),
+            (22.0, 100.0),
+        ],
+    );
+    polygon(&mut path, &[(36.0, 58.0), (64.0, 58.0), (50.0, 20.0)]);
+    path
+}
+
+/// Append a bowl from the current point `from` to `to`, bulging out to
+/// `right`, as two quarter-ellipse-like cubics. Goes down if `to` is below
+/// `from` and up otherwise.
+fn bowl(path: &mut BezPath, from: Point, to: Point, right: f64) {
+    const K: f64 = 0.55;
+    let mid_y = (from.y + to.y) / 2.0;
+    let half = (to.y - from.y) / 2.0;
+    path.curve_to(
+        (from.x + K * (right - from.x), from.y),
+        (right, mid_y - K * half),
+        (right, mid_y),
+    );
+    path.curve_to(
+        Point::new(right, mid_y + K * half),
+        Point::new(to.x + K * (right - to.x), to.y),
+        to,
+    );
+}
+
+/// A D: a stem with one bowl around one counter (10 segments).
+fn glyph_d() -> BezPath {
+    let mut path = BezPath::new();
+    path.move_to((0.0, 0.0));
+    path.line_to((40.0, 0.0));
+    bowl(
+        &mut path,
+        Point::new(40.0, 0.0),
+        Point::new(40.0, 100.0),
+        90.0,
+    );
+    path.line_to((0.0, 100.0));
+    path.close_path();
+
+    path.move_to((18.0, 18.0));
+    path.line_to((18.0, 82.0));
+    path.line_to((40.0, 82.0));
+    bowl(
+        &mut path,
+        Point::new(40.0, 82.0),
+        Point::new(40.0, 18.0),
+        72.0,
+    );
+    path.close_path();
+    path
+}
+
+/// A B: a stem with two bowls around two counters (17 segments).
+fn glyph_b() -> BezPath {
+    let mut path = BezPath::new();
+    path.move_to((0.0, 0.0));
+    path.line_to((45.0, 0.0));
+    bowl(
+        &mut path,
+        Point::new(45.0, 0.0),
+        Point::new(45.0, 48.0),
+        80.0,
+    );
+    bowl(
+        &mut path,
+        Point::new(45.0, 48.0),
+        Point::new(50.0, 100.0),
+        90.0,
+    );
+    path.line_to((0.0, 100.0));
+    path.close_path();
+
+    for (top, bottom, right) in [(16.0, 38.0, 64.0), (58.0, 84.0, 72.0)] {
+        path.move_to((18.0, top));
+        path.line_to((18.0, bottom));
+        path.line_to((45.0, bottom));
+        bowl(
+            &mut path,
+            Point::new(45.0, bottom),
+            Point::new(45.0, top),
+            right,
+        );
+        path.close_path();
+    }
+    path
+}
+
+/// The tiger's fills in document order, cut into runs of at least
+/// [`COMPLEX_MIN_SEGMENTS`] segments. A remainder shorter than that is added
+/// to the last run.
+fn tiger_runs() -> Vec<BezPath> {
+    let tiger = &get_data_items()[0];
+    let mut runs = vec![];
+    let mut run = BezPath::new();
+    let mut run_segments = 0;
+
+    for fill in &tiger.fills {
+        let mut path = fill.path.clone();
+        path.apply_affine(fill.transform);
+        run_segments += segment_count(&path);
+        run.extend(path.elements().iter().copied());
+        if run_segments >= COMPLEX_MIN_SEGMENTS {
+            runs.push(std::mem::take(&mut run));
+            run_segments = 0;
+        }
+    }
+
+    if run_segments > 0 {
+        match runs.last_mut() {
+            Some(last) => last.extend(run.elements().iter().copied()),
+            None => runs.push(run),
+        }
+    }
+    runs
+}
+
+/// An open, smoothly turning random walk of `segments` cubic segments.
+fn squiggle(rng: &mut StdRng, segments: usize) -> BezPath {
+    let mut path = BezPath::new();
+    let mut point = Point::ZERO;
+    let mut heading: f64 = 0.0;
+    path.move_to(point);
+
+    for _ in 0..segments {
+        let length = rng.random_range(10.0..30.0);
+        let from = Vec2::from_angle(heading);
+        heading += rng.random_range(-1.2..1.2);
+        let to = Vec2::from_angle(heading);
+        let end = point + to * length;
+        path.curve_to(
+            point + from * (length / 3.0),
+            end - to * (length / 3.0),
+            end,
+        );
+        point = end;
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment_counts(paths: &[BezPath]) -> Vec<usize> {
+        paths.iter().map(segment_count).collect()
+    }
+
+    /// Check that every path is normalized: inside the unit square,
+    /// spanning it along its longer side, and centered.
+    fn assert_normalized(paths: &[BezPath]) {
+        for (i, path) in paths.iter().enumerate() {
+            let bbox = path.bounding_box();
+            let eps = 1e-9;
+            assert!(
+                bbox.x0 >= -eps && bbox.y0 >= -eps && bbox.x1 <= 1.0 + eps && bbox.y1 <= 1.0 + eps,
+                "path {i}: {bbox:?}"
+            );
+            assert!(
+                (bbox.width().max(bbox.height()) - 1.0).abs() < eps,
+                "path {i}: {bbox:?}"
+            );
+            let center = bbox.center();
+            assert!(
+                (center.x - 0.5).abs() < eps && (center.y - 0.5).abs() < eps,
+                "path {i}: {bbox:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn simple_paths() {
+        let paths = simple();
+        let rects = [4; 8];
+        let ellipses = [8; 8];
+        let rounded_rects = [8; 8];
+        assert_eq!(
+            segment_counts(&paths),
+            [&rects[..], &ellipses, &rounded_rects].concat()
+        );
+        assert_normalized(&paths);
+    }
+
+    #[test]
+    fn medium_paths() {
+        let paths = medium();
+        let blobs = 10..18;
+        let stars = (5..13).map(|points| 2 * points);
+        let glyphs = [16, 11, 10, 17];
+        let expected: Vec<usize> = blobs.chain(stars).chain(glyphs).collect();
+        assert_eq!(segment_counts(&paths), expected);
+        assert_normalized(&paths);
+    }
+
+    #[test]
+    fn complex_paths() {
+        let paths = complex();
+        let counts = segment_counts(&paths);
+        assert_eq!(counts.len(), 7);
+        let (tiger, squiggles) = counts.split_at(3);
+        assert_eq!(squiggles, SQUIGGLE_SEGMENTS);
+        for &count in tiger {
+            assert!((COMPLEX_MIN_SEGMENTS..=5000).contains(&count), "{counts:?}");
+        }
+        assert_normalized(&paths);
+    }
+
+    #[test]
+    fn tiers_are_deterministic() {
+        assert_eq!(simple(), simple());
+        assert_eq!(medium(), medium());
+        assert_eq!(complex(), complex());
+    }
+}
@@ -0,0 +1,578 @@
+//! Filter expressions for selecting benchmarks.
+//!
+//! The same syntax is accepted by the CLI (`vello_bench list --filter`), the
+//! WASM API and the web UI, so a filter built in one can be pasted into the
+//! others verbatim:
+//!
+//! ```text
+//! category:scene_cpu AND NOT name:*10000
+//! (category:fine/* OR category:tile) AND id:*_long
+//! tag:images AND tag:small
+//! vello_cpu/*_quality
+//! ```
+//!
+//! A term is `field:pattern`, where `field` is `id`, `category`, `name` or
+//! `tag` (matching if any of the [`tags`](BenchmarkInfo::tags) does), or a
+//! bare `pattern`, which matches the ID. Patterns are globs: `*` matches any
+//! run of characters (including `/`), `?` matches exactly one; everything
+//! else matches literally and case-sensitively. Terms are combined with `NOT`,
+//! `AND` and `OR`, binding in that order from tightest to loosest, and can be
+//! grouped with parentheses. Keywords are case-insensitive. An empty
+//! expression matches every benchmark.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::registry::BenchmarkInfo;
+
+/// A parsed filter expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    source: String,
+    expr: Expr,
+}
+
+/// Which part of a benchmark a term matches against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Id,
+    Category,
+    Name,
+    Tag,
+}
+
+impl Field {
+    const ALL: [Self; 4] = [Self::Id, Self::Category, Self::Name, Self::Tag];
+
+    fn key(self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::Category => "category",
+            Self::Name => "name",
+            Self::Tag => "tag",
+        }
+    }
+
+    /// Whether `pattern` matches this field of `info`, or any of its tags.
+    fn matches(self, pattern: &str, info: &BenchmarkInfo) -> bool {
+        match self {
+            Self::Id => glob_match(pattern, &info.id),
+            Self::Category => glob_match(pattern, &info.category),
+            Self::Name => glob_match(pattern, &info.name),
+            Self::Tag => info.tags.iter().any(|tag| glob_match(pattern, tag)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+    All,
+    Term { field: Field, pattern: String },
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    fn matches(&self, info: &BenchmarkInfo) -> bool {
+        match self {
+            Self::All => true,
+            Self::Term { field, pattern } => field.matches(pattern, info),
+            Self::Not(inner) => !inner.matches(info),
+            Self::And(a, b) => a.matches(info) && b.matches(info),
+            Self::Or(a, b) => a.matches(info) || b.matches(info),
+        }
+    }
+}
+
+impl Filter {
+    /// Parse a filter expression.
+    ///
+    /// On error, the message names the 1-based column of the offending token
+    /// and repeats the expression with a caret underneath it.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let tokens = tokenize(source);
+        let mut parser = Parser {
+            source,
+            tokens: &tokens,
+            pos: 0,
+        };
+
+        let expr = if tokens.is_empty() {
+            Expr::All
+        } else {
+            let expr = parser.parse_or()?;
+            if let Some(token) = parser.peek() {
+                let message = match token.kind {
+                    TokenKind::RParen => "unmatched ')'",
+                    _ => "expected AND or OR between terms",
+                };
+                return Err(error_at(source, token.start, message));
+            }
+            expr
+        };
+
+        Ok(Self {
+            source: source.to_string(),
+            expr,
+        })
+    }
+
+    /// Whether `info` is selected by this filter.
+    pub fn matches(&self, info: &BenchmarkInfo) -> bool {
+        self.expr.matches(info)
+    }
+
+    /// The benchmarks in `list` selected by this filter, in order.
+    pub fn apply(&self, list: &[BenchmarkInfo]) -> Vec<BenchmarkInfo> {
+        list.iter()
+            .filter(|info| self.matches(info))
+            .cloned()
+            .collect()
+    }
+
+    /// Whether this filter selects every benchmark, i.e. the expression is
+    /// empty.
+    pub fn is_all(&self) -> bool {
+        self.expr == Expr::All
+    }
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Filter {
+    /// Writes the expression as it was parsed.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Glob matching
+// ---------------------------------------------------------------------------
+
+/// Match `text` against a glob where `*` matches any run of characters and
+/// `?` exactly one. Also used for the `--scenes` globs of the golden tool.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` in the pattern, and the text position it was
+    // tried at, to backtrack to when a later literal fails to match.
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+// ---------------------------------------------------------------------------
+// Tokenizer
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TokenKind {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Word(String),
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    /// Char offset of the token in the source.
+    start: usize,
+}
+
+fn tokenize(source: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().enumerate().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let kind = match c {
+            c if c.is_whitespace() => continue,
+            '(' => TokenKind::LParen,
+            ')' => TokenKind::RParen,
+            _ => {
+                let mut word = String::from(c);
+                while let Some(&(_, c)) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                match word.to_ascii_uppercase().as_str() {
+                    "AND" => TokenKind::And,
+                    "OR" => TokenKind::Or,
+                    "NOT" => TokenKind::Not,
+                    _ => TokenKind::Word(word),
+                }
+            }
+        };
+        tokens.push(Token { kind, start });
+    }
+
+    tokens
+}
+
+// ---------------------------------------------------------------------------
+// Parser
+// ---------------------------------------------------------------------------
+
+/// Recursive descent parser over the token list.
+///
+/// ```text
+/// or   := and ("OR" and)*
+/// and  := not ("AND" not)*
+/// not  := "NOT" not | atom
+/// atom := "(" or ")" | term
+/// term := field ":" pattern | pattern
+/// ```
+struct Parser<'a> {
+    source: &'a str,
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        token
+    }
+
+    /// Char offset just past the end of the source, for errors at the end.
+    fn end(&self) -> usize {
+        self.source.chars().count()
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, String> {
+        let mut expr = self.parse_and()?;
+        while self.peek().is_some_and(|t| t.kind == TokenKind::Or) {
+            self.pos += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.parse_not()?;
+        while self.peek().is_some_and(|t| t.kind == TokenKind::And) {
+            self.pos += 1;
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_not()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr, String> {
+        if self.peek().is_some_and(|t| t.kind == TokenKind::Not) {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_atom()
+    }
+
+    fn parse_atom(&mut self) -> Result<Expr, String> {
+        let source = self.source;
+        let end = self.end();
+        let Some(token) = self.next().cloned() else {
+            return Err(error_at(source, end, "expected a term"));
+        };
+
+        match token.kind {
+            TokenKind::LParen => {
+                let expr = self.parse_or()?;
+                match self.next() {
+                    Some(Token {
+                        kind: TokenKind::RParen,
+                        ..
+                    }) => Ok(expr),
+                    Some(other) => Err(error_at(source, other.start, "expected ')'")),
+                    None => Err(error_at(source, token.start, "'(' is never closed")),
+                }
+            }
+            TokenKind::Word(word) => parse_term(source, &word, token.start),
+            TokenKind::RParen => Err(error_at(source, token.start, "expected a term before ')'")),
+            TokenKind::And | TokenKind::Or | TokenKind::Not => Err(error_at(
+                source,
+                token.start,
+                "expected a term, found a keyword",
+            )),
+        }
+    }
+}
+
+fn parse_term(source: &str, word: &str, start: usize) -> Result<Expr, String> {
+    let Some((key, pattern)) = word.split_once(':') else {
+        return Ok(Expr::Term {
+            field: Field::Id,
+            pattern: word.to_string(),
+        });
+    };
+
+    let Some(field) = Field::ALL.into_iter().find(|f| f.key() == key) else {
+        let known: Vec<&str> = Field::ALL.iter().map(|f| f.key()).collect();
+        return Err(error_at(
+            source,
+            start,
+            &format!(
+                "unknown field '{key}', expected one of {}",
+                known.join(", ")
+            ),
+        ));
+    };
+    if pattern.is_empty() {
+        return Err(error_at(
+            source,
+            start + key.chars().count() + 1,
+            &format!("expected a pattern after '{key}:'"),
+        ));
+    }
+
+    Ok(Expr::Term {
+        field,
+        pattern: pattern.to_string(),
+    })
+}
+
+/// Format a parse error at char offset `pos` of `source`.
+fn error_at(source: &str, pos: usize, message: &str) -> String {
+    format!(
+        "Invalid filter at column {}: {message}\n  {source}\n  {}^",
+        pos + 1,
+        " ".repeat(pos)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::CostClass;
+
+    fn info(id: &str, tags: &[&'static str]) -> BenchmarkInfo {
+        let (category, name) = id.rsplit_once('/').unwrap();
+        BenchmarkInfo {
+            id: id.to_string(),
+            category: category.to_string(),
+            name: name.to_string(),
+            measures: "nothing",
+            tags: tags.to_vec(),
+            estimated_cost: CostClass::Micro,
+        }
+    }
+
+    fn benchmarks() -> Vec<BenchmarkInfo> {
+        vec![
+            info("fine/fill/opaque_short", &["cpu", "micro"]),
+            info("fine/fill/opaque_long", &["cpu", "micro"]),
+            info("tile/lines_long", &["cpu", "micro"]),
+            info("scene_cpu/tiger", &["cpu", "scene", "small"]),
+            info("scene_cpu/rects_10000", &["cpu", "scene", "large"]),
+            info("scene_hybrid/tiger", &["gpu", "scene", "small"]),
+            info("vello_cpu/tiger_quality", &["cpu", "scene", "images"]),
+        ]
+    }
+
+    /// IDs of the benchmarks `expr` selects.
+    fn select(expr: &str) -> Vec<String> {
+        Filter::parse(expr)
+            .unwrap()
+            .apply(&benchmarks())
+            .into_iter()
+            .map(|info| info.id)
+            .collect()
+    }
+
+    fn parse_error(expr: &str) -> String {
+        Filter::parse(expr).unwrap_err()
+    }
+
+    #[test]
+    fn globs() {
+        assert!(glob_match("", ""));
+        assert!(!glob_match("", "a"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*", "fine/fill/x"));
+        assert!(glob_match("fine/*", "fine/fill/opaque"));
+        assert!(glob_match("*_long", "tile/lines_long"));
+        assert!(glob_match("a?c", "abc"));
+        assert!(!glob_match("a?c", "ac"));
+        assert!(glob_match("*a*b*", "xxaxxbxx"));
+        assert!(glob_match("*ab", "aab"));
+        assert!(!glob_match("*ab", "aba"));
+        assert!(!glob_match("Tiger", "tiger"));
+        assert!(glob_match("größe_?", "größe_ü"));
+    }
+
+    #[test]
+    fn empty_expression_selects_everything() {
+        for expr in ["", "   ", "\t\n"] {
+            let filter = Filter::parse(expr).unwrap();
+            assert!(filter.is_all());
+            assert_eq!(filter.apply(&benchmarks()).len(), benchmarks().len());
+        }
+        assert!(!Filter::parse("tiger").unwrap().is_all());
+    }
+
+    #[test]
+    fn fields() {
+        assert_eq!(select("*tiger"), select("id:*tiger"));
+        assert_eq!(
+            select("category:scene_cpu"),
+            ["scene_cpu/tiger", "scene_cpu/rects_10000"]
+        );
+        assert_eq!(
+            select("name:tiger"),
+            ["scene_cpu/tiger", "scene_hybrid/tiger"]
+        );
+        assert_eq!(select("tag:gpu"), ["scene_hybrid/tiger"]);
+        assert_eq!(select("tag:ima*"), ["vello_cpu/tiger_quality"]);
+        assert_eq!(select("vello_cpu/*_quality"), ["vello_cpu/tiger_quality"]);
+        assert!(select("name:nothing").is_empty());
+    }
+
+    #[test]
+    fn operators() {
+        assert_eq!(
+            select("category:scene_cpu AND NOT name:*10000"),
+            ["scene_cpu/tiger"]
+        );
+        assert_eq!(
+            select("tag:gpu OR tag:large"),
+            ["scene_cpu/rects_10000", "scene_hybrid/tiger"]
+        );
+        assert_eq!(
+            select("(category:fine/* OR category:tile) AND id:*_long"),
+            ["fine/fill/opaque_long", "tile/lines_long"]
+        );
+        assert_eq!(select("NOT NOT tag:gpu"), select("tag:gpu"));
+        assert_eq!(
+            select("tag:images and tag:scene"),
+            select("tag:images AND tag:scene")
+        );
+        assert_eq!(select("not tag:cpu"), ["scene_hybrid/tiger"]);
+    }
+
+    #[test]
+    fn precedence() {
+        // NOT binds tighter than AND, which binds tighter than OR.
+        assert_eq!(
+            Filter::parse("a OR b AND NOT c").unwrap().expr,
+            Filter::parse("a OR (b AND (NOT c))").unwrap().expr
+        );
+        assert_ne!(
+            Filter::parse("a OR b AND c").unwrap().expr,
+            Filter::parse("(a OR b) AND c").unwrap().expr
+        );
+        assert_eq!(
+            select("tag:gpu OR tag:micro AND name:*_long"),
+            [
+                "fine/fill/opaque_long",
+                "tile/lines_long",
+                "scene_hybrid/tiger"
+            ]
+        );
+        assert_eq!(
+            select("(tag:gpu OR tag:micro) AND name:*_long"),
+            ["fine/fill/opaque_long", "tile/lines_long"]
+        );
+        assert_eq!(
+            select("NOT tag:micro AND tag:small"),
+            ["scene_cpu/tiger", "scene_hybrid/tiger"]
+        );
+        assert!(select("NOT (tag:micro OR tag:scene)").is_empty());
+    }
+
+    #[test]
+    fn parentheses_need_no_spaces() {
+        assert_eq!(
+            select("(tag:gpu)OR(name:lines_long)"),
+            ["tile/lines_long", "scene_hybrid/tiger"]
+        );
+        assert_eq!(select("((((tag:gpu))))"), ["scene_hybrid/tiger"]);
+    }
+
+    #[test]
+    fn display_repeats_the_source() {
+        let source = "(category:fine/* OR tag:gpu)  AND NOT name:*_long";
+        let filter: Filter = source.parse().unwrap();
+        assert_eq!(filter.to_string(), source);
+    }
+
+    #[test]
+    fn malformed_expressions() {
+        let cases = [
+            ("AND", 1, "expected a term, found a keyword"),
+            ("tag:gpu AND", 12, "expected a term"),
+            (
+                "tag:gpu OR OR tag:cpu",
+                12,
+                "expected a term, found a keyword",
+            ),
+            ("NOT", 4, "expected a term"),
+            ("tag:gpu tag:cpu", 9, "expected AND or OR between terms"),
+            ("(tag:gpu", 1, "'(' is never closed"),
+            ("tag:gpu)", 8, "unmatched ')'"),
+            ("()", 2, "expected a term before ')'"),
+            ("(tag:gpu tag:cpu)", 10, "expected ')'"),
+            (
+                "size:small",
+                1,
+                "unknown field 'size', expected one of id, category, name, tag",
+            ),
+            ("NOT tag:", 9, "expected a pattern after 'tag:'"),
+        ];
+        for (expr, column, message) in cases {
+            let error = parse_error(expr);
+            assert!(
+                error.starts_with(&format!("Invalid filter at column {column}: {message}\n")),
+                "{expr:?}: {error}"
+            );
+        }
+    }
+
+    #[test]
+    fn error_points_at_the_column() {
+        assert_eq!(
+            parse_error("tag:gpu tag:cpu"),
+            "Invalid filter at column 9: expected AND or OR between terms\n  \
+             tag:gpu tag:cpu\n          ^"
+        );
+        // Columns count chars, not bytes.
+        assert!(parse_error("größe bad:x").starts_with("Invalid filter at column 7:"));
+    }
+}
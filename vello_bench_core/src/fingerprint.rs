@@ -0,0 +1,657 @@
+//! Content fingerprints of the programmatic scenes, for catching accidental
+//! changes to what a benchmark draws.
+//!
+//! Golden hashes (see [`golden`](crate::golden)) change whenever the
+//! rasterizer does, so they can't tell a vello change from a scene change.
+//! A fingerprint instead hashes the commands a scene issues: every path,
+//! rect, transform, paint, stroke and layer, with coordinates quantized to
+//! 1/64 px and color components to 1/1024. It stays the same across
+//! rasterizer changes and changes whenever the layout does, e.g. when a
+//! refactor touches a pseudo-random placement.
+//!
+//! Scenes are drawn through a [`FingerprintRenderer`], which hashes each call
+//! and passes it on to a tiny Vello CPU context. Glyph runs are hashed by
+//! their font only, since the glyphs go through a builder of that context,
+//! and recordings by when they are recorded and replayed, not by content.
+//!
+//! The store is a TOML file of `scene = "hash"` entries, checked in as
+//! [`DEFAULT_STORE`] next to this crate's manifest. Native-only, like the
+//! golden hashes.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::Serialize;
+use vello_common::filter_effects::Filter;
+use vello_common::glyph::GlyphRunBuilder;
+use vello_common::kurbo::{Affine, BezPath, PathEl, Point, Rect, Stroke};
+use vello_common::mask::Mask;
+use vello_common::paint::{ImageSource, PaintType};
+use vello_common::peniko::{BlendMode, Fill, FontData};
+use vello_common::pixmap::Pixmap;
+use vello_common::recording::{Recorder, Recording};
+use vello_cpu::{RenderContext, RenderMode};
+
+use crate::filter::glob_match;
+use crate::hash::StableHasher;
+use crate::renderer::Renderer;
+use crate::runner::FrameKind;
+use crate::vello_scenes::{find_scene, get_vello_scenes};
+
+/// Path of the checked-in store.
+pub const DEFAULT_STORE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fingerprints.toml");
+
+/// Frames drawn per scene, so that animated scenes are covered beyond their
+/// first frame.
+pub const FINGERPRINT_FRAMES: u64 = 3;
+
+/// Size of the Vello CPU context the commands are passed on to.
+const INNER_SIZE: u16 = 16;
+
+/// Steps per pixel that coordinates are quantized to.
+const COORD_STEPS: f64 = 64.0;
+
+/// Steps per unit that color components and opacities are quantized to.
+const COLOR_STEPS: f32 = 1024.0;
+
+/// A [`StableHasher`] with helpers for the values scenes issue.
+#[derive(Debug, Clone, Copy)]
+struct CommandHasher(StableHasher);
+
+impl CommandHasher {
+    const INIT: Self = Self(StableHasher::new());
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.0.write(bytes);
+    }
+
+    fn u64(&mut self, v: u64) {
+        self.bytes(&v.to_le_bytes());
+    }
+
+    fn i64(&mut self, v: i64) {
+        self.bytes(&v.to_le_bytes());
+    }
+
+    fn coord(&mut self, v: f64) {
+        self.i64((v * COORD_STEPS).round() as i64);
+    }
+
+    fn point(&mut self, p: Point) {
+        self.coord(p.x);
+        self.coord(p.y);
+    }
+
+    fn unit(&mut self, v: f32) {
+        self.i64((v * COLOR_STEPS).round() as i64);
+    }
+}
+
+/// A [`Renderer`] that hashes every command it receives.
+///
+/// It reports the size it was created with, so scenes lay out as they do in
+/// the benchmarks, but passes the commands on to a 16×16 Vello CPU context.
+/// Rendering to a pixmap leaves the pixmap untouched.
+pub struct FingerprintRenderer {
+    inner: RenderContext,
+    width: u16,
+    height: u16,
+    hash: CommandHasher,
+    commands: u64,
+}
+
+impl FingerprintRenderer {
+    /// The fingerprint of the commands so far, as 16 hex digits.
+    pub fn fingerprint(&self) -> String {
+        format!("{:016x}", self.hash.0.finish())
+    }
+
+    /// Number of commands hashed so far.
+    pub fn commands(&self) -> u64 {
+        self.commands
+    }
+
+    /// Start hashing a command, identified by `tag`.
+    fn command(&mut self, tag: u8) -> &mut CommandHasher {
+        self.commands += 1;
+        self.hash.bytes(&[tag]);
+        &mut self.hash
+    }
+
+    fn hash_path(&mut self, tag: u8, path: &BezPath) {
+        let hash = self.command(tag);
+        for el in path.elements() {
+            match *el {
+                PathEl::MoveTo(p) => {
+                    hash.bytes(b"M");
+                    hash.point(p);
+                }
+                PathEl::LineTo(p) => {
+                    hash.bytes(b"L");
+                    hash.point(p);
+                }
+                PathEl::QuadTo(p1, p2) => {
+                    hash.bytes(b"Q");
+                    hash.point(p1);
+                    hash.point(p2);
+                }
+                PathEl::CurveTo(p1, p2, p3) => {
+                    hash.bytes(b"C");
+                    hash.point(p1);
+                    hash.point(p2);
+                    hash.point(p3);
+                }
+                PathEl::ClosePath => hash.bytes(b"Z"),
+            }
+        }
+    }
+
+    fn hash_rect(&mut self, tag: u8, rect: &Rect) {
+        let hash = self.command(tag);
+        for v in [rect.x0, rect.y0, rect.x1, rect.y1] {
+            hash.coord(v);
+        }
+    }
+
+    fn hash_affine(&mut self, tag: u8, affine: Affine) {
+        let hash = self.command(tag);
+        let [a, b, c, d, e, f] = affine.as_coeffs();
+        // The linear part is unitless, so it gets the finer color steps.
+        for v in [a, b, c, d] {
+            hash.unit(v as f32);
+        }
+        hash.coord(e);
+        hash.coord(f);
+    }
+
+    fn hash_blend_mode(hash: &mut CommandHasher, blend_mode: BlendMode) {
+        hash.bytes(&[blend_mode.mix as u8, blend_mode.compose as u8]);
+    }
+}
+
+impl Renderer for FingerprintRenderer {
+    type GlyphRenderer = RenderContext;
+
+    fn new(
+        width: u16,
+        height: u16,
+        num_threads: u16,
+        level: fearless_simd::Level,
+        render_mode: RenderMode,
+    ) -> Self {
+        Self {
+            inner: Renderer::new(INNER_SIZE, INNER_SIZE, num_threads, level, render_mode),
+            width,
+            height,
+            hash: CommandHasher::INIT,
+            commands: 0,
+        }
+    }
+
+    fn fill_path(&mut self, path: &BezPath) {
+        self.hash_path(b'f', path);
+        self.inner.fill_path(path);
+    }
+
+    fn stroke_path(&mut self, path: &BezPath) {
+        self.hash_path(b's', path);
+        self.inner.stroke_path(path);
+    }
+
+    fn fill_rect(&mut self, rect: &Rect) {
+        self.hash_rect(b'r', rect);
+        self.inner.fill_rect(rect);
+    }
+
+    fn fill_blurred_rounded_rect(&mut self, rect: &Rect, radius: f32, std_dev: f32) {
+        self.hash_rect(b'b', rect);
+        self.hash.coord(radius.into());
+        self.hash.coord(std_dev.into());
+        self.inner.fill_blurred_rounded_rect(rect, radius, std_dev);
+    }
+
+    fn stroke_rect(&mut self, rect: &Rect) {
+        self.hash_rect(b'R', rect);
+        self.inner.stroke_rect(rect);
+    }
+
+    fn glyph_run(&mut self, font: &FontData) -> GlyphRunBuilder<'_, RenderContext> {
+        let hash = self.command(b'g');
+        hash.u64(font.data.len() as u64);
+        hash.u64(font.index.into());
+        self.inner.glyph_run(font)
+    }
+
+    fn push_layer(
+        &mut self,
+        clip_path: Option<&BezPath>,
+        blend_mode: Option<BlendMode>,
+        opacity: Option<f32>,
+        mask: Option<Mask>,
+        filter: Option<Filter>,
+    ) {
+        match clip_path {
+            Some(path) => self.hash_path(b'l', path),
+            None => {
+                self.command(b'l');
+            }
+        }
+        let hash = &mut self.hash;
+        if let Some(blend_mode) = blend_mode {
+            hash.bytes(b"B");
+            Self::hash_blend_mode(hash, blend_mode);
+        }
+        if let Some(opacity) = opacity {
+            hash.bytes(b"O");
+            hash.unit(opacity);
+        }
+        hash.bytes(&[u8::from(mask.is_some()), u8::from(filter.is_some())]);
+        self.inner
+            .push_layer(clip_path, blend_mode, opacity, mask, filter);
+    }
+
+    fn flush(&mut self) {
+        self.inner.flush();
+    }
+
+    fn push_clip_layer(&mut self, path: &BezPath) {
+        self.hash_path(b'c', path);
+        self.inner.push_clip_layer(path);
+    }
+
+    fn push_clip_path(&mut self, path: &BezPath) {
+        self.hash_path(b'C', path);
+        self.inner.push_clip_path(path);
+    }
+
+    fn push_blend_layer(&mut self, blend_mode: BlendMode) {
+        Self::hash_blend_mode(self.command(b'x'), blend_mode);
+        self.inner.push_blend_layer(blend_mode);
+    }
+
+    fn push_opacity_layer(&mut self, opacity: f32) {
+        self.command(b'o').unit(opacity);
+        self.inner.push_opacity_layer(opacity);
+    }
+
+    fn push_mask_layer(&mut self, mask: Mask) {
+        self.command(b'm');
+        self.inner.push_mask_layer(mask);
+    }
+
+    fn push_filter_layer(&mut self, filter: Filter) {
+        self.command(b'F');
+        self.inner.push_filter_layer(filter);
+    }
+
+    fn pop_layer(&mut self) {
+        self.command(b'p');
+        self.inner.pop_layer();
+    }
+
+    fn pop_clip_path(&mut self) {
+        self.command(b'P');
+        self.inner.pop_clip_path();
+    }
+
+    fn set_stroke(&mut self, stroke: Stroke) {
+        let hash = self.command(b'S');
+        hash.coord(stroke.width);
+        hash.coord(stroke.miter_limit);
+        hash.bytes(&[
+            stroke.join as u8,
+            stroke.start_cap as u8,
+            stroke.end_cap as u8,
+        ]);
+        hash.coord(stroke.dash_offset);
+        for &dash in &stroke.dash_pattern {
+            hash.coord(dash);
+        }
+        self.inner.set_stroke(stroke);
+    }
+
+    fn set_mask(&mut self, mask: Mask) {
+        self.command(b'k');
+        self.inner.set_mask(mask);
+    }
+
+    fn set_paint(&mut self, paint: impl Into<PaintType>) {
+        let paint = paint.into();
+        let hash = self.command(b'i');
+        match &paint {
+            PaintType::Solid(color) => {
+                hash.bytes(b"s");
+                for c in color.components {
+                    hash.unit(c);
+                }
+            }
+            PaintType::Gradient(gradient) => {
+                hash.bytes(b"g");
+                // The kind only holds the gradient's positions, which are
+                // quantized well enough by formatting them.
+                hash.bytes(format!("{:?}", gradient.kind).as_bytes());
+                hash.bytes(&[gradient.extend as u8]);
+                for stop in gradient.stops.iter() {
+                    hash.unit(stop.offset);
+                    for c in stop.color.components {
+                        hash.unit(c);
+                    }
+                }
+            }
+            // The image itself is hashed by `get_image_source`.
+            PaintType::Image(_) => hash.bytes(b"i"),
+        }
+        self.inner.set_paint(paint);
+    }
+
+    fn set_paint_transform(&mut self, affine: Affine) {
+        self.hash_affine(b'I', affine);
+        self.inner.set_paint_transform(affine);
+    }
+
+    fn set_fill_rule(&mut self, fill_rule: Fill) {
+        self.command(b'w').bytes(&[fill_rule as u8]);
+        self.inner.set_fill_rule(fill_rule);
+    }
+
+    fn set_transform(&mut self, transform: Affine) {
+        self.hash_affine(b't', transform);
+        self.inner.set_transform(transform);
+    }
+
+    fn set_aliasing_threshold(&mut self, aliasing_threshold: Option<u8>) {
+        let hash = self.command(b'a');
+        hash.bytes(&[
+            u8::from(aliasing_threshold.is_some()),
+            aliasing_threshold.unwrap_or(0),
+        ]);
+        self.inner.set_aliasing_threshold(aliasing_threshold);
+    }
+
+    fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        Self::hash_blend_mode(self.command(b'X'), blend_mode);
+        self.inner.set_blend_mode(blend_mode);
+    }
+
+    fn set_filter_effect(&mut self, filter: Filter) {
+        self.command(b'e');
+        self.inner.set_filter_effect(filter);
+    }
+
+    fn reset_filter_effect(&mut self) {
+        self.command(b'E');
+        self.inner.reset_filter_effect();
+    }
+
+    fn render_to_pixmap(&self, _pixmap: &mut Pixmap) {}
+
+    fn width(&self) -> u16 {
+        self.width
+    }
+
+    fn height(&self) -> u16 {
+        self.height
+    }
+
+    fn get_image_source(&mut self, pixmap: Arc<Pixmap>) -> ImageSource {
+        let hash = self.command(b'u');
+        hash.u64(pixmap.width().into());
+        hash.u64(pixmap.height().into());
+        hash.bytes(pixmap.data_as_u8_slice());
+        self.inner.get_image_source(pixmap)
+    }
+
+    fn record(&mut self, recording: &mut Recording, f: impl FnOnce(&mut Recorder<'_>)) {
+        self.command(b'd');
+        Renderer::record(&mut self.inner, recording, f);
+    }
+
+    fn prepare_recording(&mut self, recording: &mut Recording) {
+        Renderer::prepare_recording(&mut self.inner, recording);
+    }
+
+    fn execute_recording(&mut self, recording: &Recording) {
+        self.command(b'D');
+        Renderer::execute_recording(&mut self.inner, recording);
+    }
+}
+
+/// Fingerprint of the programmatic scene `name`: its setup, then
+/// [`FINGERPRINT_FRAMES`] full frames and, for scenes with one, a warm-up
+/// proxy frame. Returns `None` for unknown scenes.
+pub fn scene_fingerprint(name: &str) -> Option<String> {
+    let registration = find_scene::<FingerprintRenderer>(name)?;
+    let info = &registration.info;
+    let mut r: FingerprintRenderer = Renderer::new(
+        info.width,
+        info.height,
+        0,
+        fearless_simd::Level::new(),
+        RenderMode::default(),
+    );
+    let state = registration.setup(&mut r);
+    for frame_index in 0..FINGERPRINT_FRAMES {
+        registration.draw_frame(state.as_ref(), &mut r, FrameKind::Full, frame_index);
+        r.flush();
+    }
+    if registration.has_warmup_proxy {
+        registration.draw_frame(state.as_ref(), &mut r, FrameKind::Proxy, 0);
+        r.flush();
+    }
+    Some(r.fingerprint())
+}
+
+/// Scene → fingerprint table, persisted as TOML.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct FingerprintStore {
+    entries: BTreeMap<String, String>,
+}
+
+impl FingerprintStore {
+    /// Parse a store from TOML.
+    pub fn from_toml(s: &str) -> Result<Self, String> {
+        let entries =
+            toml::from_str(s).map_err(|e| format!("Failed to parse fingerprint store: {e}"))?;
+        Ok(Self { entries })
+    }
+
+    /// Serialize the store to TOML, with a header saying how to update it.
+    pub fn to_toml(&self) -> String {
+        let entries =
+            toml::to_string_pretty(self).expect("fingerprint store is always serializable");
+        format!(
+            "# Content fingerprints of the programmatic scenes. Update with\n\
+             # `cargo run -p vello_bench_core --bin golden -- fingerprint-update`\n\
+             # in the same change that alters a scene's layout.\n\n{entries}"
+        )
+    }
+
+    /// Load a store from disk. A missing file yields an empty store.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => Self::from_toml(&content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("Failed to read {}: {e}", path.display())),
+        }
+    }
+
+    /// Write the store to disk.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        std::fs::write(path, self.to_toml())
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+    }
+
+    /// Whether the store has no entries at all.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Stored fingerprint for a scene.
+    pub fn get(&self, scene: &str) -> Option<&str> {
+        self.entries.get(scene).map(String::as_str)
+    }
+
+    /// Store the fingerprint for a scene.
+    pub fn set(&mut self, scene: &str, fingerprint: String) {
+        self.entries.insert(scene.to_string(), fingerprint);
+    }
+
+    /// Remove the entries of scenes that are no longer registered, and
+    /// return their names.
+    pub fn retain_registered(&mut self) -> Vec<String> {
+        let scenes = get_vello_scenes();
+        let (kept, removed): (BTreeMap<_, _>, BTreeMap<_, _>) = std::mem::take(&mut self.entries)
+            .into_iter()
+            .partition(|(scene, _)| scenes.iter().any(|s| s.name == scene.as_str()));
+        self.entries = kept;
+        removed.into_keys().collect()
+    }
+}
+
+/// A single fingerprint check failure.
+#[derive(Debug, Clone)]
+pub struct FingerprintMismatch {
+    pub scene: String,
+    /// Stored fingerprint, or `None` if the scene has no entry.
+    pub expected: Option<String>,
+    pub actual: String,
+}
+
+/// Outcome of [`check`].
+#[derive(Debug, Clone, Default)]
+pub struct FingerprintReport {
+    /// Number of scenes compared.
+    pub checked: usize,
+    pub mismatches: Vec<FingerprintMismatch>,
+    /// Scenes that drew differently when set up a second time in the same
+    /// process, i.e. whose content isn't deterministic. `expected` is the
+    /// fingerprint of the first setup.
+    pub unstable: Vec<FingerprintMismatch>,
+}
+
+impl FingerprintReport {
+    pub fn is_ok(&self) -> bool {
+        self.mismatches.is_empty() && self.unstable.is_empty()
+    }
+
+    /// Human-readable summary listing each failing scene once.
+    pub fn summary(&self) -> String {
+        let mut out = String::new();
+        if self.is_ok() {
+            let _ = writeln!(out, "All {} scene fingerprints match.", self.checked);
+            return out;
+        }
+
+        for m in &self.unstable {
+            let first = m.expected.as_deref().unwrap_or_default();
+            let _ = writeln!(
+                out,
+                "  {}: not deterministic, fingerprinted as {first} and then {}",
+                m.scene, m.actual
+            );
+        }
+        if !self.mismatches.is_empty() {
+            let _ = writeln!(
+                out,
+                "{} of {} scene fingerprints differ:",
+                self.mismatches.len(),
+                self.checked
+            );
+        }
+        for m in &self.mismatches {
+            let expected = m.expected.as_deref().unwrap_or("<missing>");
+            let _ = writeln!(out, "  {}: expected {expected}, got {}", m.scene, m.actual);
+        }
+        if !self.mismatches.is_empty() {
+            out.push_str(
+                "If the layout change is intended, run `golden fingerprint-update` and commit \
+                 the store with it.\n",
+            );
+        }
+        out
+    }
+}
+
+/// Fingerprint `scene` twice, from separate setups. Returns both if they
+/// differ.
+fn stable_fingerprint(scene: &str) -> Option<Result<String, (String, String)>> {
+    let first = scene_fingerprint(scene)?;
+    let second = scene_fingerprint(scene)?;
+    Some(if first == second {
+        Ok(first)
+    } else {
+        Err((first, second))
+    })
+}
+
+/// Fingerprint every programmatic scene matching `scene_glob` and compare
+/// against the store.
+pub fn check(store: &FingerprintStore, scene_glob: &str) -> FingerprintReport {
+    let mut report = FingerprintReport::default();
+
+    for info in get_vello_scenes() {
+        if !glob_match(scene_glob, info.name) {
+            continue;
+        }
+        let Some(fingerprint) = stable_fingerprint(info.name) else {
+            continue;
+        };
+        report.checked += 1;
+
+        match fingerprint {
+            Ok(actual) => {
+                let expected = store.get(info.name);
+                if expected != Some(actual.as_str()) {
+                    report.mismatches.push(FingerprintMismatch {
+                        scene: info.name.to_string(),
+                        expected: expected.map(str::to_string),
+                        actual,
+                    });
+                }
+            }
+            Err((first, second)) => {
+                report.unstable.push(FingerprintMismatch {
+                    scene: info.name.to_string(),
+                    expected: Some(first),
+                    actual: second,
+                });
+            }
+        }
+    }
+
+    report
+}
+
+/// Re-fingerprint every programmatic scene matching `scene_glob` and store
+/// the results. Returns the number of entries written.
+///
+/// Fails without modifying the store if a scene isn't deterministic, since
+/// its fingerprint would be meaningless.
+pub fn update(store: &mut FingerprintStore, scene_glob: &str) -> Result<usize, String> {
+    let mut updates = Vec::new();
+
+    for info in get_vello_scenes() {
+        if !glob_match(scene_glob, info.name) {
+            continue;
+        }
+        match stable_fingerprint(info.name) {
+            Some(Ok(fingerprint)) => updates.push((info.name, fingerprint)),
+            Some(Err((first, second))) => {
+                return Err(format!(
+                    "{}: not deterministic, fingerprinted as {first} and then {second}",
+                    info.name
+                ));
+            }
+            None => {}
+        }
+    }
+
+    let count = updates.len();
+    for (scene, fingerprint) in updates {
+        store.set(scene, fingerprint);
+    }
+    Ok(count)
+}
@@ -0,0 +1,267 @@
+//! Finding the largest element count of a scene family that renders within a
+//! frame-time budget, e.g. "how many flowers can this machine draw in 16 ms".
+//!
+//! The families are the counted image scenes (see
+//! [`COUNTED_FAMILIES`](crate::vello_scenes::COUNTED_FAMILIES)), drawn with
+//! the same parameterized draw functions as their benchmarks. [`CountSearch`]
+//! doubles the count until a probe exceeds the budget, then bisects between
+//! the largest count within it and the smallest count over it. Each probe
+//! draws and renders one untimed frame and then [`PROBE_FRAMES`] timed ones,
+//! and compares their median with the budget.
+//!
+//! Every probe narrows the range, so the search ends after a bounded number
+//! of probes even if noisy timings are not monotonic in the count; noise only
+//! affects where it ends up.
+//!
+//! The search is driven from outside so that the WASM build can yield to the
+//! event loop between probes; [`search`] drives it synchronously.
+
+use serde::{Deserialize, Serialize};
+
+use crate::renderer::Renderer;
+use crate::runner::median_frame_ns;
+use crate::vello_scenes::{CountedDrawFn, ImageGridState};
+
+/// Timed frames per probe.
+pub const PROBE_FRAMES: usize = 5;
+
+/// First count probed.
+const START_COUNT: u32 = 16;
+
+/// Largest count probed. Beyond this, a single frame takes long enough that
+/// the demo stops being interactive.
+pub const MAX_COUNT: u32 = 100_000;
+
+/// Backstop against a search that doesn't converge; a well-behaved search
+/// needs about 30 probes at most.
+const MAX_PROBES: u32 = 64;
+
+/// Outcome of a budget search.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BudgetResult {
+    /// Largest count whose median frame time was within the budget, or `0` if
+    /// even a single element was too slow.
+    pub count: u32,
+    /// Median frame time at `count` in milliseconds; for `count == 0`, the
+    /// time at the smallest count probed.
+    pub time_ms: f64,
+    /// Number of counts probed.
+    pub probes: u32,
+}
+
+/// State of a budget search. Ask it for the [`next_count`](Self::next_count)
+/// to probe, measure that count and [`record`](Self::record) the result,
+/// until it returns `None`.
+#[derive(Debug, Clone)]
+pub struct CountSearch {
+    budget_ms: f64,
+    /// Largest count measured within the budget, with its time.
+    within: Option<(u32, f64)>,
+    /// Smallest count measured over the budget, with its time.
+    over: Option<(u32, f64)>,
+    probes: u32,
+}
+
+impl CountSearch {
+    /// Start a search for `budget_ms`, which must be positive and finite.
+    pub fn new(budget_ms: f64) -> Result<Self, String> {
+        if !(budget_ms.is_finite() && budget_ms > 0.0) {
+            return Err(format!("Invalid frame budget: {budget_ms} ms"));
+        }
+        Ok(Self {
+            budget_ms,
+            within: None,
+            over: None,
+            probes: 0,
+        })
+    }
+
+    /// The count to probe next, or `None` once the search is done.
+    ///
+    /// The search stops when the range between the largest count within the
+    /// budget and the smallest count over it is down to 1% (or a single
+    /// element).
+    pub fn next_count(&self) -> Option<u32> {
+        if self.probes >= MAX_PROBES {
+            return None;
+        }
+        match (self.within, self.over) {
+            (None, None) => Some(START_COUNT),
+            (Some((lo, _)), None) => (lo < MAX_COUNT).then(|| lo.saturating_mul(2).min(MAX_COUNT)),
+            (None, Some((hi, _))) => (hi > 1).then_some(hi / 2),
+            (Some((lo, _)), Some((hi, _))) => {
+                let resolution = (lo / 100).max(1);
+                (hi - lo > resolution).then(|| lo + (hi - lo) / 2)
+            }
+        }
+    }
+
+    /// Record the median frame time measured at `count`.
+    ///
+    /// A measurement that contradicts an earlier one (a count within the
+    /// budget at or above one that was over it, or the other way around)
+    /// replaces it, since the newer measurement is the better informed one.
+    pub fn record(&mut self, count: u32, time_ms: f64) {
+        self.probes += 1;
+        if time_ms <= self.budget_ms {
+            if self.within.is_none_or(|(lo, _)| count > lo) {
+                self.within = Some((count, time_ms));
+            }
+            if self.over.is_some_and(|(hi, _)| hi <= count) {
+                self.over = None;
+            }
+        } else {
+            if self.over.is_none_or(|(hi, _)| count < hi) {
+                self.over = Some((count, time_ms));
+            }
+            if self.within.is_some_and(|(lo, _)| lo >= count) {
+                self.within = None;
+            }
+        }
+    }
+
+    /// The result so far.
+    pub fn result(&self) -> BudgetResult {
+        let (count, time_ms) = match (self.within, self.over) {
+            (Some((count, time_ms)), _) => (count, time_ms),
+            (None, Some((_, time_ms))) => (0, time_ms),
+            (None, None) => (0, f64::NAN),
+        };
+        BudgetResult {
+            count,
+            time_ms,
+            probes: self.probes,
+        }
+    }
+}
+
+/// Run a whole search for `budget_ms`, with `probe` returning the median
+/// frame time in milliseconds at a count.
+pub fn search(budget_ms: f64, mut probe: impl FnMut(u32) -> f64) -> Result<BudgetResult, String> {
+    let mut search = CountSearch::new(budget_ms)?;
+    while let Some(count) = search.next_count() {
+        search.record(count, probe(count));
+    }
+    Ok(search.result())
+}
+
+/// Median frame time in milliseconds of drawing `count` elements with `draw`
+/// and rendering them with `present`, e.g. flush and rasterize for Vello CPU.
+pub fn probe_frame_ms<R: Renderer>(
+    r: &mut R,
+    state: &ImageGridState,
+    draw: CountedDrawFn<R>,
+    count: u32,
+    mut present: impl FnMut(&mut R),
+) -> f64 {
+    let mut frame = || {
+        draw(state, r, count);
+        present(r);
+    };
+    frame();
+    median_frame_ns(PROBE_FRAMES, frame) / 1e6
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Frame times that grow linearly with the count, plus up to `noise`
+    /// (relative) of deterministic pseudo-random jitter, so that they are not
+    /// monotonic in the count.
+    struct FakeFrames {
+        ms_per_element: f64,
+        noise: f64,
+        state: u64,
+    }
+
+    impl FakeFrames {
+        fn new(ms_per_element: f64, noise: f64, seed: u64) -> Self {
+            Self {
+                ms_per_element,
+                noise,
+                state: seed | 1,
+            }
+        }
+
+        fn frame_ms(&mut self, count: u32) -> f64 {
+            // xorshift64
+            self.state ^= self.state << 13;
+            self.state ^= self.state >> 7;
+            self.state ^= self.state << 17;
+            let jitter = (self.state >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0;
+            count as f64 * self.ms_per_element * (1.0 + self.noise * jitter)
+        }
+    }
+
+    /// Run a search and check that it stopped on its own rather than at the
+    /// probe backstop.
+    fn run(budget_ms: f64, probe: impl FnMut(u32) -> f64) -> BudgetResult {
+        let result = search(budget_ms, probe).unwrap();
+        assert!(result.probes < MAX_PROBES, "{result:?}");
+        result
+    }
+
+    #[test]
+    fn finds_the_count_within_the_budget() {
+        // 1000 elements take exactly 16 ms.
+        let result = run(16.0, |count| count as f64 * 0.016);
+        assert!((990..=1000).contains(&result.count), "{result:?}");
+        assert!(result.time_ms <= 16.0);
+    }
+
+    #[test]
+    fn terminates_with_noisy_timings() {
+        for seed in 0..100 {
+            for noise in [0.05, 0.2, 0.5] {
+                let mut frames = FakeFrames::new(0.016, noise, seed);
+                let result = run(16.0, |count| frames.frame_ms(count));
+                // Noise moves the result by at most its own amount, plus the
+                // search resolution.
+                let lo = (1000.0 / (1.0 + noise) * 0.99).floor() as u32;
+                let hi = (1000.0 / (1.0 - noise)).ceil() as u32;
+                assert!(
+                    (lo..=hi).contains(&result.count),
+                    "seed {seed}, noise {noise}: {result:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn terminates_with_timings_unrelated_to_the_count() {
+        for seed in 0..100 {
+            let mut frames = FakeFrames::new(1.0, 1.0, seed);
+            run(16.0, |_| frames.frame_ms(16));
+        }
+    }
+
+    #[test]
+    fn stops_at_the_extremes() {
+        let result = run(16.0, |_| 1.0);
+        assert_eq!(result.count, MAX_COUNT);
+        assert_eq!(result.time_ms, 1.0);
+
+        let result = run(16.0, |_| 20.0);
+        assert_eq!(result.count, 0);
+        assert_eq!(result.time_ms, 20.0);
+    }
+
+    #[test]
+    fn contradicting_measurements_replace_earlier_ones() {
+        let mut search = CountSearch::new(16.0).unwrap();
+        search.record(100, 10.0);
+        search.record(200, 20.0);
+        // 50 is now over the budget, so 100 no longer counts as within it.
+        search.record(50, 17.0);
+        assert_eq!(search.result().count, 0);
+        assert_eq!(search.next_count(), Some(25));
+    }
+
+    #[test]
+    fn rejects_invalid_budgets() {
+        for budget_ms in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(CountSearch::new(budget_ms).is_err(), "{budget_ms}");
+        }
+    }
+}
@@ -0,0 +1,312 @@
+//! Frame pacing of a sequence of presented frames, e.g. the
+//! `requestAnimationFrame` timestamps of a browser rendering continuously.
+//!
+//! Browsers present frames on vsync, so frame intervals are quantized to
+//! multiples of the refresh period, and their mean says little about how
+//! smooth the animation looked. [`FramePacing`] instead detects the refresh
+//! period as the most common interval and sorts every interval into how many
+//! vsyncs it took: on time, one missed (2×), two missed (3×) or worse.
+//!
+//! The analysis only looks at the intervals; the caller supplies them, or
+//! the timestamps they are taken from.
+
+use serde::{Deserialize, Serialize};
+
+/// Intervals up to this multiple of the refresh period count as on time.
+/// The buckets for missed frames extend by the same margin, e.g. the 2×
+/// bucket ends at 2.05× the period.
+pub const ON_TIME_TOLERANCE: f64 = 1.05;
+
+/// Relative distance within which two intervals count as the same when
+/// detecting the refresh period.
+const MODE_TOLERANCE: f64 = 0.03;
+
+/// Fewest intervals a pacing analysis is done for.
+pub const MIN_INTERVALS: usize = 2;
+
+/// Frame pacing statistics of a sequence of frame intervals.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FramePacing {
+    /// Number of intervals analyzed.
+    pub intervals: usize,
+    /// Detected refresh period in milliseconds: the median of the most common
+    /// interval and those within 3% of it.
+    pub refresh_period_ms: f64,
+    /// `1000 / refresh_period_ms`.
+    pub refresh_hz: f64,
+    /// Percentage of intervals within [`ON_TIME_TOLERANCE`] times the refresh
+    /// period.
+    pub on_time_percent: f64,
+    /// Late intervals of up to two refresh periods (plus the tolerance), i.e.
+    /// frames that missed one vsync.
+    pub doubled: usize,
+    /// Late intervals of up to three refresh periods.
+    pub tripled: usize,
+    /// Intervals longer than three refresh periods.
+    pub worse: usize,
+    /// Total vsyncs missed: for each late interval, the number of refresh
+    /// periods it took beyond the first, rounded, but at least one.
+    pub missed_vsyncs: usize,
+    /// Longest interval in milliseconds.
+    pub longest_stall_ms: f64,
+    /// Most vsyncs missed in a row, summed over consecutive late intervals.
+    pub longest_missed_run: usize,
+}
+
+impl FramePacing {
+    /// Analyze the intervals between consecutive `timestamps_ms`, which must
+    /// be increasing.
+    pub fn from_timestamps(timestamps_ms: &[f64]) -> Result<Self, String> {
+        let intervals: Vec<f64> = timestamps_ms.windows(2).map(|w| w[1] - w[0]).collect();
+        if let Some(i) = intervals.iter().position(|&d| d <= 0.0) {
+            return Err(format!(
+                "Frame timestamps must be increasing, but {} follows {}",
+                timestamps_ms[i + 1],
+                timestamps_ms[i]
+            ));
+        }
+        Self::from_intervals(&intervals)
+    }
+
+    /// Analyze `intervals_ms`, which must be positive and finite, and at
+    /// least [`MIN_INTERVALS`] long.
+    pub fn from_intervals(intervals_ms: &[f64]) -> Result<Self, String> {
+        if intervals_ms.len() < MIN_INTERVALS {
+            return Err(format!(
+                "Frame pacing needs at least {MIN_INTERVALS} frame intervals, got {}",
+                intervals_ms.len()
+            ));
+        }
+        if let Some(d) = intervals_ms.iter().find(|d| !(d.is_finite() && **d > 0.0)) {
+            return Err(format!("Invalid frame interval: {d} ms"));
+        }
+
+        let period = refresh_period(intervals_ms);
+        let mut pacing = Self {
+            intervals: intervals_ms.len(),
+            refresh_period_ms: period,
+            refresh_hz: 1000.0 / period,
+            on_time_percent: 0.0,
+            doubled: 0,
+            tripled: 0,
+            worse: 0,
+            missed_vsyncs: 0,
+            longest_stall_ms: 0.0,
+            longest_missed_run: 0,
+        };
+
+        let mut on_time = 0;
+        let mut run = 0;
+        for &interval in intervals_ms {
+            let periods = interval / period;
+            pacing.longest_stall_ms = pacing.longest_stall_ms.max(interval);
+
+            if periods <= ON_TIME_TOLERANCE {
+                on_time += 1;
+                run = 0;
+                continue;
+            }
+            if periods <= 1.0 + ON_TIME_TOLERANCE {
+                pacing.doubled += 1;
+            } else if periods <= 2.0 + ON_TIME_TOLERANCE {
+                pacing.tripled += 1;
+            } else {
+                pacing.worse += 1;
+            }
+
+            // A late interval missed at least one vsync, even if it is
+            // closer to one period than to two.
+            let missed = (periods.round() as usize).max(2) - 1;
+            pacing.missed_vsyncs += missed;
+            run += missed;
+            pacing.longest_missed_run = pacing.longest_missed_run.max(run);
+        }
+        pacing.on_time_percent = 100.0 * on_time as f64 / intervals_ms.len() as f64;
+
+        Ok(pacing)
+    }
+
+    /// One line describing the pacing.
+    pub fn summary(&self) -> String {
+        format!(
+            "{:.1} Hz ({:.2} ms), {:.1}% on time, {} at 2x, {} at 3x, {} worse, \
+             {} vsyncs missed (at most {} in a row), longest stall {:.1} ms",
+            self.refresh_hz,
+            self.refresh_period_ms,
+            self.on_time_percent,
+            self.doubled,
+            self.tripled,
+            self.worse,
+            self.missed_vsyncs,
+            self.longest_missed_run,
+            self.longest_stall_ms
+        )
+    }
+}
+
+/// The most common of `intervals` (positive, non-empty): the one with the
+/// most intervals within [`MODE_TOLERANCE`] of it, preferring the shortest
+/// on ties, refined to the median of that cluster.
+fn refresh_period(intervals: &[f64]) -> f64 {
+    let mut sorted = intervals.to_vec();
+    sorted.sort_by(f64::total_cmp);
+
+    // Sliding window over the sorted intervals, `lo..hi` being those within
+    // the tolerance of `center`.
+    let (mut lo, mut hi) = (0, 0);
+    let mut best = 0..0;
+    for &center in &sorted {
+        while sorted[lo] < center * (1.0 - MODE_TOLERANCE) {
+            lo += 1;
+        }
+        while hi < sorted.len() && sorted[hi] <= center * (1.0 + MODE_TOLERANCE) {
+            hi += 1;
+        }
+        if hi - lo > best.len() {
+            best = lo..hi;
+        }
+    }
+
+    let cluster = &sorted[best];
+    let mid = cluster.len() / 2;
+    if cluster.len() % 2 == 0 {
+        (cluster[mid - 1] + cluster[mid]) / 2.0
+    } else {
+        cluster[mid]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PERIOD_60: f64 = 1000.0 / 60.0;
+    const PERIOD_120: f64 = 1000.0 / 120.0;
+
+    /// Timestamps of frames `intervals` apart, starting at 1000 ms.
+    fn timestamps(intervals: &[f64]) -> Vec<f64> {
+        let mut t = 1000.0;
+        let mut out = vec![t];
+        for interval in intervals {
+            t += interval;
+            out.push(t);
+        }
+        out
+    }
+
+    /// `n` intervals of `period`, alternately 0.05 ms early and late, as
+    /// timer jitter would make them.
+    fn steady(period: f64, n: usize) -> Vec<f64> {
+        (0..n).map(|i| period + [-0.05, 0.05][i % 2]).collect()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 0.1, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn steady_60_hz() {
+        let pacing = FramePacing::from_timestamps(&timestamps(&steady(PERIOD_60, 120))).unwrap();
+        assert_eq!(pacing.intervals, 120);
+        assert_close(pacing.refresh_period_ms, PERIOD_60);
+        assert_eq!(pacing.refresh_hz.round(), 60.0);
+        assert_eq!(pacing.on_time_percent, 100.0);
+        assert_eq!((pacing.doubled, pacing.tripled, pacing.worse), (0, 0, 0));
+        assert_eq!(pacing.missed_vsyncs, 0);
+        assert_eq!(pacing.longest_missed_run, 0);
+        assert_close(pacing.longest_stall_ms, PERIOD_60 + 0.05);
+    }
+
+    #[test]
+    fn steady_120_hz() {
+        let pacing = FramePacing::from_timestamps(&timestamps(&steady(PERIOD_120, 240))).unwrap();
+        assert_close(pacing.refresh_period_ms, PERIOD_120);
+        assert_eq!(pacing.refresh_hz.round(), 120.0);
+        assert_eq!(pacing.on_time_percent, 100.0);
+        assert_eq!(pacing.missed_vsyncs, 0);
+    }
+
+    #[test]
+    fn janky_60_hz() {
+        let p = PERIOD_60;
+        let mut intervals = steady(p, 20);
+        intervals.push(2.0 * p);
+        intervals.extend(steady(p, 10));
+        intervals.extend([2.0 * p, 2.0 * p]);
+        intervals.extend(steady(p, 10));
+        intervals.push(3.0 * p);
+        intervals.extend(steady(p, 10));
+        intervals.push(6.0 * p);
+        intervals.extend(steady(p, 5));
+
+        let pacing = FramePacing::from_timestamps(&timestamps(&intervals)).unwrap();
+        assert_eq!(pacing.intervals, 60);
+        // The stalls don't move the detected period.
+        assert_eq!(pacing.refresh_hz.round(), 60.0);
+        assert_close(pacing.on_time_percent, 100.0 * 55.0 / 60.0);
+        assert_eq!(pacing.doubled, 3);
+        assert_eq!(pacing.tripled, 1);
+        assert_eq!(pacing.worse, 1);
+        assert_eq!(pacing.missed_vsyncs, 3 + 2 + 5);
+        assert_eq!(pacing.longest_missed_run, 5);
+        assert_close(pacing.longest_stall_ms, 6.0 * p);
+        assert!(
+            pacing.summary().ends_with(
+                "91.7% on time, 3 at 2x, 1 at 3x, 1 worse, 10 vsyncs missed \
+                 (at most 5 in a row), longest stall 100.0 ms"
+            ),
+            "{}",
+            pacing.summary()
+        );
+    }
+
+    #[test]
+    fn consecutive_late_frames_add_up() {
+        let p = PERIOD_60;
+        let mut intervals = steady(p, 10);
+        intervals.extend([2.0 * p, 3.0 * p, 2.0 * p]);
+        intervals.extend(steady(p, 10));
+
+        let pacing = FramePacing::from_intervals(&intervals).unwrap();
+        assert_eq!(pacing.missed_vsyncs, 4);
+        assert_eq!(pacing.longest_missed_run, 4);
+    }
+
+    #[test]
+    fn slightly_late_frames_count_by_tolerance() {
+        let p = PERIOD_60;
+        let mut intervals = steady(p, 10);
+        // Within the tolerance: on time.
+        intervals.push(1.04 * p);
+        // Just beyond it: a missed vsync, though closer to one period.
+        intervals.push(1.2 * p);
+        let pacing = FramePacing::from_intervals(&intervals).unwrap();
+        assert_eq!(pacing.doubled, 1);
+        assert_eq!(pacing.missed_vsyncs, 1);
+        assert_close(pacing.on_time_percent, 100.0 * 11.0 / 12.0);
+    }
+
+    #[test]
+    fn prefers_the_shortest_of_equally_common_intervals() {
+        let intervals = [PERIOD_120, PERIOD_120, PERIOD_60, PERIOD_60];
+        let pacing = FramePacing::from_intervals(&intervals).unwrap();
+        assert_eq!(pacing.refresh_hz.round(), 120.0);
+        assert_eq!(pacing.doubled, 2);
+    }
+
+    #[test]
+    fn rejects_unusable_input() {
+        let error = FramePacing::from_timestamps(&[0.0, 16.0, 16.0, 32.0]).unwrap_err();
+        assert_eq!(
+            error,
+            "Frame timestamps must be increasing, but 16 follows 16"
+        );
+        let error = FramePacing::from_intervals(&[16.0]).unwrap_err();
+        assert_eq!(
+            error,
+            "Frame pacing needs at least 2 frame intervals, got 1"
+        );
+        let error = FramePacing::from_intervals(&[16.0, f64::NAN]).unwrap_err();
+        assert_eq!(error, "Invalid frame interval: NaN ms");
+    }
+}
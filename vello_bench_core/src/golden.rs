@@ -0,0 +1,1318 @@
+//! Golden screenshot hashes for detecting unintended rendering changes.
+//!
+//! The store is a TOML file with one table per scene, keyed by backend:
+//!
+//! ```toml
+//! [demo_scene]
+//! cpu = "5f3a9c0e12d4b7a8"
+//! cpu_quality = "9d04c6b1e83f2a57"
+//! hybrid = "0b71e2d9c4a35f60"
+//! ```
+//!
+//! Output legitimately differs between backends and between CPU render
+//! modes, so `cpu` (default mode) and `cpu_quality` (`OptimizeQuality`) are
+//! stored separately. Output must **not** differ between SIMD levels: for the
+//! CPU backends every available level is rendered and each one has to match
+//! the single stored hash.
+//!
+//! Scene names are looked up in the archive scenes first, then in the
+//! programmatic vello scenes. Native-only.
+//!
+//! GPU backends aren't byte-deterministic across drivers, so they are checked
+//! against reference PNGs instead (see [`check_perceptual`]): a render passes
+//! if its [`ssim`](crate::screenshot::ssim) against the reference reaches the
+//! threshold in the reference's sidecar file. References are blessed with
+//! [`approve`], which appends an audit note to [`APPROVALS_FILE`].
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use fearless_simd::Level;
+use serde::{Deserialize, Serialize};
+use vello_cpu::RenderMode;
+
+use crate::benchmarks::scene_cpu::CpuSceneRenderer;
+use crate::benchmarks::scene_hybrid::HybridSceneRenderer;
+use crate::benchmarks::scene_skia::SkiaSceneRenderer;
+pub use crate::filter::glob_match;
+use crate::hash::StableHasher;
+use crate::renderer::gpu_adapter_info;
+use crate::scenes::get_scenes;
+use crate::screenshot::{
+    AlphaMode, DEFAULT_BACKGROUND, DiffResult, PixmapPool, ScreenshotResult, diff,
+    render_scene_cpu_in, render_scene_hybrid_in, render_scene_skia_in,
+    render_vello_scene_cpu_with_mode_in, render_vello_scene_hybrid_in, row_profile, row_strips,
+    ssim, worst_rows,
+};
+use crate::simd::{available_levels, level_suffix};
+use crate::vello_scenes::get_vello_scenes;
+
+/// A rendering backend that golden hashes are tracked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// Vello CPU with the default render mode.
+    Cpu,
+    /// Vello CPU with `RenderMode::OptimizeQuality`. Only programmatic vello
+    /// scenes are rendered in this mode.
+    CpuQuality,
+    Hybrid,
+    Skia,
+}
+
+impl Backend {
+    /// All backends, in the order they are checked.
+    pub const ALL: [Self; 4] = [Self::Cpu, Self::CpuQuality, Self::Hybrid, Self::Skia];
+
+    /// Backends whose output is byte-deterministic, checked against hashes.
+    pub const HASHED: [Self; 3] = [Self::Cpu, Self::CpuQuality, Self::Skia];
+
+    /// Backends that render on the GPU, whose output differs between drivers
+    /// and is checked against perceptual references.
+    pub const GPU: [Self; 1] = [Self::Hybrid];
+
+    /// Key used in the golden store (e.g. `"cpu"`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Cpu => "cpu",
+            Self::CpuQuality => "cpu_quality",
+            Self::Hybrid => "hybrid",
+            Self::Skia => "skia",
+        }
+    }
+
+    /// Parse a backend from its store key.
+    pub fn from_name(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|b| b.as_str() == s)
+    }
+
+    /// Whether the backend is one of [`Self::GPU`].
+    pub fn is_gpu(self) -> bool {
+        Self::GPU.contains(&self)
+    }
+}
+
+/// Scene → backend → hash table, persisted as TOML.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct GoldenStore {
+    entries: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl GoldenStore {
+    /// Parse a store from TOML.
+    pub fn from_toml(s: &str) -> Result<Self, String> {
+        let table: toml::Table =
+            toml::from_str(s).map_err(|e| format!("Failed to parse golden store: {e}"))?;
+
+        let mut store = Self::default();
+        for (scene, value) in table {
+            let toml::Value::Table(backends) = value else {
+                return Err(format!("Expected a table of backends for scene {scene}"));
+            };
+            for (backend, hash) in backends {
+                let toml::Value::String(hash) = hash else {
+                    return Err(format!("Hash for {scene}/{backend} is not a string"));
+                };
+                store
+                    .entries
+                    .entry(scene.clone())
+                    .or_default()
+                    .insert(backend, hash);
+            }
+        }
+
+        Ok(store)
+    }
+
+    /// Serialize the store to TOML.
+    pub fn to_toml(&self) -> String {
+        toml::to_string_pretty(self).expect("golden store is always serializable")
+    }
+
+    /// Load a store from disk. A missing file yields an empty store.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => Self::from_toml(&content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("Failed to read {}: {e}", path.display())),
+        }
+    }
+
+    /// Write the store to disk.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        std::fs::write(path, self.to_toml())
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+    }
+
+    /// Stored hash for a scene and backend.
+    pub fn get(&self, scene: &str, backend: Backend) -> Option<&str> {
+        self.entries
+            .get(scene)
+            .and_then(|b| b.get(backend.as_str()))
+            .map(String::as_str)
+    }
+
+    /// Store the hash for a scene and backend.
+    pub fn set(&mut self, scene: &str, backend: Backend, hash: String) {
+        self.entries
+            .entry(scene.to_string())
+            .or_default()
+            .insert(backend.as_str().to_string(), hash);
+    }
+}
+
+/// Hash of a screenshot's dimensions and pixel data as 16 hex digits.
+pub fn screenshot_hash(shot: &ScreenshotResult) -> String {
+    let mut hasher = StableHasher::new();
+    hasher.write(&shot.width.to_le_bytes());
+    hasher.write(&shot.height.to_le_bytes());
+    hasher.write(&shot.rgba);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Names of all scenes that can be checked, archive scenes first.
+pub fn scene_names() -> Vec<String> {
+    get_scenes()
+        .iter()
+        .map(|s| s.name.clone())
+        .chain(get_vello_scenes().iter().map(|s| s.name.to_string()))
+        .collect()
+}
+
+/// Render `scene` with `backend`. `level` only affects the CPU backends.
+/// Returns `None` if the scene does not exist or the backend cannot render it.
+pub fn render(scene: &str, backend: Backend, level: Level) -> Option<ScreenshotResult> {
+    render_in(scene, backend, level, &mut PixmapPool::new())
+}
+
+/// [`render`] with the pixmap and output buffer taken from `pool`.
+pub fn render_in(
+    scene: &str,
+    backend: Backend,
+    level: Level,
+    pool: &mut PixmapPool,
+) -> Option<ScreenshotResult> {
+    let is_archive = get_scenes().iter().any(|s| s.name == scene);
+    match (backend, is_archive) {
+        (Backend::Cpu, true) => render_scene_cpu_in(scene, level, pool),
+        (Backend::Hybrid, true) => render_scene_hybrid_in(scene, pool),
+        (Backend::Skia, true) => render_scene_skia_in(scene, pool),
+        (Backend::CpuQuality, true) => None,
+        (Backend::Cpu, false) => {
+            render_vello_scene_cpu_with_mode_in(scene, level, RenderMode::default(), pool)
+        }
+        (Backend::CpuQuality, false) => {
+            render_vello_scene_cpu_with_mode_in(scene, level, RenderMode::OptimizeQuality, pool)
+        }
+        (Backend::Hybrid, false) => render_vello_scene_hybrid_in(scene, pool),
+        (Backend::Skia, false) => None,
+    }
+}
+
+/// SIMD levels to render for `backend`: every available level for the CPU
+/// backends, only the best one otherwise.
+fn levels_for(backend: Backend) -> Vec<Level> {
+    match backend {
+        Backend::Cpu | Backend::CpuQuality => available_levels(),
+        Backend::Hybrid | Backend::Skia => vec![Level::new()],
+    }
+}
+
+/// A single golden check failure.
+#[derive(Debug, Clone)]
+pub struct GoldenMismatch {
+    pub scene: String,
+    pub backend: Backend,
+    /// SIMD level suffix the mismatching render was produced with.
+    pub simd_variant: &'static str,
+    /// Stored hash, or `None` if the scene has no entry for this backend.
+    pub expected: Option<String>,
+    pub actual: String,
+    /// PNG of the mismatching render, if a dump directory was given.
+    pub dump: Option<PathBuf>,
+}
+
+/// Outcome of [`check`].
+#[derive(Debug, Clone, Default)]
+pub struct GoldenReport {
+    /// Number of (scene, backend, SIMD level) renders compared.
+    pub checked: usize,
+    pub mismatches: Vec<GoldenMismatch>,
+}
+
+impl GoldenReport {
+    pub fn is_ok(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    /// Human-readable summary listing each changed scene once.
+    pub fn summary(&self) -> String {
+        let mut out = String::new();
+        if self.is_ok() {
+            let _ = writeln!(out, "All {} golden renders match.", self.checked);
+            return out;
+        }
+
+        let _ = writeln!(
+            out,
+            "{} of {} golden renders differ:",
+            self.mismatches.len(),
+            self.checked
+        );
+        for m in &self.mismatches {
+            let expected = m.expected.as_deref().unwrap_or("<missing>");
+            let _ = write!(
+                out,
+                "  {} [{}, {}]: expected {expected}, got {}",
+                m.scene,
+                m.backend.as_str(),
+                m.simd_variant,
+                m.actual
+            );
+            if let Some(path) = &m.dump {
+                let _ = write!(out, " -> {}", path.display());
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Render every scene matching `scene_glob` on each of `backends` and compare
+/// against the store.
+///
+/// Mismatching renders are written as PNGs to `dump_dir` if given.
+pub fn check(
+    store: &GoldenStore,
+    backends: &[Backend],
+    scene_glob: &str,
+    dump_dir: Option<&Path>,
+) -> GoldenReport {
+    let mut report = GoldenReport::default();
+    let mut pool = PixmapPool::new();
+
+    for scene in scene_names().iter().filter(|s| glob_match(scene_glob, s)) {
+        for &backend in backends {
+            let expected = store.get(scene, backend);
+            for level in levels_for(backend) {
+                let Some(shot) = render_in(scene, backend, level, &mut pool) else {
+                    continue;
+                };
+                report.checked += 1;
+
+                let actual = screenshot_hash(&shot);
+                if expected == Some(actual.as_str()) {
+                    pool.recycle(shot);
+                    continue;
+                }
+
+                let simd_variant = level_suffix(level);
+                let dump = dump_dir.and_then(|dir| {
+                    let path = dir.join(format!("{scene}.{}.{simd_variant}.png", backend.as_str()));
+                    write_png(&path, &shot).ok().map(|()| path)
+                });
+                pool.recycle(shot);
+                report.mismatches.push(GoldenMismatch {
+                    scene: scene.clone(),
+                    backend,
+                    simd_variant,
+                    expected: expected.map(str::to_string),
+                    actual,
+                    dump,
+                });
+            }
+        }
+    }
+
+    report
+}
+
+/// Re-render every scene matching `scene_glob` on each of `backends` and
+/// store the resulting hashes. Returns the number of entries written.
+///
+/// Fails without modifying the store if SIMD levels of a CPU backend
+/// disagree with each other, since that is a bug rather than an expected
+/// change.
+pub fn update(
+    store: &mut GoldenStore,
+    backends: &[Backend],
+    scene_glob: &str,
+) -> Result<usize, String> {
+    let mut updates = Vec::new();
+    let mut pool = PixmapPool::new();
+
+    for scene in scene_names().iter().filter(|s| glob_match(scene_glob, s)) {
+        for &backend in backends {
+            let mut hash: Option<(String, &'static str)> = None;
+            for level in levels_for(backend) {
+                let Some(shot) = render_in(scene, backend, level, &mut pool) else {
+                    continue;
+                };
+                let actual = screenshot_hash(&shot);
+                pool.recycle(shot);
+                let simd_variant = level_suffix(level);
+                match &hash {
+                    None => hash = Some((actual, simd_variant)),
+                    Some((first, first_variant)) if *first != actual => {
+                        return Err(format!(
+                            "{scene} [{}]: {first_variant} and {simd_variant} renders differ \
+                             ({first} vs {actual})",
+                            backend.as_str()
+                        ));
+                    }
+                    Some(_) => {}
+                }
+            }
+            if let Some((hash, _)) = hash {
+                updates.push((scene.clone(), backend, hash));
+            }
+        }
+    }
+
+    let count = updates.len();
+    for (scene, backend, hash) in updates {
+        store.set(&scene, backend, hash);
+    }
+    Ok(count)
+}
+
+/// Render an archive scene `frames` times on a single renderer and return the
+/// output of the last frame. Returns `None` for non-archive scenes and for
+/// backends without an archive renderer.
+pub fn render_archive_frames(
+    scene: &str,
+    backend: Backend,
+    level: Level,
+    frames: usize,
+) -> Option<ScreenshotResult> {
+    let scenes = get_scenes();
+    let item = scenes.iter().find(|s| s.name == scene)?;
+    let frames = frames.max(1);
+
+    let (rgba, alpha_mode) = match backend {
+        Backend::Cpu => {
+            let mut renderer = CpuSceneRenderer::new(item, level);
+            for _ in 0..frames {
+                renderer.render_frame();
+            }
+            (renderer.into_rgba(), CpuSceneRenderer::ALPHA_MODE)
+        }
+        Backend::Hybrid => {
+            let mut renderer = HybridSceneRenderer::new(item);
+            // `into_rgba` renders the final frame itself.
+            for _ in 1..frames {
+                renderer.render_frame();
+            }
+            (renderer.into_rgba(), HybridSceneRenderer::ALPHA_MODE)
+        }
+        Backend::Skia => {
+            let mut renderer = SkiaSceneRenderer::new(item);
+            for _ in 0..frames {
+                renderer.render_frame();
+            }
+            (renderer.into_rgba(), SkiaSceneRenderer::ALPHA_MODE)
+        }
+        Backend::CpuQuality => return None,
+    };
+
+    Some(ScreenshotResult {
+        width: item.width.into(),
+        height: item.height.into(),
+        rgba,
+        alpha_mode,
+    })
+}
+
+/// Check that every archive scene matching `scene_glob` renders the same
+/// pixels on the first frame and after `frames` frames of the same renderer,
+/// i.e. that no state leaks from one frame into the next.
+///
+/// Mismatches report the first frame's hash as `expected`.
+pub fn check_repeatability(backends: &[Backend], scene_glob: &str, frames: usize) -> GoldenReport {
+    let mut report = GoldenReport::default();
+    let level = Level::new();
+
+    for scene in get_scenes()
+        .iter()
+        .filter(|s| glob_match(scene_glob, &s.name))
+    {
+        for &backend in backends {
+            let (Some(first), Some(last)) = (
+                render_archive_frames(&scene.name, backend, level, 1),
+                render_archive_frames(&scene.name, backend, level, frames),
+            ) else {
+                continue;
+            };
+            report.checked += 1;
+
+            let expected = screenshot_hash(&first);
+            let actual = screenshot_hash(&last);
+            if expected != actual {
+                report.mismatches.push(GoldenMismatch {
+                    scene: scene.name.clone(),
+                    backend,
+                    simd_variant: level_suffix(level),
+                    expected: Some(expected),
+                    actual,
+                    dump: None,
+                });
+            }
+        }
+    }
+
+    report
+}
+
+// ---------------------------------------------------------------------------
+// Backend parity
+// ---------------------------------------------------------------------------
+
+/// How far the hybrid render of a scene may deviate from its CPU render.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParityTolerance {
+    /// Largest allowed difference in any single channel.
+    pub max_channel_delta: u8,
+    /// Smallest allowed [`ssim`](crate::screenshot::ssim).
+    pub min_ssim: f64,
+}
+
+/// Parity tolerances by scene glob; the first matching entry applies.
+pub const PARITY_TOLERANCES: &[(&str, ParityTolerance)] = &[
+    // Flat colors through combined clip/blend/opacity layers: anything beyond
+    // rounding differences is a layer bug in one of the backends.
+    (
+        "combined_layers_*",
+        ParityTolerance {
+            max_channel_delta: 3,
+            min_ssim: 0.995,
+        },
+    ),
+    // Flat colors through 1000 sibling opacity layers over an opaque
+    // backdrop. The backends' layer stacks have diverged here before, so
+    // beyond rounding any difference is a bug.
+    (
+        "flat_opacity_layers_*",
+        ParityTolerance {
+            max_channel_delta: 3,
+            min_ssim: 0.995,
+        },
+    ),
+    // Opaque fills of self-overlapping paths: the backends have to agree on
+    // winding numbers, so only antialiasing may differ, and only slightly.
+    (
+        "fill_rule_*",
+        ParityTolerance {
+            max_channel_delta: 3,
+            min_ssim: 0.995,
+        },
+    ),
+    // Opaque strokes over an opaque backdrop. Both backends expand strokes
+    // the same way, so dashes, joins and caps must line up to within
+    // antialiasing.
+    (
+        "stroked_paths_*",
+        ParityTolerance {
+            max_channel_delta: 3,
+            min_ssim: 0.995,
+        },
+    ),
+    // Thousands of crossing edges per tile row accumulate a little more
+    // rounding, but a wrong winding number still flips whole spans.
+    (
+        "long_path_*",
+        ParityTolerance {
+            max_channel_delta: 6,
+            min_ssim: 0.99,
+        },
+    ),
+    // Random scenes include gradients, images, blurs and text, which the
+    // backends legitimately rasterize differently; only gross breakage such
+    // as missing or misplaced geometry is caught.
+    (
+        "generated_*",
+        ParityTolerance {
+            max_channel_delta: u8::MAX,
+            min_ssim: 0.97,
+        },
+    ),
+    (
+        "*",
+        ParityTolerance {
+            max_channel_delta: u8::MAX,
+            min_ssim: 0.98,
+        },
+    ),
+];
+
+/// Scenes of [`check_winding_parity`]: the fill-rule and long-path scenes,
+/// the registered generated scenes and a few more generated seeds.
+pub const WINDING_PARITY_SCENES: &[&str] = &[
+    "fill_rule_nonzero",
+    "fill_rule_evenodd",
+    "long_path_nonzero",
+    "long_path_evenodd",
+    "generated_1_100",
+    "generated_2_1000",
+    "generated_3_5000",
+    "generated_17_2000",
+    "generated_42_2000",
+    "generated_1234_10000",
+];
+
+/// Number of rows [`ParityResult::failure_details`] lists and dumps.
+pub const PARITY_WORST_ROWS: usize = 8;
+
+/// Tolerance for `scene` from [`PARITY_TOLERANCES`].
+pub fn parity_tolerance(scene: &str) -> ParityTolerance {
+    PARITY_TOLERANCES
+        .iter()
+        .find(|(glob, _)| glob_match(glob, scene))
+        .map(|(_, tolerance)| *tolerance)
+        .expect("PARITY_TOLERANCES ends with a catch-all entry")
+}
+
+/// Comparison of the CPU and hybrid renders of one scene.
+#[derive(Debug, Clone)]
+pub struct ParityResult {
+    pub scene: String,
+    pub tolerance: ParityTolerance,
+    /// `None` if the renders have different dimensions.
+    pub diff: Option<DiffResult>,
+    /// Largest channel delta per row, see
+    /// [`row_profile`](crate::screenshot::row_profile). Empty if the renders
+    /// have different dimensions.
+    pub row_profile: Vec<u8>,
+    /// PNG of the worst rows of both renders side by side, written for
+    /// failing scenes if a dump directory was given.
+    pub dump: Option<PathBuf>,
+}
+
+impl ParityResult {
+    pub fn is_ok(&self) -> bool {
+        self.diff.is_some_and(|d| {
+            d.max_channel_delta <= self.tolerance.max_channel_delta
+                && d.ssim >= self.tolerance.min_ssim
+        })
+    }
+
+    /// One line describing the comparison.
+    pub fn summary(&self) -> String {
+        let status = if self.is_ok() { "ok" } else { "FAIL" };
+        match &self.diff {
+            Some(d) => format!(
+                "{}: {status} (max delta {} <= {}, SSIM {:.4} >= {}, {} pixels differ)",
+                self.scene,
+                d.max_channel_delta,
+                self.tolerance.max_channel_delta,
+                d.ssim,
+                self.tolerance.min_ssim,
+                d.differing_pixels
+            ),
+            None => format!("{}: {status} (dimensions differ)", self.scene),
+        }
+    }
+
+    /// Indented lines naming the rows that differ most and where their
+    /// strips were written. Empty for passing scenes.
+    pub fn failure_details(&self) -> String {
+        let mut out = String::new();
+        if self.is_ok() {
+            return out;
+        }
+
+        let rows = worst_rows(&self.row_profile, PARITY_WORST_ROWS);
+        if !rows.is_empty() {
+            let rows: Vec<String> = rows
+                .iter()
+                .map(|&row| format!("{row} ({})", self.row_profile[row as usize]))
+                .collect();
+            let _ = writeln!(out, "  worst rows (max delta): {}", rows.join(", "));
+        }
+        if let Some(path) = &self.dump {
+            let _ = writeln!(out, "  cpu | hybrid | diff -> {}", path.display());
+        }
+        out
+    }
+}
+
+/// Render every scene matching `scene_glob` on the CPU (default render mode)
+/// and hybrid backends and compare the two against [`parity_tolerance`], see
+/// [`check_parity_scenes`].
+pub fn check_parity(scene_glob: &str, dump_dir: Option<&Path>) -> Vec<ParityResult> {
+    let scenes: Vec<String> = scene_names()
+        .into_iter()
+        .filter(|s| glob_match(scene_glob, s))
+        .collect();
+    check_parity_scenes(&scenes, dump_dir)
+}
+
+/// Compare the CPU and hybrid renders of the [`WINDING_PARITY_SCENES`], where
+/// the backends' strip generation has to agree on winding numbers and fill
+/// rules.
+pub fn check_winding_parity(dump_dir: Option<&Path>) -> Vec<ParityResult> {
+    let scenes: Vec<String> = WINDING_PARITY_SCENES
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    check_parity_scenes(&scenes, dump_dir)
+}
+
+/// Render each of `scenes` on the CPU (default render mode) and hybrid
+/// backends and compare the two against [`parity_tolerance`].
+///
+/// For failing scenes, the [`PARITY_WORST_ROWS`] worst rows of both renders
+/// are written side by side to `<dump_dir>/<scene>.parity_rows.png` if
+/// `dump_dir` is given. Scenes one of the backends can't render are skipped.
+/// Rendering on the hybrid backend requires a GPU adapter, see
+/// [`gpu_adapter_available`](crate::renderer::gpu_adapter_available).
+pub fn check_parity_scenes(scenes: &[String], dump_dir: Option<&Path>) -> Vec<ParityResult> {
+    let mut results = Vec::new();
+    let mut pool = PixmapPool::new();
+
+    for scene in scenes {
+        let (Some(cpu), Some(hybrid)) = (
+            render_in(scene, Backend::Cpu, Level::new(), &mut pool),
+            render_in(scene, Backend::Hybrid, Level::new(), &mut pool),
+        ) else {
+            continue;
+        };
+        let mut result = ParityResult {
+            scene: scene.clone(),
+            tolerance: parity_tolerance(scene),
+            diff: diff(&cpu, &hybrid, DEFAULT_BACKGROUND),
+            row_profile: row_profile(&cpu, &hybrid, DEFAULT_BACKGROUND).unwrap_or_default(),
+            dump: None,
+        };
+        if !result.is_ok() {
+            let rows = worst_rows(&result.row_profile, PARITY_WORST_ROWS);
+            result.dump = dump_dir.and_then(|dir| {
+                let strips = row_strips(&cpu, &hybrid, &rows, DEFAULT_BACKGROUND)?;
+                let path = dir.join(format!("{scene}.parity_rows.png"));
+                write_png(&path, &strips).ok().map(|()| path)
+            });
+        }
+        pool.recycle(cpu);
+        pool.recycle(hybrid);
+        results.push(result);
+    }
+
+    results
+}
+
+/// Pairs of an effect scene and its baseline, which draws the same content
+/// without the effect, see [`check_effect_sanity`]. The blend scene's
+/// baseline draws its shapes through `Mix::Normal` layers, the blur's without
+/// a filter layer, the aliased shapes' with antialiasing and the nearest
+/// neighbor images' with the default bilinear sampling.
+pub const EFFECT_SANITY_PAIRS: &[(&str, &str)] = &[
+    ("blend_multiply_layers_100", "blend_normal_layers_100"),
+    ("blur_radius_sweep_16", "blur_radius_sweep_0"),
+    ("tiny_shapes_20000_aliased", "tiny_shapes_20000_aa"),
+    (
+        "sampler_comparison_nearest_1000",
+        "sampler_comparison_bilinear_1000",
+    ),
+];
+
+/// Smallest fraction of pixels in which an effect scene has to differ from
+/// its baseline.
+pub const EFFECT_SANITY_MIN_DIFFERING: f64 = 0.01;
+
+/// Comparison of an effect scene with its baseline on one backend.
+#[derive(Debug, Clone)]
+pub struct EffectSanityResult {
+    pub scene: &'static str,
+    pub baseline: &'static str,
+    pub backend: Backend,
+    /// Pixels per render.
+    pub pixels: usize,
+    /// `None` if the renders have different dimensions.
+    pub diff: Option<DiffResult>,
+}
+
+impl EffectSanityResult {
+    pub fn is_ok(&self) -> bool {
+        self.diff.is_some_and(|d| {
+            d.differing_pixels as f64 >= EFFECT_SANITY_MIN_DIFFERING * self.pixels as f64
+        })
+    }
+
+    /// One line describing the comparison.
+    pub fn summary(&self) -> String {
+        let status = if self.is_ok() { "ok" } else { "FAIL" };
+        let backend = self.backend.as_str();
+        match &self.diff {
+            Some(d) => format!(
+                "{} vs {} [{backend}]: {status} ({} of {} pixels differ)",
+                self.scene, self.baseline, d.differing_pixels, self.pixels
+            ),
+            None => format!(
+                "{} vs {} [{backend}]: {status} (dimensions differ)",
+                self.scene, self.baseline
+            ),
+        }
+    }
+}
+
+/// Render each of [`EFFECT_SANITY_PAIRS`] on each of `backends` and compare
+/// the effect scene with its baseline. An effect that a backend silently
+/// ignores, such as a blend mode or a blur, renders like the baseline and
+/// fails the check.
+///
+/// Backends that can't render the scenes are skipped. Rendering on the
+/// hybrid backend requires a GPU adapter.
+pub fn check_effect_sanity(backends: &[Backend]) -> Vec<EffectSanityResult> {
+    let mut results = Vec::new();
+    let mut pool = PixmapPool::new();
+
+    for &(scene, baseline) in EFFECT_SANITY_PAIRS {
+        for &backend in backends {
+            let (Some(effect), Some(plain)) = (
+                render_in(scene, backend, Level::new(), &mut pool),
+                render_in(baseline, backend, Level::new(), &mut pool),
+            ) else {
+                continue;
+            };
+            results.push(EffectSanityResult {
+                scene,
+                baseline,
+                backend,
+                pixels: effect.width as usize * effect.height as usize,
+                diff: diff(&effect, &plain, DEFAULT_BACKGROUND),
+            });
+            pool.recycle(effect);
+            pool.recycle(plain);
+        }
+    }
+
+    results
+}
+
+/// Scenes drawn at scales where precision problems are likely, see
+/// [`check_coverage`].
+pub const COVERAGE_SCENES: &[&str] = &["zoomed_in_paths", "zoomed_out_paths"];
+
+/// Pixels covered by a scene of [`COVERAGE_SCENES`] on one backend.
+#[derive(Debug, Clone)]
+pub struct CoverageResult {
+    pub scene: &'static str,
+    pub backend: Backend,
+    /// Pixels per render.
+    pub pixels: usize,
+    /// Pixels with nonzero alpha.
+    pub covered: usize,
+}
+
+impl CoverageResult {
+    /// Both scenes leave part of the canvas uncovered and cover some of it.
+    pub fn is_ok(&self) -> bool {
+        self.covered > 0 && self.covered < self.pixels
+    }
+
+    /// One line describing the render.
+    pub fn summary(&self) -> String {
+        let status = if self.is_ok() { "ok" } else { "FAIL" };
+        format!(
+            "{} [{}]: {status} ({} of {} pixels covered)",
+            self.scene,
+            self.backend.as_str(),
+            self.covered,
+            self.pixels
+        )
+    }
+}
+
+/// Render each of [`COVERAGE_SCENES`] on each of `backends` and count the
+/// pixels it covers. A backend whose coordinates overflow or turn into NaNs
+/// tends to drop the paths, covering nothing, or to fill the whole canvas
+/// with garbage; both fail the check.
+///
+/// Backends that can't render the scenes are skipped. Rendering on the
+/// hybrid backend requires a GPU adapter.
+pub fn check_coverage(backends: &[Backend]) -> Vec<CoverageResult> {
+    let mut results = Vec::new();
+    let mut pool = PixmapPool::new();
+
+    for &scene in COVERAGE_SCENES {
+        for &backend in backends {
+            let Some(shot) = render_in(scene, backend, Level::new(), &mut pool) else {
+                continue;
+            };
+            results.push(CoverageResult {
+                scene,
+                backend,
+                pixels: shot.width as usize * shot.height as usize,
+                covered: shot.rgba.chunks_exact(4).filter(|p| p[3] != 0).count(),
+            });
+            pool.recycle(shot);
+        }
+    }
+
+    results
+}
+
+/// Pairs of a parameterized scene (see
+/// [`SceneParams`](crate::vello_scenes::SceneParams)) and the registered
+/// variant with the same count, see [`check_parameterized`].
+pub const PARAMETERIZED_PAIRS: &[(&str, &str)] = &[
+    ("tiled_flowers?count=100", "tiled_flowers_100"),
+    ("overlapping_images?count=1000", "overlapping_images_1000"),
+    (
+        "mixed_image_and_vector?count=100",
+        "mixed_image_and_vector_100",
+    ),
+];
+
+/// Comparison of a parameterized scene with its registered variant on one
+/// backend.
+#[derive(Debug, Clone)]
+pub struct ParameterizedResult {
+    pub scene: &'static str,
+    pub registered: &'static str,
+    pub backend: Backend,
+    /// `None` if the renders have different dimensions.
+    pub diff: Option<DiffResult>,
+}
+
+impl ParameterizedResult {
+    /// Both renders are identical.
+    pub fn is_ok(&self) -> bool {
+        self.diff.is_some_and(|d| d.differing_pixels == 0)
+    }
+
+    /// One line describing the comparison.
+    pub fn summary(&self) -> String {
+        let status = if self.is_ok() { "ok" } else { "FAIL" };
+        let backend = self.backend.as_str();
+        match &self.diff {
+            Some(d) => format!(
+                "{} vs {} [{backend}]: {status} ({} pixels differ)",
+                self.scene, self.registered, d.differing_pixels
+            ),
+            None => format!(
+                "{} vs {} [{backend}]: {status} (dimensions differ)",
+                self.scene, self.registered
+            ),
+        }
+    }
+}
+
+/// Render each of [`PARAMETERIZED_PAIRS`] on each of `backends` and compare
+/// the parameterized scene with its registered variant, which must render
+/// exactly the same.
+///
+/// Backends that can't render the scenes are skipped. Rendering on the
+/// hybrid backend requires a GPU adapter.
+pub fn check_parameterized(backends: &[Backend]) -> Vec<ParameterizedResult> {
+    let mut results = Vec::new();
+    let mut pool = PixmapPool::new();
+
+    for &(scene, registered) in PARAMETERIZED_PAIRS {
+        for &backend in backends {
+            let (Some(parameterized), Some(fixed)) = (
+                render_in(scene, backend, Level::new(), &mut pool),
+                render_in(registered, backend, Level::new(), &mut pool),
+            ) else {
+                continue;
+            };
+            results.push(ParameterizedResult {
+                scene,
+                registered,
+                backend,
+                diff: diff(&parameterized, &fixed, DEFAULT_BACKGROUND),
+            });
+            pool.recycle(parameterized);
+            pool.recycle(fixed);
+        }
+    }
+
+    results
+}
+
+// ---------------------------------------------------------------------------
+// Perceptual references for GPU backends
+// ---------------------------------------------------------------------------
+
+/// Default directory of the perceptual references. Each backend has a
+/// subdirectory holding `<scene>.png` and its sidecar `<scene>.toml` (see
+/// [`ReferenceMeta`]).
+pub const DEFAULT_REFERENCE_DIR: &str = "golden_references";
+
+/// Audit log of [`approve`], in the reference directory.
+pub const APPROVALS_FILE: &str = "APPROVALS.md";
+
+/// Smallest SSIM against the reference for scenes whose sidecar doesn't set
+/// one.
+pub const DEFAULT_MIN_SSIM: f64 = 0.99;
+
+fn default_min_ssim() -> f64 {
+    DEFAULT_MIN_SSIM
+}
+
+/// Sidecar of a perceptual reference, stored as TOML next to its PNG.
+///
+/// `min_ssim` may be edited by hand for scenes that vary more between
+/// drivers; [`approve`] keeps it when replacing the reference.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReferenceMeta {
+    /// Smallest allowed SSIM of a render against the reference.
+    #[serde(default = "default_min_ssim")]
+    pub min_ssim: f64,
+    /// When the reference was approved, in milliseconds since the Unix epoch.
+    #[serde(default)]
+    pub approved_ms: u64,
+    /// GPU adapter the reference was rendered on, e.g.
+    /// `"NVIDIA GeForce RTX 3080 (Vulkan)"`.
+    #[serde(default)]
+    pub adapter: String,
+    /// Why the reference was approved.
+    #[serde(default)]
+    pub note: String,
+}
+
+/// Paths of the reference PNG and its sidecar for `scene` on `backend`.
+fn reference_paths(dir: &Path, backend: Backend, scene: &str) -> (PathBuf, PathBuf) {
+    let dir = dir.join(backend.as_str());
+    (
+        dir.join(format!("{scene}.png")),
+        dir.join(format!("{scene}.toml")),
+    )
+}
+
+/// The reference image and sidecar of `scene` on `backend`, or `None` if no
+/// reference was approved. A reference without a sidecar gets the defaults.
+pub fn load_reference(
+    dir: &Path,
+    backend: Backend,
+    scene: &str,
+) -> Result<Option<(ScreenshotResult, ReferenceMeta)>, String> {
+    let (png, sidecar) = reference_paths(dir, backend, scene);
+    if !png.exists() {
+        return Ok(None);
+    }
+
+    let image = image::open(&png)
+        .map_err(|e| format!("Failed to read {}: {e}", png.display()))?
+        .to_rgba8();
+    let shot = ScreenshotResult {
+        width: image.width(),
+        height: image.height(),
+        rgba: image.into_raw(),
+        alpha_mode: AlphaMode::Straight,
+    };
+
+    let meta = match std::fs::read_to_string(&sidecar) {
+        Ok(content) => toml::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {e}", sidecar.display()))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            toml::from_str("").expect("all sidecar fields have defaults")
+        }
+        Err(e) => return Err(format!("Failed to read {}: {e}", sidecar.display())),
+    };
+    Ok(Some((shot, meta)))
+}
+
+/// How a render compared to its perceptual reference.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PerceptualOutcome {
+    /// SSIM of the render against the reference.
+    Compared { ssim: f64 },
+    /// No reference has been approved for the scene.
+    MissingReference,
+    /// The reference has different dimensions than the render.
+    DimensionsDiffer,
+}
+
+/// Comparison of one render against its perceptual reference.
+#[derive(Debug, Clone)]
+pub struct PerceptualResult {
+    pub scene: String,
+    pub backend: Backend,
+    /// Threshold from the reference's sidecar.
+    pub min_ssim: f64,
+    pub outcome: PerceptualOutcome,
+    /// PNG of the render, written for failing scenes if a dump directory was
+    /// given.
+    pub dump: Option<PathBuf>,
+}
+
+impl PerceptualResult {
+    pub fn is_ok(&self) -> bool {
+        matches!(self.outcome, PerceptualOutcome::Compared { ssim } if ssim >= self.min_ssim)
+    }
+}
+
+/// Outcome of [`check_perceptual`].
+#[derive(Debug, Clone, Default)]
+pub struct PerceptualReport {
+    pub results: Vec<PerceptualResult>,
+    /// Backends that weren't checked, with the reason.
+    pub skipped: Vec<(Backend, String)>,
+}
+
+impl PerceptualReport {
+    pub fn is_ok(&self) -> bool {
+        self.results.iter().all(PerceptualResult::is_ok)
+    }
+
+    /// Human-readable summary with the SSIM of every scene, passing or not,
+    /// so that slow drift shows up before it fails.
+    pub fn summary(&self) -> String {
+        let mut out = String::new();
+        for r in &self.results {
+            let status = if r.is_ok() { "ok" } else { "FAIL" };
+            let _ = write!(out, "{} [{}]: {status} ", r.scene, r.backend.as_str());
+            let _ = match r.outcome {
+                PerceptualOutcome::Compared { ssim } => {
+                    write!(out, "(SSIM {ssim:.4}, minimum {})", r.min_ssim)
+                }
+                PerceptualOutcome::MissingReference => write!(out, "(no reference)"),
+                PerceptualOutcome::DimensionsDiffer => write!(out, "(dimensions differ)"),
+            };
+            if let Some(path) = &r.dump {
+                let _ = write!(out, " -> {}", path.display());
+            }
+            out.push('\n');
+        }
+        for (backend, reason) in &self.skipped {
+            let _ = writeln!(out, "{}: skipped ({reason})", backend.as_str());
+        }
+
+        let failed = self.results.iter().filter(|r| !r.is_ok()).count();
+        let _ = writeln!(
+            out,
+            "{} of {} perceptual renders within tolerance.",
+            self.results.len() - failed,
+            self.results.len()
+        );
+        out
+    }
+}
+
+/// Render every scene matching `scene_glob` on each GPU backend among
+/// `backends` and compare against its reference in `dir`.
+///
+/// Without a GPU adapter, the backends are recorded as skipped. Failing
+/// renders are written as PNGs to `dump_dir` if given. Errors if a reference
+/// can't be read.
+pub fn check_perceptual(
+    dir: &Path,
+    backends: &[Backend],
+    scene_glob: &str,
+    dump_dir: Option<&Path>,
+) -> Result<PerceptualReport, String> {
+    let mut report = PerceptualReport::default();
+    let backends: Vec<Backend> = backends.iter().copied().filter(|b| b.is_gpu()).collect();
+    if backends.is_empty() {
+        return Ok(report);
+    }
+    if gpu_adapter_info().is_none() {
+        for backend in backends {
+            report.skipped.push((backend, "no GPU adapter".to_string()));
+        }
+        return Ok(report);
+    }
+
+    let mut pool = PixmapPool::new();
+    for scene in scene_names().iter().filter(|s| glob_match(scene_glob, s)) {
+        for &backend in &backends {
+            let Some(shot) = render_in(scene, backend, Level::new(), &mut pool) else {
+                continue;
+            };
+            let (outcome, min_ssim) = match load_reference(dir, backend, scene)? {
+                None => (PerceptualOutcome::MissingReference, DEFAULT_MIN_SSIM),
+                Some((reference, meta)) => {
+                    let outcome =
+                        if (reference.width, reference.height) == (shot.width, shot.height) {
+                            PerceptualOutcome::Compared {
+                                ssim: ssim(&reference, &shot, DEFAULT_BACKGROUND),
+                            }
+                        } else {
+                            PerceptualOutcome::DimensionsDiffer
+                        };
+                    (outcome, meta.min_ssim)
+                }
+            };
+
+            let mut result = PerceptualResult {
+                scene: scene.clone(),
+                backend,
+                min_ssim,
+                outcome,
+                dump: None,
+            };
+            if !result.is_ok() {
+                result.dump = dump_dir.and_then(|dir| {
+                    let path = dir.join(format!("{scene}.{}.png", backend.as_str()));
+                    write_png(&path, &shot).ok().map(|()| path)
+                });
+            }
+            pool.recycle(shot);
+            report.results.push(result);
+        }
+    }
+
+    Ok(report)
+}
+
+/// Render `scene` on the GPU backend `backend` and store it as the scene's
+/// reference in `dir`, with `note` as the reason.
+///
+/// The sidecar keeps the previous reference's `min_ssim`. A line with the
+/// date, the note, the adapter and the SSIM against the previous reference
+/// is appended to [`APPROVALS_FILE`]; that line is also returned.
+pub fn approve(dir: &Path, backend: Backend, scene: &str, note: &str) -> Result<String, String> {
+    if !backend.is_gpu() {
+        return Err(format!(
+            "{} output is deterministic and checked against hashes; use `golden update`",
+            backend.as_str()
+        ));
+    }
+    if note.trim().is_empty() {
+        return Err("Approving a reference requires a note saying why".to_string());
+    }
+    let adapter = gpu_adapter_info().ok_or("No GPU adapter available to render the reference")?;
+    let shot = render(scene, backend, Level::new())
+        .ok_or_else(|| format!("{} can't render scene {scene}", backend.as_str()))?;
+
+    let previous = load_reference(dir, backend, scene)?;
+    let change = match &previous {
+        None => "new reference".to_string(),
+        Some((old, _)) if (old.width, old.height) != (shot.width, shot.height) => format!(
+            "replaces a {}x{} reference with {}x{}",
+            old.width, old.height, shot.width, shot.height
+        ),
+        Some((old, _)) => format!(
+            "SSIM {:.4} against the previous reference",
+            ssim(old, &shot, DEFAULT_BACKGROUND)
+        ),
+    };
+
+    let approved_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64);
+    let meta = ReferenceMeta {
+        min_ssim: previous.map_or(DEFAULT_MIN_SSIM, |(_, meta)| meta.min_ssim),
+        approved_ms,
+        adapter: format!("{} ({:?})", adapter.name, adapter.backend),
+        note: note.trim().to_string(),
+    };
+
+    let (png, sidecar) = reference_paths(dir, backend, scene);
+    write_png(&png, &shot)?;
+    let toml = toml::to_string_pretty(&meta).expect("reference metadata is always serializable");
+    std::fs::write(&sidecar, toml)
+        .map_err(|e| format!("Failed to write {}: {e}", sidecar.display()))?;
+
+    let line = format!(
+        "- {} `{scene}` [{}]: {} ({change}; {})",
+        utc_date(approved_ms),
+        backend.as_str(),
+        meta.note,
+        meta.adapter
+    );
+    let log = dir.join(APPROVALS_FILE);
+    let mut content = match std::fs::read_to_string(&log) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            "# Approved perceptual references\n\n".to_string()
+        }
+        Err(e) => return Err(format!("Failed to read {}: {e}", log.display())),
+    };
+    content.push_str(&line);
+    content.push('\n');
+    std::fs::write(&log, content).map_err(|e| format!("Failed to write {}: {e}", log.display()))?;
+
+    Ok(line)
+}
+
+/// `YYYY-MM-DD` of `ms` milliseconds since the Unix epoch, in UTC.
+fn utc_date(ms: u64) -> String {
+    // Civil date from days since the epoch, after Howard Hinnant's
+    // `civil_from_days`.
+    let days = (ms / 86_400_000) as i64 + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Write a screenshot as a PNG with straight alpha, creating parent
+/// directories as needed.
+pub fn write_png(path: &Path, shot: &ScreenshotResult) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    image::save_buffer(
+        path,
+        &shot.straight_rgba(),
+        shot.width,
+        shot.height,
+        image::ExtendedColorType::Rgba8,
+    )
+    .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_repeatable(backends: &[Backend]) {
+        let report = check_repeatability(backends, "*", 50);
+        assert!(report.checked > 0);
+        let mismatches: Vec<_> = report
+            .mismatches
+            .iter()
+            .map(|m| format!("{} ({})", m.scene, m.backend.as_str()))
+            .collect();
+        assert!(
+            mismatches.is_empty(),
+            "frame 50 differs from frame 1: {}",
+            mismatches.join(", ")
+        );
+    }
+
+    #[test]
+    fn store_toml_round_trip() {
+        let mut store = GoldenStore::default();
+        store.set("demo_scene", Backend::Cpu, "5f3a9c0e12d4b7a8".to_string());
+        store.set("demo_scene", Backend::Skia, "0b71e2d9c4a35f60".to_string());
+        let parsed = GoldenStore::from_toml(&store.to_toml()).unwrap();
+        assert_eq!(parsed, store);
+        assert_eq!(
+            parsed.get("demo_scene", Backend::Skia),
+            Some("0b71e2d9c4a35f60")
+        );
+        assert_eq!(parsed.get("demo_scene", Backend::Hybrid), None);
+    }
+
+    #[test]
+    fn store_needs_a_table_per_scene() {
+        let error = GoldenStore::from_toml(r#"demo_scene = "5f3a9c0e12d4b7a8""#).unwrap_err();
+        assert!(error.contains("demo_scene"), "{error}");
+        let error = GoldenStore::from_toml("[demo_scene]\ncpu = 1").unwrap_err();
+        assert!(error.contains("demo_scene/cpu"), "{error}");
+    }
+
+    #[test]
+    fn cpu_frames_repeat() {
+        assert_repeatable(&[Backend::Cpu, Backend::Skia]);
+    }
+
+    #[test]
+    fn hybrid_frames_repeat() {
+        if gpu_adapter_info().is_none() {
+            eprintln!("Skipped: no GPU adapter");
+            return;
+        }
+        assert_repeatable(&[Backend::Hybrid]);
+    }
+}
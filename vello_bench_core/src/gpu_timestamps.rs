@@ -0,0 +1,142 @@
+//! GPU time of hybrid frames from wgpu timestamp queries.
+//!
+//! The wall-clock time of a `scene_hybrid` frame covers scene replay,
+//! encoding, the GPU work and the sync, so on its own it can't tell whether
+//! a regression is on the CPU or on the GPU. [`GpuTimestamps`] writes a
+//! timestamp before and after the GPU work of each frame and keeps the time
+//! between them.
+//!
+//! Timestamps need [`wgpu::Features::TIMESTAMP_QUERY`], which devices from
+//! [`gpu_device`](crate::renderer) request wherever the adapter offers it.
+//! They are written from empty compute passes around the frame's work, which
+//! works without the optional in-encoder timestamps, and read back through a
+//! 16-byte buffer mapped during the GPU sync the frame does anyway.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Bytes of the two resolved timestamps of a frame.
+const RESOLVED_SIZE: u64 = 2 * wgpu::QUERY_SIZE as u64;
+
+/// Timestamps around the GPU work of a frame, and the GPU times of the
+/// frames read back so far.
+///
+/// Per frame: [`Self::begin`] and [`Self::end`] around encoding the work,
+/// [`Self::start_readback`] after submitting it and [`Self::finish_readback`]
+/// once the GPU is done.
+pub struct GpuTimestamps {
+    query_set: wgpu::QuerySet,
+    resolve_buffer: wgpu::Buffer,
+    readback_buffer: wgpu::Buffer,
+    /// Nanoseconds per timestamp tick.
+    period_ns: f64,
+    /// Set by the map callback of the readback buffer.
+    mapped: Arc<AtomicBool>,
+    times_ns: Vec<f64>,
+}
+
+impl GpuTimestamps {
+    /// Timestamps for frames rendered on `device`, or `None` if it wasn't
+    /// created with [`wgpu::Features::TIMESTAMP_QUERY`].
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Self> {
+        if !device.features().contains(wgpu::Features::TIMESTAMP_QUERY) {
+            return None;
+        }
+
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("gpu_timestamps"),
+            ty: wgpu::QueryType::Timestamp,
+            count: 2,
+        });
+        let resolve_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("gpu_timestamps_resolve"),
+            size: RESOLVED_SIZE,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("gpu_timestamps_readback"),
+            size: RESOLVED_SIZE,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        Some(Self {
+            query_set,
+            resolve_buffer,
+            readback_buffer,
+            period_ns: f64::from(queue.get_timestamp_period()),
+            mapped: Arc::new(AtomicBool::new(false)),
+            times_ns: Vec::new(),
+        })
+    }
+
+    /// Write the start timestamp. Call before encoding the frame's work.
+    pub fn begin(&self, encoder: &mut wgpu::CommandEncoder) {
+        encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("gpu_timestamps_begin"),
+            timestamp_writes: Some(wgpu::ComputePassTimestampWrites {
+                query_set: &self.query_set,
+                beginning_of_pass_write_index: Some(0),
+                end_of_pass_write_index: None,
+            }),
+        });
+    }
+
+    /// Write the end timestamp and copy both to the readback buffer. Call
+    /// after encoding the frame's work.
+    pub fn end(&self, encoder: &mut wgpu::CommandEncoder) {
+        encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("gpu_timestamps_end"),
+            timestamp_writes: Some(wgpu::ComputePassTimestampWrites {
+                query_set: &self.query_set,
+                beginning_of_pass_write_index: None,
+                end_of_pass_write_index: Some(1),
+            }),
+        });
+        encoder.resolve_query_set(&self.query_set, 0..2, &self.resolve_buffer, 0);
+        encoder.copy_buffer_to_buffer(
+            &self.resolve_buffer,
+            0,
+            &self.readback_buffer,
+            0,
+            RESOLVED_SIZE,
+        );
+    }
+
+    /// Map the readback buffer. Call after submitting the frame and before
+    /// waiting for the GPU, whose device poll completes the mapping.
+    pub fn start_readback(&self) {
+        let mapped = self.mapped.clone();
+        self.readback_buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                mapped.store(result.is_ok(), Ordering::Release);
+            });
+    }
+
+    /// Record the GPU time of the frame and unmap the readback buffer. Call
+    /// once the GPU is done with the frame; a frame whose timestamps aren't
+    /// mapped by then is left out.
+    pub fn finish_readback(&mut self) {
+        if self.mapped.swap(false, Ordering::Acquire) {
+            let data = self.readback_buffer.slice(..).get_mapped_range();
+            let timestamp =
+                |i: usize| u64::from_le_bytes(data[i * 8..i * 8 + 8].try_into().unwrap());
+            let ticks = timestamp(1).saturating_sub(timestamp(0));
+            drop(data);
+            self.times_ns.push(ticks as f64 * self.period_ns);
+        }
+        self.readback_buffer.unmap();
+    }
+
+    /// GPU times of the frames read back so far in nanoseconds, oldest first.
+    pub fn times_ns(&self) -> &[f64] {
+        &self.times_ns
+    }
+
+    /// Forget the GPU times read back so far.
+    pub fn clear(&mut self) {
+        self.times_ns.clear();
+    }
+}
@@ -0,0 +1,130 @@
+//! Bounded waits for GPU work, so that a hung GPU fails the current benchmark
+//! instead of freezing the whole suite.
+//!
+//! GPU waits happen inside the timed closure, where errors cannot be returned.
+//! On native, a timeout therefore unwinds with a [`GpuTimeout`] panic payload,
+//! which [`try_run_benchmark_by_id`](crate::registry::try_run_benchmark_by_id)
+//! turns into a [`BenchmarkOutcome::Failed`](crate::result::BenchmarkOutcome).
+//! Hybrid benchmarks share a device (see
+//! [`keep_gpu_devices`](crate::renderer::keep_gpu_devices)), so the caller
+//! drops it after a failure, and the next benchmark starts on a fresh one.
+//!
+//! On WASM, `gl.finish()` cannot be interrupted; `vello_bench_wasm` checks the
+//! wall-clock time it took against [`gpu_timeout`] instead and reports the
+//! same error.
+
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Default limit for a single GPU wait.
+pub const DEFAULT_GPU_TIMEOUT: Duration = Duration::from_secs(10);
+
+static GPU_TIMEOUT_MS: AtomicU64 = AtomicU64::new(DEFAULT_GPU_TIMEOUT.as_millis() as u64);
+
+/// Set the limit for a single GPU wait. Applies to all subsequent waits.
+pub fn set_gpu_timeout(timeout: Duration) {
+    GPU_TIMEOUT_MS.store(timeout.as_millis() as u64, Ordering::Relaxed);
+}
+
+/// Current limit for a single GPU wait.
+pub fn gpu_timeout() -> Duration {
+    Duration::from_millis(GPU_TIMEOUT_MS.load(Ordering::Relaxed))
+}
+
+/// A GPU wait exceeded [`gpu_timeout`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpuTimeout {
+    /// The limit that was exceeded.
+    pub timeout: Duration,
+    /// Description of the adapter the work was submitted to, if known.
+    pub adapter: Option<String>,
+}
+
+impl fmt::Display for GpuTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = self.timeout.as_millis();
+        if ms % 1000 == 0 {
+            write!(f, "GPU timeout after {}s", ms / 1000)
+        } else {
+            write!(f, "GPU timeout after {ms}ms")
+        }
+    }
+}
+
+impl std::error::Error for GpuTimeout {}
+
+/// Granularity of the native wait loop.
+#[cfg(not(target_arch = "wasm32"))]
+const POLL_SLICE: Duration = Duration::from_millis(100);
+
+/// Wait for all work submitted to `device` to finish.
+///
+/// # Panics
+///
+/// Unwinds with a [`GpuTimeout`] payload if the work does not finish within
+/// [`gpu_timeout`]. Panics normally on any other poll error.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn wait_for_gpu(device: &wgpu::Device, adapter: &wgpu::AdapterInfo) {
+    let timeout = gpu_timeout();
+    let start = std::time::Instant::now();
+
+    loop {
+        let remaining = timeout.saturating_sub(start.elapsed());
+        let poll = device.poll(wgpu::PollType::Wait {
+            submission_index: None,
+            timeout: Some(remaining.min(POLL_SLICE)),
+        });
+
+        match poll {
+            Ok(_) => return,
+            Err(wgpu::PollError::Timeout) if start.elapsed() < timeout => {}
+            Err(wgpu::PollError::Timeout) => std::panic::panic_any(GpuTimeout {
+                timeout,
+                adapter: Some(adapter_description(adapter)),
+            }),
+            Err(e) => panic!("GPU poll failed: {e}"),
+        }
+    }
+}
+
+/// Wait until the GPU has finished the work submitted to `queue` so far,
+/// polling `device` without blocking. The native counterpart of polling a
+/// WebGL fence, for [`SyncMode::Fence`](crate::result::SyncMode::Fence).
+///
+/// # Panics
+///
+/// As [`wait_for_gpu`].
+#[cfg(not(target_arch = "wasm32"))]
+pub fn wait_for_submitted_work(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    adapter: &wgpu::AdapterInfo,
+) {
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicBool;
+
+    let done = Arc::new(AtomicBool::new(false));
+    let signal = done.clone();
+    queue.on_submitted_work_done(move || signal.store(true, Ordering::Release));
+
+    let timeout = gpu_timeout();
+    let start = std::time::Instant::now();
+    while !done.load(Ordering::Acquire) {
+        if start.elapsed() >= timeout {
+            std::panic::panic_any(GpuTimeout {
+                timeout,
+                adapter: Some(adapter_description(adapter)),
+            });
+        }
+        if let Err(e) = device.poll(wgpu::PollType::Poll) {
+            panic!("GPU poll failed: {e}");
+        }
+    }
+}
+
+/// Human-readable adapter description, e.g. `"NVIDIA GeForce RTX 3080 (Vulkan, 550.54)"`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn adapter_description(info: &wgpu::AdapterInfo) -> String {
+    format!("{} ({:?}, {})", info.name, info.backend, info.driver)
+}
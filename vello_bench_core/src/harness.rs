@@ -0,0 +1,61 @@
+//! Rendering benchmark scenes from other crates, without the timing of
+//! [`BenchRunner`](crate::BenchRunner), e.g. to run a scene under a profiler.
+//!
+//! Archive scenes render through the same structs the `scene_*` benchmarks
+//! time. Build one from a [`SceneItem`] with `new` or from the name of an
+//! embedded scene with `from_name`, then call `render_frame` once per frame:
+//! [`CpuSceneRenderer`], and natively [`HybridSceneRenderer`] (which waits for
+//! the GPU at the end of each frame) and [`SkiaSceneRenderer`].
+//!
+//! Programmatic scenes (the `vello_cpu` and `vello_hybrid` benchmarks) go
+//! through a [`SceneRegistration`] for any [`Renderer`]: `setup` once, then per
+//! frame `draw_frame` and `flush`, and finish the frame with
+//! `render_to_pixmap` on a [`RenderContext`] or
+//! [`HybridRenderer::render_and_sync`]. [`vello_scene`] looks one up.
+//!
+//! The WebGL counterpart of [`HybridSceneRenderer`] is in `vello_bench_wasm`:
+//! `deserialize_scene_webgl` and `render_hybrid_frame`.
+//!
+//! `examples/profile_one_scene.rs` renders a scene 100 times this way.
+//!
+//! # Stability
+//!
+//! This module is the supported integration surface. The rest of the crate
+//! changes along with the benchmarks; the items here keep their names and
+//! signatures, and a breaking change to any of them bumps
+//! [`HARNESS_VERSION`].
+
+pub use crate::benchmarks::scene_cpu::CpuSceneRenderer;
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::benchmarks::scene_hybrid::HybridSceneRenderer;
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::benchmarks::scene_skia::SkiaSceneRenderer;
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::renderer::HybridRenderer;
+pub use crate::renderer::Renderer;
+pub use crate::runner::FrameKind;
+pub use crate::scenes::{EmbeddedScenes, SceneItem, SceneSource, TestSceneSource};
+pub use crate::vello_scenes::{SceneRegistration, VelloSceneInfo};
+pub use fearless_simd::Level;
+pub use vello_cpu::{Pixmap, RenderContext, RenderMode};
+
+use crate::vello_scenes::find_scene;
+
+/// Version of the items in this module. Bumped on every breaking change to
+/// them, so that a consumer can check it was written against this version.
+pub const HARNESS_VERSION: u32 = 1;
+
+/// The scene called `name` in `source`.
+pub fn scene_item<'a>(source: &'a dyn SceneSource, name: &str) -> Result<&'a SceneItem, String> {
+    source.get(name).ok_or_else(|| {
+        format!(
+            "Unknown scene '{name}' (available: {})",
+            source.names().join(", ")
+        )
+    })
+}
+
+/// The programmatic scene called `name`, to draw with a renderer of type `R`.
+pub fn vello_scene<R: Renderer>(name: &str) -> Result<SceneRegistration<R>, String> {
+    find_scene(name).ok_or_else(|| format!("Unknown vello scene '{name}'"))
+}
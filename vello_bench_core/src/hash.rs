@@ -0,0 +1,55 @@
+//! 64-bit FNV-1a, for hashes that are stored or compared across runs: shard
+//! assignment, environment fingerprints and golden hashes. Used instead of
+//! `std::hash`, whose output may change between Rust versions and
+//! platforms.
+
+const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Incremental FNV-1a over bytes written one slice after another.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct StableHasher(u64);
+
+impl StableHasher {
+    pub(crate) const fn new() -> Self {
+        Self(OFFSET_BASIS)
+    }
+
+    pub(crate) fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 = (self.0 ^ u64::from(*b)).wrapping_mul(PRIME);
+        }
+    }
+
+    pub(crate) fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// FNV-1a of the bytes of `s`.
+pub(crate) fn stable_hash(s: &str) -> u64 {
+    let mut hasher = StableHasher::new();
+    hasher.write(s.as_bytes());
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stable_hash_is_fnv1a() {
+        assert_eq!(stable_hash(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(stable_hash("a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(stable_hash("foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn writes_chain() {
+        let mut hasher = StableHasher::new();
+        hasher.write(b"foo");
+        hasher.write(b"");
+        hasher.write(b"bar");
+        assert_eq!(hasher.finish(), stable_hash("foobar"));
+    }
+}
@@ -0,0 +1,369 @@
+//! Whether the environment is fit for a benchmark run, checked before trusting
+//! a big run on a new machine or browser.
+//!
+//! [`health_check`](crate::registry::health_check) runs the checks of
+//! [`HealthCheckKind`] and collects them in a [`HealthReport`], which a run
+//! records in [`ResultFile::health`](crate::ResultFile::health). Each check
+//! passes, warns or fails with a message, and any of them can be skipped,
+//! e.g. the GPU check on a machine meant for CPU benchmarks only.
+
+use std::fmt::Write as _;
+use std::hint::black_box;
+
+use serde::{Deserialize, Serialize};
+
+use crate::registry::{Platform, get_benchmark_availability, undeclared_categories};
+use crate::runner::{BenchRunner, median_frame_ns, time_ns, timer_resolution_ns};
+use crate::scenes::{get_scenes, scene_load_errors};
+use crate::simd::{available_levels, level_suffix};
+use crate::vello_scenes::scene_registration_problems;
+
+/// Timer steps up to this size pass; micro-benchmarks resolve fine.
+pub const FINE_TIMER_NS: f64 = 1_000.0;
+
+/// Timer steps above this size fail, e.g. a browser that reduces timer
+/// precision against fingerprinting.
+pub const COARSE_TIMER_NS: f64 = 1_000_000.0;
+
+/// Largest relative difference between the runner's mean and the directly
+/// timed median of the selftest workload that passes. Up to twice this warns.
+pub const SELFTEST_TOLERANCE: f64 = 0.25;
+
+/// Outcome of a single check, from best to worst. A skipped check ranks
+/// lowest, so it never decides the [`HealthReport::status`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Skip,
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl HealthStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Skip => "skip",
+            Self::Pass => "pass",
+            Self::Warn => "warn",
+            Self::Fail => "fail",
+        }
+    }
+}
+
+/// A check of [`health_check`](crate::registry::health_check).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthCheckKind {
+    /// Every embedded scene archive loads and every programmatic scene is
+    /// registered once.
+    Scenes,
+    /// Every benchmark category is in the support matrix, and how many
+    /// benchmarks can run on the platforms.
+    Availability,
+    /// A GPU adapter is present. Natively a wgpu adapter; in the browser the
+    /// WASM API reports whether WebGL was initialized instead.
+    Gpu,
+    /// The timer is fine enough for micro-benchmarks.
+    Timer,
+    /// The runner times a fixed workload like a plain timer does.
+    Selftest,
+    /// The detected SIMD levels include those the build was compiled for.
+    Simd,
+}
+
+impl HealthCheckKind {
+    /// All checks, in the order they run.
+    pub const ALL: [Self; 6] = [
+        Self::Scenes,
+        Self::Availability,
+        Self::Gpu,
+        Self::Timer,
+        Self::Selftest,
+        Self::Simd,
+    ];
+
+    /// Name of the check, as accepted by [`Self::from_name`].
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Scenes => "scenes",
+            Self::Availability => "availability",
+            Self::Gpu => "gpu",
+            Self::Timer => "timer",
+            Self::Selftest => "selftest",
+            Self::Simd => "simd",
+        }
+    }
+
+    pub fn from_name(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.as_str() == s)
+    }
+}
+
+/// Outcome of one check.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthCheck {
+    pub kind: HealthCheckKind,
+    pub status: HealthStatus,
+    /// What was found, or why the check was skipped.
+    pub message: String,
+}
+
+impl HealthCheck {
+    pub fn new(kind: HealthCheckKind, status: HealthStatus, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+/// Outcomes of all checks, in the order of [`HealthCheckKind::ALL`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub checks: Vec<HealthCheck>,
+}
+
+impl HealthReport {
+    /// The worst status of any check, or [`HealthStatus::Skip`] if all were
+    /// skipped.
+    pub fn status(&self) -> HealthStatus {
+        self.checks
+            .iter()
+            .map(|c| c.status)
+            .max()
+            .unwrap_or(HealthStatus::Skip)
+    }
+
+    /// Whether no check failed. Warnings are fine.
+    pub fn is_ok(&self) -> bool {
+        self.status() < HealthStatus::Fail
+    }
+
+    pub fn get(&self, kind: HealthCheckKind) -> Option<&HealthCheck> {
+        self.checks.iter().find(|c| c.kind == kind)
+    }
+
+    /// Replace the outcome of `check.kind`, or add it if it's missing.
+    pub fn set(&mut self, check: HealthCheck) {
+        match self.checks.iter_mut().find(|c| c.kind == check.kind) {
+            Some(existing) => *existing = check,
+            None => self.checks.push(check),
+        }
+    }
+
+    /// One line per check, e.g. `timer: pass (resolution 41 ns)`.
+    pub fn summary(&self) -> String {
+        let mut out = String::new();
+        for check in &self.checks {
+            let _ = writeln!(
+                out,
+                "{}: {} ({})",
+                check.kind.as_str(),
+                check.status.as_str(),
+                check.message
+            );
+        }
+        out
+    }
+}
+
+pub(crate) fn check_scenes() -> HealthCheck {
+    let mut problems: Vec<String> = scene_load_errors()
+        .iter()
+        .map(|e| format!("{}: {}", e.name, e.message))
+        .collect();
+    problems.extend(scene_registration_problems());
+
+    if problems.is_empty() {
+        HealthCheck::new(
+            HealthCheckKind::Scenes,
+            HealthStatus::Pass,
+            format!("{} scene archives loaded", get_scenes().len()),
+        )
+    } else {
+        HealthCheck::new(
+            HealthCheckKind::Scenes,
+            HealthStatus::Fail,
+            problems.join("; "),
+        )
+    }
+}
+
+pub(crate) fn check_availability(platforms: &[Platform]) -> HealthCheck {
+    let undeclared = undeclared_categories();
+    if !undeclared.is_empty() {
+        return HealthCheck::new(
+            HealthCheckKind::Availability,
+            HealthStatus::Fail,
+            format!(
+                "Categories missing from the support matrix: {}",
+                undeclared.join(", ")
+            ),
+        );
+    }
+
+    let list = get_benchmark_availability(platforms);
+    let available = list
+        .iter()
+        .filter(|b| b.unavailable_reason.is_none())
+        .count();
+    HealthCheck::new(
+        HealthCheckKind::Availability,
+        HealthStatus::Pass,
+        format!("{available} of {} benchmarks run here", list.len()),
+    )
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn check_gpu() -> HealthCheck {
+    match crate::renderer::gpu_adapter_info() {
+        Some(info) => HealthCheck::new(
+            HealthCheckKind::Gpu,
+            HealthStatus::Pass,
+            format!("{} ({:?})", info.name, info.backend),
+        ),
+        None => HealthCheck::new(
+            HealthCheckKind::Gpu,
+            HealthStatus::Warn,
+            "No GPU adapter; GPU benchmarks will fail",
+        ),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn check_gpu() -> HealthCheck {
+    HealthCheck::new(
+        HealthCheckKind::Gpu,
+        HealthStatus::Skip,
+        "The WebGL context is owned by the page",
+    )
+}
+
+pub(crate) fn check_timer() -> HealthCheck {
+    let resolution_ns = timer_resolution_ns();
+    let (status, note) = if resolution_ns <= FINE_TIMER_NS {
+        (HealthStatus::Pass, "")
+    } else if resolution_ns <= COARSE_TIMER_NS {
+        (
+            HealthStatus::Warn,
+            "; micro-benchmarks need many iterations per measurement",
+        )
+    } else {
+        (HealthStatus::Fail, "; too coarse for meaningful timings")
+    };
+    HealthCheck::new(
+        HealthCheckKind::Timer,
+        status,
+        format!("resolution {resolution_ns:.0} ns{note}"),
+    )
+}
+
+/// A fixed amount of work, `rounds` dependent multiply-adds.
+fn selftest_workload(rounds: u64) -> u64 {
+    let mut x = 1_u64;
+    for i in 0..rounds {
+        x = black_box(x.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(i));
+    }
+    x
+}
+
+pub(crate) fn check_selftest() -> HealthCheck {
+    // Long enough per call that the timer's steps don't matter, within
+    // reason for a coarse timer.
+    let target_ns = (100.0 * timer_resolution_ns()).clamp(100_000.0, 20_000_000.0);
+    let mut rounds = 1_000_u64;
+    while rounds < 1 << 32 && time_ns(|| selftest_workload(rounds)).1 < target_ns {
+        rounds *= 2;
+    }
+
+    let direct_ns = median_frame_ns(20, || {
+        black_box(selftest_workload(rounds));
+    });
+    let runner_ns = BenchRunner::new(3, 20)
+        .run("health/selftest", "health", "selftest", "", || {
+            black_box(selftest_workload(rounds));
+        })
+        .statistics
+        .mean_ns;
+
+    let difference = (runner_ns / direct_ns - 1.0).abs();
+    let status = if difference <= SELFTEST_TOLERANCE {
+        HealthStatus::Pass
+    } else if difference <= 2.0 * SELFTEST_TOLERANCE {
+        HealthStatus::Warn
+    } else {
+        HealthStatus::Fail
+    };
+    HealthCheck::new(
+        HealthCheckKind::Selftest,
+        status,
+        format!(
+            "runner {runner_ns:.0} ns, timed directly {direct_ns:.0} ns ({:.1}% apart)",
+            difference * 100.0
+        ),
+    )
+}
+
+/// Suffixes of the SIMD levels the build requires at compile time.
+fn compiled_levels() -> Vec<&'static str> {
+    let mut levels = Vec::new();
+    if cfg!(all(target_arch = "wasm32", target_feature = "simd128")) {
+        levels.push("wasm_simd128");
+    }
+    if cfg!(all(target_arch = "aarch64", target_feature = "neon")) {
+        levels.push("neon");
+    }
+    if cfg!(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "avx2",
+        target_feature = "fma"
+    )) {
+        levels.push("avx2");
+    }
+    if cfg!(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse4.2"
+    )) {
+        levels.push("sse42");
+    }
+    levels
+}
+
+pub(crate) fn check_simd() -> HealthCheck {
+    let detected: Vec<&str> = available_levels().into_iter().map(level_suffix).collect();
+    let missing: Vec<&str> = compiled_levels()
+        .into_iter()
+        .filter(|level| !detected.contains(level))
+        .collect();
+    if !missing.is_empty() {
+        return HealthCheck::new(
+            HealthCheckKind::Simd,
+            HealthStatus::Fail,
+            format!(
+                "Compiled for {} but detected only {}",
+                missing.join(", "),
+                detected.join(", ")
+            ),
+        );
+    }
+
+    let best = level_suffix(fearless_simd::Level::new());
+    if detected.first() != Some(&best) {
+        return HealthCheck::new(
+            HealthCheckKind::Simd,
+            HealthStatus::Warn,
+            format!(
+                "fearless_simd picks {best}, but the best detected level is {}",
+                detected.first().unwrap_or(&"none")
+            ),
+        );
+    }
+
+    HealthCheck::new(
+        HealthCheckKind::Simd,
+        HealthStatus::Pass,
+        format!("detected {}", detected.join(", ")),
+    )
+}
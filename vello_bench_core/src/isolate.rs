@@ -0,0 +1,194 @@
+//! Running benchmarks in child processes, so that a crash in one benchmark
+//! (a panicking scene, a GPU driver abort) fails only that benchmark instead
+//! of taking down the whole suite.
+//!
+//! The parent re-runs its own executable once per benchmark with
+//! [`RUN_SINGLE_FLAG`] and the benchmark ID. The child runs the benchmark
+//! in-process and prints its [`BenchmarkOutcome`] as a single JSON line on
+//! stdout (see [`single_outcome_json`]), which the parent parses back, so
+//! isolated results are identical to in-process ones. A child that exits with
+//! a non-zero status, prints no outcome or runs past the timeout is reported
+//! as [`BenchmarkOutcome::Failed`] with the tail of its stderr attached.
+//!
+//! The child inherits the parent's environment and working directory, so
+//! adapter selection through `WGPU_*` variables applies to it as well. Scenes
+//! and SVG data are embedded in the binary and need no propagation.
+
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use fearless_simd::Level;
+
+use crate::registry::try_run_benchmark_by_id;
+use crate::result::{BenchmarkOutcome, WarmupInput};
+use crate::runner::BenchRunner;
+use crate::simd::level_suffix;
+
+/// Hidden command-line flag that makes the benchmark binary run a single
+/// benchmark and print its outcome as JSON.
+pub const RUN_SINGLE_FLAG: &str = "--run-single-json";
+
+/// Default limit for the whole run of one isolated benchmark.
+pub const DEFAULT_PROCESS_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Lines of stderr kept in the error of a failed child.
+const STDERR_TAIL_LINES: usize = 40;
+
+/// How often a running child is polled for exit.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Configuration passed to every child process.
+#[derive(Debug, Clone)]
+pub struct IsolationConfig {
+    /// Executable to run; normally [`std::env::current_exe`].
+    pub exe: PathBuf,
+    pub warmup: u64,
+    pub iterations: u64,
+    pub level: Level,
+    /// What micro-benchmarks warm up on, see [`BenchRunner::warmup_input`].
+    pub warmup_input: WarmupInput,
+    /// Limit for a single GPU wait inside the child, see
+    /// [`crate::gpu_watchdog`].
+    pub gpu_timeout: Duration,
+    /// Limit for the whole child process. A child still running after this
+    /// is killed.
+    pub process_timeout: Duration,
+}
+
+impl IsolationConfig {
+    /// Arguments that make the executable run benchmark `id` in
+    /// single-benchmark mode with this configuration.
+    pub fn child_args(&self, id: &str) -> Vec<String> {
+        vec![
+            RUN_SINGLE_FLAG.to_string(),
+            id.to_string(),
+            "--warmup".to_string(),
+            self.warmup.to_string(),
+            "--iterations".to_string(),
+            self.iterations.to_string(),
+            "--simd".to_string(),
+            level_suffix(self.level).to_string(),
+            "--gpu-timeout".to_string(),
+            self.gpu_timeout.as_secs_f64().to_string(),
+            "--warmup-input".to_string(),
+            self.warmup_input.name().to_string(),
+        ]
+    }
+}
+
+/// Run benchmark `id` in-process and serialize its outcome as one line of
+/// JSON, for the child side of [`run_isolated`].
+///
+/// Returns an error for unknown IDs.
+pub fn single_outcome_json(runner: &BenchRunner, id: &str, level: Level) -> Result<String, String> {
+    let outcome = try_run_benchmark_by_id(runner, id, level)
+        .ok_or_else(|| format!("Unknown benchmark: {id}"))?;
+    serde_json::to_string(&outcome).map_err(|e| format!("Failed to serialize outcome: {e}"))
+}
+
+/// Run benchmark `id` in a child process and return its outcome.
+///
+/// Never panics because of the child: spawn errors, crashes, timeouts and
+/// unparsable output all become a [`BenchmarkOutcome::Failed`].
+pub fn run_isolated(config: &IsolationConfig, id: &str) -> BenchmarkOutcome {
+    match run_child(config, id) {
+        Ok(outcome) => outcome,
+        Err(error) => BenchmarkOutcome::Failed {
+            id: id.to_string(),
+            error,
+            adapter: None,
+        },
+    }
+}
+
+fn run_child(config: &IsolationConfig, id: &str) -> Result<BenchmarkOutcome, String> {
+    let mut child = Command::new(&config.exe)
+        .args(config.child_args(id))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {e}", config.exe.display()))?;
+
+    // Drain both pipes on their own threads so a chatty child can't block on
+    // a full pipe while we wait for it to exit.
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if started.elapsed() >= config.process_timeout => {
+                // The child may exit between the check and the kill; either
+                // way it is gone afterwards.
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            }
+            Ok(None) => thread::sleep(POLL_INTERVAL),
+            Err(e) => return Err(format!("Failed to wait for benchmark process: {e}")),
+        }
+    };
+
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    let Some(status) = status else {
+        return Err(with_stderr(
+            format!(
+                "Benchmark process timed out after {}s",
+                config.process_timeout.as_secs_f64()
+            ),
+            &stderr,
+        ));
+    };
+    if !status.success() {
+        return Err(with_stderr(describe_exit(status), &stderr));
+    }
+
+    // The outcome is the last line; anything a benchmark printed before it is
+    // ignored.
+    let line = stdout
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .ok_or_else(|| with_stderr("Benchmark process printed no result".into(), &stderr))?;
+    serde_json::from_str(line).map_err(|e| {
+        with_stderr(
+            format!("Failed to parse benchmark process output: {e}"),
+            &stderr,
+        )
+    })
+}
+
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    })
+}
+
+fn describe_exit(status: ExitStatus) -> String {
+    match status.code() {
+        Some(code) => format!("Benchmark process exited with status {code}"),
+        // No code means the process was killed by a signal on Unix.
+        None => format!("Benchmark process was terminated ({status})"),
+    }
+}
+
+/// Append the last [`STDERR_TAIL_LINES`] lines of `stderr` to `error`.
+fn with_stderr(error: String, stderr: &str) -> String {
+    let lines: Vec<&str> = stderr.trim().lines().collect();
+    if lines.is_empty() {
+        return error;
+    }
+    let tail = &lines[lines.len().saturating_sub(STDERR_TAIL_LINES)..];
+    format!("{error}\nstderr:\n{}", tail.join("\n"))
+}
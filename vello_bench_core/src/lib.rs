@@ -1,18 +1,53 @@
 pub mod benchmarks;
 pub mod data;
+pub mod filter;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod fingerprint;
+pub mod frame_budget;
+pub mod frame_pacing;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod golden;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod gpu_timestamps;
+pub mod gpu_watchdog;
+pub mod harness;
+mod hash;
+pub mod health;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod isolate;
+pub mod marks;
+pub mod memory;
+pub mod readback;
+pub mod reference;
 pub mod registry;
 pub mod renderer;
 pub mod result;
 pub mod runner;
+pub mod scene_cache;
 pub mod scenes;
 pub mod screenshot;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod serve;
 pub mod simd;
 pub mod vello_scenes;
 
 pub use fearless_simd::Level;
-pub use registry::{BenchmarkInfo, get_benchmark_list, run_benchmark_by_id};
-pub use result::{BenchmarkResult, Statistics};
-pub use runner::BenchRunner;
+pub use filter::Filter;
+pub use registry::{
+    BenchmarkAvailability, BenchmarkInfo, Platform, RunObserver, filter_benchmarks,
+    generated_benchmarks, get_benchmark_availability, get_benchmark_list,
+    get_benchmark_list_filtered, list_by_tag, run_all, run_all_observed, run_all_parallel,
+    run_benchmark_all_levels, run_benchmark_by_id, run_benchmarks_matching, run_category,
+    support_matrix, try_run_benchmark_by_id,
+};
+pub use result::{
+    BenchmarkOutcome, BenchmarkResult, EnvironmentInfo, MeasurementMode, ResultFile, RunConfig,
+    Statistics, SyncMode, Throughput, WarmupInput,
+};
+pub use runner::{
+    BenchRunner, CancellationToken, FrameKind, MicroBench, OutlierPolicy, ProgressEvent,
+    ProgressPhase, StabilityTarget, TimeBudget,
+};
 pub use simd::{
     SimdLevelInfo, available_level_infos, available_levels, level_from_suffix, level_suffix,
 };
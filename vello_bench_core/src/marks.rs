@@ -0,0 +1,205 @@
+//! Names of the `performance.mark()` entries the runner emits on WASM, so
+//! that DevTools traces can be matched to a run.
+//!
+//! Every run gets a nonce that is part of all its mark and measure names, so
+//! two runs of the same benchmark in one page session never share a name:
+//!
+//! ```text
+//! bench:{id}#{nonce}:warmup:start    bench:{id}#{nonce}:warmup:end
+//! bench:{id}#{nonce}:measure:start   bench:{id}#{nonce}:measure:end
+//! bench:{id}#{nonce}:iter:{i}        bench:{id}#{nonce}:iter:{i}:end
+//! ```
+//!
+//! The measure spans between them are named `{id}#{nonce} warm-up`,
+//! `{id}#{nonce} measurement` and `{id}#{nonce} iter {i}`.
+//!
+//! The names of the last run's marks can be read back with
+//! [`last_run_marks`]; with [`set_record_marks`] on, every result carries them
+//! as well (see [`BenchmarkResult::marks`](crate::BenchmarkResult::marks)).
+//! Native runs emit no marks but name and record them the same way.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+/// Most per-iteration mark names recorded for one run. Phase marks are
+/// always recorded; per-iteration marks beyond this are only counted.
+pub const MAX_RECORDED_ITERATION_MARKS: usize = 200;
+
+static RECORD_MARKS: AtomicBool = AtomicBool::new(false);
+
+static NEXT_NONCE: AtomicU32 = AtomicU32::new(1);
+
+thread_local! {
+    static LAST_RUN_MARKS: RefCell<RecordedMarks> = RefCell::new(RecordedMarks::default());
+}
+
+/// Attach the mark names of each run to its result. Applies to all
+/// subsequent runs.
+pub fn set_record_marks(record: bool) {
+    RECORD_MARKS.store(record, Ordering::Relaxed);
+}
+
+/// Whether results carry their mark names, see [`set_record_marks`].
+pub fn record_marks() -> bool {
+    RECORD_MARKS.load(Ordering::Relaxed)
+}
+
+/// A nonce not handed out before in this process (or WASM instance).
+pub fn next_nonce() -> u32 {
+    NEXT_NONCE.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Mark names of the last run on this thread, empty if there was none.
+pub fn last_run_marks() -> RecordedMarks {
+    LAST_RUN_MARKS.with(|marks| marks.borrow().clone())
+}
+
+pub(crate) fn set_last_run_marks(marks: RecordedMarks) {
+    LAST_RUN_MARKS.with(|last| *last.borrow_mut() = marks);
+}
+
+/// Mark and measure names of one run of a benchmark.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunMarks {
+    /// `{id}#{nonce}`, shared by all names.
+    run: String,
+}
+
+impl RunMarks {
+    /// Names for a new run of benchmark `id`, with a fresh nonce.
+    pub fn new(id: &str) -> Self {
+        Self::with_nonce(id, next_nonce())
+    }
+
+    pub fn with_nonce(id: &str, nonce: u32) -> Self {
+        Self {
+            run: format!("{id}#{nonce}"),
+        }
+    }
+
+    pub fn warmup_start(&self) -> String {
+        format!("bench:{}:warmup:start", self.run)
+    }
+
+    pub fn warmup_end(&self) -> String {
+        format!("bench:{}:warmup:end", self.run)
+    }
+
+    pub fn measure_start(&self) -> String {
+        format!("bench:{}:measure:start", self.run)
+    }
+
+    pub fn measure_end(&self) -> String {
+        format!("bench:{}:measure:end", self.run)
+    }
+
+    /// Start of measured iteration `i`.
+    pub fn iter(&self, i: usize) -> String {
+        format!("bench:{}:iter:{i}", self.run)
+    }
+
+    pub fn iter_end(&self, i: usize) -> String {
+        format!("bench:{}:iter:{i}:end", self.run)
+    }
+
+    /// Name of the measure span covering the warm-up.
+    pub fn warmup_span(&self) -> String {
+        format!("{} warm-up", self.run)
+    }
+
+    /// Name of the measure span covering the measurement.
+    pub fn measure_span(&self) -> String {
+        format!("{} measurement", self.run)
+    }
+
+    /// Name of the measure span covering iteration `i`.
+    pub fn iter_span(&self, i: usize) -> String {
+        format!("{} iter {i}", self.run)
+    }
+}
+
+/// Names of the marks emitted by one run, in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedMarks {
+    pub names: Vec<String>,
+    /// Per-iteration marks left out of `names` beyond
+    /// [`MAX_RECORDED_ITERATION_MARKS`].
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub omitted: usize,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_follow_the_documented_formats() {
+        let marks = RunMarks::with_nonce("vello_cpu/tiger", 7);
+        assert_eq!(marks.warmup_start(), "bench:vello_cpu/tiger#7:warmup:start");
+        assert_eq!(marks.warmup_end(), "bench:vello_cpu/tiger#7:warmup:end");
+        assert_eq!(
+            marks.measure_start(),
+            "bench:vello_cpu/tiger#7:measure:start"
+        );
+        assert_eq!(marks.measure_end(), "bench:vello_cpu/tiger#7:measure:end");
+        assert_eq!(marks.iter(3), "bench:vello_cpu/tiger#7:iter:3");
+        assert_eq!(marks.iter_end(3), "bench:vello_cpu/tiger#7:iter:3:end");
+        assert_eq!(marks.warmup_span(), "vello_cpu/tiger#7 warm-up");
+        assert_eq!(marks.measure_span(), "vello_cpu/tiger#7 measurement");
+        assert_eq!(marks.iter_span(3), "vello_cpu/tiger#7 iter 3");
+    }
+
+    #[test]
+    fn runs_never_share_a_nonce() {
+        let nonces: Vec<u32> = std::thread::scope(|scope| {
+            let threads: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| (0..100).map(|_| next_nonce()).collect::<Vec<_>>()))
+                .collect();
+            threads
+                .into_iter()
+                .flat_map(|thread| thread.join().unwrap())
+                .collect()
+        });
+        let unique: std::collections::HashSet<u32> = nonces.iter().copied().collect();
+        assert_eq!(unique.len(), nonces.len());
+
+        let id = "vello_cpu/tiger";
+        assert_ne!(RunMarks::new(id), RunMarks::new(id));
+        assert_ne!(
+            RunMarks::new(id).measure_start(),
+            RunMarks::new(id).measure_start()
+        );
+    }
+
+    #[test]
+    fn last_run_marks_are_per_thread() {
+        let marks = RecordedMarks {
+            names: vec![RunMarks::with_nonce("a/b", 1).warmup_start()],
+            omitted: 3,
+        };
+        set_last_run_marks(marks.clone());
+        assert_eq!(last_run_marks(), marks);
+        let other = std::thread::spawn(last_run_marks).join().unwrap();
+        assert_eq!(other, RecordedMarks::default());
+    }
+
+    #[test]
+    fn omitted_count_is_left_out_when_zero() {
+        let marks = RecordedMarks {
+            names: vec!["bench:a/b#1:warmup:start".to_string()],
+            omitted: 0,
+        };
+        assert_eq!(
+            serde_json::to_string(&marks).unwrap(),
+            r#"{"names":["bench:a/b#1:warmup:start"]}"#
+        );
+        let parsed: RecordedMarks = serde_json::from_str(r#"{"names":[]}"#).unwrap();
+        assert_eq!(parsed, RecordedMarks::default());
+    }
+}
@@ -0,0 +1,134 @@
+//! Memory usage sampled between benchmarks, to make leaks visible during
+//! long sessions.
+//!
+//! Samples are only ever taken between benchmarks, never inside a timed
+//! region. On native, [`sample_memory`] reads the resident set size of the
+//! process. On WASM, `vello_bench_wasm` samples the linear memory size and,
+//! where the browser exposes it, `performance.memory.usedJSHeapSize`.
+
+use serde::{Deserialize, Serialize};
+
+/// Default growth across a session above which [`detect_growth`] reports a
+/// possible leak.
+pub const DEFAULT_GROWTH_THRESHOLD_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Memory usage at one point of a session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemorySample {
+    /// ID of the benchmark that finished just before the sample was taken.
+    pub after: String,
+    /// When the sample was taken (milliseconds since epoch).
+    pub timestamp_ms: u64,
+    /// Size of the WASM linear memory in bytes (WASM only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wasm_memory_bytes: Option<u64>,
+    /// `performance.memory.usedJSHeapSize` (Chromium only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub js_heap_bytes: Option<u64>,
+    /// Resident set size of the process (native only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rss_bytes: Option<u64>,
+}
+
+impl MemorySample {
+    /// The figure used to track growth: the resident set size on native,
+    /// linear memory plus JS heap on WASM. `None` if nothing was measured.
+    pub fn total_bytes(&self) -> Option<u64> {
+        if self.rss_bytes.is_some() {
+            return self.rss_bytes;
+        }
+        match (self.wasm_memory_bytes, self.js_heap_bytes) {
+            (None, None) => None,
+            (wasm, js) => Some(wasm.unwrap_or(0) + js.unwrap_or(0)),
+        }
+    }
+}
+
+/// Memory growth reported by [`detect_growth`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryGrowth {
+    /// [`MemorySample::total_bytes`] of the first sample.
+    pub first_bytes: u64,
+    /// [`MemorySample::total_bytes`] of the last sample.
+    pub last_bytes: u64,
+    /// Number of samples considered.
+    pub samples: usize,
+}
+
+impl MemoryGrowth {
+    /// Growth from the first to the last sample in bytes.
+    pub fn growth_bytes(&self) -> u64 {
+        self.last_bytes - self.first_bytes
+    }
+}
+
+/// Flag a timeline whose memory usage never decreases and grows by more than
+/// `threshold_bytes` overall.
+///
+/// Samples without any measurement are skipped. Returns `None` if fewer than
+/// two samples remain, usage dropped at some point, or the total growth is
+/// within the threshold.
+pub fn detect_growth(timeline: &[MemorySample], threshold_bytes: u64) -> Option<MemoryGrowth> {
+    let totals: Vec<u64> = timeline
+        .iter()
+        .filter_map(MemorySample::total_bytes)
+        .collect();
+    let (&first_bytes, &last_bytes) = (totals.first()?, totals.last()?);
+
+    let monotonic = totals.windows(2).all(|w| w[0] <= w[1]);
+    if totals.len() < 2 || !monotonic || last_bytes - first_bytes <= threshold_bytes {
+        return None;
+    }
+
+    Some(MemoryGrowth {
+        first_bytes,
+        last_bytes,
+        samples: totals.len(),
+    })
+}
+
+/// Sample the memory usage of this process after benchmark `after` finished.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn sample_memory(after: &str) -> MemorySample {
+    MemorySample {
+        after: after.to_string(),
+        timestamp_ms: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0),
+        wasm_memory_bytes: None,
+        js_heap_bytes: None,
+        rss_bytes: resident_set_size(),
+    }
+}
+
+/// Resident set size of this process in bytes, if it can be determined on
+/// this OS.
+#[cfg(target_os = "linux")]
+pub fn resident_set_size() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+/// Resident set size of this process in bytes, if it can be determined on
+/// this OS.
+#[cfg(target_os = "macos")]
+pub fn resident_set_size() -> Option<u64> {
+    // Querying the kernel directly would need `unsafe`; `ps` is cheap enough
+    // between benchmarks.
+    let output = std::process::Command::new("ps")
+        .args(["-o", "rss=", "-p", &std::process::id().to_string()])
+        .output()
+        .ok()?;
+    let kib: u64 = String::from_utf8(output.stdout).ok()?.trim().parse().ok()?;
+    Some(kib * 1024)
+}
+
+/// Resident set size of this process in bytes, if it can be determined on
+/// this OS.
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_arch = "wasm32")))]
+pub fn resident_set_size() -> Option<u64> {
+    None
+}
@@ -0,0 +1,187 @@
+//! Size checks and row copies for reading rendered pixels back.
+//!
+//! Readback paths copy from a source whose size the caller has to get right: a
+//! pixmap created separately from the scene, or a GPU buffer whose rows are
+//! padded to [`COPY_ROW_ALIGNMENT`]. These helpers check the sizes up front and
+//! return a descriptive error, rather than panicking halfway through a copy or
+//! silently leaving part of the destination unwritten.
+
+use vello_common::pixmap::Pixmap;
+
+/// Alignment of `bytes_per_row` in GPU texture-to-buffer copies
+/// (`wgpu::COPY_BYTES_PER_ROW_ALIGNMENT`).
+pub const COPY_ROW_ALIGNMENT: u32 = 256;
+
+/// Bytes per row of a readback buffer for an RGBA8 texture `width` pixels
+/// wide. Rows that are already a multiple of [`COPY_ROW_ALIGNMENT`] bytes long
+/// get no padding.
+pub fn padded_bytes_per_row(width: u32) -> u32 {
+    (width * 4).next_multiple_of(COPY_ROW_ALIGNMENT)
+}
+
+/// Check that `pixmap` has the size of a `width`×`height` scene.
+pub fn check_pixmap_size(pixmap: &Pixmap, width: u16, height: u16) -> Result<(), String> {
+    if (pixmap.width(), pixmap.height()) == (width, height) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Pixmap is {}×{} but the scene is {width}×{height}; create the pixmap \
+             with the scene's size",
+            pixmap.width(),
+            pixmap.height(),
+        ))
+    }
+}
+
+/// Copy `height` rows of `width` RGBA8 pixels from `src`, whose rows start
+/// `src_stride` bytes apart, into the tightly packed `dst`.
+///
+/// `src` may end right after the last pixel of its last row. `dst` must be
+/// exactly `width * height * 4` bytes long.
+pub fn copy_unpadded_rows(
+    src: &[u8],
+    src_stride: usize,
+    width: u32,
+    height: u32,
+    dst: &mut [u8],
+) -> Result<(), String> {
+    let row_bytes = width as usize * 4;
+    let rows = height as usize;
+    if src_stride < row_bytes {
+        return Err(format!(
+            "Source rows are {src_stride} bytes apart, less than the {row_bytes} bytes of \
+             a {width} pixel row"
+        ));
+    }
+    if dst.len() != row_bytes * rows {
+        return Err(format!(
+            "Destination holds {} bytes, but {width}×{height} pixels need {}",
+            dst.len(),
+            row_bytes * rows,
+        ));
+    }
+    let src_needed = match rows {
+        0 => 0,
+        _ => (rows - 1) * src_stride + row_bytes,
+    };
+    if src.len() < src_needed {
+        return Err(format!(
+            "Source holds {} bytes, but {height} rows {src_stride} bytes apart need \
+             {src_needed}",
+            src.len(),
+        ));
+    }
+    if row_bytes == 0 {
+        return Ok(());
+    }
+
+    for (row, dst_row) in dst.chunks_exact_mut(row_bytes).enumerate() {
+        let start = row * src_stride;
+        dst_row.copy_from_slice(&src[start..start + row_bytes]);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A readback buffer of `height` rows `stride` bytes apart, ending after
+    /// the last pixel, where pixel `(x, y)` is `[x, y, 0xaa, 0xff]` and the
+    /// padding is `0xee`.
+    fn padded(width: u32, height: u32, stride: usize) -> Vec<u8> {
+        let mut src = vec![0xee; (height as usize - 1) * stride + width as usize * 4];
+        for y in 0..height {
+            for x in 0..width {
+                let start = y as usize * stride + x as usize * 4;
+                src[start..start + 4].copy_from_slice(&[x as u8, y as u8, 0xaa, 0xff]);
+            }
+        }
+        src
+    }
+
+    fn packed(width: u32, height: u32) -> Vec<u8> {
+        padded(width, height, width as usize * 4)
+    }
+
+    #[test]
+    fn pads_rows_to_the_copy_alignment() {
+        assert_eq!(padded_bytes_per_row(1), 256);
+        assert_eq!(padded_bytes_per_row(63), 256);
+        assert_eq!(padded_bytes_per_row(65), 512);
+        assert_eq!(padded_bytes_per_row(100), 512);
+        // Aligned rows get no padding.
+        assert_eq!(padded_bytes_per_row(64), 256);
+        assert_eq!(padded_bytes_per_row(1024), 4096);
+    }
+
+    #[test]
+    fn copies_a_one_pixel_wide_scene() {
+        let stride = padded_bytes_per_row(1) as usize;
+        let src = padded(1, 3, stride);
+        let mut dst = vec![0; 12];
+        copy_unpadded_rows(&src, stride, 1, 3, &mut dst).unwrap();
+        assert_eq!(dst, packed(1, 3));
+    }
+
+    #[test]
+    fn copies_aligned_rows_as_they_are() {
+        let stride = padded_bytes_per_row(64) as usize;
+        assert_eq!(stride, 64 * 4);
+        let src = packed(64, 4);
+        let mut dst = vec![0; src.len()];
+        copy_unpadded_rows(&src, stride, 64, 4, &mut dst).unwrap();
+        assert_eq!(dst, src);
+    }
+
+    #[test]
+    fn copies_padded_rows() {
+        let stride = padded_bytes_per_row(100) as usize;
+        let src = padded(100, 5, stride);
+        let mut dst = vec![0; 100 * 5 * 4];
+        copy_unpadded_rows(&src, stride, 100, 5, &mut dst).unwrap();
+        assert_eq!(dst, packed(100, 5));
+
+        // An empty image needs no source.
+        copy_unpadded_rows(&[], stride, 100, 0, &mut []).unwrap();
+    }
+
+    #[test]
+    fn rejects_mismatched_sizes() {
+        let stride = padded_bytes_per_row(10) as usize;
+        let src = padded(10, 4, stride);
+        let mut dst = vec![0; 10 * 4 * 4];
+
+        assert_eq!(
+            copy_unpadded_rows(&src, 36, 10, 4, &mut dst),
+            Err(
+                "Source rows are 36 bytes apart, less than the 40 bytes of a 10 pixel row"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            copy_unpadded_rows(&src, stride, 10, 4, &mut dst[4..]),
+            Err("Destination holds 156 bytes, but 10×4 pixels need 160".to_string())
+        );
+        assert_eq!(
+            copy_unpadded_rows(&src[..src.len() - 1], stride, 10, 4, &mut dst),
+            Err("Source holds 807 bytes, but 4 rows 256 bytes apart need 808".to_string())
+        );
+        // Nothing was written.
+        assert!(dst.iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn checks_pixmap_sizes() {
+        let pixmap = Pixmap::new(64, 32);
+        assert_eq!(check_pixmap_size(&pixmap, 64, 32), Ok(()));
+        assert_eq!(
+            check_pixmap_size(&pixmap, 32, 64),
+            Err(
+                "Pixmap is 64×32 but the scene is 32×64; create the pixmap with the scene's size"
+                    .to_string()
+            )
+        );
+        assert!(check_pixmap_size(&Pixmap::new(1, 1), 1, 2).is_err());
+    }
+}
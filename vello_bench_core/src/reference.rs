@@ -0,0 +1,194 @@
+//! Reference results of known machines, embedded in the crate, so that a run
+//! can be compared against them without a result file of one's own.
+//!
+//! Each `references/<name>.json` file in the crate, e.g.
+//! `desktop_reference.json`, is a [`ResultFile`] of a small, quick selection
+//! of benchmarks on one machine. They are (re)generated from a run with
+//! `vello_bench update-reference`, which checks them with [`check_reference`]
+//! and writes them to the source tree for check-in. `vello_bench list` fails if an embedded file doesn't parse under
+//! the current schema, see [`reference_problems`].
+
+use crate::result::ResultFile;
+use crate::result::compare::{Comparison, compare};
+
+// Include the auto-generated list of reference files from the build script.
+include!(concat!(env!("OUT_DIR"), "/reference_list.rs"));
+
+/// Directory of the reference files in the source tree, written by
+/// `vello_bench update-reference`.
+pub const REFERENCE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/references");
+
+/// Names of the embedded references, e.g. `"desktop_reference"`.
+pub fn reference_names() -> Vec<&'static str> {
+    REFERENCE_FILES.iter().map(|(name, _)| *name).collect()
+}
+
+/// The embedded reference `name`, or `None` if there is none or it doesn't
+/// parse (which [`reference_problems`] reports).
+pub fn get_reference_results(name: &str) -> Option<ResultFile> {
+    let (_, json) = REFERENCE_FILES.iter().find(|(n, _)| *n == name)?;
+    ResultFile::parse(json.as_bytes()).ok()
+}
+
+/// Compare `file` against the embedded reference `name`, see [`compare`].
+pub fn compare_to_reference(file: &ResultFile, name: &str) -> Result<Comparison, String> {
+    let reference = get_reference_results(name).ok_or_else(|| {
+        let names = reference_names();
+        if names.is_empty() {
+            format!("Unknown reference {name:?}; no references are embedded")
+        } else {
+            format!(
+                "Unknown reference {name:?}; available: {}",
+                names.join(", ")
+            )
+        }
+    })?;
+    Ok(compare(file, &reference))
+}
+
+/// Check that `file` is usable as a reference: a complete run under the
+/// current schema, without estimates, whose environment says which machine
+/// it is.
+pub fn check_reference(file: &ResultFile) -> Result<(), String> {
+    if file.schema_version != ResultFile::SCHEMA_VERSION {
+        return Err(format!(
+            "Schema version is {}, but the current one is {}",
+            file.schema_version,
+            ResultFile::SCHEMA_VERSION
+        ));
+    }
+    if file.partial {
+        return Err("The run was stopped early".to_string());
+    }
+    if file.results.is_empty() {
+        return Err("There are no results".to_string());
+    }
+    if let Some(result) = file.results.iter().find(|r| r.is_estimate()) {
+        return Err(format!("{} is a single-shot estimate", result.id));
+    }
+
+    let environment = &file.environment;
+    let mut missing = Vec::new();
+    if environment.label.as_deref().is_none_or(str::is_empty) {
+        missing.push("label");
+    }
+    if environment.target_arch.is_empty() {
+        missing.push("target_arch");
+    }
+    if environment.simd_level.is_empty() {
+        missing.push("simd_level");
+    }
+    if environment.core_count.is_none() {
+        missing.push("core_count");
+    }
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Environment lacks {}", missing.join(", ")))
+    }
+}
+
+/// Embedded references that don't parse or fail [`check_reference`], as
+/// human-readable messages.
+pub fn reference_problems() -> Vec<String> {
+    REFERENCE_FILES
+        .iter()
+        .filter_map(|(name, json)| {
+            ResultFile::parse(json.as_bytes())
+                .and_then(|file| check_reference(&file))
+                .err()
+                .map(|e| format!("{name}: {e}"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::result::{EnvironmentInfo, MeasurementMode};
+
+    fn reference_file() -> ResultFile {
+        ResultFile::from_json(
+            r#"{
+                "schema_version": 1,
+                "started_ms": 1700000000000,
+                "finished_ms": 1700000060000,
+                "environment": {
+                    "label": "test machine",
+                    "target_arch": "x86_64",
+                    "simd_level": "avx2",
+                    "core_count": 8
+                },
+                "results": [{
+                    "id": "vello_cpu/filled_rects",
+                    "category": "vello_cpu",
+                    "name": "filled_rects",
+                    "simd_variant": "avx2",
+                    "statistics": {
+                        "mean_ns": 1000.0,
+                        "median_ns": 1000.0,
+                        "p95_ns": 1000.0,
+                        "min_ns": 1000.0,
+                        "max_ns": 1000.0,
+                        "std_dev_ns": 0.0,
+                        "iterations": 10
+                    },
+                    "timestamp_ms": 1700000030000
+                }]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn embedded_references_parse_under_the_current_schema() {
+        assert_eq!(reference_problems(), Vec::<String>::new());
+        for name in reference_names() {
+            let file = get_reference_results(name).unwrap();
+            assert_eq!(file.schema_version, ResultFile::SCHEMA_VERSION, "{name}");
+            let comparison = compare_to_reference(&file, name).unwrap();
+            assert_eq!(comparison.geometric_mean_ratio, Some(1.0), "{name}");
+        }
+    }
+
+    #[test]
+    fn unknown_reference_is_an_error() {
+        let error = compare_to_reference(&reference_file(), "no_such_reference").unwrap_err();
+        assert!(error.starts_with("Unknown reference"), "{error}");
+    }
+
+    #[test]
+    fn complete_runs_with_an_environment_are_references() {
+        assert_eq!(check_reference(&reference_file()), Ok(()));
+    }
+
+    #[test]
+    fn rejects_unusable_references() {
+        let mut partial = reference_file();
+        partial.partial = true;
+        assert!(check_reference(&partial).is_err());
+
+        let mut empty = reference_file();
+        empty.results.clear();
+        assert!(check_reference(&empty).is_err());
+
+        let mut estimate = reference_file();
+        estimate.results[0].measurement_mode = MeasurementMode::SingleShot;
+        let error = check_reference(&estimate).unwrap_err();
+        assert!(error.contains("vello_cpu/filled_rects"), "{error}");
+
+        let mut old = reference_file();
+        old.schema_version = ResultFile::SCHEMA_VERSION - 1;
+        assert!(check_reference(&old).is_err());
+
+        let mut anonymous = reference_file();
+        anonymous.environment = EnvironmentInfo {
+            target_arch: "x86_64".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            check_reference(&anonymous),
+            Err("Environment lacks label, simd_level, core_count".to_string())
+        );
+    }
+}
@@ -1,8 +1,14 @@
 use crate::benchmarks::*;
-use crate::result::BenchmarkResult;
+use crate::filter::{Filter, glob_match};
+use crate::hash::stable_hash;
+use crate::health::{self, HealthCheck, HealthCheckKind, HealthReport, HealthStatus};
+use crate::result::{BenchmarkOutcome, BenchmarkResult};
 use crate::runner::BenchRunner;
+use crate::scenes::{EmbeddedScenes, SceneSource};
+use crate::vello_scenes::GeneratedParams;
 use fearless_simd::Level;
 use serde::{Deserialize, Serialize};
+use vello_cpu::RenderMode;
 
 /// Benchmark info for the frontend/CLI.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,36 +16,201 @@ pub struct BenchmarkInfo {
     pub id: String,
     pub category: String,
     pub name: String,
+    /// What the timed closure covers, and what it leaves out, e.g. "scene
+    /// draw + flush + rasterize to pixmap; excludes image uploads". Set by
+    /// each category's `list()`; must not be empty (see
+    /// [`undocumented_benchmarks`]).
+    #[serde(skip_deserializing)]
+    pub measures: &'static str,
+    /// Tags for selecting suites, e.g. `["cpu", "scene", "images", "small"]`:
+    /// those of the category (see [`category_tags`]) followed by, for
+    /// programmatic scenes, those of the scene (see
+    /// [`scene_tags`](crate::vello_scenes::scene_tags)). Must not be empty
+    /// (see [`untagged_benchmarks`]).
+    #[serde(skip_deserializing)]
+    pub tags: Vec<&'static str>,
+    /// Rough cost of running the benchmark, derived from the tags, for
+    /// estimating how long a suite takes (see [`estimate_suite_duration`]).
+    #[serde(default)]
+    pub estimated_cost: CostClass,
 }
 
 impl BenchmarkInfo {
-    /// Build a list from static benchmark names.
-    pub fn from_names(category: &str, names: &[&str]) -> Vec<Self> {
+    /// Info for benchmark `name` of `category`, tagged with the
+    /// [`category_tags`].
+    pub fn new(category: &str, name: String, measures: &'static str) -> Self {
+        let tags = category_tags(category).to_vec();
+        Self {
+            id: format!("{category}/{name}"),
+            category: category.into(),
+            name,
+            measures,
+            estimated_cost: CostClass::from_tags(&tags),
+            tags,
+        }
+    }
+
+    /// Build a list from static benchmark names that all measure the same
+    /// span.
+    pub fn from_names(category: &str, names: &[&str], measures: &'static str) -> Vec<Self> {
         names
             .iter()
-            .map(|name| Self {
-                id: format!("{category}/{name}"),
-                category: category.into(),
-                name: (*name).into(),
-            })
+            .map(|name| Self::new(category, (*name).into(), measures))
             .collect()
     }
 
     /// Build a list from data items (one benchmark per SVG).
-    pub fn from_data_items(category: &str) -> Vec<Self> {
+    pub fn from_data_items(category: &str, measures: &'static str) -> Vec<Self> {
         crate::data::get_data_items()
             .iter()
-            .map(|item| Self {
-                id: format!("{category}/{}", item.name),
-                category: category.into(),
-                name: item.name.clone(),
-            })
+            .map(|item| Self::new(category, item.name.clone(), measures))
             .collect()
     }
+
+    /// Add the tags of the programmatic scene `scene` to those of the
+    /// category, updating the cost estimate.
+    pub(crate) fn with_scene_tags(mut self, scene: &str) -> Self {
+        for tag in crate::vello_scenes::scene_tags(scene) {
+            if !self.tags.contains(&tag) {
+                self.tags.push(tag);
+            }
+        }
+        self.estimated_cost = CostClass::from_tags(&self.tags);
+        self
+    }
+}
+
+/// Rough cost of a benchmark: how long one iteration takes, including what
+/// setup adds per run. Ordered from cheapest to most expensive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CostClass {
+    /// A single pipeline stage on small inputs.
+    Micro,
+    /// A scene of at most 100 elements.
+    Fast,
+    /// A scene without a notable element count, the default.
+    #[default]
+    Medium,
+    /// A scene of at least 10 000 elements.
+    Slow,
+    /// A scene of at least 10 000 elements on Vello Hybrid.
+    VerySlow,
+}
+
+impl CostClass {
+    /// The class of a benchmark with `tags` (see [`BenchmarkInfo::tags`]).
+    /// `micro` benchmarks are [`Micro`](Self::Micro); otherwise `small` and
+    /// `large` scenes are [`Fast`](Self::Fast) and [`Slow`](Self::Slow),
+    /// and `cold` ones and `large` ones on `hybrid` a class slower.
+    pub fn from_tags(tags: &[&str]) -> Self {
+        let has = |tag| tags.contains(&tag);
+        if has("micro") {
+            return Self::Micro;
+        }
+        let class = if has("small") {
+            Self::Fast
+        } else if has("large") {
+            Self::Slow
+        } else {
+            Self::Medium
+        };
+        if has("cold") || (has("large") && has("hybrid")) {
+            class.slower()
+        } else {
+            class
+        }
+    }
+
+    /// The next more expensive class; [`VerySlow`](Self::VerySlow) stays.
+    pub fn slower(self) -> Self {
+        match self {
+            Self::Micro => Self::Fast,
+            Self::Fast => Self::Medium,
+            Self::Medium => Self::Slow,
+            Self::Slow | Self::VerySlow => Self::VerySlow,
+        }
+    }
+
+    /// Typical time of one iteration in milliseconds, the order of magnitude
+    /// of the class.
+    pub fn typical_iteration_ms(self) -> u64 {
+        match self {
+            Self::Micro => 0,
+            Self::Fast => 1,
+            Self::Medium => 10,
+            Self::Slow => 100,
+            Self::VerySlow => 1000,
+        }
+    }
+}
+
+/// Estimated wall time in milliseconds of running the benchmarks `ids` with
+/// [`BenchRunner::with_time_budget`]`(calibration_ms, measurement_ms)`.
+///
+/// Each benchmark takes its calibration and measurement time, or at least one
+/// iteration for each if an iteration is slower, plus one more iteration for
+/// setup. Iteration times are the [`CostClass::typical_iteration_ms`], so the
+/// estimate is only good to within a factor of a few; IDs that aren't listed
+/// count as [`CostClass::Medium`].
+pub fn estimate_suite_duration(ids: &[String], calibration_ms: u64, measurement_ms: u64) -> u64 {
+    let list = get_benchmark_list();
+    ids.iter()
+        .map(|id| {
+            let iteration_ms = list
+                .iter()
+                .find(|info| info.id == *id)
+                .map_or(CostClass::Medium, |info| info.estimated_cost)
+                .typical_iteration_ms();
+            calibration_ms.max(iteration_ms) + measurement_ms.max(iteration_ms) + iteration_ms
+        })
+        .sum()
+}
+
+/// Tags every benchmark of `category` has: the renderer (`cpu`, `hybrid` or
+/// `skia`), `gpu` if it needs a GPU, and `micro` for single pipeline stages or
+/// `scene` for whole scenes, plus `threads` and `cold` for the categories
+/// varying those. Empty for unknown categories.
+pub fn category_tags(category: &str) -> &'static [&'static str] {
+    match category {
+        "fine/fill" | "fine/gradient" | "fine/image" | "fine/pack" | "fine/strip" | "tile"
+        | "flatten" | "strokes" | "render_strips" | "set_paint_cpu" | "transform_cpu" => {
+            &["cpu", "micro"]
+        }
+        "set_paint_hybrid" | "transform_hybrid" => &["hybrid", "micro"],
+        "hybrid_internals" => &["hybrid", "gpu", "micro"],
+        "scene_cpu" | "vello_cpu" | "vello_recording" => &["cpu", "scene"],
+        "scene_cpu_mt" | "cpu_threads" => &["cpu", "scene", "threads"],
+        "scene_cpu_cold" => &["cpu", "scene", "cold"],
+        "scene_hybrid" | "vello_hybrid" | "vello_recording_hybrid" => &["hybrid", "gpu", "scene"],
+        "scene_hybrid_cold" => &["hybrid", "gpu", "scene", "cold"],
+        "scene_skia" => &["skia", "scene"],
+        _ => &[],
+    }
 }
 
 /// Get the complete list of all available benchmarks.
+///
+/// Debug builds [`validate`] the registry on the first call and panic if it
+/// has errors.
 pub fn get_benchmark_list() -> Vec<BenchmarkInfo> {
+    #[cfg(debug_assertions)]
+    {
+        static VALIDATED: std::sync::Once = std::sync::Once::new();
+        VALIDATED.call_once(|| {
+            if let Err(errors) = validate() {
+                let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+                panic!("invalid benchmark registry: {}", errors.join("; "));
+            }
+        });
+    }
+    get_benchmark_list_from(&EmbeddedScenes)
+}
+
+/// [`get_benchmark_list`] with the archive scene benchmarks (`scene_cpu`,
+/// `scene_hybrid`, `scene_skia`, `scene_cpu_mt` and the `_cold` variants)
+/// taken from `scenes` instead of the embedded scenes.
+pub fn get_benchmark_list_from(scenes: &dyn SceneSource) -> Vec<BenchmarkInfo> {
     let mut benchmarks = Vec::new();
 
     benchmarks.extend(fine::fill::list());
@@ -51,19 +222,621 @@ pub fn get_benchmark_list() -> Vec<BenchmarkInfo> {
     benchmarks.extend(flatten::list());
     benchmarks.extend(strokes::list());
     benchmarks.extend(render_strips::list());
-    benchmarks.extend(scene_cpu::list());
-    benchmarks.extend(scene_hybrid::list());
-    benchmarks.extend(scene_skia::list());
+    benchmarks.extend(set_paint::list());
+    benchmarks.extend(transform::list());
+    benchmarks.extend(scene_cpu::list_from(scenes));
+    benchmarks.extend(scene_cpu::list_phases_from(scenes));
+    benchmarks.extend(scene_cpu::list_threaded_from(scenes));
+    benchmarks.extend(scene_hybrid::list_from(scenes));
+    benchmarks.extend(scene_skia::list_from(scenes));
+    benchmarks.extend(scene_cpu::list_cold_from(scenes));
+    benchmarks.extend(scene_hybrid::list_cold_from(scenes));
     benchmarks.extend(vello_cpu::list());
+    benchmarks.extend(cpu_threads::list());
     benchmarks.extend(vello_hybrid::list());
+    benchmarks.extend(recording::list());
+    benchmarks.extend(hybrid_internals::list());
 
     benchmarks
 }
 
+/// Benchmarks of the generated scene `params` (see [`GeneratedParams`]) on
+/// the programmatic scene backends. These run for any seed, but only the
+/// registered seeds are part of [`get_benchmark_list`].
+pub fn generated_benchmarks(params: GeneratedParams) -> Vec<BenchmarkInfo> {
+    vec![
+        vello_cpu::info(params.name()),
+        vello_hybrid::info(params.name()),
+    ]
+}
+
+/// The benchmarks selected by a filter expression (see [`Filter`]), in the
+/// order of [`get_benchmark_list`]. Pass the result to [`run_all`] or
+/// [`shard`] to run or split a filtered set.
+pub fn get_benchmark_list_filtered(filter: &str) -> Result<Vec<BenchmarkInfo>, String> {
+    Ok(Filter::parse(filter)?.apply(&get_benchmark_list()))
+}
+
+/// The benchmarks whose ID matches `pattern`, in the order of
+/// [`get_benchmark_list`].
+///
+/// A pattern with `*` or `?` is a glob over the whole ID (see
+/// [`glob_match`]), e.g. `scene_cpu/*` or `scene_*/tiled*`; any other
+/// pattern selects the IDs containing it, e.g. `tiled_flowers`. An empty
+/// pattern selects everything, and `tag:<tag>` the benchmarks of
+/// [`list_by_tag`]. To combine patterns, use a [`Filter`] with
+/// [`get_benchmark_list_filtered`].
+pub fn filter_benchmarks(pattern: &str) -> Vec<BenchmarkInfo> {
+    select_matching(get_benchmark_list(), pattern)
+}
+
+/// The benchmarks of `list` whose ID matches `pattern`, see
+/// [`filter_benchmarks`].
+fn select_matching(list: Vec<BenchmarkInfo>, pattern: &str) -> Vec<BenchmarkInfo> {
+    if let Some(tag) = pattern.strip_prefix("tag:") {
+        return list
+            .into_iter()
+            .filter(|info| info.tags.contains(&tag))
+            .collect();
+    }
+    let is_glob = pattern.contains(['*', '?']);
+    list.into_iter()
+        .filter(|info| {
+            if is_glob {
+                glob_match(pattern, &info.id)
+            } else {
+                info.id.contains(pattern)
+            }
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Support matrix
+// ---------------------------------------------------------------------------
+
+/// An environment benchmarks can run in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Platform {
+    /// Native build (Tauri app or CLI).
+    Native,
+    /// Browser, on the main thread (needed for canvas access).
+    WasmMainThread,
+    /// Browser, in a Web Worker.
+    WasmWorker,
+}
+
+impl Platform {
+    /// All platforms, in the column order of [`support_matrix_markdown`].
+    pub const ALL: [Self; 3] = [Self::Native, Self::WasmMainThread, Self::WasmWorker];
+
+    /// Human-readable name.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Self::Native => "Native",
+            Self::WasmMainThread => "WASM (main thread)",
+            Self::WasmWorker => "WASM (worker)",
+        }
+    }
+}
+
+/// Something a category needs from the environment beyond a CPU.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    /// A wgpu adapter.
+    GpuAdapter,
+    /// A WebGL2 context on a canvas.
+    #[serde(rename = "webgl2")]
+    WebGl2,
+    /// A WebGPU adapter.
+    #[serde(rename = "webgpu")]
+    WebGpu,
+}
+
+impl Capability {
+    /// Human-readable name.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Self::GpuAdapter => "GPU adapter",
+            Self::WebGl2 => "WebGL2",
+            Self::WebGpu => "WebGPU",
+        }
+    }
+}
+
+/// A platform a category runs on, and what it needs there.
+#[derive(Debug, Clone, Serialize)]
+pub struct PlatformSupport {
+    pub platform: Platform,
+    /// Capabilities that must be present on `platform`.
+    pub requires: &'static [Capability],
+}
+
+/// Where the benchmarks of one category are expected to work.
+#[derive(Debug, Clone, Serialize)]
+pub struct CategorySupport {
+    /// Category, as in [`BenchmarkInfo::category`].
+    pub category: &'static str,
+    /// Platforms the category runs on. Missing platforms are unsupported.
+    pub platforms: &'static [PlatformSupport],
+    /// Why the category is missing from the remaining platforms, if it is.
+    pub unsupported_because: Option<&'static str>,
+    /// Known gaps that apply wherever the category runs.
+    pub limitations: &'static [&'static str],
+}
+
+impl CategorySupport {
+    /// Support on `platform`, or `None` if the category doesn't run there.
+    pub fn on(&self, platform: Platform) -> Option<&PlatformSupport> {
+        self.platforms.iter().find(|p| p.platform == platform)
+    }
+
+    /// Why the category can't run on any of `platforms`, or `None` if it runs
+    /// on at least one of them.
+    pub fn unavailable_reason(&self, platforms: &[Platform]) -> Option<String> {
+        if platforms.iter().any(|&p| self.on(p).is_some()) {
+            return None;
+        }
+        let supported: Vec<&str> = self
+            .platforms
+            .iter()
+            .map(|p| p.platform.display_name())
+            .collect();
+        let mut reason = format!("Only supported on {}", supported.join(", "));
+        if let Some(because) = self.unsupported_because {
+            reason.push_str(": ");
+            reason.push_str(because);
+        }
+        Some(reason)
+    }
+}
+
+const CPU_ANYWHERE: &[PlatformSupport] = &[
+    PlatformSupport {
+        platform: Platform::Native,
+        requires: &[],
+    },
+    PlatformSupport {
+        platform: Platform::WasmMainThread,
+        requires: &[],
+    },
+    PlatformSupport {
+        platform: Platform::WasmWorker,
+        requires: &[],
+    },
+];
+
+/// Native uses wgpu; the browser uses WebGL2, which needs the canvas and
+/// hence the main thread.
+const HYBRID: &[PlatformSupport] = &[
+    PlatformSupport {
+        platform: Platform::Native,
+        requires: &[Capability::GpuAdapter],
+    },
+    PlatformSupport {
+        platform: Platform::WasmMainThread,
+        requires: &[Capability::WebGl2],
+    },
+];
+
+/// Gaps of `vello_hybrid::Scene`, which has no masks at all. The programmatic
+/// scenes additionally hit unimplemented paths of the `Renderer` adapter.
+const HYBRID_LIMITATIONS: &[&str] = &["Masks are not supported"];
+const VELLO_HYBRID_LIMITATIONS: &[&str] = &[
+    "Masks are not supported; scenes using them are not listed",
+    "Blurred rounded rects are not supported; scenes using them are not listed",
+    "Blend modes only apply to layers",
+];
+
+const fn cpu(category: &'static str) -> CategorySupport {
+    CategorySupport {
+        category,
+        platforms: CPU_ANYWHERE,
+        unsupported_because: None,
+        limitations: &[],
+    }
+}
+
+/// The support matrix. Every category returned by [`get_benchmark_list`]
+/// must have exactly one entry; see [`undeclared_categories`].
+const SUPPORT_MATRIX: &[CategorySupport] = &[
+    cpu("fine/fill"),
+    cpu("fine/gradient"),
+    cpu("fine/image"),
+    cpu("fine/pack"),
+    cpu("fine/strip"),
+    cpu("tile"),
+    cpu("flatten"),
+    cpu("strokes"),
+    cpu("render_strips"),
+    cpu("set_paint_cpu"),
+    CategorySupport {
+        category: "set_paint_hybrid",
+        platforms: &[PlatformSupport {
+            platform: Platform::Native,
+            requires: &[],
+        }],
+        unsupported_because: Some("the WASM build of the core crate doesn't link Vello Hybrid"),
+        limitations: &[],
+    },
+    cpu("transform_cpu"),
+    CategorySupport {
+        category: "transform_hybrid",
+        platforms: &[PlatformSupport {
+            platform: Platform::Native,
+            requires: &[],
+        }],
+        unsupported_because: Some("the WASM build of the core crate doesn't link Vello Hybrid"),
+        limitations: &[],
+    },
+    cpu("scene_cpu"),
+    CategorySupport {
+        category: "scene_hybrid",
+        platforms: HYBRID,
+        unsupported_because: Some("rendering needs the canvas, which workers can't access"),
+        limitations: HYBRID_LIMITATIONS,
+    },
+    CategorySupport {
+        category: "scene_skia",
+        platforms: &[PlatformSupport {
+            platform: Platform::Native,
+            requires: &[],
+        }],
+        unsupported_because: Some("Skia is not built for WASM"),
+        limitations: &[],
+    },
+    CategorySupport {
+        category: "scene_cpu_mt",
+        platforms: &[PlatformSupport {
+            platform: Platform::Native,
+            requires: &[],
+        }],
+        unsupported_because: Some("the WASM build renders on a single thread"),
+        limitations: &[],
+    },
+    cpu("scene_cpu_cold"),
+    CategorySupport {
+        category: "scene_hybrid_cold",
+        platforms: &[PlatformSupport {
+            platform: Platform::Native,
+            requires: &[Capability::GpuAdapter],
+        }],
+        unsupported_because: Some(
+            "the WebGL renderer belongs to the page's canvas and isn't rebuilt per frame",
+        ),
+        limitations: HYBRID_LIMITATIONS,
+    },
+    cpu("vello_cpu"),
+    CategorySupport {
+        category: "cpu_threads",
+        platforms: &[PlatformSupport {
+            platform: Platform::Native,
+            requires: &[],
+        }],
+        unsupported_because: Some("the WASM build renders on a single thread"),
+        limitations: &[],
+    },
+    CategorySupport {
+        category: "vello_hybrid",
+        platforms: HYBRID,
+        unsupported_because: Some("rendering needs the canvas, which workers can't access"),
+        limitations: VELLO_HYBRID_LIMITATIONS,
+    },
+    cpu("vello_recording"),
+    CategorySupport {
+        category: "vello_recording_hybrid",
+        platforms: &[PlatformSupport {
+            platform: Platform::Native,
+            requires: &[Capability::GpuAdapter],
+        }],
+        unsupported_because: Some("the WASM build of the core crate doesn't link Vello Hybrid"),
+        limitations: VELLO_HYBRID_LIMITATIONS,
+    },
+    CategorySupport {
+        category: "hybrid_internals",
+        platforms: HYBRID,
+        unsupported_because: Some("rendering needs the canvas, which workers can't access"),
+        limitations: &[],
+    },
+];
+
+/// Which (category × platform × capability) combinations are expected to
+/// work, one entry per category.
+pub fn support_matrix() -> &'static [CategorySupport] {
+    SUPPORT_MATRIX
+}
+
+/// Support entry for `category`, or `None` if it has none.
+pub fn category_support(category: &str) -> Option<&'static CategorySupport> {
+    SUPPORT_MATRIX.iter().find(|s| s.category == category)
+}
+
+/// Categories of registered benchmarks that are missing from
+/// [`support_matrix`]. Must be empty; `vello_bench support` fails otherwise.
+pub fn undeclared_categories() -> Vec<String> {
+    let mut missing: Vec<String> = get_benchmark_list()
+        .into_iter()
+        .map(|info| info.category)
+        .filter(|category| category_support(category).is_none())
+        .collect();
+    missing.dedup();
+    missing
+}
+
+/// IDs of registered benchmarks without a [`BenchmarkInfo::measures`]
+/// description. Must be empty; `vello_bench list` fails otherwise.
+pub fn undocumented_benchmarks() -> Vec<String> {
+    get_benchmark_list()
+        .into_iter()
+        .filter(|info| info.measures.trim().is_empty())
+        .map(|info| info.id)
+        .collect()
+}
+
+/// IDs of registered benchmarks without [`BenchmarkInfo::tags`]. Must be
+/// empty; `vello_bench list` fails otherwise.
+pub fn untagged_benchmarks() -> Vec<String> {
+    get_benchmark_list()
+        .into_iter()
+        .filter(|info| info.tags.is_empty())
+        .map(|info| info.id)
+        .collect()
+}
+
+/// A problem with the registered benchmarks, found by [`validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryError {
+    /// More than one benchmark has this ID, e.g. because two scenes register
+    /// the same name and only the first can be run.
+    DuplicateId(String),
+    /// A benchmark of this category has an empty name.
+    EmptyName { category: String },
+    /// A programmatic or archive scene has a zero width or height.
+    ZeroSize {
+        scene: String,
+        width: u16,
+        height: u16,
+    },
+    /// A list of scene names in a benchmark module, e.g. the scenes getting
+    /// render mode variants, names a scene no programmatic scene's
+    /// `info().name` matches, so its benchmarks can't run.
+    NameMismatch { list: &'static str, scene: String },
+    /// An archive scene has the name of a programmatic scene, so e.g.
+    /// `scene_cpu/<name>` and `vello_cpu/<name>` draw different things.
+    NameCollision(String),
+}
+
+impl std::fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DuplicateId(id) => write!(f, "benchmark ID `{id}` is registered more than once"),
+            Self::EmptyName { category } => write!(f, "a `{category}` benchmark has no name"),
+            Self::ZeroSize {
+                scene,
+                width,
+                height,
+            } => write!(f, "scene `{scene}` has a size of {width}×{height}"),
+            Self::NameMismatch { list, scene } => {
+                write!(f, "`{scene}` in the {list} is not a registered scene")
+            }
+            Self::NameCollision(name) => write!(
+                f,
+                "archive scene `{name}` has the name of a programmatic scene"
+            ),
+        }
+    }
+}
+
+/// Check the registered benchmarks and scenes for duplicate IDs, empty
+/// names, zero-sized scenes, hand-written scene lists naming unknown scenes,
+/// and archive scenes named like programmatic ones. `vello_bench list` fails
+/// on errors, and debug builds check on the first [`get_benchmark_list`].
+pub fn validate() -> Result<(), Vec<RegistryError>> {
+    validate_from(&EmbeddedScenes)
+}
+
+/// [`validate`] with the archive scenes taken from `scenes`, see
+/// [`get_benchmark_list_from`].
+pub fn validate_from(scenes: &dyn SceneSource) -> Result<(), Vec<RegistryError>> {
+    validate_with(scenes, &scene_name_lists())
+}
+
+/// [`validate_from`] checking `name_lists` in place of
+/// [`scene_name_lists`].
+fn validate_with(
+    scenes: &dyn SceneSource,
+    name_lists: &[(&'static str, Vec<&'static str>)],
+) -> Result<(), Vec<RegistryError>> {
+    let mut errors = Vec::new();
+
+    let list = get_benchmark_list_from(scenes);
+    let mut ids: Vec<&str> = list.iter().map(|info| info.id.as_str()).collect();
+    ids.sort_unstable();
+    let mut duplicates: Vec<&str> = ids
+        .windows(2)
+        .filter(|pair| pair[0] == pair[1])
+        .map(|pair| pair[0])
+        .collect();
+    duplicates.dedup();
+    errors.extend(
+        duplicates
+            .into_iter()
+            .map(|id| RegistryError::DuplicateId(id.to_string())),
+    );
+    for info in &list {
+        if info.name.is_empty() {
+            errors.push(RegistryError::EmptyName {
+                category: info.category.clone(),
+            });
+        }
+    }
+
+    let programmatic = crate::vello_scenes::get_vello_scenes();
+    let archive = scenes
+        .names()
+        .into_iter()
+        .filter_map(|name| scenes.get(name));
+    let sizes = programmatic
+        .iter()
+        .map(|scene| (scene.name, scene.width, scene.height))
+        .chain(archive.map(|item| (item.name.as_str(), item.width, item.height)));
+    for (scene, width, height) in sizes {
+        if width == 0 || height == 0 {
+            errors.push(RegistryError::ZeroSize {
+                scene: scene.to_string(),
+                width,
+                height,
+            });
+        }
+    }
+
+    let is_programmatic = |name: &str| programmatic.iter().any(|scene| scene.name == name);
+    for (list, names) in name_lists {
+        for &scene in names {
+            if !is_programmatic(scene) {
+                errors.push(RegistryError::NameMismatch {
+                    list: *list,
+                    scene: scene.to_string(),
+                });
+            }
+        }
+    }
+    for name in scenes.names() {
+        if is_programmatic(name) {
+            errors.push(RegistryError::NameCollision(name.to_string()));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// The lists of programmatic scene names written out by hand in benchmark
+/// modules, with what they are for.
+fn scene_name_lists() -> [(&'static str, Vec<&'static str>); 3] {
+    [
+        (
+            "vello_cpu render mode scenes",
+            vello_cpu::RENDER_MODE_SCENES.to_vec(),
+        ),
+        (
+            "vello_cpu strided scenes",
+            vello_cpu::STRIDED_SCENES.to_vec(),
+        ),
+        ("cpu_threads scenes", cpu_threads::registered_scenes()),
+    ]
+}
+
+/// A benchmark and whether it can run on the platforms it was listed for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkAvailability {
+    /// The benchmark; its fields are inlined when serialized.
+    #[serde(flatten)]
+    pub info: BenchmarkInfo,
+    /// Why the benchmark can't run, from [`CategorySupport::unavailable_reason`].
+    /// `None` if it can.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unavailable_reason: Option<String>,
+}
+
+/// [`get_benchmark_list`], annotated with whether each benchmark runs on any
+/// of `platforms`. The browser UI passes both WASM platforms, since it
+/// dispatches each benchmark to the thread that can run it.
+///
+/// # Panics
+///
+/// Panics if a registered category is missing from [`support_matrix`].
+pub fn get_benchmark_availability(platforms: &[Platform]) -> Vec<BenchmarkAvailability> {
+    get_benchmark_list()
+        .into_iter()
+        .map(|info| {
+            let support = category_support(&info.category).unwrap_or_else(|| {
+                panic!(
+                    "category {} is missing from the support matrix",
+                    info.category
+                )
+            });
+            BenchmarkAvailability {
+                unavailable_reason: support.unavailable_reason(platforms),
+                info,
+            }
+        })
+        .collect()
+}
+
+/// Render [`support_matrix`] as a markdown table.
+pub fn support_matrix_markdown() -> String {
+    let mut out = String::from("| Category |");
+    for platform in Platform::ALL {
+        out.push_str(&format!(" {} |", platform.display_name()));
+    }
+    out.push_str(" Limitations |\n|---|");
+    out.push_str(&"---|".repeat(Platform::ALL.len()));
+    out.push_str("---|\n");
+
+    for support in SUPPORT_MATRIX {
+        out.push_str(&format!("| `{}` |", support.category));
+        for platform in Platform::ALL {
+            let cell = match support.on(platform) {
+                None => "no".to_string(),
+                Some(p) if p.requires.is_empty() => "yes".to_string(),
+                Some(p) => {
+                    let names: Vec<&str> = p.requires.iter().map(|c| c.display_name()).collect();
+                    format!("yes ({})", names.join(", "))
+                }
+            };
+            out.push_str(&format!(" {cell} |"));
+        }
+        let mut notes: Vec<&str> = support.limitations.to_vec();
+        notes.extend(support.unsupported_because);
+        out.push_str(&format!(" {} |\n", notes.join("; ")));
+    }
+    out
+}
+
+/// Check whether this environment is fit for benchmarking on `platforms`
+/// (see [`health`](crate::health)), skipping the checks in `skip`. Skipped
+/// checks are listed with [`HealthStatus::Skip`].
+pub fn health_check(platforms: &[Platform], skip: &[HealthCheckKind]) -> HealthReport {
+    let checks = HealthCheckKind::ALL
+        .into_iter()
+        .map(|kind| {
+            if skip.contains(&kind) {
+                return HealthCheck::new(kind, HealthStatus::Skip, "skipped on request");
+            }
+            match kind {
+                HealthCheckKind::Scenes => health::check_scenes(),
+                HealthCheckKind::Availability => health::check_availability(platforms),
+                HealthCheckKind::Gpu => health::check_gpu(),
+                HealthCheckKind::Timer => health::check_timer(),
+                HealthCheckKind::Selftest => health::check_selftest(),
+                HealthCheckKind::Simd => health::check_simd(),
+            }
+        })
+        .collect();
+    HealthReport { checks }
+}
+
 pub fn run_benchmark_by_id(
     runner: &BenchRunner,
     id: &str,
     level: Level,
+) -> Option<BenchmarkResult> {
+    run_benchmark_by_id_from(&EmbeddedScenes, runner, id, level)
+}
+
+/// [`run_benchmark_by_id`] with archive scenes taken from `scenes`, see
+/// [`get_benchmark_list_from`].
+pub fn run_benchmark_by_id_from(
+    scenes: &dyn SceneSource,
+    runner: &BenchRunner,
+    id: &str,
+    level: Level,
 ) -> Option<BenchmarkResult> {
     if let Some(name) = id.strip_prefix("fine/fill/") {
         return fine::fill::run(name, runner, level);
@@ -92,21 +865,1160 @@ pub fn run_benchmark_by_id(
     if let Some(name) = id.strip_prefix("render_strips/") {
         return render_strips::run(name, runner, level);
     }
+    if let Some(name) = id.strip_prefix("set_paint_cpu/") {
+        return set_paint::run_cpu(name, runner, level);
+    }
+    if let Some(name) = id.strip_prefix("set_paint_hybrid/") {
+        return set_paint::run_hybrid(name, runner, level);
+    }
+    if let Some(name) = id.strip_prefix("transform_cpu/") {
+        return transform::run_cpu(name, runner, level);
+    }
+    if let Some(name) = id.strip_prefix("transform_hybrid/") {
+        return transform::run_hybrid(name, runner, level);
+    }
     if let Some(name) = id.strip_prefix("scene_cpu/") {
-        return scene_cpu::run(name, runner, level);
+        return scene_cpu::run_from(scenes, name, runner, level);
     }
     if let Some(name) = id.strip_prefix("scene_hybrid/") {
-        return scene_hybrid::run(name, runner, level);
+        return scene_hybrid::run_from(scenes, name, runner, level);
     }
     if let Some(name) = id.strip_prefix("scene_skia/") {
-        return scene_skia::run(name, runner, level);
+        return scene_skia::run_from(scenes, name, runner, level);
+    }
+    if let Some(name) = id.strip_prefix("scene_cpu_mt/") {
+        return scene_cpu::run_threaded_from(scenes, name, runner, level);
+    }
+    if let Some(name) = id.strip_prefix("scene_cpu_cold/") {
+        return scene_cpu::run_cold_from(scenes, name, runner, level);
+    }
+    if let Some(name) = id.strip_prefix("scene_hybrid_cold/") {
+        return scene_hybrid::run_cold_from(scenes, name, runner, level);
     }
     if let Some(name) = id.strip_prefix("vello_cpu/") {
         return vello_cpu::run(name, runner, level);
     }
+    if let Some(name) = id.strip_prefix("cpu_threads/") {
+        return cpu_threads::run(name, runner, level);
+    }
     if let Some(name) = id.strip_prefix("vello_hybrid/") {
         return vello_hybrid::run(name, runner, level);
     }
+    if let Some(name) = id.strip_prefix("vello_recording/") {
+        return recording::run_cpu(name, runner, level);
+    }
+    if let Some(name) = id.strip_prefix("vello_recording_hybrid/") {
+        return recording::run_hybrid(name, runner, level);
+    }
+    if let Some(name) = id.strip_prefix("hybrid_internals/") {
+        return hybrid_internals::run(name, runner, level);
+    }
 
     None
 }
+
+/// Run benchmark `id` once per SIMD level this CPU supports (see
+/// [`available_levels`](crate::simd::available_levels)), best level first,
+/// e.g. to compare AVX2 against SSE4.2 and scalar code.
+///
+/// Categories that ignore the level (see [`matrix_dimensions`]) run once, at
+/// the best level. Each level sets the benchmark up anew, since renderers
+/// are built for one level. All results get the `timestamp_ms` of the first,
+/// so they can be grouped as one sweep. Empty if the benchmark is unknown.
+pub fn run_benchmark_all_levels(runner: &BenchRunner, id: &str) -> Vec<BenchmarkResult> {
+    let mut levels = crate::simd::available_levels().into_iter();
+    let Some(first) = levels
+        .next()
+        .and_then(|level| run_benchmark_by_id(runner, id, level))
+    else {
+        return Vec::new();
+    };
+
+    let sweep_ms = first.timestamp_ms;
+    let simd_levels = matrix_dimensions(&first.category).simd_levels;
+    let mut results = vec![first];
+    if simd_levels {
+        results.extend(levels.filter_map(|level| run_benchmark_by_id(runner, id, level)));
+    }
+    for result in &mut results {
+        result.timestamp_ms = sweep_ms;
+    }
+    results
+}
+
+/// Like [`run_benchmark_by_id`], but reports a GPU hang as a
+/// [`BenchmarkOutcome::Failed`] instead of unwinding.
+///
+/// See [`crate::gpu_watchdog`]. Other panics are propagated unchanged. On WASM,
+/// where panics cannot be caught, this is equivalent to wrapping
+/// [`run_benchmark_by_id`].
+pub fn try_run_benchmark_by_id(
+    runner: &BenchRunner,
+    id: &str,
+    level: Level,
+) -> Option<BenchmarkOutcome> {
+    try_run_benchmark_by_id_from(&EmbeddedScenes, runner, id, level)
+}
+
+/// [`try_run_benchmark_by_id`] with archive scenes taken from `scenes`, see
+/// [`get_benchmark_list_from`].
+pub fn try_run_benchmark_by_id_from(
+    scenes: &dyn SceneSource,
+    runner: &BenchRunner,
+    id: &str,
+    level: Level,
+) -> Option<BenchmarkOutcome> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::gpu_watchdog::GpuTimeout;
+        use std::panic::{AssertUnwindSafe, catch_unwind, resume_unwind};
+
+        let run = || run_benchmark_by_id_from(scenes, runner, id, level);
+        match catch_unwind(AssertUnwindSafe(run)) {
+            Ok(result) => result.map(BenchmarkOutcome::Completed),
+            Err(payload) => match payload.downcast::<GpuTimeout>() {
+                Ok(timeout) => Some(BenchmarkOutcome::Failed {
+                    id: id.to_string(),
+                    error: timeout.to_string(),
+                    adapter: timeout.adapter,
+                }),
+                Err(payload) => resume_unwind(payload),
+            },
+        }
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        run_benchmark_by_id_from(scenes, runner, id, level).map(BenchmarkOutcome::Completed)
+    }
+}
+
+/// Whether benchmarks in `category` use the GPU, i.e. need a
+/// [`Capability::GpuAdapter`] on native. These must run exclusively: never in
+/// parallel with each other or with CPU benchmarks.
+pub fn is_gpu_category(category: &str) -> bool {
+    category_support(category).is_some_and(|support| {
+        support
+            .platforms
+            .iter()
+            .any(|p| p.requires.contains(&Capability::GpuAdapter))
+    })
+}
+
+/// Categories whose benchmarks are [`MicroBench`](crate::MicroBench)es and
+/// so honour [`BenchRunner::warmup_input`].
+pub const MICRO_BENCH_CATEGORIES: &[&str] = &["flatten", "tile"];
+
+/// Whether benchmarks in `category` can warm up on a different input than
+/// they measure, see [`WarmupInput`](crate::WarmupInput).
+pub fn supports_distinct_warmup_input(category: &str) -> bool {
+    MICRO_BENCH_CATEGORIES.contains(&category)
+}
+
+/// Run every benchmark in `list` one after another, in order.
+///
+/// Unknown IDs are skipped. GPU hangs are reported as failed outcomes, see
+/// [`try_run_benchmark_by_id`].
+pub fn run_all(
+    runner: &BenchRunner,
+    list: &[BenchmarkInfo],
+    level: Level,
+) -> Vec<BenchmarkOutcome> {
+    list.iter()
+        .filter_map(|info| try_run_benchmark_by_id(runner, &info.id, level))
+        .collect()
+}
+
+/// The benchmarks tagged `tag` (see [`BenchmarkInfo::tags`]), in the order
+/// of [`get_benchmark_list`].
+pub fn list_by_tag(tag: &str) -> Vec<BenchmarkInfo> {
+    get_benchmark_list()
+        .into_iter()
+        .filter(|info| info.tags.contains(&tag))
+        .collect()
+}
+
+/// Run every benchmark matching `pattern` (see [`filter_benchmarks`]) one
+/// after another, in list order. Empty if nothing matches.
+///
+/// The matched benchmarks share their setup where possible: archive scenes
+/// stay deserialized in the [scene cache](crate::scene_cache) from one
+/// backend's benchmark to the next, and on native the GPU benchmarks share a
+/// wgpu device unless
+/// [`keep_gpu_devices`](crate::renderer::keep_gpu_devices) is off.
+pub fn run_benchmarks_matching(
+    runner: &BenchRunner,
+    pattern: &str,
+    level: Level,
+) -> Vec<BenchmarkResult> {
+    run_listed(runner, &filter_benchmarks(pattern), level)
+}
+
+/// Run every benchmark of `category` one after another, in list order, with
+/// results identical in shape to running them one by one. Empty if the
+/// category is unknown.
+///
+/// `vello_cpu` and `vello_hybrid` create their renderer once per canvas size
+/// (and render mode) and reset it between scenes rather than creating one per
+/// benchmark, see [`vello_cpu::run_category`] and
+/// [`vello_hybrid::run_category`]. Other categories share their setup as
+/// [`run_benchmarks_matching`] does.
+pub fn run_category(runner: &BenchRunner, category: &str, level: Level) -> Vec<BenchmarkResult> {
+    match category {
+        "vello_cpu" => vello_cpu::run_category(runner, level),
+        "vello_hybrid" => vello_hybrid::run_category(runner, level),
+        _ => {
+            let list: Vec<_> = get_benchmark_list()
+                .into_iter()
+                .filter(|info| info.category == category)
+                .collect();
+            run_listed(runner, &list, level)
+        }
+    }
+}
+
+/// Run the benchmarks of `list`.
+fn run_listed(runner: &BenchRunner, list: &[BenchmarkInfo], level: Level) -> Vec<BenchmarkResult> {
+    list.iter()
+        .filter_map(|info| run_benchmark_by_id(runner, &info.id, level))
+        .collect()
+}
+
+/// Receives outcomes from [`run_all_observed`] as they are produced.
+pub trait RunObserver {
+    /// Called after each benchmark, before the next one starts.
+    fn on_outcome(&mut self, outcome: &BenchmarkOutcome);
+
+    /// Checked before each benchmark; returning `true` ends the run early.
+    fn should_stop(&self) -> bool {
+        false
+    }
+}
+
+/// Like [`run_all`], but hands every outcome to `observer` as soon as the
+/// benchmark finishes, e.g. to write it to disk with a
+/// [`JsonLinesObserver`](crate::result::JsonLinesObserver) so an interrupted
+/// run keeps its results. Stops early once [`RunObserver::should_stop`]
+/// returns `true`, returning the outcomes so far.
+pub fn run_all_observed(
+    runner: &BenchRunner,
+    list: &[BenchmarkInfo],
+    level: Level,
+    observer: &mut dyn RunObserver,
+) -> Vec<BenchmarkOutcome> {
+    let mut outcomes = Vec::new();
+    for info in list {
+        if observer.should_stop() {
+            break;
+        }
+        if let Some(outcome) = try_run_benchmark_by_id(runner, &info.id, level) {
+            observer.on_outcome(&outcome);
+            outcomes.push(outcome);
+        }
+    }
+    outcomes
+}
+
+/// Run every benchmark in `list`, with CPU benchmarks spread over up to
+/// `max_cpu_parallelism` threads.
+///
+/// CPU benchmarks run first on a thread pool; GPU benchmarks (see
+/// [`is_gpu_category`]) then run one at a time on the calling thread, so the
+/// two classes never overlap. Results are returned in the order of `list`.
+/// Results of CPU benchmarks that shared the machine with others are marked
+/// [`BenchmarkResult::ran_concurrently`]; they are noisier than exclusive
+/// runs.
+///
+/// With `max_cpu_parallelism <= 1` this runs fully serially, but unlike
+/// [`run_all`] still every CPU benchmark before the GPU ones. On WASM,
+/// where threads are unavailable, it always runs serially.
+pub fn run_all_parallel(
+    runner: &BenchRunner,
+    list: &[BenchmarkInfo],
+    level: Level,
+    max_cpu_parallelism: usize,
+) -> Vec<BenchmarkOutcome> {
+    #[cfg(target_arch = "wasm32")]
+    let max_cpu_parallelism = {
+        let _ = max_cpu_parallelism;
+        1
+    };
+
+    let outcomes = schedule(
+        list,
+        |info| is_gpu_category(&info.category),
+        max_cpu_parallelism,
+        |info, concurrent| {
+            let mut outcome = try_run_benchmark_by_id(runner, &info.id, level)?;
+            if let BenchmarkOutcome::Completed(result) = &mut outcome {
+                result.ran_concurrently = concurrent;
+            }
+            Some(outcome)
+        },
+    );
+    outcomes.into_iter().flatten().collect()
+}
+
+/// Run `run` on every item, returning the outputs in input order.
+///
+/// Exclusive items (`is_exclusive`) run one at a time on the calling thread
+/// after all other items, which are distributed over up to `max_parallelism`
+/// scoped threads. `run` is told whether the item may run concurrently with
+/// others.
+fn schedule<I, T, E, R>(items: &[I], is_exclusive: E, max_parallelism: usize, run: R) -> Vec<T>
+where
+    I: Sync,
+    T: Send,
+    E: Fn(&I) -> bool,
+    R: Fn(&I, bool) -> T + Sync,
+{
+    let (exclusive, shared): (Vec<usize>, Vec<usize>) =
+        (0..items.len()).partition(|&i| is_exclusive(&items[i]));
+    let mut outputs: Vec<Option<T>> = items.iter().map(|_| None).collect();
+
+    let workers = max_parallelism.clamp(1, shared.len().max(1));
+    if workers == 1 {
+        for &i in &shared {
+            outputs[i] = Some(run(&items[i], false));
+        }
+    } else {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let next = AtomicUsize::new(0);
+        let finished: Vec<Vec<(usize, T)>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut done = Vec::new();
+                        while let Some(&i) = shared.get(next.fetch_add(1, Ordering::Relaxed)) {
+                            done.push((i, run(&items[i], true)));
+                        }
+                        done
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|e| std::panic::resume_unwind(e))
+                })
+                .collect()
+        });
+        for (i, output) in finished.into_iter().flatten() {
+            outputs[i] = Some(output);
+        }
+    }
+
+    for &i in &exclusive {
+        outputs[i] = Some(run(&items[i], false));
+    }
+
+    outputs
+        .into_iter()
+        .map(|output| output.expect("every item is scheduled exactly once"))
+        .collect()
+}
+
+/// Run a benchmark with an explicit render mode, overriding any mode suffix
+/// in `id`. Only `vello_cpu` and whole-frame `scene_cpu` benchmarks support
+/// this; returns `None` for any other ID.
+pub fn run_benchmark_with_render_mode(
+    runner: &BenchRunner,
+    id: &str,
+    level: Level,
+    mode: RenderMode,
+) -> Option<BenchmarkResult> {
+    if let Some(name) = id.strip_prefix("scene_cpu/") {
+        let (scene, _) = scene_cpu::split_render_mode_from(&EmbeddedScenes, name);
+        return scene_cpu::run_with_mode_from(&EmbeddedScenes, scene, runner, level, mode);
+    }
+    let name = id.strip_prefix("vello_cpu/")?;
+    let (scene, _) = vello_cpu::split_render_mode(name);
+    vello_cpu::run_with_mode(scene, runner, level, mode)
+}
+
+/// The largest count of the counted scene `family` (e.g. `tiled_flowers`)
+/// that `backend` (`"cpu"` or `"hybrid"`) renders within `budget_ms`; see
+/// [`frame_budget`](crate::frame_budget). The native counterpart of the
+/// interactive demo in the web UI.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn find_max_count_for_budget(
+    family: &str,
+    budget_ms: f64,
+    backend: &str,
+    level: Level,
+) -> Result<crate::frame_budget::BudgetResult, String> {
+    use crate::renderer::{HybridRenderer, Renderer};
+    use crate::vello_scenes::{COUNTED_SCENE_HEIGHT, COUNTED_SCENE_WIDTH};
+    use vello_cpu::{Pixmap, RenderContext};
+
+    let (width, height) = (COUNTED_SCENE_WIDTH, COUNTED_SCENE_HEIGHT);
+    match backend {
+        "cpu" => {
+            let mut ctx: RenderContext =
+                Renderer::new(width, height, 0, level, RenderMode::default());
+            let mut pixmap = Pixmap::new(width, height);
+            search_budget(&mut ctx, family, budget_ms, |ctx| {
+                ctx.flush();
+                ctx.render_to_pixmap(&mut pixmap);
+            })
+        }
+        "hybrid" => {
+            let mut hybrid: HybridRenderer =
+                Renderer::new(width, height, 0, level, RenderMode::default());
+            search_budget(&mut hybrid, family, budget_ms, |hybrid| {
+                hybrid.render_and_sync();
+            })
+        }
+        _ => Err(format!(
+            "Unknown backend: {backend} (expected \"cpu\" or \"hybrid\")"
+        )),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn search_budget<R: crate::renderer::Renderer>(
+    r: &mut R,
+    family: &str,
+    budget_ms: f64,
+    mut present: impl FnMut(&mut R),
+) -> Result<crate::frame_budget::BudgetResult, String> {
+    use crate::frame_budget::{probe_frame_ms, search};
+    use crate::vello_scenes::{counted_draw_fn, setup_image_grid};
+
+    let draw =
+        counted_draw_fn::<R>(family).ok_or_else(|| format!("Unknown scene family: {family}"))?;
+    let state = setup_image_grid(r);
+    search(budget_ms, |count| {
+        probe_frame_ms(r, &state, draw, count, &mut present)
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Full matrix
+// ---------------------------------------------------------------------------
+
+/// Dimensions of the full matrix (see [`full_matrix_plan`]) that apply to a
+/// category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatrixDimensions {
+    /// Run at every available SIMD level, not just the best one.
+    pub simd_levels: bool,
+    /// Run every scene in every render mode.
+    pub render_modes: bool,
+    /// The benchmarks vary the renderer's thread count; it is part of their
+    /// names.
+    pub thread_counts: bool,
+}
+
+/// The matrix dimensions of `category`.
+///
+/// SIMD levels only matter for the CPU renderers: the GPU categories, Skia
+/// and Vello Hybrid's CPU-side `Scene` ignore the level. Thread scaling is
+/// measured at the best level only, which keeps the matrix to a few hours.
+/// Render modes only exist in Vello CPU.
+pub fn matrix_dimensions(category: &str) -> MatrixDimensions {
+    let thread_counts = matches!(category, "cpu_threads" | "scene_cpu_mt");
+    let level_independent = matches!(
+        category,
+        "scene_skia" | "set_paint_hybrid" | "transform_hybrid"
+    );
+    MatrixDimensions {
+        simd_levels: !is_gpu_category(category) && !level_independent && !thread_counts,
+        render_modes: category == "vello_cpu",
+        thread_counts,
+    }
+}
+
+/// One run of the full matrix: a listed benchmark at one SIMD level, and for
+/// `vello_cpu` in one render mode.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatrixEntry {
+    /// ID of the listed benchmark, as in [`BenchmarkInfo::id`].
+    pub id: String,
+    /// Category, as in [`BenchmarkInfo::category`].
+    pub category: String,
+    /// SIMD level suffix (see [`level_suffix`](crate::simd::level_suffix)).
+    pub simd_level: String,
+    /// Render mode suffix (see
+    /// [`render_mode_suffix`](vello_cpu::render_mode_suffix)), for `vello_cpu`
+    /// scenes. `None` runs the benchmark as listed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub render_mode: Option<String>,
+    /// Worker threads of `cpu_threads` benchmarks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_threads: Option<u16>,
+}
+
+impl MatrixEntry {
+    /// ID of the result this entry produces: the benchmark ID, with the
+    /// render mode appended for an explicit mode.
+    pub fn result_id(&self) -> String {
+        match &self.render_mode {
+            Some(mode) => format!("{}_{mode}", self.id),
+            None => self.id.clone(),
+        }
+    }
+
+    /// Whether `result` was produced by this entry, e.g. in an earlier,
+    /// interrupted run.
+    pub fn is_done_by(&self, result: &BenchmarkResult) -> bool {
+        result.id == self.result_id()
+            && result.simd_variant == self.simd_level
+            && (self.render_mode.is_none() || result.render_mode == self.render_mode)
+    }
+
+    /// Run this entry. Returns `None` if the benchmark or render mode is
+    /// unknown.
+    pub fn run(&self, runner: &BenchRunner) -> Option<BenchmarkOutcome> {
+        let level = crate::simd::level_from_suffix(&self.simd_level);
+        match &self.render_mode {
+            Some(mode) => {
+                let mode = vello_cpu::render_mode_from_suffix(mode)?;
+                run_benchmark_with_render_mode(runner, &self.id, level, mode)
+                    .map(BenchmarkOutcome::Completed)
+            }
+            None => try_run_benchmark_by_id(runner, &self.id, level),
+        }
+    }
+}
+
+/// Every meaningful combination of the benchmarks in `list` with the SIMD
+/// levels, thread counts and render modes of this machine, for a full nightly
+/// run.
+///
+/// Covers the benchmarks that run natively, expanded along their
+/// [`matrix_dimensions`] over [`available_levels`](crate::simd::available_levels).
+/// See [`full_matrix_plan_for`].
+pub fn full_matrix_plan(list: &[BenchmarkInfo]) -> Vec<MatrixEntry> {
+    let levels: Vec<&str> = crate::simd::available_levels()
+        .into_iter()
+        .map(crate::simd::level_suffix)
+        .collect();
+    full_matrix_plan_for(list, &levels)
+}
+
+/// The full matrix of the benchmarks in `list`, with `levels` the available
+/// SIMD level suffixes, best first.
+///
+/// Benchmarks of categories that don't run natively are left out.
+/// Level-independent benchmarks run once, at the best level. `vello_cpu`
+/// scenes run once per render mode, so their listed `_speed` and `_quality`
+/// variants, which would repeat those runs, are left out; `_strided` variants
+/// run as listed. Entries are ordered like `list`, with the levels of a
+/// benchmark next to each other.
+pub fn full_matrix_plan_for(list: &[BenchmarkInfo], levels: &[&str]) -> Vec<MatrixEntry> {
+    let Some(&best) = levels.first() else {
+        return Vec::new();
+    };
+
+    let mut plan = Vec::new();
+    for info in list {
+        let runs_natively = category_support(&info.category)
+            .is_some_and(|support| support.on(Platform::Native).is_some());
+        if !runs_natively {
+            continue;
+        }
+
+        let dimensions = matrix_dimensions(&info.category);
+        let modes: Vec<Option<&str>> =
+            if !dimensions.render_modes || vello_cpu::split_strided(&info.name).is_some() {
+                vec![None]
+            } else if vello_cpu::split_render_mode(&info.name).1.is_some() {
+                continue;
+            } else {
+                vello_cpu::RENDER_MODES
+                    .iter()
+                    .map(|&mode| Some(vello_cpu::render_mode_suffix(mode)))
+                    .collect()
+            };
+        let levels = if dimensions.simd_levels {
+            levels
+        } else {
+            std::slice::from_ref(&best)
+        };
+        let num_threads = match info.category.as_str() {
+            _ if !dimensions.thread_counts => None,
+            "scene_cpu_mt" => scene_cpu::split_threads(&info.name),
+            _ => cpu_threads::split_threads(&info.name),
+        }
+        .map(|(_, threads)| threads);
+
+        for mode in &modes {
+            for level in levels {
+                plan.push(MatrixEntry {
+                    id: info.id.clone(),
+                    category: info.category.clone(),
+                    simd_level: (*level).to_string(),
+                    render_mode: mode.map(str::to_string),
+                    num_threads,
+                });
+            }
+        }
+    }
+    plan
+}
+
+/// Select the entries of `plan` belonging to shard `shard_index` out of
+/// `shard_count`, like [`shard`]. All entries of a benchmark land in the same
+/// shard, so its per-level comparison never spans machines.
+///
+/// # Panics
+///
+/// Panics if `shard_count` is zero or `shard_index >= shard_count`.
+pub fn shard_plan(plan: &[MatrixEntry], shard_index: u32, shard_count: u32) -> Vec<MatrixEntry> {
+    check_shard(shard_index, shard_count);
+    plan.iter()
+        .filter(|entry| in_shard(&entry.id, shard_index, shard_count))
+        .cloned()
+        .collect()
+}
+
+/// Select the benchmarks belonging to shard `shard_index` (zero-based) out of
+/// `shard_count` shards.
+///
+/// Assignment is based on a stable hash of each benchmark ID, so adding or
+/// removing a benchmark never moves any other benchmark to a different shard.
+/// The order of `list` is preserved within each shard.
+///
+/// # Panics
+///
+/// Panics if `shard_count` is zero or `shard_index >= shard_count`.
+pub fn shard(list: &[BenchmarkInfo], shard_index: u32, shard_count: u32) -> Vec<BenchmarkInfo> {
+    check_shard(shard_index, shard_count);
+    list.iter()
+        .filter(|info| in_shard(&info.id, shard_index, shard_count))
+        .cloned()
+        .collect()
+}
+
+fn check_shard(shard_index: u32, shard_count: u32) {
+    assert!(shard_count > 0, "shard_count must be non-zero");
+    assert!(
+        shard_index < shard_count,
+        "shard_index {shard_index} out of range for {shard_count} shards"
+    );
+}
+
+/// Whether the benchmark `id` belongs to shard `shard_index`.
+pub(crate) fn in_shard(id: &str, shard_index: u32, shard_count: u32) -> bool {
+    stable_hash(id) % u64::from(shard_count) == u64::from(shard_index)
+}
+
+/// Parse a shard specification of the form `"INDEX/COUNT"` (e.g. `"2/8"`),
+/// where `INDEX` is zero-based. Returns `None` if the spec is malformed or
+/// out of range.
+pub fn parse_shard_spec(spec: &str) -> Option<(u32, u32)> {
+    let (index, count) = spec.split_once('/')?;
+    let index: u32 = index.trim().parse().ok()?;
+    let count: u32 = count.trim().parse().ok()?;
+    (count > 0 && index < count).then_some((index, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenes::TestSceneSource;
+
+    fn benchmarks(ids: &[&str]) -> Vec<BenchmarkInfo> {
+        ids.iter()
+            .map(|id| {
+                let (category, name) = id.rsplit_once('/').unwrap();
+                BenchmarkInfo {
+                    id: id.to_string(),
+                    category: category.to_string(),
+                    name: name.to_string(),
+                    measures: "nothing",
+                    tags: vec!["test"],
+                    estimated_cost: CostClass::Micro,
+                }
+            })
+            .collect()
+    }
+
+    fn ids(list: &[BenchmarkInfo]) -> Vec<&str> {
+        list.iter().map(|info| info.id.as_str()).collect()
+    }
+
+    fn many_benchmarks() -> Vec<BenchmarkInfo> {
+        let ids: Vec<String> = (0..200).map(|i| format!("test/bench_{i}")).collect();
+        benchmarks(&ids.iter().map(String::as_str).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn shards_cover_every_benchmark_once() {
+        let list = many_benchmarks();
+        for count in [1, 2, 3, 8] {
+            let mut sharded: Vec<&str> = Vec::new();
+            let shards: Vec<_> = (0..count).map(|index| shard(&list, index, count)).collect();
+            for shard in &shards {
+                sharded.extend(ids(shard));
+            }
+            sharded.sort_unstable();
+            let mut all = ids(&list);
+            all.sort_unstable();
+            assert_eq!(sharded, all, "{count} shards");
+        }
+    }
+
+    #[test]
+    fn shards_keep_list_order() {
+        let list = many_benchmarks();
+        let shard = shard(&list, 1, 4);
+        let positions: Vec<_> = shard
+            .iter()
+            .map(|info| list.iter().position(|listed| listed.id == info.id).unwrap())
+            .collect();
+        assert!(positions.is_sorted());
+    }
+
+    #[test]
+    fn adding_a_benchmark_moves_no_other() {
+        let list = many_benchmarks();
+        let mut extended = list.clone();
+        extended.extend(benchmarks(&["test/new"]));
+        for index in 0..4 {
+            let before = ids(&shard(&list, index, 4)).join(",");
+            let after: Vec<_> = shard(&extended, index, 4)
+                .into_iter()
+                .filter(|info| info.id != "test/new")
+                .collect();
+            assert_eq!(before, ids(&after).join(","), "shard {index}");
+        }
+    }
+
+    #[test]
+    fn recording_benchmarks_follow_scene_metadata() {
+        use crate::vello_scenes::{get_vello_scenes, is_recordable};
+
+        assert!(!is_recordable("recorded_scroll"));
+        assert!(is_recordable("rebuilt_scroll"));
+        assert!(!is_recordable("no_such_scene"));
+
+        let list = get_benchmark_list();
+        for scene in get_vello_scenes() {
+            let record = format!("{}_record", scene.name);
+            let listed = list
+                .iter()
+                .any(|info| info.category == "vello_recording" && info.name == record);
+            assert_eq!(listed, is_recordable(scene.name), "{}", scene.name);
+        }
+    }
+
+    #[test]
+    fn registry_is_valid() {
+        assert_eq!(validate(), Ok(()));
+        assert_eq!(validate_from(&TestSceneSource::new()), Ok(()));
+    }
+
+    #[test]
+    fn selects_benchmarks_by_glob() {
+        let scenes = TestSceneSource::empty()
+            .with_scene("tiled_demo", 16, 16, |_| {})
+            .with_scene("tiger", 16, 16, |_| {});
+        let list = get_benchmark_list_from(&scenes);
+
+        let selected = select_matching(list.clone(), "scene_*/tiled*");
+        let expected: Vec<&str> = ids(&list)
+            .into_iter()
+            .filter(|id| id.starts_with("scene_") && id.contains("/tiled_demo"))
+            .collect();
+        assert_eq!(ids(&selected), expected);
+        for id in ["scene_cpu/tiled_demo", "scene_hybrid/tiled_demo"] {
+            assert!(expected.contains(&id), "{id} missing from {expected:?}");
+        }
+
+        // Without a wildcard, a pattern matches anywhere in the ID, which
+        // includes the programmatic tiled scenes.
+        let selected = select_matching(list.clone(), "tiled");
+        assert!(ids(&selected).iter().all(|id| id.contains("tiled")));
+        assert!(ids(&selected).contains(&"vello_cpu/tiled_flowers_100"));
+        assert!(selected.len() > expected.len());
+
+        assert_eq!(select_matching(list.clone(), "").len(), list.len());
+    }
+
+    #[test]
+    fn selects_nothing_without_a_match() {
+        for pattern in ["no_such_benchmark", "scene_*/no_such*", "tag:no_such_tag"] {
+            assert!(filter_benchmarks(pattern).is_empty(), "{pattern}");
+        }
+    }
+
+    #[test]
+    fn every_benchmark_has_tags() {
+        assert_eq!(untagged_benchmarks(), Vec::<String>::new());
+        assert_eq!(undocumented_benchmarks(), Vec::<String>::new());
+        for info in get_benchmark_list() {
+            for tag in category_tags(&info.category) {
+                assert!(info.tags.contains(tag), "{} lacks {tag}", info.id);
+            }
+        }
+    }
+
+    #[test]
+    fn tags_select_subsets() {
+        let list = get_benchmark_list();
+        let mut tags: Vec<&str> = list.iter().flat_map(|info| info.tags.clone()).collect();
+        tags.sort_unstable();
+        tags.dedup();
+
+        for tag in tags {
+            let tagged = list_by_tag(tag);
+            assert!(!tagged.is_empty(), "{tag}");
+            assert!(tagged.len() < list.len(), "every benchmark is tagged {tag}");
+            assert!(tagged.iter().all(|info| info.tags.contains(&tag)), "{tag}");
+            let expected: Vec<&str> = list
+                .iter()
+                .filter(|info| info.tags.contains(&tag))
+                .map(|info| info.id.as_str())
+                .collect();
+            assert_eq!(ids(&tagged), expected, "{tag}");
+
+            let filtered = get_benchmark_list_filtered(&format!("tag:{tag}")).unwrap();
+            assert_eq!(ids(&filtered), expected, "tag:{tag}");
+            assert_eq!(ids(&filter_benchmarks(&format!("tag:{tag}"))), expected);
+        }
+
+        let tagged = |tag: &str| -> std::collections::HashSet<String> {
+            list_by_tag(tag).into_iter().map(|info| info.id).collect()
+        };
+        for (tag, of) in [
+            ("gpu", "hybrid"),
+            ("cold", "scene"),
+            ("threads", "cpu"),
+            ("images", "scene"),
+            ("small", "scene"),
+            ("large", "scene"),
+        ] {
+            assert!(
+                tagged(tag).is_subset(&tagged(of)),
+                "tag:{tag} not within tag:{of}"
+            );
+        }
+        for (tag, other) in [("micro", "scene"), ("small", "large"), ("cpu", "hybrid")] {
+            assert!(
+                tagged(tag).is_disjoint(&tagged(other)),
+                "tag:{tag} overlaps tag:{other}"
+            );
+        }
+    }
+
+    #[test]
+    fn cost_classes_follow_tags() {
+        use CostClass::*;
+
+        assert_eq!(CostClass::from_tags(&["cpu", "micro"]), Micro);
+        assert_eq!(CostClass::from_tags(&["cpu", "scene", "small"]), Fast);
+        assert_eq!(CostClass::from_tags(&["cpu", "scene"]), Medium);
+        assert_eq!(CostClass::from_tags(&["cpu", "scene", "large"]), Slow);
+        assert_eq!(
+            CostClass::from_tags(&["cpu", "scene", "small", "cold"]),
+            Medium
+        );
+        assert_eq!(
+            CostClass::from_tags(&["hybrid", "gpu", "scene", "large"]),
+            VerySlow
+        );
+        assert_eq!(
+            CostClass::from_tags(&["hybrid", "gpu", "scene", "large", "cold"]),
+            VerySlow
+        );
+        assert_eq!(CostClass::from_tags(&[]), Medium);
+    }
+
+    #[test]
+    fn large_scenes_cost_more_than_their_small_siblings() {
+        let list = get_benchmark_list();
+        let mut pairs = 0;
+        for large in &list {
+            // `tiled_flowers_10000` and `tiled_flowers_10000_speed`, but not
+            // `complex_path_10000seg`.
+            let sibling = match large.id.strip_suffix("_10000") {
+                Some(prefix) => format!("{prefix}_100"),
+                None if large.id.contains("_10000_") => large.id.replacen("_10000_", "_100_", 1),
+                None => continue,
+            };
+            let Some(small) = list.iter().find(|info| info.id == sibling) else {
+                continue;
+            };
+            assert!(
+                large.estimated_cost > small.estimated_cost,
+                "{} is {:?}, {} is {:?}",
+                large.id,
+                large.estimated_cost,
+                small.id,
+                small.estimated_cost
+            );
+            pairs += 1;
+        }
+        assert!(pairs > 0);
+        for family in ["tiled_flowers", "overlapping_images", "rotated_images"] {
+            let cost = |id: String| {
+                let info = list.iter().find(|info| info.id == id);
+                info.unwrap_or_else(|| panic!("{id} missing"))
+                    .estimated_cost
+            };
+            assert!(
+                cost(format!("vello_cpu/{family}_10000")) > cost(format!("vello_cpu/{family}_100"))
+            );
+        }
+    }
+
+    #[test]
+    fn reports_broken_registrations() {
+        let programmatic = crate::vello_scenes::get_vello_scenes()[0].name;
+        let scenes = TestSceneSource::empty()
+            .with_scene("twice", 16, 16, |_| {})
+            .with_scene("twice", 16, 16, |_| {})
+            .with_scene("", 16, 16, |_| {})
+            .with_scene("flat", 16, 0, |_| {})
+            .with_scene(programmatic, 16, 16, |_| {});
+        let lists = [("test scenes", vec![programmatic, "no_such_scene"])];
+        let errors = validate_with(&scenes, &lists).unwrap_err();
+
+        let expected = [
+            RegistryError::DuplicateId("scene_cpu/twice".to_string()),
+            RegistryError::EmptyName {
+                category: "scene_cpu".to_string(),
+            },
+            RegistryError::ZeroSize {
+                scene: "flat".to_string(),
+                width: 16,
+                height: 0,
+            },
+            RegistryError::NameMismatch {
+                list: "test scenes",
+                scene: "no_such_scene".to_string(),
+            },
+            RegistryError::NameCollision(programmatic.to_string()),
+        ];
+        for error in &expected {
+            assert!(errors.contains(error), "{error} missing from {errors:?}");
+        }
+        // Only the broken scenes are reported.
+        assert!(errors.iter().all(|error| match error {
+            RegistryError::DuplicateId(id) => id.contains("twice"),
+            RegistryError::EmptyName { .. } => true,
+            RegistryError::ZeroSize { scene, .. } => scene == "flat",
+            RegistryError::NameMismatch { scene, .. } => scene == "no_such_scene",
+            RegistryError::NameCollision(name) => name == programmatic,
+        }));
+    }
+
+    /// The names of the fields `result` serializes, which leaves out unset
+    /// optional ones.
+    fn fields(result: &BenchmarkResult) -> Vec<String> {
+        let json = serde_json::to_value(result).unwrap();
+        json.as_object().unwrap().keys().cloned().collect()
+    }
+
+    #[test]
+    fn category_runs_give_the_results_of_single_runs() {
+        let runner = BenchRunner::new(0, 2);
+        let level = Level::new();
+        // One scene of one canvas size, in both render modes and strided, so
+        // the shared render targets are reset and reused.
+        let list: Vec<_> = vello_cpu::list()
+            .into_iter()
+            .filter(|info| info.name.starts_with("filled_rects"))
+            .collect();
+        assert_eq!(list.len(), 4, "{:?}", ids(&list));
+
+        let category = vello_cpu::run_listed(&list, &runner, level);
+        assert_eq!(category.len(), list.len());
+        for (info, result) in list.iter().zip(&category) {
+            let single = run_benchmark_by_id(&runner, &info.id, level).unwrap();
+            assert_eq!(result.id, single.id);
+            assert_eq!(result.category, single.category);
+            assert_eq!(result.name, single.name);
+            assert_eq!(result.simd_variant, single.simd_variant);
+            assert_eq!(result.throughput, single.throughput);
+            assert_eq!(result.render_mode, single.render_mode);
+            assert_eq!(result.animated, single.animated);
+            assert_eq!(fields(result), fields(&single), "{}", info.id);
+        }
+
+        assert!(run_category(&runner, "no_such_category", level).is_empty());
+    }
+
+    /// What [`schedule`] did with the items of a test: the order they
+    /// started in, and the most items in flight at once.
+    #[derive(Default)]
+    struct ScheduleLog {
+        started: std::sync::Mutex<Vec<usize>>,
+        in_flight: std::sync::atomic::AtomicUsize,
+        max_in_flight: std::sync::atomic::AtomicUsize,
+        exclusive_overlapped: std::sync::atomic::AtomicBool,
+    }
+
+    /// Schedule `items` of which `exclusive` are exclusive, each taking a
+    /// millisecond. Returns the outputs, each item with whether it was told
+    /// it runs concurrently, and the log.
+    fn schedule_instrumented(
+        items: usize,
+        exclusive: &[usize],
+        max_parallelism: usize,
+    ) -> (Vec<(usize, bool)>, ScheduleLog) {
+        use std::sync::atomic::Ordering;
+
+        let log = ScheduleLog::default();
+        let items: Vec<usize> = (0..items).collect();
+        let outputs = schedule(
+            &items,
+            |i| exclusive.contains(i),
+            max_parallelism,
+            |&i, concurrent| {
+                let in_flight = log.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                log.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+                if exclusive.contains(&i) && in_flight > 1 {
+                    log.exclusive_overlapped.store(true, Ordering::SeqCst);
+                }
+                log.started.lock().unwrap().push(i);
+                std::thread::sleep(std::time::Duration::from_millis(1));
+                log.in_flight.fetch_sub(1, Ordering::SeqCst);
+                (i, concurrent)
+            },
+        );
+        (outputs, log)
+    }
+
+    #[test]
+    fn schedule_runs_exclusive_items_last_and_alone() {
+        use std::sync::atomic::Ordering;
+
+        let exclusive = [1, 4, 7];
+        let (outputs, log) = schedule_instrumented(12, &exclusive, 4);
+
+        // Outputs come back in input order.
+        let order: Vec<usize> = outputs.iter().map(|&(i, _)| i).collect();
+        assert_eq!(order, (0..12).collect::<Vec<_>>());
+        for &(i, concurrent) in &outputs {
+            assert_eq!(concurrent, !exclusive.contains(&i), "item {i}");
+        }
+
+        // Every shared item starts before the exclusive ones, which run one
+        // at a time, in order, with nothing else in flight.
+        let started = log.started.into_inner().unwrap();
+        assert_eq!(started.len(), 12);
+        assert_eq!(started[9..], exclusive);
+        assert!(!log.exclusive_overlapped.load(Ordering::SeqCst));
+        assert!(log.max_in_flight.load(Ordering::SeqCst) <= 4);
+    }
+
+    #[test]
+    fn serial_schedule_runs_shared_items_in_order() {
+        use std::sync::atomic::Ordering;
+
+        let (outputs, log) = schedule_instrumented(6, &[0, 3], 1);
+        assert!(outputs.iter().all(|&(_, concurrent)| !concurrent));
+        assert_eq!(log.started.into_inner().unwrap(), [1, 2, 4, 5, 0, 3]);
+        assert_eq!(log.max_in_flight.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn schedule_without_shared_items() {
+        let (outputs, log) = schedule_instrumented(3, &[0, 1, 2], 8);
+        assert_eq!(outputs, [(0, false), (1, false), (2, false)]);
+        assert_eq!(log.started.into_inner().unwrap(), [0, 1, 2]);
+        assert!(schedule_instrumented(0, &[], 8).0.is_empty());
+    }
+
+    #[test]
+    fn parses_shard_specs() {
+        assert_eq!(parse_shard_spec("0/1"), Some((0, 1)));
+        assert_eq!(parse_shard_spec("2/8"), Some((2, 8)));
+        assert_eq!(parse_shard_spec(" 3 / 4 "), Some((3, 4)));
+        for spec in ["", "1", "4/4", "0/0", "-1/2", "a/2", "1/2/3"] {
+            assert_eq!(parse_shard_spec(spec), None, "{spec:?}");
+        }
+    }
+
+    #[test]
+    fn full_matrix_expands_each_benchmark_along_its_dimensions() {
+        let list = benchmarks(&[
+            "vello_cpu/filled_rects",
+            "vello_cpu/filled_rects_speed",
+            "vello_cpu/filled_rects_quality",
+            "vello_cpu/filled_rects_strided",
+            "scene_cpu/tiger",
+            "scene_hybrid/tiger",
+            "scene_skia/tiger",
+            "cpu_threads/filled_rects_t4",
+            "scene_cpu_mt/tiger_t8",
+            "no_such_category/tiger",
+        ]);
+        let levels = ["avx2", "sse42", "scalar"];
+        let plan = full_matrix_plan_for(&list, &levels);
+        let entries = |id: &str| -> Vec<&MatrixEntry> {
+            plan.iter().filter(|entry| entry.id == id).collect()
+        };
+        let runs = |id: &str| -> Vec<(&str, Option<&str>)> {
+            entries(id)
+                .into_iter()
+                .map(|entry| (entry.simd_level.as_str(), entry.render_mode.as_deref()))
+                .collect()
+        };
+
+        // Plain Vello CPU scenes run in both render modes at every level;
+        // their listed mode variants would repeat those runs.
+        assert_eq!(
+            runs("vello_cpu/filled_rects"),
+            [
+                ("avx2", Some("speed")),
+                ("sse42", Some("speed")),
+                ("scalar", Some("speed")),
+                ("avx2", Some("quality")),
+                ("sse42", Some("quality")),
+                ("scalar", Some("quality")),
+            ]
+        );
+        assert!(runs("vello_cpu/filled_rects_speed").is_empty());
+        assert!(runs("vello_cpu/filled_rects_quality").is_empty());
+        assert_eq!(
+            runs("vello_cpu/filled_rects_strided"),
+            [("avx2", None), ("sse42", None), ("scalar", None)]
+        );
+
+        // Render modes only exist in Vello CPU.
+        assert_eq!(
+            runs("scene_cpu/tiger"),
+            [("avx2", None), ("sse42", None), ("scalar", None)]
+        );
+
+        // Level-agnostic categories run once, at the best level.
+        for id in [
+            "scene_hybrid/tiger",
+            "scene_skia/tiger",
+            "cpu_threads/filled_rects_t4",
+            "scene_cpu_mt/tiger_t8",
+        ] {
+            assert_eq!(runs(id), [("avx2", None)], "{id}");
+        }
+        assert_eq!(
+            entries("cpu_threads/filled_rects_t4")[0].num_threads,
+            Some(4)
+        );
+        assert_eq!(entries("scene_cpu_mt/tiger_t8")[0].num_threads, Some(8));
+        assert_eq!(entries("scene_cpu/tiger")[0].num_threads, None);
+
+        // Categories without native support are left out.
+        assert!(runs("no_such_category/tiger").is_empty());
+
+        assert_eq!(plan.len(), 6 + 3 + 3 + 4);
+        assert_eq!(
+            plan.iter()
+                .map(MatrixEntry::result_id)
+                .take(4)
+                .collect::<Vec<_>>(),
+            [
+                "vello_cpu/filled_rects_speed",
+                "vello_cpu/filled_rects_speed",
+                "vello_cpu/filled_rects_speed",
+                "vello_cpu/filled_rects_quality",
+            ]
+        );
+        assert!(full_matrix_plan_for(&list, &[]).is_empty());
+    }
+}
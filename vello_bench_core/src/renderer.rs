@@ -12,6 +12,10 @@ use std::sync::Arc;
 
 #[cfg(not(target_arch = "wasm32"))]
 use std::cell::RefCell;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::{Mutex, PoisonError};
 
 use vello_common::filter_effects::Filter;
 use vello_common::glyph::{GlyphRenderer, GlyphRunBuilder};
@@ -23,6 +27,11 @@ use vello_common::pixmap::Pixmap;
 use vello_common::recording::{Recordable, Recorder, Recording};
 use vello_cpu::{RenderContext, RenderMode, RenderSettings};
 
+use crate::readback::check_pixmap_size;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::readback::{copy_unpadded_rows, padded_bytes_per_row};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::result::SyncMode;
 #[cfg(not(target_arch = "wasm32"))]
 use vello_hybrid::Scene;
 
@@ -69,7 +78,51 @@ pub trait Renderer: Sized {
     fn set_blend_mode(&mut self, blend_mode: BlendMode);
     fn set_filter_effect(&mut self, filter: Filter);
     fn reset_filter_effect(&mut self);
+
+    /// Render into `pixmap`, which must have the renderer's size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pixmap` has a different size, or the render fails. Use
+    /// [`try_render_to_pixmap`](Renderer::try_render_to_pixmap) to get an
+    /// error instead.
     fn render_to_pixmap(&self, pixmap: &mut Pixmap);
+
+    /// Like [`render_to_pixmap`](Renderer::render_to_pixmap), but returns an
+    /// error if `pixmap` has a different size than the renderer, or if the
+    /// render or readback fails.
+    ///
+    /// The default checks the size and then calls `render_to_pixmap`; GPU
+    /// backends override it to report readback failures as well.
+    fn try_render_to_pixmap(&self, pixmap: &mut Pixmap) -> Result<(), String> {
+        check_pixmap_size(pixmap, self.width(), self.height())?;
+        self.render_to_pixmap(pixmap);
+        Ok(())
+    }
+
+    /// Render into a caller-provided premultiplied RGBA8 buffer whose rows
+    /// start `stride_bytes` apart. Bytes between the end of one row and the
+    /// start of the next are left untouched.
+    ///
+    /// The default renders into a scratch [`Pixmap`] and copies it row by row,
+    /// which is what embedders have to do until backends can write strided
+    /// output directly.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stride_bytes` is less than `width * 4` or `buf` is shorter
+    /// than [`strided_buffer_len`].
+    fn render_to_buffer(&self, buf: &mut [u8], stride_bytes: usize) {
+        let mut pixmap = Pixmap::new(self.width(), self.height());
+        self.render_to_pixmap(&mut pixmap);
+        copy_rows(
+            pixmap.data_as_u8_slice(),
+            usize::from(self.width()) * 4,
+            buf,
+            stride_bytes,
+        );
+    }
+
     fn width(&self) -> u16;
     fn height(&self) -> u16;
     fn get_image_source(&mut self, pixmap: Arc<Pixmap>) -> ImageSource;
@@ -78,6 +131,32 @@ pub trait Renderer: Sized {
     fn execute_recording(&mut self, recording: &Recording);
 }
 
+/// Minimum length of a buffer passed to [`Renderer::render_to_buffer`]. The
+/// last row needs no padding after it.
+pub fn strided_buffer_len(width: u16, height: u16, stride_bytes: usize) -> usize {
+    match height {
+        0 => 0,
+        _ => stride_bytes * (usize::from(height) - 1) + usize::from(width) * 4,
+    }
+}
+
+/// Copy tightly packed rows of `row_bytes` each from `src` into `dst`, where
+/// rows start `stride_bytes` apart.
+fn copy_rows(src: &[u8], row_bytes: usize, dst: &mut [u8], stride_bytes: usize) {
+    assert!(
+        stride_bytes >= row_bytes,
+        "stride of {stride_bytes} bytes is shorter than a row of {row_bytes} bytes"
+    );
+    if row_bytes == 0 {
+        return;
+    }
+
+    for (row, src_row) in src.chunks_exact(row_bytes).enumerate() {
+        let start = row * stride_bytes;
+        dst[start..start + row_bytes].copy_from_slice(src_row);
+    }
+}
+
 // ---------------------------------------------------------------------------
 // CPU backend — delegates to vello_cpu::RenderContext (all targets)
 // ---------------------------------------------------------------------------
@@ -248,11 +327,126 @@ impl Renderer for RenderContext {
 #[cfg(not(target_arch = "wasm32"))]
 pub struct HybridRenderer {
     scene: Scene,
+    adapter: wgpu::AdapterInfo,
     device: wgpu::Device,
     queue: wgpu::Queue,
     texture: wgpu::Texture,
     texture_view: wgpu::TextureView,
     renderer: RefCell<vello_hybrid::Renderer>,
+    sync_mode: SyncMode,
+}
+
+/// The adapter [`HybridRenderer::new`] would pick, or `None` if wgpu finds
+/// none. Headless CI machines often have none.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn gpu_adapter_info() -> Option<wgpu::AdapterInfo> {
+    let instance = wgpu::Instance::default();
+    pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+        .ok()
+        .map(|adapter| adapter.get_info())
+}
+
+/// Whether wgpu finds an adapter, i.e. whether [`HybridRenderer::new`] can
+/// succeed.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn gpu_adapter_available() -> bool {
+    gpu_adapter_info().is_some()
+}
+
+/// A wgpu device with its queue and the adapter it was created on.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone)]
+pub(crate) struct GpuDevice {
+    pub(crate) adapter: wgpu::AdapterInfo,
+    pub(crate) device: wgpu::Device,
+    pub(crate) queue: wgpu::Queue,
+}
+
+/// Whether [`gpu_device`] keeps the devices it creates, see
+/// [`keep_gpu_devices`].
+#[cfg(not(target_arch = "wasm32"))]
+static KEEP_GPU_DEVICES: AtomicBool = AtomicBool::new(true);
+
+/// Devices kept by [`gpu_device`], one per power preference.
+#[cfg(not(target_arch = "wasm32"))]
+static KEPT_GPU_DEVICES: Mutex<Vec<(wgpu::PowerPreference, GpuDevice)>> = Mutex::new(Vec::new());
+
+/// Whether every hybrid renderer reuses one device per power preference for
+/// the rest of the process, or creates its own.
+///
+/// Creating a device takes far longer than most benchmarks, and some drivers
+/// run out of adapter handles when a run creates one per benchmark, so
+/// devices are kept by default. Turning it off gives every renderer a fresh
+/// device, which keeps one benchmark's GPU state from leaking into the next,
+/// and drops the kept devices.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn keep_gpu_devices(keep: bool) {
+    KEEP_GPU_DEVICES.store(keep, Ordering::Relaxed);
+    if !keep {
+        discard_kept_gpu_devices();
+    }
+}
+
+/// Whether hybrid renderers currently reuse kept devices, see
+/// [`keep_gpu_devices`].
+#[cfg(not(target_arch = "wasm32"))]
+pub fn gpu_devices_kept() -> bool {
+    KEEP_GPU_DEVICES.load(Ordering::Relaxed)
+}
+
+/// Drop the devices kept since [`keep_gpu_devices`], e.g. after a GPU
+/// timeout may have left one unusable. The next renderer creates a new one.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn discard_kept_gpu_devices() {
+    KEPT_GPU_DEVICES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+}
+
+/// A device on the adapter wgpu picks for `power_preference`: the kept one
+/// unless [`keep_gpu_devices`] is off, a new one otherwise.
+///
+/// Panics if there is no adapter or the device can't be created.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn gpu_device(power_preference: wgpu::PowerPreference) -> GpuDevice {
+    if !KEEP_GPU_DEVICES.load(Ordering::Relaxed) {
+        return create_gpu_device(power_preference);
+    }
+
+    let mut kept = KEPT_GPU_DEVICES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if let Some((_, gpu)) = kept.iter().find(|(p, _)| *p == power_preference) {
+        return gpu.clone();
+    }
+    let gpu = create_gpu_device(power_preference);
+    kept.push((power_preference, gpu.clone()));
+    gpu
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn create_gpu_device(power_preference: wgpu::PowerPreference) -> GpuDevice {
+    let instance = wgpu::Instance::default();
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference,
+        force_fallback_adapter: false,
+        compatible_surface: None,
+    }))
+    .expect("Failed to find an appropriate adapter");
+    let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+        label: Some("Device"),
+        // For `scene_hybrid`'s GPU times, see `crate::gpu_timestamps`.
+        required_features: adapter.features() & wgpu::Features::TIMESTAMP_QUERY,
+        ..Default::default()
+    }))
+    .expect("Failed to create device");
+
+    GpuDevice {
+        adapter: adapter.get_info(),
+        device,
+        queue,
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -262,6 +456,11 @@ impl HybridRenderer {
         &self.scene
     }
 
+    /// Information about the adapter the device was created on.
+    pub fn adapter_info(&self) -> &wgpu::AdapterInfo {
+        &self.adapter
+    }
+
     /// Access the wgpu device.
     pub fn device(&self) -> &wgpu::Device {
         &self.device
@@ -277,7 +476,27 @@ impl HybridRenderer {
         &self.texture
     }
 
-    /// Render the current scene to the GPU texture and sync.
+    /// Discard everything drawn into the scene without rendering it.
+    ///
+    /// Together with the [`Renderer`] drawing methods this allows building a
+    /// scene without paying for a GPU render, e.g. to time `reset` in
+    /// isolation.
+    pub fn reset(&mut self) {
+        self.scene.reset();
+    }
+
+    /// How [`Self::render_and_sync`] waits for the GPU.
+    pub fn sync_mode(&self) -> SyncMode {
+        self.sync_mode
+    }
+
+    /// Set how [`Self::render_and_sync`] waits for the GPU.
+    pub fn set_sync_mode(&mut self, sync_mode: SyncMode) {
+        self.sync_mode = sync_mode;
+    }
+
+    /// Render the current scene to the GPU texture and sync as set by
+    /// [`Self::set_sync_mode`].
     ///
     /// This is the lightweight render path used in the benchmark hot loop
     /// (no pixel readback). For screenshots, use `render_to_pixmap()` instead.
@@ -310,9 +529,14 @@ impl HybridRenderer {
             .expect("Hybrid render failed");
 
         self.queue.submit(Some(encoder.finish()));
-        self.device
-            .poll(wgpu::PollType::wait_indefinitely())
-            .unwrap();
+        match self.sync_mode {
+            SyncMode::Finish => crate::gpu_watchdog::wait_for_gpu(&self.device, &self.adapter),
+            SyncMode::Fence => crate::gpu_watchdog::wait_for_submitted_work(
+                &self.device,
+                &self.queue,
+                &self.adapter,
+            ),
+        }
 
         self.scene.reset();
     }
@@ -334,19 +558,11 @@ impl Renderer for HybridRenderer {
         }
 
         let scene = Scene::new(width, height);
-        let instance = wgpu::Instance::default();
-        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
-            power_preference: wgpu::PowerPreference::default(),
-            force_fallback_adapter: false,
-            compatible_surface: None,
-        }))
-        .expect("Failed to find an appropriate adapter");
-        let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
-            label: Some("Device"),
-            required_features: wgpu::Features::empty(),
-            ..Default::default()
-        }))
-        .expect("Failed to create device");
+        let GpuDevice {
+            adapter,
+            device,
+            queue,
+        } = gpu_device(wgpu::PowerPreference::default());
 
         let texture = device.create_texture(&wgpu::TextureDescriptor {
             label: Some("Render Target"),
@@ -379,11 +595,13 @@ impl Renderer for HybridRenderer {
 
         Self {
             scene,
+            adapter,
             device,
             queue,
             texture,
             texture_view,
             renderer: RefCell::new(renderer),
+            sync_mode: SyncMode::default(),
         }
     }
 
@@ -499,8 +717,15 @@ impl Renderer for HybridRenderer {
     }
 
     fn render_to_pixmap(&self, pixmap: &mut Pixmap) {
+        if let Err(e) = self.try_render_to_pixmap(pixmap) {
+            panic!("{e}");
+        }
+    }
+
+    fn try_render_to_pixmap(&self, pixmap: &mut Pixmap) -> Result<(), String> {
         let width = self.scene.width();
         let height = self.scene.height();
+        check_pixmap_size(pixmap, width, height)?;
 
         let render_size = vello_hybrid::RenderSize {
             width: width.into(),
@@ -522,9 +747,9 @@ impl Renderer for HybridRenderer {
                 &render_size,
                 &self.texture_view,
             )
-            .unwrap();
+            .map_err(|e| format!("Hybrid render failed: {e:?}"))?;
 
-        let bytes_per_row = (u32::from(width) * 4).next_multiple_of(256);
+        let bytes_per_row = padded_bytes_per_row(width.into());
         let texture_copy_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Output Buffer"),
             size: u64::from(bytes_per_row) * u64::from(height),
@@ -562,23 +787,17 @@ impl Renderer for HybridRenderer {
                     panic!("Failed to map texture for reading");
                 }
             });
-        self.device
-            .poll(wgpu::PollType::wait_indefinitely())
-            .unwrap();
-
-        for (row, buf) in texture_copy_buffer
-            .slice(..)
-            .get_mapped_range()
-            .chunks_exact(bytes_per_row as usize)
-            .zip(
-                pixmap
-                    .data_as_u8_slice_mut()
-                    .chunks_exact_mut(width as usize * 4),
-            )
-        {
-            buf.copy_from_slice(&row[0..width as usize * 4]);
-        }
+        crate::gpu_watchdog::wait_for_gpu(&self.device, &self.adapter);
+
+        let copied = copy_unpadded_rows(
+            &texture_copy_buffer.slice(..).get_mapped_range(),
+            bytes_per_row as usize,
+            width.into(),
+            height.into(),
+            pixmap.data_as_u8_slice_mut(),
+        );
         texture_copy_buffer.unmap();
+        copied
     }
 
     fn width(&self) -> u16 {
@@ -620,3 +839,203 @@ impl Renderer for HybridRenderer {
         Recordable::execute_recording(&mut self.scene, recording);
     }
 }
+
+// ---------------------------------------------------------------------------
+// Recorder adapter — draws into a `Recording` (all targets)
+// ---------------------------------------------------------------------------
+
+/// A [`Renderer`] that draws into the [`Recorder`] of a
+/// [`Renderer::record`] call, so that the programmatic scenes can be
+/// recorded.
+///
+/// Resources such as images must come from the renderer the recording is
+/// made for, i.e. the scene's setup runs on that renderer. Operations a
+/// recording can't hold (blurred rounded rects, clip paths, masks, blend
+/// modes and filters outside layers, nested recordings) panic, as does
+/// rendering.
+pub struct RecorderRenderer<'a, 'b> {
+    recorder: &'a mut Recorder<'b>,
+    width: u16,
+    height: u16,
+}
+
+impl<'a, 'b> RecorderRenderer<'a, 'b> {
+    /// Draw into `recorder`, for a renderer of `width`×`height`.
+    pub fn wrap(recorder: &'a mut Recorder<'b>, width: u16, height: u16) -> Self {
+        Self {
+            recorder,
+            width,
+            height,
+        }
+    }
+}
+
+impl<'b> Renderer for RecorderRenderer<'_, 'b> {
+    type GlyphRenderer = Recorder<'b>;
+
+    fn new(_: u16, _: u16, _: u16, _: fearless_simd::Level, _: RenderMode) -> Self {
+        panic!(
+            "RecorderRenderer cannot be created via Renderer::new(); \
+             use RecorderRenderer::wrap() inside Renderer::record() instead"
+        );
+    }
+
+    fn fill_path(&mut self, path: &BezPath) {
+        self.recorder.fill_path(path);
+    }
+
+    fn stroke_path(&mut self, path: &BezPath) {
+        self.recorder.stroke_path(path);
+    }
+
+    fn fill_rect(&mut self, rect: &Rect) {
+        self.recorder.fill_rect(rect);
+    }
+
+    fn fill_blurred_rounded_rect(&mut self, _: &Rect, _: f32, _: f32) {
+        unimplemented!(
+            "RecorderRenderer::fill_blurred_rounded_rect: a recording can't hold blurred rounded rects"
+        )
+    }
+
+    fn stroke_rect(&mut self, rect: &Rect) {
+        self.recorder.stroke_rect(rect);
+    }
+
+    fn glyph_run(&mut self, font: &FontData) -> GlyphRunBuilder<'_, Self::GlyphRenderer> {
+        self.recorder.glyph_run(font)
+    }
+
+    fn push_layer(
+        &mut self,
+        clip_path: Option<&BezPath>,
+        blend_mode: Option<BlendMode>,
+        opacity: Option<f32>,
+        mask: Option<Mask>,
+        filter: Option<Filter>,
+    ) {
+        self.recorder
+            .push_layer(clip_path, blend_mode, opacity, mask, filter);
+    }
+
+    fn flush(&mut self) {}
+
+    fn push_clip_layer(&mut self, path: &BezPath) {
+        self.recorder.push_clip_layer(path);
+    }
+
+    fn push_clip_path(&mut self, _: &BezPath) {
+        unimplemented!(
+            "RecorderRenderer::push_clip_path: a recording can't hold clip paths; use push_clip_layer"
+        )
+    }
+
+    fn push_blend_layer(&mut self, blend_mode: BlendMode) {
+        self.recorder
+            .push_layer(None, Some(blend_mode), None, None, None);
+    }
+
+    fn push_opacity_layer(&mut self, opacity: f32) {
+        self.recorder
+            .push_layer(None, None, Some(opacity), None, None);
+    }
+
+    fn push_mask_layer(&mut self, mask: Mask) {
+        self.recorder.push_layer(None, None, None, Some(mask), None);
+    }
+
+    fn push_filter_layer(&mut self, filter: Filter) {
+        self.recorder
+            .push_layer(None, None, None, None, Some(filter));
+    }
+
+    fn pop_layer(&mut self) {
+        self.recorder.pop_layer();
+    }
+
+    fn pop_clip_path(&mut self) {
+        unimplemented!("RecorderRenderer::pop_clip_path: a recording can't hold clip paths")
+    }
+
+    fn set_stroke(&mut self, stroke: Stroke) {
+        self.recorder.set_stroke(stroke);
+    }
+
+    fn set_mask(&mut self, _: Mask) {
+        unimplemented!(
+            "RecorderRenderer::set_mask: a recording can't hold masks outside layers; use push_mask_layer"
+        )
+    }
+
+    fn set_paint(&mut self, paint: impl Into<PaintType>) {
+        self.recorder.set_paint(paint);
+    }
+
+    fn set_paint_transform(&mut self, affine: Affine) {
+        self.recorder.set_paint_transform(affine);
+    }
+
+    fn set_fill_rule(&mut self, fill_rule: Fill) {
+        self.recorder.set_fill_rule(fill_rule);
+    }
+
+    fn set_transform(&mut self, transform: Affine) {
+        self.recorder.set_transform(transform);
+    }
+
+    fn set_aliasing_threshold(&mut self, _: Option<u8>) {
+        unimplemented!(
+            "RecorderRenderer::set_aliasing_threshold: a recording can't hold an aliasing threshold"
+        )
+    }
+
+    fn set_blend_mode(&mut self, _: BlendMode) {
+        unimplemented!(
+            "RecorderRenderer::set_blend_mode: a recording can't hold blend modes outside layers; use push_blend_layer"
+        )
+    }
+
+    fn set_filter_effect(&mut self, _: Filter) {
+        unimplemented!(
+            "RecorderRenderer::set_filter_effect: a recording can't hold filters outside layers; use push_filter_layer"
+        )
+    }
+
+    fn reset_filter_effect(&mut self) {
+        unimplemented!(
+            "RecorderRenderer::reset_filter_effect: a recording can't hold filters outside layers"
+        )
+    }
+
+    fn render_to_pixmap(&self, _: &mut Pixmap) {
+        unimplemented!(
+            "RecorderRenderer::render_to_pixmap: a recording is rendered by executing it on a renderer"
+        )
+    }
+
+    fn width(&self) -> u16 {
+        self.width
+    }
+
+    fn height(&self) -> u16 {
+        self.height
+    }
+
+    fn get_image_source(&mut self, _: Arc<Pixmap>) -> ImageSource {
+        unimplemented!(
+            "RecorderRenderer::get_image_source: upload images during setup, on the renderer the recording is for"
+        )
+    }
+
+    fn record(&mut self, _: &mut Recording, _: impl FnOnce(&mut Recorder<'_>)) {
+        unimplemented!("RecorderRenderer::record: recordings can't be nested")
+    }
+
+    fn prepare_recording(&mut self, _: &mut Recording) {
+        unimplemented!("RecorderRenderer::prepare_recording: recordings can't be nested")
+    }
+
+    fn execute_recording(&mut self, _: &Recording) {
+        unimplemented!("RecorderRenderer::execute_recording: recordings can't be nested")
+    }
+}
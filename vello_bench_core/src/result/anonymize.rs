@@ -0,0 +1,447 @@
+//! Anonymization and aggregation of result files for public data collection.
+//!
+//! [`anonymize`] strips or coarsens potentially identifying fields before a
+//! result file leaves the user's machine. [`aggregate`] combines many
+//! (anonymized) submissions into per-benchmark medians and interquartile
+//! ranges, grouped by coarse environment.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{EnvironmentInfo, ResultFile, quantile};
+
+const HOUR_MS: u64 = 60 * 60 * 1000;
+
+/// What to do with the GPU adapter description.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AdapterPolicy {
+    /// Leave the adapter string untouched.
+    Keep,
+    /// Replace the adapter string with just the GPU vendor (e.g. "NVIDIA").
+    VendorOnly,
+    /// Remove the adapter string.
+    Drop,
+}
+
+/// Which fields [`anonymize`] removes or coarsens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnonymizePolicy {
+    /// Remove the user-supplied environment label.
+    pub drop_label: bool,
+    /// Reduce the user agent to browser name and major version.
+    pub coarsen_user_agent: bool,
+    /// Round all timestamps down to the hour.
+    pub round_timestamps: bool,
+    /// Treatment of the GPU adapter description.
+    pub adapter: AdapterPolicy,
+    /// Remove the recorded command line and environment variables of the
+    /// run configuration, which may contain paths.
+    pub drop_command_line: bool,
+}
+
+impl Default for AnonymizePolicy {
+    /// The strictest policy: everything is dropped or coarsened.
+    fn default() -> Self {
+        Self {
+            drop_label: true,
+            coarsen_user_agent: true,
+            round_timestamps: true,
+            adapter: AdapterPolicy::VendorOnly,
+            drop_command_line: true,
+        }
+    }
+}
+
+/// Remove or coarsen potentially identifying fields of `file` in place.
+pub fn anonymize(file: &mut ResultFile, policy: AnonymizePolicy) {
+    let env = &mut file.environment;
+
+    if policy.drop_label {
+        env.label = None;
+    }
+    if policy.coarsen_user_agent {
+        env.user_agent = env.user_agent.as_deref().map(coarsen_user_agent);
+    }
+    match policy.adapter {
+        AdapterPolicy::Keep => {}
+        AdapterPolicy::VendorOnly => {
+            env.adapter = env
+                .adapter
+                .as_deref()
+                .map(|a| adapter_vendor(a).to_string());
+        }
+        AdapterPolicy::Drop => env.adapter = None,
+    }
+
+    if policy.drop_command_line {
+        if let Some(config) = &mut file.config {
+            config.args.clear();
+            config.env.clear();
+        }
+    }
+
+    if policy.round_timestamps {
+        let round = |ms: u64| ms - ms % HOUR_MS;
+        file.started_ms = round(file.started_ms);
+        file.finished_ms = round(file.finished_ms);
+        for result in &mut file.results {
+            result.timestamp_ms = round(result.timestamp_ms);
+        }
+        for sample in &mut file.memory_timeline {
+            sample.timestamp_ms = round(sample.timestamp_ms);
+        }
+    }
+}
+
+/// Reduce a browser user agent to `"<Browser> <major>"` (e.g. `"Chrome 120"`).
+///
+/// Unrecognized user agents become `"Other"`. Already coarsened strings are
+/// returned unchanged, so the function can be applied repeatedly.
+pub fn coarsen_user_agent(ua: &str) -> String {
+    const COARSE_NAMES: &[&str] = &["Edge", "Opera", "Firefox", "Chrome", "Safari"];
+    // Order matters: Edge and Opera also advertise Chrome, and Chrome
+    // advertises Safari.
+    const BROWSERS: &[(&str, &str)] = &[
+        ("Edg/", "Edge"),
+        ("OPR/", "Opera"),
+        ("Firefox/", "Firefox"),
+        ("FxiOS/", "Firefox"),
+        ("CriOS/", "Chrome"),
+        ("Chrome/", "Chrome"),
+    ];
+
+    let major_after = |token: &str| -> Option<&str> {
+        let rest = &ua[ua.find(token)? + token.len()..];
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        (end > 0).then(|| &rest[..end])
+    };
+
+    let is_coarse = |s: &str| {
+        s == "Other"
+            || s.split_once(' ').is_some_and(|(name, major)| {
+                COARSE_NAMES.contains(&name)
+                    && !major.is_empty()
+                    && major.bytes().all(|b| b.is_ascii_digit())
+            })
+    };
+    if is_coarse(ua) {
+        return ua.to_string();
+    }
+
+    for (token, name) in BROWSERS {
+        if let Some(major) = major_after(token) {
+            return format!("{name} {major}");
+        }
+    }
+
+    // Safari reports its own version in `Version/x.y`.
+    if let Some(major) = major_after("Version/").filter(|_| ua.contains("Safari/")) {
+        return format!("Safari {major}");
+    }
+
+    "Other".to_string()
+}
+
+/// Map a GPU adapter description to a vendor name.
+fn adapter_vendor(adapter: &str) -> &'static str {
+    const VENDORS: &[(&str, &str)] = &[
+        ("nvidia", "NVIDIA"),
+        ("geforce", "NVIDIA"),
+        ("amd", "AMD"),
+        ("radeon", "AMD"),
+        ("intel", "Intel"),
+        ("apple", "Apple"),
+        ("qualcomm", "Qualcomm"),
+        ("adreno", "Qualcomm"),
+        ("mali", "ARM"),
+        ("powervr", "Imagination"),
+        ("swiftshader", "Google"),
+        ("llvmpipe", "Mesa"),
+        ("microsoft", "Microsoft"),
+    ];
+
+    let lower = adapter.to_ascii_lowercase();
+    VENDORS
+        .iter()
+        .find(|(needle, _)| lower.contains(needle))
+        .map_or("Other", |(_, vendor)| vendor)
+}
+
+/// Coarse environment key used to group submissions in [`aggregate`].
+fn environment_key(env: &EnvironmentInfo) -> String {
+    let client = env
+        .user_agent
+        .as_deref()
+        .map(coarsen_user_agent)
+        .unwrap_or_else(|| "native".to_string());
+    let adapter = env.adapter.as_deref().map_or("-", adapter_vendor);
+    format!("{} {} {client} {adapter}", env.target_arch, env.simd_level)
+}
+
+/// Aggregated statistics for one benchmark across submissions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregateEntry {
+    pub id: String,
+    pub simd_variant: String,
+    /// Number of submissions containing this benchmark.
+    pub submissions: usize,
+    /// Median of the submissions' mean times, in nanoseconds.
+    pub median_ns: f64,
+    /// First quartile of the submissions' mean times, in nanoseconds.
+    pub q1_ns: f64,
+    /// Third quartile of the submissions' mean times, in nanoseconds.
+    pub q3_ns: f64,
+    /// Interquartile range (`q3_ns - q1_ns`).
+    pub iqr_ns: f64,
+}
+
+/// All aggregated benchmarks of one coarse environment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregateGroup {
+    /// Coarse environment: arch, SIMD level, browser + major version (or
+    /// "native") and GPU vendor.
+    pub environment: String,
+    /// Number of result files in this group.
+    pub submissions: usize,
+    pub benchmarks: Vec<AggregateEntry>,
+}
+
+/// Output of [`aggregate`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AggregateReport {
+    pub groups: Vec<AggregateGroup>,
+}
+
+/// Combine result files into per-benchmark medians and interquartile ranges,
+/// grouped by coarse environment.
+///
+/// Each file contributes its mean time per benchmark as one data point.
+/// Groups and benchmarks are sorted by name.
+pub fn aggregate(files: &[ResultFile]) -> AggregateReport {
+    // environment -> (submission count, (id, simd_variant) -> means)
+    type Samples = BTreeMap<(String, String), Vec<f64>>;
+    let mut groups: BTreeMap<String, (usize, Samples)> = BTreeMap::new();
+
+    for file in files {
+        let (count, samples) = groups
+            .entry(environment_key(&file.environment))
+            .or_default();
+        *count += 1;
+        for result in &file.results {
+            samples
+                .entry((result.id.clone(), result.simd_variant.clone()))
+                .or_default()
+                .push(result.statistics.mean_ns);
+        }
+    }
+
+    let groups = groups
+        .into_iter()
+        .map(|(environment, (submissions, samples))| AggregateGroup {
+            environment,
+            submissions,
+            benchmarks: samples
+                .into_iter()
+                .map(|((id, simd_variant), mut means)| {
+                    means.sort_by(f64::total_cmp);
+                    let q1_ns = quantile(&means, 0.25);
+                    let q3_ns = quantile(&means, 0.75);
+                    AggregateEntry {
+                        id,
+                        simd_variant,
+                        submissions: means.len(),
+                        median_ns: quantile(&means, 0.5),
+                        q1_ns,
+                        q3_ns,
+                        iqr_ns: q3_ns - q1_ns,
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+
+    AggregateReport { groups }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::result::{BenchmarkResult, Statistics};
+
+    const CHROME: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                          (KHTML, like Gecko) Chrome/120.0.6099.109 Safari/537.36";
+    const EDGE: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                        (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36 Edg/120.0.2210.91";
+    const OPERA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                         (KHTML, like Gecko) Chrome/119.0.0.0 Safari/537.36 OPR/105.0.0.0";
+    const FIREFOX: &str = "Mozilla/5.0 (X11; Linux x86_64; rv:121.0) Gecko/20100101 Firefox/121.0";
+    const FIREFOX_IOS: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_2 like Mac OS X) \
+                               AppleWebKit/605.1.15 (KHTML, like Gecko) FxiOS/121.0 \
+                               Mobile/15E148 Safari/605.1.15";
+    const CHROME_IOS: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_2 like Mac OS X) \
+                              AppleWebKit/605.1.15 (KHTML, like Gecko) CriOS/120.0.6099.119 \
+                              Mobile/15E148 Safari/604.1";
+    const SAFARI: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) \
+                          AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.2 \
+                          Safari/605.1.15";
+
+    fn file(environment: EnvironmentInfo, means: &[(&str, f64)]) -> ResultFile {
+        let results = means
+            .iter()
+            .map(|&(id, mean_ns)| {
+                let (category, name) = id.split_once('/').unwrap();
+                serde_json::from_value::<BenchmarkResult>(serde_json::json!({
+                    "id": id,
+                    "category": category,
+                    "name": name,
+                    "simd_variant": environment.simd_level,
+                    "statistics": Statistics::from_mean(mean_ns, 10),
+                    "timestamp_ms": 1_700_000_123_456_u64,
+                }))
+                .unwrap()
+            })
+            .collect();
+        ResultFile {
+            schema_version: ResultFile::SCHEMA_VERSION,
+            started_ms: 1_700_000_000_001,
+            finished_ms: 1_700_003_700_000,
+            environment,
+            results,
+            memory_timeline: Vec::new(),
+            partial: false,
+            config: None,
+            health: None,
+        }
+    }
+
+    fn browser(user_agent: &str, adapter: &str) -> EnvironmentInfo {
+        EnvironmentInfo {
+            label: Some("my laptop".to_string()),
+            target_arch: "wasm32".to_string(),
+            simd_level: "wasm_simd128".to_string(),
+            user_agent: Some(user_agent.to_string()),
+            adapter: Some(adapter.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn coarsens_user_agents_to_browser_and_major_version() {
+        for (ua, coarse) in [
+            (CHROME, "Chrome 120"),
+            (EDGE, "Edge 120"),
+            (OPERA, "Opera 105"),
+            (FIREFOX, "Firefox 121"),
+            (FIREFOX_IOS, "Firefox 121"),
+            (CHROME_IOS, "Chrome 120"),
+            (SAFARI, "Safari 17"),
+            ("curl/8.4.0", "Other"),
+        ] {
+            assert_eq!(coarsen_user_agent(ua), coarse, "{ua}");
+        }
+    }
+
+    #[test]
+    fn coarsening_twice_changes_nothing() {
+        for ua in [
+            CHROME,
+            EDGE,
+            OPERA,
+            FIREFOX,
+            FIREFOX_IOS,
+            CHROME_IOS,
+            SAFARI,
+            "",
+        ] {
+            let once = coarsen_user_agent(ua);
+            assert_eq!(coarsen_user_agent(&once), once, "{ua}");
+        }
+    }
+
+    #[test]
+    fn anonymize_drops_and_coarsens() {
+        let mut file = file(browser(CHROME, "NVIDIA GeForce RTX 3080"), &[("a/b", 1.0)]);
+        anonymize(&mut file, AnonymizePolicy::default());
+        let env = &file.environment;
+        assert_eq!(env.label, None);
+        assert_eq!(env.user_agent.as_deref(), Some("Chrome 120"));
+        assert_eq!(env.adapter.as_deref(), Some("NVIDIA"));
+        assert_eq!(file.started_ms, 1_699_999_200_000);
+        assert_eq!(file.finished_ms, 1_700_002_800_000);
+        assert_eq!(file.results[0].timestamp_ms, 1_699_999_200_000);
+    }
+
+    #[test]
+    fn aggregates_quartiles_per_benchmark() {
+        let env = || browser(CHROME, "Intel UHD 620");
+        let files: Vec<ResultFile> = [100.0, 500.0, 300.0, 200.0, 400.0]
+            .iter()
+            .map(|&mean| {
+                file(
+                    env(),
+                    &[("vello_cpu/tiger", mean), ("vello_cpu/rects", 10.0)],
+                )
+            })
+            .collect();
+
+        let report = aggregate(&files);
+        assert_eq!(report.groups.len(), 1);
+        let group = &report.groups[0];
+        assert_eq!(group.environment, "wasm32 wasm_simd128 Chrome 120 Intel");
+        assert_eq!(group.submissions, 5);
+        // Sorted by ID.
+        let rects = &group.benchmarks[0];
+        assert_eq!(rects.id, "vello_cpu/rects");
+        assert_eq!(rects.iqr_ns, 0.0);
+        let tiger = &group.benchmarks[1];
+        assert_eq!(tiger.id, "vello_cpu/tiger");
+        assert_eq!(tiger.submissions, 5);
+        assert_eq!(tiger.median_ns, 300.0);
+        assert_eq!(tiger.q1_ns, 200.0);
+        assert_eq!(tiger.q3_ns, 400.0);
+        assert_eq!(tiger.iqr_ns, 200.0);
+    }
+
+    #[test]
+    fn aggregate_interpolates_between_submissions() {
+        let files: Vec<ResultFile> = [4.0, 1.0, 3.0, 2.0]
+            .iter()
+            .map(|&mean| file(browser(FIREFOX, "Apple M2"), &[("a/b", mean)]))
+            .collect();
+        let entry = &aggregate(&files).groups[0].benchmarks[0];
+        assert_eq!(entry.median_ns, 2.5);
+        assert!((entry.q1_ns - 1.75).abs() < 1e-9);
+        assert!((entry.q3_ns - 3.25).abs() < 1e-9);
+        assert!((entry.iqr_ns - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn aggregate_groups_by_coarse_environment() {
+        // Minor browser versions and GPU models share a group.
+        let files = [
+            file(browser(CHROME, "NVIDIA GeForce RTX 3080"), &[("a/b", 1.0)]),
+            file(
+                browser(&CHROME.replace("6099.109", "6099.217"), "NVIDIA RTX 4090"),
+                &[("a/b", 3.0)],
+            ),
+            file(browser(FIREFOX, "NVIDIA GeForce RTX 3080"), &[("a/b", 2.0)]),
+        ];
+        let report = aggregate(&files);
+        let groups: Vec<_> = report
+            .groups
+            .iter()
+            .map(|group| (group.environment.as_str(), group.submissions))
+            .collect();
+        assert_eq!(
+            groups,
+            [
+                ("wasm32 wasm_simd128 Chrome 120 NVIDIA", 2),
+                ("wasm32 wasm_simd128 Firefox 121 NVIDIA", 1),
+            ]
+        );
+        assert_eq!(report.groups[0].benchmarks[0].median_ns, 2.0);
+    }
+}
@@ -0,0 +1,819 @@
+//! Compact binary encoding of [`ResultFile`], for uploads over slow
+//! connections.
+//!
+//! Layout (all integers are LEB128 varints unless noted):
+//!
+//! ```text
+//! magic           b"VBRB"
+//! format_version  u8
+//! schema_version, started_ms, finished_ms
+//! environment     label?, target_arch, simd_level, user_agent?, cpu_model?,
+//!                 core_count?, adapter?,
+//!                 crate_version?                      (version 9 and later)
+//!                 available_simd_levels count, then each string
+//!                                                     (version 11 and later)
+//! strings         count, then each string
+//! results         count, then per result:
+//!                   flags u8 (bit 0: throughput, bit 1: render_mode,
+//!                             bit 2: single-shot, bit 3: warm-up proxy,
+//!                             bit 4: ran concurrently, bit 5: num_threads,
+//!                             bit 6: distinct warm-up input,
+//!                             bit 7: spread)
+//!                   id, category, name, simd_variant  (string table indices)
+//!                   mean_ns f64 (little endian), iterations
+//!                   median_ns, p95_ns, min_ns, max_ns, std_dev_ns
+//!                                                     (f64 each, if flagged;
+//!                                                      version 6 and later)
+//!                   throughput                        (if flagged)
+//!                   render_mode                       (string index, if flagged)
+//!                   num_threads                       (if flagged; version 4
+//!                                                      and later)
+//!                   sync_mode u8 (0: none, 1: finish, 2: fence;
+//!                                 version 7 and later)
+//!                   gpu_time_ns?                      (rounded to whole
+//!                                                      nanoseconds; version 8
+//!                                                      and later)
+//!                   timestamp_ms delta (zigzag, relative to the previous
+//!                   result, or `started_ms` for the first)
+//! memory timeline count, then per sample (version 2 and later):
+//!                   after                             (string table index)
+//!                   timestamp_ms delta                (as for results)
+//!                   wasm_memory_bytes?, js_heap_bytes?, rss_bytes?
+//! file flags      u8 (bit 0: partial, bit 1: config; version 3 and later)
+//! config          (if flagged; version 5 and later)
+//!                   args count, then each string
+//!                   env count, then each name and value
+//!                   warmup, iterations
+//!                   measurement_mode u8 (0: full, 1: single-shot)
+//!                   filter?, gpu_timeout_ms?
+//!                   shard count (0: none), then if non-zero the count of
+//!                   shard indices and each index     (version 10 and later)
+//! ```
+//!
+//! Per-iteration samples, the statistics before outliers were removed and
+//! their count, whether a run was cancelled, the warm-up statistics,
+//! recorded marks, deltas and the health report are not stored. The spread
+//! computed from the samples is, unless there is none.
+//!
+//! Strings are length-prefixed UTF-8. Optional strings store `len + 1`, with
+//! `0` meaning absent. Strings repeated across results (categories, SIMD
+//! variants, ...) are stored once in the string table.
+
+use rustc_hash::FxHashMap;
+
+use super::{
+    BenchmarkResult, EnvironmentInfo, MeasurementMode, ResultFile, RunConfig, ShardSet, Statistics,
+    SyncMode, Throughput, WarmupInput,
+};
+use crate::memory::MemorySample;
+
+/// Leading bytes of every binary result file.
+pub const MAGIC: &[u8; 4] = b"VBRB";
+
+/// Version of the binary layout, independent of [`ResultFile::SCHEMA_VERSION`].
+const FORMAT_VERSION: u8 = 11;
+
+/// First format version with a memory timeline.
+const MEMORY_TIMELINE_VERSION: u8 = 2;
+
+/// First format version with file flags.
+const FILE_FLAGS_VERSION: u8 = 3;
+
+/// First format version with a run configuration.
+const CONFIG_VERSION: u8 = 5;
+
+/// First format version with the GPU sync mode of each result.
+const SYNC_MODE_VERSION: u8 = 7;
+
+/// First format version with the GPU time of each result.
+const GPU_TIME_VERSION: u8 = 8;
+
+/// First format version with the crate version of the environment.
+const CRATE_VERSION_VERSION: u8 = 9;
+
+/// First format version with the shards of a run configuration.
+const SHARDS_VERSION: u8 = 10;
+
+/// First format version with the available SIMD levels of the environment.
+const AVAILABLE_SIMD_LEVELS_VERSION: u8 = 11;
+
+const FLAG_THROUGHPUT: u8 = 1 << 0;
+const FLAG_RENDER_MODE: u8 = 1 << 1;
+const FLAG_SINGLE_SHOT: u8 = 1 << 2;
+const FLAG_WARMUP_PROXY: u8 = 1 << 3;
+const FLAG_RAN_CONCURRENTLY: u8 = 1 << 4;
+const FLAG_NUM_THREADS: u8 = 1 << 5;
+const FLAG_DISTINCT_WARMUP_INPUT: u8 = 1 << 6;
+const FLAG_SPREAD: u8 = 1 << 7;
+
+const FILE_FLAG_PARTIAL: u8 = 1 << 0;
+const FILE_FLAG_CONFIG: u8 = 1 << 1;
+
+/// Whether `bytes` start with the binary result file magic.
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+impl ResultFile {
+    /// Encode as the compact binary format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut strings = StringTable::default();
+        for r in &self.results {
+            strings.intern(&r.id);
+            strings.intern(&r.category);
+            strings.intern(&r.name);
+            strings.intern(&r.simd_variant);
+            if let Some(mode) = &r.render_mode {
+                strings.intern(mode);
+            }
+        }
+        for sample in &self.memory_timeline {
+            strings.intern(&sample.after);
+        }
+
+        let mut w = Writer::default();
+        w.buf.extend_from_slice(MAGIC);
+        w.buf.push(FORMAT_VERSION);
+        w.varint(self.schema_version.into());
+        w.varint(self.started_ms);
+        w.varint(self.finished_ms);
+
+        let env = &self.environment;
+        w.opt_str(env.label.as_deref());
+        w.str(&env.target_arch);
+        w.str(&env.simd_level);
+        w.opt_str(env.user_agent.as_deref());
+        w.opt_str(env.cpu_model.as_deref());
+        w.opt_varint(env.core_count.map(u64::from));
+        w.opt_str(env.adapter.as_deref());
+        w.opt_str(env.crate_version.as_deref());
+        w.varint(env.available_simd_levels.len() as u64);
+        for level in &env.available_simd_levels {
+            w.str(level);
+        }
+
+        w.varint(strings.list.len() as u64);
+        for s in &strings.list {
+            w.str(s);
+        }
+
+        w.varint(self.results.len() as u64);
+        let mut last_timestamp = self.started_ms;
+        for r in &self.results {
+            let mut flags = 0;
+            if r.throughput.is_some() {
+                flags |= FLAG_THROUGHPUT;
+            }
+            if r.render_mode.is_some() {
+                flags |= FLAG_RENDER_MODE;
+            }
+            if r.is_estimate() {
+                flags |= FLAG_SINGLE_SHOT;
+            }
+            if r.warmup_proxy {
+                flags |= FLAG_WARMUP_PROXY;
+            }
+            if r.ran_concurrently {
+                flags |= FLAG_RAN_CONCURRENTLY;
+            }
+            if r.num_threads.is_some() {
+                flags |= FLAG_NUM_THREADS;
+            }
+            if r.warmup_input == WarmupInput::Distinct {
+                flags |= FLAG_DISTINCT_WARMUP_INPUT;
+            }
+            if r.statistics.has_spread() {
+                flags |= FLAG_SPREAD;
+            }
+            w.buf.push(flags);
+
+            w.varint(strings.index(&r.id));
+            w.varint(strings.index(&r.category));
+            w.varint(strings.index(&r.name));
+            w.varint(strings.index(&r.simd_variant));
+            w.buf.extend_from_slice(&r.statistics.mean_ns.to_le_bytes());
+            w.varint(r.statistics.iterations as u64);
+            if r.statistics.has_spread() {
+                let s = &r.statistics;
+                for v in [s.median_ns, s.p95_ns, s.min_ns, s.max_ns, s.std_dev_ns] {
+                    w.buf.extend_from_slice(&v.to_le_bytes());
+                }
+            }
+            if let Some(Throughput::Elements(n)) = r.throughput {
+                w.varint(n);
+            }
+            if let Some(mode) = &r.render_mode {
+                w.varint(strings.index(mode));
+            }
+            if let Some(threads) = r.num_threads {
+                w.varint(u64::from(threads));
+            }
+            w.buf.push(match r.sync_mode {
+                None => 0,
+                Some(SyncMode::Finish) => 1,
+                Some(SyncMode::Fence) => 2,
+            });
+            w.opt_varint(r.statistics.gpu_time_ns.map(|ns| ns.round() as u64));
+            w.varint(zigzag(r.timestamp_ms.wrapping_sub(last_timestamp) as i64));
+            last_timestamp = r.timestamp_ms;
+        }
+
+        w.varint(self.memory_timeline.len() as u64);
+        let mut last_timestamp = self.started_ms;
+        for sample in &self.memory_timeline {
+            w.varint(strings.index(&sample.after));
+            w.varint(zigzag(
+                sample.timestamp_ms.wrapping_sub(last_timestamp) as i64
+            ));
+            last_timestamp = sample.timestamp_ms;
+            w.opt_varint(sample.wasm_memory_bytes);
+            w.opt_varint(sample.js_heap_bytes);
+            w.opt_varint(sample.rss_bytes);
+        }
+
+        let mut file_flags = 0;
+        if self.partial {
+            file_flags |= FILE_FLAG_PARTIAL;
+        }
+        if self.config.is_some() {
+            file_flags |= FILE_FLAG_CONFIG;
+        }
+        w.buf.push(file_flags);
+
+        if let Some(config) = &self.config {
+            w.varint(config.args.len() as u64);
+            for arg in &config.args {
+                w.str(arg);
+            }
+            w.varint(config.env.len() as u64);
+            for (name, value) in &config.env {
+                w.str(name);
+                w.str(value);
+            }
+            w.varint(config.warmup);
+            w.varint(config.iterations);
+            w.buf.push(match config.measurement_mode {
+                MeasurementMode::Full => 0,
+                MeasurementMode::SingleShot => 1,
+            });
+            w.opt_str(config.filter.as_deref());
+            w.opt_varint(config.gpu_timeout_ms);
+            match &config.shards {
+                Some(shards) => {
+                    w.varint(shards.count.into());
+                    w.varint(shards.indices.len() as u64);
+                    for &index in &shards.indices {
+                        w.varint(index.into());
+                    }
+                }
+                None => w.varint(0),
+            }
+        }
+
+        w.buf
+    }
+
+    /// Decode from the compact binary format produced by [`Self::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut r = Reader { bytes, pos: 0 };
+
+        if !is_binary(bytes) {
+            return Err("Not a binary result file".to_string());
+        }
+        r.pos = MAGIC.len();
+        let version = r.u8()?;
+        if version == 0 || version > FORMAT_VERSION {
+            return Err(format!(
+                "Unsupported binary result format version {version}"
+            ));
+        }
+
+        let schema_version = r.varint_u32()?;
+        let started_ms = r.varint()?;
+        let finished_ms = r.varint()?;
+
+        let mut environment = EnvironmentInfo {
+            label: r.opt_str()?,
+            target_arch: r.str()?,
+            simd_level: r.str()?,
+            user_agent: r.opt_str()?,
+            cpu_model: r.opt_str()?,
+            core_count: r
+                .opt_varint()?
+                .map(u32::try_from)
+                .transpose()
+                .map_err(|_| "Core count out of range".to_string())?,
+            adapter: r.opt_str()?,
+            crate_version: if version >= CRATE_VERSION_VERSION {
+                r.opt_str()?
+            } else {
+                None
+            },
+            available_simd_levels: Vec::new(),
+        };
+        if version >= AVAILABLE_SIMD_LEVELS_VERSION {
+            let level_count = r.len()?;
+            environment.available_simd_levels = (0..level_count)
+                .map(|_| r.str())
+                .collect::<Result<_, _>>()?;
+        }
+
+        let string_count = r.len()?;
+        let strings = (0..string_count)
+            .map(|_| r.str())
+            .collect::<Result<Vec<_>, _>>()?;
+        let string = |index: u64| {
+            strings
+                .get(index as usize)
+                .cloned()
+                .ok_or_else(|| format!("String index {index} out of range"))
+        };
+
+        let result_count = r.len()?;
+        let mut results = Vec::with_capacity(result_count);
+        let mut last_timestamp = started_ms;
+        for _ in 0..result_count {
+            let flags = r.u8()?;
+            let id = string(r.varint()?)?;
+            let category = string(r.varint()?)?;
+            let name = string(r.varint()?)?;
+            let simd_variant = string(r.varint()?)?;
+            let mean_ns = r.f64()?;
+            let iterations = r.varint()? as usize;
+            let mut statistics = Statistics::from_mean(mean_ns, iterations);
+            if flags & FLAG_SPREAD != 0 {
+                statistics.median_ns = r.f64()?;
+                statistics.p95_ns = r.f64()?;
+                statistics.min_ns = r.f64()?;
+                statistics.max_ns = r.f64()?;
+                statistics.std_dev_ns = r.f64()?;
+            }
+            let throughput = if flags & FLAG_THROUGHPUT != 0 {
+                Some(Throughput::Elements(r.varint()?))
+            } else {
+                None
+            };
+            let render_mode = if flags & FLAG_RENDER_MODE != 0 {
+                Some(string(r.varint()?)?)
+            } else {
+                None
+            };
+            let num_threads = if flags & FLAG_NUM_THREADS != 0 {
+                let threads = u16::try_from(r.varint()?)
+                    .map_err(|_| "Thread count out of range".to_string())?;
+                Some(threads)
+            } else {
+                None
+            };
+            let sync_mode = if version >= SYNC_MODE_VERSION {
+                match r.u8()? {
+                    0 => None,
+                    1 => Some(SyncMode::Finish),
+                    2 => Some(SyncMode::Fence),
+                    mode => return Err(format!("Invalid sync mode {mode}")),
+                }
+            } else {
+                None
+            };
+            if version >= GPU_TIME_VERSION {
+                statistics.gpu_time_ns = r.opt_varint()?.map(|ns| ns as f64);
+            }
+            let measurement_mode = if flags & FLAG_SINGLE_SHOT != 0 {
+                MeasurementMode::SingleShot
+            } else {
+                MeasurementMode::Full
+            };
+            let warmup_input = if flags & FLAG_DISTINCT_WARMUP_INPUT != 0 {
+                WarmupInput::Distinct
+            } else {
+                WarmupInput::Same
+            };
+            let timestamp_ms = last_timestamp.wrapping_add(unzigzag(r.varint()?) as u64);
+            last_timestamp = timestamp_ms;
+
+            results.push(BenchmarkResult {
+                id,
+                category,
+                name,
+                simd_variant,
+                statistics,
+                raw_statistics: None,
+                outliers_removed: None,
+                cancelled: false,
+                warmup: None,
+                throughput,
+                render_mode,
+                measurement_mode,
+                warmup_proxy: flags & FLAG_WARMUP_PROXY != 0,
+                animated: false,
+                warmup_input,
+                ran_concurrently: flags & FLAG_RAN_CONCURRENTLY != 0,
+                num_threads,
+                sync_mode,
+                marks: None,
+                delta: None,
+                canvas_overhead: None,
+                timestamp_ms,
+            });
+        }
+
+        let mut memory_timeline = Vec::new();
+        if version >= MEMORY_TIMELINE_VERSION {
+            let sample_count = r.len()?;
+            let mut last_timestamp = started_ms;
+            for _ in 0..sample_count {
+                let after = string(r.varint()?)?;
+                let timestamp_ms = last_timestamp.wrapping_add(unzigzag(r.varint()?) as u64);
+                last_timestamp = timestamp_ms;
+                memory_timeline.push(MemorySample {
+                    after,
+                    timestamp_ms,
+                    wasm_memory_bytes: r.opt_varint()?,
+                    js_heap_bytes: r.opt_varint()?,
+                    rss_bytes: r.opt_varint()?,
+                });
+            }
+        }
+
+        let file_flags = if version >= FILE_FLAGS_VERSION {
+            r.u8()?
+        } else {
+            0
+        };
+
+        let config = if version >= CONFIG_VERSION && file_flags & FILE_FLAG_CONFIG != 0 {
+            let arg_count = r.len()?;
+            let args = (0..arg_count)
+                .map(|_| r.str())
+                .collect::<Result<Vec<_>, _>>()?;
+            let env_count = r.len()?;
+            let env = (0..env_count)
+                .map(|_| Ok((r.str()?, r.str()?)))
+                .collect::<Result<_, String>>()?;
+            let warmup = r.varint()?;
+            let iterations = r.varint()?;
+            let measurement_mode = match r.u8()? {
+                0 => MeasurementMode::Full,
+                1 => MeasurementMode::SingleShot,
+                mode => return Err(format!("Invalid measurement mode {mode}")),
+            };
+            Some(RunConfig {
+                args,
+                env,
+                warmup,
+                iterations,
+                measurement_mode,
+                filter: r.opt_str()?,
+                gpu_timeout_ms: r.opt_varint()?,
+                shards: if version >= SHARDS_VERSION {
+                    match r.varint_u32()? {
+                        0 => None,
+                        count => {
+                            let index_count = r.len()?;
+                            let indices = (0..index_count)
+                                .map(|_| r.varint_u32())
+                                .collect::<Result<_, _>>()?;
+                            Some(ShardSet { count, indices })
+                        }
+                    }
+                } else {
+                    None
+                },
+            })
+        } else {
+            None
+        };
+
+        if r.pos != bytes.len() {
+            return Err(format!(
+                "{} trailing bytes after binary result file",
+                bytes.len() - r.pos
+            ));
+        }
+
+        Ok(Self {
+            schema_version,
+            started_ms,
+            finished_ms,
+            environment,
+            results,
+            memory_timeline,
+            partial: file_flags & FILE_FLAG_PARTIAL != 0,
+            config,
+            health: None,
+        })
+    }
+}
+
+#[derive(Default)]
+struct StringTable<'a> {
+    list: Vec<&'a str>,
+    indices: FxHashMap<&'a str, u64>,
+}
+
+impl<'a> StringTable<'a> {
+    fn intern(&mut self, s: &'a str) {
+        let next = self.list.len() as u64;
+        self.indices.entry(s).or_insert_with(|| {
+            self.list.push(s);
+            next
+        });
+    }
+
+    fn index(&self, s: &str) -> u64 {
+        self.indices[s]
+    }
+}
+
+#[derive(Default)]
+struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    fn varint(&mut self, mut v: u64) {
+        while v >= 0x80 {
+            self.buf.push(v as u8 | 0x80);
+            v >>= 7;
+        }
+        self.buf.push(v as u8);
+    }
+
+    fn opt_varint(&mut self, v: Option<u64>) {
+        match v {
+            Some(v) => {
+                self.buf.push(1);
+                self.varint(v);
+            }
+            None => self.buf.push(0),
+        }
+    }
+
+    fn str(&mut self, s: &str) {
+        self.varint(s.len() as u64);
+        self.buf.extend_from_slice(s.as_bytes());
+    }
+
+    fn opt_str(&mut self, s: Option<&str>) {
+        match s {
+            Some(s) => {
+                self.varint(s.len() as u64 + 1);
+                self.buf.extend_from_slice(s.as_bytes());
+            }
+            None => self.varint(0),
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, n: usize) -> Result<&[u8], String> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or("Unexpected end of binary result file")?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn f64(&mut self) -> Result<f64, String> {
+        let bytes = self.take(8)?;
+        Ok(f64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0_u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("Varint too long".to_string())
+    }
+
+    fn varint_u32(&mut self) -> Result<u32, String> {
+        u32::try_from(self.varint()?).map_err(|_| "Value out of range".to_string())
+    }
+
+    /// A count or length, bounded by the remaining input so that corrupt
+    /// data cannot trigger huge allocations.
+    fn len(&mut self) -> Result<usize, String> {
+        let len = self.varint()?;
+        if len > (self.bytes.len() - self.pos) as u64 {
+            return Err(format!("Length {len} exceeds remaining input"));
+        }
+        Ok(len as usize)
+    }
+
+    fn opt_varint(&mut self) -> Result<Option<u64>, String> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => self.varint().map(Some),
+            tag => Err(format!("Invalid option tag {tag}")),
+        }
+    }
+
+    fn utf8(&mut self, len: usize) -> Result<String, String> {
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|e| format!("Invalid UTF-8 string: {e}"))
+    }
+
+    fn str(&mut self) -> Result<String, String> {
+        let len = self.len()?;
+        self.utf8(len)
+    }
+
+    fn opt_str(&mut self) -> Result<Option<String>, String> {
+        match self.varint()? {
+            0 => Ok(None),
+            n => {
+                let len = usize::try_from(n - 1).map_err(|_| "Length out of range")?;
+                self.utf8(len).map(Some)
+            }
+        }
+    }
+}
+
+fn zigzag(v: i64) -> u64 {
+    ((v << 1) ^ (v >> 63)) as u64
+}
+
+fn unzigzag(v: u64) -> i64 {
+    (v >> 1) as i64 ^ -((v & 1) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(id: &str, statistics: Statistics, timestamp_ms: u64) -> BenchmarkResult {
+        let (category, name) = id.rsplit_once('/').unwrap();
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "category": category,
+            "name": name,
+            "simd_variant": "avx2",
+            "statistics": statistics,
+            "timestamp_ms": timestamp_ms,
+        }))
+        .unwrap()
+    }
+
+    /// A file using every field the binary format keeps.
+    fn full_file() -> ResultFile {
+        let spread = Statistics {
+            mean_ns: 1250.5,
+            iterations: 100,
+            median_ns: 1200.0,
+            p95_ns: 1500.25,
+            min_ns: 1100.0,
+            max_ns: 1800.0,
+            std_dev_ns: 95.5,
+            samples: Vec::new(),
+            gpu_time_ns: Some(900.0),
+        };
+        let mut cpu = result("vello_cpu/tiger", spread, 1_700_000_000_500);
+        cpu.throughput = Some(Throughput::Elements(4000));
+        cpu.render_mode = Some("speed".to_string());
+        cpu.num_threads = Some(4);
+        cpu.warmup_proxy = true;
+        cpu.ran_concurrently = true;
+        cpu.warmup_input = WarmupInput::Distinct;
+        let mut gpu = result(
+            "vello_hybrid/tiger",
+            Statistics::from_mean(2000.0, 50),
+            1_700_000_000_200,
+        );
+        gpu.sync_mode = Some(SyncMode::Fence);
+        let mut estimate = result(
+            "vello_cpu/paris",
+            Statistics::from_mean(5e6, 1),
+            1_700_000_000_900,
+        );
+        estimate.measurement_mode = MeasurementMode::SingleShot;
+
+        ResultFile {
+            schema_version: ResultFile::SCHEMA_VERSION,
+            started_ms: 1_700_000_000_000,
+            finished_ms: 1_700_000_001_000,
+            environment: EnvironmentInfo {
+                label: Some("laptop, on battery".to_string()),
+                target_arch: "x86_64".to_string(),
+                simd_level: "avx2".to_string(),
+                available_simd_levels: vec![
+                    "avx2".to_string(),
+                    "sse42".to_string(),
+                    "scalar".to_string(),
+                ],
+                user_agent: None,
+                cpu_model: Some("Some CPU @ 3.00GHz".to_string()),
+                core_count: Some(16),
+                adapter: Some("Some GPU (Vulkan)".to_string()),
+                crate_version: Some("0.1.0".to_string()),
+            },
+            results: vec![cpu, gpu, estimate],
+            memory_timeline: vec![MemorySample {
+                after: "vello_cpu/tiger".to_string(),
+                timestamp_ms: 1_700_000_000_600,
+                wasm_memory_bytes: None,
+                js_heap_bytes: None,
+                rss_bytes: Some(123_456_789),
+            }],
+            partial: true,
+            config: Some(RunConfig {
+                args: vec![
+                    "run".to_string(),
+                    "--filter".to_string(),
+                    "tag:cpu".to_string(),
+                ],
+                env: [("VELLO_BENCH_SEED".to_string(), "7".to_string())].into(),
+                warmup: 10,
+                iterations: 100,
+                measurement_mode: MeasurementMode::Full,
+                filter: Some("tag:cpu".to_string()),
+                gpu_timeout_ms: Some(10_000),
+                shards: Some(ShardSet::single(1, 4)),
+            }),
+            health: None,
+        }
+    }
+
+    fn json(file: &ResultFile) -> String {
+        file.to_json().unwrap()
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let file = full_file();
+        let bytes = file.to_bytes();
+        assert!(is_binary(&bytes));
+        assert_eq!(json(&ResultFile::from_bytes(&bytes).unwrap()), json(&file));
+        assert_eq!(json(&ResultFile::parse(&bytes).unwrap()), json(&file));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let file = full_file();
+        let text = json(&file);
+        assert!(!is_binary(text.as_bytes()));
+        assert_eq!(json(&ResultFile::from_json(&text).unwrap()), text);
+        assert_eq!(json(&ResultFile::parse(text.as_bytes()).unwrap()), text);
+    }
+
+    #[test]
+    fn json_to_binary_and_back() {
+        let text = json(&full_file());
+        let bytes = ResultFile::parse(text.as_bytes()).unwrap().to_bytes();
+        assert_eq!(json(&ResultFile::parse(&bytes).unwrap()), text);
+    }
+
+    #[test]
+    fn binary_keeps_spread_without_samples() {
+        let samples: Vec<f64> = (0..1000)
+            .map(|i| 1000.0 + f64::from(i * 37 % 101))
+            .collect();
+        let statistics = Statistics::from_mean(1050.0, 1000).with_samples(samples);
+        let mut file = full_file();
+        file.results = vec![result("vello_cpu/tiger", statistics.clone(), 0)];
+
+        let pretty = json(&file);
+        let bytes = file.to_bytes();
+        assert!(
+            bytes.len() * 5 <= pretty.len(),
+            "binary is {} bytes, pretty JSON {} bytes",
+            bytes.len(),
+            pretty.len()
+        );
+
+        let decoded = &ResultFile::from_bytes(&bytes).unwrap().results[0].statistics;
+        assert!(decoded.samples.is_empty());
+        assert_eq!(decoded.mean_ns, statistics.mean_ns);
+        assert_eq!(decoded.median_ns, statistics.median_ns);
+        assert_eq!(decoded.p95_ns, statistics.p95_ns);
+        assert_eq!(decoded.std_dev_ns, statistics.std_dev_ns);
+    }
+
+    #[test]
+    fn rejects_truncated_and_newer_files() {
+        let bytes = full_file().to_bytes();
+        assert!(ResultFile::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        let mut newer = bytes.clone();
+        newer[MAGIC.len()] = FORMAT_VERSION + 1;
+        assert!(ResultFile::from_bytes(&newer).is_err());
+        let mut trailing = bytes;
+        trailing.push(0);
+        assert!(ResultFile::from_bytes(&trailing).is_err());
+    }
+}
@@ -0,0 +1,773 @@
+//! Per-benchmark comparison of a result file against a baseline, e.g. a
+//! reference machine (see [`reference`](crate::reference)).
+//!
+//! Benchmarks are matched by ID and render mode. The SIMD level is not part
+//! of the match, so a browser on a phone compares against whatever level the
+//! baseline machine used. Single-shot estimates are left out on both sides, and so are GPU
+//! benchmarks that the two files measured with different [`SyncMode`]s;
+//! [`Comparison::sync_mode_warnings`] names them. Results that don't record a
+//! sync mode count as [`SyncMode::Finish`], which GPU benchmarks used before
+//! it was recorded.
+//!
+//! Runs produced under different settings (see [`RunConfig`]) aren't directly
+//! comparable; [`Comparison::config_warnings`] lists how they differ.
+//!
+//! [`compare_reports`] is for two runs on the same machine instead, e.g. a
+//! pull request against `main`: it matches results by ID, SIMD variant and
+//! render mode and flags the ones that changed by more than a threshold. It warns when
+//! the two runs have different environment
+//! [fingerprints](EnvironmentInfo::fingerprint), e.g. after a browser update,
+//! and when the baseline holds only some shards of a sharded run.
+
+use serde::{Deserialize, Serialize};
+
+use std::fmt::Write as _;
+
+use super::{BenchmarkResult, EnvironmentInfo, ResultFile, RunConfig, ShardSet, SyncMode};
+
+/// One benchmark present in both files.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkRatio {
+    /// Full benchmark ID.
+    pub id: String,
+    /// Render mode of both sides, see [`BenchmarkResult::render_mode`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub render_mode: Option<String>,
+    /// Mean time of the compared file, in nanoseconds.
+    pub mean_ns: f64,
+    /// Mean time of the baseline, in nanoseconds.
+    pub baseline_mean_ns: f64,
+    /// `mean_ns / baseline_mean_ns`; above 1 is slower than the baseline.
+    pub ratio: f64,
+    /// How both sides waited for the GPU. A benchmark that both files
+    /// measured in both modes is listed once per mode.
+    #[serde(default, skip_serializing_if = "SyncMode::is_finish")]
+    pub sync_mode: SyncMode,
+}
+
+/// Result of [`compare`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Comparison {
+    /// Environment of the compared file, to label it.
+    pub environment: EnvironmentInfo,
+    /// Environment of the baseline, to label it.
+    pub baseline_environment: EnvironmentInfo,
+    /// Benchmarks present in both files, in the order of the compared file.
+    pub benchmarks: Vec<BenchmarkRatio>,
+    /// Geometric mean of the [`BenchmarkRatio::ratio`]s, or `None` if no
+    /// benchmark is in both files.
+    pub geometric_mean_ratio: Option<f64>,
+    /// How the configurations of the two runs differ, compared file first,
+    /// see [`RunConfig::differences`]. Empty if they match or either file
+    /// has none recorded.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub config_warnings: Vec<String>,
+    /// Benchmarks in both files that were left out because they waited for
+    /// the GPU differently, see [`BenchmarkResult::sync_mode`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sync_mode_warnings: Vec<String>,
+}
+
+/// Compare `file` against `baseline`. Where a file has several results for
+/// an ID and render mode, e.g. one per SIMD level, the fastest one with the
+/// same sync mode counts.
+pub fn compare(file: &ResultFile, baseline: &ResultFile) -> Comparison {
+    let baseline_means = fastest_means(&baseline.results, compare_key);
+    let means = fastest_means(&file.results, compare_key);
+    let benchmarks: Vec<BenchmarkRatio> = means
+        .iter()
+        .filter_map(|&(key, mean_ns)| {
+            let baseline_mean_ns = baseline_means.iter().find(|(k, _)| *k == key)?.1;
+            let (id, render_mode, sync_mode) = key;
+            Some(BenchmarkRatio {
+                id: id.to_string(),
+                render_mode: render_mode.map(str::to_string),
+                mean_ns,
+                baseline_mean_ns,
+                ratio: mean_ns / baseline_mean_ns,
+                sync_mode,
+            })
+        })
+        .collect();
+
+    let geometric_mean_ratio = (!benchmarks.is_empty()).then(|| {
+        let log_sum: f64 = benchmarks.iter().map(|b| b.ratio.ln()).sum();
+        (log_sum / benchmarks.len() as f64).exp()
+    });
+
+    Comparison {
+        environment: file.environment.clone(),
+        baseline_environment: baseline.environment.clone(),
+        benchmarks,
+        geometric_mean_ratio,
+        config_warnings: config_warnings(file.config.as_ref(), baseline.config.as_ref()),
+        sync_mode_warnings: sync_mode_warnings(&means, &baseline_means),
+    }
+}
+
+/// A benchmark at one SIMD level, as [`compare_reports`] matches results.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResultKey {
+    /// Full benchmark ID.
+    pub id: String,
+    /// SIMD level suffix, e.g. `avx2`.
+    pub simd_variant: String,
+    /// Render mode, for benchmarks where it applies; a full matrix run
+    /// measures `vello_cpu` scenes in each one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub render_mode: Option<String>,
+    /// How the results waited for the GPU; results measured in different
+    /// modes don't match.
+    #[serde(default, skip_serializing_if = "SyncMode::is_finish")]
+    pub sync_mode: SyncMode,
+}
+
+impl ResultKey {
+    /// The key `result` is matched by.
+    fn of(result: &BenchmarkResult) -> Self {
+        Self {
+            id: result.id.clone(),
+            simd_variant: result.simd_variant.clone(),
+            render_mode: result.render_mode.clone(),
+            sync_mode: result.sync_mode.unwrap_or_default(),
+        }
+    }
+}
+
+impl std::fmt::Display for ResultKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} [{}", self.id, self.simd_variant)?;
+        if let Some(mode) = &self.render_mode {
+            write!(f, ", {mode}")?;
+        }
+        if !self.sync_mode.is_finish() {
+            write!(f, ", {} sync", self.sync_mode.name())?;
+        }
+        write!(f, "]")
+    }
+}
+
+/// Whether a benchmark got slower or faster by more than the threshold of
+/// [`compare_reports`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Change {
+    Regression,
+    Improvement,
+    Unchanged,
+}
+
+/// One benchmark present in both runs of [`compare_reports`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultChange {
+    #[serde(flatten)]
+    pub key: ResultKey,
+    /// Mean time of the baseline, in nanoseconds.
+    pub baseline_mean_ns: f64,
+    /// Mean time of the candidate, in nanoseconds.
+    pub candidate_mean_ns: f64,
+    /// `candidate_mean_ns / baseline_mean_ns`; above 1 is slower.
+    pub ratio: f64,
+    /// Change of the mean time in percent; positive means slower.
+    pub change_pct: f64,
+    /// Whether `change_pct` is beyond the threshold, and which way.
+    pub change: Change,
+}
+
+/// A reason the runs of [`compare_reports`] may not be comparable.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ComparisonWarning {
+    /// The runs have different [`EnvironmentInfo::fingerprint`]s.
+    EnvironmentMismatch {
+        baseline_fingerprint: String,
+        candidate_fingerprint: String,
+        /// The fingerprinted fields that differ, see
+        /// [`EnvironmentInfo::fingerprint_differences`].
+        fields: Vec<String>,
+    },
+    /// The baseline holds only some shards of a sharded run, and the
+    /// candidate has benchmarks of the other shards, which the baseline
+    /// lacks for that reason rather than because they are new.
+    MissingShards {
+        /// The shards the baseline holds.
+        baseline_shards: ShardSet,
+        /// Number of candidate benchmarks in shards the baseline lacks.
+        benchmarks: usize,
+    },
+}
+
+impl std::fmt::Display for ComparisonWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EnvironmentMismatch {
+                baseline_fingerprint,
+                candidate_fingerprint,
+                fields,
+            } => write!(
+                f,
+                "Environments differ in {} (fingerprint {baseline_fingerprint} vs \
+                 {candidate_fingerprint}), changes may not be due to the code",
+                fields.join(", ")
+            ),
+            Self::MissingShards {
+                baseline_shards,
+                benchmarks,
+            } => write!(
+                f,
+                "Baseline holds only shards {baseline_shards}, {benchmarks} benchmarks of \
+                 other shards can't be compared"
+            ),
+        }
+    }
+}
+
+/// Result of [`compare_reports`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparisonReport {
+    /// Environment of the baseline, to label it.
+    pub baseline_environment: EnvironmentInfo,
+    /// Environment of the candidate, to label it.
+    pub candidate_environment: EnvironmentInfo,
+    /// Changes beyond this many percent are regressions or improvements.
+    pub threshold_pct: f64,
+    /// Benchmarks in both runs, in the order of the candidate.
+    pub benchmarks: Vec<ResultChange>,
+    /// Benchmarks only the baseline has, e.g. because they were removed or
+    /// failed in the candidate.
+    pub only_in_baseline: Vec<ResultKey>,
+    /// Benchmarks only the candidate has, e.g. new ones.
+    pub only_in_candidate: Vec<ResultKey>,
+    /// How the configurations of the two runs differ, candidate first, see
+    /// [`config_warnings`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub config_warnings: Vec<String>,
+    /// Reasons the runs may not be comparable besides their configurations.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<ComparisonWarning>,
+}
+
+impl ComparisonReport {
+    /// The benchmarks that got slower by more than the threshold.
+    pub fn regressions(&self) -> impl Iterator<Item = &ResultChange> {
+        self.with_change(Change::Regression)
+    }
+
+    /// The benchmarks that got faster by more than the threshold.
+    pub fn improvements(&self) -> impl Iterator<Item = &ResultChange> {
+        self.with_change(Change::Improvement)
+    }
+
+    fn with_change(&self, change: Change) -> impl Iterator<Item = &ResultChange> {
+        self.benchmarks.iter().filter(move |b| b.change == change)
+    }
+
+    /// Plain-text summary: regressions and improvements with both times,
+    /// the number of unchanged benchmarks, the benchmarks only one run has,
+    /// and warnings.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "Threshold: {}%, {} benchmarks in both runs",
+            self.threshold_pct,
+            self.benchmarks.len()
+        );
+        for (title, change) in [
+            ("Regressions", Change::Regression),
+            ("Improvements", Change::Improvement),
+        ] {
+            let changes: Vec<&ResultChange> = self.with_change(change).collect();
+            let _ = writeln!(out, "\n{title} ({}):", changes.len());
+            for b in changes {
+                let _ = writeln!(
+                    out,
+                    "  {}: {:.3} ms -> {:.3} ms ({:+.1}%)",
+                    b.key,
+                    b.baseline_mean_ns / 1e6,
+                    b.candidate_mean_ns / 1e6,
+                    b.change_pct
+                );
+            }
+        }
+        let unchanged = self.with_change(Change::Unchanged).count();
+        let _ = writeln!(out, "\nUnchanged: {unchanged}");
+        for (title, keys) in [
+            ("Only in baseline", &self.only_in_baseline),
+            ("Only in candidate", &self.only_in_candidate),
+        ] {
+            if !keys.is_empty() {
+                let _ = writeln!(out, "\n{title} ({}):", keys.len());
+                for key in keys {
+                    let _ = writeln!(out, "  {key}");
+                }
+            }
+        }
+        for warning in &self.config_warnings {
+            let _ = writeln!(out, "\nWarning: {warning}");
+        }
+        for warning in &self.warnings {
+            let _ = writeln!(out, "\nWarning: {warning}");
+        }
+        out
+    }
+
+    /// The report as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize comparison: {e}"))
+    }
+}
+
+/// Compare `candidate` against `baseline`, two runs that should be alike,
+/// e.g. of a pull request and of `main` on the same machine.
+///
+/// Results are matched by ID, SIMD variant, render mode and sync mode; where a run has
+/// several results for one of these, the fastest counts. A change of more
+/// than `threshold_pct` percent either way is a regression or an
+/// improvement. Benchmarks only one run has are listed separately, and
+/// single-shot estimates are left out on both sides. Runs in environments
+/// with different fingerprints are compared all the same, with a
+/// [`ComparisonWarning::EnvironmentMismatch`]; a baseline of only some
+/// shards of a run gets a [`ComparisonWarning::MissingShards`] if the
+/// candidate has benchmarks of the others.
+pub fn compare_reports(
+    baseline: &ResultFile,
+    candidate: &ResultFile,
+    threshold_pct: f64,
+) -> ComparisonReport {
+    let baseline_means = fastest_means(&baseline.results, ResultKey::of);
+    let candidate_means = fastest_means(&candidate.results, ResultKey::of);
+    let mean_of = |means: &[(ResultKey, f64)], key: &ResultKey| {
+        means.iter().find(|(k, _)| k == key).map(|(_, mean)| *mean)
+    };
+
+    let mut benchmarks = Vec::new();
+    let mut only_in_candidate = Vec::new();
+    for (key, candidate_mean_ns) in &candidate_means {
+        let Some(baseline_mean_ns) = mean_of(&baseline_means, key) else {
+            only_in_candidate.push(key.clone());
+            continue;
+        };
+        let ratio = candidate_mean_ns / baseline_mean_ns;
+        let change_pct = (ratio - 1.0) * 100.0;
+        let change = if change_pct > threshold_pct {
+            Change::Regression
+        } else if change_pct < -threshold_pct {
+            Change::Improvement
+        } else {
+            Change::Unchanged
+        };
+        benchmarks.push(ResultChange {
+            key: key.clone(),
+            baseline_mean_ns,
+            candidate_mean_ns: *candidate_mean_ns,
+            ratio,
+            change_pct,
+            change,
+        });
+    }
+    let only_in_baseline = baseline_means
+        .iter()
+        .filter(|(key, _)| mean_of(&candidate_means, key).is_none())
+        .map(|(key, _)| key.clone())
+        .collect();
+
+    let mut warnings = Vec::new();
+    let baseline_fingerprint = baseline.environment.fingerprint();
+    let candidate_fingerprint = candidate.environment.fingerprint();
+    if baseline_fingerprint != candidate_fingerprint {
+        warnings.push(ComparisonWarning::EnvironmentMismatch {
+            baseline_fingerprint,
+            candidate_fingerprint,
+            fields: baseline
+                .environment
+                .fingerprint_differences(&candidate.environment)
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        });
+    }
+    let baseline_shards = baseline
+        .config
+        .as_ref()
+        .and_then(|config| config.shards.as_ref())
+        .filter(|shards| !shards.is_complete());
+    if let Some(baseline_shards) = baseline_shards {
+        let benchmarks = only_in_candidate
+            .iter()
+            .filter(|key| {
+                let result = candidate.results.iter().find(|result| result.id == key.id);
+                !baseline_shards.contains(result.map_or(key.id.as_str(), benchmark_id))
+            })
+            .count();
+        if benchmarks > 0 {
+            warnings.push(ComparisonWarning::MissingShards {
+                baseline_shards: baseline_shards.clone(),
+                benchmarks,
+            });
+        }
+    }
+
+    ComparisonReport {
+        baseline_environment: baseline.environment.clone(),
+        candidate_environment: candidate.environment.clone(),
+        threshold_pct,
+        benchmarks,
+        only_in_baseline,
+        only_in_candidate,
+        config_warnings: config_warnings(candidate.config.as_ref(), baseline.config.as_ref()),
+        warnings,
+    }
+}
+
+/// The ID of the benchmark that produced `result`, without the render mode
+/// a matrix run appends to it (see
+/// [`MatrixEntry::result_id`](crate::registry::MatrixEntry::result_id)).
+fn benchmark_id(result: &BenchmarkResult) -> &str {
+    result
+        .render_mode
+        .as_ref()
+        .and_then(|mode| result.id.strip_suffix(mode.as_str()))
+        .and_then(|id| id.strip_suffix('_'))
+        .unwrap_or(&result.id)
+}
+
+/// [`Comparison::config_warnings`] for the configurations of a compared file
+/// and its baseline.
+pub fn config_warnings(config: Option<&RunConfig>, baseline: Option<&RunConfig>) -> Vec<String> {
+    match (config, baseline) {
+        (Some(config), Some(baseline)) => config
+            .differences(baseline)
+            .into_iter()
+            .map(|difference| format!("Configurations differ in {difference}"))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// [`Comparison::sync_mode_warnings`]: one per ID and render mode in both
+/// `means` and `baseline_means` that the two measured in different sync
+/// modes only.
+fn sync_mode_warnings(means: &[Mean<'_>], baseline_means: &[Mean<'_>]) -> Vec<String> {
+    let modes_of = |means: &[Mean<'_>], benchmark: (&str, Option<&str>)| -> Vec<SyncMode> {
+        means
+            .iter()
+            .filter(|((id, render_mode, _), _)| (*id, *render_mode) == benchmark)
+            .map(|((_, _, mode), _)| *mode)
+            .collect()
+    };
+    let mut warnings = Vec::new();
+    for (index, &((id, render_mode, _), _)) in means.iter().enumerate() {
+        if means[..index]
+            .iter()
+            .any(|((earlier, earlier_mode, _), _)| (*earlier, *earlier_mode) == (id, render_mode))
+        {
+            continue;
+        }
+        let modes = modes_of(means, (id, render_mode));
+        let baseline_modes = modes_of(baseline_means, (id, render_mode));
+        if !baseline_modes.is_empty() && !modes.iter().any(|mode| baseline_modes.contains(mode)) {
+            let name = match render_mode {
+                Some(render_mode) => format!("{id} ({render_mode})"),
+                None => id.to_string(),
+            };
+            warnings.push(format!(
+                "{name} was measured with {} sync, the baseline with {}; not compared",
+                sync_mode_names(&modes),
+                sync_mode_names(&baseline_modes),
+            ));
+        }
+    }
+    warnings
+}
+
+fn sync_mode_names(modes: &[SyncMode]) -> String {
+    modes
+        .iter()
+        .map(|mode| mode.name())
+        .collect::<Vec<_>>()
+        .join(" and ")
+}
+
+/// What [`compare`] matches results by: ID, render mode and sync mode.
+type CompareKey<'a> = (&'a str, Option<&'a str>, SyncMode);
+
+/// A [`CompareKey`] with the fastest mean time of its results.
+type Mean<'a> = (CompareKey<'a>, f64);
+
+/// The [`CompareKey`] of `result`.
+fn compare_key(result: &BenchmarkResult) -> CompareKey<'_> {
+    (
+        &result.id,
+        result.render_mode.as_deref(),
+        result.sync_mode.unwrap_or_default(),
+    )
+}
+
+/// The fastest mean time per `key` of the results, in order of first
+/// appearance, leaving out estimates and results without a positive time.
+fn fastest_means<'a, K: PartialEq>(
+    results: &'a [BenchmarkResult],
+    key: impl Fn(&'a BenchmarkResult) -> K,
+) -> Vec<(K, f64)> {
+    let mut means: Vec<(K, f64)> = Vec::new();
+    for result in results {
+        let mean_ns = result.statistics.mean_ns;
+        if result.is_estimate() || mean_ns.is_nan() || mean_ns <= 0.0 {
+            continue;
+        }
+        let key = key(result);
+        match means.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, best)) => *best = best.min(mean_ns),
+            None => means.push((key, mean_ns)),
+        }
+    }
+    means
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::result::Statistics;
+
+    fn result(id: &str, mean_ns: f64) -> BenchmarkResult {
+        let (category, name) = id.rsplit_once('/').unwrap();
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "category": category,
+            "name": name,
+            "simd_variant": "avx2",
+            "statistics": Statistics::from_mean(mean_ns, 10),
+            "timestamp_ms": 0,
+        }))
+        .unwrap()
+    }
+
+    fn file(results: Vec<BenchmarkResult>, shards: Option<ShardSet>) -> ResultFile {
+        ResultFile {
+            schema_version: ResultFile::SCHEMA_VERSION,
+            started_ms: 0,
+            finished_ms: 0,
+            environment: EnvironmentInfo::default(),
+            results,
+            memory_timeline: Vec::new(),
+            partial: false,
+            config: Some(RunConfig {
+                shards,
+                ..Default::default()
+            }),
+            health: None,
+        }
+    }
+
+    /// IDs of `count` benchmarks in shard 0 of 2 and `count` in shard 1.
+    fn ids_by_shard(count: usize) -> (Vec<String>, Vec<String>) {
+        let (in_first, in_second): (Vec<String>, Vec<String>) = (0..)
+            .map(|i| format!("test/bench_{i}"))
+            .take(8 * count)
+            .partition(|id| crate::registry::in_shard(id, 0, 2));
+        (
+            in_first.into_iter().take(count).collect(),
+            in_second.into_iter().take(count).collect(),
+        )
+    }
+
+    fn missing_shards(report: &ComparisonReport) -> Option<usize> {
+        report.warnings.iter().find_map(|warning| match warning {
+            ComparisonWarning::MissingShards { benchmarks, .. } => Some(*benchmarks),
+            _ => None,
+        })
+    }
+
+    fn key(id: &str, simd_variant: &str) -> ResultKey {
+        ResultKey {
+            id: id.to_string(),
+            simd_variant: simd_variant.to_string(),
+            render_mode: None,
+            sync_mode: SyncMode::Finish,
+        }
+    }
+
+    fn with_mode(mut result: BenchmarkResult, mode: &str) -> BenchmarkResult {
+        result.render_mode = Some(mode.to_string());
+        result
+    }
+
+    #[test]
+    fn matches_results_by_id_and_level() {
+        let mut sse = result("test/a", 100.0);
+        sse.simd_variant = "sse4_2".to_string();
+        let baseline = file(vec![result("test/a", 100.0), sse], None);
+        // The fastest of several results for a key counts.
+        let candidate = file(vec![result("test/a", 120.0), result("test/a", 102.0)], None);
+
+        let report = compare_reports(&baseline, &candidate, 5.0);
+        assert_eq!(report.benchmarks.len(), 1);
+        let change = &report.benchmarks[0];
+        assert_eq!(change.key, key("test/a", "avx2"));
+        assert_eq!(change.baseline_mean_ns, 100.0);
+        assert_eq!(change.candidate_mean_ns, 102.0);
+        assert_eq!(change.change, Change::Unchanged);
+        assert_eq!(report.only_in_baseline, [key("test/a", "sse4_2")]);
+        assert!(report.only_in_candidate.is_empty());
+    }
+
+    #[test]
+    fn matches_results_by_render_mode() {
+        let baseline = file(
+            vec![
+                with_mode(result("vello_cpu/tiger", 100.0), "speed"),
+                with_mode(result("vello_cpu/tiger", 200.0), "quality"),
+            ],
+            None,
+        );
+        // The quality mode regressed; the faster speed mode must not hide it.
+        let candidate = file(
+            vec![
+                with_mode(result("vello_cpu/tiger", 100.0), "speed"),
+                with_mode(result("vello_cpu/tiger", 240.0), "quality"),
+            ],
+            None,
+        );
+
+        let report = compare_reports(&baseline, &candidate, 5.0);
+        let changes: Vec<_> = report
+            .benchmarks
+            .iter()
+            .map(|change| (change.key.render_mode.as_deref(), change.change))
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                (Some("speed"), Change::Unchanged),
+                (Some("quality"), Change::Regression),
+            ]
+        );
+        assert_eq!(report.benchmarks[1].baseline_mean_ns, 200.0);
+        assert!(report.to_text().contains("vello_cpu/tiger [avx2, quality]"));
+
+        let comparison = compare(&candidate, &baseline);
+        let ratios: Vec<_> = comparison
+            .benchmarks
+            .iter()
+            .map(|b| (b.render_mode.as_deref(), b.ratio))
+            .collect();
+        assert_eq!(ratios, [(Some("speed"), 1.0), (Some("quality"), 1.2)]);
+    }
+
+    #[test]
+    fn lists_benchmarks_of_only_one_run() {
+        let baseline = file(
+            vec![result("test/a", 100.0), result("test/old", 50.0)],
+            None,
+        );
+        let candidate = file(
+            vec![result("test/new", 70.0), result("test/a", 100.0)],
+            None,
+        );
+
+        let report = compare_reports(&baseline, &candidate, 5.0);
+        assert_eq!(report.benchmarks.len(), 1);
+        assert_eq!(report.only_in_baseline, [key("test/old", "avx2")]);
+        assert_eq!(report.only_in_candidate, [key("test/new", "avx2")]);
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn flags_changes_beyond_the_threshold() {
+        let ids = ["test/slower", "test/faster", "test/same"];
+        let baseline = file(ids.iter().map(|id| result(id, 100.0)).collect(), None);
+        let candidate = file(
+            vec![
+                result("test/slower", 110.0),
+                result("test/faster", 80.0),
+                result("test/same", 104.0),
+            ],
+            None,
+        );
+
+        let report = compare_reports(&baseline, &candidate, 5.0);
+        let changes: Vec<_> = report
+            .benchmarks
+            .iter()
+            .map(|change| (change.key.id.as_str(), change.change))
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                ("test/slower", Change::Regression),
+                ("test/faster", Change::Improvement),
+                ("test/same", Change::Unchanged),
+            ]
+        );
+        let slower = &report.benchmarks[0];
+        assert!((slower.ratio - 1.1).abs() < 1e-9);
+        assert!((slower.change_pct - 10.0).abs() < 1e-9);
+
+        // A wider threshold lets the regression pass.
+        let lenient = compare_reports(&baseline, &candidate, 15.0);
+        assert_eq!(lenient.benchmarks[0].change, Change::Unchanged);
+        assert_eq!(lenient.benchmarks[1].change, Change::Improvement);
+    }
+
+    #[test]
+    fn warns_about_shards_missing_from_the_baseline() {
+        let (first, second) = ids_by_shard(3);
+        let baseline = file(
+            first.iter().map(|id| result(id, 100.0)).collect(),
+            Some(ShardSet::single(0, 2)),
+        );
+        let candidate = file(
+            first
+                .iter()
+                .chain(&second)
+                .map(|id| result(id, 100.0))
+                .collect(),
+            None,
+        );
+        let report = compare_reports(&baseline, &candidate, 5.0);
+        assert_eq!(report.only_in_candidate.len(), 3);
+        assert_eq!(missing_shards(&report), Some(3));
+        assert!(report.to_text().contains("shards 0 of 2"));
+    }
+
+    #[test]
+    fn new_benchmarks_of_the_baseline_shard_are_not_missing_shards() {
+        let (first, _) = ids_by_shard(3);
+        let baseline = file(
+            first[..2].iter().map(|id| result(id, 100.0)).collect(),
+            Some(ShardSet::single(0, 2)),
+        );
+        let candidate = file(first.iter().map(|id| result(id, 100.0)).collect(), None);
+        let report = compare_reports(&baseline, &candidate, 5.0);
+        assert_eq!(report.only_in_candidate.len(), 1);
+        assert_eq!(missing_shards(&report), None);
+    }
+
+    #[test]
+    fn whole_baseline_has_no_missing_shards() {
+        let (first, second) = ids_by_shard(2);
+        let baseline = file(first.iter().map(|id| result(id, 100.0)).collect(), None);
+        let candidate = file(
+            first
+                .iter()
+                .chain(&second)
+                .map(|id| result(id, 100.0))
+                .collect(),
+            None,
+        );
+        let report = compare_reports(&baseline, &candidate, 5.0);
+        assert_eq!(report.only_in_candidate.len(), 2);
+        assert_eq!(missing_shards(&report), None);
+    }
+
+    #[test]
+    fn matrix_results_count_towards_their_benchmark_shard() {
+        let (first, _) = ids_by_shard(1);
+        let mut with_mode = result(&format!("{}_speed", first[0]), 100.0);
+        with_mode.render_mode = Some("speed".to_string());
+        assert_eq!(benchmark_id(&with_mode), first[0]);
+        assert_eq!(benchmark_id(&result(&first[0], 100.0)), first[0]);
+    }
+}
@@ -0,0 +1,411 @@
+//! The configuration a run was produced under, recorded in its result file so
+//! that a shared file can be reproduced and compared fairly.
+//!
+//! Native runs record their command line and every `VELLO_BENCH_*`
+//! environment variable besides the runner settings; the browser records the
+//! settings of the UI.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::MeasurementMode;
+use crate::runner::BenchRunner;
+
+/// Prefix of the environment variables that are recorded.
+pub const ENV_PREFIX: &str = "VELLO_BENCH_";
+
+/// Settings a run was produced with, see [`ResultFile::config`](super::ResultFile::config).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunConfig {
+    /// Arguments of the `vello_bench` command line, without the executable.
+    /// Empty for runs not started from the command line.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// `VELLO_BENCH_*` environment variables set for the run, by name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Warm-up iterations per benchmark.
+    pub warmup: u64,
+    /// Timed iterations per benchmark.
+    pub iterations: u64,
+    /// How the results were measured. Omitted from JSON for full
+    /// measurements.
+    #[serde(default, skip_serializing_if = "MeasurementMode::is_full")]
+    pub measurement_mode: MeasurementMode,
+    /// Filter expression selecting the benchmarks, if one was given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    /// GPU timeout in milliseconds, where known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gpu_timeout_ms: Option<u64>,
+    /// The shards of a sharded run the results belong to, or `None` for a
+    /// whole run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shards: Option<ShardSet>,
+}
+
+/// The shards of a run split with [`shard`](crate::registry::shard) that a
+/// result file holds: one for the file of a single shard, several for merged
+/// files (see [`ResultFile::merge`](super::ResultFile::merge)).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardSet {
+    /// Number of shards the run was split into.
+    pub count: u32,
+    /// Zero-based indices of the shards held, ascending.
+    pub indices: Vec<u32>,
+}
+
+impl ShardSet {
+    /// Shard `index` of `count`.
+    pub fn single(index: u32, count: u32) -> Self {
+        Self {
+            count,
+            indices: vec![index],
+        }
+    }
+
+    /// Whether every shard is held, i.e. the results are those of a whole
+    /// run.
+    pub fn is_complete(&self) -> bool {
+        (0..self.count).all(|index| self.indices.contains(&index))
+    }
+
+    /// Whether the benchmark `id` belongs to one of the held shards.
+    pub fn contains(&self, id: &str) -> bool {
+        self.indices
+            .iter()
+            .any(|&index| crate::registry::in_shard(id, index, self.count))
+    }
+
+    /// The shards of both sets, or `None` if they split the run into
+    /// different numbers of shards.
+    pub fn union(&self, other: &Self) -> Option<Self> {
+        if self.count != other.count {
+            return None;
+        }
+        let mut indices: Vec<u32> = self.indices.iter().chain(&other.indices).copied().collect();
+        indices.sort_unstable();
+        indices.dedup();
+        Some(Self {
+            count: self.count,
+            indices,
+        })
+    }
+}
+
+impl std::fmt::Display for ShardSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let indices: Vec<String> = self.indices.iter().map(u32::to_string).collect();
+        write!(f, "{} of {}", indices.join(", "), self.count)
+    }
+}
+
+impl RunConfig {
+    /// The settings of `runner`, with nothing else recorded.
+    pub fn from_runner(runner: &BenchRunner) -> Self {
+        Self {
+            warmup: runner.warmup,
+            iterations: runner.iterations,
+            measurement_mode: runner.measurement_mode,
+            ..Default::default()
+        }
+    }
+
+    /// The settings of `runner` in this process: its command line and its
+    /// `VELLO_BENCH_*` environment variables.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn capture(runner: &BenchRunner) -> Self {
+        Self {
+            args: std::env::args().skip(1).collect(),
+            env: env_snapshot(std::env::vars_os().filter_map(|(name, value)| {
+                Some((name.into_string().ok()?, value.into_string().ok()?))
+            })),
+            ..Self::from_runner(runner)
+        }
+    }
+
+    /// The `vello_bench` arguments that repeat the run: the recorded command
+    /// line, or for runs without one a `run` command with the same settings.
+    pub fn command_args(&self) -> Vec<String> {
+        if !self.args.is_empty() {
+            return self.args.clone();
+        }
+        let mut args = vec![
+            "run".to_string(),
+            "--warmup".to_string(),
+            self.warmup.to_string(),
+            "--iterations".to_string(),
+            self.iterations.to_string(),
+        ];
+        if let Some(filter) = &self.filter {
+            args.extend(["--filter".to_string(), filter.clone()]);
+        }
+        if let Some(ms) = self.gpu_timeout_ms {
+            args.extend([
+                "--gpu-timeout".to_string(),
+                (ms as f64 / 1000.0).to_string(),
+            ]);
+        }
+        if let Some(ShardSet { count, indices }) = &self.shards {
+            if let [index] = indices[..] {
+                args.extend(["--shard".to_string(), format!("{index}/{count}")]);
+            }
+        }
+        args
+    }
+
+    /// [`Self::command_args`] run by `program`, with the recorded environment
+    /// variables, as a command line for a POSIX shell.
+    pub fn shell_command(&self, program: &str) -> String {
+        let env = self
+            .env
+            .iter()
+            .map(|(name, value)| format!("{name}={}", shell_quote(value)));
+        let args = self.command_args();
+        let command =
+            std::iter::once(shell_quote(program)).chain(args.iter().map(|a| shell_quote(a)));
+        env.chain(command).collect::<Vec<_>>().join(" ")
+    }
+
+    /// How `self` differs from `other` in settings that can change measured
+    /// times, as human-readable messages. The command line, filter, GPU
+    /// timeout and shards are not compared: they change which benchmarks run
+    /// and where results go, but not how a benchmark is timed.
+    pub fn differences(&self, other: &Self) -> Vec<String> {
+        let mut differences = Vec::new();
+        if self.warmup != other.warmup {
+            differences.push(format!(
+                "warm-up iterations: {} vs {}",
+                self.warmup, other.warmup
+            ));
+        }
+        if self.iterations != other.iterations {
+            differences.push(format!(
+                "iterations: {} vs {}",
+                self.iterations, other.iterations
+            ));
+        }
+        if self.measurement_mode != other.measurement_mode {
+            differences.push(format!(
+                "measurement mode: {:?} vs {:?}",
+                self.measurement_mode, other.measurement_mode
+            ));
+        }
+
+        let names: BTreeSet<&String> = self.env.keys().chain(other.env.keys()).collect();
+        for name in names {
+            let (ours, theirs) = (self.env.get(name), other.env.get(name));
+            if ours != theirs {
+                let show = |value: Option<&String>| match value {
+                    Some(value) => format!("{value:?}"),
+                    None => "unset".to_string(),
+                };
+                differences.push(format!("{name}: {} vs {}", show(ours), show(theirs)));
+            }
+        }
+        differences
+    }
+}
+
+/// The `VELLO_BENCH_*` variables among `vars`, by name.
+pub fn env_snapshot(vars: impl IntoIterator<Item = (String, String)>) -> BTreeMap<String, String> {
+    vars.into_iter()
+        .filter(|(name, _)| name.starts_with(ENV_PREFIX))
+        .collect()
+}
+
+/// `arg` quoted for a POSIX shell where needed.
+fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"-_./:=,@%+".contains(&b));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shard_sets_union_to_a_whole_run() {
+        let first = ShardSet::single(0, 3);
+        assert!(!first.is_complete());
+        let two = first.union(&ShardSet::single(2, 3)).unwrap();
+        assert_eq!(two.indices, [0, 2]);
+        assert_eq!(two.to_string(), "0, 2 of 3");
+        assert!(!two.is_complete());
+        assert!(two.union(&ShardSet::single(1, 3)).unwrap().is_complete());
+        assert_eq!(first.union(&ShardSet::single(0, 4)), None);
+    }
+
+    #[test]
+    fn shard_set_contains_its_benchmarks() {
+        let ids: Vec<String> = (0..50).map(|i| format!("test/bench_{i}")).collect();
+        let set = ShardSet::single(1, 2);
+        for id in &ids {
+            assert_eq!(
+                set.contains(id),
+                crate::registry::in_shard(id, 1, 2),
+                "{id}"
+            );
+        }
+        assert!(ids.iter().any(|id| set.contains(id)));
+        assert!(ids.iter().any(|id| !set.contains(id)));
+    }
+
+    #[test]
+    fn single_shard_repeats_on_the_command_line() {
+        let config = RunConfig {
+            shards: Some(ShardSet::single(1, 4)),
+            ..Default::default()
+        };
+        let args = config.command_args();
+        assert!(args.ends_with(&["--shard".to_string(), "1/4".to_string()]));
+    }
+
+    fn vars(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|&(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn config(warmup: u64, iterations: u64, env: &[(&str, &str)]) -> RunConfig {
+        RunConfig {
+            warmup,
+            iterations,
+            env: vars(env).into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn snapshots_only_vello_bench_variables() {
+        let snapshot = env_snapshot(vars(&[
+            ("VELLO_BENCH_GPU_TIMEOUT", "5"),
+            ("PATH", "/usr/bin"),
+            ("VELLO_BENCH_SEED", "1"),
+            ("vello_bench_lowercase", "x"),
+            ("MY_VELLO_BENCH_THING", "y"),
+        ]));
+        assert_eq!(
+            snapshot.into_iter().collect::<Vec<_>>(),
+            vars(&[("VELLO_BENCH_GPU_TIMEOUT", "5"), ("VELLO_BENCH_SEED", "1")])
+        );
+    }
+
+    #[test]
+    fn captures_the_runner_and_this_process() {
+        let mut runner = BenchRunner::new(3, 10);
+        runner.measurement_mode = MeasurementMode::SingleShot;
+        let config = RunConfig::capture(&runner);
+        assert_eq!((config.warmup, config.iterations), (3, 10));
+        assert_eq!(config.measurement_mode, MeasurementMode::SingleShot);
+        assert_eq!(config.args, std::env::args().skip(1).collect::<Vec<_>>());
+        assert!(config.env.keys().all(|name| name.starts_with(ENV_PREFIX)));
+        assert_eq!(
+            (config.filter, config.gpu_timeout_ms, config.shards),
+            (None, None, None)
+        );
+    }
+
+    #[test]
+    fn equal_settings_have_no_differences() {
+        let config = config(3, 10, &[("VELLO_BENCH_SEED", "1")]);
+        assert!(config.differences(&config.clone()).is_empty());
+
+        // What selects the benchmarks or where they run doesn't count.
+        let other = RunConfig {
+            args: vec!["run".to_string()],
+            filter: Some("vello_cpu/*".to_string()),
+            gpu_timeout_ms: Some(5000),
+            shards: Some(ShardSet::single(0, 2)),
+            ..config.clone()
+        };
+        assert!(config.differences(&other).is_empty());
+    }
+
+    #[test]
+    fn lists_differences_in_timing_settings() {
+        let ours = RunConfig {
+            measurement_mode: MeasurementMode::SingleShot,
+            ..config(
+                3,
+                10,
+                &[
+                    ("VELLO_BENCH_A", "1"),
+                    ("VELLO_BENCH_B", "x"),
+                    ("VELLO_BENCH_C", "same"),
+                ],
+            )
+        };
+        let theirs = config(
+            5,
+            100,
+            &[
+                ("VELLO_BENCH_B", "y"),
+                ("VELLO_BENCH_C", "same"),
+                ("VELLO_BENCH_D", "2"),
+            ],
+        );
+        assert_eq!(
+            ours.differences(&theirs),
+            [
+                "warm-up iterations: 3 vs 5",
+                "iterations: 10 vs 100",
+                "measurement mode: SingleShot vs Full",
+                "VELLO_BENCH_A: \"1\" vs unset",
+                "VELLO_BENCH_B: \"x\" vs \"y\"",
+                "VELLO_BENCH_D: unset vs \"2\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn differences_become_comparison_warnings() {
+        use crate::result::compare::config_warnings;
+
+        let ours = config(3, 10, &[]);
+        let theirs = config(3, 20, &[]);
+        assert_eq!(
+            config_warnings(Some(&ours), Some(&theirs)),
+            ["Configurations differ in iterations: 10 vs 20"]
+        );
+        // Files without a recorded configuration can't be checked.
+        assert!(config_warnings(None, Some(&theirs)).is_empty());
+        assert!(config_warnings(Some(&ours), None).is_empty());
+    }
+
+    #[test]
+    fn shell_command_repeats_the_run() {
+        let config = RunConfig {
+            filter: Some("vello_cpu/*".to_string()),
+            gpu_timeout_ms: Some(2500),
+            ..config(3, 10, &[("VELLO_BENCH_LABEL", "it's a test")])
+        };
+        assert_eq!(
+            config.shell_command("vello_bench"),
+            "VELLO_BENCH_LABEL='it'\\''s a test' vello_bench run --warmup 3 --iterations 10 \
+             --filter 'vello_cpu/*' --gpu-timeout 2.5"
+        );
+
+        let recorded = RunConfig {
+            args: vec![
+                "list".to_string(),
+                "--filter".to_string(),
+                "tag:gpu".to_string(),
+            ],
+            ..config
+        };
+        assert!(
+            recorded
+                .shell_command("vello_bench")
+                .ends_with("vello_bench list --filter tag:gpu")
+        );
+    }
+}
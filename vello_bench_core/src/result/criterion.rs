@@ -0,0 +1,343 @@
+//! Result files in the directory layout of Criterion, for tools built
+//! around it such as `critcmp`.
+//!
+//! Each result becomes a benchmark whose group is its category and whose
+//! function is its name, so `vello_cpu/filled_rects` is written to
+//! `<out_dir>/vello_cpu/filled_rects/new/`. Where a file has an ID at more
+//! than one SIMD level, e.g. a matrix run, the level is the benchmark's
+//! parameter instead: `<out_dir>/vello_cpu/filled_rects/avx2/new/`. The
+//! same goes for an ID in more than one render mode, and an ID at several
+//! levels in several modes gets both, e.g. `avx2_quality`. Characters
+//! Criterion doesn't allow in directory names, such as `?`, are replaced by
+//! `_` there, but kept in the IDs. Where a file has several results for an
+//! ID, level and render mode, e.g. one per sync mode, the fastest one is
+//! written.
+//!
+//! `new/` holds `benchmark.json` with the IDs and throughput and
+//! `estimates.json` with point estimates from [`Statistics`]: the mean,
+//! median and standard deviation as measured, and the median absolute
+//! deviation from the standard deviation, assuming normally distributed
+//! iteration times. The 95% confidence intervals are those of the mean,
+//! from its standard error; the other estimates have no interval and
+//! repeat the point estimate. Criterion's `slope` is left out, since
+//! iterations are not timed in batches of growing size.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::{BenchmarkResult, ResultFile, Statistics, Throughput};
+
+/// The characters Criterion replaces in directory names.
+const UNSAFE_CHARS: &[char] = &['?', '"', '/', '\\', '*', '<', '>', ':', '|', '^'];
+
+/// Scale from the standard deviation to the median absolute deviation of a
+/// normal distribution.
+const MAD_PER_STD_DEV: f64 = 0.674_489_75;
+
+/// `benchmark.json` of a benchmark.
+#[derive(Serialize)]
+struct BenchmarkId<'a> {
+    group_id: &'a str,
+    function_id: Option<&'a str>,
+    value_str: Option<String>,
+    throughput: Option<Throughput>,
+    full_id: String,
+    directory_name: String,
+    title: String,
+}
+
+/// `estimates.json` of a benchmark.
+#[derive(Serialize)]
+struct Estimates {
+    mean: Estimate,
+    median: Estimate,
+    median_abs_dev: Estimate,
+    slope: Option<Estimate>,
+    std_dev: Estimate,
+}
+
+#[derive(Serialize)]
+struct Estimate {
+    confidence_interval: ConfidenceInterval,
+    point_estimate: f64,
+    standard_error: f64,
+}
+
+#[derive(Serialize)]
+struct ConfidenceInterval {
+    confidence_level: f64,
+    lower_bound: f64,
+    upper_bound: f64,
+}
+
+impl Estimate {
+    /// An estimate without an interval.
+    fn point(point_estimate: f64) -> Self {
+        Self::with_error(point_estimate, 0.0)
+    }
+
+    /// An estimate with the 95% confidence interval of a normal
+    /// distribution with `standard_error`.
+    fn with_error(point_estimate: f64, standard_error: f64) -> Self {
+        Self {
+            confidence_interval: ConfidenceInterval {
+                confidence_level: 0.95,
+                lower_bound: point_estimate - 1.96 * standard_error,
+                upper_bound: point_estimate + 1.96 * standard_error,
+            },
+            point_estimate,
+            standard_error,
+        }
+    }
+}
+
+impl From<&Statistics> for Estimates {
+    fn from(statistics: &Statistics) -> Self {
+        let standard_error = statistics.std_dev_ns / (statistics.iterations.max(1) as f64).sqrt();
+        Self {
+            mean: Estimate::with_error(statistics.mean_ns, standard_error),
+            median: Estimate::point(statistics.median_ns),
+            median_abs_dev: Estimate::point(statistics.std_dev_ns * MAD_PER_STD_DEV),
+            slope: None,
+            std_dev: Estimate::point(statistics.std_dev_ns),
+        }
+    }
+}
+
+/// Write the results of `report` to `out_dir` in Criterion's layout (see
+/// the [module docs](self)), creating directories as needed and replacing
+/// the `new/` files of earlier exports. Single-shot estimates are left out.
+pub fn write_criterion_compatible(report: &ResultFile, out_dir: &Path) -> Result<(), String> {
+    for (result, value_str) in fastest_results(&report.results) {
+        let mut ids = vec![result.category.as_str(), result.name.as_str()];
+        ids.extend(value_str.as_deref());
+        let directory_name = ids
+            .iter()
+            .map(|id| id.replace(UNSAFE_CHARS, "_"))
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let full_id = ids.join("/");
+        let benchmark = BenchmarkId {
+            group_id: &result.category,
+            function_id: Some(&result.name),
+            value_str,
+            throughput: result.throughput,
+            title: full_id.clone(),
+            full_id,
+            directory_name,
+        };
+        let dir = out_dir.join(&benchmark.directory_name).join("new");
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        write_json(&dir.join("benchmark.json"), &benchmark)?;
+        write_json(
+            &dir.join("estimates.json"),
+            &Estimates::from(&result.statistics),
+        )?;
+    }
+    Ok(())
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {e}", path.display()))?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// The fastest result per ID, SIMD level and render mode, in order of first
+/// appearance, each with its parameter: the level if its ID is at more than
+/// one level, and the render mode if it is in more than one mode. Estimates
+/// are left out.
+fn fastest_results(results: &[BenchmarkResult]) -> Vec<(&BenchmarkResult, Option<String>)> {
+    let mut fastest: Vec<&BenchmarkResult> = Vec::new();
+    for result in results.iter().filter(|result| !result.is_estimate()) {
+        let same = fastest.iter_mut().find(|r| {
+            r.id == result.id
+                && r.simd_variant == result.simd_variant
+                && r.render_mode == result.render_mode
+        });
+        match same {
+            Some(best) if result.statistics.mean_ns < best.statistics.mean_ns => *best = result,
+            Some(_) => {}
+            None => fastest.push(result),
+        }
+    }
+    fastest
+        .iter()
+        .map(|result| {
+            let same_id = || fastest.iter().filter(|r| r.id == result.id);
+            let mut parameter = Vec::new();
+            if same_id().any(|r| r.simd_variant != result.simd_variant) {
+                parameter.push(result.simd_variant.as_str());
+            }
+            if same_id().any(|r| r.render_mode != result.render_mode) {
+                parameter.push(result.render_mode.as_deref().unwrap_or("default"));
+            }
+            let parameter = (!parameter.is_empty()).then(|| parameter.join("_"));
+            (*result, parameter)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::result::EnvironmentInfo;
+
+    fn result(id: &str, simd_variant: &str, mean_ns: f64) -> BenchmarkResult {
+        let (category, name) = id.split_once('/').unwrap();
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "category": category,
+            "name": name,
+            "simd_variant": simd_variant,
+            "statistics": Statistics::from_mean(mean_ns, 10)
+                .with_samples(vec![mean_ns - 10.0, mean_ns, mean_ns + 10.0]),
+            "timestamp_ms": 0,
+        }))
+        .unwrap()
+    }
+
+    fn file(results: Vec<BenchmarkResult>) -> ResultFile {
+        ResultFile {
+            schema_version: ResultFile::SCHEMA_VERSION,
+            started_ms: 0,
+            finished_ms: 0,
+            environment: EnvironmentInfo::default(),
+            results,
+            memory_timeline: Vec::new(),
+            partial: false,
+            config: None,
+            health: None,
+        }
+    }
+
+    /// An empty directory in the system's temporary directory, removed
+    /// again when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("vello_bench_{name}_{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn read_json(path: &Path) -> serde_json::Value {
+        let text = std::fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("Failed to read {}: {e}", path.display()));
+        serde_json::from_str(&text).unwrap()
+    }
+
+    /// Check that `dir` holds the files critcmp reads, with `mean_ns` as the
+    /// mean, and return its `benchmark.json`.
+    fn check_benchmark(dir: &Path, mean_ns: f64) -> serde_json::Value {
+        let estimates = read_json(&dir.join("new/estimates.json"));
+        for estimate in ["mean", "median", "median_abs_dev", "std_dev"] {
+            let estimate = &estimates[estimate];
+            assert!(estimate["point_estimate"].is_f64(), "{estimates}");
+            assert!(estimate["standard_error"].is_f64(), "{estimates}");
+            let interval = &estimate["confidence_interval"];
+            assert_eq!(interval["confidence_level"], 0.95);
+            assert!(interval["lower_bound"].as_f64() <= interval["upper_bound"].as_f64());
+        }
+        assert!(estimates["slope"].is_null());
+        assert_eq!(estimates["mean"]["point_estimate"], mean_ns);
+        read_json(&dir.join("new/benchmark.json"))
+    }
+
+    #[test]
+    fn writes_a_directory_per_benchmark() {
+        let dir = TempDir::new("criterion_layout");
+        let report = file(vec![
+            result("vello_cpu/filled_rects", "avx2", 1000.0),
+            result("scene_cpu/tiger?", "avx2", 2000.0),
+        ]);
+        write_criterion_compatible(&report, &dir.0).unwrap();
+
+        let benchmark = check_benchmark(&dir.0.join("vello_cpu/filled_rects"), 1000.0);
+        assert_eq!(benchmark["group_id"], "vello_cpu");
+        assert_eq!(benchmark["function_id"], "filled_rects");
+        assert!(benchmark["value_str"].is_null());
+        assert_eq!(benchmark["full_id"], "vello_cpu/filled_rects");
+        assert_eq!(benchmark["directory_name"], "vello_cpu/filled_rects");
+
+        // The directory name is sanitized, the ID isn't.
+        let benchmark = check_benchmark(&dir.0.join("scene_cpu/tiger_"), 2000.0);
+        assert_eq!(benchmark["full_id"], "scene_cpu/tiger?");
+        assert_eq!(benchmark["directory_name"], "scene_cpu/tiger_");
+    }
+
+    #[test]
+    fn levels_become_parameters() {
+        let dir = TempDir::new("criterion_levels");
+        let report = file(vec![
+            result("vello_cpu/filled_rects", "avx2", 1000.0),
+            result("vello_cpu/filled_rects", "sse4_2", 1500.0),
+            // Only the fastest result of a level is written.
+            result("vello_cpu/filled_rects", "avx2", 900.0),
+        ]);
+        write_criterion_compatible(&report, &dir.0).unwrap();
+
+        let benchmark = check_benchmark(&dir.0.join("vello_cpu/filled_rects/avx2"), 900.0);
+        assert_eq!(benchmark["value_str"], "avx2");
+        assert_eq!(benchmark["full_id"], "vello_cpu/filled_rects/avx2");
+        check_benchmark(&dir.0.join("vello_cpu/filled_rects/sse4_2"), 1500.0);
+        assert!(!dir.0.join("vello_cpu/filled_rects/new").exists());
+    }
+
+    #[test]
+    fn render_modes_become_parameters() {
+        let dir = TempDir::new("criterion_modes");
+        let with_mode = |simd_variant, mode: &str, mean_ns| {
+            let mut result = result("vello_cpu/filled_rects", simd_variant, mean_ns);
+            result.render_mode = Some(mode.to_string());
+            result
+        };
+        // As a full matrix run writes them: every level in every mode.
+        let report = file(vec![
+            with_mode("avx2", "speed", 1000.0),
+            with_mode("sse4_2", "speed", 1500.0),
+            with_mode("avx2", "quality", 2000.0),
+            with_mode("sse4_2", "quality", 2500.0),
+            with_mode("avx2", "quality", 2100.0),
+            result("vello_cpu/tiger", "avx2", 500.0),
+        ]);
+        write_criterion_compatible(&report, &dir.0).unwrap();
+
+        for (parameter, mean_ns) in [
+            ("avx2_speed", 1000.0),
+            ("sse4_2_speed", 1500.0),
+            ("avx2_quality", 2000.0),
+            ("sse4_2_quality", 2500.0),
+        ] {
+            let path = dir.0.join("vello_cpu/filled_rects").join(parameter);
+            let benchmark = check_benchmark(&path, mean_ns);
+            assert_eq!(benchmark["value_str"], parameter);
+        }
+        check_benchmark(&dir.0.join("vello_cpu/tiger"), 500.0);
+
+        // A single level leaves only the mode.
+        let dir = TempDir::new("criterion_modes_one_level");
+        let report = file(vec![
+            with_mode("avx2", "speed", 1000.0),
+            with_mode("avx2", "quality", 2000.0),
+        ]);
+        write_criterion_compatible(&report, &dir.0).unwrap();
+        check_benchmark(&dir.0.join("vello_cpu/filled_rects/speed"), 1000.0);
+        check_benchmark(&dir.0.join("vello_cpu/filled_rects/quality"), 2000.0);
+    }
+}
@@ -0,0 +1,457 @@
+//! Change of a result against the previous result of the same benchmark on
+//! the same machine, for instant feedback after a run.
+//!
+//! Prior results are looked up in a [`PriorStore`] by [`PriorKey`]: benchmark
+//! ID, SIMD variant, render mode, GPU sync mode and an
+//! [`environment_fingerprint`]. The
+//! fingerprint covers the coarse environment only (architecture, SIMD level,
+//! browser and major version, CPU, core count and adapter), so a browser
+//! update starts over while a new label does not. The browser persists the
+//! store itself (e.g. in IndexedDB) through [`MemoryPriorStore::entries`] and
+//! [`MemoryPriorStore::from_entries`].
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{BenchmarkResult, EnvironmentInfo, SyncMode, coarsen_user_agent};
+
+/// Change of a result against the prior result of its benchmark.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ResultDelta {
+    /// Mean time of the prior result, in nanoseconds.
+    pub prior_mean_ns: f64,
+    /// Change of the mean time in percent; positive means slower.
+    pub change_pct: f64,
+    /// When the prior result was measured (milliseconds since epoch).
+    pub prior_timestamp: u64,
+}
+
+impl ResultDelta {
+    /// The change from `prior` to a mean time of `mean_ns`, or `None` if the
+    /// prior mean is not positive.
+    pub fn new(prior: &PriorResult, mean_ns: f64) -> Option<Self> {
+        (prior.mean_ns > 0.0).then(|| Self {
+            prior_mean_ns: prior.mean_ns,
+            change_pct: (mean_ns - prior.mean_ns) / prior.mean_ns * 100.0,
+            prior_timestamp: prior.timestamp_ms,
+        })
+    }
+}
+
+/// A stable hash of the coarse fields of `env`, identifying the machine and
+/// browser a result was measured on, as 16 hex digits (a `u64` doesn't fit
+/// a JavaScript number). The label is left out.
+pub fn environment_fingerprint(env: &EnvironmentInfo) -> String {
+    let client = env
+        .user_agent
+        .as_deref()
+        .map(coarsen_user_agent)
+        .unwrap_or_else(|| "native".to_string());
+    let core_count = env.core_count.map(|n| n.to_string());
+    let fields = [
+        env.target_arch.as_str(),
+        env.simd_level.as_str(),
+        &client,
+        env.cpu_model.as_deref().unwrap_or("-"),
+        core_count.as_deref().unwrap_or("-"),
+        env.adapter.as_deref().unwrap_or("-"),
+    ];
+    // Separated by a byte that can't occur in the fields.
+    let hash = crate::hash::stable_hash(&fields.join("\0"));
+    format!("{hash:016x}")
+}
+
+/// What a prior result is looked up by.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct PriorKey {
+    pub id: String,
+    pub simd_variant: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub render_mode: Option<String>,
+    /// Results that don't record one count as [`SyncMode::Finish`], which
+    /// GPU benchmarks used before it was recorded.
+    #[serde(default, skip_serializing_if = "SyncMode::is_finish")]
+    pub sync_mode: SyncMode,
+    /// See [`environment_fingerprint`].
+    pub fingerprint: String,
+}
+
+impl PriorKey {
+    /// The key of `result` measured in the environment with `fingerprint`.
+    pub fn new(result: &BenchmarkResult, fingerprint: &str) -> Self {
+        Self {
+            id: result.id.clone(),
+            simd_variant: result.simd_variant.clone(),
+            render_mode: result.render_mode.clone(),
+            sync_mode: result.sync_mode.unwrap_or_default(),
+            fingerprint: fingerprint.to_string(),
+        }
+    }
+}
+
+/// The part of a result kept to compare later ones against.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PriorResult {
+    pub mean_ns: f64,
+    pub timestamp_ms: u64,
+}
+
+/// Storage of the latest result per [`PriorKey`].
+pub trait PriorStore {
+    fn get(&self, key: &PriorKey) -> Option<PriorResult>;
+
+    /// Store `prior`, replacing any earlier result for `key`.
+    fn put(&mut self, key: PriorKey, prior: PriorResult);
+}
+
+/// One entry of a [`MemoryPriorStore`], as persisted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriorEntry {
+    #[serde(flatten)]
+    pub key: PriorKey,
+    #[serde(flatten)]
+    pub prior: PriorResult,
+}
+
+/// A [`PriorStore`] in memory.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryPriorStore {
+    priors: HashMap<PriorKey, PriorResult>,
+}
+
+impl MemoryPriorStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A store holding `entries`. Of entries with the same key, the last
+    /// one wins.
+    pub fn from_entries(entries: impl IntoIterator<Item = PriorEntry>) -> Self {
+        Self {
+            priors: entries.into_iter().map(|e| (e.key, e.prior)).collect(),
+        }
+    }
+
+    /// All entries, sorted by key, for persisting.
+    pub fn entries(&self) -> Vec<PriorEntry> {
+        let mut entries: Vec<PriorEntry> = self
+            .priors
+            .iter()
+            .map(|(key, prior)| PriorEntry {
+                key: key.clone(),
+                prior: *prior,
+            })
+            .collect();
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.priors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.priors.is_empty()
+    }
+}
+
+impl PriorStore for MemoryPriorStore {
+    fn get(&self, key: &PriorKey) -> Option<PriorResult> {
+        self.priors.get(key).copied()
+    }
+
+    fn put(&mut self, key: PriorKey, prior: PriorResult) {
+        self.priors.insert(key, prior);
+    }
+}
+
+/// Attaches a [`ResultDelta`] to each result measured in one environment and
+/// remembers the result as the prior of the next one.
+#[derive(Debug, Clone)]
+pub struct DeltaTracker<S: PriorStore = MemoryPriorStore> {
+    fingerprint: String,
+    store: S,
+}
+
+impl<S: PriorStore> DeltaTracker<S> {
+    /// A tracker for results measured in `env`, with priors from `store`.
+    pub fn new(env: &EnvironmentInfo, store: S) -> Self {
+        Self {
+            fingerprint: environment_fingerprint(env),
+            store,
+        }
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    pub fn store_mut(&mut self) -> &mut S {
+        &mut self.store
+    }
+
+    pub fn into_store(self) -> S {
+        self.store
+    }
+
+    /// Set [`BenchmarkResult::delta`] from the prior result of the same
+    /// benchmark, if there is one, and make `result` the new prior.
+    /// Single-shot estimates are neither compared nor kept.
+    pub fn apply(&mut self, result: &mut BenchmarkResult) {
+        if result.is_estimate() {
+            return;
+        }
+        let key = PriorKey::new(result, &self.fingerprint);
+        let mean_ns = result.statistics.mean_ns;
+        result.delta = self
+            .store
+            .get(&key)
+            .and_then(|prior| ResultDelta::new(&prior, mean_ns));
+        self.store.put(
+            key,
+            PriorResult {
+                mean_ns,
+                timestamp_ms: result.timestamp_ms,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::result::{MeasurementMode, Statistics};
+
+    fn result(id: &str, mean_ns: f64, timestamp_ms: u64) -> BenchmarkResult {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "category": "vello_cpu",
+            "name": id,
+            "simd_variant": "avx2",
+            "statistics": Statistics::from_mean(mean_ns, 10),
+            "timestamp_ms": timestamp_ms,
+        }))
+        .unwrap()
+    }
+
+    fn chrome(version: &str) -> Option<String> {
+        Some(format!(
+            "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) \
+             Chrome/{version} Safari/537.36"
+        ))
+    }
+
+    fn environment() -> EnvironmentInfo {
+        EnvironmentInfo {
+            label: Some("laptop".to_string()),
+            target_arch: "wasm32".to_string(),
+            simd_level: "wasm_simd128".to_string(),
+            user_agent: chrome("120.0.6099.109"),
+            core_count: Some(8),
+            adapter: Some("Intel UHD 620".to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Run `results` through `tracker` and return the change of each in
+    /// percent.
+    fn deltas(
+        tracker: &mut DeltaTracker<impl PriorStore>,
+        results: &mut [BenchmarkResult],
+    ) -> Vec<Option<f64>> {
+        results
+            .iter_mut()
+            .map(|result| {
+                tracker.apply(result);
+                result.delta.map(|delta| delta.change_pct)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn delta_is_the_change_of_the_mean_in_percent() {
+        let prior = PriorResult {
+            mean_ns: 2000.0,
+            timestamp_ms: 1_700_000_000_000,
+        };
+        let slower = ResultDelta::new(&prior, 2500.0).unwrap();
+        assert_eq!(slower.change_pct, 25.0);
+        assert_eq!(slower.prior_mean_ns, 2000.0);
+        assert_eq!(slower.prior_timestamp, 1_700_000_000_000);
+        assert_eq!(ResultDelta::new(&prior, 1500.0).unwrap().change_pct, -25.0);
+        assert_eq!(ResultDelta::new(&prior, 2000.0).unwrap().change_pct, 0.0);
+
+        let empty = PriorResult {
+            mean_ns: 0.0,
+            timestamp_ms: 0,
+        };
+        assert_eq!(ResultDelta::new(&empty, 2000.0), None);
+    }
+
+    #[test]
+    fn fingerprint_covers_the_coarse_environment() {
+        let env = environment();
+        let fingerprint = environment_fingerprint(&env);
+        assert_eq!(fingerprint.len(), 16);
+        assert!(fingerprint.bytes().all(|b| b.is_ascii_hexdigit()));
+
+        // A new label or a minor browser update keep the priors.
+        let same = [
+            EnvironmentInfo {
+                label: None,
+                ..env.clone()
+            },
+            EnvironmentInfo {
+                user_agent: chrome("120.0.6099.217"),
+                ..env.clone()
+            },
+            EnvironmentInfo {
+                crate_version: Some("0.2.0".to_string()),
+                ..env.clone()
+            },
+        ];
+        for other in &same {
+            assert_eq!(environment_fingerprint(other), fingerprint, "{other:?}");
+        }
+
+        let different = [
+            EnvironmentInfo {
+                target_arch: "x86_64".to_string(),
+                ..env.clone()
+            },
+            EnvironmentInfo {
+                simd_level: "scalar".to_string(),
+                ..env.clone()
+            },
+            EnvironmentInfo {
+                user_agent: chrome("121.0.6167.85"),
+                ..env.clone()
+            },
+            EnvironmentInfo {
+                user_agent: None,
+                ..env.clone()
+            },
+            EnvironmentInfo {
+                cpu_model: Some("Apple M2".to_string()),
+                ..env.clone()
+            },
+            EnvironmentInfo {
+                core_count: Some(4),
+                ..env.clone()
+            },
+            EnvironmentInfo {
+                adapter: None,
+                ..env.clone()
+            },
+        ];
+        for other in &different {
+            assert_ne!(environment_fingerprint(other), fingerprint, "{other:?}");
+        }
+    }
+
+    #[test]
+    fn tracker_compares_each_result_with_the_last_of_its_key() {
+        let mut tracker = DeltaTracker::new(&environment(), MemoryPriorStore::new());
+        let mut quality = result("vello_cpu/tiger_quality", 1000.0, 4);
+        quality.render_mode = Some("quality".to_string());
+        let mut scalar = result("vello_cpu/tiger", 3000.0, 5);
+        scalar.simd_variant = "scalar".to_string();
+
+        let mut results = [
+            result("vello_cpu/tiger", 1000.0, 1),
+            result("vello_cpu/tiger", 1100.0, 2),
+            result("vello_cpu/tiger", 880.0, 3),
+            quality,
+            scalar,
+        ];
+        let changes = deltas(&mut tracker, &mut results);
+        assert_eq!(changes[0], None);
+        assert!((changes[1].unwrap() - 10.0).abs() < 1e-9);
+        assert!((changes[2].unwrap() + 20.0).abs() < 1e-9);
+        // Other render modes and SIMD variants have their own priors.
+        assert_eq!(changes[3..], [None, None]);
+        assert_eq!(results[2].delta.unwrap().prior_timestamp, 2);
+        assert_eq!(tracker.store().len(), 3);
+    }
+
+    #[test]
+    fn tracker_keys_by_sync_mode() {
+        let mut tracker = DeltaTracker::new(&environment(), MemoryPriorStore::new());
+        let mut fence = result("vello_hybrid/tiger", 500.0, 2);
+        fence.sync_mode = Some(SyncMode::Fence);
+        let mut finish = result("vello_hybrid/tiger", 1200.0, 3);
+        finish.sync_mode = Some(SyncMode::Finish);
+
+        // A result without a sync mode counts as `Finish`.
+        let mut results = [result("vello_hybrid/tiger", 1000.0, 1), fence, finish];
+        let changes = deltas(&mut tracker, &mut results);
+        assert_eq!(changes[..2], [None, None]);
+        assert!((changes[2].unwrap() - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn tracker_ignores_estimates() {
+        let mut tracker = DeltaTracker::new(&environment(), MemoryPriorStore::new());
+        let mut estimate = result("vello_cpu/tiger", 5000.0, 2);
+        estimate.measurement_mode = MeasurementMode::SingleShot;
+
+        let mut results = [
+            result("vello_cpu/tiger", 1000.0, 1),
+            estimate,
+            result("vello_cpu/tiger", 1000.0, 3),
+        ];
+        let changes = deltas(&mut tracker, &mut results);
+        assert_eq!(changes, [None, None, Some(0.0)]);
+        assert_eq!(tracker.store().len(), 1);
+    }
+
+    #[test]
+    fn priors_persist_through_entries() {
+        let mut tracker = DeltaTracker::new(&environment(), MemoryPriorStore::new());
+        deltas(
+            &mut tracker,
+            &mut [
+                result("vello_cpu/tiger", 1000.0, 1),
+                result("vello_cpu/paris", 2000.0, 2),
+            ],
+        );
+
+        // As the browser would save and restore them.
+        let json = serde_json::to_string(&tracker.into_store().entries()).unwrap();
+        let entries: Vec<PriorEntry> = serde_json::from_str(&json).unwrap();
+        let store = MemoryPriorStore::from_entries(entries);
+        assert_eq!(store.len(), 2);
+
+        let mut tracker = DeltaTracker::new(&environment(), store.clone());
+        let changes = deltas(&mut tracker, &mut [result("vello_cpu/paris", 3000.0, 3)]);
+        assert_eq!(changes, [Some(50.0)]);
+
+        // Another machine starts over.
+        let other = EnvironmentInfo {
+            core_count: Some(16),
+            ..environment()
+        };
+        let mut tracker = DeltaTracker::new(&other, store);
+        let changes = deltas(&mut tracker, &mut [result("vello_cpu/paris", 3000.0, 3)]);
+        assert_eq!(changes, [None]);
+    }
+
+    #[test]
+    fn later_entries_win() {
+        let key = PriorKey::new(
+            &result("vello_cpu/tiger", 1.0, 0),
+            &environment_fingerprint(&environment()),
+        );
+        let entry = |mean_ns| PriorEntry {
+            key: key.clone(),
+            prior: PriorResult {
+                mean_ns,
+                timestamp_ms: 0,
+            },
+        };
+        let store = MemoryPriorStore::from_entries([entry(1.0), entry(2.0)]);
+        assert_eq!(store.len(), 1);
+        assert_eq!(store.get(&key).unwrap().mean_ns, 2.0);
+    }
+}
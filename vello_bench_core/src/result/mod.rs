@@ -0,0 +1,1016 @@
+mod anonymize;
+mod binary;
+pub mod compare;
+mod config;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod criterion;
+pub mod delta;
+pub mod multi_canvas;
+pub mod report;
+#[cfg(not(target_arch = "wasm32"))]
+mod stream;
+
+pub use anonymize::{
+    AdapterPolicy, AggregateEntry, AggregateGroup, AggregateReport, AnonymizePolicy, aggregate,
+    anonymize, coarsen_user_agent,
+};
+pub use binary::is_binary;
+pub use config::{ENV_PREFIX, RunConfig, ShardSet, env_snapshot};
+#[cfg(not(target_arch = "wasm32"))]
+pub use stream::{JsonLinesObserver, recover_partial};
+
+use crate::health::HealthReport;
+use crate::marks::RecordedMarks;
+use crate::memory::MemorySample;
+use serde::{Deserialize, Serialize};
+
+/// Statistics from a benchmark run.
+///
+/// The spread (`median_ns` to `std_dev_ns`) is computed from
+/// [`samples`](Self::samples). Without samples, the percentiles, minimum and
+/// maximum are the mean and the standard deviation is 0, which is also what
+/// results from before these fields existed read as.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "StatisticsFields")]
+pub struct Statistics {
+    /// Mean time in nanoseconds.
+    pub mean_ns: f64,
+    /// Number of iterations.
+    pub iterations: usize,
+    /// Median time of an iteration in nanoseconds.
+    pub median_ns: f64,
+    /// 95th percentile time of an iteration in nanoseconds.
+    pub p95_ns: f64,
+    /// Fastest iteration in nanoseconds.
+    pub min_ns: f64,
+    /// Slowest iteration in nanoseconds.
+    pub max_ns: f64,
+    /// Sample standard deviation of the iteration times in nanoseconds.
+    pub std_dev_ns: f64,
+    /// Times of individual iterations in nanoseconds, if the runner kept them
+    /// (see [`BenchRunner::sample_iterations`](crate::BenchRunner::sample_iterations)).
+    /// In measurement order, unless there were more iterations than the
+    /// runner keeps; then a random subset, and the spread is that of the
+    /// subset. The mean is still over all iterations.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub samples: Vec<f64>,
+    /// Mean GPU time of a measured iteration in nanoseconds, between
+    /// timestamps written before and after its GPU work (see
+    /// [`gpu_timestamps`](crate::gpu_timestamps)). Leaves out CPU-side scene
+    /// replay, encoding and the sync. `None` for CPU benchmarks and where the
+    /// adapter has no timestamp queries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gpu_time_ns: Option<f64>,
+}
+
+impl Statistics {
+    /// Create statistics from a single measurement.
+    pub fn from_measurement(total_time_ns: f64, iterations: usize) -> Self {
+        Self::from_mean(total_time_ns / iterations as f64, iterations)
+    }
+
+    /// Statistics with a mean of `mean_ns` and no spread.
+    pub fn from_mean(mean_ns: f64, iterations: usize) -> Self {
+        Self {
+            mean_ns,
+            iterations,
+            median_ns: mean_ns,
+            p95_ns: mean_ns,
+            min_ns: mean_ns,
+            max_ns: mean_ns,
+            std_dev_ns: 0.0,
+            samples: Vec::new(),
+            gpu_time_ns: None,
+        }
+    }
+
+    /// These statistics with `samples` as their samples and the spread
+    /// computed from them. Keeps the mean; without samples, there is no
+    /// spread.
+    pub fn with_samples(self, samples: Vec<f64>) -> Self {
+        if samples.is_empty() {
+            return Self::from_mean(self.mean_ns, self.iterations);
+        }
+
+        let mut sorted = samples.clone();
+        sorted.sort_by(f64::total_cmp);
+        let n = sorted.len() as f64;
+        let sample_mean = sorted.iter().sum::<f64>() / n;
+        let std_dev_ns = if sorted.len() > 1 {
+            let square_sum: f64 = sorted.iter().map(|t| (t - sample_mean).powi(2)).sum();
+            (square_sum / (n - 1.0)).sqrt()
+        } else {
+            0.0
+        };
+
+        Self {
+            median_ns: quantile(&sorted, 0.5),
+            p95_ns: quantile(&sorted, 0.95),
+            min_ns: sorted[0],
+            max_ns: sorted[sorted.len() - 1],
+            std_dev_ns,
+            samples,
+            ..self
+        }
+    }
+
+    /// These statistics without the samples outside Tukey's fences, `k`
+    /// interquartile ranges below the first and above the third quartile,
+    /// and the number of samples removed.
+    ///
+    /// Mean, spread and iteration count are then those of the remaining
+    /// samples. Without samples, or with nothing outside the fences, the
+    /// statistics are returned as they are.
+    ///
+    /// If the samples are a random subset of more iterations (see
+    /// [`Self::samples`]), the mean is still that of the remaining subset, an
+    /// estimate of the trimmed mean of all iterations. The iteration count is
+    /// then the share of the subset that remained, applied to all
+    /// iterations, and the number removed is that of the subset.
+    pub fn without_outliers(&self, k: f64) -> (Self, usize) {
+        let mut sorted = self.samples.clone();
+        sorted.sort_by(f64::total_cmp);
+        let q1 = quantile(&sorted, 0.25);
+        let q3 = quantile(&sorted, 0.75);
+        let (low, high) = (q1 - k * (q3 - q1), q3 + k * (q3 - q1));
+
+        let kept: Vec<f64> = self
+            .samples
+            .iter()
+            .copied()
+            .filter(|t| (low..=high).contains(t))
+            .collect();
+        let removed = self.samples.len() - kept.len();
+        if removed == 0 || kept.is_empty() {
+            return (self.clone(), 0);
+        }
+        let iterations = if self.samples.len() < self.iterations {
+            let kept_share = kept.len() as f64 / self.samples.len() as f64;
+            ((self.iterations as f64 * kept_share).round() as usize).max(kept.len())
+        } else {
+            kept.len()
+        };
+        let trimmed = Self {
+            iterations,
+            gpu_time_ns: self.gpu_time_ns,
+            ..Self::from_measurement(kept.iter().sum(), kept.len()).with_samples(kept)
+        };
+        (trimmed, removed)
+    }
+
+    /// Whether the spread differs from that of [`Self::from_mean`].
+    pub fn has_spread(&self) -> bool {
+        [self.median_ns, self.p95_ns, self.min_ns, self.max_ns] != [self.mean_ns; 4]
+            || self.std_dev_ns != 0.0
+    }
+}
+
+/// [`Statistics`] as stored, where the spread may be missing.
+#[derive(Deserialize)]
+struct StatisticsFields {
+    mean_ns: f64,
+    iterations: usize,
+    median_ns: Option<f64>,
+    p95_ns: Option<f64>,
+    min_ns: Option<f64>,
+    max_ns: Option<f64>,
+    std_dev_ns: Option<f64>,
+    #[serde(default)]
+    samples: Vec<f64>,
+    #[serde(default)]
+    gpu_time_ns: Option<f64>,
+}
+
+impl From<StatisticsFields> for Statistics {
+    fn from(f: StatisticsFields) -> Self {
+        Self {
+            median_ns: f.median_ns.unwrap_or(f.mean_ns),
+            p95_ns: f.p95_ns.unwrap_or(f.mean_ns),
+            min_ns: f.min_ns.unwrap_or(f.mean_ns),
+            max_ns: f.max_ns.unwrap_or(f.mean_ns),
+            std_dev_ns: f.std_dev_ns.unwrap_or(0.0),
+            samples: f.samples,
+            gpu_time_ns: f.gpu_time_ns,
+            ..Self::from_mean(f.mean_ns, f.iterations)
+        }
+    }
+}
+
+/// Amount of work done by a single benchmark iteration, used to derive a
+/// per-element cost from the mean iteration time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Throughput {
+    /// Number of logical elements (e.g. draw commands) processed per iteration.
+    Elements(u64),
+}
+
+/// How a result was measured.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MeasurementMode {
+    /// Warm-up followed by the configured number of timed iterations.
+    #[default]
+    Full,
+    /// A single timed iteration without warm-up. Only a rough estimate; must
+    /// not be used as a comparison baseline.
+    SingleShot,
+}
+
+impl MeasurementMode {
+    fn is_full(&self) -> bool {
+        *self == Self::Full
+    }
+}
+
+/// What a micro-benchmark warms up on, see
+/// [`BenchRunner::run_micro`](crate::BenchRunner::run_micro).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WarmupInput {
+    /// The input that is measured.
+    #[default]
+    Same,
+    /// A different input of the same size, so that branch predictors and
+    /// caches can't be trained on the measured data.
+    Distinct,
+}
+
+impl WarmupInput {
+    /// Parse `same` or `distinct`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "same" => Some(Self::Same),
+            "distinct" => Some(Self::Distinct),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Same => "same",
+            Self::Distinct => "distinct",
+        }
+    }
+
+    fn is_same(&self) -> bool {
+        *self == Self::Same
+    }
+}
+
+/// How a GPU benchmark waits for each frame to finish on the GPU.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub enum SyncMode {
+    /// Wait for the whole pipeline to drain: `gl.finish()` in WebGL, a
+    /// blocking device poll natively. Can overstate the frame time compared
+    /// to real applications.
+    #[default]
+    Finish,
+    /// Wait only for the frame's own commands: poll a `fenceSync` with
+    /// `clientWaitSync` in WebGL, `Queue::on_submitted_work_done` natively.
+    Fence,
+}
+
+impl SyncMode {
+    /// Parse `finish` or `fence`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "finish" => Some(Self::Finish),
+            "fence" => Some(Self::Fence),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Finish => "finish",
+            Self::Fence => "fence",
+        }
+    }
+
+    pub(crate) fn is_finish(&self) -> bool {
+        *self == Self::Finish
+    }
+}
+
+/// Result from running a single benchmark.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkResult {
+    /// Full benchmark ID (e.g., "fine/fill/opaque_short").
+    pub id: String,
+    /// Category (e.g., "fine/fill").
+    pub category: String,
+    /// Benchmark name (e.g., "opaque_short").
+    pub name: String,
+    /// SIMD variant used (e.g., "u8_neon", "scalar").
+    pub simd_variant: String,
+    /// Benchmark statistics, without the outliers a runner with
+    /// [`OutlierPolicy::Trim`](crate::runner::OutlierPolicy::Trim) removed.
+    pub statistics: Statistics,
+    /// Statistics over all measured iterations, if outliers were removed
+    /// from [`Self::statistics`]. Not kept in the binary format.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_statistics: Option<Statistics>,
+    /// Number of iterations removed as outliers, if the runner looked for
+    /// them; of the kept samples only, if those are a subset (see
+    /// [`Statistics::without_outliers`]). Not kept in the binary format.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outliers_removed: Option<usize>,
+    /// Whether the run was stopped through a
+    /// [`CancellationToken`](crate::runner::CancellationToken), so that
+    /// [`Self::statistics`] cover only the iterations measured until then.
+    /// Not kept in the binary format.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cancelled: bool,
+    /// Statistics of the warm-up, or of the calibration of a
+    /// [`TimeBudget`](crate::runner::TimeBudget), if it ran any iterations.
+    /// GPU benchmarks, benchmarks with per-iteration setup and runners with
+    /// [`BenchRunner::sample_iterations`](crate::BenchRunner::sample_iterations)
+    /// time each warm-up iteration and keep the times in order in its
+    /// `samples`, so the first frame (shader compiles, JIT tiers) can be
+    /// compared with the last. Others only time the warm-up as a whole, which
+    /// includes the bookkeeping between iterations. Not kept in the binary
+    /// format.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warmup: Option<Statistics>,
+    /// Work done per iteration, if the benchmark reports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub throughput: Option<Throughput>,
+    /// Render mode the benchmark ran with (e.g. "speed", "quality"), for
+    /// benchmarks where it applies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub render_mode: Option<String>,
+    /// How the result was measured. Omitted from JSON for full measurements.
+    #[serde(default, skip_serializing_if = "MeasurementMode::is_full")]
+    pub measurement_mode: MeasurementMode,
+    /// Whether the warm-up drew a lighter proxy of the scene for all but its
+    /// last frame. Measured frames are always full-size.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub warmup_proxy: bool,
+    /// Whether every frame drew different content (see
+    /// [`VelloScene::ANIMATED`](crate::vello_scenes::VelloScene::ANIMATED)),
+    /// so the renderer couldn't reuse work between frames. Not kept in the
+    /// binary format.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub animated: bool,
+    /// Whether the warm-up ran on a different input than the measurement.
+    /// Only micro-benchmarks do; see [`WarmupInput`].
+    #[serde(default, skip_serializing_if = "WarmupInput::is_same")]
+    pub warmup_input: WarmupInput,
+    /// Whether this benchmark ran on a thread pool alongside other benchmarks
+    /// (see [`run_all_parallel`](crate::registry::run_all_parallel)). Such
+    /// results are noisier than exclusive runs.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ran_concurrently: bool,
+    /// Worker threads of the renderer, for benchmarks that vary it (see
+    /// [`cpu_threads`](crate::benchmarks::cpu_threads)). `0` renders on the
+    /// calling thread.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_threads: Option<u16>,
+    /// How a GPU benchmark waited for its frames, see [`SyncMode`]. Results
+    /// with different sync modes are not compared. `None` for CPU
+    /// benchmarks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_mode: Option<SyncMode>,
+    /// Names of the performance marks the run emitted, if
+    /// [`set_record_marks`](crate::marks::set_record_marks) was on. Not kept
+    /// in the binary format.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub marks: Option<RecordedMarks>,
+    /// Change against the previous result of this benchmark on the same
+    /// machine, if [`DeltaTracker`](delta::DeltaTracker) attached one. Not
+    /// kept in the binary format.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delta: Option<delta::ResultDelta>,
+    /// For a run rendering to several canvases in turn, its comparison with
+    /// a single-canvas run of the same scene. Not kept in the binary format.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canvas_overhead: Option<multi_canvas::CanvasOverhead>,
+    /// Timestamp when benchmark was run (milliseconds since epoch).
+    pub timestamp_ms: u64,
+}
+
+impl BenchmarkResult {
+    /// Whether this is a single-shot estimate rather than a full measurement.
+    /// Estimates must not be used as comparison baselines.
+    pub fn is_estimate(&self) -> bool {
+        self.measurement_mode == MeasurementMode::SingleShot
+    }
+
+    /// Mean time per element in nanoseconds, if [`Self::throughput`] is set.
+    pub fn mean_ns_per_element(&self) -> Option<f64> {
+        match self.throughput? {
+            Throughput::Elements(0) => None,
+            Throughput::Elements(n) => Some(self.statistics.mean_ns / n as f64),
+        }
+    }
+}
+
+/// Outcome of attempting to run a benchmark.
+///
+/// Serialized with a `status` tag; a completed outcome serializes as the
+/// result's own fields plus `"status": "completed"`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum BenchmarkOutcome {
+    /// The benchmark ran to completion.
+    Completed(BenchmarkResult),
+    /// The benchmark was aborted, e.g. because the GPU stopped responding.
+    Failed {
+        /// Full benchmark ID.
+        id: String,
+        /// What went wrong (e.g. "GPU timeout after 10s").
+        error: String,
+        /// GPU adapter in use when the failure happened, if relevant.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        adapter: Option<String>,
+    },
+}
+
+impl BenchmarkOutcome {
+    /// The result, if the benchmark completed.
+    pub fn result(&self) -> Option<&BenchmarkResult> {
+        match self {
+            Self::Completed(result) => Some(result),
+            Self::Failed { .. } => None,
+        }
+    }
+}
+
+/// Machine and configuration a set of results was produced on.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnvironmentInfo {
+    /// Optional user-supplied label (e.g. "laptop, on battery").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Target architecture (e.g. "x86_64", "wasm32").
+    pub target_arch: String,
+    /// SIMD level suffix the results were produced with (e.g. "avx2").
+    pub simd_level: String,
+    /// Suffixes of every SIMD level the machine supports, best first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub available_simd_levels: Vec<String>,
+    /// Browser user agent (WASM only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    /// CPU model name (native only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_model: Option<String>,
+    /// Number of logical CPU cores.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub core_count: Option<u32>,
+    /// GPU adapter description used by the hybrid benchmarks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adapter: Option<String>,
+    /// Version of `vello_bench_core` the results were produced with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crate_version: Option<String>,
+}
+
+impl EnvironmentInfo {
+    /// Describe the machine this runs on, with `level` as the SIMD level.
+    ///
+    /// Natively the CPU model comes from `/proc/cpuinfo`, so it is only
+    /// known on Linux, and only where the kernel reports a model name. In
+    /// the browser the user agent and core count come from `navigator`, of
+    /// the window or, on a worker, of the worker scope. The label and
+    /// adapter are left for the caller to fill in.
+    pub fn capture(level: fearless_simd::Level) -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        let (user_agent, cpu_model, core_count) = (
+            None,
+            native_cpu_model(),
+            std::thread::available_parallelism()
+                .ok()
+                .map(|n| n.get() as u32),
+        );
+        #[cfg(target_arch = "wasm32")]
+        let (user_agent, cpu_model, core_count) = {
+            let navigator = js_sys::Reflect::get(&js_sys::global(), &"navigator".into()).ok();
+            let field = |name: &str| js_sys::Reflect::get(navigator.as_ref()?, &name.into()).ok();
+            (
+                field("userAgent").and_then(|v| v.as_string()),
+                None,
+                field("hardwareConcurrency")
+                    .and_then(|v| v.as_f64())
+                    .map(|n| n as u32),
+            )
+        };
+
+        Self {
+            label: None,
+            target_arch: std::env::consts::ARCH.to_string(),
+            simd_level: crate::simd::level_suffix(level).to_string(),
+            available_simd_levels: crate::simd::available_levels()
+                .into_iter()
+                .map(|level| crate::simd::level_suffix(level).to_string())
+                .collect(),
+            user_agent,
+            cpu_model,
+            core_count,
+            adapter: None,
+            crate_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        }
+    }
+
+    /// A stable hash of the fields that decide whether results of two
+    /// environments are comparable, as 16 hex digits: architecture, the
+    /// available SIMD levels, browser and major version, CPU model, core
+    /// count, adapter and crate version. Equal for two captures on the same
+    /// machine and build, whatever level they ran at: the level of each
+    /// result is part of its key instead.
+    ///
+    /// Unlike [`delta::environment_fingerprint`], which keys prior results
+    /// across updates, it includes the crate version.
+    pub fn fingerprint(&self) -> String {
+        let fields = self.fingerprint_fields().map(|(_, value)| value);
+        // Separated by a byte that can't occur in the fields.
+        let hash = crate::hash::stable_hash(&fields.join("\0"));
+        format!("{hash:016x}")
+    }
+
+    /// Names of the fields covered by [`Self::fingerprint`] in which `self`
+    /// and `other` differ.
+    pub fn fingerprint_differences(&self, other: &Self) -> Vec<&'static str> {
+        self.fingerprint_fields()
+            .into_iter()
+            .zip(other.fingerprint_fields())
+            .filter(|((_, a), (_, b))| a != b)
+            .map(|((name, _), _)| name)
+            .collect()
+    }
+
+    fn fingerprint_fields(&self) -> [(&'static str, String); 7] {
+        let client = self
+            .user_agent
+            .as_deref()
+            .map(coarsen_user_agent)
+            .unwrap_or_else(|| "native".to_string());
+        let or_dash = |value: Option<&str>| value.unwrap_or("-").to_string();
+        [
+            ("target_arch", self.target_arch.clone()),
+            (
+                "available_simd_levels",
+                self.available_simd_levels.join(","),
+            ),
+            ("user_agent", client),
+            ("cpu_model", or_dash(self.cpu_model.as_deref())),
+            (
+                "core_count",
+                or_dash(self.core_count.map(|n| n.to_string()).as_deref()),
+            ),
+            ("adapter", or_dash(self.adapter.as_deref())),
+            ("crate_version", or_dash(self.crate_version.as_deref())),
+        ]
+    }
+}
+
+/// The `model name` of the first CPU in `/proc/cpuinfo`.
+#[cfg(not(target_arch = "wasm32"))]
+fn native_cpu_model() -> Option<String> {
+    let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").ok()?;
+    cpuinfo.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == "model name").then(|| value.trim().to_string())
+    })
+}
+
+/// A complete set of results from one run, as saved to or loaded from disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultFile {
+    /// Format version of this document.
+    pub schema_version: u32,
+    /// Wall-clock start of the run (milliseconds since epoch).
+    pub started_ms: u64,
+    /// Wall-clock end of the run (milliseconds since epoch).
+    pub finished_ms: u64,
+    /// Environment the run was performed in.
+    pub environment: EnvironmentInfo,
+    /// Individual benchmark results.
+    pub results: Vec<BenchmarkResult>,
+    /// Memory usage sampled between benchmarks, if sampling was enabled.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub memory_timeline: Vec<MemorySample>,
+    /// Whether the run was interrupted before all selected benchmarks ran,
+    /// e.g. a file rebuilt by [`recover_partial`].
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub partial: bool,
+    /// Settings the run was produced with, if recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<RunConfig>,
+    /// Health of the environment before the run, if it was checked (see
+    /// [`health_check`](crate::registry::health_check)).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<HealthReport>,
+}
+
+impl ResultFile {
+    /// Current [`Self::schema_version`].
+    ///
+    /// Bump it when a change makes old files read wrongly or not at all:
+    /// removing or renaming a field, changing a field's meaning or unit, or
+    /// adding a field without a default. New fields with `#[serde(default)]`
+    /// don't need a bump, since old files still parse and mean the same. The
+    /// binary format has its own version, see [`Self::to_bytes`].
+    pub const SCHEMA_VERSION: u32 = 1;
+
+    /// Parse either the JSON or the binary (see [`Self::to_bytes`]) format,
+    /// detected from the content.
+    pub fn parse(bytes: &[u8]) -> Result<Self, String> {
+        if is_binary(bytes) {
+            Self::from_bytes(bytes)
+        } else {
+            serde_json::from_slice(bytes).map_err(|e| format!("Failed to parse result file: {e}"))
+        }
+    }
+
+    /// Parse the JSON format.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Failed to parse result file: {e}"))
+    }
+
+    /// The file in the JSON format, pretty-printed as written to disk.
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize results: {e}"))
+    }
+
+    /// Read a result file in either format from disk.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(path: &std::path::Path) -> Result<Self, String> {
+        let bytes =
+            std::fs::read(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        Self::parse(&bytes)
+    }
+
+    /// Combine the files of one run split over several processes, e.g. the
+    /// shards of a matrix run. The environment, configuration and health
+    /// report are taken from the first file; the run spans from the earliest start to the
+    /// latest finish and is partial if any file is. Returns `None` for no
+    /// files.
+    ///
+    /// The [`RunConfig::shards`] of the merged file are those of all files,
+    /// or `None` once they add up to a whole run, or if any file doesn't
+    /// record its shards.
+    pub fn merge(files: impl IntoIterator<Item = ResultFile>) -> Option<Self> {
+        let mut files = files.into_iter();
+        let mut merged = files.next()?;
+        let mut shards = merged.config.as_ref().and_then(|c| c.shards.clone());
+        for file in files {
+            merged.started_ms = merged.started_ms.min(file.started_ms);
+            merged.finished_ms = merged.finished_ms.max(file.finished_ms);
+            merged.results.extend(file.results);
+            merged.memory_timeline.extend(file.memory_timeline);
+            merged.partial |= file.partial;
+            let file_shards = file.config.and_then(|c| c.shards);
+            shards = shards.zip(file_shards).and_then(|(a, b)| a.union(&b));
+        }
+        if let Some(config) = &mut merged.config {
+            config.shards = shards.filter(|shards| !shards.is_complete());
+        }
+        Some(merged)
+    }
+}
+
+/// Quantile `q` in `[0, 1]` of an ascending slice, linearly interpolating
+/// between the closest ranks. Returns `NaN` for an empty slice.
+pub(crate) fn quantile(sorted: &[f64], q: f64) -> f64 {
+    match sorted.len() {
+        0 => f64::NAN,
+        1 => sorted[0],
+        n => {
+            let pos = q.clamp(0.0, 1.0) * (n - 1) as f64;
+            let lo = pos.floor() as usize;
+            let hi = pos.ceil() as usize;
+            sorted[lo] + (sorted[hi] - sorted[lo]) * (pos - lo as f64)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{actual} is not {expected}"
+        );
+    }
+
+    #[test]
+    fn quantiles_interpolate_between_ranks() {
+        assert!(quantile(&[], 0.5).is_nan());
+        for q in [0.0, 0.5, 0.95, 1.0] {
+            assert_eq!(quantile(&[7.0], q), 7.0);
+        }
+        let sorted = [1.0, 2.0, 3.0, 4.0];
+        assert_close(quantile(&sorted, 0.0), 1.0);
+        assert_close(quantile(&sorted, 0.25), 1.75);
+        assert_close(quantile(&sorted, 0.5), 2.5);
+        assert_close(quantile(&sorted, 0.95), 3.85);
+        assert_close(quantile(&sorted, 1.0), 4.0);
+        assert_close(quantile(&sorted, -1.0), 1.0);
+        assert_close(quantile(&sorted, 2.0), 4.0);
+    }
+
+    #[test]
+    fn spread_of_samples() {
+        let statistics = Statistics::from_mean(5.0, 4).with_samples(vec![4.0, 2.0, 8.0, 6.0]);
+        assert_eq!(statistics.mean_ns, 5.0);
+        assert_eq!(statistics.iterations, 4);
+        assert_close(statistics.median_ns, 5.0);
+        assert_close(statistics.p95_ns, 7.7);
+        assert_eq!(statistics.min_ns, 2.0);
+        assert_eq!(statistics.max_ns, 8.0);
+        assert_close(statistics.std_dev_ns, (20.0f64 / 3.0).sqrt());
+        // In measurement order, not sorted.
+        assert_eq!(statistics.samples, [4.0, 2.0, 8.0, 6.0]);
+        assert!(statistics.has_spread());
+    }
+
+    #[test]
+    fn single_sample_has_no_spread() {
+        let statistics = Statistics::from_mean(5.0, 1).with_samples(vec![5.0]);
+        assert_eq!(statistics.median_ns, 5.0);
+        assert_eq!(statistics.p95_ns, 5.0);
+        assert_eq!(statistics.min_ns, 5.0);
+        assert_eq!(statistics.max_ns, 5.0);
+        assert_eq!(statistics.std_dev_ns, 0.0);
+        assert!(!statistics.has_spread());
+    }
+
+    #[test]
+    fn no_samples_keep_the_mean() {
+        let statistics = Statistics::from_mean(3.0, 10).with_samples(Vec::new());
+        assert_eq!(statistics.mean_ns, 3.0);
+        assert_eq!(statistics.iterations, 10);
+        assert!(!statistics.has_spread());
+    }
+
+    /// 100 samples around 1000 ns, and their mean.
+    fn clean_samples() -> (Vec<f64>, f64) {
+        let samples: Vec<f64> = (0..100).map(|i| 1000.0 + f64::from(i % 7)).collect();
+        let mean = samples.iter().sum::<f64>() / 100.0;
+        (samples, mean)
+    }
+
+    /// `clean` with a spike of 1 ms after every 20th sample.
+    fn with_spikes(clean: &[f64]) -> Vec<f64> {
+        let mut samples = Vec::new();
+        for chunk in clean.chunks(20) {
+            samples.extend_from_slice(chunk);
+            samples.push(1e6);
+        }
+        samples
+    }
+
+    #[test]
+    fn trimming_spikes_restores_the_clean_mean() {
+        let (clean, clean_mean) = clean_samples();
+        let spiky = with_spikes(&clean);
+        let mut statistics =
+            Statistics::from_measurement(spiky.iter().sum(), spiky.len()).with_samples(spiky);
+        statistics.gpu_time_ns = Some(500.0);
+        assert!(statistics.mean_ns > 2.0 * clean_mean);
+
+        let (trimmed, removed) = statistics.without_outliers(1.5);
+        assert_eq!(removed, 5);
+        assert_close(trimmed.mean_ns, clean_mean);
+        assert_eq!(trimmed.iterations, 100);
+        assert_eq!(trimmed.samples, clean);
+        assert_eq!(trimmed.max_ns, 1006.0);
+        assert_eq!(trimmed.gpu_time_ns, Some(500.0));
+    }
+
+    #[test]
+    fn trimming_without_outliers_changes_nothing() {
+        let (clean, clean_mean) = clean_samples();
+        let statistics = Statistics::from_mean(clean_mean, 100).with_samples(clean);
+        let (trimmed, removed) = statistics.without_outliers(1.5);
+        assert_eq!(removed, 0);
+        assert_eq!(trimmed.mean_ns, statistics.mean_ns);
+        assert_eq!(trimmed.samples, statistics.samples);
+
+        let (trimmed, removed) = Statistics::from_mean(5.0, 10).without_outliers(1.5);
+        assert_eq!(removed, 0);
+        assert_eq!((trimmed.mean_ns, trimmed.iterations), (5.0, 10));
+    }
+
+    #[test]
+    fn trimming_a_subset_scales_the_iteration_count() {
+        let (clean, clean_mean) = clean_samples();
+        // The 105 samples were kept out of 1050 iterations.
+        let spiky = with_spikes(&clean);
+        let statistics = Statistics::from_mean(10_000.0, 1050).with_samples(spiky);
+
+        let (trimmed, removed) = statistics.without_outliers(1.5);
+        assert_eq!(removed, 5);
+        assert_eq!(trimmed.iterations, 1000);
+        assert_close(trimmed.mean_ns, clean_mean);
+    }
+
+    #[test]
+    fn extreme_outlier_moves_only_max_and_std_dev() {
+        let mut samples = vec![100.0; 99];
+        samples.push(1e9);
+        let mean = samples.iter().sum::<f64>() / 100.0;
+        let statistics = Statistics::from_mean(mean, 100).with_samples(samples);
+        assert_eq!(statistics.mean_ns, mean);
+        assert_eq!(statistics.median_ns, 100.0);
+        assert_eq!(statistics.p95_ns, 100.0);
+        assert_eq!(statistics.min_ns, 100.0);
+        assert_eq!(statistics.max_ns, 1e9);
+        // One sample of 1e9 among 100: about 1e9 / sqrt(100).
+        assert!((9e7..1.1e8).contains(&statistics.std_dev_ns));
+    }
+
+    fn sharded_file(shards: Option<ShardSet>) -> ResultFile {
+        ResultFile {
+            schema_version: ResultFile::SCHEMA_VERSION,
+            started_ms: 0,
+            finished_ms: 0,
+            environment: EnvironmentInfo::default(),
+            results: Vec::new(),
+            memory_timeline: Vec::new(),
+            partial: false,
+            config: Some(RunConfig {
+                shards,
+                ..Default::default()
+            }),
+            health: None,
+        }
+    }
+
+    fn merged_shards(shards: &[Option<ShardSet>]) -> Option<ShardSet> {
+        let files = shards.iter().cloned().map(sharded_file);
+        ResultFile::merge(files).unwrap().config.unwrap().shards
+    }
+
+    #[test]
+    fn merge_unites_shards() {
+        let shard = |index| Some(ShardSet::single(index, 3));
+        assert_eq!(
+            merged_shards(&[shard(2), shard(0)]).unwrap().indices,
+            [0, 2]
+        );
+        assert_eq!(merged_shards(&[shard(0), shard(1), shard(2)]), None);
+        assert_eq!(merged_shards(&[shard(0), None]), None);
+        assert_eq!(
+            merged_shards(&[shard(0), Some(ShardSet::single(1, 4))]),
+            None
+        );
+    }
+
+    #[test]
+    fn result_file_json_round_trip() {
+        let mut file = sharded_file(Some(ShardSet::single(1, 4)));
+        file.started_ms = 1_700_000_000_000;
+        file.finished_ms = 1_700_000_060_000;
+        file.partial = true;
+        file.environment = EnvironmentInfo {
+            label: Some("browser, second tab".to_string()),
+            target_arch: "wasm32".to_string(),
+            simd_level: "wasm_simd128".to_string(),
+            user_agent: Some("Mozilla/5.0 (X11; Linux x86_64)".to_string()),
+            core_count: Some(8),
+            ..Default::default()
+        };
+        let samples = vec![1010.0, 990.0, 1005.0, 1200.0];
+        let statistics = Statistics::from_mean(1051.25, 4).with_samples(samples.clone());
+        file.results = vec![
+            serde_json::from_value(serde_json::json!({
+                "id": "vello_cpu/tiger",
+                "category": "vello_cpu",
+                "name": "tiger",
+                "simd_variant": "wasm_simd128",
+                "statistics": statistics,
+                "timestamp_ms": 1_700_000_030_000_u64,
+            }))
+            .unwrap(),
+        ];
+
+        let text = file.to_json().unwrap();
+        let parsed = ResultFile::from_json(&text).unwrap();
+        assert_eq!(parsed.to_json().unwrap(), text);
+        assert_eq!(parsed.schema_version, ResultFile::SCHEMA_VERSION);
+        assert_eq!(parsed.started_ms, file.started_ms);
+        assert_eq!(parsed.finished_ms, file.finished_ms);
+        assert!(parsed.partial);
+        assert_eq!(parsed.environment, file.environment);
+        assert_eq!(parsed.config.unwrap().shards, Some(ShardSet::single(1, 4)));
+        let result = &parsed.results[0];
+        assert_eq!(result.id, "vello_cpu/tiger");
+        assert_eq!(result.timestamp_ms, 1_700_000_030_000);
+        assert_eq!(result.statistics.samples, samples);
+        assert_eq!(result.statistics.p95_ns, statistics.p95_ns);
+    }
+
+    #[test]
+    fn result_file_optional_fields_default() {
+        let file = ResultFile::from_json(
+            r#"{
+                "schema_version": 1,
+                "started_ms": 0,
+                "finished_ms": 5,
+                "environment": { "target_arch": "x86_64", "simd_level": "avx2" },
+                "results": []
+            }"#,
+        )
+        .unwrap();
+        assert!(!file.partial);
+        assert!(file.config.is_none());
+        assert!(file.health.is_none());
+        assert!(file.memory_timeline.is_empty());
+        assert_eq!(file.environment.label, None);
+
+        let error = ResultFile::from_json(r#"{ "schema_version": 1 }"#).unwrap_err();
+        assert!(error.starts_with("Failed to parse result file"), "{error}");
+    }
+
+    #[test]
+    fn captures_share_a_fingerprint() {
+        let first = EnvironmentInfo::capture(fearless_simd::Level::fallback());
+        let second = EnvironmentInfo::capture(fearless_simd::Level::fallback());
+        assert_eq!(first.fingerprint(), second.fingerprint());
+        assert!(first.fingerprint_differences(&second).is_empty());
+        assert_eq!(first.fingerprint().len(), 16);
+        assert_eq!(first.available_simd_levels.last().unwrap(), "scalar");
+
+        // Runs of one machine at different levels are comparable.
+        let best = EnvironmentInfo::capture(fearless_simd::Level::new());
+        assert_eq!(first.fingerprint(), best.fingerprint());
+    }
+
+    #[test]
+    fn changing_a_field_changes_the_fingerprint() {
+        let chrome = |version: &str| {
+            Some(format!(
+                "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) \
+                 Chrome/{version} Safari/537.36"
+            ))
+        };
+        let env = EnvironmentInfo {
+            label: Some("laptop".to_string()),
+            target_arch: "wasm32".to_string(),
+            simd_level: "wasm_simd128".to_string(),
+            available_simd_levels: vec!["wasm_simd128".to_string(), "scalar".to_string()],
+            user_agent: chrome("120.0.6099.109"),
+            cpu_model: None,
+            core_count: Some(8),
+            adapter: Some("Intel UHD 620".to_string()),
+            crate_version: Some("0.1.0".to_string()),
+        };
+        let tampered = [
+            EnvironmentInfo {
+                target_arch: "x86_64".to_string(),
+                ..env.clone()
+            },
+            EnvironmentInfo {
+                available_simd_levels: vec!["scalar".to_string()],
+                ..env.clone()
+            },
+            EnvironmentInfo {
+                user_agent: chrome("121.0.6167.85"),
+                ..env.clone()
+            },
+            EnvironmentInfo {
+                cpu_model: Some("Apple M2".to_string()),
+                ..env.clone()
+            },
+            EnvironmentInfo {
+                core_count: Some(4),
+                ..env.clone()
+            },
+            EnvironmentInfo {
+                adapter: None,
+                ..env.clone()
+            },
+            EnvironmentInfo {
+                crate_version: Some("0.2.0".to_string()),
+                ..env.clone()
+            },
+        ];
+        let fields = [
+            "target_arch",
+            "available_simd_levels",
+            "user_agent",
+            "cpu_model",
+            "core_count",
+            "adapter",
+            "crate_version",
+        ];
+        for (other, field) in tampered.iter().zip(fields) {
+            assert_ne!(env.fingerprint(), other.fingerprint(), "{field}");
+            assert_eq!(env.fingerprint_differences(other), [field]);
+        }
+
+        // Neither the label, a minor browser update nor the level the
+        // results were run at count.
+        let other = EnvironmentInfo {
+            label: None,
+            simd_level: "scalar".to_string(),
+            user_agent: chrome("120.0.6099.217"),
+            ..env.clone()
+        };
+        assert_eq!(env.fingerprint(), other.fingerprint());
+    }
+}
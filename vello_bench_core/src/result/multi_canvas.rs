@@ -0,0 +1,74 @@
+//! Overhead of rendering to several canvases in turn, for the WASM
+//! multi-canvas hybrid benchmark.
+//!
+//! A round robin over N canvases is compared with N times one frame of the
+//! same scene on a single canvas, measured right before it with the same
+//! renderer. Whatever the round robin takes beyond that is the cost of
+//! switching between render targets, e.g. WebGL context switches.
+
+use serde::{Deserialize, Serialize};
+
+/// A multi-canvas run compared with its single-canvas baseline.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CanvasOverhead {
+    /// Canvases rendered per iteration.
+    pub canvases: u32,
+    /// Mean time of one frame on a single canvas, in nanoseconds.
+    pub single_mean_ns: f64,
+    /// `canvases` times `single_mean_ns`: what an iteration would take if
+    /// switching canvases were free.
+    pub expected_ns: f64,
+    /// Time of an iteration beyond `expected_ns`, per canvas, in
+    /// nanoseconds. Negative if the round robin was faster.
+    pub overhead_ns_per_canvas: f64,
+    /// Time of an iteration beyond `expected_ns`, in percent of it.
+    pub overhead_pct: f64,
+}
+
+impl CanvasOverhead {
+    /// Compare a round robin over `canvases` canvases taking `mean_ns` per
+    /// iteration with a single-canvas frame taking `single_mean_ns`, or
+    /// `None` if there are no canvases or the single-canvas mean is not
+    /// positive.
+    pub fn new(canvases: u32, single_mean_ns: f64, mean_ns: f64) -> Option<Self> {
+        (canvases > 0 && single_mean_ns > 0.0).then(|| {
+            let expected_ns = f64::from(canvases) * single_mean_ns;
+            Self {
+                canvases,
+                single_mean_ns,
+                expected_ns,
+                overhead_ns_per_canvas: (mean_ns - expected_ns) / f64::from(canvases),
+                overhead_pct: (mean_ns - expected_ns) / expected_ns * 100.0,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overhead_is_what_the_round_robin_adds() {
+        let overhead = CanvasOverhead::new(4, 1_000.0, 4_400.0).unwrap();
+        assert_eq!(overhead.canvases, 4);
+        assert_eq!(overhead.expected_ns, 4_000.0);
+        assert_eq!(overhead.overhead_ns_per_canvas, 100.0);
+        assert!((overhead.overhead_pct - 10.0).abs() < 1e-9);
+
+        let free = CanvasOverhead::new(1, 1_000.0, 1_000.0).unwrap();
+        assert_eq!(free.overhead_ns_per_canvas, 0.0);
+        assert_eq!(free.overhead_pct, 0.0);
+
+        let faster = CanvasOverhead::new(2, 1_000.0, 1_800.0).unwrap();
+        assert_eq!(faster.overhead_ns_per_canvas, -100.0);
+        assert!((faster.overhead_pct + 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn needs_canvases_and_a_baseline() {
+        assert_eq!(CanvasOverhead::new(0, 1_000.0, 1_000.0), None);
+        assert_eq!(CanvasOverhead::new(2, 0.0, 1_000.0), None);
+        assert_eq!(CanvasOverhead::new(2, f64::NAN, 1_000.0), None);
+    }
+}
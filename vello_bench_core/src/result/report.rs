@@ -0,0 +1,430 @@
+//! Human-readable summaries of a [`ResultFile`], as markdown or HTML.
+//!
+//! Both formats render the same tables. The HTML report is a standalone page
+//! without scripts or external resources, so it can be attached to a CI run
+//! as it is.
+
+use super::{BenchmarkResult, ResultFile};
+
+/// Baseline column of [`simd_levels_markdown`].
+const SCALAR: &str = "scalar";
+
+/// Categories compared by [`backends_markdown`]: backends rendering the same
+/// scenes, the first of each group being the baseline.
+const BACKEND_GROUPS: &[&[&str]] = &[
+    &["vello_cpu", "vello_hybrid"],
+    &["scene_cpu", "scene_hybrid", "scene_skia"],
+    &["scene_cpu_cold", "scene_hybrid_cold"],
+];
+
+/// Markdown report of `file`.
+pub fn markdown_report(file: &ResultFile) -> String {
+    let mut out = String::from("# Benchmark report\n\n");
+    if file.partial {
+        out.push_str("This run was stopped early; results are incomplete.\n\n");
+    }
+    out.push_str("## Backends\n\n");
+    out.push_str(&backends_markdown(&file.results));
+    out.push_str("\n## SIMD levels\n\n");
+    out.push_str(&simd_levels_markdown(&file.results));
+    out.push_str("\n## Thread scaling\n\n");
+    out.push_str(&thread_scaling_markdown(&file.results));
+    out
+}
+
+/// Standalone HTML page with the same content as [`markdown_report`].
+pub fn html_report(file: &ResultFile) -> String {
+    let mut out = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Benchmark report</title>\n<style>\n\
+         body { font-family: sans-serif; }\n\
+         table { border-collapse: collapse; }\n\
+         th, td { border: 1px solid #ccc; padding: 4px 8px; text-align: right; }\n\
+         th:first-child, td:first-child { text-align: left; }\n\
+         </style>\n</head>\n<body>\n<h1>Benchmark report</h1>\n",
+    );
+    if file.partial {
+        out.push_str("<p>This run was stopped early; results are incomplete.</p>\n");
+    }
+    out.push_str("<h2>Backends</h2>\n");
+    out.push_str(&backends_html(&file.results));
+    out.push_str("<h2>SIMD levels</h2>\n");
+    out.push_str(&simd_levels_html(&file.results));
+    out.push_str("<h2>Thread scaling</h2>\n");
+    out.push_str(&thread_scaling_html(&file.results));
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Markdown table of the scenes rendered by more than one backend: Vello CPU
+/// against Vello Hybrid for the programmatic scenes, and additionally Skia for
+/// the archive scenes. One column per backend category; each cell is the
+/// backend's fastest result over SIMD levels and render modes, and the
+/// speedup over the first backend of its group.
+pub fn backends_markdown(results: &[BenchmarkResult]) -> String {
+    backends_table(results).markdown("No scenes with results from more than one backend.")
+}
+
+/// HTML `<table>` with the content of [`backends_markdown`].
+pub fn backends_html(results: &[BenchmarkResult]) -> String {
+    backends_table(results).html("No scenes with results from more than one backend.")
+}
+
+/// Markdown table of the results measured at more than one SIMD level, one
+/// row per result ID and one column per level. Each cell is the mean time and
+/// the speedup over the scalar result.
+pub fn simd_levels_markdown(results: &[BenchmarkResult]) -> String {
+    simd_levels_table(results).markdown("No results at more than one SIMD level.")
+}
+
+/// HTML `<table>` with the content of [`simd_levels_markdown`].
+pub fn simd_levels_html(results: &[BenchmarkResult]) -> String {
+    simd_levels_table(results).html("No results at more than one SIMD level.")
+}
+
+/// Markdown table of the results that record a thread count, one row per
+/// benchmark and SIMD level and one column per thread count. Each cell is the
+/// mean time and the speedup over the single-threaded (`0` threads) result.
+pub fn thread_scaling_markdown(results: &[BenchmarkResult]) -> String {
+    ThreadScaling::new(results)
+        .table()
+        .markdown("No results with a thread count.")
+}
+
+/// HTML `<table>` with the content of [`thread_scaling_markdown`].
+pub fn thread_scaling_html(results: &[BenchmarkResult]) -> String {
+    ThreadScaling::new(results)
+        .table()
+        .html("No results with a thread count.")
+}
+
+/// A table of leading label columns followed by value columns. The first
+/// label is set as code.
+struct Table {
+    labels: &'static [&'static str],
+    columns: Vec<String>,
+    /// Label cells and value cells; `None` values are shown as a dash.
+    rows: Vec<(Vec<String>, Vec<Option<String>>)>,
+}
+
+impl Table {
+    fn markdown(&self, empty: &str) -> String {
+        if self.rows.is_empty() {
+            return format!("{empty}\n");
+        }
+
+        let mut out = String::from("|");
+        for header in self
+            .labels
+            .iter()
+            .copied()
+            .chain(self.columns.iter().map(String::as_str))
+        {
+            out.push_str(&format!(" {header} |"));
+        }
+        out.push_str("\n|");
+        out.push_str(&"---|".repeat(self.labels.len()));
+        out.push_str(&"---:|".repeat(self.columns.len()));
+        out.push('\n');
+
+        for (labels, cells) in &self.rows {
+            out.push('|');
+            for (i, label) in labels.iter().enumerate() {
+                if i == 0 {
+                    out.push_str(&format!(" `{label}` |"));
+                } else {
+                    out.push_str(&format!(" {label} |"));
+                }
+            }
+            for cell in cells {
+                out.push_str(&format!(" {} |", cell.as_deref().unwrap_or("–")));
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    fn html(&self, empty: &str) -> String {
+        if self.rows.is_empty() {
+            return format!("<p>{}</p>\n", escape_html(empty));
+        }
+
+        let mut out = String::from("<table>\n<tr>");
+        for header in self
+            .labels
+            .iter()
+            .copied()
+            .chain(self.columns.iter().map(String::as_str))
+        {
+            out.push_str(&format!("<th>{}</th>", escape_html(header)));
+        }
+        out.push_str("</tr>\n");
+
+        for (labels, cells) in &self.rows {
+            out.push_str("<tr>");
+            for (i, label) in labels.iter().enumerate() {
+                if i == 0 {
+                    out.push_str(&format!("<td><code>{}</code></td>", escape_html(label)));
+                } else {
+                    out.push_str(&format!("<td>{}</td>", escape_html(label)));
+                }
+            }
+            for cell in cells {
+                out.push_str(&format!("<td>{}</td>", cell.as_deref().unwrap_or("–")));
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</table>\n");
+        out
+    }
+}
+
+/// `mean_ns` as milliseconds, with the speedup over `baseline_ns` unless this
+/// is the baseline itself.
+fn time_cell(mean_ns: f64, baseline_ns: Option<f64>, is_baseline: bool) -> String {
+    let time = format!("{:.3} ms", mean_ns / 1e6);
+    match baseline_ns {
+        Some(base) if !is_baseline && mean_ns > 0.0 => format!("{time} ({:.2}×)", base / mean_ns),
+        _ => time,
+    }
+}
+
+fn simd_levels_table(results: &[BenchmarkResult]) -> Table {
+    let mut levels: Vec<&str> = Vec::new();
+    // Result ID and the mean time per level, in order of first appearance.
+    let mut rows: Vec<(&str, Vec<(&str, f64)>)> = Vec::new();
+    for result in results {
+        let level = result.simd_variant.as_str();
+        if !levels.contains(&level) {
+            levels.push(level);
+        }
+        let entry = (level, result.statistics.mean_ns);
+        match rows.iter_mut().find(|(id, _)| *id == result.id) {
+            Some((_, means)) => {
+                if !means.iter().any(|(l, _)| *l == level) {
+                    means.push(entry);
+                }
+            }
+            None => rows.push((&result.id, vec![entry])),
+        }
+    }
+    rows.retain(|(_, means)| means.len() > 1);
+    // Scalar last, as the baseline the others are compared with.
+    levels.sort_by_key(|level| *level == SCALAR);
+    levels.retain(|level| {
+        rows.iter()
+            .any(|(_, means)| means.iter().any(|(l, _)| l == level))
+    });
+
+    let rows = rows
+        .into_iter()
+        .map(|(id, means)| {
+            let mean_at = |level: &str| means.iter().find(|(l, _)| *l == level).map(|(_, m)| *m);
+            let baseline = mean_at(SCALAR);
+            let cells = levels
+                .iter()
+                .map(|level| Some(time_cell(mean_at(level)?, baseline, *level == SCALAR)))
+                .collect();
+            (vec![id.to_string()], cells)
+        })
+        .collect();
+
+    Table {
+        labels: &["Benchmark"],
+        columns: levels.iter().map(|level| level.to_string()).collect(),
+        rows,
+    }
+}
+
+fn backends_table(results: &[BenchmarkResult]) -> Table {
+    let categories: Vec<&str> = BACKEND_GROUPS
+        .iter()
+        .flat_map(|g| g.iter().copied())
+        .collect();
+    // Group index, scene name, and the fastest result per category.
+    let mut rows: Vec<(usize, String, Vec<(&str, &BenchmarkResult)>)> = Vec::new();
+    for result in results {
+        let Some(group) = BACKEND_GROUPS
+            .iter()
+            .position(|g| g.contains(&result.category.as_str()))
+        else {
+            continue;
+        };
+        let scene = scene_name(result);
+        let category = result.category.as_str();
+
+        let fastest = match rows
+            .iter_mut()
+            .find(|(g, name, _)| *g == group && *name == scene)
+        {
+            Some((_, _, fastest)) => fastest,
+            None => {
+                rows.push((group, scene, Vec::new()));
+                &mut rows.last_mut().unwrap().2
+            }
+        };
+        match fastest.iter_mut().find(|(c, _)| *c == category) {
+            Some((_, best)) if best.statistics.mean_ns <= result.statistics.mean_ns => {}
+            Some((_, best)) => *best = result,
+            None => fastest.push((category, result)),
+        }
+    }
+    rows.retain(|(_, _, fastest)| fastest.len() > 1);
+
+    let rows = rows
+        .into_iter()
+        .map(|(group, scene, fastest)| {
+            let result_of = |category: &str| {
+                fastest
+                    .iter()
+                    .find(|(c, _)| *c == category)
+                    .map(|(_, r)| *r)
+            };
+            let baseline_category = BACKEND_GROUPS[group]
+                .iter()
+                .find(|c| result_of(c).is_some())
+                .copied();
+            let baseline = baseline_category
+                .and_then(result_of)
+                .map(|r| r.statistics.mean_ns);
+            let cells = categories
+                .iter()
+                .map(|category| {
+                    let result = result_of(category)?;
+                    let time = time_cell(
+                        result.statistics.mean_ns,
+                        baseline,
+                        Some(*category) == baseline_category,
+                    );
+                    Some(format!("{time}, {}", result.simd_variant))
+                })
+                .collect();
+            (vec![scene], cells)
+        })
+        .collect();
+
+    Table {
+        labels: &["Scene"],
+        columns: categories.iter().map(|c| c.to_string()).collect(),
+        rows,
+    }
+}
+
+/// Scene a result rendered: its name without the render mode suffix.
+fn scene_name(result: &BenchmarkResult) -> String {
+    result
+        .render_mode
+        .as_ref()
+        .and_then(|mode| result.name.strip_suffix(mode.as_str())?.strip_suffix('_'))
+        .unwrap_or(&result.name)
+        .to_string()
+}
+
+/// Results with a thread count, grouped by benchmark and SIMD level.
+struct ThreadScaling {
+    /// Every thread count that occurs, ascending.
+    thread_counts: Vec<u16>,
+    /// In order of first appearance.
+    rows: Vec<ScalingRow>,
+}
+
+struct ScalingRow {
+    /// Benchmark ID without the thread count suffix, e.g.
+    /// `cpu_threads/filled_rects`.
+    benchmark: String,
+    simd_variant: String,
+    /// Mean time per thread count.
+    mean_ns: Vec<(u16, f64)>,
+}
+
+impl ThreadScaling {
+    fn new(results: &[BenchmarkResult]) -> Self {
+        let mut thread_counts = Vec::new();
+        let mut rows: Vec<ScalingRow> = Vec::new();
+
+        for result in results {
+            let Some(threads) = result.num_threads else {
+                continue;
+            };
+            let suffix = format!("_t{threads}");
+            let benchmark = result
+                .id
+                .strip_suffix(&suffix)
+                .unwrap_or(&result.id)
+                .to_string();
+
+            if !thread_counts.contains(&threads) {
+                thread_counts.push(threads);
+            }
+            let entry = (threads, result.statistics.mean_ns);
+            match rows
+                .iter_mut()
+                .find(|r| r.benchmark == benchmark && r.simd_variant == result.simd_variant)
+            {
+                Some(row) => row.mean_ns.push(entry),
+                None => rows.push(ScalingRow {
+                    benchmark,
+                    simd_variant: result.simd_variant.clone(),
+                    mean_ns: vec![entry],
+                }),
+            }
+        }
+
+        thread_counts.sort_unstable();
+        Self {
+            thread_counts,
+            rows,
+        }
+    }
+
+    fn table(&self) -> Table {
+        Table {
+            labels: &["Benchmark", "SIMD"],
+            columns: self
+                .thread_counts
+                .iter()
+                .map(|threads| threads_label(*threads))
+                .collect(),
+            rows: self
+                .rows
+                .iter()
+                .map(|row| {
+                    let labels = vec![row.benchmark.clone(), row.simd_variant.clone()];
+                    (labels, self.cells(row))
+                })
+                .collect(),
+        }
+    }
+
+    /// Formatted cells of `row`, one per thread count; `None` where `row` has
+    /// no result.
+    fn cells(&self, row: &ScalingRow) -> Vec<Option<String>> {
+        let mean_at = |threads: u16| {
+            row.mean_ns
+                .iter()
+                .find(|(t, _)| *t == threads)
+                .map(|(_, mean_ns)| *mean_ns)
+        };
+        let baseline = mean_at(0);
+
+        self.thread_counts
+            .iter()
+            .map(|threads| Some(time_cell(mean_at(*threads)?, baseline, *threads == 0)))
+            .collect()
+    }
+}
+
+fn threads_label(threads: u16) -> String {
+    match threads {
+        0 => "0 threads (baseline)".to_string(),
+        1 => "1 thread".to_string(),
+        n => format!("{n} threads"),
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
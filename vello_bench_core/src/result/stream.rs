@@ -0,0 +1,452 @@
+//! Incremental result files, so that an interrupted run keeps the results it
+//! already produced.
+//!
+//! While a run is in progress, its output file is in JSON-lines form: a
+//! header line with the run metadata, followed by one
+//! [`BenchmarkOutcome`] per line, each flushed as soon as the benchmark
+//! finishes:
+//!
+//! ```text
+//! {"schema_version":1,"started_ms":1700000000000,"environment":{...},"config":{...}}
+//! {"status":"completed","id":"fine/fill/opaque_short",...}
+//! {"status":"failed","id":"vello_hybrid/tiger","error":"GPU timeout after 10s"}
+//! ```
+//!
+//! [`JsonLinesObserver::finish`] replaces it with the canonical [`ResultFile`]
+//! JSON on a clean exit. If the process dies first, [`recover_partial`] turns
+//! what was written into a [`ResultFile`] marked [`ResultFile::partial`], and
+//! [`JsonLinesObserver::resume`] continues writing to it.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use super::{BenchmarkOutcome, BenchmarkResult, EnvironmentInfo, ResultFile, RunConfig};
+use crate::health::HealthReport;
+use crate::registry::RunObserver;
+
+/// First line of a JSON-lines result file.
+#[derive(Debug, Serialize, Deserialize)]
+struct StreamHeader {
+    schema_version: u32,
+    started_ms: u64,
+    environment: EnvironmentInfo,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    config: Option<RunConfig>,
+}
+
+/// Writes outcomes to a JSON-lines result file as they arrive.
+///
+/// Use it as the observer of
+/// [`run_all_observed`](crate::registry::run_all_observed), or call
+/// [`Self::push`] directly, then [`Self::finish`] to write the final
+/// [`ResultFile`].
+pub struct JsonLinesObserver {
+    path: PathBuf,
+    out: BufWriter<File>,
+    started_ms: u64,
+    environment: EnvironmentInfo,
+    config: RunConfig,
+    results: Vec<BenchmarkResult>,
+    health: Option<HealthReport>,
+    stop: Option<Arc<AtomicBool>>,
+    /// First write error, reported by [`Self::finish`]; observers can't
+    /// return errors.
+    error: Option<String>,
+}
+
+impl JsonLinesObserver {
+    /// Create (or truncate) `path` and write the header line.
+    pub fn create(
+        path: &Path,
+        environment: EnvironmentInfo,
+        config: RunConfig,
+    ) -> Result<Self, String> {
+        let file =
+            File::create(path).map_err(|e| format!("Failed to create {}: {e}", path.display()))?;
+        let mut observer = Self {
+            path: path.to_path_buf(),
+            out: BufWriter::new(file),
+            started_ms: now_ms(),
+            environment,
+            config,
+            results: Vec::new(),
+            health: None,
+            stop: None,
+            error: None,
+        };
+
+        let header = StreamHeader {
+            schema_version: ResultFile::SCHEMA_VERSION,
+            started_ms: observer.started_ms,
+            environment: observer.environment.clone(),
+            config: Some(observer.config.clone()),
+        };
+        observer.write_line(&header)?;
+        Ok(observer)
+    }
+
+    /// Continue the run whose output is at `path`, or start a new one if
+    /// there is none.
+    ///
+    /// An existing file, whether finished or left behind by an interrupted
+    /// run, is read like [`recover_partial`] does and rewritten in JSON-lines
+    /// form with its completed results; failed outcomes are dropped, so those
+    /// benchmarks can be retried. The original start time, environment and
+    /// configuration are kept; `config` only applies to files without one.
+    pub fn resume(
+        path: &Path,
+        environment: EnvironmentInfo,
+        config: RunConfig,
+    ) -> Result<Self, String> {
+        if !path.exists() {
+            return Self::create(path, environment, config);
+        }
+        let previous = recover_partial(path)?;
+        let config = previous.config.unwrap_or(config);
+
+        let header = StreamHeader {
+            schema_version: previous.schema_version,
+            started_ms: previous.started_ms,
+            environment: previous.environment.clone(),
+            config: Some(config.clone()),
+        };
+        let mut text = json_line(&header)?;
+        for result in &previous.results {
+            text.push_str(&json_line(&BenchmarkOutcome::Completed(result.clone()))?);
+        }
+        replace_file(path, &text)?;
+
+        let file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            out: BufWriter::new(file),
+            started_ms: previous.started_ms,
+            environment: previous.environment,
+            config,
+            results: previous.results,
+            health: previous.health,
+            stop: None,
+            error: None,
+        })
+    }
+
+    /// Completed results so far, including those of a resumed run.
+    pub fn results(&self) -> &[BenchmarkResult] {
+        &self.results
+    }
+
+    /// End the run early once `stop` is set, e.g. from a ctrl-C handler.
+    pub fn with_stop_flag(mut self, stop: Arc<AtomicBool>) -> Self {
+        self.stop = Some(stop);
+        self
+    }
+
+    /// Record the health of the environment in the final [`ResultFile`].
+    pub fn with_health(mut self, health: HealthReport) -> Self {
+        self.health = Some(health);
+        self
+    }
+
+    /// Append `outcome` to the file and flush it.
+    pub fn push(&mut self, outcome: &BenchmarkOutcome) -> Result<(), String> {
+        self.write_line(outcome)?;
+        if let BenchmarkOutcome::Completed(result) = outcome {
+            self.results.push(result.clone());
+        }
+        Ok(())
+    }
+
+    /// Replace the JSON-lines file with the canonical [`ResultFile`] JSON and
+    /// return it. `partial` marks a run that was stopped early.
+    ///
+    /// The file is replaced atomically, so it is never left half-written.
+    pub fn finish(mut self, partial: bool) -> Result<ResultFile, String> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+
+        let file = ResultFile {
+            schema_version: ResultFile::SCHEMA_VERSION,
+            started_ms: self.started_ms,
+            finished_ms: now_ms(),
+            environment: self.environment,
+            results: self.results,
+            memory_timeline: Vec::new(),
+            partial,
+            config: Some(self.config),
+            health: self.health,
+        };
+        let json = file.to_json()?;
+        replace_file(&self.path, &(json + "\n"))?;
+        Ok(file)
+    }
+
+    fn write_line<T: Serialize>(&mut self, value: &T) -> Result<(), String> {
+        let line = json_line(value)?;
+        self.out
+            .write_all(line.as_bytes())
+            .and_then(|()| self.out.flush())
+            .map_err(|e| format!("Failed to write {}: {e}", self.path.display()))
+    }
+}
+
+impl RunObserver for JsonLinesObserver {
+    fn on_outcome(&mut self, outcome: &BenchmarkOutcome) {
+        if let Err(e) = self.push(outcome) {
+            self.error.get_or_insert(e);
+        }
+    }
+
+    fn should_stop(&self) -> bool {
+        self.stop
+            .as_ref()
+            .is_some_and(|stop| stop.load(Ordering::Relaxed))
+    }
+}
+
+/// `value` as a line of a JSON-lines file, including the newline.
+fn json_line<T: Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string(value)
+        .map(|line| line + "\n")
+        .map_err(|e| format!("Failed to serialize result line: {e}"))
+}
+
+/// Replace the content of `path` atomically, through a temporary file next to
+/// it.
+fn replace_file(path: &Path, content: &str) -> Result<(), String> {
+    let mut tmp = path.to_path_buf().into_os_string();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    std::fs::write(&tmp, content).map_err(|e| format!("Failed to write {}: {e}", tmp.display()))?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("Failed to replace {}: {e}", path.display()))
+}
+
+/// Read a result file left behind by an interrupted run and convert it into a
+/// [`ResultFile`] marked [`ResultFile::partial`].
+///
+/// A truncated last line, as left by a process killed mid-write, is ignored;
+/// any other malformed line is an error. `finished_ms` is the timestamp of
+/// the last completed result. A file that was already finalized is returned
+/// as it is.
+pub fn recover_partial(path: &Path) -> Result<ResultFile, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    if let Ok(file) = ResultFile::from_json(&text) {
+        return Ok(file);
+    }
+
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty());
+    let (_, header) = lines
+        .next()
+        .ok_or_else(|| format!("{} is empty", path.display()))?;
+    let header: StreamHeader = serde_json::from_str(header)
+        .map_err(|e| format!("{} is not a JSON-lines result file: {e}", path.display()))?;
+
+    let lines: Vec<(usize, &str)> = lines.collect();
+    let mut results = Vec::new();
+    for (i, &(number, line)) in lines.iter().enumerate() {
+        match serde_json::from_str::<BenchmarkOutcome>(line) {
+            Ok(BenchmarkOutcome::Completed(result)) => results.push(result),
+            Ok(BenchmarkOutcome::Failed { .. }) => {}
+            Err(_) if i + 1 == lines.len() => {}
+            Err(e) => {
+                return Err(format!(
+                    "{}: malformed result on line {}: {e}",
+                    path.display(),
+                    number + 1
+                ));
+            }
+        }
+    }
+
+    let finished_ms = results
+        .iter()
+        .map(|r| r.timestamp_ms)
+        .max()
+        .unwrap_or(header.started_ms);
+    Ok(ResultFile {
+        schema_version: header.schema_version,
+        started_ms: header.started_ms,
+        finished_ms,
+        environment: header.environment,
+        results,
+        memory_timeline: Vec::new(),
+        partial: true,
+        config: header.config,
+        health: None,
+    })
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::result::Statistics;
+
+    /// A path in the system's temporary directory, removed again when
+    /// dropped.
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir()
+                .join(format!("vello_bench_{name}_{}.json", std::process::id()));
+            let _ = std::fs::remove_file(&path);
+            Self(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    fn completed(id: &str, timestamp_ms: u64) -> BenchmarkOutcome {
+        BenchmarkOutcome::Completed(
+            serde_json::from_value(serde_json::json!({
+                "id": id,
+                "category": "vello_cpu",
+                "name": id,
+                "simd_variant": "avx2",
+                "statistics": Statistics::from_mean(1000.0, 10),
+                "timestamp_ms": timestamp_ms,
+            }))
+            .unwrap(),
+        )
+    }
+
+    fn failed(id: &str) -> BenchmarkOutcome {
+        BenchmarkOutcome::Failed {
+            id: id.to_string(),
+            error: "GPU timeout after 10s".to_string(),
+            adapter: None,
+        }
+    }
+
+    fn environment() -> EnvironmentInfo {
+        EnvironmentInfo {
+            label: Some("laptop".to_string()),
+            target_arch: "x86_64".to_string(),
+            simd_level: "avx2".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn config() -> RunConfig {
+        RunConfig {
+            warmup: 3,
+            iterations: 10,
+            filter: Some("vello_cpu/*".to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Stream `outcomes` to `path` and leave the file as a killed process
+    /// would, without finishing it.
+    fn write_stream(path: &Path, outcomes: &[BenchmarkOutcome]) {
+        let mut observer = JsonLinesObserver::create(path, environment(), config()).unwrap();
+        for outcome in outcomes {
+            observer.push(outcome).unwrap();
+        }
+    }
+
+    fn ids(file: &ResultFile) -> Vec<&str> {
+        file.results.iter().map(|r| r.id.as_str()).collect()
+    }
+
+    #[test]
+    fn recovers_the_lines_before_a_truncated_one() {
+        let tmp = TempFile::new("truncated_stream");
+        write_stream(
+            &tmp.0,
+            &[
+                completed("a", 1_700_000_001_000),
+                failed("b"),
+                completed("c", 1_700_000_002_000),
+                completed("d", 1_700_000_003_000),
+            ],
+        );
+
+        // Cut the last line in half, as a process killed mid-write would.
+        let text = std::fs::read_to_string(&tmp.0).unwrap();
+        let last_line = text.trim_end().rfind('\n').unwrap() + 1;
+        let cut = last_line + (text.len() - last_line) / 2;
+        std::fs::write(&tmp.0, &text[..cut]).unwrap();
+
+        let file = recover_partial(&tmp.0).unwrap();
+        assert!(file.partial);
+        assert_eq!(ids(&file), ["a", "c"]);
+        assert_eq!(file.finished_ms, 1_700_000_002_000);
+        assert_eq!(file.environment, environment());
+        assert_eq!(file.config, Some(config()));
+    }
+
+    #[test]
+    fn recovers_a_stream_without_results() {
+        let tmp = TempFile::new("empty_stream");
+        write_stream(&tmp.0, &[]);
+
+        let file = recover_partial(&tmp.0).unwrap();
+        assert!(file.partial);
+        assert!(file.results.is_empty());
+        assert_eq!(file.finished_ms, file.started_ms);
+    }
+
+    #[test]
+    fn malformed_lines_before_the_last_are_errors() {
+        let tmp = TempFile::new("malformed_stream");
+        write_stream(&tmp.0, &[completed("a", 1), completed("b", 2)]);
+
+        let text = std::fs::read_to_string(&tmp.0).unwrap();
+        let mut lines: Vec<&str> = text.lines().collect();
+        lines[1] = "{\"status\":\"completed\",\"id\"";
+        std::fs::write(&tmp.0, lines.join("\n")).unwrap();
+
+        let error = recover_partial(&tmp.0).unwrap_err();
+        assert!(error.contains("malformed result on line 2"), "{error}");
+    }
+
+    #[test]
+    fn finished_files_are_returned_as_they_are() {
+        let tmp = TempFile::new("finished_stream");
+        let mut observer = JsonLinesObserver::create(&tmp.0, environment(), config()).unwrap();
+        observer.push(&completed("a", 1)).unwrap();
+        observer.finish(false).unwrap();
+
+        let file = recover_partial(&tmp.0).unwrap();
+        assert!(!file.partial);
+        assert_eq!(ids(&file), ["a"]);
+    }
+
+    #[test]
+    fn resume_keeps_completed_results_and_drops_failures() {
+        let tmp = TempFile::new("resumed_stream");
+        write_stream(&tmp.0, &[completed("a", 1), failed("b")]);
+
+        let mut observer = JsonLinesObserver::resume(&tmp.0, environment(), config()).unwrap();
+        assert_eq!(observer.results().len(), 1);
+        observer.push(&completed("b", 2)).unwrap();
+        let file = observer.finish(false).unwrap();
+        assert_eq!(ids(&file), ["a", "b"]);
+        let written = std::fs::read_to_string(&tmp.0).unwrap();
+        assert_eq!(ResultFile::from_json(&written).unwrap().results.len(), 2);
+    }
+}
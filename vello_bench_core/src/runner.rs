@@ -1,4 +1,17 @@
-use crate::result::{BenchmarkResult, Statistics};
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::ops::Range;
+use std::pin::pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::task::{Context, Poll, Waker};
+
+use serde::Serialize;
+
+use crate::marks::{
+    MAX_RECORDED_ITERATION_MARKS, RecordedMarks, RunMarks, record_marks, set_last_run_marks,
+};
+use crate::result::{BenchmarkResult, MeasurementMode, Statistics, WarmupInput, quantile};
 
 /// Per-iteration performance marks are only emitted when the total iteration
 /// count stays at or below this threshold. This avoids flooding the browser
@@ -7,27 +20,561 @@ use crate::result::{BenchmarkResult, Statistics};
 /// typically have far fewer iterations and always receive marks.
 const MAX_MARKED_ITERS: usize = 10_000;
 
+/// Most per-iteration times a [`BenchRunner`] keeps in
+/// [`Statistics::samples`] unless configured otherwise.
+pub const DEFAULT_MAX_SAMPLES: usize = 100_000;
+
+/// Most iterations a [`TimeBudget`] measures, however fast the benchmark.
+/// Keeps a run bounded when the calibration underestimates the cost, e.g.
+/// because a coarse browser timer rounds it to zero.
+pub const MAX_BUDGET_ITERATIONS: u64 = 1_000_000;
+
+/// Longest time between two [`ProgressEvent`]s of a phase, except while a
+/// single slow iteration is running.
+pub const PROGRESS_INTERVAL_MS: u64 = 100;
+
+/// Most iterations between two looks at the clock for progress. Fast
+/// iterations read it less often, so that reporting progress costs next to
+/// nothing.
+const MAX_PROGRESS_CHECK_EVERY: u64 = 1024;
+
+/// Phase of a benchmark run, see [`ProgressEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressPhase {
+    /// The warm-up, or the calibration of a time budget.
+    Warmup,
+    Measurement,
+}
+
+/// How far a benchmark run is, reported to the callback of
+/// [`BenchRunner::run_with_callback`] or [`with_progress`].
+///
+/// Each phase reports when it starts, when it ends, and in between at most
+/// every [`PROGRESS_INTERVAL_MS`]. Reports during the measurement fall inside
+/// the timed span, so the callback should be cheap.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ProgressEvent {
+    pub phase: ProgressPhase,
+    /// Iterations of the phase done so far.
+    pub completed: u64,
+    /// Iterations the phase runs, or `None` if that isn't known in advance:
+    /// while calibrating a [`TimeBudget`], and while measuring until stable.
+    pub total: Option<u64>,
+    /// Time since the phase started in nanoseconds.
+    pub elapsed_ns: f64,
+}
+
+thread_local! {
+    /// Installed by [`with_progress`].
+    static PROGRESS_CALLBACK: RefCell<Option<Box<dyn FnMut(ProgressEvent)>>> =
+        RefCell::new(None);
+}
+
+/// Call `f` with `on_progress` receiving the [`ProgressEvent`]s of every
+/// benchmark run on this thread until it returns, including those started
+/// through [`run_benchmark_by_id`](crate::registry::run_benchmark_by_id).
+pub fn with_progress<R>(
+    on_progress: impl FnMut(ProgressEvent) + 'static,
+    f: impl FnOnce() -> R,
+) -> R {
+    /// Puts the previous callback back, even if `f` panics.
+    struct Restore(Option<Box<dyn FnMut(ProgressEvent)>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            PROGRESS_CALLBACK.with(|callback| *callback.borrow_mut() = previous);
+        }
+    }
+
+    let previous =
+        PROGRESS_CALLBACK.with(|callback| callback.borrow_mut().replace(Box::new(on_progress)));
+    let _restore = Restore(previous);
+    f()
+}
+
+/// Counts the iterations of the phases of one run and reports them as
+/// [`ProgressEvent`]s. Does nothing if no one listens.
+struct Progress<'a, 'b, T: Timer> {
+    timer: &'a T,
+    /// Receives the events; the callback of [`with_progress`] if `None`.
+    on_progress: RefCell<Option<&'b mut dyn FnMut(ProgressEvent)>>,
+    active: bool,
+    phase: Cell<ProgressPhase>,
+    total: Cell<Option<u64>>,
+    completed: Cell<u64>,
+    start: Cell<T::Instant>,
+    /// Elapsed time of the phase at the last look at the clock, and at the
+    /// last report.
+    last_check_ns: Cell<f64>,
+    last_report_ns: Cell<f64>,
+    /// Iterations completed at the last report.
+    last_report_completed: Cell<u64>,
+    /// Iterations between looks at the clock, and until the next one.
+    check_every: Cell<u64>,
+    until_check: Cell<u64>,
+}
+
+impl<'a, 'b, T: Timer> Progress<'a, 'b, T> {
+    fn new(timer: &'a T, on_progress: Option<&'b mut dyn FnMut(ProgressEvent)>) -> Self {
+        let active =
+            on_progress.is_some() || PROGRESS_CALLBACK.with(|callback| callback.borrow().is_some());
+        Self {
+            timer,
+            on_progress: RefCell::new(on_progress),
+            active,
+            phase: Cell::new(ProgressPhase::Warmup),
+            total: Cell::new(None),
+            completed: Cell::new(0),
+            start: Cell::new(timer.now()),
+            last_check_ns: Cell::new(0.0),
+            last_report_ns: Cell::new(0.0),
+            last_report_completed: Cell::new(0),
+            check_every: Cell::new(1),
+            until_check: Cell::new(1),
+        }
+    }
+
+    /// Start counting `phase`, which runs `total` iterations if known.
+    fn start(&self, phase: ProgressPhase, total: Option<u64>) {
+        if !self.active {
+            return;
+        }
+        self.phase.set(phase);
+        self.total.set(total);
+        self.completed.set(0);
+        self.start.set(self.timer.now());
+        self.last_check_ns.set(0.0);
+        self.check_every.set(1);
+        self.until_check.set(1);
+        self.report(0.0);
+    }
+
+    /// Count one iteration, and report if it's time to.
+    fn tick(&self) {
+        if !self.active {
+            return;
+        }
+        self.completed.set(self.completed.get() + 1);
+        let until_check = self.until_check.get() - 1;
+        if until_check > 0 {
+            self.until_check.set(until_check);
+            return;
+        }
+
+        let elapsed_ns = self.timer.elapsed_ns(self.start.get());
+        let check_every = self.check_every.get();
+        // Aim for about a millisecond between looks at the clock.
+        self.check_every
+            .set(if elapsed_ns - self.last_check_ns.get() < 1e6 {
+                (check_every * 2).min(MAX_PROGRESS_CHECK_EVERY)
+            } else {
+                (check_every / 2).max(1)
+            });
+        self.last_check_ns.set(elapsed_ns);
+        self.until_check.set(self.check_every.get());
+
+        if elapsed_ns - self.last_report_ns.get() >= PROGRESS_INTERVAL_MS as f64 * 1e6 {
+            self.report(elapsed_ns);
+        }
+    }
+
+    /// Report the end of the current phase, unless the last iteration was
+    /// just reported.
+    fn finish(&self) {
+        if self.active && self.completed.get() != self.last_report_completed.get() {
+            self.report(self.timer.elapsed_ns(self.start.get()));
+        }
+    }
+
+    fn report(&self, elapsed_ns: f64) {
+        self.last_report_ns.set(elapsed_ns);
+        self.last_report_completed.set(self.completed.get());
+        let event = ProgressEvent {
+            phase: self.phase.get(),
+            completed: self.completed.get(),
+            total: self.total.get(),
+            elapsed_ns,
+        };
+        match self.on_progress.borrow_mut().as_mut() {
+            Some(on_progress) => on_progress(event),
+            None => PROGRESS_CALLBACK.with(|callback| {
+                if let Some(callback) = callback.borrow_mut().as_mut() {
+                    callback(event);
+                }
+            }),
+        }
+    }
+}
+
+/// Stops a running benchmark from elsewhere, e.g. from an abort button or
+/// another thread. Clones share the same state.
+///
+/// A [`BenchRunner`] with [`BenchRunner::cancellation`] set checks the token
+/// between iterations. Once it is cancelled, the warm-up stops, the
+/// measurement stops after at least one iteration, and the result covers the
+/// iterations measured so far and is marked [`BenchmarkResult::cancelled`].
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Coefficient of variation of the batch means below which a
+/// [`BenchRunner::run_until_stable`] runner stops measuring, unless
+/// configured otherwise.
+pub const DEFAULT_STABLE_CV: f64 = 0.02;
+
+/// Batches over which a [`StabilityTarget`] computes the coefficient of
+/// variation; it never stops on stability before it measured this many.
+pub const STABLE_WINDOW: usize = 5;
+
+/// When a [`BenchRunner::run_until_stable`] runner stops measuring.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StabilityTarget {
+    /// Stop once the coefficient of variation (standard deviation over mean)
+    /// of the means of the last [`STABLE_WINDOW`] batches is below this.
+    pub max_cv: f64,
+    /// Stop once the measurement has taken this long, stable or not. Checked
+    /// after each batch, so a run overshoots it by up to one batch.
+    pub max_ms: u64,
+}
+
+impl StabilityTarget {
+    /// Coefficient of variation of the last [`STABLE_WINDOW`] of
+    /// `batch_means`, or `None` if there are fewer.
+    pub fn recent_cv(batch_means: &[f64]) -> Option<f64> {
+        let window = batch_means.get(batch_means.len().checked_sub(STABLE_WINDOW)?..)?;
+        let n = window.len() as f64;
+        let mean = window.iter().sum::<f64>() / n;
+        let variance = window.iter().map(|m| (m - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        Some(variance.sqrt() / mean)
+    }
+
+    /// Call `batch` with the indices of the next `batch_iters` iterations
+    /// until the batch means are stable or the time is up, at least once.
+    /// `batch` runs the iterations and returns the time they took and how
+    /// many it ran; fewer than asked for mean the run was cancelled. The
+    /// statistics cover all of them.
+    fn measure<T: Timer>(
+        &self,
+        timer: &T,
+        batch_iters: usize,
+        mut batch: impl FnMut(Range<usize>) -> (f64, usize),
+    ) -> Statistics {
+        let batch_iters = batch_iters.max(1);
+        let start = timer.now();
+        let mut batch_means = Vec::new();
+        let mut total_ns = 0.0;
+        let mut measured = 0;
+        loop {
+            let (batch_ns, ran) = batch(measured..measured + batch_iters);
+            total_ns += batch_ns;
+            measured += ran;
+            if ran < batch_iters {
+                break;
+            }
+            batch_means.push(batch_ns / batch_iters as f64);
+
+            let stable = Self::recent_cv(&batch_means).is_some_and(|cv| cv < self.max_cv);
+            if stable || timer.elapsed_ns(start) >= self.max_ms as f64 * 1e6 {
+                break;
+            }
+        }
+        Statistics::from_measurement(total_ns, measured)
+    }
+}
+
+/// What a [`BenchRunner`] does with iterations far slower or faster than the
+/// rest, such as a WebGL frame that stalls on a shader compile.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum OutlierPolicy {
+    /// Keep every iteration.
+    #[default]
+    Keep,
+    /// Time every iteration on its own and leave out those outside Tukey's
+    /// fences, `k` interquartile ranges beyond the quartiles (1.5 is usual,
+    /// 3 removes only extreme outliers). The result keeps the statistics of
+    /// all iterations in [`BenchmarkResult::raw_statistics`] and counts the
+    /// removed ones in [`BenchmarkResult::outliers_removed`].
+    Trim { k: f64 },
+}
+
+/// How long a [`BenchRunner::with_time_budget`] runner spends on a benchmark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeBudget {
+    /// Time to call the benchmark for before measuring, to estimate the cost
+    /// of an iteration. Doubles as the warm-up.
+    pub calibration_ms: u64,
+    /// Time the measured iterations should take together.
+    pub measurement_ms: u64,
+}
+
+impl TimeBudget {
+    /// Iterations that fill [`Self::measurement_ms`] at `iteration_ns` each:
+    /// at least 1, even for an iteration slower than the whole budget, and at
+    /// most [`MAX_BUDGET_ITERATIONS`].
+    pub fn iterations_for(&self, iteration_ns: f64) -> u64 {
+        if iteration_ns <= 0.0 {
+            return MAX_BUDGET_ITERATIONS;
+        }
+        let iterations = (self.measurement_ms as f64 * 1e6 / iteration_ns).round();
+        (iterations as u64).clamp(1, MAX_BUDGET_ITERATIONS)
+    }
+
+    /// Call `f` for about [`Self::calibration_ms`], at least once, and return
+    /// the number of iterations to measure. Stops early once `cancelled`.
+    ///
+    /// Calls are timed in batches that double in size, so that the timer
+    /// costs little next to a fast `f`, but stop short of overrunning the
+    /// calibration time by much.
+    fn calibrate<T: Timer>(
+        &self,
+        timer: &T,
+        cancelled: impl Fn() -> bool,
+        mut f: impl FnMut(),
+    ) -> u64 {
+        let budget_ns = self.calibration_ms as f64 * 1e6;
+        let mut calls = 0_u64;
+        let mut elapsed_ns = 0.0;
+        let mut batch = 1_u64;
+        loop {
+            let start = timer.now();
+            for _ in 0..batch {
+                f();
+            }
+            elapsed_ns += timer.elapsed_ns(start);
+            calls += batch;
+            if elapsed_ns >= budget_ns || calls >= MAX_BUDGET_ITERATIONS || cancelled() {
+                break;
+            }
+            // A timer too coarse to see the calls so far counts them as 1 ns.
+            let call_ns = (elapsed_ns / calls as f64).max(1.0);
+            let remaining = ((budget_ns - elapsed_ns) / call_ns).ceil() as u64;
+            batch = (batch * 2).min(remaining).max(1);
+        }
+        self.iterations_for(elapsed_ns / calls as f64)
+    }
+}
+
+/// Which version of a scene a benchmark closure should draw.
+///
+/// See [`BenchRunner::run_with_warmup_proxy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameKind {
+    /// A lighter stand-in, drawn during warm-up only.
+    Proxy,
+    /// The real scene.
+    Full,
+}
+
+/// Cold first frames [`BenchRunner::measure_first_iteration`] times, enough
+/// for a minimum and median without rebuilding a renderer for long.
+pub const COLD_REPETITIONS: usize = 5;
+
+/// Seed of the input a [`MicroBench`] is measured on.
+pub const MEASURE_SEED: u64 = 0;
+
+/// Seed of the input a [`MicroBench`] warms up on with
+/// [`WarmupInput::Distinct`].
+pub const WARMUP_SEED: u64 = 1;
+
+/// A micro-benchmark whose input can be generated, so that the warm-up can
+/// run on different data than the measurement (see
+/// [`BenchRunner::run_micro`]).
+pub trait MicroBench {
+    type Input;
+
+    /// Build a deterministic input for `seed`. Inputs for different seeds
+    /// must be the same size, so that they do the same amount of work, but
+    /// should differ in the data that branches depend on.
+    fn make_input(&self, seed: u64) -> Self::Input;
+
+    /// Run one iteration on `input`.
+    fn run(&mut self, input: &Self::Input);
+}
+
 #[derive(Debug, Clone)]
 pub struct BenchRunner {
     pub warmup: u64,
     pub iterations: u64,
+    /// Recorded in every result produced by this runner.
+    pub measurement_mode: MeasurementMode,
+    /// What [`MicroBench`]es warm up on. Other benchmarks ignore it.
+    pub warmup_input: WarmupInput,
+    /// Time every measured iteration on its own and keep the times in
+    /// [`Statistics::samples`], so that their distribution can be analyzed.
+    /// Benchmarks otherwise timed as one span are then timed per iteration
+    /// as well, without a frame wait; [`MicroBench`]es are always timed as
+    /// one span, since the timer would cost more than an iteration.
+    pub sample_iterations: bool,
+    /// Most samples kept with [`Self::sample_iterations`]. Beyond that, a
+    /// uniform random subset of this size is kept.
+    pub max_samples: usize,
+    /// If set, `warmup` and `iterations` are ignored; each run calibrates
+    /// its iteration count to this budget instead (see
+    /// [`Self::with_time_budget`]).
+    pub time_budget: Option<TimeBudget>,
+    /// Whether to remove outliers from the statistics. Trimming needs the
+    /// time of every iteration, so it times iterations on its own like
+    /// [`Self::sample_iterations`] does, but keeps the samples in the result
+    /// only if that is set too. [`MicroBench`]es are never trimmed.
+    pub outlier_policy: OutlierPolicy,
+    /// If set, measure in batches of `iterations` until the batch means are
+    /// stable (see [`Self::run_until_stable`]).
+    pub until_stable: Option<StabilityTarget>,
+    /// If set, checked between iterations to stop the run early (see
+    /// [`CancellationToken`]).
+    pub cancellation: Option<CancellationToken>,
 }
 
 impl BenchRunner {
     pub fn new(warmup: u64, iterations: u64) -> Self {
-        Self { warmup, iterations }
+        Self {
+            warmup,
+            iterations,
+            measurement_mode: MeasurementMode::Full,
+            warmup_input: WarmupInput::Same,
+            sample_iterations: false,
+            max_samples: DEFAULT_MAX_SAMPLES,
+            time_budget: None,
+            outlier_policy: OutlierPolicy::Keep,
+            until_stable: None,
+            cancellation: None,
+        }
+    }
+
+    /// A runner that measures every benchmark for about `measurement_ms`,
+    /// however slow or fast an iteration of it is.
+    ///
+    /// Instead of a fixed warm-up, each run first calls the benchmark for
+    /// about `calibration_ms` to estimate the cost of an iteration, then
+    /// measures as many iterations as fit `measurement_ms`. A benchmark slower
+    /// than the calibration time is called once to calibrate and measured at
+    /// least once; a very fast one is measured at most
+    /// [`MAX_BUDGET_ITERATIONS`] times. Results record the iteration count in
+    /// [`Statistics::iterations`]. Benchmarks with a proxy warm-up calibrate
+    /// on full frames.
+    pub fn with_time_budget(calibration_ms: u64, measurement_ms: u64) -> Self {
+        Self {
+            time_budget: Some(TimeBudget {
+                calibration_ms,
+                measurement_ms,
+            }),
+            ..Self::new(0, 0)
+        }
+    }
+
+    /// A runner that measures in batches of `batch_iterations` until the
+    /// means of the last [`STABLE_WINDOW`] batches vary by less than
+    /// [`DEFAULT_STABLE_CV`], or until `max_ms` have passed, after `warmup`
+    /// iterations. Meant for noisy machines, where a fixed count gives a
+    /// different confidence every run. [`Statistics::iterations`] records how
+    /// many iterations were measured.
+    ///
+    /// Per-iteration performance marks are not emitted in this mode.
+    pub fn run_until_stable(warmup: u64, batch_iterations: u64, max_ms: u64) -> Self {
+        Self {
+            until_stable: Some(StabilityTarget {
+                max_cv: DEFAULT_STABLE_CV,
+                max_ms,
+            }),
+            ..Self::new(warmup, batch_iterations)
+        }
+    }
+
+    /// A runner that skips warm-up and times exactly one iteration. Its
+    /// results are marked [`MeasurementMode::SingleShot`].
+    ///
+    /// Meant for smoke runs and quick previews of large scenes where even the
+    /// warm-up is too slow. Pass it to
+    /// [`run_benchmark_by_id`](crate::registry::run_benchmark_by_id) to get an
+    /// estimate for any benchmark.
+    pub fn single_shot() -> Self {
+        Self {
+            measurement_mode: MeasurementMode::SingleShot,
+            ..Self::new(0, 1)
+        }
     }
 }
 
 impl BenchRunner {
-    /// Runs `self.warmup` iterations of `f.
-    fn warmup<F>(&self, mut f: F)
+    /// Runs `self.warmup` iterations of `f`. With `proxy`, all but the last
+    /// iteration draw [`FrameKind::Proxy`]; the last one is always full-size
+    /// so caches are primed at the real size.
+    fn warmup<F>(&self, mut f: F, proxy: bool)
     where
-        F: FnMut(),
+        F: FnMut(FrameKind),
     {
-        for _ in 0..self.warmup {
-            f();
+        for i in 0..self.warmup {
+            if self.is_cancelled() {
+                break;
+            }
+            if proxy && i + 1 < self.warmup {
+                f(FrameKind::Proxy);
+            } else {
+                f(FrameKind::Full);
+            }
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// Whether to stop the measurement before iteration `i`. It always runs
+    /// its first iteration, so that a cancelled run still has statistics.
+    fn stops_before(&self, i: usize) -> bool {
+        i > 0 && self.is_cancelled()
+    }
+
+    /// Whether a run with `proxy` requested actually draws proxy frames.
+    fn uses_warmup_proxy(&self, proxy: bool) -> bool {
+        proxy && self.warmup > 1
+    }
+
+    /// Where to keep the times of `total_iters` iterations, if this runner
+    /// samples them.
+    fn samples(&self, total_iters: usize) -> Option<Samples> {
+        let trims = matches!(self.outlier_policy, OutlierPolicy::Trim { .. });
+        (self.sample_iterations || trims).then(|| Samples::new(self.max_samples, total_iters))
+    }
+
+    /// `statistics` as this runner reports them, and those of all iterations
+    /// with how many were outliers, if it looked for them.
+    fn apply_outlier_policy(
+        &self,
+        statistics: Statistics,
+    ) -> (Statistics, Option<Statistics>, Option<usize>) {
+        let (mut statistics, mut raw, removed) = match self.outlier_policy {
+            OutlierPolicy::Trim { k } if !statistics.samples.is_empty() => {
+                let (trimmed, removed) = statistics.without_outliers(k);
+                let raw = (removed > 0).then_some(statistics);
+                (trimmed, raw, Some(removed))
+            }
+            _ => (statistics, None, None),
+        };
+        if !self.sample_iterations {
+            statistics.samples = Vec::new();
+            if let Some(raw) = &mut raw {
+                raw.samples = Vec::new();
+            }
         }
+        (statistics, raw, removed)
     }
 
     /// Bulk-timing measurement: times the entire loop as a single span.
@@ -35,81 +582,170 @@ impl BenchRunner {
     /// No per-iteration `performance.mark()` calls are emitted — use
     /// [`Self::measure_per_iteration_with_frame_wait`] when DevTools per-iteration marks are
     /// needed (e.g. GPU benchmarks).
-    fn measure<F, T: Timer>(
+    fn measure<F, T: Timer>(&self, timer: &T, mut f: F, total_iters: usize) -> Statistics
+    where
+        F: FnMut(),
+    {
+        let mut measured = 0;
+        let start = timer.now();
+        for i in 0..total_iters {
+            if self.stops_before(i) {
+                break;
+            }
+            f();
+            measured += 1;
+        }
+        let elapsed_ns = timer.elapsed_ns(start);
+
+        Statistics::from_measurement(elapsed_ns, measured)
+    }
+
+    /// Per-iteration measurement without a frame wait: times each call of
+    /// `f` on its own and keeps the times in `samples`.
+    fn measure_per_iteration<F, T: Timer>(
+        &self,
         timer: &T,
         mut f: F,
         total_iters: usize,
+        mut samples: Samples,
     ) -> Statistics
     where
         F: FnMut(),
     {
-        let start = timer.now();
-        for _ in 0..total_iters {
+        let mut measured = 0;
+        let mut total_ns = 0.0;
+        for i in 0..total_iters {
+            if self.stops_before(i) {
+                break;
+            }
+            measured += 1;
+            let iter_start = timer.now();
             f();
+            let elapsed_ns = timer.elapsed_ns(iter_start);
+            total_ns += elapsed_ns;
+            samples.push(elapsed_ns);
         }
-        let elapsed_ns = timer.elapsed_ns(start);
 
-        Statistics::from_measurement(elapsed_ns, total_iters)
+        samples.finish(Statistics::from_measurement(total_ns, measured))
     }
 
     /// Run the measurement phase with **per-iteration timing** and an untimed
     /// frame wait between iterations.
     ///
-    /// Each call to `f()` is timed individually and the elapsed durations are
-    /// accumulated. Between iterations the timer's [`Timer::wait_one_frame`] is
-    /// called — that pause is **not** included in the measurement.
+    /// Each call to `f()`, up to the end of the future it returns, is timed
+    /// individually and the elapsed durations are
+    /// accumulated, and kept in `samples` if given. Between iterations the timer's [`Timer::wait_one_frame`] is
+    /// called, or with `yield_frames` [`Timer::next_frame`] is awaited — that pause is **not** included in the measurement.
     ///
     /// This variant is designed for GPU / WebGL benchmarks where giving the
     /// compositor a full frame between renders prevents pipeline overlap from
     /// skewing results. On native the frame wait is a no-op, so the only
     /// difference from [`Self::measure`] is the per-iteration timing overhead
     /// (negligible for GPU-bound work).
-    fn measure_per_iteration_with_frame_wait<F, T: Timer>(
-        timer: &T,
-        bench_id: &str,
+    async fn measure_per_iteration_with_frame_wait<F, Fut, T: Timer>(
+        &self,
+        marker: &Marker<'_, T>,
         mut f: F,
         total_iters: usize,
+        mut samples: Option<Samples>,
+        yield_frames: bool,
     ) -> Statistics
     where
-        F: FnMut(),
+        F: FnMut() -> Fut,
+        Fut: Future<Output = ()>,
     {
+        let timer = marker.timer;
         let emit_marks = total_iters <= MAX_MARKED_ITERS;
+        let mut measured = 0;
         let mut total_ns = 0.0;
 
         for i in 0..total_iters {
-            if emit_marks {
-                timer.mark(&format!("bench:{bench_id}:iter:{i}"));
+            if self.stops_before(i) {
+                break;
+            }
+            measured += 1;
+            let start_mark = emit_marks.then(|| marker.names.iter(i));
+            if let Some(start_mark) = &start_mark {
+                marker.mark_iteration(start_mark);
             }
 
             let iter_start = timer.now();
-            f();
-            total_ns += timer.elapsed_ns(iter_start);
-
-            if emit_marks {
-                timer.mark(&format!("bench:{bench_id}:iter:{i}:end"));
-                timer.measure_span(
-                    &format!("{bench_id} iter {i}"),
-                    &format!("bench:{bench_id}:iter:{i}"),
-                    &format!("bench:{bench_id}:iter:{i}:end"),
-                );
+            f().await;
+            let elapsed_ns = timer.elapsed_ns(iter_start);
+            total_ns += elapsed_ns;
+            if let Some(samples) = &mut samples {
+                samples.push(elapsed_ns);
+            }
+
+            if let Some(start_mark) = &start_mark {
+                let end_mark = marker.names.iter_end(i);
+                marker.mark_iteration(&end_mark);
+                timer.measure_span(&marker.names.iter_span(i), start_mark, &end_mark);
             }
 
             // Untimed frame wait — gives the GPU time to fully flush.
             if i + 1 < total_iters {
-                timer.wait_one_frame();
+                if yield_frames {
+                    timer.next_frame().await;
+                } else {
+                    timer.wait_one_frame();
+                }
             }
         }
 
-        Statistics::from_measurement(total_ns, total_iters)
+        let statistics = Statistics::from_measurement(total_ns, measured);
+        match samples {
+            Some(samples) => samples.finish(statistics),
+            None => statistics,
+        }
     }
 
     /// Run a benchmark using the provided timer, with optional callback after
-    /// calibration.
+    /// calibration. Progress goes to `on_progress`, or to the callback of
+    /// [`with_progress`] if `None`.
     ///
     /// When `per_iteration` is `true` the measurement phase uses
-    /// [`Self::measure_per_iteration`] (individual timing + frame waits);
-    /// otherwise it uses the bulk [`Self::measure`] loop.
+    /// [`Self::measure_per_iteration_with_frame_wait`] (individual timing +
+    /// frame waits); otherwise it uses the bulk [`Self::measure`] loop, or
+    /// [`Self::measure_per_iteration`] with [`Self::sample_iterations`]. With `proxy`, the
+    /// warm-up draws proxy frames (see [`Self::warmup`]); measured iterations
+    /// always draw [`FrameKind::Full`].
     fn run_with_timer<F, T: Timer, C: FnOnce()>(
+        &self,
+        timer: &T,
+        id: &str,
+        category: &str,
+        name: &str,
+        simd_variant: &str,
+        f: F,
+        on_calibrated: C,
+        on_progress: Option<&mut dyn FnMut(ProgressEvent)>,
+        per_iteration: bool,
+        proxy: bool,
+    ) -> BenchmarkResult
+    where
+        F: FnMut(FrameKind),
+    {
+        block_on_ready(self.run_with_timer_async(
+            timer,
+            id,
+            category,
+            name,
+            simd_variant,
+            f,
+            on_calibrated,
+            on_progress,
+            per_iteration,
+            proxy,
+            false,
+        ))
+    }
+
+    /// [`Self::run_with_timer`], awaiting [`Timer::next_frame`] between
+    /// measured iterations with `per_iteration` and `yield_frames`. A runner
+    /// with [`Self::until_stable`] still waits with
+    /// [`Timer::wait_one_frame`].
+    async fn run_with_timer_async<F, T: Timer, C: FnOnce()>(
         &self,
         timer: &T,
         id: &str,
@@ -118,66 +754,154 @@ impl BenchRunner {
         simd_variant: &str,
         mut f: F,
         on_calibrated: C,
+        on_progress: Option<&mut dyn FnMut(ProgressEvent)>,
         per_iteration: bool,
+        proxy: bool,
+        yield_frames: bool,
     ) -> BenchmarkResult
     where
-        F: FnMut(),
+        F: FnMut(FrameKind),
     {
         // Clear stale marks/measures from any previous benchmark run.
         timer.clear_marks();
         timer.clear_measures();
+        let marker = Marker::new(timer, id);
+        let progress = Progress::new(timer, on_progress);
+        let mut step = |kind| {
+            f(kind);
+            progress.tick();
+        };
 
-        timer.mark(&format!("bench:{id}:warmup:start"));
-        self.warmup(&mut f);
-        timer.mark(&format!("bench:{id}:warmup:end"));
-        timer.measure_span(
-            &format!("{id} warm-up"),
-            &format!("bench:{id}:warmup:start"),
-            &format!("bench:{id}:warmup:end"),
+        progress.start(
+            ProgressPhase::Warmup,
+            self.time_budget.is_none().then_some(self.warmup),
         );
+        let mut warmup_times =
+            WarmupTimes::new(timer, per_iteration || self.sample_iterations, self);
+        let total_iters = with_span(&marker, Phase::Warmup, || match self.time_budget {
+            Some(budget) => budget.calibrate(
+                timer,
+                || self.is_cancelled(),
+                || warmup_times.time(|| step(FrameKind::Full)),
+            ),
+            None => {
+                self.warmup(|kind| warmup_times.time(|| step(kind)), proxy);
+                self.iterations
+            }
+        }) as usize;
+        let warmup = warmup_times.finish();
+        progress.finish();
 
         on_calibrated();
 
-        let total_iters = self.iterations as usize;
-
-        timer.mark(&format!("bench:{id}:measure:start"));
-        let statistics = if per_iteration {
-            Self::measure_per_iteration_with_frame_wait(timer, id, f, total_iters)
-        } else {
-            Self::measure(timer, f, total_iters)
-        };
-        timer.mark(&format!("bench:{id}:measure:end"));
-        timer.measure_span(
-            &format!("{id} measurement"),
-            &format!("bench:{id}:measure:start"),
-            &format!("bench:{id}:measure:end"),
+        progress.start(
+            ProgressPhase::Measurement,
+            self.until_stable.is_none().then_some(total_iters as u64),
         );
+        let mut full = || step(FrameKind::Full);
 
-        BenchmarkResult {
-            id: id.to_string(),
-            category: category.to_string(),
-            name: name.to_string(),
-            simd_variant: simd_variant.to_string(),
-            statistics,
-            timestamp_ms: timer.timestamp_ms(),
-        }
+        let mut samples = self.samples(total_iters);
+
+        let statistics = with_span_async(&marker, Phase::Measure, async {
+            if let Some(target) = &self.until_stable {
+                let timed = per_iteration || samples.is_some();
+                let statistics = target.measure(timer, total_iters, |batch| {
+                    let mut ran = 0;
+                    if !timed {
+                        let start = timer.now();
+                        for i in batch {
+                            if self.stops_before(i) {
+                                break;
+                            }
+                            full();
+                            ran += 1;
+                        }
+                        return (timer.elapsed_ns(start), ran);
+                    }
+                    let mut batch_ns = 0.0;
+                    for i in batch {
+                        if self.stops_before(i) {
+                            break;
+                        }
+                        ran += 1;
+                        let iter_start = timer.now();
+                        full();
+                        let elapsed_ns = timer.elapsed_ns(iter_start);
+                        batch_ns += elapsed_ns;
+                        if let Some(samples) = &mut samples {
+                            samples.push(elapsed_ns);
+                        }
+                        if per_iteration {
+                            timer.wait_one_frame();
+                        }
+                    }
+                    (batch_ns, ran)
+                });
+                match samples {
+                    Some(samples) => samples.finish(statistics),
+                    None => statistics,
+                }
+            } else if per_iteration {
+                self.measure_per_iteration_with_frame_wait(
+                    &marker,
+                    || std::future::ready(full()),
+                    total_iters,
+                    samples,
+                    yield_frames,
+                )
+                .await
+            } else if let Some(samples) = samples {
+                self.measure_per_iteration(timer, full, total_iters, samples)
+            } else {
+                self.measure(timer, full, total_iters)
+            }
+        })
+        .await;
+        progress.finish();
+
+        let mut result = make_result(self, timer, id, category, name, simd_variant, statistics);
+        result.warmup = warmup;
+        result.warmup_proxy = self.uses_warmup_proxy(proxy);
+        result.marks = marker.finish();
+        result
     }
 
     /// Run a benchmark and return the result.
-    pub fn run<F>(&self, id: &str, category: &str, name: &str, simd_variant: &str, f: F) -> BenchmarkResult
+    pub fn run<F>(&self, id: &str, category: &str, name: &str, simd_variant: &str, mut f: F) -> BenchmarkResult
     where
         F: FnMut(),
     {
-        self.run_with_timer(&PlatformTimer::default(), id, category, name, simd_variant, f, || {}, false)
+        self.run_with_timer(&PlatformTimer::default(), id, category, name, simd_variant, |_| f(), || {}, None, false, false)
     }
 
-    /// Run a benchmark with a callback when calibration completes.
-    pub fn run_with_callback<F, C>(&self, id: &str, category: &str, name: &str, simd_variant: &str, f: F, on_calibrated: C) -> BenchmarkResult
+    /// Run a benchmark with a callback when calibration completes, and one
+    /// with the [`ProgressEvent`]s of the run (pass `|_| {}` to ignore them).
+    pub fn run_with_callback<F, C, P>(&self, id: &str, category: &str, name: &str, simd_variant: &str, mut f: F, on_calibrated: C, mut on_progress: P) -> BenchmarkResult
     where
         F: FnMut(),
         C: FnOnce(),
+        P: FnMut(ProgressEvent),
+    {
+        self.run_with_timer(&PlatformTimer::default(), id, category, name, simd_variant, |_| f(), on_calibrated, Some(&mut on_progress), false, false)
+    }
+
+    /// Run a benchmark whose warm-up draws a lighter proxy frame.
+    ///
+    /// Meant for gigantic scenes where a single full frame takes hundreds of
+    /// milliseconds. `f` is told which [`FrameKind`] to draw: every warm-up
+    /// iteration except the last gets [`FrameKind::Proxy`], the last warm-up
+    /// iteration and all measured iterations get [`FrameKind::Full`]. The
+    /// result records whether any proxy frame was drawn in
+    /// [`BenchmarkResult::warmup_proxy`].
+    ///
+    /// With `proxy` set to `false`, `f` only ever gets [`FrameKind::Full`] and
+    /// this behaves like [`Self::run`]; this lets callers that only sometimes
+    /// have a proxy share one code path.
+    pub fn run_with_warmup_proxy<F>(&self, id: &str, category: &str, name: &str, simd_variant: &str, proxy: bool, f: F) -> BenchmarkResult
+    where
+        F: FnMut(FrameKind),
     {
-        self.run_with_timer(&PlatformTimer::default(), id, category, name, simd_variant, f, on_calibrated, false)
+        self.run_with_timer(&PlatformTimer::default(), id, category, name, simd_variant, f, || {}, None, false, proxy)
     }
 
     /// Run a benchmark with per-iteration timing and an untimed frame wait
@@ -192,11 +916,692 @@ impl BenchRunner {
     /// wall-clock duration of the benchmark will be significantly longer than
     /// the sum of iteration times alone. For example, 50 iterations adds
     /// ~800 ms of untimed waiting on top of the actual render time.
-    pub fn run_with_frame_wait<F>(&self, id: &str, category: &str, name: &str, simd_variant: &str, f: F) -> BenchmarkResult
+    pub fn run_with_frame_wait<F>(&self, id: &str, category: &str, name: &str, simd_variant: &str, mut f: F) -> BenchmarkResult
     where
         F: FnMut(),
     {
-        self.run_with_timer(&PlatformTimer::default(), id, category, name, simd_variant, f, || {}, true)
+        self.run_with_timer(&PlatformTimer::default(), id, category, name, simd_variant, |_| f(), || {}, None, true, false)
+    }
+
+    /// [`Self::run_with_frame_wait`], but between iterations it waits for the
+    /// next animation frame (`requestAnimationFrame`) instead of busy-waiting
+    /// for one, so that the page stays responsive and no core is kept busy.
+    /// The timed part of each iteration is the same.
+    ///
+    /// Where there is no `requestAnimationFrame`, as in some workers, it
+    /// busy-waits like [`Self::run_with_frame_wait`]; natively there is no
+    /// frame wait at all. Browsers pause animation frames in hidden tabs,
+    /// which pauses the run with them.
+    pub async fn run_with_frame_wait_async<F>(
+        &self,
+        id: &str,
+        category: &str,
+        name: &str,
+        simd_variant: &str,
+        mut f: F,
+    ) -> BenchmarkResult
+    where
+        F: FnMut(),
+    {
+        self.run_with_timer_async(
+            &PlatformTimer::default(),
+            id,
+            category,
+            name,
+            simd_variant,
+            |_| f(),
+            || {},
+            None,
+            true,
+            false,
+            true,
+        )
+        .await
+    }
+
+    /// [`Self::run_with_frame_wait_async`] for frames that finish
+    /// asynchronously: every iteration, of the warm-up too, is timed up to
+    /// the end of the future `f` returns. This is how a frame can wait for a
+    /// WebGL fence, whose status browsers only update between tasks. With
+    /// `proxy`, the warm-up draws proxy frames as in
+    /// [`Self::run_with_warmup_proxy`].
+    ///
+    /// Time budgets and stability targets don't apply; the run measures
+    /// [`Self::iterations`] frames.
+    pub async fn run_with_async_frames<F, Fut>(
+        &self,
+        id: &str,
+        category: &str,
+        name: &str,
+        simd_variant: &str,
+        proxy: bool,
+        mut f: F,
+    ) -> BenchmarkResult
+    where
+        F: FnMut(FrameKind) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        let timer = PlatformTimer::default();
+        timer.clear_marks();
+        timer.clear_measures();
+        let marker = Marker::new(&timer, id);
+        let progress = Progress::new(&timer, None);
+        let mut step = |kind| {
+            let frame = f(kind);
+            let progress = &progress;
+            async move {
+                frame.await;
+                progress.tick();
+            }
+        };
+
+        progress.start(ProgressPhase::Warmup, Some(self.warmup));
+        let mut warmup_times = WarmupTimes::new(&timer, true, self);
+        with_span_async(&marker, Phase::Warmup, async {
+            for i in 0..self.warmup {
+                if self.is_cancelled() {
+                    break;
+                }
+                let kind = if proxy && i + 1 < self.warmup {
+                    FrameKind::Proxy
+                } else {
+                    FrameKind::Full
+                };
+                warmup_times.time_async(step(kind)).await;
+            }
+        })
+        .await;
+        let warmup = warmup_times.finish();
+        progress.finish();
+
+        let total_iters = self.iterations as usize;
+        progress.start(ProgressPhase::Measurement, Some(self.iterations));
+        let samples = self.samples(total_iters);
+        let statistics = with_span_async(
+            &marker,
+            Phase::Measure,
+            self.measure_per_iteration_with_frame_wait(
+                &marker,
+                || step(FrameKind::Full),
+                total_iters,
+                samples,
+                true,
+            ),
+        )
+        .await;
+        progress.finish();
+
+        let mut result = make_result(self, &timer, id, category, name, simd_variant, statistics);
+        result.warmup = warmup;
+        result.warmup_proxy = self.uses_warmup_proxy(proxy);
+        result.marks = marker.finish();
+        result
+    }
+
+    /// [`Self::run_with_frame_wait`] with a proxy warm-up as in
+    /// [`Self::run_with_warmup_proxy`].
+    pub fn run_with_frame_wait_and_warmup_proxy<F>(&self, id: &str, category: &str, name: &str, simd_variant: &str, proxy: bool, f: F) -> BenchmarkResult
+    where
+        F: FnMut(FrameKind),
+    {
+        self.run_with_timer(&PlatformTimer::default(), id, category, name, simd_variant, f, || {}, None, true, proxy)
+    }
+
+    /// Run a benchmark where every iteration is preceded by an untimed setup
+    /// step.
+    ///
+    /// `setup` prepares `state` before each warm-up and measurement iteration;
+    /// only `f` is timed. This is meant for operations that consume or
+    /// invalidate what they work on, such as resetting a freshly built scene.
+    /// Each iteration is timed individually, so `f` should take at least a few
+    /// microseconds for the timer overhead to be negligible.
+    pub fn run_with_setup<C, S, F>(
+        &self,
+        id: &str,
+        category: &str,
+        name: &str,
+        simd_variant: &str,
+        state: &mut C,
+        mut setup: S,
+        mut f: F,
+    ) -> BenchmarkResult
+    where
+        S: FnMut(&mut C),
+        F: FnMut(&mut C),
+    {
+        let timer = PlatformTimer::default();
+        timer.clear_marks();
+        timer.clear_measures();
+        let marker = Marker::new(&timer, id);
+        let progress = Progress::new(&timer, None);
+        let mut step = |state: &mut C| {
+            f(state);
+            progress.tick();
+        };
+
+        // A calibration counts the setup as part of an iteration, so it errs
+        // on the side of fewer iterations.
+        progress.start(
+            ProgressPhase::Warmup,
+            self.time_budget.is_none().then_some(self.warmup),
+        );
+        let mut warmup_times = WarmupTimes::new(&timer, true, self);
+        let total_iters = with_span(&marker, Phase::Warmup, || match self.time_budget {
+            Some(budget) => budget.calibrate(
+                &timer,
+                || self.is_cancelled(),
+                || {
+                    setup(state);
+                    warmup_times.time(|| step(state));
+                },
+            ),
+            None => {
+                for _ in 0..self.warmup {
+                    if self.is_cancelled() {
+                        break;
+                    }
+                    setup(state);
+                    warmup_times.time(|| step(state));
+                }
+                self.iterations
+            }
+        }) as usize;
+        let warmup = warmup_times.finish();
+        progress.finish();
+
+        let mut samples = self.samples(total_iters);
+
+        progress.start(
+            ProgressPhase::Measurement,
+            self.until_stable.is_none().then_some(total_iters as u64),
+        );
+        let statistics = with_span(&marker, Phase::Measure, || {
+            let mut run_batch = |batch: Range<usize>| {
+                let mut ran = 0;
+                let mut total_ns = 0.0;
+                for i in batch {
+                    if self.stops_before(i) {
+                        break;
+                    }
+                    ran += 1;
+                    setup(state);
+                    let iter_start = timer.now();
+                    step(state);
+                    let elapsed_ns = timer.elapsed_ns(iter_start);
+                    total_ns += elapsed_ns;
+                    if let Some(samples) = &mut samples {
+                        samples.push(elapsed_ns);
+                    }
+                }
+                (total_ns, ran)
+            };
+            let statistics = match &self.until_stable {
+                Some(target) => target.measure(&timer, total_iters, run_batch),
+                None => {
+                    let (total_ns, ran) = run_batch(0..total_iters);
+                    Statistics::from_measurement(total_ns, ran)
+                }
+            };
+            match samples {
+                Some(samples) => samples.finish(statistics),
+                None => statistics,
+            }
+        });
+        progress.finish();
+
+        let mut result = make_result(self, &timer, id, category, name, simd_variant, statistics);
+        result.warmup = warmup;
+        result.marks = marker.finish();
+        result
+    }
+
+    /// Run a [`MicroBench`], measured on its input for [`MEASURE_SEED`].
+    ///
+    /// With [`WarmupInput::Distinct`], the warm-up runs on the input for
+    /// [`WARMUP_SEED`] instead, so the measurement can't profit from branch
+    /// predictors and caches trained on exactly the data it times. Inputs are
+    /// built before the warm-up and are not timed.
+    ///
+    /// The measurement is always timed as one span and never sampled, even
+    /// with [`Self::sample_iterations`].
+    pub fn run_micro<B: MicroBench>(
+        &self,
+        id: &str,
+        category: &str,
+        name: &str,
+        simd_variant: &str,
+        bench: &mut B,
+    ) -> BenchmarkResult {
+        let timer = PlatformTimer::default();
+        timer.clear_marks();
+        timer.clear_measures();
+        let marker = Marker::new(&timer, id);
+
+        let input = bench.make_input(MEASURE_SEED);
+        let warmup_input = match self.warmup_input {
+            WarmupInput::Distinct if self.warmup > 0 || self.time_budget.is_some() => {
+                Some(bench.make_input(WARMUP_SEED))
+            }
+            _ => None,
+        };
+        let progress = Progress::new(&timer, None);
+        let mut step = |input: &B::Input| {
+            bench.run(input);
+            progress.tick();
+        };
+
+        // The calibration doubles as the warm-up, so it runs on the warm-up
+        // input; both inputs take the same work.
+        progress.start(
+            ProgressPhase::Warmup,
+            self.time_budget.is_none().then_some(self.warmup),
+        );
+        let mut warmup_times = WarmupTimes::new(&timer, false, self);
+        let total_iters = with_span(&marker, Phase::Warmup, || {
+            let warmup_input = warmup_input.as_ref().unwrap_or(&input);
+            match self.time_budget {
+                Some(budget) => budget.calibrate(
+                    &timer,
+                    || self.is_cancelled(),
+                    || warmup_times.time(|| step(warmup_input)),
+                ),
+                None => {
+                    for _ in 0..self.warmup {
+                        if self.is_cancelled() {
+                            break;
+                        }
+                        warmup_times.time(|| step(warmup_input));
+                    }
+                    self.iterations
+                }
+            }
+        }) as usize;
+        let warmup = warmup_times.finish();
+        progress.finish();
+
+        progress.start(
+            ProgressPhase::Measurement,
+            self.until_stable.is_none().then_some(total_iters as u64),
+        );
+        let statistics = with_span(&marker, Phase::Measure, || match &self.until_stable {
+            Some(target) => target.measure(&timer, total_iters, |batch| {
+                let mut ran = 0;
+                let start = timer.now();
+                for i in batch {
+                    if self.stops_before(i) {
+                        break;
+                    }
+                    step(&input);
+                    ran += 1;
+                }
+                (timer.elapsed_ns(start), ran)
+            }),
+            None => self.measure(&timer, || step(&input), total_iters),
+        });
+        progress.finish();
+
+        let mut result = make_result(self, &timer, id, category, name, simd_variant, statistics);
+        result.warmup = warmup;
+        result.warmup_input = self.warmup_input;
+        result.marks = marker.finish();
+        result
+    }
+
+    /// Time the first frame of fresh state instead of the steady state: cold
+    /// caches, first GPU uploads, shader compiles.
+    ///
+    /// `f` builds the state from scratch (such as a renderer from its scene)
+    /// and renders one frame with it; all of it is timed. It runs
+    /// [`COLD_REPETITIONS`] times without warm-up, and the statistics are
+    /// over those first frames, with their median and minimum. The state `f`
+    /// returns is dropped outside the timed region. The warm-up, time budget
+    /// and stability target of the runner don't apply.
+    pub fn measure_first_iteration<S>(
+        &self,
+        id: &str,
+        category: &str,
+        name: &str,
+        simd_variant: &str,
+        mut f: impl FnMut() -> S,
+    ) -> BenchmarkResult {
+        let timer = PlatformTimer::default();
+        timer.clear_marks();
+        timer.clear_measures();
+        let marker = Marker::new(&timer, id);
+        let progress = Progress::new(&timer, None);
+
+        progress.start(ProgressPhase::Measurement, Some(COLD_REPETITIONS as u64));
+        let statistics = with_span(&marker, Phase::Measure, || {
+            let mut times = Vec::with_capacity(COLD_REPETITIONS);
+            for i in 0..COLD_REPETITIONS {
+                if self.stops_before(i) {
+                    break;
+                }
+                let start = timer.now();
+                let state = f();
+                times.push(timer.elapsed_ns(start));
+                drop(state);
+                progress.tick();
+            }
+            Statistics::from_measurement(times.iter().sum(), times.len()).with_samples(times)
+        });
+        progress.finish();
+
+        let mut result = make_result(self, &timer, id, category, name, simd_variant, statistics);
+        result.marks = marker.finish();
+        result
+    }
+
+    /// Time a single iteration of `f` without warm-up and return a result
+    /// marked [`MeasurementMode::SingleShot`].
+    pub fn run_single<F>(
+        id: &str,
+        category: &str,
+        name: &str,
+        simd_variant: &str,
+        f: F,
+    ) -> BenchmarkResult
+    where
+        F: FnMut(),
+    {
+        Self::single_shot().run(id, category, name, simd_variant, f)
+    }
+}
+
+/// Times of individual iterations, kept for [`Statistics::samples`].
+///
+/// Keeps the first `max` times in order. Beyond that it keeps a uniform
+/// random subset of `max` times (reservoir sampling), no longer in order. The
+/// subset is the same for the same number of iterations.
+struct Samples {
+    max: usize,
+    seen: u64,
+    times: Vec<f64>,
+    rng_state: u64,
+}
+
+impl Samples {
+    fn new(max: usize, expected: usize) -> Self {
+        Self {
+            max,
+            seen: 0,
+            times: Vec::with_capacity(expected.min(max)),
+            rng_state: 0x5eed,
+        }
+    }
+
+    fn push(&mut self, time_ns: f64) {
+        self.seen += 1;
+        if self.times.len() < self.max {
+            self.times.push(time_ns);
+        } else if self.max > 0 {
+            // Replace a random kept time with probability `max / seen`.
+            let slot = self.next_random() % self.seen;
+            if let Some(kept) = self.times.get_mut(slot as usize) {
+                *kept = time_ns;
+            }
+        }
+    }
+
+    /// SplitMix64.
+    fn next_random(&mut self) -> u64 {
+        self.rng_state = self.rng_state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.rng_state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// `statistics` with the kept times as its samples, and their spread.
+    fn finish(self, statistics: Statistics) -> Statistics {
+        statistics.with_samples(self.times)
+    }
+}
+
+/// Times the warm-up (or calibration) of a run for
+/// [`BenchmarkResult::warmup`]: as a whole, or with `per_iteration` each
+/// iteration on its own, so that the first and last ones can be told apart.
+struct WarmupTimes<'a, T: Timer> {
+    timer: &'a T,
+    start: T::Instant,
+    iterations: usize,
+    /// Sum of the per-iteration times.
+    total_ns: f64,
+    samples: Option<Samples>,
+}
+
+impl<'a, T: Timer> WarmupTimes<'a, T> {
+    fn new(timer: &'a T, per_iteration: bool, runner: &BenchRunner) -> Self {
+        Self {
+            timer,
+            start: timer.now(),
+            iterations: 0,
+            total_ns: 0.0,
+            samples: per_iteration
+                .then(|| Samples::new(runner.max_samples, runner.warmup as usize)),
+        }
+    }
+
+    /// Run one warm-up iteration.
+    fn time<R>(&mut self, f: impl FnOnce() -> R) -> R {
+        block_on_ready(self.time_async(async { f() }))
+    }
+
+    /// Run one warm-up iteration that ends with `f`.
+    async fn time_async<R>(&mut self, f: impl Future<Output = R>) -> R {
+        self.iterations += 1;
+        let Some(samples) = &mut self.samples else {
+            return f.await;
+        };
+        let start = self.timer.now();
+        let out = f.await;
+        let elapsed_ns = self.timer.elapsed_ns(start);
+        self.total_ns += elapsed_ns;
+        samples.push(elapsed_ns);
+        out
+    }
+
+    /// The statistics of the warm-up, or `None` if it ran no iterations.
+    fn finish(self) -> Option<Statistics> {
+        if self.iterations == 0 {
+            return None;
+        }
+        Some(match self.samples {
+            Some(samples) => {
+                samples.finish(Statistics::from_measurement(self.total_ns, self.iterations))
+            }
+            None => {
+                Statistics::from_measurement(self.timer.elapsed_ns(self.start), self.iterations)
+            }
+        })
+    }
+}
+
+/// Time `frames` calls of `f` one by one and return the median in
+/// nanoseconds, for quick probes that don't need a full [`BenchmarkResult`].
+/// Returns `NaN` for zero frames.
+pub fn median_frame_ns<F: FnMut()>(frames: usize, mut f: F) -> f64 {
+    let timer = PlatformTimer::default();
+    let mut times: Vec<f64> = (0..frames)
+        .map(|_| {
+            let start = timer.now();
+            f();
+            timer.elapsed_ns(start)
+        })
+        .collect();
+    times.sort_by(f64::total_cmp);
+    quantile(&times, 0.5)
+}
+
+/// Smallest step of the platform timer in nanoseconds. Browsers coarsen
+/// `performance.now()` to anywhere from 5 µs to a millisecond or more.
+///
+/// Measured from one change of the timer's reading to the next, so that the
+/// first, partial step doesn't count; the smallest of a few such steps.
+pub fn timer_resolution_ns() -> f64 {
+    let timer = PlatformTimer::default();
+    let next_step = |start, after: f64| loop {
+        let elapsed = timer.elapsed_ns(start);
+        if elapsed > after {
+            break elapsed;
+        }
+    };
+    (0..5)
+        .map(|_| {
+            let start = timer.now();
+            let edge = next_step(start, 0.0);
+            next_step(start, edge) - edge
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// Call `f` once and return its output with the time it took in nanoseconds.
+pub fn time_ns<T>(f: impl FnOnce() -> T) -> (T, f64) {
+    let timer = PlatformTimer::default();
+    let start = timer.now();
+    let output = f();
+    (output, timer.elapsed_ns(start))
+}
+
+/// A phase of a run, bracketed by a pair of marks.
+#[derive(Clone, Copy)]
+enum Phase {
+    Warmup,
+    Measure,
+}
+
+/// Emits the performance marks of one run, named by [`RunMarks`], and records
+/// their names for [`crate::marks::last_run_marks`] and the result.
+struct Marker<'a, T: Timer> {
+    timer: &'a T,
+    names: RunMarks,
+    recorded: RefCell<RecordedMarks>,
+    iteration_marks: Cell<usize>,
+}
+
+impl<'a, T: Timer> Marker<'a, T> {
+    fn new(timer: &'a T, id: &str) -> Self {
+        Self {
+            timer,
+            names: RunMarks::new(id),
+            recorded: RefCell::new(RecordedMarks::default()),
+            iteration_marks: Cell::new(0),
+        }
+    }
+
+    fn mark(&self, name: &str) {
+        self.timer.mark(name);
+        self.recorded.borrow_mut().names.push(name.to_string());
+    }
+
+    /// Like [`Self::mark`], but only the first
+    /// [`MAX_RECORDED_ITERATION_MARKS`] are recorded by name.
+    fn mark_iteration(&self, name: &str) {
+        self.timer.mark(name);
+        let count = self.iteration_marks.get();
+        self.iteration_marks.set(count + 1);
+
+        let mut recorded = self.recorded.borrow_mut();
+        if count < MAX_RECORDED_ITERATION_MARKS {
+            recorded.names.push(name.to_string());
+        } else {
+            recorded.omitted += 1;
+        }
+    }
+
+    /// Keep the recorded names as the last run's, and return them for the
+    /// result if [`record_marks`] is on.
+    fn finish(self) -> Option<RecordedMarks> {
+        let recorded = self.recorded.into_inner();
+        set_last_run_marks(recorded.clone());
+        record_marks().then_some(recorded)
+    }
+}
+
+/// Run `f` between the start and end marks of `phase` and record a measure
+/// span covering it.
+fn with_span<T: Timer, R>(marker: &Marker<'_, T>, phase: Phase, f: impl FnOnce() -> R) -> R {
+    block_on_ready(with_span_async(marker, phase, async { f() }))
+}
+
+/// [`with_span`] for a future.
+async fn with_span_async<T: Timer, R>(
+    marker: &Marker<'_, T>,
+    phase: Phase,
+    f: impl Future<Output = R>,
+) -> R {
+    let names = &marker.names;
+    let (start_mark, end_mark, span) = match phase {
+        Phase::Warmup => (
+            names.warmup_start(),
+            names.warmup_end(),
+            names.warmup_span(),
+        ),
+        Phase::Measure => (
+            names.measure_start(),
+            names.measure_end(),
+            names.measure_span(),
+        ),
+    };
+
+    marker.mark(&start_mark);
+    let out = f.await;
+    marker.mark(&end_mark);
+    marker.timer.measure_span(&span, &start_mark, &end_mark);
+
+    out
+}
+
+/// Run a future that never waits to completion.
+///
+/// The synchronous runs share their code with the async ones this way; they
+/// busy-wait for frames instead of awaiting [`Timer::next_frame`], so the
+/// future is ready on the first poll.
+fn block_on_ready<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    match future
+        .as_mut()
+        .poll(&mut Context::from_waker(Waker::noop()))
+    {
+        Poll::Ready(out) => out,
+        Poll::Pending => unreachable!("a synchronous benchmark run awaited a frame"),
+    }
+}
+
+/// Assemble a [`BenchmarkResult`] stamped with the timer's current time.
+fn make_result<T: Timer>(
+    runner: &BenchRunner,
+    timer: &T,
+    id: &str,
+    category: &str,
+    name: &str,
+    simd_variant: &str,
+    statistics: Statistics,
+) -> BenchmarkResult {
+    let (statistics, raw_statistics, outliers_removed) = runner.apply_outlier_policy(statistics);
+    BenchmarkResult {
+        id: id.to_string(),
+        category: category.to_string(),
+        name: name.to_string(),
+        simd_variant: simd_variant.to_string(),
+        statistics,
+        raw_statistics,
+        outliers_removed,
+        cancelled: runner.is_cancelled(),
+        warmup: None,
+        throughput: None,
+        render_mode: None,
+        measurement_mode: runner.measurement_mode,
+        warmup_proxy: false,
+        animated: false,
+        warmup_input: WarmupInput::Same,
+        ran_concurrently: false,
+        num_threads: None,
+        sync_mode: None,
+        marks: None,
+        delta: None,
+        canvas_overhead: None,
+        timestamp_ms: timer.timestamp_ms(),
     }
 }
 
@@ -226,6 +1631,13 @@ trait Timer {
     /// **not** included in benchmark timing — it gives the GPU compositor time
     /// to fully flush between frames. No-op on native.
     fn wait_one_frame(&self) {}
+
+    /// Wait for the next display frame without blocking, for async runs.
+    /// Defaults to [`Self::wait_one_frame`].
+    fn next_frame(&self) -> impl Future<Output = ()> {
+        self.wait_one_frame();
+        std::future::ready(())
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -337,4 +1749,259 @@ impl Timer for WasmTimer {
         let target = self.performance.now() + FRAME_WAIT_MS;
         while self.performance.now() < target {}
     }
+
+    /// Awaits `requestAnimationFrame`, falling back to [`Self::wait_one_frame`]
+    /// in workers that don't have it.
+    async fn next_frame(&self) {
+        use wasm_bindgen::JsCast;
+
+        let global = js_sys::global();
+        let raf = js_sys::Reflect::get(
+            &global,
+            &wasm_bindgen::JsValue::from_str("requestAnimationFrame"),
+        )
+        .ok()
+        .and_then(|raf| raf.dyn_into::<js_sys::Function>().ok());
+        let Some(raf) = raf else {
+            self.wait_one_frame();
+            return;
+        };
+        let frame = js_sys::Promise::new(&mut |resolve, _reject| {
+            let _ = raf.call1(&global, &resolve);
+        });
+        let _ = wasm_bindgen_futures::JsFuture::from(frame).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A clock that only moves when told to, so that runs take exactly as
+    /// long as the benchmark says.
+    #[derive(Default)]
+    struct FakeTimer {
+        now_ns: Cell<f64>,
+    }
+
+    impl FakeTimer {
+        fn advance(&self, ns: f64) {
+            self.now_ns.set(self.now_ns.get() + ns);
+        }
+    }
+
+    impl Timer for FakeTimer {
+        type Instant = f64;
+
+        fn now(&self) -> f64 {
+            self.now_ns.get()
+        }
+
+        fn elapsed_ns(&self, start: f64) -> f64 {
+            self.now_ns.get() - start
+        }
+
+        fn timestamp_ms(&self) -> u64 {
+            0
+        }
+    }
+
+    /// Run `f` on `runner` with `timer`, without progress reports.
+    fn run(runner: &BenchRunner, timer: &FakeTimer, mut f: impl FnMut()) -> BenchmarkResult {
+        runner.run_with_timer(
+            timer,
+            "test/fake",
+            "test",
+            "fake",
+            "scalar",
+            |_| f(),
+            || {},
+            None,
+            false,
+            false,
+        )
+    }
+
+    fn target(max_cv: f64, max_ms: u64) -> StabilityTarget {
+        StabilityTarget { max_cv, max_ms }
+    }
+
+    #[test]
+    fn progress_reports_both_phases() {
+        let timer = FakeTimer::default();
+        let runner = BenchRunner::new(20, 1000);
+        let mut events = Vec::new();
+        let result = runner.run_with_timer(
+            &timer,
+            "test/fake",
+            "test",
+            "fake",
+            "scalar",
+            |_| timer.advance(1e6),
+            || {},
+            Some(&mut |event| events.push(event)),
+            false,
+            false,
+        );
+        assert_eq!(result.statistics.iterations, 1000);
+
+        let counts = |phase| {
+            events
+                .iter()
+                .filter(|event| event.phase == phase)
+                .map(|event| (event.completed, event.total))
+                .collect::<Vec<_>>()
+        };
+        // The 20 ms warm-up reports only its start and its end.
+        assert_eq!(
+            counts(ProgressPhase::Warmup),
+            [(0, Some(20)), (20, Some(20))]
+        );
+        // The 1 s measurement also reports every 100 ms, the last of which
+        // is its end.
+        let measurement = (0..=10).map(|i| (i * 100, Some(1000))).collect::<Vec<_>>();
+        assert_eq!(counts(ProgressPhase::Measurement), measurement);
+        assert!(
+            events[..2]
+                .iter()
+                .all(|event| event.phase == ProgressPhase::Warmup)
+        );
+        for event in &events {
+            assert_eq!(event.elapsed_ns, event.completed as f64 * 1e6);
+        }
+    }
+
+    #[test]
+    fn cancelling_keeps_the_iterations_measured_so_far() {
+        let timer = FakeTimer::default();
+        let token = CancellationToken::new();
+        let runner = BenchRunner {
+            sample_iterations: true,
+            cancellation: Some(token.clone()),
+            ..BenchRunner::new(5, 100)
+        };
+        let mut calls = 0_u32;
+        let result = run(&runner, &timer, || {
+            calls += 1;
+            // Measured iteration `k` takes `k` microseconds.
+            timer.advance(calls.saturating_sub(5) as f64 * 1000.0);
+            if calls == 15 {
+                token.cancel();
+            }
+        });
+
+        assert_eq!(calls, 15);
+        assert!(result.cancelled);
+        let statistics = &result.statistics;
+        assert_eq!(statistics.iterations, 10);
+        assert_eq!(statistics.samples.len(), 10);
+        assert_eq!(statistics.mean_ns, 5500.0);
+        assert_eq!(statistics.min_ns, 1000.0);
+        assert_eq!(statistics.max_ns, 10_000.0);
+    }
+
+    #[test]
+    fn cancelling_the_warmup_still_measures_once() {
+        let timer = FakeTimer::default();
+        let token = CancellationToken::new();
+        let runner = BenchRunner {
+            cancellation: Some(token.clone()),
+            ..BenchRunner::new(5, 100)
+        };
+        let mut calls = 0;
+        let result = run(&runner, &timer, || {
+            calls += 1;
+            timer.advance(1000.0);
+            if calls == 2 {
+                token.cancel();
+            }
+        });
+
+        assert_eq!(calls, 3);
+        assert!(result.cancelled);
+        assert_eq!(result.statistics.iterations, 1);
+        assert_eq!(result.statistics.mean_ns, 1000.0);
+    }
+
+    #[test]
+    fn stable_batches_stop_after_the_window() {
+        let timer = FakeTimer::default();
+        let mut batches = 0;
+        let statistics = target(0.02, 1000).measure(&timer, 10, |batch| {
+            batches += 1;
+            timer.advance(batch.len() as f64 * 100.0);
+            (batch.len() as f64 * 100.0, batch.len())
+        });
+        assert_eq!(batches, STABLE_WINDOW);
+        assert_eq!(statistics.iterations, 10 * STABLE_WINDOW);
+        assert_eq!(statistics.mean_ns, 100.0);
+    }
+
+    #[test]
+    fn stability_needs_a_full_window_below_the_target() {
+        // Alternating means have a CV of over 25%; from the sixth batch on
+        // they settle, and the window is below 2% once it holds only those.
+        let means = [
+            100.0, 160.0, 100.0, 160.0, 100.0, 130.0, 130.0, 130.0, 130.0, 130.0,
+        ];
+        let timer = FakeTimer::default();
+        let mut batches = 0;
+        let statistics = target(0.02, 1000).measure(&timer, 1, |_| {
+            let mean = means[batches];
+            batches += 1;
+            timer.advance(mean);
+            (mean, 1)
+        });
+        assert_eq!(batches, 10);
+        assert_eq!(statistics.iterations, 10);
+    }
+
+    #[test]
+    fn unstable_batches_stop_at_max_ms() {
+        let timer = FakeTimer::default();
+        let mut batches = 0_u32;
+        let statistics = target(0.02, 1).measure(&timer, 1, |_| {
+            // 100 us and 200 us in turn never get stable.
+            let ns = if batches % 2 == 0 {
+                100_000.0
+            } else {
+                200_000.0
+            };
+            batches += 1;
+            timer.advance(ns);
+            (ns, 1)
+        });
+        // 100 + 200 + 100 + 200 + 100 + 200 + 100 us reach the 1 ms ceiling.
+        assert_eq!(batches, 7);
+        assert_eq!(statistics.iterations, 7);
+        assert_eq!(timer.now(), 1e6);
+    }
+
+    #[test]
+    fn max_ms_is_checked_after_each_batch() {
+        let timer = FakeTimer::default();
+        let mut batches = 0;
+        let statistics = target(0.0, 1).measure(&timer, 4, |batch| {
+            batches += 1;
+            timer.advance(batch.len() as f64 * 2e6);
+            (batch.len() as f64 * 2e6, batch.len())
+        });
+        // A single batch overshoots the ceiling, and ends the run.
+        assert_eq!(batches, 1);
+        assert_eq!(statistics.iterations, 4);
+    }
+
+    #[test]
+    fn until_stable_runner_measures_whole_batches() {
+        let timer = FakeTimer::default();
+        let runner = BenchRunner::run_until_stable(3, 10, 1000);
+        let mut calls = 0;
+        let result = run(&runner, &timer, || {
+            calls += 1;
+            timer.advance(1000.0);
+        });
+        assert_eq!(calls, 3 + 10 * STABLE_WINDOW);
+        assert_eq!(result.statistics.iterations, 10 * STABLE_WINDOW);
+        assert_eq!(result.statistics.mean_ns, 1000.0);
+    }
 }
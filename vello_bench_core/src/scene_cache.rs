@@ -0,0 +1,108 @@
+//! Deserialized archive scenes kept between runs of the same benchmark.
+//!
+//! Turning a [`SceneArchive`](anyrender_serialize::SceneArchive) into an
+//! [`anyrender::Scene`] decodes its images and registers them with a
+//! backend's render context, so the scene is only valid together with that
+//! context. Scene renderers take both from this cache when they are created
+//! and put them back once a benchmark is done (e.g.
+//! [`CpuSceneRenderer::into_cache`](crate::benchmarks::scene_cpu::CpuSceneRenderer::into_cache)),
+//! so running a scene again, as the web UI does when repeating a benchmark,
+//! skips the image decoding. Vello Hybrid's context uploads the images to
+//! the renderer it paints with, so
+//! [`HybridSceneRenderer`](crate::benchmarks::scene_hybrid::HybridSceneRenderer)
+//! keeps that renderer in its context entry too, and reuses it only on the
+//! same wgpu device.
+//!
+//! Entries are keyed by scene name and context type, i.e. backend. A context
+//! only holds the scene's resources, which don't change while rendering, so
+//! a reused one renders the same as a fresh one; see the `scene_cpu` module
+//! docs for what persists across frames. The cache is per thread, as the
+//! contexts aren't necessarily `Send`, and grows with every scene run, so
+//! memory-sensitive callers should [`clear_scene_cache`] between runs.
+
+use std::any::{Any, TypeId};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+/// A render context of type `C` with a scene deserialized into it.
+type Entry<C> = (C, anyrender::Scene);
+
+thread_local! {
+    static SCENES: RefCell<HashMap<(String, TypeId), Box<dyn Any>>> =
+        RefCell::new(HashMap::new());
+    /// Scenes deserialized by [`take_or_deserialize`], see
+    /// [`deserialized_scene_count`].
+    static DESERIALIZED: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Take the context and scene of `name` for context type `C` out of the
+/// cache, or create them with `deserialize` if there are none.
+pub(crate) fn take_or_deserialize<C: 'static>(
+    name: &str,
+    deserialize: impl FnOnce() -> Entry<C>,
+) -> Entry<C> {
+    let cached =
+        SCENES.with_borrow_mut(|scenes| scenes.remove(&(name.to_string(), TypeId::of::<C>())));
+    match cached.and_then(|entry| entry.downcast::<Entry<C>>().ok()) {
+        Some(entry) => *entry,
+        None => {
+            DESERIALIZED.set(DESERIALIZED.get() + 1);
+            deserialize()
+        }
+    }
+}
+
+/// Put the context and scene of `name` back for the next
+/// [`take_or_deserialize`].
+pub(crate) fn put_back<C: 'static>(name: &str, ctx: C, scene: anyrender::Scene) {
+    SCENES.with_borrow_mut(|scenes| {
+        scenes.insert(
+            (name.to_string(), TypeId::of::<C>()),
+            Box::new((ctx, scene)),
+        );
+    });
+}
+
+/// Drop all scenes cached on this thread, with their decoded images. The
+/// next run of each scene deserializes it again.
+pub fn clear_scene_cache() {
+    SCENES.with_borrow_mut(HashMap::clear);
+}
+
+/// Number of scenes cached on this thread, counting each backend
+/// separately.
+pub fn cached_scene_count() -> usize {
+    SCENES.with_borrow(HashMap::len)
+}
+
+/// Number of times a renderer on this thread found no cached scene and
+/// deserialized it, e.g. to check that repeated runs reuse their scene.
+/// Renderers that bypass the cache aren't counted.
+pub fn deserialized_scene_count() -> usize {
+    DESERIALIZED.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scenes_put_back_are_not_deserialized_again() {
+        let before = deserialized_scene_count();
+        for _ in 0..3 {
+            let (ctx, scene) =
+                take_or_deserialize("cache_test", || (7_u32, anyrender::Scene::new()));
+            assert_eq!(ctx, 7);
+            put_back("cache_test", ctx, scene);
+        }
+        assert_eq!(deserialized_scene_count() - before, 1);
+
+        // Each context type is cached on its own.
+        let _ = take_or_deserialize("cache_test", || ((), anyrender::Scene::new()));
+        assert_eq!(deserialized_scene_count() - before, 2);
+
+        clear_scene_cache();
+        let _ = take_or_deserialize("cache_test", || (7_u32, anyrender::Scene::new()));
+        assert_eq!(deserialized_scene_count() - before, 3);
+    }
+}
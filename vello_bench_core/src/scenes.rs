@@ -4,10 +4,27 @@
 //! by the build script, which generates `include_bytes!` entries for each
 //! `.anyrender.zip` file. At runtime, scenes are lazily deserialized from the
 //! embedded ZIP data using `anyrender_serialize`.
+//!
+//! The format version in each archive's `resources.json` is checked before
+//! deserializing, so a scene exported with a different `anyrender_serialize`
+//! fails with a message naming the versions (see [`scene_load_errors`])
+//! rather than an opaque parse error.
+//!
+//! Code that lists or runs archive scenes takes them from a [`SceneSource`].
+//! The benchmarks use [`EmbeddedScenes`]; [`TestSceneSource`] serves tiny
+//! scenes built in code, so the registry and runners can be exercised without
+//! the embedded archives.
 
 use std::io::Cursor;
+use std::ops::RangeInclusive;
 use std::sync::OnceLock;
 
+use anyrender::PaintScene;
+use serde::Serialize;
+use vello_common::kurbo::{Affine, Circle, Rect};
+use vello_common::peniko::color::palette;
+use vello_common::peniko::{BlendMode, Fill};
+
 // Include the auto-generated scene list from the build script.
 include!(concat!(env!("OUT_DIR"), "/scene_list.rs"));
 
@@ -28,41 +45,366 @@ pub struct SceneItem {
     pub height: u16,
 }
 
-static SCENES: OnceLock<Vec<SceneItem>> = OnceLock::new();
+/// Archive format versions this build can load: the `version` field of an
+/// archive's `resources.json`, as written by `anyrender_serialize`. Update
+/// together with the `anyrender_serialize` dependency.
+pub const SUPPORTED_ARCHIVE_VERSIONS: RangeInclusive<u32> = 1..=1;
 
-/// Get the list of all loaded scenes (lazily deserialized on first access).
-pub fn get_scenes() -> &'static [SceneItem] {
+/// Version assumed for archives whose `resources.json` has no `version`
+/// field, which predate it.
+const UNVERSIONED_ARCHIVE: u32 = 0;
+
+/// The manifest entry of every scene archive.
+const RESOURCES_ENTRY: &str = "resources.json";
+
+/// An embedded scene that failed to load.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SceneLoadError {
+    /// Name of the scene.
+    pub name: String,
+    /// Format version of the archive, if it could be read.
+    pub version: Option<u32>,
+    /// What went wrong, naming the scene.
+    pub message: String,
+}
+
+struct LoadedScenes {
+    items: Vec<SceneItem>,
+    errors: Vec<SceneLoadError>,
+}
+
+static SCENES: OnceLock<LoadedScenes> = OnceLock::new();
+
+fn loaded_scenes() -> &'static LoadedScenes {
     SCENES.get_or_init(|| {
-        SCENE_FILES
-            .iter()
-            .filter_map(|(name, zip_bytes)| {
-                match load_archive_from_zip(zip_bytes) {
-                    Ok(archive) => Some(SceneItem {
-                        name: (*name).to_string(),
-                        archive,
-                        width: DEFAULT_SCENE_WIDTH,
-                        height: DEFAULT_SCENE_HEIGHT,
-                    }),
-                    Err(e) => {
-                        // Log but don't panic — allow other scenes to load.
-                        #[cfg(target_arch = "wasm32")]
-                        web_sys::console::error_1(
-                            &format!("Failed to load scene '{name}': {e}").into(),
-                        );
-                        #[cfg(not(target_arch = "wasm32"))]
-                        eprintln!("Failed to load scene '{name}': {e}");
-                        None
-                    }
+        let mut items = Vec::new();
+        let mut errors = Vec::new();
+        for (name, zip_bytes) in SCENE_FILES {
+            match load_archive_from_zip(name, zip_bytes) {
+                Ok(archive) => items.push(SceneItem {
+                    name: (*name).to_string(),
+                    archive,
+                    width: DEFAULT_SCENE_WIDTH,
+                    height: DEFAULT_SCENE_HEIGHT,
+                }),
+                Err(error) => {
+                    // Log but don't panic — allow other scenes to load.
+                    #[cfg(target_arch = "wasm32")]
+                    web_sys::console::error_1(&error.message.as_str().into());
+                    #[cfg(not(target_arch = "wasm32"))]
+                    eprintln!("{}", error.message);
+                    errors.push(error);
                 }
-            })
-            .collect()
+            }
+        }
+        LoadedScenes { items, errors }
     })
 }
 
-/// Parse a scene archive from ZIP bytes.
+/// Get the list of all loaded scenes (lazily deserialized on first access).
+/// Scenes that fail to load are left out, see [`scene_load_errors`].
+pub fn get_scenes() -> &'static [SceneItem] {
+    &loaded_scenes().items
+}
+
+/// The embedded scenes that failed to load, in listing order. Empty when
+/// all of them loaded.
+pub fn scene_load_errors() -> &'static [SceneLoadError] {
+    &loaded_scenes().errors
+}
+
+/// Read the format version of a scene archive from its `resources.json`,
+/// without deserializing the scene.
+///
+/// Archives whose manifest has no `version` field predate it and are
+/// reported as version 0.
+pub fn archive_version(zip_bytes: &[u8]) -> Result<u32, String> {
+    let mut zip = zip::ZipArchive::new(Cursor::new(zip_bytes))
+        .map_err(|e| format!("not a ZIP archive: {e}"))?;
+    let resources = zip.by_name(RESOURCES_ENTRY).map_err(|e| match e {
+        zip::result::ZipError::FileNotFound => {
+            format!("no {RESOURCES_ENTRY}; not a scene archive")
+        }
+        e => format!("unreadable {RESOURCES_ENTRY}: {e}"),
+    })?;
+    let manifest: serde_json::Value = serde_json::from_reader(resources)
+        .map_err(|e| format!("invalid {RESOURCES_ENTRY}: {e}"))?;
+
+    match manifest.get("version") {
+        None => Ok(UNVERSIONED_ARCHIVE),
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| format!("invalid version {version} in {RESOURCES_ENTRY}")),
+    }
+}
+
+/// Check that archive format `version` is one this build can load. The
+/// error names the scene, the version and the supported range.
+pub fn check_archive_version(name: &str, version: u32) -> Result<(), String> {
+    if SUPPORTED_ARCHIVE_VERSIONS.contains(&version) {
+        return Ok(());
+    }
+    let (min, max) = (
+        SUPPORTED_ARCHIVE_VERSIONS.start(),
+        SUPPORTED_ARCHIVE_VERSIONS.end(),
+    );
+    let supported = if min == max {
+        format!("only version {min}")
+    } else {
+        format!("versions {min} to {max}")
+    };
+    let advice = if version > *max {
+        "it was written by a newer anyrender_serialize; update the dependency"
+    } else {
+        "it was written by an older anyrender_serialize; re-export the scene"
+    };
+    Err(format!(
+        "Scene '{name}' has archive format version {version}, but this build \
+         supports {supported}: {advice}"
+    ))
+}
+
+/// Parse the scene archive `name` from ZIP bytes, checking its format
+/// version first.
 fn load_archive_from_zip(
+    name: &str,
     zip_bytes: &[u8],
-) -> Result<anyrender_serialize::SceneArchive, Box<dyn std::error::Error>> {
-    let cursor = Cursor::new(zip_bytes);
-    Ok(anyrender_serialize::SceneArchive::deserialize(cursor)?)
+) -> Result<anyrender_serialize::SceneArchive, SceneLoadError> {
+    let error = |version, message| SceneLoadError {
+        name: name.to_string(),
+        version,
+        message,
+    };
+
+    let version = archive_version(zip_bytes)
+        .map_err(|e| error(None, format!("Failed to load scene '{name}': {e}")))?;
+    check_archive_version(name, version).map_err(|message| error(Some(version), message))?;
+
+    anyrender_serialize::SceneArchive::deserialize(Cursor::new(zip_bytes)).map_err(|e| {
+        error(
+            Some(version),
+            format!("Failed to load scene '{name}' (archive format version {version}): {e}"),
+        )
+    })
+}
+
+/// A set of archive scenes to list and run.
+pub trait SceneSource {
+    /// Names of all scenes, in listing order.
+    fn names(&self) -> Vec<&str>;
+
+    /// The scene called `name`.
+    fn get(&self, name: &str) -> Option<&SceneItem>;
+}
+
+/// The scenes embedded at build time, see [`get_scenes`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmbeddedScenes;
+
+impl SceneSource for EmbeddedScenes {
+    fn names(&self) -> Vec<&str> {
+        get_scenes().iter().map(|item| item.name.as_str()).collect()
+    }
+
+    fn get(&self, name: &str) -> Option<&SceneItem> {
+        get_scenes().iter().find(|item| item.name == name)
+    }
+}
+
+/// In-memory scenes built in code.
+///
+/// [`TestSceneSource::new`] serves two tiny scenes, `test_rect` and
+/// `test_layer`, that render in microseconds on any backend; add others with
+/// [`TestSceneSource::with_scene`].
+pub struct TestSceneSource {
+    items: Vec<SceneItem>,
+}
+
+impl TestSceneSource {
+    /// Size of the built-in test scenes.
+    pub const SIZE: u16 = 64;
+
+    /// A source with the built-in test scenes.
+    pub fn new() -> Self {
+        let size = f64::from(Self::SIZE);
+        Self::empty()
+            .with_scene("test_rect", Self::SIZE, Self::SIZE, |scene| {
+                let rect = Rect::new(8.0, 8.0, size - 8.0, size - 8.0);
+                scene.fill(
+                    Fill::NonZero,
+                    Affine::IDENTITY,
+                    palette::css::CRIMSON,
+                    None,
+                    &rect,
+                );
+            })
+            .with_scene("test_layer", Self::SIZE, Self::SIZE, |scene| {
+                let half = size / 2.0;
+                let clip = Circle::new((half, half), half * 0.75);
+                scene.push_layer(BlendMode::default(), 0.5, Affine::IDENTITY, &clip);
+                for (i, color) in [palette::css::STEEL_BLUE, palette::css::GOLDENROD]
+                    .into_iter()
+                    .enumerate()
+                {
+                    let offset = i as f64 * half / 2.0;
+                    let rect = Rect::new(offset, offset, offset + half * 1.5, offset + half * 1.5);
+                    scene.fill(Fill::NonZero, Affine::IDENTITY, color, None, &rect);
+                }
+                scene.pop_layer();
+            })
+    }
+
+    /// A source without any scenes.
+    pub fn empty() -> Self {
+        Self { items: Vec::new() }
+    }
+
+    /// Add a `width`×`height` scene called `name`, painted by `paint`. The
+    /// scene goes through an archive like the embedded ones.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the painted scene can't be archived.
+    pub fn with_scene(
+        mut self,
+        name: &str,
+        width: u16,
+        height: u16,
+        paint: impl FnOnce(&mut anyrender::Scene),
+    ) -> Self {
+        let mut scene = anyrender::Scene::new();
+        paint(&mut scene);
+        let archive = anyrender_serialize::SceneArchive::from_scene(
+            &scene,
+            &anyrender_serialize::SerializeConfig::default(),
+        )
+        .unwrap_or_else(|e| panic!("Failed to archive test scene '{name}': {e}"));
+
+        self.items.push(SceneItem {
+            name: name.to_string(),
+            archive,
+            width,
+            height,
+        });
+        self
+    }
+}
+
+impl Default for TestSceneSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SceneSource for TestSceneSource {
+    fn names(&self) -> Vec<&str> {
+        self.items.iter().map(|item| item.name.as_str()).collect()
+    }
+
+    fn get(&self, name: &str) -> Option<&SceneItem> {
+        self.items.iter().find(|item| item.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// A ZIP archive of `entries`, as (name, content) pairs.
+    fn zip_of(entries: &[(&str, &str)]) -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        for (name, content) in entries {
+            zip.start_file(*name, options).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    /// A scene archive whose manifest is `resources`.
+    fn archive(resources: &str) -> Vec<u8> {
+        zip_of(&[(RESOURCES_ENTRY, resources), ("draw_commands.json", "[]")])
+    }
+
+    #[test]
+    fn reads_the_manifest_version() {
+        let current = archive(r#"{ "version": 1, "images": [] }"#);
+        assert_eq!(archive_version(&current), Ok(1));
+        let newer = archive(r#"{ "version": 2 }"#);
+        assert_eq!(archive_version(&newer), Ok(2));
+        let unversioned = archive(r#"{ "images": [] }"#);
+        assert_eq!(archive_version(&unversioned), Ok(UNVERSIONED_ARCHIVE));
+    }
+
+    #[test]
+    fn rejects_unreadable_archives() {
+        let error = archive_version(b"not a zip").unwrap_err();
+        assert!(error.starts_with("not a ZIP archive: "), "{error}");
+
+        let no_manifest = zip_of(&[("draw_commands.json", "[]")]);
+        assert_eq!(
+            archive_version(&no_manifest),
+            Err("no resources.json; not a scene archive".to_string())
+        );
+
+        let error = archive_version(&archive("{ version: 1 }")).unwrap_err();
+        assert!(error.starts_with("invalid resources.json: "), "{error}");
+
+        for version in ["\"1\"", "-1", "1.5", "4294967296"] {
+            let doctored = archive(&format!(r#"{{ "version": {version} }}"#));
+            assert_eq!(
+                archive_version(&doctored),
+                Err(format!("invalid version {version} in resources.json"))
+            );
+        }
+    }
+
+    #[test]
+    fn names_the_remedy_for_unsupported_versions() {
+        assert_eq!(check_archive_version("tiger", 1), Ok(()));
+        assert_eq!(
+            check_archive_version("tiger", 2),
+            Err(
+                "Scene 'tiger' has archive format version 2, but this build supports \
+                 only version 1: it was written by a newer anyrender_serialize; update \
+                 the dependency"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            check_archive_version("tiger", 0),
+            Err(
+                "Scene 'tiger' has archive format version 0, but this build supports \
+                 only version 1: it was written by an older anyrender_serialize; \
+                 re-export the scene"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn doctored_archives_fail_to_load_with_their_version() {
+        let error = load_archive_from_zip("tiger", &archive(r#"{ "version": 7 }"#))
+            .err()
+            .unwrap();
+        assert_eq!(error.name, "tiger");
+        assert_eq!(error.version, Some(7));
+        assert_eq!(
+            error.message,
+            check_archive_version("tiger", 7).unwrap_err()
+        );
+
+        let error = load_archive_from_zip("tiger", b"garbage").err().unwrap();
+        assert_eq!(error.version, None);
+        assert!(
+            error
+                .message
+                .starts_with("Failed to load scene 'tiger': not a ZIP archive"),
+            "{}",
+            error.message
+        );
+    }
 }
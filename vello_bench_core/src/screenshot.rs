@@ -3,13 +3,78 @@
 //! Each function delegates to the corresponding renderer struct from the
 //! benchmark modules, ensuring that screenshots use the exact same codepath
 //! as the benchmarks.
+//!
+//! The render functions allocate a fresh pixmap and output buffer per call.
+//! Code rendering many screenshots in a row uses their `*_in` variants with a
+//! [`PixmapPool`] instead, which reuses both.
 
 use crate::benchmarks::scene_cpu::CpuSceneRenderer;
-use crate::renderer::Renderer;
+use crate::benchmarks::vello_cpu::{STRIDE_PADDING_BYTES, split_render_mode};
+use crate::renderer::{Renderer, strided_buffer_len};
 use crate::scenes::get_scenes;
-use crate::vello_scenes::{draw_scene, get_vello_scenes, setup_scene};
+use crate::vello_scenes::find_scene;
 use fearless_simd::Level;
-use vello_cpu::RenderMode;
+use std::borrow::Cow;
+use vello_common::peniko::Color;
+use vello_common::peniko::color::PremulRgba8;
+use vello_cpu::{Pixmap, RenderMode};
+
+/// How the color channels of a [`ScreenshotResult`] relate to its alpha.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AlphaMode {
+    /// Color channels are independent of alpha (what PNG and `ImageData` expect).
+    #[default]
+    Straight,
+    /// Color channels are already multiplied by alpha (what GPUs render).
+    Premultiplied,
+}
+
+/// The two colors of the checkerboard matte, light first, as image editors
+/// draw it behind transparent areas.
+pub const CHECKERBOARD_COLORS: [[u8; 3]; 2] = [[0xff; 3], [0xcc; 3]];
+
+/// Checkerboard cell size in pixels used where none is given.
+pub const DEFAULT_CHECKERBOARD_CELL: u32 = 8;
+
+/// An opaque background to composite a screenshot over, so that partially
+/// transparent scenes look the same in every viewer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Matte {
+    /// [`CHECKERBOARD_COLORS`] in square cells of `cell` pixels, light in the
+    /// top-left corner.
+    Checkerboard { cell: u32 },
+    /// A solid color; its alpha is ignored.
+    Color(Color),
+}
+
+impl Matte {
+    /// Parse `checkerboard`, `checkerboard:CELL` or a `#rrggbb` color.
+    pub fn from_spec(spec: &str) -> Result<Self, String> {
+        let invalid = || {
+            format!("Invalid matte '{spec}', expected checkerboard, checkerboard:CELL or #rrggbb")
+        };
+        if spec == "checkerboard" {
+            return Ok(Self::Checkerboard {
+                cell: DEFAULT_CHECKERBOARD_CELL,
+            });
+        }
+        if let Some(cell) = spec.strip_prefix("checkerboard:") {
+            let cell = cell.parse().map_err(|_| invalid())?;
+            if cell == 0 {
+                return Err(invalid());
+            }
+            return Ok(Self::Checkerboard { cell });
+        }
+
+        let hex = spec.strip_prefix('#').ok_or_else(invalid)?;
+        if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let rgb = u32::from_str_radix(hex, 16).map_err(|_| invalid())?;
+        let [_, r, g, b] = rgb.to_be_bytes();
+        Ok(Self::Color(Color::from_rgb8(r, g, b)))
+    }
+}
 
 /// The result of rendering a scene screenshot.
 pub struct ScreenshotResult {
@@ -17,24 +82,268 @@ pub struct ScreenshotResult {
     pub width: u32,
     /// Height in pixels.
     pub height: u32,
-    /// Non-premultiplied RGBA8 pixel data, row-major order (4 bytes per pixel).
+    /// RGBA8 pixel data, row-major order (4 bytes per pixel).
     pub rgba: Vec<u8>,
+    /// Alpha convention of `rgba`.
+    pub alpha_mode: AlphaMode,
+}
+
+impl ScreenshotResult {
+    /// Pixel data with straight alpha, converting if necessary.
+    pub fn straight_rgba(&self) -> Cow<'_, [u8]> {
+        match self.alpha_mode {
+            AlphaMode::Straight => Cow::Borrowed(&self.rgba),
+            AlphaMode::Premultiplied => Cow::Owned(
+                self.rgba
+                    .chunks_exact(4)
+                    .flat_map(|p| {
+                        let a = p[3];
+                        [
+                            unpremultiply(p[0], a),
+                            unpremultiply(p[1], a),
+                            unpremultiply(p[2], a),
+                            a,
+                        ]
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Convert to straight alpha.
+    pub fn into_straight(mut self) -> Self {
+        if self.alpha_mode == AlphaMode::Premultiplied {
+            self.rgba = self.straight_rgba().into_owned();
+            self.alpha_mode = AlphaMode::Straight;
+        }
+        self
+    }
+
+    /// Composite over an opaque `background` color and return RGB8 pixel
+    /// data (3 bytes per pixel).
+    ///
+    /// This is where the alpha conventions of different backends meet: two
+    /// screenshots of the same content flatten to the same pixels regardless
+    /// of their [`AlphaMode`].
+    pub fn flatten(&self, background: [u8; 3]) -> Vec<u8> {
+        let premultiplied = self.alpha_mode == AlphaMode::Premultiplied;
+        self.rgba
+            .chunks_exact(4)
+            .flat_map(|p| {
+                [
+                    over(p[0], p[3], background[0], premultiplied),
+                    over(p[1], p[3], background[1], premultiplied),
+                    over(p[2], p[3], background[2], premultiplied),
+                ]
+            })
+            .collect()
+    }
+
+    /// Composite over a checkerboard of [`CHECKERBOARD_COLORS`] with square
+    /// cells of `cell` pixels (at least 1), returning an opaque screenshot.
+    /// Opaque pixels are unchanged.
+    pub fn composite_over_checkerboard(&self, cell: u32) -> Self {
+        let cell = cell.max(1);
+        self.composite_with(|x, y| CHECKERBOARD_COLORS[((x / cell + y / cell) % 2) as usize])
+    }
+
+    /// Composite over an opaque `color`, ignoring its alpha, returning an
+    /// opaque screenshot. Opaque pixels are unchanged.
+    pub fn composite_over_color(&self, color: Color) -> Self {
+        let rgba = color.to_rgba8();
+        self.composite_with(|_, _| [rgba.r, rgba.g, rgba.b])
+    }
+
+    /// Composite over `matte`, see [`Self::composite_over_checkerboard`] and
+    /// [`Self::composite_over_color`].
+    pub fn composite_over(&self, matte: Matte) -> Self {
+        match matte {
+            Matte::Checkerboard { cell } => self.composite_over_checkerboard(cell),
+            Matte::Color(color) => self.composite_over_color(color),
+        }
+    }
+
+    /// Composite over the opaque background color `background(x, y)`.
+    fn composite_with(&self, background: impl Fn(u32, u32) -> [u8; 3]) -> Self {
+        let premultiplied = self.alpha_mode == AlphaMode::Premultiplied;
+        let width = self.width.max(1);
+        let rgba = self
+            .rgba
+            .chunks_exact(4)
+            .enumerate()
+            .flat_map(|(i, p)| {
+                let i = i as u32;
+                let bg = background(i % width, i / width);
+                [
+                    over(p[0], p[3], bg[0], premultiplied),
+                    over(p[1], p[3], bg[1], premultiplied),
+                    over(p[2], p[3], bg[2], premultiplied),
+                    255,
+                ]
+            })
+            .collect();
+
+        Self {
+            width: self.width,
+            height: self.height,
+            rgba,
+            // Opaque, so straight and premultiplied coincide.
+            alpha_mode: AlphaMode::Straight,
+        }
+    }
+}
+
+/// Source-over of color channel `c` with alpha `a` onto opaque `bg`, scaled by
+/// 255 to stay in integers.
+fn over(c: u8, a: u8, bg: u8, premultiplied: bool) -> u8 {
+    let a = u32::from(a);
+    let src = if premultiplied {
+        u32::from(c) * 255
+    } else {
+        u32::from(c) * a
+    };
+    ((src + u32::from(bg) * (255 - a) + 127) / 255).min(255) as u8
+}
+
+/// Undo premultiplication of one color channel, rounding to nearest.
+fn unpremultiply(c: u8, a: u8) -> u8 {
+    if a == 0 {
+        0
+    } else {
+        ((u32::from(c) * 255 + u32::from(a) / 2) / u32::from(a)).min(255) as u8
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Buffer reuse
+// ---------------------------------------------------------------------------
+
+/// Pixmaps and pixel buffers kept for reuse across screenshots, so that
+/// rendering many scenes in a row doesn't allocate a multi-megabyte pixmap
+/// and output buffer per scene (and on WASM, doesn't grow memory that is
+/// never returned).
+///
+/// Pass it to the `*_in` render functions, and hand each screenshot back with
+/// [`Self::recycle`] once done with it. At most [`Self::CAPACITY`] pixmaps,
+/// matched by size, and as many buffers are kept.
+#[derive(Default)]
+pub struct PixmapPool {
+    /// Least recently returned first.
+    pixmaps: Vec<Pixmap>,
+    /// Least recently returned first.
+    buffers: Vec<Vec<u8>>,
+}
+
+impl PixmapPool {
+    /// Maximum number of pixmaps, and of buffers, kept.
+    pub const CAPACITY: usize = 4;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A transparent `width`×`height` pixmap, reusing a pooled one of that
+    /// size if there is one.
+    pub fn take_pixmap(&mut self, width: u16, height: u16) -> Pixmap {
+        let pooled = self
+            .pixmaps
+            .iter()
+            .rposition(|p| p.width() == width && p.height() == height);
+        match pooled {
+            Some(i) => {
+                let mut pixmap = self.pixmaps.remove(i);
+                pixmap.data_mut().fill(PremulRgba8 {
+                    r: 0,
+                    g: 0,
+                    b: 0,
+                    a: 0,
+                });
+                pixmap
+            }
+            None => Pixmap::new(width, height),
+        }
+    }
+
+    /// Keep `pixmap` for reuse, dropping the least recently returned one if
+    /// the pool is full.
+    pub fn put_pixmap(&mut self, pixmap: Pixmap) {
+        if self.pixmaps.len() == Self::CAPACITY {
+            self.pixmaps.remove(0);
+        }
+        self.pixmaps.push(pixmap);
+    }
+
+    /// An empty buffer, with the capacity of the most recently recycled one
+    /// if there is one.
+    pub fn take_buffer(&mut self) -> Vec<u8> {
+        let mut buffer = self.buffers.pop().unwrap_or_default();
+        buffer.clear();
+        buffer
+    }
+
+    /// Keep the pixel buffer of a screenshot that is no longer needed,
+    /// dropping the least recently returned one if the pool is full.
+    pub fn recycle(&mut self, shot: ScreenshotResult) {
+        if self.buffers.len() == Self::CAPACITY {
+            self.buffers.remove(0);
+        }
+        self.buffers.push(shot.rgba);
+    }
+}
+
+/// Write the pixels of `pixmap` to `out` as unpremultiplied RGBA8, replacing
+/// its content, and return the pixmap's allocation as a pixmap of the same
+/// size for reuse. The returned pixels are unspecified.
+pub(crate) fn unpremultiplied_rgba_into(pixmap: Pixmap, out: &mut Vec<u8>) -> Pixmap {
+    let (width, height) = (pixmap.width(), pixmap.height());
+    let pixels = pixmap.take_unpremultiplied();
+    out.clear();
+    out.extend(pixels.iter().flat_map(|p| [p.r, p.g, p.b, p.a]));
+
+    // Same layout, so this reuses the allocation.
+    let data = pixels
+        .into_iter()
+        .map(|p| PremulRgba8 {
+            r: p.r,
+            g: p.g,
+            b: p.b,
+            a: p.a,
+        })
+        .collect();
+    Pixmap::from_parts(data, width, height)
 }
 
+// ---------------------------------------------------------------------------
+// Archive scenes
+// ---------------------------------------------------------------------------
+
 /// Render a scene by name using the Vello CPU renderer and return the pixel data.
 ///
 /// `level` selects the SIMD instruction set; use `Level::new()` for auto-detect.
 pub fn render_scene_cpu(scene_name: &str, level: Level) -> Option<ScreenshotResult> {
+    render_scene_cpu_in(scene_name, level, &mut PixmapPool::new())
+}
+
+/// [`render_scene_cpu`] with the pixmap and output buffer taken from `pool`.
+pub fn render_scene_cpu_in(
+    scene_name: &str,
+    level: Level,
+    pool: &mut PixmapPool,
+) -> Option<ScreenshotResult> {
     let scenes = get_scenes();
     let item = scenes.iter().find(|s| s.name == scene_name)?;
 
-    let mut renderer = CpuSceneRenderer::new(item, level);
+    let pixmap = pool.take_pixmap(item.width, item.height);
+    let mut renderer = CpuSceneRenderer::with_pixmap(item, level, pixmap);
     renderer.render_frame();
+    let mut rgba = pool.take_buffer();
+    pool.put_pixmap(renderer.into_rgba_into(&mut rgba));
 
     Some(ScreenshotResult {
         width: item.width as u32,
         height: item.height as u32,
-        rgba: renderer.into_rgba(),
+        rgba,
+        alpha_mode: CpuSceneRenderer::ALPHA_MODE,
     })
 }
 
@@ -44,6 +353,11 @@ pub fn render_scene_cpu(scene_name: &str, level: Level) -> Option<ScreenshotResu
 /// On WASM this returns `None` — hybrid screenshots are handled by
 /// `vello_bench_wasm` via WebGL canvas.
 pub fn render_scene_hybrid(scene_name: &str) -> Option<ScreenshotResult> {
+    render_scene_hybrid_in(scene_name, &mut PixmapPool::new())
+}
+
+/// [`render_scene_hybrid`] with the output buffer taken from `pool`.
+pub fn render_scene_hybrid_in(scene_name: &str, pool: &mut PixmapPool) -> Option<ScreenshotResult> {
     #[cfg(not(target_arch = "wasm32"))]
     {
         use crate::benchmarks::scene_hybrid::HybridSceneRenderer;
@@ -52,16 +366,19 @@ pub fn render_scene_hybrid(scene_name: &str) -> Option<ScreenshotResult> {
         let item = scenes.iter().find(|s| s.name == scene_name)?;
 
         let renderer = HybridSceneRenderer::new(item);
+        let mut rgba = pool.take_buffer();
+        renderer.into_rgba_into(&mut rgba);
 
         Some(ScreenshotResult {
             width: item.width as u32,
             height: item.height as u32,
-            rgba: renderer.into_rgba(),
+            rgba,
+            alpha_mode: HybridSceneRenderer::ALPHA_MODE,
         })
     }
     #[cfg(target_arch = "wasm32")]
     {
-        let _ = scene_name;
+        let _ = (scene_name, pool);
         None
     }
 }
@@ -70,6 +387,11 @@ pub fn render_scene_hybrid(scene_name: &str) -> Option<ScreenshotResult> {
 ///
 /// On WASM this returns `None` — Skia is not available on the WASM target.
 pub fn render_scene_skia(scene_name: &str) -> Option<ScreenshotResult> {
+    render_scene_skia_in(scene_name, &mut PixmapPool::new())
+}
+
+/// [`render_scene_skia`] with the output buffer taken from `pool`.
+pub fn render_scene_skia_in(scene_name: &str, pool: &mut PixmapPool) -> Option<ScreenshotResult> {
     #[cfg(not(target_arch = "wasm32"))]
     {
         use crate::benchmarks::scene_skia::SkiaSceneRenderer;
@@ -77,18 +399,19 @@ pub fn render_scene_skia(scene_name: &str) -> Option<ScreenshotResult> {
         let scenes = get_scenes();
         let item = scenes.iter().find(|s| s.name == scene_name)?;
 
-        let mut renderer = SkiaSceneRenderer::new(item);
+        let mut renderer = SkiaSceneRenderer::with_buffer(item, pool.take_buffer());
         renderer.render_frame();
 
         Some(ScreenshotResult {
             width: item.width as u32,
             height: item.height as u32,
             rgba: renderer.into_rgba(),
+            alpha_mode: SkiaSceneRenderer::ALPHA_MODE,
         })
     }
     #[cfg(target_arch = "wasm32")]
     {
-        let _ = scene_name;
+        let _ = (scene_name, pool);
         None
     }
 }
@@ -98,67 +421,878 @@ pub fn render_scene_skia(scene_name: &str) -> Option<ScreenshotResult> {
 // ---------------------------------------------------------------------------
 
 /// Render a programmatic vello scene using the CPU backend.
+///
+/// A `_speed` / `_quality` suffix on `scene_name` selects the render mode, as
+/// for the `vello_cpu` benchmark IDs.
 pub fn render_vello_scene_cpu(scene_name: &str, level: Level) -> Option<ScreenshotResult> {
-    let scenes = get_vello_scenes();
-    let info = scenes.iter().find(|s| s.name == scene_name)?;
+    render_vello_scene_cpu_in(scene_name, level, &mut PixmapPool::new())
+}
 
-    let mut ctx: vello_cpu::RenderContext =
-        Renderer::new(info.width, info.height, 0, level, RenderMode::default());
-    let mut pixmap = vello_cpu::Pixmap::new(info.width, info.height);
+/// [`render_vello_scene_cpu`] with the pixmap and output buffer taken from
+/// `pool`.
+pub fn render_vello_scene_cpu_in(
+    scene_name: &str,
+    level: Level,
+    pool: &mut PixmapPool,
+) -> Option<ScreenshotResult> {
+    let (scene_name, mode) = split_render_mode(scene_name);
+    render_vello_cpu(scene_name, level, mode.unwrap_or_default(), None, pool)
+}
+
+/// Render a programmatic vello scene using the CPU backend with an explicit
+/// render mode. Output differs between modes.
+pub fn render_vello_scene_cpu_with_mode(
+    scene_name: &str,
+    level: Level,
+    mode: RenderMode,
+) -> Option<ScreenshotResult> {
+    render_vello_scene_cpu_with_mode_in(scene_name, level, mode, &mut PixmapPool::new())
+}
+
+/// [`render_vello_scene_cpu_with_mode`] with the pixmap and output buffer
+/// taken from `pool`.
+pub fn render_vello_scene_cpu_with_mode_in(
+    scene_name: &str,
+    level: Level,
+    mode: RenderMode,
+    pool: &mut PixmapPool,
+) -> Option<ScreenshotResult> {
+    render_vello_cpu(scene_name, level, mode, None, pool)
+}
 
-    let state = setup_scene(scene_name, &mut ctx).expect("scene not found");
-    draw_scene(scene_name, state.as_ref(), &mut ctx);
+fn render_vello_cpu(
+    scene_name: &str,
+    level: Level,
+    mode: RenderMode,
+    aliasing_threshold: Option<u8>,
+    pool: &mut PixmapPool,
+) -> Option<ScreenshotResult> {
+    let scene = find_scene::<vello_cpu::RenderContext>(scene_name)?;
+    let info = &scene.info;
+
+    let mut ctx: vello_cpu::RenderContext = Renderer::new(info.width, info.height, 0, level, mode);
+    let mut pixmap = pool.take_pixmap(info.width, info.height);
+
+    let state = scene.setup(&mut ctx);
+    ctx.set_aliasing_threshold(aliasing_threshold);
+    scene.draw(state.as_ref(), &mut ctx);
     ctx.flush();
     ctx.render_to_pixmap(&mut pixmap);
 
-    let rgba = pixmap
-        .take_unpremultiplied()
-        .into_iter()
-        .flat_map(|p| [p.r, p.g, p.b, p.a])
-        .collect();
+    let mut rgba = pool.take_buffer();
+    pool.put_pixmap(unpremultiplied_rgba_into(pixmap, &mut rgba));
 
     Some(ScreenshotResult {
         width: info.width as u32,
         height: info.height as u32,
         rgba,
+        alpha_mode: AlphaMode::Straight,
     })
 }
 
 /// Render a programmatic vello scene using the Hybrid (wgpu) backend.
 ///
-/// On WASM this returns `None` — hybrid screenshots are handled by
-/// `vello_bench_wasm` via WebGL canvas.
+/// Returns `None` for scenes only Vello CPU can render. On WASM this returns
+/// `None` — hybrid screenshots are handled by `vello_bench_wasm` via WebGL
+/// canvas.
 pub fn render_vello_scene_hybrid(scene_name: &str) -> Option<ScreenshotResult> {
+    render_vello_scene_hybrid_in(scene_name, &mut PixmapPool::new())
+}
+
+/// [`render_vello_scene_hybrid`] with the pixmap and output buffer taken from
+/// `pool`.
+pub fn render_vello_scene_hybrid_in(
+    scene_name: &str,
+    pool: &mut PixmapPool,
+) -> Option<ScreenshotResult> {
     #[cfg(not(target_arch = "wasm32"))]
     {
         use crate::renderer::HybridRenderer;
 
-        let scenes = get_vello_scenes();
-        let info = scenes.iter().find(|s| s.name == scene_name)?;
+        let scene = find_scene::<HybridRenderer>(scene_name).filter(|s| !s.cpu_only)?;
+        let info = &scene.info;
 
         let mut hybrid: HybridRenderer =
             Renderer::new(info.width, info.height, 0, Level::new(), RenderMode::default());
-        let mut pixmap = vello_cpu::Pixmap::new(info.width, info.height);
+        let mut pixmap = pool.take_pixmap(info.width, info.height);
 
-        let state = setup_scene(scene_name, &mut hybrid).expect("scene not found");
-        draw_scene(scene_name, state.as_ref(), &mut hybrid);
+        let state = scene.setup(&mut hybrid);
+        scene.draw(state.as_ref(), &mut hybrid);
         hybrid.render_to_pixmap(&mut pixmap);
 
-        let rgba = pixmap
-            .take_unpremultiplied()
-            .into_iter()
-            .flat_map(|p| [p.r, p.g, p.b, p.a])
-            .collect();
+        let mut rgba = pool.take_buffer();
+        pool.put_pixmap(unpremultiplied_rgba_into(pixmap, &mut rgba));
 
         Some(ScreenshotResult {
             width: info.width as u32,
             height: info.height as u32,
             rgba,
+            alpha_mode: AlphaMode::Straight,
         })
     }
     #[cfg(target_arch = "wasm32")]
     {
-        let _ = scene_name;
+        let _ = (scene_name, pool);
         None
     }
 }
+
+// ---------------------------------------------------------------------------
+// Strided rendering
+// ---------------------------------------------------------------------------
+
+/// Byte written to every byte of the destination buffer before a strided
+/// render, so that padding written by mistake can be told apart.
+const STRIDE_SENTINEL: u8 = 0xa5;
+
+/// Copy the `width * 4` pixel bytes of each row out of a buffer whose rows
+/// start `stride_bytes` apart, dropping the padding between them.
+pub fn strip_row_padding(buf: &[u8], width: u32, height: u32, stride_bytes: usize) -> Vec<u8> {
+    let row_bytes = width as usize * 4;
+    (0..height as usize)
+        .flat_map(|row| &buf[row * stride_bytes..row * stride_bytes + row_bytes])
+        .copied()
+        .collect()
+}
+
+/// Render a vello scene on the CPU into a pixmap and, from the same flushed
+/// context, through [`Renderer::render_to_buffer`] with the stride of the
+/// `vello_cpu/*_strided` benchmarks.
+///
+/// Returns `None` for unknown scenes, otherwise a description of every
+/// problem found: pixels that differ once the padding is stripped, and
+/// padding bytes the render wrote to. An empty list means the renders match.
+pub fn check_strided_render(scene_name: &str, level: Level) -> Option<Vec<String>> {
+    let scene = find_scene::<vello_cpu::RenderContext>(scene_name)?;
+    let info = &scene.info;
+    let (width, height) = (u32::from(info.width), u32::from(info.height));
+
+    let mut ctx: vello_cpu::RenderContext =
+        Renderer::new(info.width, info.height, 0, level, RenderMode::default());
+    let state = scene.setup(&mut ctx);
+    scene.draw(state.as_ref(), &mut ctx);
+    ctx.flush();
+
+    let mut pixmap = vello_cpu::Pixmap::new(info.width, info.height);
+    ctx.render_to_pixmap(&mut pixmap);
+    let expected = pixmap.data_as_u8_slice();
+
+    let row_bytes = width as usize * 4;
+    let stride_bytes = row_bytes + STRIDE_PADDING_BYTES;
+    let mut buf = vec![STRIDE_SENTINEL; strided_buffer_len(info.width, info.height, stride_bytes)];
+    Renderer::render_to_buffer(&ctx, &mut buf, stride_bytes);
+
+    let mut problems = Vec::new();
+
+    let actual = strip_row_padding(&buf, width, height, stride_bytes);
+    let differing = expected
+        .chunks_exact(4)
+        .zip(actual.chunks_exact(4))
+        .enumerate()
+        .filter(|(_, (e, a))| e != a)
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    if let Some(&first) = differing.first() {
+        problems.push(format!(
+            "{} pixel(s) differ from the pixmap render, first at ({}, {})",
+            differing.len(),
+            first % width as usize,
+            first / width as usize
+        ));
+    }
+
+    let overwritten = (0..(height as usize).saturating_sub(1))
+        .flat_map(|row| &buf[row * stride_bytes + row_bytes..(row + 1) * stride_bytes])
+        .filter(|&&b| b != STRIDE_SENTINEL)
+        .count();
+    if overwritten > 0 {
+        problems.push(format!("{overwritten} padding byte(s) were overwritten"));
+    }
+
+    Some(problems)
+}
+
+// ---------------------------------------------------------------------------
+// Aliasing threshold comparison
+// ---------------------------------------------------------------------------
+
+/// Aliasing thresholds rendered by [`aliasing_report`]: fully antialiased, an
+/// intermediate threshold, and fully aliased.
+pub const ALIASING_THRESHOLDS: [Option<u8>; 3] = [None, Some(128), Some(255)];
+
+/// Coverage ranges used by [`AliasingReport::summary`].
+const COVERAGE_BUCKETS: [(u8, u8); 6] = [
+    (0, 0),
+    (1, 63),
+    (64, 127),
+    (128, 191),
+    (192, 254),
+    (255, 255),
+];
+
+/// Pixels that differ between renders at two aliasing thresholds.
+#[derive(Debug, Clone)]
+pub struct AliasingComparison {
+    /// Threshold of the first render.
+    pub from: Option<u8>,
+    /// Threshold of the second render.
+    pub to: Option<u8>,
+    /// `changed_by_coverage[c]` is the number of differing pixels whose
+    /// coverage in the antialiased render is `c`.
+    pub changed_by_coverage: [u64; 256],
+}
+
+impl AliasingComparison {
+    /// Total number of differing pixels.
+    pub fn changed(&self) -> u64 {
+        self.changed_by_coverage.iter().sum()
+    }
+
+    /// Number of differing pixels with coverage in `min..=max`.
+    pub fn changed_in(&self, min: u8, max: u8) -> u64 {
+        self.changed_by_coverage[usize::from(min)..=usize::from(max)]
+            .iter()
+            .sum()
+    }
+}
+
+/// Result of [`aliasing_report`].
+#[derive(Debug, Clone)]
+pub struct AliasingReport {
+    /// Scene name.
+    pub scene: String,
+    /// Number of pixels at each coverage value in the antialiased render.
+    pub pixels_by_coverage: [u64; 256],
+    /// One comparison per pair of [`ALIASING_THRESHOLDS`].
+    pub comparisons: Vec<AliasingComparison>,
+}
+
+impl AliasingReport {
+    /// Number of fully covered pixels that changed between the antialiased
+    /// and the fully aliased render. Aliasing must only affect edges, so this
+    /// should be zero for scenes whose shapes don't touch.
+    pub fn full_coverage_changed(&self) -> u64 {
+        self.comparisons
+            .iter()
+            .find(|c| c.from.is_none() && c.to == Some(255))
+            .map_or(0, |c| c.changed_by_coverage[255])
+    }
+
+    /// Human-readable summary with changed pixels bucketed by coverage.
+    pub fn summary(&self) -> String {
+        fn name(threshold: Option<u8>) -> String {
+            threshold.map_or("none".to_string(), |t| t.to_string())
+        }
+
+        let mut out = format!("{}\n", self.scene);
+        for c in &self.comparisons {
+            let buckets: Vec<String> = COVERAGE_BUCKETS
+                .iter()
+                .map(|&(min, max)| {
+                    let range = if min == max {
+                        min.to_string()
+                    } else {
+                        format!("{min}-{max}")
+                    };
+                    format!("{range}: {}", c.changed_in(min, max))
+                })
+                .collect();
+            out.push_str(&format!(
+                "  {} -> {}: {} changed (by coverage {})\n",
+                name(c.from),
+                name(c.to),
+                c.changed(),
+                buckets.join(", ")
+            ));
+        }
+        out
+    }
+}
+
+/// Render a programmatic vello scene on the CPU backend at each of
+/// [`ALIASING_THRESHOLDS`] and count, for each pair, the changed pixels by
+/// their coverage in the antialiased render.
+///
+/// Coverage is approximated by the alpha channel of the antialiased render on
+/// a transparent background. This is exact for opaque paint where shapes
+/// don't overlap; where shapes abut, seam pixels reach full coverage but
+/// still change when aliased.
+pub fn aliasing_report(scene_name: &str) -> Option<AliasingReport> {
+    let level = Level::new();
+    let renders = ALIASING_THRESHOLDS
+        .iter()
+        .map(|&threshold| {
+            render_vello_cpu(
+                scene_name,
+                level,
+                RenderMode::default(),
+                threshold,
+                &mut PixmapPool::new(),
+            )
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let coverage: Vec<u8> = renders[0].rgba.chunks_exact(4).map(|p| p[3]).collect();
+    let mut pixels_by_coverage = [0; 256];
+    for &c in &coverage {
+        pixels_by_coverage[usize::from(c)] += 1;
+    }
+
+    let mut comparisons = Vec::new();
+    for i in 0..renders.len() {
+        for j in i + 1..renders.len() {
+            let mut changed_by_coverage = [0; 256];
+            let pixels = renders[i]
+                .rgba
+                .chunks_exact(4)
+                .zip(renders[j].rgba.chunks_exact(4));
+            for ((a, b), &c) in pixels.zip(&coverage) {
+                if a != b {
+                    changed_by_coverage[usize::from(c)] += 1;
+                }
+            }
+            comparisons.push(AliasingComparison {
+                from: ALIASING_THRESHOLDS[i],
+                to: ALIASING_THRESHOLDS[j],
+                changed_by_coverage,
+            });
+        }
+    }
+
+    Some(AliasingReport {
+        scene: scene_name.to_string(),
+        pixels_by_coverage,
+        comparisons,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Image comparison metrics
+// ---------------------------------------------------------------------------
+
+/// Side length of the Gaussian window used by [`ssim`].
+const SSIM_WINDOW: usize = 11;
+/// Standard deviation of the Gaussian window used by [`ssim`].
+const SSIM_SIGMA: f64 = 1.5;
+/// SSIM stabilisation constant `(0.01 * 255)^2`.
+const SSIM_C1: f64 = 6.5025;
+/// SSIM stabilisation constant `(0.03 * 255)^2`.
+const SSIM_C2: f64 = 58.5225;
+
+/// Difference between two screenshots of the same dimensions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiffResult {
+    /// Number of pixels where at least one channel differs.
+    pub differing_pixels: usize,
+    /// Largest absolute difference seen in any single channel.
+    pub max_channel_delta: u8,
+    /// Structural similarity in `[-1, 1]`; `1.0` means identical. See [`ssim`].
+    pub ssim: f64,
+    /// Peak signal-to-noise ratio in dB; infinite for identical images. See [`psnr`].
+    pub psnr: f64,
+}
+
+/// Background used when comparing screenshots with transparent pixels.
+pub const DEFAULT_BACKGROUND: [u8; 3] = [0, 0, 0];
+
+/// Compare two screenshots. Returns `None` if their dimensions differ.
+///
+/// Both screenshots are [flattened](ScreenshotResult::flatten) over
+/// `background` first, so backends with different [`AlphaMode`]s can be
+/// compared, and differences in fully transparent pixels are ignored.
+pub fn diff(a: &ScreenshotResult, b: &ScreenshotResult, background: [u8; 3]) -> Option<DiffResult> {
+    if a.width != b.width || a.height != b.height {
+        return None;
+    }
+
+    let fa = a.flatten(background);
+    let fb = b.flatten(background);
+
+    let mut differing_pixels = 0;
+    let mut max_channel_delta = 0;
+    for (pa, pb) in fa.chunks_exact(3).zip(fb.chunks_exact(3)) {
+        let delta = pa
+            .iter()
+            .zip(pb)
+            .map(|(ca, cb)| ca.abs_diff(*cb))
+            .max()
+            .unwrap_or(0);
+        if delta > 0 {
+            differing_pixels += 1;
+        }
+        max_channel_delta = max_channel_delta.max(delta);
+    }
+
+    let width = a.width as usize;
+    let height = a.height as usize;
+    Some(DiffResult {
+        differing_pixels,
+        max_channel_delta,
+        ssim: ssim_flat(&fa, &fb, width, height),
+        psnr: psnr_flat(&fa, &fb),
+    })
+}
+
+/// Grayscale structural similarity index between two screenshots.
+///
+/// Both screenshots are [flattened](ScreenshotResult::flatten) over
+/// `background` and converted to Rec. 601 luma, then compared with an 11×11
+/// Gaussian window (σ = 1.5). Windows are clipped and renormalised at the
+/// image border, so small images are supported. Returns the mean SSIM over
+/// all pixels.
+///
+/// # Panics
+///
+/// Panics if the two screenshots have different dimensions.
+pub fn ssim(a: &ScreenshotResult, b: &ScreenshotResult, background: [u8; 3]) -> f64 {
+    assert_eq!(
+        (a.width, a.height),
+        (b.width, b.height),
+        "SSIM requires screenshots of equal dimensions"
+    );
+
+    ssim_flat(
+        &a.flatten(background),
+        &b.flatten(background),
+        a.width as usize,
+        a.height as usize,
+    )
+}
+
+/// [`ssim`] on RGB8 data that has already been flattened.
+fn ssim_flat(a: &[u8], b: &[u8], width: usize, height: usize) -> f64 {
+    if width == 0 || height == 0 {
+        return 1.0;
+    }
+
+    let la = luma(a);
+    let lb = luma(b);
+    let aa: Vec<f64> = la.iter().map(|v| v * v).collect();
+    let bb: Vec<f64> = lb.iter().map(|v| v * v).collect();
+    let ab: Vec<f64> = la.iter().zip(&lb).map(|(x, y)| x * y).collect();
+
+    let kernel = gaussian_kernel(SSIM_WINDOW, SSIM_SIGMA);
+    let mu_a = gaussian_blur(&la, width, height, &kernel);
+    let mu_b = gaussian_blur(&lb, width, height, &kernel);
+    let e_aa = gaussian_blur(&aa, width, height, &kernel);
+    let e_bb = gaussian_blur(&bb, width, height, &kernel);
+    let e_ab = gaussian_blur(&ab, width, height, &kernel);
+
+    let mut total = 0.0;
+    for i in 0..width * height {
+        let (ma, mb) = (mu_a[i], mu_b[i]);
+        let var_a = e_aa[i] - ma * ma;
+        let var_b = e_bb[i] - mb * mb;
+        let cov = e_ab[i] - ma * mb;
+
+        total += ((2.0 * ma * mb + SSIM_C1) * (2.0 * cov + SSIM_C2))
+            / ((ma * ma + mb * mb + SSIM_C1) * (var_a + var_b + SSIM_C2));
+    }
+
+    total / (width * height) as f64
+}
+
+/// Peak signal-to-noise ratio in dB over the RGB channels of both
+/// screenshots [flattened](ScreenshotResult::flatten) over `background`.
+///
+/// Returns [`f64::INFINITY`] for identical images.
+///
+/// # Panics
+///
+/// Panics if the two screenshots have different dimensions.
+pub fn psnr(a: &ScreenshotResult, b: &ScreenshotResult, background: [u8; 3]) -> f64 {
+    assert_eq!(
+        (a.width, a.height),
+        (b.width, b.height),
+        "PSNR requires screenshots of equal dimensions"
+    );
+
+    psnr_flat(&a.flatten(background), &b.flatten(background))
+}
+
+/// [`psnr`] on RGB8 data that has already been flattened.
+fn psnr_flat(a: &[u8], b: &[u8]) -> f64 {
+    if a.is_empty() {
+        return f64::INFINITY;
+    }
+
+    let sum_sq: f64 = a
+        .iter()
+        .zip(b)
+        .map(|(x, y)| {
+            let d = f64::from(*x) - f64::from(*y);
+            d * d
+        })
+        .sum();
+    let mse = sum_sq / a.len() as f64;
+
+    if mse == 0.0 {
+        f64::INFINITY
+    } else {
+        10.0 * (255.0 * 255.0 / mse).log10()
+    }
+}
+
+/// Largest difference in any channel, per row, between two screenshots
+/// [flattened](ScreenshotResult::flatten) over `background`. Returns `None`
+/// if their dimensions differ.
+///
+/// Strips are generated per row of tiles, so a coverage bug in one backend
+/// shows up as a band of rows with large deltas rather than as scattered
+/// pixels, which the whole-image numbers of [`diff`] don't reveal.
+pub fn row_profile(
+    a: &ScreenshotResult,
+    b: &ScreenshotResult,
+    background: [u8; 3],
+) -> Option<Vec<u8>> {
+    if a.width != b.width || a.height != b.height {
+        return None;
+    }
+    if a.width == 0 {
+        return Some(vec![0; a.height as usize]);
+    }
+
+    let stride = a.width as usize * 3;
+    let fa = a.flatten(background);
+    let fb = b.flatten(background);
+    Some(
+        fa.chunks_exact(stride)
+            .zip(fb.chunks_exact(stride))
+            .map(|(ra, rb)| {
+                ra.iter()
+                    .zip(rb)
+                    .map(|(ca, cb)| ca.abs_diff(*cb))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect(),
+    )
+}
+
+/// Up to `count` rows of a [`row_profile`] with the largest deltas, largest
+/// first. Rows without any difference are never included.
+pub fn worst_rows(profile: &[u8], count: usize) -> Vec<u32> {
+    let mut rows: Vec<u32> = (0..profile.len() as u32)
+        .filter(|&row| profile[row as usize] > 0)
+        .collect();
+    // Stable, so equal deltas stay in top-to-bottom order.
+    rows.sort_by_key(|&row| std::cmp::Reverse(profile[row as usize]));
+    rows.truncate(count);
+    rows
+}
+
+/// Rows above and below each requested row included by [`row_strips`].
+const STRIP_CONTEXT: usize = 2;
+/// Factor the channel deltas are multiplied by in the diff panel of
+/// [`row_strips`], so that off-by-a-few differences are visible.
+const STRIP_DIFF_GAIN: u8 = 16;
+/// Color of the lines separating panels and bands in [`row_strips`].
+const STRIP_SEPARATOR: [u8; 3] = [128, 128, 128];
+
+/// Side-by-side strips of `rows` of two screenshots, for looking at where
+/// they differ.
+///
+/// For each row, in the order given, a band of the row and two rows on
+/// either side is copied from `a` and `b` (both
+/// [flattened](ScreenshotResult::flatten) over `background`), followed by a
+/// third panel showing the largest channel delta per pixel in red, amplified.
+/// Panels and bands are separated by gray lines. Returns `None` if the
+/// dimensions differ or there are no rows.
+pub fn row_strips(
+    a: &ScreenshotResult,
+    b: &ScreenshotResult,
+    rows: &[u32],
+    background: [u8; 3],
+) -> Option<ScreenshotResult> {
+    if a.width != b.width || a.height != b.height || a.height == 0 || rows.is_empty() {
+        return None;
+    }
+
+    let width = a.width as usize;
+    let height = a.height as usize;
+    let out_width = 3 * width + 2;
+    let fa = a.flatten(background);
+    let fb = b.flatten(background);
+
+    let mut rgba = Vec::new();
+    let mut out_height = 0;
+    let push = |rgba: &mut Vec<u8>, rgb: [u8; 3]| rgba.extend([rgb[0], rgb[1], rgb[2], 255]);
+    for (i, &row) in rows.iter().enumerate() {
+        if i > 0 {
+            for _ in 0..out_width {
+                push(&mut rgba, STRIP_SEPARATOR);
+            }
+            out_height += 1;
+        }
+
+        let row = (row as usize).min(height - 1);
+        let band = row.saturating_sub(STRIP_CONTEXT)..(row + STRIP_CONTEXT + 1).min(height);
+        for y in band {
+            let ra = &fa[y * width * 3..(y + 1) * width * 3];
+            let rb = &fb[y * width * 3..(y + 1) * width * 3];
+            for p in ra.chunks_exact(3) {
+                push(&mut rgba, [p[0], p[1], p[2]]);
+            }
+            push(&mut rgba, STRIP_SEPARATOR);
+            for p in rb.chunks_exact(3) {
+                push(&mut rgba, [p[0], p[1], p[2]]);
+            }
+            push(&mut rgba, STRIP_SEPARATOR);
+            for (pa, pb) in ra.chunks_exact(3).zip(rb.chunks_exact(3)) {
+                let delta = pa
+                    .iter()
+                    .zip(pb)
+                    .map(|(ca, cb)| ca.abs_diff(*cb))
+                    .max()
+                    .unwrap_or(0);
+                push(&mut rgba, [delta.saturating_mul(STRIP_DIFF_GAIN), 0, 0]);
+            }
+            out_height += 1;
+        }
+    }
+
+    Some(ScreenshotResult {
+        width: out_width as u32,
+        height: out_height,
+        rgba,
+        alpha_mode: AlphaMode::Straight,
+    })
+}
+
+/// Convert opaque RGB8 to Rec. 601 luma.
+fn luma(rgb: &[u8]) -> Vec<f64> {
+    rgb.chunks_exact(3)
+        .map(|p| 0.299 * f64::from(p[0]) + 0.587 * f64::from(p[1]) + 0.114 * f64::from(p[2]))
+        .collect()
+}
+
+/// Normalised 1D Gaussian kernel of the given (odd) size.
+fn gaussian_kernel(size: usize, sigma: f64) -> Vec<f64> {
+    let half = (size / 2) as f64;
+    let kernel: Vec<f64> = (0..size)
+        .map(|i| {
+            let x = i as f64 - half;
+            (-(x * x) / (2.0 * sigma * sigma)).exp()
+        })
+        .collect();
+    let sum: f64 = kernel.iter().sum();
+    kernel.into_iter().map(|k| k / sum).collect()
+}
+
+/// Separable Gaussian blur. Taps falling outside the image are dropped and
+/// the remaining weights renormalised.
+fn gaussian_blur(src: &[f64], width: usize, height: usize, kernel: &[f64]) -> Vec<f64> {
+    let half = kernel.len() / 2;
+
+    let blur_1d = |get: &dyn Fn(usize) -> f64, len: usize, center: usize| -> f64 {
+        let mut acc = 0.0;
+        let mut weight = 0.0;
+        for (k, w) in kernel.iter().enumerate() {
+            let Some(pos) = (center + k).checked_sub(half) else {
+                continue;
+            };
+            if pos >= len {
+                continue;
+            }
+            acc += get(pos) * w;
+            weight += w;
+        }
+        acc / weight
+    };
+
+    let mut horizontal = vec![0.0; width * height];
+    for y in 0..height {
+        let row = &src[y * width..(y + 1) * width];
+        for x in 0..width {
+            horizontal[y * width + x] = blur_1d(&|i| row[i], width, x);
+        }
+    }
+
+    let mut out = vec![0.0; width * height];
+    for y in 0..height {
+        for x in 0..width {
+            out[y * width + x] = blur_1d(&|i| horizontal[i * width + x], height, y);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: u32 = 32;
+
+    /// An opaque gray screenshot with the value `gray(x, y)` at each pixel.
+    fn gray(gray: impl Fn(u32, u32) -> u8) -> ScreenshotResult {
+        let rgba = (0..SIZE * SIZE)
+            .flat_map(|i| {
+                let v = gray(i % SIZE, i / SIZE);
+                [v, v, v, 255]
+            })
+            .collect();
+        ScreenshotResult {
+            width: SIZE,
+            height: SIZE,
+            rgba,
+            alpha_mode: AlphaMode::Straight,
+        }
+    }
+
+    /// A busy pattern, so that every SSIM window has structure.
+    fn pattern(x: u32, y: u32) -> u8 {
+        ((x * 37 + y * 91) % 256) as u8
+    }
+
+    /// [`pattern`] off by up to 2 in either direction.
+    fn noisy_pattern(x: u32, y: u32) -> u8 {
+        let v = pattern(x, y);
+        match (x + 3 * y) % 5 {
+            0 => v.saturating_add(2),
+            1 => v.saturating_sub(2),
+            2 => v.saturating_add(1),
+            3 => v.saturating_sub(1),
+            _ => v,
+        }
+    }
+
+    #[test]
+    fn identical_images_are_fully_similar() {
+        let a = gray(pattern);
+        assert_eq!(ssim(&a, &gray(pattern), [0; 3]), 1.0);
+        assert_eq!(psnr(&a, &gray(pattern), [0; 3]), f64::INFINITY);
+    }
+
+    #[test]
+    fn inverted_images_are_dissimilar() {
+        let a = gray(pattern);
+        let b = gray(|x, y| 255 - pattern(x, y));
+        assert!(ssim(&a, &b, [0; 3]) < 0.05);
+
+        let black = gray(|_, _| 0);
+        let white = gray(|_, _| 255);
+        let value = ssim(&black, &white, [0; 3]);
+        assert!(value.abs() < 0.01, "SSIM of black and white: {value}");
+        assert_eq!(psnr(&black, &white, [0; 3]), 0.0);
+    }
+
+    #[test]
+    fn slightly_noisy_images_are_similar() {
+        let a = gray(pattern);
+        let b = gray(noisy_pattern);
+        let value = ssim(&a, &b, [0; 3]);
+        assert!(value > 0.95 && value < 1.0, "SSIM: {value}");
+        let value = psnr(&a, &b, [0; 3]);
+        assert!(value > 40.0 && value.is_finite(), "PSNR: {value} dB");
+    }
+
+    /// A `width`-pixel-wide screenshot of `pixels`.
+    fn screenshot(width: u32, pixels: &[[u8; 4]], alpha_mode: AlphaMode) -> ScreenshotResult {
+        ScreenshotResult {
+            width,
+            height: pixels.len() as u32 / width,
+            rgba: pixels.concat(),
+            alpha_mode,
+        }
+    }
+
+    fn pixels(shot: &ScreenshotResult) -> Vec<[u8; 4]> {
+        shot.rgba
+            .chunks_exact(4)
+            .map(|p| [p[0], p[1], p[2], p[3]])
+            .collect()
+    }
+
+    #[test]
+    fn composites_translucent_pixels_over_a_color() {
+        let white = Matte::Color(Color::from_rgb8(0xff, 0xff, 0xff));
+        // The same half-transparent orange in either alpha convention, and a
+        // transparent pixel.
+        let straight = screenshot(2, &[[200, 100, 0, 128], [0; 4]], AlphaMode::Straight);
+        let premultiplied = screenshot(2, &[[100, 50, 0, 128], [0; 4]], AlphaMode::Premultiplied);
+        for shot in [straight, premultiplied] {
+            let composited = shot.composite_over(white);
+            assert_eq!(
+                pixels(&composited),
+                [[227, 177, 127, 255], [255, 255, 255, 255]]
+            );
+            assert_eq!(composited.alpha_mode, AlphaMode::Straight);
+        }
+
+        let shot = screenshot(1, &[[200, 100, 0, 128]], AlphaMode::Straight);
+        let dark = Matte::from_spec("#333333").unwrap();
+        assert_eq!(pixels(&shot.composite_over(dark)), [[126, 76, 25, 255]]);
+    }
+
+    #[test]
+    fn checkerboard_alternates_from_a_light_corner() {
+        let transparent = screenshot(2, &[[0; 4]; 4], AlphaMode::Straight);
+        let [light, dark] = CHECKERBOARD_COLORS.map(|[r, g, b]| [r, g, b, 255]);
+        assert_eq!(
+            pixels(&transparent.composite_over_checkerboard(1)),
+            [light, dark, dark, light]
+        );
+        assert_eq!(
+            pixels(&transparent.composite_over_checkerboard(2)),
+            [light; 4]
+        );
+
+        let translucent = screenshot(2, &[[200, 100, 0, 64]; 2], AlphaMode::Straight);
+        assert_eq!(
+            pixels(&translucent.composite_over_checkerboard(1)),
+            [[241, 216, 191, 255], [203, 178, 153, 255]]
+        );
+    }
+
+    #[test]
+    fn compositing_leaves_opaque_scenes_unchanged() {
+        let opaque = gray(pattern);
+        for matte in [
+            Matte::Checkerboard { cell: 8 },
+            Matte::Color(Color::from_rgb8(0, 0, 0)),
+            Matte::Color(Color::from_rgb8(0x12, 0x34, 0x56)),
+        ] {
+            let composited = opaque.composite_over(matte);
+            assert_eq!(composited.rgba, opaque.rgba, "{matte:?}");
+            assert_eq!((composited.width, composited.height), (SIZE, SIZE));
+        }
+
+        let premultiplied = ScreenshotResult {
+            alpha_mode: AlphaMode::Premultiplied,
+            ..gray(pattern)
+        };
+        let composited = premultiplied.composite_over_checkerboard(4);
+        assert_eq!(composited.rgba, opaque.rgba);
+    }
+
+    #[test]
+    fn parses_matte_specs() {
+        assert_eq!(
+            Matte::from_spec("checkerboard"),
+            Ok(Matte::Checkerboard {
+                cell: DEFAULT_CHECKERBOARD_CELL
+            })
+        );
+        assert_eq!(
+            Matte::from_spec("checkerboard:4"),
+            Ok(Matte::Checkerboard { cell: 4 })
+        );
+        assert_eq!(
+            Matte::from_spec("#ff8000"),
+            Ok(Matte::Color(Color::from_rgb8(0xff, 0x80, 0x00)))
+        );
+        for spec in [
+            "checkerboard:0",
+            "checkerboard:",
+            "#fff",
+            "#ff80zz",
+            "white",
+        ] {
+            let error = Matte::from_spec(spec).unwrap_err();
+            assert!(
+                error.starts_with(&format!("Invalid matte '{spec}'")),
+                "{error}"
+            );
+        }
+    }
+}
@@ -0,0 +1,386 @@
+//! Warm standby: a long-lived process running benchmarks on request, so that
+//! repeated runs skip device creation and scene deserialization.
+//!
+//! `vello_bench serve --socket PATH` listens on a Unix domain socket (see
+//! [`listen`]; other platforms have no transport yet) and reads
+//! newline-delimited JSON [`Request`]s, answering each with one line of JSON
+//! [`Response`]. For example:
+//!
+//! ```text
+//! {"command":"list","filter":"category:scene_cpu"}
+//! {"command":"run","id":"scene_cpu/demo_scene","iterations":50}
+//! {"command":"screenshot","scene":"demo_scene","path":"demo.png","matte":"checkerboard"}
+//! {"command":"shutdown"}
+//! ```
+//!
+//! What stays warm between requests: the deserialized archive scenes and
+//! other lazily built caches, which live for the whole process anyway, the
+//! wgpu devices (see [`keep_gpu_devices`]) and the screenshot buffers of a
+//! [`PixmapPool`]. The benchmark code itself is whatever the server was built
+//! with; picking up a rebuilt vello means restarting it.
+//!
+//! Connections are served one at a time, so runs never overlap; a second
+//! client waits until the first disconnects. A benchmark that panics or hits
+//! the GPU timeout is answered with an error or a failed outcome, and the
+//! kept devices are dropped in case it left one unusable.
+
+use std::io::{BufRead, BufReader, Write};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::path::Path;
+
+use fearless_simd::Level;
+use serde::{Deserialize, Serialize};
+
+use crate::golden::{self, Backend, screenshot_hash};
+use crate::registry::{BenchmarkInfo, get_benchmark_list_filtered, try_run_benchmark_by_id};
+use crate::renderer::{discard_kept_gpu_devices, keep_gpu_devices};
+use crate::result::BenchmarkOutcome;
+use crate::runner::BenchRunner;
+use crate::screenshot::{Matte, PixmapPool};
+use crate::simd::available_level_from_suffix;
+
+/// One command sent to the server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum Request {
+    /// List the benchmarks matching a [`Filter`](crate::Filter) expression,
+    /// all of them if empty.
+    List {
+        #[serde(default)]
+        filter: String,
+    },
+    /// Run one benchmark. Settings left out use the server's defaults.
+    Run {
+        id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        warmup: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        iterations: Option<u64>,
+        /// SIMD level suffix, e.g. `avx2`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        simd: Option<String>,
+    },
+    /// Render a scene once, by name as in [`golden::scene_names`].
+    Screenshot {
+        scene: String,
+        /// Backend name as in [`Backend::as_str`]; `cpu` if left out.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        backend: Option<String>,
+        /// Where to write the render as PNG, relative to the server's
+        /// working directory.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        path: Option<String>,
+        /// Background to composite the render over, as in
+        /// [`Matte::from_spec`], e.g. `checkerboard` for docs.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        matte: Option<String>,
+    },
+    /// Stop the server once this request is answered.
+    Shutdown,
+}
+
+/// The server's answer to one [`Request`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "response", rename_all = "snake_case")]
+pub enum Response {
+    /// Answer to [`Request::List`].
+    Benchmarks { benchmarks: Vec<BenchmarkInfo> },
+    /// Answer to [`Request::Run`].
+    Outcome { outcome: BenchmarkOutcome },
+    /// Answer to [`Request::Screenshot`].
+    Screenshot {
+        width: u32,
+        height: u32,
+        /// See [`screenshot_hash`].
+        hash: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        path: Option<String>,
+    },
+    /// Answer to [`Request::Shutdown`].
+    ShuttingDown,
+    /// The request was invalid or couldn't be carried out.
+    Error { message: String },
+}
+
+/// Settings of [`Request::Run`] that the request leaves out.
+#[derive(Debug, Clone, Copy)]
+pub struct RunDefaults {
+    pub warmup: u64,
+    pub iterations: u64,
+    pub level: Level,
+}
+
+/// The state kept warm between requests. Independent of the transport, so
+/// that requests can also be handled in-process.
+pub struct Server {
+    defaults: RunDefaults,
+    pool: PixmapPool,
+}
+
+impl Server {
+    /// A server answering with `defaults`. Turns on [`keep_gpu_devices`] for
+    /// the rest of the process, in case it was turned off.
+    pub fn new(defaults: RunDefaults) -> Self {
+        keep_gpu_devices(true);
+        Self {
+            defaults,
+            pool: PixmapPool::new(),
+        }
+    }
+
+    /// Answer a single request.
+    pub fn handle(&mut self, request: &Request) -> Response {
+        let result = match request {
+            Request::List { filter } => get_benchmark_list_filtered(filter)
+                .map(|benchmarks| Response::Benchmarks { benchmarks }),
+            Request::Run {
+                id,
+                warmup,
+                iterations,
+                simd,
+            } => self.run(id, *warmup, *iterations, simd.as_deref()),
+            Request::Screenshot {
+                scene,
+                backend,
+                path,
+                matte,
+            } => self.screenshot(scene, backend.as_deref(), path.as_deref(), matte.as_deref()),
+            Request::Shutdown => Ok(Response::ShuttingDown),
+        };
+        result.unwrap_or_else(|message| Response::Error { message })
+    }
+
+    /// Answer one line of JSON, which must hold a single [`Request`].
+    /// Returns the response and whether it was a shutdown request.
+    pub fn handle_line(&mut self, line: &str) -> (Response, bool) {
+        match serde_json::from_str::<Request>(line) {
+            Ok(request) => (self.handle(&request), request == Request::Shutdown),
+            Err(e) => (
+                Response::Error {
+                    message: format!("Invalid request: {e}"),
+                },
+                false,
+            ),
+        }
+    }
+
+    fn run(
+        &self,
+        id: &str,
+        warmup: Option<u64>,
+        iterations: Option<u64>,
+        simd: Option<&str>,
+    ) -> Result<Response, String> {
+        let runner = BenchRunner::new(
+            warmup.unwrap_or(self.defaults.warmup),
+            iterations.unwrap_or(self.defaults.iterations),
+        );
+        let level = match simd {
+            Some(suffix) => available_level_from_suffix(suffix)?,
+            None => self.defaults.level,
+        };
+
+        let outcome = guard_panics(|| try_run_benchmark_by_id(&runner, id, level))?
+            .ok_or_else(|| format!("Unknown benchmark: {id}"))?;
+        if outcome.result().is_none() {
+            // A GPU timeout may have lost the device.
+            discard_kept_gpu_devices();
+        }
+        Ok(Response::Outcome { outcome })
+    }
+
+    fn screenshot(
+        &mut self,
+        scene: &str,
+        backend: Option<&str>,
+        path: Option<&str>,
+        matte: Option<&str>,
+    ) -> Result<Response, String> {
+        let matte = matte.map(Matte::from_spec).transpose()?;
+        let backend = match backend {
+            Some(name) => {
+                Backend::from_name(name).ok_or_else(|| format!("Unknown backend: {name}"))?
+            }
+            None => Backend::Cpu,
+        };
+        let level = self.defaults.level;
+        let pool = &mut self.pool;
+        let mut shot = guard_panics(|| golden::render_in(scene, backend, level, pool))?
+            .ok_or_else(|| format!("{} can't render {scene}", backend.as_str()))?;
+        if let Some(matte) = matte {
+            let matted = shot.composite_over(matte);
+            self.pool.recycle(std::mem::replace(&mut shot, matted));
+        }
+
+        if let Some(path) = path {
+            golden::write_png(Path::new(path), &shot)?;
+        }
+        let response = Response::Screenshot {
+            width: shot.width,
+            height: shot.height,
+            hash: screenshot_hash(&shot),
+            path: path.map(str::to_string),
+        };
+        self.pool.recycle(shot);
+        Ok(response)
+    }
+}
+
+/// Run `f`, turning a panic into an error so that one broken benchmark
+/// doesn't take down the server.
+fn guard_panics<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        discard_kept_gpu_devices();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        format!("Panicked: {message}")
+    })
+}
+
+/// Read requests from `reader` line by line and write each response to
+/// `writer`, until the input ends or a shutdown request was answered.
+/// Returns whether it stopped because of a shutdown request.
+pub fn serve_stream(
+    server: &mut Server,
+    reader: impl BufRead,
+    mut writer: impl Write,
+) -> Result<bool, String> {
+    for line in reader.lines() {
+        let line = line.map_err(|e| format!("Failed to read request: {e}"))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (response, shutdown) = server.handle_line(&line);
+        write_line(&mut writer, &response)?;
+        if shutdown {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Listen on a Unix domain socket at `path` and serve connections one after
+/// another until a client sends [`Request::Shutdown`], then remove the
+/// socket file. A connection that fails only ends that connection.
+///
+/// A stale socket file left by a server that didn't shut down cleanly is
+/// replaced; if another server is listening at `path`, this fails.
+#[cfg(unix)]
+pub fn listen(server: &mut Server, path: &Path) -> Result<(), String> {
+    let listener = bind(path)?;
+    eprintln!("Listening on {}", path.display());
+    let served = serve_connections(server, &listener);
+    let _ = std::fs::remove_file(path);
+    served
+}
+
+#[cfg(unix)]
+fn bind(path: &Path) -> Result<std::os::unix::net::UnixListener, String> {
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    if let Ok(metadata) = std::fs::symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            return Err(format!("{} exists and is not a socket", path.display()));
+        }
+        if UnixStream::connect(path).is_ok() {
+            return Err(format!(
+                "A server is already listening on {}",
+                path.display()
+            ));
+        }
+        std::fs::remove_file(path)
+            .map_err(|e| format!("Failed to remove stale socket {}: {e}", path.display()))?;
+    }
+    UnixListener::bind(path).map_err(|e| format!("Failed to listen on {}: {e}", path.display()))
+}
+
+#[cfg(unix)]
+fn serve_connections(
+    server: &mut Server,
+    listener: &std::os::unix::net::UnixListener,
+) -> Result<(), String> {
+    for stream in listener.incoming() {
+        let stream = stream.map_err(|e| format!("Failed to accept a connection: {e}"))?;
+        let reader = match stream.try_clone() {
+            Ok(reader) => BufReader::new(reader),
+            Err(e) => {
+                eprintln!("Dropping connection: {e}");
+                continue;
+            }
+        };
+        match serve_stream(server, reader, &stream) {
+            Ok(true) => return Ok(()),
+            Ok(false) => {}
+            Err(e) => eprintln!("Dropping connection: {e}"),
+        }
+    }
+    Ok(())
+}
+
+/// Send `request` to the server listening on `path` and wait for its
+/// response.
+#[cfg(unix)]
+pub fn request(path: &Path, request: &Request) -> Result<Response, String> {
+    let stream = std::os::unix::net::UnixStream::connect(path)
+        .map_err(|e| format!("Failed to connect to {}: {e}", path.display()))?;
+    write_line(&stream, request)?;
+
+    let mut line = String::new();
+    BufReader::new(&stream)
+        .read_line(&mut line)
+        .map_err(|e| format!("Failed to read the response: {e}"))?;
+    if line.is_empty() {
+        return Err("The server closed the connection without responding".to_string());
+    }
+    serde_json::from_str(&line).map_err(|e| format!("Invalid response: {e}"))
+}
+
+/// Unix domain sockets are the only transport so far.
+#[cfg(not(unix))]
+pub fn listen(_: &mut Server, _: &Path) -> Result<(), String> {
+    Err(UNSUPPORTED.to_string())
+}
+
+#[cfg(not(unix))]
+pub fn request(_: &Path, _: &Request) -> Result<Response, String> {
+    Err(UNSUPPORTED.to_string())
+}
+
+#[cfg(not(unix))]
+const UNSUPPORTED: &str = "Serving needs Unix domain sockets, which this platform lacks";
+
+fn write_line(mut writer: impl Write, value: &impl Serialize) -> Result<(), String> {
+    let json = serde_json::to_string(value).map_err(|e| format!("Failed to serialize: {e}"))?;
+    writeln!(writer, "{json}")
+        .and_then(|()| writer.flush())
+        .map_err(|e| format!("Failed to write: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unavailable_simd_level_is_an_error() {
+        let mut server = Server::new(RunDefaults {
+            warmup: 0,
+            iterations: 1,
+            level: Level::new(),
+        });
+        for simd in ["bogus", "avx512"] {
+            let line = format!(r#"{{"command":"run","id":"scene_cpu/tiger","simd":"{simd}"}}"#);
+            let (response, shutdown) = server.handle_line(&line);
+            assert!(!shutdown);
+            match response {
+                Response::Error { message } => {
+                    assert!(message.contains("not available"), "{message}");
+                }
+                other => panic!("expected an error, got {other:?}"),
+            }
+        }
+    }
+}
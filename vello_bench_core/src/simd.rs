@@ -94,6 +94,24 @@ pub fn level_from_suffix(s: &str) -> Level {
     }
 }
 
+/// The level with suffix `s` (see [`level_suffix`]) if this CPU supports it.
+/// Unlike [`level_from_suffix`], returns an error naming the available
+/// levels for an unknown or unsupported suffix.
+pub fn available_level_from_suffix(s: &str) -> Result<Level, String> {
+    let levels = available_levels();
+    levels
+        .iter()
+        .copied()
+        .find(|&level| level_suffix(level) == s)
+        .ok_or_else(|| {
+            let available: Vec<&str> = levels.iter().map(|&level| level_suffix(level)).collect();
+            format!(
+                "SIMD level {s:?} is not available here, only {}",
+                available.join(", ")
+            )
+        })
+}
+
 /// Get `SimdLevelInfo` structs for all available levels, suitable for sending to a frontend.
 pub fn available_level_infos() -> Vec<SimdLevelInfo> {
     available_levels()
@@ -0,0 +1,91 @@
+//! Content generated anew every frame.
+//!
+//! Real UIs change from frame to frame, while most scenes draw the same frame
+//! every time, which lets a renderer that reuses work between frames look
+//! faster than it would be in practice. The scenes here build their content
+//! in [`draw_frame`](VelloScene::draw_frame) from a generator seeded with the
+//! frame index:
+//!
+//! - `animated_paths_1000`: 1000 closed polygons of 3 to 8 vertices, placed
+//!   and shaped differently in every frame.
+//!
+//! See also `animated_tiled_flowers_1000` among the [image
+//! scenes](super::images), which shifts a static grid instead.
+
+use std::f64::consts::TAU;
+
+use super::rng::Lcg;
+use super::{SceneRegistry, VelloScene, VelloSceneInfo};
+use crate::renderer::Renderer;
+use vello_common::kurbo::{BezPath, Point, Vec2};
+use vello_common::peniko::color::palette;
+
+/// Paths drawn per frame by `animated_paths_1000`.
+const ANIMATED_PATHS: u32 = 1000;
+
+/// Added to the frame index to seed the paths of a frame.
+const SEED: u64 = 0x5eed;
+
+/// 1000 polygons, different in every frame.
+pub struct AnimatedPaths1000;
+
+impl VelloScene for AnimatedPaths1000 {
+    type State = ();
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "animated_paths_1000",
+            width: 1024,
+            height: 768,
+        }
+    }
+
+    fn setup<R: Renderer>(_r: &mut R) -> Self::State {}
+
+    fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
+        Self::draw_frame(state, r, 0);
+    }
+
+    fn draw_frame<R: Renderer>(_state: &Self::State, r: &mut R, frame_index: u64) {
+        let width = f64::from(r.width());
+        let height = f64::from(r.height());
+        let colors = [
+            palette::css::CRIMSON,
+            palette::css::SEA_GREEN,
+            palette::css::ROYAL_BLUE,
+            palette::css::DARK_ORANGE,
+            palette::css::DARK_ORCHID,
+        ];
+
+        let mut rng = Lcg::new(SEED.wrapping_add(frame_index));
+        for i in 0..ANIMATED_PATHS {
+            let center = Point::new(rng.next_f64() * width, rng.next_f64() * height);
+            let radius = 8.0 + 32.0 * rng.next_f64();
+            let vertices = 3 + (rng.next_f64() * 6.0) as u32;
+            let rotation = rng.next_f64() * TAU;
+
+            let mut path = BezPath::new();
+            for v in 0..vertices {
+                let angle = rotation + TAU * f64::from(v) / f64::from(vertices);
+                let distance = radius * (0.6 + 0.4 * rng.next_f64());
+                let point = center + distance * Vec2::from_angle(angle);
+                if v == 0 {
+                    path.move_to(point);
+                } else {
+                    path.line_to(point);
+                }
+            }
+            path.close_path();
+
+            r.set_paint(colors[i as usize % colors.len()]);
+            r.fill_path(&path);
+        }
+    }
+
+    const ANIMATED: bool = true;
+    const ELEMENTS: Option<u64> = Some(ANIMATED_PATHS as u64);
+}
+
+pub(super) fn register(scenes: &mut impl SceneRegistry) {
+    scenes.add::<AnimatedPaths1000>();
+}
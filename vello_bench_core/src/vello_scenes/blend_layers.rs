@@ -0,0 +1,170 @@
+//! Layers composited with blend modes other than plain source-over.
+//!
+//! Every scene stretches the splash-flower image (see [`ImageGridState`])
+//! over the canvas as the backdrop, then draws a grid of layers, each pushed
+//! with [`push_blend_layer`](Renderer::push_blend_layer) and holding a rect
+//! and a circle that spill into the neighboring cells. Blending with the
+//! backdrop takes a slower path in the fine rasterizers than source-over,
+//! which is what these scenes measure.
+//!
+//! - `blend_normal_layers_100`: `Mix::Normal`, the baseline for the others.
+//! - `blend_multiply_layers_100` / `blend_screen_layers_100`: one mix for
+//!   all layers.
+//! - `blend_mixed_modes_300`: every separable and non-separable mix in turn.
+//!
+//! Only `push_blend_layer` is used, since Vello Hybrid has no
+//! `set_blend_mode`. The multiply scene must render differently from the
+//! baseline, see [`check_effect_sanity`](crate::golden::check_effect_sanity).
+
+use super::images::{ImageGridState, setup_image_grid};
+use super::{SceneRegistry, VelloScene, VelloSceneInfo};
+use crate::renderer::Renderer;
+use vello_common::kurbo::{Affine, Circle, Rect, Shape};
+use vello_common::paint::Image;
+use vello_common::peniko::color::palette;
+use vello_common::peniko::{BlendMode, Compose, ImageSampler, Mix};
+
+const WIDTH: u16 = 1024;
+const HEIGHT: u16 = 768;
+
+/// Mixes of `blend_mixed_modes_300`, in the order the layers cycle through
+/// them.
+const MIXED_MODES: [Mix; 15] = [
+    Mix::Multiply,
+    Mix::Screen,
+    Mix::Overlay,
+    Mix::Darken,
+    Mix::Lighten,
+    Mix::ColorDodge,
+    Mix::ColorBurn,
+    Mix::HardLight,
+    Mix::SoftLight,
+    Mix::Difference,
+    Mix::Exclusion,
+    Mix::Hue,
+    Mix::Saturation,
+    Mix::Color,
+    Mix::Luminosity,
+];
+
+/// Draw the image backdrop and `count` layers in a grid, layer `i` blended
+/// with `mixes[i % mixes.len()]`.
+fn draw_blend_layers<R: Renderer>(state: &ImageGridState, r: &mut R, count: u32, mixes: &[Mix]) {
+    let canvas_w = f64::from(r.width());
+    let canvas_h = f64::from(r.height());
+    let img_w = f64::from(state.img_w);
+    let img_h = f64::from(state.img_h);
+
+    r.set_transform(Affine::scale_non_uniform(
+        canvas_w / img_w,
+        canvas_h / img_h,
+    ));
+    r.set_paint(Image {
+        image: state.image_source.clone(),
+        sampler: ImageSampler::default(),
+    });
+    r.fill_rect(&Rect::new(0.0, 0.0, img_w, img_h));
+    r.set_transform(Affine::IDENTITY);
+
+    let aspect = canvas_w / canvas_h;
+    let cols = (f64::from(count) * aspect).sqrt().ceil() as u32;
+    let rows = count.div_ceil(cols);
+    let cell_w = canvas_w / f64::from(cols);
+    let cell_h = canvas_h / f64::from(rows);
+
+    let colors = [
+        palette::css::CRIMSON,
+        palette::css::GOLD,
+        palette::css::DODGER_BLUE,
+        palette::css::MEDIUM_SEA_GREEN,
+    ];
+    for i in 0..count {
+        let x = f64::from(i % cols) * cell_w;
+        let y = f64::from(i / cols) * cell_h;
+        let mix = mixes[i as usize % mixes.len()];
+
+        r.push_blend_layer(BlendMode::new(mix, Compose::SrcOver));
+        r.set_paint(colors[i as usize % colors.len()]);
+        r.fill_rect(&Rect::new(
+            x - cell_w * 0.1,
+            y - cell_h * 0.1,
+            x + cell_w * 0.8,
+            y + cell_h * 0.8,
+        ));
+        r.set_paint(colors[(i as usize + 2) % colors.len()]);
+        r.fill_path(
+            &Circle::new(
+                (x + cell_w * 0.65, y + cell_h * 0.65),
+                cell_w.min(cell_h) * 0.4,
+            )
+            .to_path(0.1),
+        );
+        r.pop_layer();
+    }
+}
+
+/// Stamp out a blend layer scene drawing `$count` layers with `$mixes`.
+macro_rules! blend_layer_scene {
+    ($(#[$meta:meta])* $ty:ident, $name:literal, $count:expr, $mixes:expr) => {
+        $(#[$meta])*
+        pub struct $ty;
+
+        impl VelloScene for $ty {
+            type State = ImageGridState;
+
+            fn info() -> VelloSceneInfo {
+                VelloSceneInfo {
+                    name: $name,
+                    width: WIDTH,
+                    height: HEIGHT,
+                }
+            }
+
+            fn setup<R: Renderer>(r: &mut R) -> Self::State {
+                setup_image_grid(r)
+            }
+
+            fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
+                draw_blend_layers(state, r, $count, &$mixes);
+            }
+
+            const ELEMENTS: Option<u64> = Some($count);
+        }
+    };
+}
+
+blend_layer_scene!(
+    /// 100 source-over layers; the cost baseline of the blend scenes.
+    BlendNormalLayers100,
+    "blend_normal_layers_100",
+    100,
+    [Mix::Normal]
+);
+blend_layer_scene!(
+    /// 100 multiply layers.
+    BlendMultiplyLayers100,
+    "blend_multiply_layers_100",
+    100,
+    [Mix::Multiply]
+);
+blend_layer_scene!(
+    /// 100 screen layers.
+    BlendScreenLayers100,
+    "blend_screen_layers_100",
+    100,
+    [Mix::Screen]
+);
+blend_layer_scene!(
+    /// 300 layers cycling through [`MIXED_MODES`].
+    BlendMixedModes300,
+    "blend_mixed_modes_300",
+    300,
+    MIXED_MODES
+);
+
+pub(super) fn register(scenes: &mut impl SceneRegistry) {
+    scenes.add::<BlendNormalLayers100>();
+    scenes.add::<BlendMultiplyLayers100>();
+    scenes.add::<BlendScreenLayers100>();
+    scenes.add::<BlendMixedModes300>();
+}
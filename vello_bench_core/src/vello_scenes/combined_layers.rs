@@ -0,0 +1,195 @@
+//! Layers that combine a clip, a blend mode and an opacity in a single
+//! [`push_layer`](Renderer::push_layer) call.
+//!
+//! Each feature on its own is covered elsewhere; these scenes exercise their
+//! interaction, which takes a different path through the renderers. They are
+//! mainly meant for golden screenshots and CPU/hybrid parity (see
+//! [`check_parity`](crate::golden::check_parity)), but are benchmarked too,
+//! since a combined layer costs more than its parts.
+//!
+//! Every scene draws the same test pattern: colored stripes as a backdrop,
+//! and in each cell of an 8×6 grid a layer holding two overlapping rects and
+//! a circle that extend past the cell's star-shaped clip.
+
+use std::f64::consts::PI;
+
+use super::{SceneRegistry, VelloScene, VelloSceneInfo};
+use crate::renderer::Renderer;
+use vello_common::kurbo::{BezPath, Circle, Rect, Shape};
+use vello_common::peniko::color::palette;
+use vello_common::peniko::{BlendMode, Compose, Mix};
+
+/// Width of the backdrop stripes.
+const STRIPE_WIDTH: f64 = 32.0;
+
+/// Five-pointed star centered at `(cx, cy)`.
+fn star(cx: f64, cy: f64, outer: f64, inner: f64) -> BezPath {
+    let mut path = BezPath::new();
+    for i in 0..10 {
+        let radius = if i % 2 == 0 { outer } else { inner };
+        let angle = f64::from(i) * PI / 5.0 - PI / 2.0;
+        let point = (cx + radius * angle.cos(), cy + radius * angle.sin());
+        if i == 0 {
+            path.move_to(point);
+        } else {
+            path.line_to(point);
+        }
+    }
+    path.close_path();
+    path
+}
+
+/// Draw the test pattern, pushing each cell's content through
+/// `push_layer(clip, blend_mode, opacity, None, None)`.
+fn draw_combined<R: Renderer>(
+    r: &mut R,
+    clip: bool,
+    blend_mode: Option<BlendMode>,
+    opacity: Option<f32>,
+) {
+    let width = f64::from(r.width());
+    let height = f64::from(r.height());
+
+    let stripes = [
+        palette::css::LIGHT_GRAY,
+        palette::css::STEEL_BLUE,
+        palette::css::KHAKI,
+    ];
+    let stripe_count = (width / STRIPE_WIDTH).ceil() as usize;
+    for i in 0..stripe_count {
+        let x = i as f64 * STRIPE_WIDTH;
+        r.set_paint(stripes[i % stripes.len()]);
+        r.fill_rect(&Rect::new(x, 0.0, x + STRIPE_WIDTH, height));
+    }
+
+    let cols = 8u16;
+    let rows = 6u16;
+    let cell_w = width / f64::from(cols);
+    let cell_h = height / f64::from(rows);
+
+    for row in 0..rows {
+        for col in 0..cols {
+            let x = f64::from(col) * cell_w;
+            let y = f64::from(row) * cell_h;
+            let cx = x + cell_w * 0.5;
+            let cy = y + cell_h * 0.5;
+            let size = cell_w.min(cell_h);
+
+            let clip_path = clip.then(|| star(cx, cy, size * 0.45, size * 0.2));
+            r.push_layer(clip_path.as_ref(), blend_mode, opacity, None, None);
+
+            r.set_paint(palette::css::CRIMSON);
+            r.fill_rect(&Rect::new(
+                x + cell_w * 0.05,
+                y + cell_h * 0.1,
+                x + cell_w * 0.65,
+                y + cell_h * 0.7,
+            ));
+            r.set_paint(palette::css::SEA_GREEN);
+            r.fill_rect(&Rect::new(
+                x + cell_w * 0.35,
+                y + cell_h * 0.3,
+                x + cell_w * 0.95,
+                y + cell_h * 0.9,
+            ));
+            r.set_paint(palette::css::GOLDENROD);
+            r.fill_path(&Circle::new((cx, cy), size * 0.22).to_path(0.1));
+
+            r.pop_layer();
+        }
+    }
+}
+
+/// Star clip with 50% opacity.
+pub struct CombinedLayersClipOpacity;
+
+impl VelloScene for CombinedLayersClipOpacity {
+    type State = ();
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "combined_layers_clip_opacity",
+            width: 1024,
+            height: 768,
+        }
+    }
+
+    fn setup<R: Renderer>(_r: &mut R) -> Self::State {}
+
+    fn draw<R: Renderer>(_state: &Self::State, r: &mut R) {
+        draw_combined(r, true, None, Some(0.5));
+    }
+}
+
+/// Star clip with a multiply blend.
+pub struct CombinedLayersClipMultiply;
+
+impl VelloScene for CombinedLayersClipMultiply {
+    type State = ();
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "combined_layers_clip_multiply",
+            width: 1024,
+            height: 768,
+        }
+    }
+
+    fn setup<R: Renderer>(_r: &mut R) -> Self::State {}
+
+    fn draw<R: Renderer>(_state: &Self::State, r: &mut R) {
+        let multiply = BlendMode::new(Mix::Multiply, Compose::SrcOver);
+        draw_combined(r, true, Some(multiply), None);
+    }
+}
+
+/// Unclipped screen blend with 60% opacity.
+pub struct CombinedLayersOpacityScreen;
+
+impl VelloScene for CombinedLayersOpacityScreen {
+    type State = ();
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "combined_layers_opacity_screen",
+            width: 1024,
+            height: 768,
+        }
+    }
+
+    fn setup<R: Renderer>(_r: &mut R) -> Self::State {}
+
+    fn draw<R: Renderer>(_state: &Self::State, r: &mut R) {
+        let screen = BlendMode::new(Mix::Screen, Compose::SrcOver);
+        draw_combined(r, false, Some(screen), Some(0.6));
+    }
+}
+
+/// Star clip, multiply blend and 70% opacity in one layer.
+pub struct CombinedLayersAll;
+
+impl VelloScene for CombinedLayersAll {
+    type State = ();
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "combined_layers_all",
+            width: 1024,
+            height: 768,
+        }
+    }
+
+    fn setup<R: Renderer>(_r: &mut R) -> Self::State {}
+
+    fn draw<R: Renderer>(_state: &Self::State, r: &mut R) {
+        let multiply = BlendMode::new(Mix::Multiply, Compose::SrcOver);
+        draw_combined(r, true, Some(multiply), Some(0.7));
+    }
+}
+
+pub(super) fn register(scenes: &mut impl SceneRegistry) {
+    scenes.add::<CombinedLayersClipOpacity>();
+    scenes.add::<CombinedLayersClipMultiply>();
+    scenes.add::<CombinedLayersOpacityScreen>();
+    scenes.add::<CombinedLayersAll>();
+}
@@ -0,0 +1,219 @@
+//! A single filled path at increasing segment counts.
+//!
+//! Each scene fills one closed path of pseudo-random cubics whose points
+//! spread over most of the canvas, so the cost of flattening the path and
+//! generating its strips can be plotted against its segment count:
+//!
+//! - `complex_path_100seg`, `complex_path_1000seg` and
+//!   `complex_path_10000seg`: 100, 1000 and 10 000 segments, non-zero fill.
+//! - `complex_path_1000seg_evenodd`: the 1000-segment path with the even-odd
+//!   fill rule.
+//!
+//! The path only depends on the segment count, so all variants with the same
+//! count fill the same path.
+//!
+//! Two more scenes draw these paths at extreme scales, where coordinates get
+//! large or tiny enough for precision problems to show:
+//!
+//! - `zoomed_in_paths`: the 1000-segment path scaled by [`ZOOM_IN`] around the
+//!   middle of its first segment, so only a sliver of it is on the canvas.
+//! - `zoomed_out_paths`: [`ZOOMED_OUT_COPIES`] copies of the 100-segment path
+//!   scaled by [`ZOOM_OUT`], each under a pixel across, piled up in a few
+//!   pixels in the middle of the canvas.
+//!
+//! `golden coverage` checks that both leave some pixels covered, see
+//! [`check_coverage`](crate::golden::check_coverage).
+
+use super::rng::Lcg;
+use super::{SceneRegistry, VelloScene, VelloSceneInfo};
+use crate::renderer::Renderer;
+use vello_common::kurbo::{Affine, BezPath, ParamCurve, Point, Vec2};
+use vello_common::peniko::Fill;
+use vello_common::peniko::color::palette;
+
+const WIDTH: u16 = 1024;
+const HEIGHT: u16 = 768;
+
+/// Scale of `zoomed_in_paths`.
+const ZOOM_IN: f64 = 1000.0;
+
+/// Scale of `zoomed_out_paths`.
+const ZOOM_OUT: f64 = 0.001;
+
+/// Paths drawn by `zoomed_out_paths`.
+const ZOOMED_OUT_COPIES: u32 = 2000;
+
+/// A closed path of `segments` cubics through pseudo-random points in the
+/// middle 90% of a `width`×`height` canvas.
+fn complex_path(segments: u32, width: f64, height: f64) -> BezPath {
+    let mut rng = Lcg::new(u64::from(segments));
+    let mut point = || {
+        Point::new(
+            width * (0.05 + 0.9 * rng.next_f64()),
+            height * (0.05 + 0.9 * rng.next_f64()),
+        )
+    };
+
+    let mut path = BezPath::new();
+    path.move_to(point());
+    for _ in 0..segments {
+        path.curve_to(point(), point(), point());
+    }
+    path.close_path();
+    path
+}
+
+/// Stamp out a scene filling the complex path of `$segments` segments with
+/// `$fill`.
+macro_rules! complex_path_scene {
+    ($(#[$meta:meta])* $ty:ident, $name:literal, $segments:expr, $fill:expr) => {
+        $(#[$meta])*
+        pub struct $ty;
+
+        impl VelloScene for $ty {
+            type State = BezPath;
+
+            fn info() -> VelloSceneInfo {
+                VelloSceneInfo {
+                    name: $name,
+                    width: WIDTH,
+                    height: HEIGHT,
+                }
+            }
+
+            fn setup<R: Renderer>(r: &mut R) -> Self::State {
+                complex_path($segments, f64::from(r.width()), f64::from(r.height()))
+            }
+
+            fn draw<R: Renderer>(path: &Self::State, r: &mut R) {
+                r.set_fill_rule($fill);
+                r.set_paint(palette::css::DARK_SLATE_BLUE);
+                r.fill_path(path);
+                r.set_fill_rule(Fill::NonZero);
+            }
+
+            const ELEMENTS: Option<u64> = Some($segments);
+        }
+    };
+}
+
+complex_path_scene!(
+    /// A path of 100 cubic segments.
+    ComplexPath100Seg,
+    "complex_path_100seg",
+    100,
+    Fill::NonZero
+);
+complex_path_scene!(
+    /// A path of 1000 cubic segments.
+    ComplexPath1000Seg,
+    "complex_path_1000seg",
+    1000,
+    Fill::NonZero
+);
+complex_path_scene!(
+    /// A path of 10 000 cubic segments.
+    ComplexPath10000Seg,
+    "complex_path_10000seg",
+    10_000,
+    Fill::NonZero
+);
+complex_path_scene!(
+    /// [`ComplexPath1000Seg`] filled with the even-odd rule.
+    ComplexPath1000SegEvenOdd,
+    "complex_path_1000seg_evenodd",
+    1000,
+    Fill::EvenOdd
+);
+
+/// A path and the transform it is drawn with.
+pub struct ZoomedPath {
+    path: BezPath,
+    transform: Affine,
+}
+
+/// [`ComplexPath1000Seg`] zoomed in so far that the canvas shows about a
+/// pixel of it.
+pub struct ZoomedInPaths;
+
+impl VelloScene for ZoomedInPaths {
+    type State = ZoomedPath;
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "zoomed_in_paths",
+            width: WIDTH,
+            height: HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(r: &mut R) -> Self::State {
+        let (width, height) = (f64::from(r.width()), f64::from(r.height()));
+        let path = complex_path(1000, width, height);
+        // An edge runs through the middle of the canvas, so some pixels are
+        // partly covered wherever the inside of the path ends up.
+        let focus = path
+            .segments()
+            .next()
+            .expect("complex paths have segments")
+            .eval(0.5);
+        let transform = Affine::translate((width / 2.0, height / 2.0))
+            * Affine::scale(ZOOM_IN)
+            * Affine::translate(-focus.to_vec2());
+        ZoomedPath { path, transform }
+    }
+
+    fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
+        r.set_transform(state.transform);
+        r.set_paint(palette::css::DARK_SLATE_BLUE);
+        r.fill_path(&state.path);
+        r.set_transform(Affine::IDENTITY);
+    }
+
+    const ELEMENTS: Option<u64> = Some(1000);
+}
+
+/// [`ZOOMED_OUT_COPIES`] copies of [`ComplexPath100Seg`], zoomed out to
+/// under a pixel each and offset by half pixels within an 8×8 px block.
+pub struct ZoomedOutPaths;
+
+impl VelloScene for ZoomedOutPaths {
+    type State = BezPath;
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "zoomed_out_paths",
+            width: WIDTH,
+            height: HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(r: &mut R) -> Self::State {
+        complex_path(100, f64::from(r.width()), f64::from(r.height()))
+    }
+
+    fn draw<R: Renderer>(path: &Self::State, r: &mut R) {
+        let corner = Vec2::new(
+            f64::from(r.width()) / 2.0 - 4.0,
+            f64::from(r.height()) / 2.0 - 4.0,
+        );
+        r.set_paint(palette::css::DARK_SLATE_BLUE);
+        for i in 0..ZOOMED_OUT_COPIES {
+            let offset = Vec2::new(f64::from(i % 16), f64::from(i / 16 % 16)) * 0.5;
+            r.set_transform(Affine::translate(corner + offset) * Affine::scale(ZOOM_OUT));
+            r.fill_path(path);
+        }
+        r.set_transform(Affine::IDENTITY);
+    }
+
+    const ELEMENTS: Option<u64> = Some(ZOOMED_OUT_COPIES as u64 * 100);
+}
+
+pub(super) fn register(scenes: &mut impl SceneRegistry) {
+    scenes.add::<ComplexPath100Seg>();
+    scenes.add::<ComplexPath1000Seg>();
+    scenes.add::<ComplexPath10000Seg>();
+    scenes.add::<ComplexPath1000SegEvenOdd>();
+    scenes.add::<ZoomedInPaths>();
+    scenes.add::<ZoomedOutPaths>();
+}
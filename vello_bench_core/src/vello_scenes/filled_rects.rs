@@ -1,6 +1,6 @@
 //! A simple filled-rectangles scene with no images.
 
-use super::{VelloScene, VelloSceneInfo};
+use super::{SceneRegistry, VelloScene, VelloSceneInfo};
 use crate::renderer::Renderer;
 use vello_common::kurbo::Rect;
 use vello_common::peniko::color::palette;
@@ -50,3 +50,7 @@ impl VelloScene for FilledRects {
         }
     }
 }
+
+pub(super) fn register(scenes: &mut impl SceneRegistry) {
+    scenes.add::<FilledRects>();
+}
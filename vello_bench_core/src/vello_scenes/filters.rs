@@ -0,0 +1,351 @@
+//! Groups drawn through filter layers.
+//!
+//! Filters whose parameters change from element to element, and from frame to
+//! frame, defeat any caching of filter results, as in animated blur effects.
+//! `filters_animated_50` and `filters_static_50` are timed per iteration so
+//! that frame-to-frame variance, such as caches warming up, shows up in the
+//! individual iteration times.
+//!
+//! The other scenes draw UI-like content over an opaque backdrop:
+//!
+//! - `blurred_cards_100`: 100 cards, each in its own gaussian blur layer.
+//! - `drop_shadow_rects_300`: 300 rounded rects, each in its own drop shadow
+//!   layer.
+//! - `blur_radius_sweep_<n>`: the same 20 cards in a single blur layer with a
+//!   standard deviation of 1, 4, 16 or 64 pixels, to see how the cost scales
+//!   with the radius. `blur_radius_sweep_0` draws them without a filter layer
+//!   as the baseline, and `blur_radius_sweep_16` must render differently from
+//!   it, see [`check_effect_sanity`](crate::golden::check_effect_sanity).
+
+use super::layer_stack::{draw_backdrop, grid_cell};
+use super::{SceneRegistry, VelloScene, VelloSceneInfo};
+use crate::renderer::Renderer;
+use vello_common::color::{AlphaColor, Srgb};
+use vello_common::filter_effects::{EdgeMode, Filter, FilterPrimitive};
+use vello_common::kurbo::{Circle, Rect, RoundedRect, Shape};
+use vello_common::peniko::color::palette;
+
+const WIDTH: u16 = 1024;
+const HEIGHT: u16 = 768;
+
+/// Number of filter groups drawn per frame.
+const GROUP_COUNT: u64 = 50;
+
+/// Number of distinct blur radii. Coprime with [`RADIUS_STRIDE`], so the 50
+/// groups of a frame all get different radii.
+const RADIUS_STEPS: u64 = 50;
+const RADIUS_STRIDE: u64 = 7;
+
+/// Blur standard deviation of `group` in frame `frame_index`, between 1 and
+/// about 8.4 pixels.
+fn blur_std_deviation(group: u64, frame_index: u64) -> f32 {
+    let step = (group * RADIUS_STRIDE + frame_index) % RADIUS_STEPS;
+    1.0 + step as f32 * 0.15
+}
+
+/// Draw [`GROUP_COUNT`] groups of a rect and a circle in a 10×5 grid, each
+/// through its own blur filter layer.
+fn draw_filter_groups<R: Renderer>(r: &mut R, frame_index: u64) {
+    let colors = [
+        palette::css::CRIMSON,
+        palette::css::SEA_GREEN,
+        palette::css::ROYAL_BLUE,
+        palette::css::GOLDENROD,
+        palette::css::DARK_ORCHID,
+    ];
+
+    let cols = 10u64;
+    let rows = GROUP_COUNT.div_ceil(cols);
+    let cell_w = f64::from(r.width()) / cols as f64;
+    let cell_h = f64::from(r.height()) / rows as f64;
+
+    for group in 0..GROUP_COUNT {
+        let x = (group % cols) as f64 * cell_w;
+        let y = (group / cols) as f64 * cell_h;
+
+        r.push_filter_layer(blur(blur_std_deviation(group, frame_index)));
+
+        r.set_paint(colors[group as usize % colors.len()]);
+        r.fill_rect(&Rect::new(
+            x + cell_w * 0.15,
+            y + cell_h * 0.15,
+            x + cell_w * 0.65,
+            y + cell_h * 0.65,
+        ));
+        r.set_paint(colors[(group as usize + 2) % colors.len()]);
+        r.fill_path(
+            &Circle::new(
+                (x + cell_w * 0.6, y + cell_h * 0.6),
+                cell_w.min(cell_h) * 0.25,
+            )
+            .to_path(0.1),
+        );
+
+        r.pop_layer();
+    }
+}
+
+/// 50 blurred groups whose blur radii differ per group and change every frame.
+pub struct FiltersAnimated50;
+
+impl VelloScene for FiltersAnimated50 {
+    type State = ();
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "filters_animated_50",
+            width: WIDTH,
+            height: HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(_r: &mut R) -> Self::State {}
+
+    fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
+        Self::draw_frame(state, r, 0);
+    }
+
+    fn draw_frame<R: Renderer>(_state: &Self::State, r: &mut R, frame_index: u64) {
+        draw_filter_groups(r, frame_index);
+    }
+
+    const PER_ITERATION: bool = true;
+    const ANIMATED: bool = true;
+}
+
+/// Control for [`FiltersAnimated50`]: the same groups and per-group radii as
+/// its first frame, identical in every frame.
+pub struct FiltersStatic50;
+
+impl VelloScene for FiltersStatic50 {
+    type State = ();
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "filters_static_50",
+            width: WIDTH,
+            height: HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(_r: &mut R) -> Self::State {}
+
+    fn draw<R: Renderer>(_state: &Self::State, r: &mut R) {
+        draw_filter_groups(r, 0);
+    }
+
+    const PER_ITERATION: bool = true;
+}
+
+/// A gaussian blur with `std_deviation`, in pixels.
+fn blur(std_deviation: f32) -> Filter {
+    Filter::from_primitive(FilterPrimitive::GaussianBlur {
+        std_deviation,
+        edge_mode: EdgeMode::None,
+    })
+}
+
+/// Draw a card filling `rect`: a rounded panel in `color` with a darker
+/// header and three lines of placeholder text.
+fn draw_card<R: Renderer>(r: &mut R, rect: Rect, color: AlphaColor<Srgb>) {
+    let radius = rect.width().min(rect.height()) * 0.08;
+    r.set_paint(color);
+    r.fill_path(&RoundedRect::from_rect(rect, radius).to_path(0.1));
+
+    let pad = rect.width() * 0.08;
+    let line_h = rect.height() * 0.08;
+    r.set_paint(palette::css::DARK_SLATE_GRAY);
+    r.fill_rect(&Rect::new(
+        rect.x0 + pad,
+        rect.y0 + pad,
+        rect.x1 - pad,
+        rect.y0 + pad + line_h * 2.0,
+    ));
+    r.set_paint(palette::css::WHITE);
+    for line in 0..3 {
+        let y = rect.y0 + pad + line_h * (3.5 + 1.8 * f64::from(line));
+        // Lines get shorter towards the bottom, like a paragraph.
+        let x1 = rect.x1 - pad - rect.width() * 0.15 * f64::from(line);
+        r.fill_rect(&Rect::new(rect.x0 + pad, y, x1, y + line_h));
+    }
+}
+
+const CARD_COLORS: [AlphaColor<Srgb>; 4] = [
+    palette::css::LIGHT_SKY_BLUE,
+    palette::css::LIGHT_CORAL,
+    palette::css::PALE_GREEN,
+    palette::css::KHAKI,
+];
+
+/// Cards of `blurred_cards_100`.
+const BLURRED_CARDS: u32 = 100;
+
+/// 100 cards in a grid, each in its own gaussian blur layer.
+pub struct BlurredCards100;
+
+impl VelloScene for BlurredCards100 {
+    type State = ();
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "blurred_cards_100",
+            width: WIDTH,
+            height: HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(_r: &mut R) -> Self::State {}
+
+    fn draw<R: Renderer>(_state: &Self::State, r: &mut R) {
+        draw_backdrop(r);
+
+        for i in 0..BLURRED_CARDS {
+            let cell = grid_cell(r, BLURRED_CARDS, i);
+            r.push_filter_layer(blur(3.0));
+            draw_card(
+                r,
+                cell.inset(-cell.width() * 0.1),
+                CARD_COLORS[i as usize % CARD_COLORS.len()],
+            );
+            r.pop_layer();
+        }
+    }
+
+    const ELEMENTS: Option<u64> = Some(BLURRED_CARDS as u64);
+}
+
+/// Rects of `drop_shadow_rects_300`.
+const SHADOW_RECTS: u32 = 300;
+
+/// 300 rounded rects in a grid, each in its own drop shadow layer.
+pub struct DropShadowRects300;
+
+impl VelloScene for DropShadowRects300 {
+    type State = ();
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "drop_shadow_rects_300",
+            width: WIDTH,
+            height: HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(_r: &mut R) -> Self::State {}
+
+    fn draw<R: Renderer>(_state: &Self::State, r: &mut R) {
+        draw_backdrop(r);
+
+        for i in 0..SHADOW_RECTS {
+            let cell = grid_cell(r, SHADOW_RECTS, i);
+            let size = cell.width().min(cell.height());
+            r.push_filter_layer(Filter::from_primitive(FilterPrimitive::DropShadow {
+                dx: 3.0,
+                dy: 4.0,
+                std_deviation: 3.0,
+                color: palette::css::BLACK.with_alpha(0.5),
+                edge_mode: EdgeMode::None,
+            }));
+            r.set_paint(CARD_COLORS[i as usize % CARD_COLORS.len()]);
+            r.fill_path(&RoundedRect::from_rect(cell.inset(-size * 0.2), size * 0.1).to_path(0.1));
+            r.pop_layer();
+        }
+    }
+
+    const ELEMENTS: Option<u64> = Some(SHADOW_RECTS as u64);
+}
+
+/// Cards of each `blur_radius_sweep_<n>` scene.
+const SWEEP_CARDS: u32 = 20;
+
+/// Draw the cards of the blur radius sweep, in a single blur layer with
+/// `std_deviation` unless it is zero.
+fn draw_blur_sweep<R: Renderer>(r: &mut R, std_deviation: f32) {
+    draw_backdrop(r);
+
+    if std_deviation > 0.0 {
+        r.push_filter_layer(blur(std_deviation));
+    }
+    for i in 0..SWEEP_CARDS {
+        let cell = grid_cell(r, SWEEP_CARDS, i);
+        draw_card(
+            r,
+            cell.inset(-cell.width() * 0.1),
+            CARD_COLORS[i as usize % CARD_COLORS.len()],
+        );
+    }
+    if std_deviation > 0.0 {
+        r.pop_layer();
+    }
+}
+
+/// Stamp out a blur radius sweep scene blurring with `$std_deviation`.
+macro_rules! blur_sweep_scene {
+    ($(#[$meta:meta])* $ty:ident, $name:literal, $std_deviation:expr) => {
+        $(#[$meta])*
+        pub struct $ty;
+
+        impl VelloScene for $ty {
+            type State = ();
+
+            fn info() -> VelloSceneInfo {
+                VelloSceneInfo {
+                    name: $name,
+                    width: WIDTH,
+                    height: HEIGHT,
+                }
+            }
+
+            fn setup<R: Renderer>(_r: &mut R) -> Self::State {}
+
+            fn draw<R: Renderer>(_state: &Self::State, r: &mut R) {
+                draw_blur_sweep(r, $std_deviation);
+            }
+
+            const ELEMENTS: Option<u64> = Some(SWEEP_CARDS as u64);
+        }
+    };
+}
+
+blur_sweep_scene!(
+    /// The sweep's cards without blur; the baseline of the sweep.
+    BlurRadiusSweep0,
+    "blur_radius_sweep_0",
+    0.0
+);
+blur_sweep_scene!(
+    /// The sweep's cards blurred with a standard deviation of 1 pixel.
+    BlurRadiusSweep1,
+    "blur_radius_sweep_1",
+    1.0
+);
+blur_sweep_scene!(
+    /// The sweep's cards blurred with a standard deviation of 4 pixels.
+    BlurRadiusSweep4,
+    "blur_radius_sweep_4",
+    4.0
+);
+blur_sweep_scene!(
+    /// The sweep's cards blurred with a standard deviation of 16 pixels.
+    BlurRadiusSweep16,
+    "blur_radius_sweep_16",
+    16.0
+);
+blur_sweep_scene!(
+    /// The sweep's cards blurred with a standard deviation of 64 pixels.
+    BlurRadiusSweep64,
+    "blur_radius_sweep_64",
+    64.0
+);
+
+pub(super) fn register(scenes: &mut impl SceneRegistry) {
+    scenes.add::<FiltersAnimated50>();
+    scenes.add::<FiltersStatic50>();
+    scenes.add::<BlurredCards100>();
+    scenes.add::<DropShadowRects300>();
+    scenes.add::<BlurRadiusSweep0>();
+    scenes.add::<BlurRadiusSweep1>();
+    scenes.add::<BlurRadiusSweep4>();
+    scenes.add::<BlurRadiusSweep16>();
+    scenes.add::<BlurRadiusSweep64>();
+}
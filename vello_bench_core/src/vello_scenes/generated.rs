@@ -0,0 +1,700 @@
+//! Seeded random scenes for fuzz-style benchmarking.
+//!
+//! The hand-written scenes cover the combinations we thought of. These are
+//! generated from a `u64` seed and a size budget instead: a deterministic
+//! random sequence of fills, strokes, images, glyph runs and clip, blend and
+//! opacity layers, with parameters drawn from distributions that resemble
+//! real content (mostly small shapes, mostly opaque colors, mostly untransformed,
+//! shallow layer nesting).
+//!
+//! A few fixed seeds are registered as `generated_<seed>_<budget>` scenes, so
+//! they get golden hashes and take part in the CPU/hybrid parity check like
+//! any other scene. Any other seed can be run ad hoc under the same naming
+//! scheme: [`find_scene`](super::find_scene) accepts every well-formed name,
+//! the CLI takes `run --generated SEED:BUDGET` and the WASM API has
+//! `generated_benchmarks`. When a seed exposes a backend divergence or a
+//! performance cliff, the name alone reproduces it, and lowering the budget
+//! minimizes it: the first `n` operations of a seed don't depend on the
+//! budget.
+//!
+//! The operations are generated during setup, so the random number generator
+//! is not part of the measurement. Glyph runs use the embedded DejaVu Sans
+//! Mono with random glyph IDs; there is no shaping.
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use rand::prelude::StdRng;
+use rand::{Rng, SeedableRng};
+use smallvec::SmallVec;
+use vello_common::color::{AlphaColor, DynamicColor, Srgb};
+use vello_common::glyph::Glyph;
+use vello_common::kurbo::{
+    Affine, BezPath, Cap, Ellipse, Join, Point, Rect, RoundedRect, Shape, Stroke, Vec2,
+};
+use vello_common::paint::{Image, ImageSource};
+use vello_common::peniko::color::PremulRgba8;
+use vello_common::peniko::{
+    BlendMode, Blob, ColorStop, ColorStops, Compose, Extend, Fill, FontData, Gradient,
+    ImageSampler, LinearGradientPosition, Mix, RadialGradientPosition,
+};
+use vello_common::pixmap::Pixmap;
+
+use super::{SceneRegistry, VelloScene, VelloSceneInfo};
+use crate::renderer::Renderer;
+
+pub(super) static FONT_BYTES: &[u8] = include_bytes!("../../assets/DejaVuSansMono.ttf");
+
+const WIDTH: u16 = 1024;
+const HEIGHT: u16 = 768;
+
+/// Deepest nesting of layers.
+const MAX_LAYER_DEPTH: usize = 4;
+
+/// Sizes of the images generated during setup.
+const IMAGE_SIZES: [(u16, u16); 4] = [(16, 16), (64, 48), (200, 150), (256, 256)];
+
+/// Blend modes used by blend layers.
+const MIXES: [Mix; 6] = [
+    Mix::Multiply,
+    Mix::Screen,
+    Mix::Overlay,
+    Mix::Darken,
+    Mix::Lighten,
+    Mix::Difference,
+];
+
+/// Seed and size budget of a generated scene.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeneratedParams {
+    pub seed: u64,
+    /// Number of operations: fills, strokes, images, glyph runs, and layer
+    /// pushes and pops. Layers still open at the end are closed on top.
+    pub budget: u32,
+}
+
+impl GeneratedParams {
+    /// Largest accepted budget, so that a typo can't exhaust memory.
+    pub const MAX_BUDGET: u32 = 100_000;
+
+    /// Scene name, `generated_<seed>_<budget>`.
+    pub fn name(self) -> String {
+        format!("generated_{}_{}", self.seed, self.budget)
+    }
+
+    /// Parse a scene name produced by [`Self::name`]. Returns `None` for
+    /// other names, non-canonical numbers (e.g. leading zeros) and budgets
+    /// of zero or above [`Self::MAX_BUDGET`].
+    pub fn parse(name: &str) -> Option<Self> {
+        let (seed, budget) = name.strip_prefix("generated_")?.split_once('_')?;
+        let params = Self {
+            seed: seed.parse().ok()?,
+            budget: budget.parse().ok()?,
+        };
+        (params.budget > 0 && params.budget <= Self::MAX_BUDGET && params.name() == name)
+            .then_some(params)
+    }
+
+    /// Check that `budget` is between 1 and [`Self::MAX_BUDGET`].
+    pub fn new(seed: u64, budget: u32) -> Result<Self, String> {
+        if budget == 0 || budget > Self::MAX_BUDGET {
+            return Err(format!(
+                "Generated scene budget must be between 1 and {}",
+                Self::MAX_BUDGET
+            ));
+        }
+        Ok(Self { seed, budget })
+    }
+
+    /// Parse the `SEED:BUDGET` form used on the command line.
+    pub fn from_spec(spec: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid generated scene '{spec}', expected SEED:BUDGET");
+        let (seed, budget) = spec.split_once(':').ok_or_else(invalid)?;
+        Self::new(
+            seed.parse().map_err(|_| invalid())?,
+            budget.parse().map_err(|_| invalid())?,
+        )
+    }
+
+    pub(super) fn info(self) -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: intern(self.name()),
+            width: WIDTH,
+            height: HEIGHT,
+        }
+    }
+}
+
+/// A static copy of `name`. Scene names are `&'static str`, so names of
+/// generated and parameterized scenes are leaked, once per distinct name.
+pub(super) fn intern(name: String) -> &'static str {
+    static NAMES: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+    let mut names = NAMES.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(&interned) = names.iter().find(|n| **n == name) {
+        return interned;
+    }
+    let interned: &'static str = Box::leak(name.into_boxed_str());
+    names.push(interned);
+    interned
+}
+
+/// A generated scene with fixed parameters.
+pub struct Generated<const SEED: u64, const BUDGET: u32>;
+
+impl<const SEED: u64, const BUDGET: u32> Generated<SEED, BUDGET> {
+    const PARAMS: GeneratedParams = GeneratedParams {
+        seed: SEED,
+        budget: BUDGET,
+    };
+}
+
+impl<const SEED: u64, const BUDGET: u32> VelloScene for Generated<SEED, BUDGET> {
+    type State = GeneratedState;
+
+    fn info() -> VelloSceneInfo {
+        Self::PARAMS.info()
+    }
+
+    fn setup<R: Renderer>(r: &mut R) -> Self::State {
+        setup(Self::PARAMS, r)
+    }
+
+    fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
+        draw(state, r);
+    }
+}
+
+// ===========================================================================
+// Operations
+// ===========================================================================
+
+enum Geometry {
+    Rect(Rect),
+    Path(BezPath),
+}
+
+enum Paint {
+    Solid(AlphaColor<Srgb>),
+    Gradient(Gradient),
+    /// An uploaded image, with its origin placed at `origin`.
+    Image {
+        index: usize,
+        origin: Point,
+    },
+}
+
+enum Op {
+    Fill {
+        geometry: Geometry,
+        paint: Paint,
+        fill_rule: Fill,
+        transform: Affine,
+    },
+    Stroke {
+        geometry: Geometry,
+        paint: Paint,
+        stroke: Stroke,
+        transform: Affine,
+    },
+    /// An uploaded image, drawn at its own size through `transform`.
+    Image {
+        index: usize,
+        transform: Affine,
+    },
+    Glyphs {
+        glyphs: Vec<Glyph>,
+        font_size: f32,
+        paint: Paint,
+        /// Stroke the outlines instead of filling them.
+        stroke: Option<Stroke>,
+        transform: Affine,
+    },
+    PushLayer {
+        clip: Option<BezPath>,
+        blend_mode: Option<BlendMode>,
+        opacity: Option<f32>,
+    },
+    PopLayer,
+}
+
+/// Operations of a generated scene, plus the resources they refer to.
+pub struct GeneratedState {
+    ops: Vec<Op>,
+    images: Vec<(ImageSource, u16, u16)>,
+    font: FontData,
+}
+
+// ===========================================================================
+// Generation
+// ===========================================================================
+
+/// Generate the scene for `params` and upload its images.
+pub(super) fn setup<R: Renderer>(params: GeneratedParams, r: &mut R) -> GeneratedState {
+    let mut rng = StdRng::seed_from_u64(params.seed);
+
+    let images = IMAGE_SIZES
+        .iter()
+        .map(|&(w, h)| {
+            let pixmap = random_pixmap(&mut rng, w, h);
+            (r.get_image_source(Arc::new(pixmap)), w, h)
+        })
+        .collect();
+
+    let mut generator = Generator {
+        rng,
+        width: f64::from(WIDTH),
+        height: f64::from(HEIGHT),
+        glyph_count: glyph_count(FONT_BYTES),
+    };
+
+    GeneratedState {
+        ops: generator.ops(params.budget),
+        images,
+        font: FontData::new(Blob::new(Arc::new(FONT_BYTES)), 0),
+    }
+}
+
+/// A smooth color ramp, with a transparent checkerboard over half of the
+/// images.
+fn random_pixmap(rng: &mut StdRng, width: u16, height: u16) -> Pixmap {
+    let from: [f32; 3] = rng.random();
+    let to: [f32; 3] = rng.random();
+    let checkered = rng.random_bool(0.5);
+    let cell = rng.random_range(2..16_u16);
+
+    let mut pixels = Vec::with_capacity(usize::from(width) * usize::from(height));
+    for y in 0..height {
+        for x in 0..width {
+            let t = f32::from(x + y) / f32::from(width + height);
+            let alpha = if checkered && (x / cell + y / cell) % 2 == 1 {
+                0.25
+            } else {
+                1.0
+            };
+            let channel = |i: usize| {
+                let value = from[i] + (to[i] - from[i]) * t;
+                (value * alpha * 255.0).round() as u8
+            };
+            pixels.push(PremulRgba8 {
+                r: channel(0),
+                g: channel(1),
+                b: channel(2),
+                a: (alpha * 255.0).round() as u8,
+            });
+        }
+    }
+
+    Pixmap::from_parts(pixels, width, height)
+}
+
+/// Number of glyphs in an OpenType font, from its `maxp` table.
+fn glyph_count(font: &[u8]) -> u32 {
+    let read_u16 = |at: usize| u16::from_be_bytes([font[at], font[at + 1]]);
+    let read_u32 =
+        |at: usize| u32::from_be_bytes([font[at], font[at + 1], font[at + 2], font[at + 3]]);
+
+    let tables = usize::from(read_u16(4));
+    (0..tables)
+        .map(|i| 12 + i * 16)
+        .find(|&record| &font[record..record + 4] == b"maxp")
+        .map(|record| u32::from(read_u16(read_u32(record + 8) as usize + 4)))
+        .expect("embedded font has no maxp table")
+}
+
+struct Generator {
+    rng: StdRng,
+    width: f64,
+    height: f64,
+    glyph_count: u32,
+}
+
+impl Generator {
+    fn ops(&mut self, budget: u32) -> Vec<Op> {
+        let mut ops = Vec::with_capacity(budget as usize);
+        let mut depth = 0;
+
+        for _ in 0..budget {
+            let op = match self.rng.random_range(0..100) {
+                0..40 => self.fill(),
+                40..60 => self.stroke(),
+                60..68 => self.image(),
+                68..76 => self.glyphs(),
+                76..88 if depth < MAX_LAYER_DEPTH => {
+                    depth += 1;
+                    self.layer()
+                }
+                88..100 if depth > 0 => {
+                    depth -= 1;
+                    Op::PopLayer
+                }
+                _ => self.fill(),
+            };
+            ops.push(op);
+        }
+        ops.extend((0..depth).map(|_| Op::PopLayer));
+
+        ops
+    }
+
+    fn fill(&mut self) -> Op {
+        let (geometry, center, size) = self.geometry();
+        Op::Fill {
+            paint: self.paint(center, size),
+            fill_rule: if self.rng.random_bool(0.1) {
+                Fill::EvenOdd
+            } else {
+                Fill::NonZero
+            },
+            transform: self.transform(center),
+            geometry,
+        }
+    }
+
+    fn stroke(&mut self) -> Op {
+        let (geometry, center, size) = self.geometry();
+        Op::Stroke {
+            paint: self.paint(center, size),
+            stroke: self.stroke_style(),
+            transform: self.transform(center),
+            geometry,
+        }
+    }
+
+    fn image(&mut self) -> Op {
+        let index = self.rng.random_range(0..IMAGE_SIZES.len());
+        let (w, h) = IMAGE_SIZES[index];
+        let origin = self.point();
+        let scale = self.log_uniform(0.25, 4.0);
+        let center = origin + Vec2::new(f64::from(w), f64::from(h)) * (scale * 0.5);
+        Op::Image {
+            index,
+            transform: self.transform(center)
+                * Affine::translate(origin.to_vec2())
+                * Affine::scale(scale),
+        }
+    }
+
+    fn glyphs(&mut self) -> Op {
+        let font_size = self.log_uniform(6.0, 96.0) as f32;
+        let origin = self.point();
+        let advance = font_size * 0.6;
+        let count = self.rng.random_range(1..=40_u16);
+        let glyphs = (0..count)
+            .map(|i| Glyph {
+                id: self.rng.random_range(1..self.glyph_count),
+                x: origin.x as f32 + advance * i as f32,
+                y: origin.y as f32,
+            })
+            .collect();
+
+        let size = Vec2::new(f64::from(advance) * f64::from(count), f64::from(font_size));
+        Op::Glyphs {
+            glyphs,
+            font_size,
+            paint: self.paint(origin + size * 0.5, size),
+            stroke: self.rng.random_bool(0.2).then(|| self.stroke_style()),
+            transform: self.transform(origin),
+        }
+    }
+
+    fn layer(&mut self) -> Op {
+        // At least one of clip, blend and opacity; sometimes all of them.
+        let (clip, blend, opacity) = match self.rng.random_range(0..10) {
+            0..4 => (true, false, false),
+            4..7 => (false, false, true),
+            7..9 => (false, true, false),
+            _ => (true, true, true),
+        };
+        Op::PushLayer {
+            clip: clip.then(|| match self.geometry().0 {
+                Geometry::Rect(rect) => rect.to_path(0.1),
+                Geometry::Path(path) => path,
+            }),
+            blend_mode: blend.then(|| {
+                let mix = MIXES[self.rng.random_range(0..MIXES.len())];
+                BlendMode::new(mix, Compose::SrcOver)
+            }),
+            opacity: opacity.then(|| self.rng.random_range(0.2..0.9)),
+        }
+    }
+
+    /// A random shape, with its center and bounding size.
+    fn geometry(&mut self) -> (Geometry, Point, Vec2) {
+        let center = self.point();
+        let extent = self.extent();
+        let aspect = self.log_uniform(0.25, 4.0);
+        let size = Vec2::new(extent * aspect.sqrt(), extent / aspect.sqrt());
+        let rect = Rect::from_center_size(center, size.to_size());
+
+        let geometry = match self.rng.random_range(0..100) {
+            0..35 => Geometry::Rect(rect),
+            35..55 => {
+                let radius = self.rng.random_range(0.0..0.5) * size.x.min(size.y);
+                Geometry::Path(RoundedRect::from_rect(rect, radius).to_path(0.1))
+            }
+            55..75 => {
+                let rotation = self.rng.random_range(0.0..std::f64::consts::PI);
+                Geometry::Path(Ellipse::new(center, size * 0.5, rotation).to_path(0.1))
+            }
+            75..90 => Geometry::Path(self.polygon(center, size * 0.5)),
+            _ => Geometry::Path(self.blob(center, size * 0.5)),
+        };
+        (geometry, center, size)
+    }
+
+    /// A closed polygon with vertices at random angles and distances, which
+    /// may intersect itself.
+    fn polygon(&mut self, center: Point, radii: Vec2) -> BezPath {
+        let mut path = BezPath::new();
+        for i in 0..self.rng.random_range(3..12) {
+            let point = self.point_around(center, radii);
+            if i == 0 {
+                path.move_to(point);
+            } else {
+                path.line_to(point);
+            }
+        }
+        path.close_path();
+        path
+    }
+
+    /// A closed path of cubic curves through random points.
+    fn blob(&mut self, center: Point, radii: Vec2) -> BezPath {
+        let mut path = BezPath::new();
+        path.move_to(self.point_around(center, radii));
+        for _ in 0..self.rng.random_range(2..8) {
+            let c1 = self.point_around(center, radii);
+            let c2 = self.point_around(center, radii);
+            let end = self.point_around(center, radii);
+            path.curve_to(c1, c2, end);
+        }
+        path.close_path();
+        path
+    }
+
+    fn paint(&mut self, center: Point, size: Vec2) -> Paint {
+        match self.rng.random_range(0..100) {
+            0..80 => Paint::Solid(self.color()),
+            80..95 => Paint::Gradient(self.gradient(center, size)),
+            _ => Paint::Image {
+                index: self.rng.random_range(0..IMAGE_SIZES.len()),
+                origin: center - size * 0.5,
+            },
+        }
+    }
+
+    fn gradient(&mut self, center: Point, size: Vec2) -> Gradient {
+        let radius = size.length() * 0.5;
+        let kind = if self.rng.random_bool(0.5) {
+            LinearGradientPosition {
+                start: self.point_around(center, size * 0.5),
+                end: self.point_around(center, size * 0.5),
+            }
+            .into()
+        } else {
+            RadialGradientPosition {
+                start_center: center,
+                start_radius: 0.0,
+                end_center: self.point_around(center, size * 0.1),
+                end_radius: (radius * self.rng.random_range(0.2..1.0)) as f32,
+            }
+            .into()
+        };
+
+        let count = self.rng.random_range(2..=5);
+        let mut offsets: SmallVec<[f32; 5]> = (0..count).map(|_| self.rng.random()).collect();
+        offsets.sort_by(f32::total_cmp);
+        let stops = offsets
+            .into_iter()
+            .map(|offset| ColorStop {
+                offset,
+                color: DynamicColor::from_alpha_color(self.color()),
+            })
+            .collect();
+
+        Gradient {
+            kind,
+            stops: ColorStops(stops),
+            extend: [Extend::Pad, Extend::Repeat, Extend::Reflect][self.rng.random_range(0..3)],
+            ..Default::default()
+        }
+    }
+
+    fn stroke_style(&mut self) -> Stroke {
+        let join = [Join::Miter, Join::Round, Join::Bevel][self.rng.random_range(0..3)];
+        let cap = [Cap::Butt, Cap::Round, Cap::Square][self.rng.random_range(0..3)];
+        let stroke = Stroke::new(self.log_uniform(0.25, 20.0))
+            .with_join(join)
+            .with_caps(cap);
+        if self.rng.random_bool(0.15) {
+            let on = self.log_uniform(1.0, 20.0);
+            let off = self.log_uniform(1.0, 20.0);
+            stroke.with_dashes(0.0, [on, off])
+        } else {
+            stroke
+        }
+    }
+
+    /// Mostly untransformed, sometimes rotated about `center`, rarely skewed
+    /// as well.
+    fn transform(&mut self, center: Point) -> Affine {
+        match self.rng.random_range(0..10) {
+            0..7 => Affine::IDENTITY,
+            7..9 => Affine::rotate_about(self.rng.random_range(0.0..std::f64::consts::TAU), center),
+            _ => {
+                let skew = Affine::skew(
+                    self.rng.random_range(-0.5..0.5),
+                    self.rng.random_range(-0.5..0.5),
+                );
+                Affine::translate(center.to_vec2()) * skew * Affine::translate(-center.to_vec2())
+            }
+        }
+    }
+
+    /// Mostly opaque, otherwise anywhere from faint to opaque.
+    fn color(&mut self) -> AlphaColor<Srgb> {
+        let [r, g, b]: [f32; 3] = self.rng.random();
+        let alpha = if self.rng.random_bool(0.7) {
+            1.0
+        } else {
+            self.rng.random_range(0.1..1.0)
+        };
+        AlphaColor::new([r, g, b, alpha])
+    }
+
+    /// A point on the canvas or slightly outside of it.
+    fn point(&mut self) -> Point {
+        Point::new(
+            self.rng.random_range(-0.1..1.1) * self.width,
+            self.rng.random_range(-0.1..1.1) * self.height,
+        )
+    }
+
+    fn point_around(&mut self, center: Point, radii: Vec2) -> Point {
+        center
+            + Vec2::new(
+                self.rng.random_range(-1.0..=1.0) * radii.x,
+                self.rng.random_range(-1.0..=1.0) * radii.y,
+            )
+    }
+
+    /// Shape size: log-uniform, so small shapes are far more common than
+    /// large ones.
+    fn extent(&mut self) -> f64 {
+        self.log_uniform(2.0, self.width.min(self.height) * 0.8)
+    }
+
+    fn log_uniform(&mut self, low: f64, high: f64) -> f64 {
+        (low.ln() + self.rng.random::<f64>() * (high.ln() - low.ln())).exp()
+    }
+}
+
+// ===========================================================================
+// Drawing
+// ===========================================================================
+
+fn draw<R: Renderer>(state: &GeneratedState, r: &mut R) {
+    for op in &state.ops {
+        match op {
+            Op::Fill {
+                geometry,
+                paint,
+                fill_rule,
+                transform,
+            } => {
+                r.set_transform(*transform);
+                r.set_fill_rule(*fill_rule);
+                set_paint(state, r, paint);
+                match geometry {
+                    Geometry::Rect(rect) => r.fill_rect(rect),
+                    Geometry::Path(path) => r.fill_path(path),
+                }
+            }
+            Op::Stroke {
+                geometry,
+                paint,
+                stroke,
+                transform,
+            } => {
+                r.set_transform(*transform);
+                r.set_stroke(stroke.clone());
+                set_paint(state, r, paint);
+                match geometry {
+                    Geometry::Rect(rect) => r.stroke_rect(rect),
+                    Geometry::Path(path) => r.stroke_path(path),
+                }
+            }
+            Op::Image { index, transform } => {
+                let (image, w, h) = &state.images[*index];
+                r.set_transform(*transform);
+                r.set_paint_transform(Affine::IDENTITY);
+                r.set_paint(Image {
+                    image: image.clone(),
+                    sampler: ImageSampler::default(),
+                });
+                r.fill_rect(&Rect::new(0.0, 0.0, f64::from(*w), f64::from(*h)));
+            }
+            Op::Glyphs {
+                glyphs,
+                font_size,
+                paint,
+                stroke,
+                transform,
+            } => {
+                r.set_transform(*transform);
+                set_paint(state, r, paint);
+                match stroke {
+                    Some(stroke) => {
+                        r.set_stroke(stroke.clone());
+                        r.glyph_run(&state.font)
+                            .font_size(*font_size)
+                            .stroke_glyphs(glyphs.iter().copied());
+                    }
+                    None => r
+                        .glyph_run(&state.font)
+                        .font_size(*font_size)
+                        .fill_glyphs(glyphs.iter().copied()),
+                }
+            }
+            Op::PushLayer {
+                clip,
+                blend_mode,
+                opacity,
+            } => {
+                r.set_transform(Affine::IDENTITY);
+                r.push_layer(clip.as_ref(), *blend_mode, *opacity, None, None);
+            }
+            Op::PopLayer => r.pop_layer(),
+        }
+    }
+
+    r.set_transform(Affine::IDENTITY);
+    r.set_paint_transform(Affine::IDENTITY);
+    r.set_fill_rule(Fill::NonZero);
+}
+
+fn set_paint<R: Renderer>(state: &GeneratedState, r: &mut R, paint: &Paint) {
+    match paint {
+        Paint::Solid(color) => {
+            r.set_paint_transform(Affine::IDENTITY);
+            r.set_paint(*color);
+        }
+        Paint::Gradient(gradient) => {
+            r.set_paint_transform(Affine::IDENTITY);
+            r.set_paint(gradient.clone());
+        }
+        Paint::Image { index, origin } => {
+            r.set_paint_transform(Affine::translate(origin.to_vec2()));
+            r.set_paint(Image {
+                image: state.images[*index].0.clone(),
+                sampler: ImageSampler::default(),
+            });
+        }
+    }
+}
+
+pub(super) fn register(scenes: &mut impl SceneRegistry) {
+    scenes.add::<Generated<1, 100>>();
+    scenes.add::<Generated<2, 1000>>();
+    scenes.add::<Generated<3, 5000>>();
+}
@@ -2,64 +2,149 @@
 //!
 //! All scenes in this module share a single uploaded image (`splash-flower.jpg`)
 //! via [`ImageGridState`]. The image is uploaded once during [`VelloScene::setup`]
-//! and referenced by opaque handle in the draw loop.
+//! and referenced by opaque handle in the draw loop. The `alpha_*` scenes also
+//! upload a translucent badge (`alpha-badge.png`), and `unique_images_256`
+//! uploads 256 generated images instead.
 //!
 //! To add a new image scene:
 //! 1. Write a `fn draw_my_scene<R: Renderer>(state: &ImageGridState, r: &mut R, count: u32)`.
-//! 2. Stamp out variants with the [`counted_image_scene!`] macro.
-//! 3. Register them in `mod.rs`'s `register_vello_scenes!` invocation.
+//! 2. Stamp out variants with the [`counted_image_scene!`] macro. Very large
+//!    counts should pass a `warmup_count` so warm-up draws a smaller proxy.
+//! 3. Add them to `register` at the bottom of this file.
+//! 4. Add the family to [`COUNTED_FAMILIES`] and [`counted_draw_fn`], so it
+//!    can be drawn with any count (see [`crate::frame_budget`]).
 
+use std::cell::Cell;
 use std::sync::Arc;
 
-use super::{VelloScene, VelloSceneInfo};
+use serde::{Deserialize, Serialize};
+
+use super::layer_stack::grid_cell;
+use super::rng::Lcg;
+use super::{SceneRegistry, VelloScene, VelloSceneInfo, count_tags};
+use crate::data::paths;
 use crate::renderer::Renderer;
-use vello_common::kurbo::{Affine, BezPath, Rect, RoundedRect, Shape, Stroke};
+use crate::runner::time_ns;
+use vello_common::kurbo::{Affine, BezPath, Rect, RoundedRect, Shape, Stroke, Vec2};
 use vello_common::paint::{Image, ImageSource};
 use vello_common::peniko::color::palette;
 use vello_common::peniko::color::PremulRgba8;
-use vello_common::peniko::ImageSampler;
+use vello_common::peniko::{Extend, ImageQuality, ImageSampler};
 use vello_common::pixmap::Pixmap;
 
 // ===========================================================================
 // Shared helpers
 // ===========================================================================
 
-/// Decode the embedded splash-flower JPEG into a premultiplied-alpha [`Pixmap`].
-fn load_splash_flower_pixmap() -> Pixmap {
-    static JPEG_BYTES: &[u8] = include_bytes!("../../assets/splash-flower.jpg");
+/// Time spent in the two steps of loading an embedded image, e.g. during
+/// [`setup_image_grid`], to see where setup time goes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImageSetupTiming {
+    /// Decoding the JPEG or PNG into straight-alpha RGBA8.
+    pub decode_ns: f64,
+    /// Premultiplying the decoded pixels into a [`Pixmap`].
+    pub convert_ns: f64,
+}
 
-    let img = image::load_from_memory_with_format(JPEG_BYTES, image::ImageFormat::Jpeg)
-        .expect("failed to decode splash-flower.jpg")
-        .into_rgba8();
+thread_local! {
+    static LAST_IMAGE_SETUP: Cell<Option<ImageSetupTiming>> = const { Cell::new(None) };
+}
 
-    let (w, h) = img.dimensions();
+/// Timing of the last [`setup_image_grid`] on this thread, if there was one.
+pub fn last_image_setup_timing() -> Option<ImageSetupTiming> {
+    LAST_IMAGE_SETUP.with(Cell::get)
+}
+
+/// Premultiply straight-alpha RGBA8 pixels, rounding to nearest.
+///
+/// # Panics
+///
+/// If `rgba` is not a whole number of pixels.
+pub fn premultiply_rgba8(rgba: &[u8]) -> Vec<PremulRgba8> {
+    assert!(rgba.len() % 4 == 0, "RGBA8 data must be 4 bytes per pixel");
 
     #[expect(
         clippy::cast_possible_truncation,
-        reason = "Image is known to be small enough."
+        reason = "c * a / 255 is at most 255."
     )]
-    let pixels: Vec<PremulRgba8> = img
-        .pixels()
+    let premultiply = |c: u8, a: u8| ((u16::from(c) * u16::from(a) + 127) / 255) as u8;
+
+    rgba.chunks_exact(4)
         .map(|p| PremulRgba8 {
-            r: p[0],
-            g: p[1],
-            b: p[2],
+            r: premultiply(p[0], p[3]),
+            g: premultiply(p[1], p[3]),
+            b: premultiply(p[2], p[3]),
             a: p[3],
         })
-        .collect();
+        .collect()
+}
+
+/// Decode an embedded image into a premultiplied-alpha [`Pixmap`], timing
+/// both steps. `name` is only used in the panic message.
+fn decode_pixmap(
+    bytes: &[u8],
+    format: image::ImageFormat,
+    name: &str,
+) -> (Pixmap, ImageSetupTiming) {
+    let (img, decode_ns) = time_ns(|| {
+        image::load_from_memory_with_format(bytes, format)
+            .unwrap_or_else(|e| panic!("failed to decode {name}: {e}"))
+            .into_rgba8()
+    });
+
+    let (w, h) = img.dimensions();
+
+    // A no-op for the opaque JPEG, but not for the alpha badge.
+    let (pixels, convert_ns) = time_ns(|| premultiply_rgba8(img.as_raw()));
 
-    Pixmap::from_parts(pixels, w as u16, h as u16)
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "Image is known to be small enough."
+    )]
+    let pixmap = Pixmap::from_parts(pixels, w as u16, h as u16);
+
+    (
+        pixmap,
+        ImageSetupTiming {
+            decode_ns,
+            convert_ns,
+        },
+    )
+}
+
+/// Decode the embedded splash-flower JPEG into a premultiplied-alpha [`Pixmap`].
+fn load_splash_flower_pixmap() -> (Pixmap, ImageSetupTiming) {
+    static JPEG_BYTES: &[u8] = include_bytes!("../../assets/splash-flower.jpg");
+    decode_pixmap(JPEG_BYTES, image::ImageFormat::Jpeg, "splash-flower.jpg")
+}
+
+/// Decode the embedded alpha-badge PNG, a translucent disc whose alpha varies
+/// across it, into a premultiplied-alpha [`Pixmap`].
+fn load_alpha_badge_pixmap() -> (Pixmap, ImageSetupTiming) {
+    static PNG_BYTES: &[u8] = include_bytes!("../../assets/alpha-badge.png");
+    decode_pixmap(PNG_BYTES, image::ImageFormat::Png, "alpha-badge.png")
 }
 
 /// Shared state for image scenes: an uploaded image handle + dimensions.
 pub struct ImageGridState {
-    image_source: ImageSource,
-    img_w: u16,
-    img_h: u16,
+    pub(super) image_source: ImageSource,
+    pub(super) img_w: u16,
+    pub(super) img_h: u16,
+    setup_timing: ImageSetupTiming,
 }
 
-pub(super) fn setup_image_grid<R: Renderer>(r: &mut R) -> ImageGridState {
-    let pixmap = load_splash_flower_pixmap();
+impl ImageGridState {
+    /// How long decoding and converting the image took.
+    pub fn setup_timing(&self) -> ImageSetupTiming {
+        self.setup_timing
+    }
+}
+
+/// Upload a decoded image to `r`.
+fn upload_image<R: Renderer>(
+    r: &mut R,
+    (pixmap, setup_timing): (Pixmap, ImageSetupTiming),
+) -> ImageGridState {
     let img_w = pixmap.width();
     let img_h = pixmap.height();
     let image_source = r.get_image_source(Arc::new(pixmap));
@@ -67,15 +152,35 @@ pub(super) fn setup_image_grid<R: Renderer>(r: &mut R) -> ImageGridState {
         image_source,
         img_w,
         img_h,
+        setup_timing,
     }
 }
 
+/// Decode and upload the shared splash-flower image. The decode and convert
+/// times are kept in the state and in [`last_image_setup_timing`].
+pub fn setup_image_grid<R: Renderer>(r: &mut R) -> ImageGridState {
+    let (pixmap, setup_timing) = load_splash_flower_pixmap();
+    LAST_IMAGE_SETUP.with(|last| last.set(Some(setup_timing)));
+    upload_image(r, (pixmap, setup_timing))
+}
+
+/// Decode and upload the translucent alpha badge. Its timing is only kept in
+/// the state, so [`last_image_setup_timing`] keeps reporting the flower.
+pub fn setup_alpha_badge<R: Renderer>(r: &mut R) -> ImageGridState {
+    upload_image(r, load_alpha_badge_pixmap())
+}
+
 // ===========================================================================
 // Parameterized draw functions
 // ===========================================================================
 
 /// Draw `count` images in a non-overlapping grid.
 fn draw_tiled_flowers<R: Renderer>(state: &ImageGridState, r: &mut R, count: u32) {
+    draw_tiled_flowers_at(state, r, count, Vec2::ZERO);
+}
+
+/// Draw `count` images in a non-overlapping grid shifted by `offset`.
+fn draw_tiled_flowers_at<R: Renderer>(state: &ImageGridState, r: &mut R, count: u32, offset: Vec2) {
     let canvas_w = f64::from(r.width());
     let canvas_h = f64::from(r.height());
     let img_w = f64::from(state.img_w);
@@ -103,7 +208,9 @@ fn draw_tiled_flowers<R: Renderer>(state: &ImageGridState, r: &mut R, count: u32
             let x = f64::from(col) * cell_w;
             let y = f64::from(row) * cell_h;
 
-            r.set_transform(Affine::translate((x, y)) * Affine::scale_non_uniform(sx, sy));
+            r.set_transform(
+                Affine::translate(Vec2::new(x, y) + offset) * Affine::scale_non_uniform(sx, sy),
+            );
             r.set_paint(Image {
                 image: state.image_source.clone(),
                 sampler: ImageSampler::default(),
@@ -114,8 +221,51 @@ fn draw_tiled_flowers<R: Renderer>(state: &ImageGridState, r: &mut R, count: u32
     r.set_transform(Affine::IDENTITY);
 }
 
+/// Draw `count` images in a non-overlapping grid like
+/// [`draw_tiled_flowers`], sampled with `sampler`. Each cell fills a rect
+/// three times the image's size with the image in its middle third, so the
+/// sampler's extend mode fills the rest.
+fn draw_sampled_flowers<R: Renderer>(
+    state: &ImageGridState,
+    r: &mut R,
+    count: u32,
+    sampler: ImageSampler,
+) {
+    let canvas_w = f64::from(r.width());
+    let canvas_h = f64::from(r.height());
+    let img_w = f64::from(state.img_w);
+    let img_h = f64::from(state.img_h);
+
+    let aspect = canvas_w / canvas_h;
+    let rows_f = (f64::from(count) / aspect).sqrt();
+    let cols = (rows_f * aspect).ceil() as u32;
+    let rows = rows_f.ceil() as u32;
+
+    let cell_w = canvas_w / f64::from(cols);
+    let cell_h = canvas_h / f64::from(rows);
+    let sx = cell_w / (3.0 * img_w);
+    let sy = cell_h / (3.0 * img_h);
+
+    for n in 0..count {
+        let x = f64::from(n % cols) * cell_w;
+        let y = f64::from(n / cols) * cell_h;
+
+        r.set_transform(
+            Affine::translate((x, y))
+                * Affine::scale_non_uniform(sx, sy)
+                * Affine::translate((img_w, img_h)),
+        );
+        r.set_paint(Image {
+            image: state.image_source.clone(),
+            sampler,
+        });
+        r.fill_rect(&Rect::new(-img_w, -img_h, 2.0 * img_w, 2.0 * img_h));
+    }
+    r.set_transform(Affine::IDENTITY);
+}
+
 /// Draw `count` overlapping opaque images at pseudo-random positions.
-fn draw_overlapping_images<R: Renderer>(state: &ImageGridState, r: &mut R, count: u32) {
+pub fn draw_overlapping_images<R: Renderer>(state: &ImageGridState, r: &mut R, count: u32) {
     let canvas_w = f64::from(r.width());
     let canvas_h = f64::from(r.height());
     let img_w = f64::from(state.img_w);
@@ -223,6 +373,39 @@ fn draw_large_overlapping_images<R: Renderer>(state: &ImageGridState, r: &mut R,
 
 /// Draw `count` images each rotated by a different angle.
 fn draw_rotated_images<R: Renderer>(state: &ImageGridState, r: &mut R, count: u32) {
+    draw_images_rotated_by(state, r, count, |n| {
+        Affine::rotate(f64::from(n) * std::f64::consts::TAU / f64::from(count))
+    });
+}
+
+/// Draw `count` images all rotated by `degrees`, in the layout of
+/// [`draw_rotated_images`].
+///
+/// Quarter turns are built exactly rather than from `sin`/`cos`, so that
+/// 0° and 90° are axis-aligned without rounding noise and can take a
+/// backend's fast path.
+fn draw_images_at_angle<R: Renderer>(state: &ImageGridState, r: &mut R, count: u32, degrees: f64) {
+    let rotation = if degrees % 90.0 == 0.0 {
+        match (degrees / 90.0).rem_euclid(4.0) as u8 {
+            0 => Affine::IDENTITY,
+            1 => Affine::new([0.0, 1.0, -1.0, 0.0, 0.0, 0.0]),
+            2 => Affine::new([-1.0, 0.0, 0.0, -1.0, 0.0, 0.0]),
+            _ => Affine::new([0.0, -1.0, 1.0, 0.0, 0.0, 0.0]),
+        }
+    } else {
+        Affine::rotate(degrees.to_radians())
+    };
+    draw_images_rotated_by(state, r, count, |_| rotation);
+}
+
+/// Draw `count` images in a grid, image `n` rotated by `rotation(n)` around
+/// its center.
+fn draw_images_rotated_by<R: Renderer>(
+    state: &ImageGridState,
+    r: &mut R,
+    count: u32,
+    rotation: impl Fn(u32) -> Affine,
+) {
     let canvas_w = f64::from(r.width());
     let canvas_h = f64::from(r.height());
     let img_w = f64::from(state.img_w);
@@ -243,7 +426,7 @@ fn draw_rotated_images<R: Renderer>(state: &ImageGridState, r: &mut R, count: u3
                 r.set_transform(Affine::IDENTITY);
                 return;
             }
-            let angle = (n as f64) * std::f64::consts::TAU / f64::from(count);
+            let rotation = rotation(n);
             n += 1;
 
             let cx = f64::from(col) * cell_w + cell_w * 0.5;
@@ -251,7 +434,7 @@ fn draw_rotated_images<R: Renderer>(state: &ImageGridState, r: &mut R, count: u3
 
             r.set_transform(
                 Affine::translate((cx, cy))
-                    * Affine::rotate(angle)
+                    * rotation
                     * Affine::scale_non_uniform(sx, sy)
                     * Affine::translate((-img_w * 0.5, -img_h * 0.5)),
             );
@@ -402,21 +585,30 @@ fn draw_mixed_image_and_vector<R: Renderer>(state: &ImageGridState, r: &mut R, c
     }
 }
 
-/// Draw a scene that interleaves batches of random SVG paths with images.
+/// State of [`PathsAndImages100`]: the shared image and the
+/// [`medium`](crate::data::paths::medium) path corpus.
+pub struct PathsAndImagesState {
+    grid: ImageGridState,
+    paths: Vec<BezPath>,
+}
+
+/// Draw a scene that interleaves batches of corpus paths with images.
 ///
-/// For each of `iterations` rounds: draw `paths_per_batch` filled bezier
-/// paths (deterministic pseudo-random curves) and then one image.
+/// For each of `iterations` rounds: draw `paths_per_batch` paths from the
+/// [`medium`](crate::data::paths::medium) corpus, cycling through it, at
+/// deterministic pseudo-random positions and sizes, alternately filled and
+/// stroked, and then one image.
 /// Total elements = iterations * (paths_per_batch + 1).
 fn draw_paths_and_images<R: Renderer>(
-    state: &ImageGridState,
+    state: &PathsAndImagesState,
     r: &mut R,
     iterations: u32,
     paths_per_batch: u32,
 ) {
     let canvas_w = f64::from(r.width());
     let canvas_h = f64::from(r.height());
-    let img_w = f64::from(state.img_w);
-    let img_h = f64::from(state.img_h);
+    let img_w = f64::from(state.grid.img_w);
+    let img_h = f64::from(state.grid.img_h);
 
     let img_cols = (iterations as f64).sqrt().ceil() as u32;
     let img_rows = (iterations + img_cols - 1) / img_cols;
@@ -436,58 +628,32 @@ fn draw_paths_and_images<R: Renderer>(
         palette::css::DARK_CYAN,
     ];
 
-    // Simple deterministic LCG for reproducible "random" coordinates.
-    let mut seed: u64 = 12345;
-    let mut rng = || -> f64 {
-        seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
-        (seed >> 33) as f64 / (1u64 << 31) as f64
-    };
-
-    let path_stroke = Stroke {
-        width: 1.5,
-        ..Default::default()
-    };
+    let mut rng = Lcg::new(12345);
 
     for iter in 0..iterations {
         for p in 0..paths_per_batch {
             let global_idx = (iter * paths_per_batch + p) as usize;
             let color = path_colors[global_idx % path_colors.len()];
-
-            let mut path = BezPath::new();
-            let x0 = rng() * canvas_w;
-            let y0 = rng() * canvas_h;
-            path.move_to((x0, y0));
-
-            let seg_count = 4 + (global_idx % 3);
-            for _ in 0..seg_count {
-                match global_idx % 3 {
-                    0 => {
-                        path.line_to((rng() * canvas_w, rng() * canvas_h));
-                    }
-                    1 => {
-                        path.quad_to(
-                            (rng() * canvas_w, rng() * canvas_h),
-                            (rng() * canvas_w, rng() * canvas_h),
-                        );
-                    }
-                    _ => {
-                        path.curve_to(
-                            (rng() * canvas_w, rng() * canvas_h),
-                            (rng() * canvas_w, rng() * canvas_h),
-                            (rng() * canvas_w, rng() * canvas_h),
-                        );
-                    }
-                }
-            }
-            path.close_path();
+            let path = &state.paths[global_idx % state.paths.len()];
+
+            // Corpus paths fit the unit square; place them at 10% to 50% of
+            // the canvas height.
+            let size = (0.1 + 0.4 * rng.next_f64()) * canvas_h;
+            let x = rng.next_f64() * (canvas_w - size);
+            let y = rng.next_f64() * (canvas_h - size);
+            r.set_transform(Affine::translate((x, y)) * Affine::scale(size));
+            r.set_paint(color);
 
             if global_idx % 2 == 0 {
-                r.set_paint(color);
-                r.fill_path(&path);
+                r.fill_path(path);
             } else {
-                r.set_stroke(path_stroke.clone());
-                r.set_paint(color);
-                r.stroke_path(&path);
+                // The stroke is scaled along with the path, so that it ends
+                // up 1.5 pixels wide.
+                r.set_stroke(Stroke {
+                    width: 1.5 / size,
+                    ..Default::default()
+                });
+                r.stroke_path(path);
             }
         }
 
@@ -498,7 +664,7 @@ fn draw_paths_and_images<R: Renderer>(
 
         r.set_transform(Affine::translate((x, y)) * Affine::scale_non_uniform(sx, sy));
         r.set_paint(Image {
-            image: state.image_source.clone(),
+            image: state.grid.image_source.clone(),
             sampler: ImageSampler::default(),
         });
         r.fill_rect(&Rect::new(0.0, 0.0, img_w, img_h));
@@ -506,18 +672,145 @@ fn draw_paths_and_images<R: Renderer>(
     }
 }
 
+// ===========================================================================
+// Scene families with a runtime count
+// ===========================================================================
+
+/// Canvas width of the counted image scenes.
+pub const COUNTED_SCENE_WIDTH: u16 = 1920;
+/// Canvas height of the counted image scenes.
+pub const COUNTED_SCENE_HEIGHT: u16 = 1080;
+
+/// Canvas width of the `_4k` variants, where memory bandwidth dominates.
+pub const UHD_SCENE_WIDTH: u16 = 3840;
+/// Canvas height of the `_4k` variants.
+pub const UHD_SCENE_HEIGHT: u16 = 2160;
+
+/// A parameterized draw function, drawing `count` elements of its family.
+pub type CountedDrawFn<R> = fn(&ImageGridState, &mut R, u32);
+
+/// Names of the counted scene families, e.g. `tiled_flowers` for
+/// `tiled_flowers_100`, `tiled_flowers_1000` and so on.
+pub const COUNTED_FAMILIES: &[&str] = &[
+    "tiled_flowers",
+    "overlapping_images",
+    "clipped_image_cards",
+    "large_overlapping_images",
+    "rotated_images",
+    "image_cards_with_borders",
+    "mixed_image_and_vector",
+];
+
+/// The draw function of a counted scene family, for drawing it with a count
+/// chosen at runtime. `None` if `family` is not in [`COUNTED_FAMILIES`].
+pub fn counted_draw_fn<R: Renderer>(family: &str) -> Option<CountedDrawFn<R>> {
+    let draw: CountedDrawFn<R> = match family {
+        "tiled_flowers" => draw_tiled_flowers,
+        "overlapping_images" => draw_overlapping_images,
+        "clipped_image_cards" => draw_clipped_image_cards,
+        "large_overlapping_images" => draw_large_overlapping_images,
+        "rotated_images" => draw_rotated_images,
+        "image_cards_with_borders" => draw_image_cards_with_borders,
+        "mixed_image_and_vector" => draw_mixed_image_and_vector,
+        _ => return None,
+    };
+    Some(draw)
+}
+
+/// Count of a counted scene family drawn without a registered variant, named
+/// `<family>?count=<count>`, e.g. `tiled_flowers?count=2500`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SceneParams {
+    pub count: u32,
+}
+
+impl SceneParams {
+    /// Largest accepted count, so that a typo can't stall a benchmark run.
+    pub const MAX_COUNT: u32 = 100_000;
+
+    /// Check that `count` is between 1 and [`Self::MAX_COUNT`].
+    pub fn new(count: u32) -> Result<Self, String> {
+        if count == 0 || count > Self::MAX_COUNT {
+            return Err(format!(
+                "Scene count must be between 1 and {}",
+                Self::MAX_COUNT
+            ));
+        }
+        Ok(Self { count })
+    }
+
+    /// Scene name of `family` drawn with these parameters.
+    pub fn name(self, family: &str) -> String {
+        format!("{family}?count={}", self.count)
+    }
+
+    /// Parse a `<family>?count=<count>` name into its family, one of
+    /// [`COUNTED_FAMILIES`], and parameters. Rejects non-canonical numbers
+    /// (e.g. `+5`, `007`) and counts of zero or above [`Self::MAX_COUNT`].
+    pub fn parse(name: &str) -> Option<(&'static str, Self)> {
+        let (family, count) = name.split_once("?count=")?;
+        let family = *COUNTED_FAMILIES.iter().find(|&&f| f == family)?;
+        let params = Self::new(count.parse().ok()?).ok()?;
+        (params.name(family) == name).then_some((family, params))
+    }
+}
+
+/// State of a counted scene drawn through [`SceneParams`].
+pub struct ParameterizedState {
+    grid: ImageGridState,
+    family: &'static str,
+    count: u32,
+}
+
+/// Set up `family` for [`draw_parameterized`]. Every counted family draws
+/// the flower of [`setup_image_grid`].
+pub fn setup_parameterized<R: Renderer>(
+    r: &mut R,
+    family: &'static str,
+    params: SceneParams,
+) -> ParameterizedState {
+    ParameterizedState {
+        grid: setup_image_grid(r),
+        family,
+        count: params.count,
+    }
+}
+
+/// Draw a counted scene with the family and count it was set up with.
+///
+/// The draw function is looked up on every call rather than kept in the
+/// state, since it is generic over `R` and renderers with a lifetime can't
+/// be stored in a `dyn Any`. The lookup is a match on a handful of strings,
+/// negligible next to drawing.
+pub fn draw_parameterized<R: Renderer>(state: &ParameterizedState, r: &mut R) {
+    let draw = counted_draw_fn::<R>(state.family).expect("family of a parsed scene name");
+    draw(&state.grid, r, state.count);
+}
+
 // ===========================================================================
 // Macro to stamp out VelloScene impls at specific counts
 // ===========================================================================
 
 /// Generate a scene struct + [`VelloScene`] impl that delegates to a
 /// parameterized draw function with a fixed count.
+///
+/// With `width` and `height`, the canvas has that size instead of
+/// [`COUNTED_SCENE_WIDTH`]×[`COUNTED_SCENE_HEIGHT`]. With `warmup_count`, the
+/// scene's warm-up proxy draws the same scene with that count instead.
 macro_rules! counted_image_scene {
+    (@or_default , $default:expr) => {
+        $default
+    };
+    (@or_default $value:expr, $default:expr) => {
+        $value
+    };
     (
         struct $name:ident,
         bench_name: $bench_name:expr,
         count: $count:expr,
-        draw_fn: $draw_fn:ident $(,)?
+        draw_fn: $draw_fn:ident
+        $(, width: $width:expr, height: $height:expr)?
+        $(, warmup_count: $warmup_count:expr)? $(,)?
     ) => {
         pub struct $name;
 
@@ -527,8 +820,8 @@ macro_rules! counted_image_scene {
             fn info() -> VelloSceneInfo {
                 VelloSceneInfo {
                     name: $bench_name,
-                    width: 1920,
-                    height: 1080,
+                    width: counted_image_scene!(@or_default $($width)?, COUNTED_SCENE_WIDTH),
+                    height: counted_image_scene!(@or_default $($height)?, COUNTED_SCENE_HEIGHT),
                 }
             }
 
@@ -539,6 +832,16 @@ macro_rules! counted_image_scene {
             fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
                 $draw_fn(state, r, $count);
             }
+
+            const TAGS: &'static [&'static str] = count_tags($count);
+
+            $(
+                const HAS_WARMUP_PROXY: bool = true;
+
+                fn draw_warmup_proxy<R: Renderer>(state: &Self::State, r: &mut R) {
+                    $draw_fn(state, r, $warmup_count);
+                }
+            )?
         }
     };
 }
@@ -547,58 +850,440 @@ macro_rules! counted_image_scene {
 counted_image_scene!(struct TiledFlowers100,   bench_name: "tiled_flowers_100",   count: 100,   draw_fn: draw_tiled_flowers);
 counted_image_scene!(struct TiledFlowers300,   bench_name: "tiled_flowers_300",   count: 300,   draw_fn: draw_tiled_flowers);
 counted_image_scene!(struct TiledFlowers1000,  bench_name: "tiled_flowers_1000",  count: 1000,  draw_fn: draw_tiled_flowers);
-counted_image_scene!(struct TiledFlowers10000, bench_name: "tiled_flowers_10000", count: 10000, draw_fn: draw_tiled_flowers);
+counted_image_scene!(struct TiledFlowers10000, bench_name: "tiled_flowers_10000", count: 10000, draw_fn: draw_tiled_flowers, warmup_count: 1000);
+counted_image_scene!(struct TiledFlowers1000_4k, bench_name: "tiled_flowers_1000_4k", count: 1000, draw_fn: draw_tiled_flowers, width: UHD_SCENE_WIDTH, height: UHD_SCENE_HEIGHT);
+
+// Animated tiled flowers — the grid of tiled_flowers_1000, shifted by a
+// different amount every frame
+/// [`TiledFlowers1000`] shifted right and down by `frame % 16` pixels.
+pub struct AnimatedTiledFlowers1000;
+
+impl VelloScene for AnimatedTiledFlowers1000 {
+    type State = ImageGridState;
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "animated_tiled_flowers_1000",
+            width: COUNTED_SCENE_WIDTH,
+            height: COUNTED_SCENE_HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(r: &mut R) -> Self::State {
+        setup_image_grid(r)
+    }
+
+    fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
+        Self::draw_frame(state, r, 0);
+    }
+
+    fn draw_frame<R: Renderer>(state: &Self::State, r: &mut R, frame_index: u64) {
+        let shift = (frame_index % 16) as f64;
+        draw_tiled_flowers_at(state, r, 1000, Vec2::new(shift, shift));
+    }
+
+    const ANIMATED: bool = true;
+    const ELEMENTS: Option<u64> = Some(1000);
+}
 
 // Overlapping images — opaque, pseudo-random positions
 counted_image_scene!(struct OverlappingImages100,   bench_name: "overlapping_images_100",   count: 100,   draw_fn: draw_overlapping_images);
 counted_image_scene!(struct OverlappingImages1000,  bench_name: "overlapping_images_1000",  count: 1000,  draw_fn: draw_overlapping_images);
-counted_image_scene!(struct OverlappingImages10000, bench_name: "overlapping_images_10000", count: 10000, draw_fn: draw_overlapping_images);
+counted_image_scene!(struct OverlappingImages10000, bench_name: "overlapping_images_10000", count: 10000, draw_fn: draw_overlapping_images, warmup_count: 1000);
+counted_image_scene!(struct OverlappingImages1000_4k, bench_name: "overlapping_images_1000_4k", count: 1000, draw_fn: draw_overlapping_images, width: UHD_SCENE_WIDTH, height: UHD_SCENE_HEIGHT);
 
 // Clipped image cards — rounded-rect clip + stroked border
 counted_image_scene!(struct ClippedImageCards100,   bench_name: "clipped_image_cards_100",   count: 100,   draw_fn: draw_clipped_image_cards);
 counted_image_scene!(struct ClippedImageCards1000,  bench_name: "clipped_image_cards_1000",  count: 1000,  draw_fn: draw_clipped_image_cards);
-counted_image_scene!(struct ClippedImageCards10000, bench_name: "clipped_image_cards_10000", count: 10000, draw_fn: draw_clipped_image_cards);
+counted_image_scene!(struct ClippedImageCards10000, bench_name: "clipped_image_cards_10000", count: 10000, draw_fn: draw_clipped_image_cards, warmup_count: 1000);
 
 // Large overlapping images — opaque, heavy overdraw
 counted_image_scene!(struct LargeOverlappingImages100,   bench_name: "large_overlapping_images_100",   count: 100,   draw_fn: draw_large_overlapping_images);
 counted_image_scene!(struct LargeOverlappingImages1000,  bench_name: "large_overlapping_images_1000",  count: 1000,  draw_fn: draw_large_overlapping_images);
-counted_image_scene!(struct LargeOverlappingImages10000, bench_name: "large_overlapping_images_10000", count: 10000, draw_fn: draw_large_overlapping_images);
+counted_image_scene!(struct LargeOverlappingImages10000, bench_name: "large_overlapping_images_10000", count: 10000, draw_fn: draw_large_overlapping_images, warmup_count: 1000);
 
 // Rotated images — non-axis-aligned sampling
 counted_image_scene!(struct RotatedImages100,   bench_name: "rotated_images_100",   count: 100,   draw_fn: draw_rotated_images);
 counted_image_scene!(struct RotatedImages1000,  bench_name: "rotated_images_1000",  count: 1000,  draw_fn: draw_rotated_images);
-counted_image_scene!(struct RotatedImages10000, bench_name: "rotated_images_10000", count: 10000, draw_fn: draw_rotated_images);
+counted_image_scene!(struct RotatedImages10000, bench_name: "rotated_images_10000", count: 10000, draw_fn: draw_rotated_images, warmup_count: 1000);
+
+// Image rotation sweep — the same grid at one fixed angle per scene, around
+// the axis-aligned angles renderers tend to special-case
+/// Images drawn by each `image_rotation_*` scene.
+pub const ROTATION_SWEEP_COUNT: u32 = 1000;
+
+/// Generate a scene drawing [`ROTATION_SWEEP_COUNT`] images rotated by a
+/// fixed number of degrees.
+macro_rules! fixed_rotation_scene {
+    (struct $name:ident, bench_name: $bench_name:expr, degrees: $degrees:expr $(,)?) => {
+        pub struct $name;
+
+        impl VelloScene for $name {
+            type State = ImageGridState;
+
+            fn info() -> VelloSceneInfo {
+                VelloSceneInfo {
+                    name: $bench_name,
+                    width: COUNTED_SCENE_WIDTH,
+                    height: COUNTED_SCENE_HEIGHT,
+                }
+            }
+
+            fn setup<R: Renderer>(r: &mut R) -> Self::State {
+                setup_image_grid(r)
+            }
+
+            fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
+                draw_images_at_angle(state, r, ROTATION_SWEEP_COUNT, $degrees);
+            }
+
+            const ELEMENTS: Option<u64> = Some(ROTATION_SWEEP_COUNT as u64);
+        }
+    };
+}
+
+fixed_rotation_scene!(struct ImageRotation0,    bench_name: "image_rotation_0deg_1000",    degrees: 0.0);
+fixed_rotation_scene!(struct ImageRotation0_5,  bench_name: "image_rotation_0_5deg_1000",  degrees: 0.5);
+fixed_rotation_scene!(struct ImageRotation15,   bench_name: "image_rotation_15deg_1000",   degrees: 15.0);
+fixed_rotation_scene!(struct ImageRotation45,   bench_name: "image_rotation_45deg_1000",   degrees: 45.0);
+fixed_rotation_scene!(struct ImageRotation89_5, bench_name: "image_rotation_89_5deg_1000", degrees: 89.5);
+fixed_rotation_scene!(struct ImageRotation90,   bench_name: "image_rotation_90deg_1000",   degrees: 90.0);
+
+// Sampler comparison — the same grid with one sampler per scene
+/// Images drawn by each `sampler_comparison_*` scene.
+pub const SAMPLER_COMPARISON_COUNT: u32 = 1000;
+
+/// A sampler with the same extend mode on both axes.
+const fn sampler(quality: ImageQuality, extend: Extend) -> ImageSampler {
+    ImageSampler {
+        x_extend: extend,
+        y_extend: extend,
+        quality,
+        alpha: 1.0,
+    }
+}
+
+/// Generate a scene drawing [`SAMPLER_COMPARISON_COUNT`] images with a fixed
+/// sampler (see [`draw_sampled_flowers`]).
+macro_rules! sampler_scene {
+    (struct $name:ident, bench_name: $bench_name:expr, sampler: $sampler:expr $(,)?) => {
+        pub struct $name;
+
+        impl VelloScene for $name {
+            type State = ImageGridState;
+
+            fn info() -> VelloSceneInfo {
+                VelloSceneInfo {
+                    name: $bench_name,
+                    width: COUNTED_SCENE_WIDTH,
+                    height: COUNTED_SCENE_HEIGHT,
+                }
+            }
+
+            fn setup<R: Renderer>(r: &mut R) -> Self::State {
+                setup_image_grid(r)
+            }
+
+            fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
+                draw_sampled_flowers(state, r, SAMPLER_COMPARISON_COUNT, $sampler);
+            }
+
+            const ELEMENTS: Option<u64> = Some(SAMPLER_COMPARISON_COUNT as u64);
+        }
+    };
+}
+
+sampler_scene!(struct SamplerComparisonNearest,        bench_name: "sampler_comparison_nearest_1000",        sampler: sampler(ImageQuality::Low, Extend::Pad));
+sampler_scene!(struct SamplerComparisonBilinear,       bench_name: "sampler_comparison_bilinear_1000",       sampler: sampler(ImageQuality::Medium, Extend::Pad));
+sampler_scene!(struct SamplerComparisonBicubic,        bench_name: "sampler_comparison_bicubic_1000",        sampler: sampler(ImageQuality::High, Extend::Pad));
+sampler_scene!(struct SamplerComparisonRepeatNearest,  bench_name: "sampler_comparison_repeat_nearest_1000",  sampler: sampler(ImageQuality::Low, Extend::Repeat));
+sampler_scene!(struct SamplerComparisonRepeatBilinear, bench_name: "sampler_comparison_repeat_bilinear_1000", sampler: sampler(ImageQuality::Medium, Extend::Repeat));
+sampler_scene!(struct SamplerComparisonReflect,        bench_name: "sampler_comparison_reflect_1000",        sampler: sampler(ImageQuality::Medium, Extend::Reflect));
+
+// Alpha images — the translucent badge, which can't take the opaque image
+// paths, alone and over the flower grid
+/// Badges drawn by each `alpha_*` scene.
+pub const ALPHA_IMAGE_COUNT: u32 = 1000;
+
+/// 1000 overlapping translucent badges, laid out like
+/// [`OverlappingImages1000`] to compare against it.
+pub struct AlphaImages1000;
+
+impl VelloScene for AlphaImages1000 {
+    type State = ImageGridState;
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "alpha_images_1000",
+            width: COUNTED_SCENE_WIDTH,
+            height: COUNTED_SCENE_HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(r: &mut R) -> Self::State {
+        setup_alpha_badge(r)
+    }
+
+    fn draw<R: Renderer>(badge: &Self::State, r: &mut R) {
+        draw_overlapping_images(badge, r, ALPHA_IMAGE_COUNT);
+    }
+
+    const ELEMENTS: Option<u64> = Some(ALPHA_IMAGE_COUNT as u64);
+}
+
+/// State of [`AlphaOverOpaque1000`]: two images uploaded to the same
+/// renderer.
+pub struct AlphaOverOpaqueState {
+    flowers: ImageGridState,
+    badge: ImageGridState,
+}
+
+/// [`TiledFlowers1000`] with a translucent badge over every tile.
+pub struct AlphaOverOpaque1000;
+
+impl VelloScene for AlphaOverOpaque1000 {
+    type State = AlphaOverOpaqueState;
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "alpha_over_opaque_1000",
+            width: COUNTED_SCENE_WIDTH,
+            height: COUNTED_SCENE_HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(r: &mut R) -> Self::State {
+        AlphaOverOpaqueState {
+            flowers: setup_image_grid(r),
+            badge: setup_alpha_badge(r),
+        }
+    }
+
+    fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
+        draw_tiled_flowers(&state.flowers, r, ALPHA_IMAGE_COUNT);
+        draw_tiled_flowers(&state.badge, r, ALPHA_IMAGE_COUNT);
+    }
+
+    const ELEMENTS: Option<u64> = Some(2 * ALPHA_IMAGE_COUNT as u64);
+}
+
+// Unique images — a separately uploaded image for every element, so the
+// cost of holding and switching between many images shows
+/// Images of `unique_images_256`.
+pub const UNIQUE_IMAGE_COUNT: u32 = 256;
+
+/// Width and height of each image of `unique_images_256`.
+const UNIQUE_IMAGE_SIZE: u16 = 64;
+
+/// Image `i` of `unique_images_256`: an opaque gradient between two colors
+/// picked by a generator seeded with `i`, so every run generates the same
+/// images.
+fn unique_pixmap(i: u32) -> Pixmap {
+    let mut rng = Lcg::new(u64::from(i));
+    let mut color = || [rng.next_f32(), rng.next_f32(), rng.next_f32()];
+    let (from, to) = (color(), color());
+
+    let size = f32::from(UNIQUE_IMAGE_SIZE);
+    let pixels = (0..UNIQUE_IMAGE_SIZE)
+        .flat_map(|y| (0..UNIQUE_IMAGE_SIZE).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let t = (f32::from(x) + f32::from(y)) / (2.0 * size - 2.0);
+            let channel = |c: usize| ((from[c] + (to[c] - from[c]) * t) * 255.0).round() as u8;
+            PremulRgba8 {
+                r: channel(0),
+                g: channel(1),
+                b: channel(2),
+                a: 255,
+            }
+        })
+        .collect();
+    Pixmap::from_parts(pixels, UNIQUE_IMAGE_SIZE, UNIQUE_IMAGE_SIZE)
+}
+
+/// State of [`UniqueImages256`]: one uploaded image per cell.
+pub struct UniqueImagesState {
+    images: Vec<ImageSource>,
+}
+
+/// 256 different images in a grid, each drawn once.
+pub struct UniqueImages256;
+
+impl VelloScene for UniqueImages256 {
+    type State = UniqueImagesState;
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "unique_images_256",
+            width: COUNTED_SCENE_WIDTH,
+            height: COUNTED_SCENE_HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(r: &mut R) -> Self::State {
+        let images = (0..UNIQUE_IMAGE_COUNT)
+            .map(|i| r.get_image_source(Arc::new(unique_pixmap(i))))
+            .collect();
+        UniqueImagesState { images }
+    }
+
+    fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
+        let size = f64::from(UNIQUE_IMAGE_SIZE);
+        for (i, image) in (0..UNIQUE_IMAGE_COUNT).zip(&state.images) {
+            let cell = grid_cell(r, UNIQUE_IMAGE_COUNT, i);
+            r.set_transform(
+                Affine::translate((cell.x0, cell.y0))
+                    * Affine::scale_non_uniform(cell.width() / size, cell.height() / size),
+            );
+            r.set_paint(Image {
+                image: image.clone(),
+                sampler: ImageSampler::default(),
+            });
+            r.fill_rect(&Rect::new(0.0, 0.0, size, size));
+        }
+        r.set_transform(Affine::IDENTITY);
+    }
+
+    const ELEMENTS: Option<u64> = Some(UNIQUE_IMAGE_COUNT as u64);
+}
 
 // Image cards with SVG-style borders — clip + double stroke
 counted_image_scene!(struct ImageCardsWithBorders100,   bench_name: "image_cards_with_borders_100",   count: 100,   draw_fn: draw_image_cards_with_borders);
 counted_image_scene!(struct ImageCardsWithBorders1000,  bench_name: "image_cards_with_borders_1000",  count: 1000,  draw_fn: draw_image_cards_with_borders);
-counted_image_scene!(struct ImageCardsWithBorders10000, bench_name: "image_cards_with_borders_10000", count: 10000, draw_fn: draw_image_cards_with_borders);
+counted_image_scene!(struct ImageCardsWithBorders10000, bench_name: "image_cards_with_borders_10000", count: 10000, draw_fn: draw_image_cards_with_borders, warmup_count: 1000);
 
 // Mixed image and vector — alternating image tiles and coloured rects
 counted_image_scene!(struct MixedImageAndVector100,   bench_name: "mixed_image_and_vector_100",   count: 100,   draw_fn: draw_mixed_image_and_vector);
 counted_image_scene!(struct MixedImageAndVector1000,  bench_name: "mixed_image_and_vector_1000",  count: 1000,  draw_fn: draw_mixed_image_and_vector);
-counted_image_scene!(struct MixedImageAndVector10000, bench_name: "mixed_image_and_vector_10000", count: 10000, draw_fn: draw_mixed_image_and_vector);
+counted_image_scene!(struct MixedImageAndVector10000, bench_name: "mixed_image_and_vector_10000", count: 10000, draw_fn: draw_mixed_image_and_vector, warmup_count: 1000);
 
-// Paths and images — 100 random SVG paths then 1 image, repeated 100 times
-/// 100 iterations of (100 random SVG paths + 1 image) = 10,000 paths + 100 images.
-pub struct PathsAndImages100;
+// Paths and images — 100 corpus paths then 1 image, repeated 10 times
+/// Generate a scene drawing 10 iterations of (100 corpus paths + 1 image) =
+/// 1,000 paths + 10 images on a `width`×`height` canvas.
+macro_rules! paths_and_images_scene {
+    (struct $name:ident, bench_name: $bench_name:expr, width: $width:expr, height: $height:expr $(,)?) => {
+        pub struct $name;
 
-impl VelloScene for PathsAndImages100 {
-    type State = ImageGridState;
+        impl VelloScene for $name {
+            type State = PathsAndImagesState;
 
-    fn info() -> VelloSceneInfo {
-        VelloSceneInfo {
-            name: "paths_and_images_100",
-            width: 1920,
-            height: 1080,
+            fn info() -> VelloSceneInfo {
+                VelloSceneInfo {
+                    name: $bench_name,
+                    width: $width,
+                    height: $height,
+                }
+            }
+
+            fn setup<R: Renderer>(r: &mut R) -> Self::State {
+                PathsAndImagesState {
+                    grid: setup_image_grid(r),
+                    paths: paths::medium(),
+                }
+            }
+
+            fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
+                draw_paths_and_images(state, r, 10, 100);
+            }
         }
+    };
+}
+
+paths_and_images_scene!(struct PathsAndImages100,    bench_name: "paths_and_images_100",    width: 1920,            height: 1080);
+paths_and_images_scene!(struct PathsAndImages100_4k, bench_name: "paths_and_images_100_4k", width: UHD_SCENE_WIDTH, height: UHD_SCENE_HEIGHT);
+
+// ===========================================================================
+// Registration
+// ===========================================================================
+
+pub(super) fn register(scenes: &mut impl SceneRegistry) {
+    scenes.add::<TiledFlowers100>();
+    scenes.add::<TiledFlowers300>();
+    scenes.add::<TiledFlowers1000>();
+    scenes.add::<TiledFlowers10000>();
+    scenes.add::<TiledFlowers1000_4k>();
+    scenes.add::<AnimatedTiledFlowers1000>();
+    scenes.add::<OverlappingImages100>();
+    scenes.add::<OverlappingImages1000>();
+    scenes.add::<OverlappingImages10000>();
+    scenes.add::<OverlappingImages1000_4k>();
+    scenes.add::<ClippedImageCards100>();
+    scenes.add::<ClippedImageCards1000>();
+    scenes.add::<ClippedImageCards10000>();
+    scenes.add::<LargeOverlappingImages100>();
+    scenes.add::<LargeOverlappingImages1000>();
+    scenes.add::<LargeOverlappingImages10000>();
+    scenes.add::<RotatedImages100>();
+    scenes.add::<RotatedImages1000>();
+    scenes.add::<RotatedImages10000>();
+    scenes.add::<ImageRotation0>();
+    scenes.add::<ImageRotation0_5>();
+    scenes.add::<ImageRotation15>();
+    scenes.add::<ImageRotation45>();
+    scenes.add::<ImageRotation89_5>();
+    scenes.add::<ImageRotation90>();
+    scenes.add::<SamplerComparisonNearest>();
+    scenes.add::<SamplerComparisonBilinear>();
+    scenes.add::<SamplerComparisonBicubic>();
+    scenes.add::<SamplerComparisonRepeatNearest>();
+    scenes.add::<SamplerComparisonRepeatBilinear>();
+    scenes.add::<SamplerComparisonReflect>();
+    scenes.add::<AlphaImages1000>();
+    scenes.add::<AlphaOverOpaque1000>();
+    scenes.add::<UniqueImages256>();
+    scenes.add::<ImageCardsWithBorders100>();
+    scenes.add::<ImageCardsWithBorders1000>();
+    scenes.add::<ImageCardsWithBorders10000>();
+    scenes.add::<MixedImageAndVector100>();
+    scenes.add::<MixedImageAndVector1000>();
+    scenes.add::<MixedImageAndVector10000>();
+    scenes.add::<PathsAndImages100>();
+    scenes.add::<PathsAndImages100_4k>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn premul(r: u8, g: u8, b: u8, a: u8) -> PremulRgba8 {
+        PremulRgba8 { r, g, b, a }
     }
 
-    fn setup<R: Renderer>(r: &mut R) -> Self::State {
-        setup_image_grid(r)
+    #[test]
+    fn premultiplies_a_translucent_source() {
+        let rgba = [
+            [255, 128, 0, 128],
+            [10, 20, 30, 0],
+            [200, 100, 50, 255],
+            [255, 255, 255, 64],
+            [1, 254, 128, 1],
+        ]
+        .concat();
+        assert_eq!(
+            premultiply_rgba8(&rgba),
+            [
+                premul(128, 64, 0, 128),
+                premul(0, 0, 0, 0),
+                premul(200, 100, 50, 255),
+                premul(64, 64, 64, 64),
+                premul(0, 1, 1, 1),
+            ]
+        );
     }
 
-    fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
-        draw_paths_and_images(state, r, 10, 100);
+    #[test]
+    fn premultiplying_rounds_to_nearest() {
+        for a in 0..=255_u8 {
+            let rgba: Vec<u8> = (0..=255_u8).flat_map(|c| [c, c, c, a]).collect();
+            for (c, pixel) in premultiply_rgba8(&rgba).into_iter().enumerate() {
+                let expected = (c as f64 * f64::from(a) / 255.0).round() as u8;
+                assert_eq!(pixel, premul(expected, expected, expected, a), "{c} at {a}");
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "4 bytes per pixel")]
+    fn rejects_partial_pixels() {
+        premultiply_rgba8(&[255, 255, 255]);
     }
 }
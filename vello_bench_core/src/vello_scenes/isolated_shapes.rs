@@ -0,0 +1,85 @@
+//! Antialiased shapes that neither overlap nor touch.
+
+use super::{SceneRegistry, VelloScene, VelloSceneInfo};
+use crate::renderer::Renderer;
+use vello_common::kurbo::{Affine, Circle, Ellipse, Rect, RoundedRect, Shape, Stroke};
+use vello_common::peniko::color::palette;
+
+/// A grid of filled and stroked shapes with curved and rotated edges, placed
+/// at fractional offsets with gaps between them.
+///
+/// Since no two shapes share a pixel, every pixel with full coverage lies
+/// strictly inside a single shape. This makes the scene suitable for
+/// [`aliasing_report`](crate::screenshot::aliasing_report), where abutting
+/// shapes would produce fully covered seam pixels that legitimately change.
+pub struct IsolatedShapes;
+
+impl VelloScene for IsolatedShapes {
+    type State = ();
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "isolated_shapes",
+            width: 1024,
+            height: 768,
+        }
+    }
+
+    fn setup<R: Renderer>(_r: &mut R) -> Self::State {}
+
+    fn draw<R: Renderer>(_state: &Self::State, r: &mut R) {
+        let colors = [
+            palette::css::CRIMSON,
+            palette::css::SEA_GREEN,
+            palette::css::ROYAL_BLUE,
+            palette::css::GOLDENROD,
+        ];
+
+        let cols = 8u16;
+        let rows = 6u16;
+        let cell_w = f64::from(r.width()) / f64::from(cols);
+        let cell_h = f64::from(r.height()) / f64::from(rows);
+
+        for row in 0..rows {
+            for col in 0..cols {
+                let idx = usize::from(row * cols + col);
+                // Fractional offsets so that edges never align with pixels.
+                let cx = (f64::from(col) + 0.5) * cell_w + 0.37;
+                let cy = (f64::from(row) + 0.5) * cell_h + 0.61;
+                let size = cell_w.min(cell_h) * 0.35;
+
+                r.set_paint(colors[idx % colors.len()]);
+                match idx % 4 {
+                    0 => r.fill_path(&Circle::new((cx, cy), size).to_path(0.1)),
+                    1 => {
+                        r.set_transform(
+                            Affine::translate((cx, cy)) * Affine::rotate(0.1 * idx as f64),
+                        );
+                        r.fill_rect(&Rect::new(-size, -size * 0.6, size, size * 0.6));
+                        r.set_transform(Affine::IDENTITY);
+                    }
+                    2 => {
+                        r.set_stroke(Stroke::new(size * 0.15));
+                        r.stroke_path(
+                            &Ellipse::new((cx, cy), (size * 0.8, size * 0.5), 0.3).to_path(0.1),
+                        );
+                    }
+                    _ => r.fill_path(
+                        &RoundedRect::new(
+                            cx - size,
+                            cy - size * 0.7,
+                            cx + size,
+                            cy + size * 0.7,
+                            9.5,
+                        )
+                        .to_path(0.1),
+                    ),
+                }
+            }
+        }
+    }
+}
+
+pub(super) fn register(scenes: &mut impl SceneRegistry) {
+    scenes.add::<IsolatedShapes>();
+}
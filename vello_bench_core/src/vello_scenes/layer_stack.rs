@@ -0,0 +1,191 @@
+//! Many small layers, as in DOM-like content where every element is its own
+//! group.
+//!
+//! The layers hold little content each, so the cost of pushing, compositing
+//! and popping them dominates:
+//!
+//! - `nested_opacity_layers_64`: 64 opacity layers nested in each other, each
+//!   drawing a small rect, so the innermost rect is composited 64 times.
+//! - `flat_opacity_layers_1000`: 1000 sibling opacity layers holding one rect
+//!   each. The CPU and hybrid renders of this scene have diverged before, so
+//!   it has a tight parity tolerance (see
+//!   [`PARITY_TOLERANCES`](crate::golden::PARITY_TOLERANCES)).
+//! - `clip_in_opacity_in_clip_200`: 200 groups of a rounded-rect clip around
+//!   an opacity layer around a circular clip, alternating the two stacks.
+//!
+//! All scenes draw over an opaque backdrop, so renders compare without
+//! depending on how a backend treats transparency.
+
+use super::{SceneRegistry, VelloScene, VelloSceneInfo};
+use crate::renderer::Renderer;
+use vello_common::kurbo::{Circle, Rect, RoundedRect, Shape};
+use vello_common::peniko::color::palette;
+
+const WIDTH: u16 = 1024;
+const HEIGHT: u16 = 768;
+
+/// Nesting depth of `nested_opacity_layers_64`.
+const NESTED_DEPTH: u32 = 64;
+
+/// Layers of `flat_opacity_layers_1000`.
+const FLAT_LAYERS: u32 = 1000;
+
+/// Groups of `clip_in_opacity_in_clip_200`.
+const CLIP_GROUPS: u32 = 200;
+
+/// Fill the canvas with an opaque light gray.
+pub(super) fn draw_backdrop<R: Renderer>(r: &mut R) {
+    r.set_paint(palette::css::WHITE_SMOKE);
+    r.fill_rect(&Rect::new(
+        0.0,
+        0.0,
+        f64::from(r.width()),
+        f64::from(r.height()),
+    ));
+}
+
+/// Cell `i` of a grid of `count` cells covering the canvas, with about
+/// square cells.
+pub(super) fn grid_cell<R: Renderer>(r: &R, count: u32, i: u32) -> Rect {
+    let width = f64::from(r.width());
+    let height = f64::from(r.height());
+    let cols = (f64::from(count) * width / height).sqrt().ceil() as u32;
+    let rows = count.div_ceil(cols);
+    let cell_w = width / f64::from(cols);
+    let cell_h = height / f64::from(rows);
+    let x = f64::from(i % cols) * cell_w;
+    let y = f64::from(i / cols) * cell_h;
+    Rect::new(x, y, x + cell_w, y + cell_h)
+}
+
+/// 64 nested opacity layers, each drawing a small rect further along the
+/// diagonal.
+pub struct NestedOpacityLayers64;
+
+impl VelloScene for NestedOpacityLayers64 {
+    type State = ();
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "nested_opacity_layers_64",
+            width: WIDTH,
+            height: HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(_r: &mut R) -> Self::State {}
+
+    fn draw<R: Renderer>(_state: &Self::State, r: &mut R) {
+        draw_backdrop(r);
+
+        let step_x = f64::from(r.width()) / f64::from(NESTED_DEPTH + 4);
+        let step_y = f64::from(r.height()) / f64::from(NESTED_DEPTH + 4);
+        let colors = [palette::css::CRIMSON, palette::css::ROYAL_BLUE];
+        for level in 0..NESTED_DEPTH {
+            // 0.99^64 is about 0.5, so the innermost rect stays visible.
+            r.push_opacity_layer(0.99);
+            let x = f64::from(level) * step_x;
+            let y = f64::from(level) * step_y;
+            r.set_paint(colors[level as usize % colors.len()]);
+            r.fill_rect(&Rect::new(x, y, x + 4.0 * step_x, y + 4.0 * step_y));
+        }
+        for _ in 0..NESTED_DEPTH {
+            r.pop_layer();
+        }
+    }
+
+    const ELEMENTS: Option<u64> = Some(NESTED_DEPTH as u64);
+}
+
+/// 1000 sibling opacity layers of one rect each.
+pub struct FlatOpacityLayers1000;
+
+impl VelloScene for FlatOpacityLayers1000 {
+    type State = ();
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "flat_opacity_layers_1000",
+            width: WIDTH,
+            height: HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(_r: &mut R) -> Self::State {}
+
+    fn draw<R: Renderer>(_state: &Self::State, r: &mut R) {
+        draw_backdrop(r);
+
+        let colors = [
+            palette::css::DARK_ORANGE,
+            palette::css::TEAL,
+            palette::css::MEDIUM_VIOLET_RED,
+        ];
+        for i in 0..FLAT_LAYERS {
+            let cell = grid_cell(r, FLAT_LAYERS, i);
+            // Rects overlap their right and bottom neighbors.
+            let rect = Rect::new(
+                cell.x0,
+                cell.y0,
+                cell.x1 + cell.width() * 0.3,
+                cell.y1 + cell.height() * 0.3,
+            );
+            r.push_opacity_layer(0.3 + 0.6 * (i % 7) as f32 / 6.0);
+            r.set_paint(colors[i as usize % colors.len()]);
+            r.fill_rect(&rect);
+            r.pop_layer();
+        }
+    }
+
+    const ELEMENTS: Option<u64> = Some(FLAT_LAYERS as u64);
+}
+
+/// 200 groups nesting a clip, an opacity layer and another clip.
+pub struct ClipInOpacityInClip200;
+
+impl VelloScene for ClipInOpacityInClip200 {
+    type State = ();
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "clip_in_opacity_in_clip_200",
+            width: WIDTH,
+            height: HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(_r: &mut R) -> Self::State {}
+
+    fn draw<R: Renderer>(_state: &Self::State, r: &mut R) {
+        draw_backdrop(r);
+
+        for i in 0..CLIP_GROUPS {
+            let cell = grid_cell(r, CLIP_GROUPS, i);
+            let size = cell.width().min(cell.height());
+            let outer = RoundedRect::from_rect(cell.inset(-size * 0.05), size * 0.2);
+            let inner = Circle::new(cell.center(), size * 0.45);
+
+            r.push_clip_layer(&outer.to_path(0.1));
+            r.push_opacity_layer(0.7);
+            r.push_clip_layer(&inner.to_path(0.1));
+            r.set_paint(if i % 2 == 0 {
+                palette::css::SLATE_BLUE
+            } else {
+                palette::css::OLIVE_DRAB
+            });
+            // Larger than both clips, so each of them cuts it.
+            r.fill_rect(&cell.inset(size * 0.1));
+            r.pop_layer();
+            r.pop_layer();
+            r.pop_layer();
+        }
+    }
+
+    const ELEMENTS: Option<u64> = Some(CLIP_GROUPS as u64);
+}
+
+pub(super) fn register(scenes: &mut impl SceneRegistry) {
+    scenes.add::<NestedOpacityLayers64>();
+    scenes.add::<FlatOpacityLayers1000>();
+    scenes.add::<ClipInOpacityInClip200>();
+}
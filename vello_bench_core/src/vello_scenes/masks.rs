@@ -0,0 +1,180 @@
+//! Fills drawn inside mask layers.
+//!
+//! Setup builds a [`Mask`] from a pixmap computed pixel by pixel, and every
+//! element of the scene is drawn in its own layer pushed with
+//! [`push_mask_layer`](Renderer::push_mask_layer), so each one is composited
+//! through the mask:
+//!
+//! - `masked_images_100`: 100 splash-flower tiles (see [`ImageGridState`])
+//!   under an alpha mask of soft dots.
+//! - `gradient_masked_rects_300`: 300 solid rects under a luminance mask of a
+//!   diagonal gradient.
+//!
+//! Vello Hybrid has no masks, so these scenes are
+//! [`CPU_ONLY`](VelloScene::CPU_ONLY). Masks are sampled in canvas
+//! coordinates, so each covers the whole canvas.
+
+use super::images::{ImageGridState, setup_image_grid};
+use super::layer_stack::{draw_backdrop, grid_cell};
+use super::{SceneRegistry, VelloScene, VelloSceneInfo};
+use crate::renderer::Renderer;
+use vello_common::kurbo::{Affine, Rect};
+use vello_common::mask::Mask;
+use vello_common::paint::Image;
+use vello_common::peniko::ImageSampler;
+use vello_common::peniko::color::{PremulRgba8, palette};
+use vello_common::pixmap::Pixmap;
+
+const WIDTH: u16 = 1024;
+const HEIGHT: u16 = 768;
+
+/// Images of `masked_images_100`.
+const MASKED_IMAGES: u32 = 100;
+
+/// Rects of `gradient_masked_rects_300`.
+const MASKED_RECTS: u32 = 300;
+
+/// Distance between the centers of the soft dots, in pixels.
+const DOT_SPACING: f32 = 48.0;
+
+/// A `width`×`height` pixmap with `pixel(x, y)` at each pixel.
+fn mask_pixmap(width: u16, height: u16, pixel: impl Fn(u16, u16) -> PremulRgba8) -> Pixmap {
+    let pixels = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| pixel(x, y))
+        .collect();
+    Pixmap::from_parts(pixels, width, height)
+}
+
+/// An alpha mask of soft dots on a square grid, opaque at their centers and
+/// fading out towards the edge of their cell.
+fn soft_dots_mask(width: u16, height: u16) -> Mask {
+    let pixmap = mask_pixmap(width, height, |x, y| {
+        let dx = (f32::from(x) + 0.5) % DOT_SPACING - DOT_SPACING / 2.0;
+        let dy = (f32::from(y) + 0.5) % DOT_SPACING - DOT_SPACING / 2.0;
+        let falloff = 1.0 - (dx * dx + dy * dy).sqrt() / (DOT_SPACING / 2.0);
+        let a = (falloff.clamp(0.0, 1.0) * 255.0).round() as u8;
+        PremulRgba8 {
+            r: 0,
+            g: 0,
+            b: 0,
+            a,
+        }
+    });
+    Mask::new_alpha(&pixmap)
+}
+
+/// A luminance mask of opaque grays, black in the top left corner and white
+/// in the bottom right one.
+fn diagonal_gradient_mask(width: u16, height: u16) -> Mask {
+    let extent = f32::from(width) + f32::from(height);
+    let pixmap = mask_pixmap(width, height, |x, y| {
+        let v = ((f32::from(x) + f32::from(y)) / extent * 255.0).round() as u8;
+        PremulRgba8 {
+            r: v,
+            g: v,
+            b: v,
+            a: 255,
+        }
+    });
+    Mask::new_luminance(&pixmap)
+}
+
+/// The image and mask of `masked_images_100`.
+pub struct MaskedImagesState {
+    grid: ImageGridState,
+    mask: Mask,
+}
+
+/// 100 image tiles in a grid, each in its own layer masked by soft dots.
+pub struct MaskedImages100;
+
+impl VelloScene for MaskedImages100 {
+    type State = MaskedImagesState;
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "masked_images_100",
+            width: WIDTH,
+            height: HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(r: &mut R) -> Self::State {
+        MaskedImagesState {
+            grid: setup_image_grid(r),
+            mask: soft_dots_mask(r.width(), r.height()),
+        }
+    }
+
+    fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
+        draw_backdrop(r);
+
+        let img_w = f64::from(state.grid.img_w);
+        let img_h = f64::from(state.grid.img_h);
+        for i in 0..MASKED_IMAGES {
+            let cell = grid_cell(r, MASKED_IMAGES, i);
+            r.push_mask_layer(state.mask.clone());
+            r.set_transform(
+                Affine::translate((cell.x0, cell.y0))
+                    * Affine::scale_non_uniform(cell.width() / img_w, cell.height() / img_h),
+            );
+            r.set_paint(Image {
+                image: state.grid.image_source.clone(),
+                sampler: ImageSampler::default(),
+            });
+            r.fill_rect(&Rect::new(0.0, 0.0, img_w, img_h));
+            r.set_transform(Affine::IDENTITY);
+            r.pop_layer();
+        }
+    }
+
+    const CPU_ONLY: bool = true;
+    const ELEMENTS: Option<u64> = Some(MASKED_IMAGES as u64);
+    const TAGS: &'static [&'static str] = &["images"];
+}
+
+/// 300 rects in a grid, each in its own layer masked by a diagonal gradient.
+pub struct GradientMaskedRects300;
+
+impl VelloScene for GradientMaskedRects300 {
+    type State = Mask;
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "gradient_masked_rects_300",
+            width: WIDTH,
+            height: HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(r: &mut R) -> Self::State {
+        diagonal_gradient_mask(r.width(), r.height())
+    }
+
+    fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
+        draw_backdrop(r);
+
+        let colors = [
+            palette::css::CRIMSON,
+            palette::css::DODGER_BLUE,
+            palette::css::GOLDENROD,
+        ];
+        for i in 0..MASKED_RECTS {
+            let cell = grid_cell(r, MASKED_RECTS, i);
+            r.push_mask_layer(state.clone());
+            r.set_paint(colors[i as usize % colors.len()]);
+            // Rects overlap their neighbors.
+            r.fill_rect(&cell.inset(cell.width().min(cell.height()) * 0.1));
+            r.pop_layer();
+        }
+    }
+
+    const CPU_ONLY: bool = true;
+    const ELEMENTS: Option<u64> = Some(MASKED_RECTS as u64);
+}
+
+pub(super) fn register(scenes: &mut impl SceneRegistry) {
+    scenes.add::<MaskedImages100>();
+    scenes.add::<GradientMaskedRects300>();
+}
@@ -4,21 +4,92 @@
 //! into a **setup** phase (run once, for image uploads etc.) and a **draw**
 //! phase (run in the benchmark hot loop).
 //!
+//! Gigantic scenes can also provide a lighter **warm-up proxy**, drawn instead
+//! of the full scene for all but the last warm-up frame (see
+//! [`BenchRunner::run_with_warmup_proxy`](crate::runner::BenchRunner::run_with_warmup_proxy)).
+//!
+//! Animated scenes vary what they draw with the index of the frame, counted
+//! from the first warm-up frame (see [`VelloScene::draw_frame`]).
+//!
 //! Scene files are organised by category:
 //! - [`filled_rects`] — simple vector-only scenes.
+//! - [`isolated_shapes`] — non-touching antialiased shapes.
 //! - [`images`] — image-heavy scenes at varying counts.
+//! - [`filters`] — groups drawn through filter layers.
+//! - [`combined_layers`] — layers combining clip, blend and opacity.
+//! - [`blend_layers`] — layers with non-default blend modes over an image.
+//! - [`layer_stack`] — many small nested or sibling layers.
+//! - [`generated`] — seeded random scenes covering the whole [`Renderer`] API.
+//! - [`winding`] — self-overlapping paths filled with either fill rule.
+//! - [`strokes`] — dashed, round-joined and hairline strokes.
+//! - [`complex_paths`] — one filled path at increasing segment counts.
+//! - [`tiny_shapes`] — shapes of a few pixels, antialiased or aliased.
+//! - [`animated`] — content generated anew every frame.
+//! - [`text`] — glyph runs of an embedded font.
+//! - [`masks`] — fills inside mask layers (Vello CPU only).
+//! - [`shadows`] — blurred rounded rects as box shadows (Vello CPU only).
 //!
-//! To add a new category, create a new sub-module and register its scenes
-//! in the [`register_vello_scenes!`] invocation at the bottom of this file.
+//! Each sub-module registers its own scenes in a `register` function (see
+//! [`SceneRegistry`]). To add a new category, create a new sub-module with
+//! such a function and list it in `register_all`. Scenes that need
+//! pseudo-random content draw it from the seeded generator in `rng`.
 
+mod animated;
+mod blend_layers;
+mod combined_layers;
+mod complex_paths;
 mod filled_rects;
+mod filters;
+pub mod generated;
 pub mod images;
+mod isolated_shapes;
+mod layer_stack;
+mod masks;
+mod paints;
+mod rng;
+mod scroll;
+mod shadows;
+mod strokes;
+mod text;
+mod tiny_shapes;
+mod winding;
+
+use std::any::Any;
+use std::sync::OnceLock;
 
-use crate::renderer::Renderer;
+use crate::renderer::{RecorderRenderer, Renderer};
+use crate::runner::FrameKind;
 
 // Re-export scene types so external code can reference them if needed.
+pub use animated::AnimatedPaths1000;
+pub use blend_layers::{
+    BlendMixedModes300, BlendMultiplyLayers100, BlendNormalLayers100, BlendScreenLayers100,
+};
+pub use combined_layers::{
+    CombinedLayersAll, CombinedLayersClipMultiply, CombinedLayersClipOpacity,
+    CombinedLayersOpacityScreen,
+};
+pub use complex_paths::{
+    ComplexPath100Seg, ComplexPath1000Seg, ComplexPath1000SegEvenOdd, ComplexPath10000Seg,
+    ZoomedInPaths, ZoomedOutPaths,
+};
 pub use filled_rects::FilledRects;
+pub use filters::{
+    BlurRadiusSweep0, BlurRadiusSweep1, BlurRadiusSweep4, BlurRadiusSweep16, BlurRadiusSweep64,
+    BlurredCards100, DropShadowRects300, FiltersAnimated50, FiltersStatic50,
+};
+pub use generated::{Generated, GeneratedParams};
 pub use images::*;
+pub use isolated_shapes::IsolatedShapes;
+pub use layer_stack::{ClipInOpacityInClip200, FlatOpacityLayers1000, NestedOpacityLayers64};
+pub use masks::{GradientMaskedRects300, MaskedImages100};
+pub use paints::{UniquePaints10000, UniquePaints10000Preconverted};
+pub use scroll::{RebuiltScroll, RecordedScroll};
+pub use shadows::BlurredRoundedRects500;
+pub use strokes::{HairlineGrid5000, StrokedPathsDashed1000, StrokedPathsRoundJoins1000};
+pub use text::{TextLargeGlyphs, TextParagraphs1000, TextTransformed};
+pub use tiny_shapes::TinyShapes20000;
+pub use winding::{FillRuleShapes, LONG_PATH_SEGMENTS, LongPath};
 
 /// Metadata for a programmatic vello scene.
 #[derive(Debug, Clone)]
@@ -48,94 +119,519 @@ pub trait VelloScene {
 
     /// Draw the scene. Called in the benchmark hot loop.
     fn draw<R: Renderer>(state: &Self::State, r: &mut R);
+
+    /// Draw frame `frame_index` of the scene. Animated scenes override this to
+    /// vary their content per frame; defaults to [`draw`](VelloScene::draw).
+    fn draw_frame<R: Renderer>(state: &Self::State, r: &mut R, frame_index: u64) {
+        let _ = frame_index;
+        Self::draw(state, r);
+    }
+
+    /// Whether benchmarks time each iteration of this scene individually
+    /// rather than the measurement loop as a whole.
+    const PER_ITERATION: bool = false;
+
+    /// Whether [`draw_warmup_proxy`](VelloScene::draw_warmup_proxy) draws a
+    /// lighter stand-in rather than the full scene.
+    const HAS_WARMUP_PROXY: bool = false;
+
+    /// Whether the scene uses features only Vello CPU implements, such as
+    /// masks. Vello Hybrid benchmarks and renders skip such scenes.
+    const CPU_ONLY: bool = false;
+
+    /// Whether the scene can be drawn into a
+    /// [`Recording`](vello_common::recording::Recording). Scenes that make
+    /// recordings themselves or use what a recorder can't hold get no
+    /// `vello_recording` benchmarks.
+    const RECORDABLE: bool = true;
+
+    /// Whether [`draw_frame`](VelloScene::draw_frame) draws different content
+    /// every frame, so a renderer can't reuse work from the frame before.
+    /// Benchmarks record it in their
+    /// [`BenchmarkResult::animated`](crate::result::BenchmarkResult::animated).
+    const ANIMATED: bool = false;
+
+    /// Elements drawn per frame, reported as the benchmark's
+    /// [`Throughput`](crate::result::Throughput) so that results can be
+    /// normalized per element. `None` for scenes without a natural count.
+    const ELEMENTS: Option<u64> = None;
+
+    /// Tags of the scene's benchmarks besides the ones every scene gets, see
+    /// [`scene_tags`]. E.g. `"images"` for a scene drawing images outside the
+    /// `images` module.
+    const TAGS: &'static [&'static str] = &[];
+
+    /// Draw a lighter version of the scene for warm-up frames. Defaults to the
+    /// full scene.
+    fn draw_warmup_proxy<R: Renderer>(state: &Self::State, r: &mut R) {
+        Self::draw(state, r);
+    }
 }
 
 // ===========================================================================
-// Registration macro & dispatch
+// Registration & dispatch
 // ===========================================================================
 
-/// Register all vello scenes. Add new scene types to the list below.
+/// Receives the scenes of each scene module.
+///
+/// Every scene module has a `pub(super) fn register(scenes: &mut impl
+/// SceneRegistry)` that [`add`](SceneRegistry::add)s each of its scenes, and
+/// is listed once in `register_all`. Adding a scene to an existing module
+/// therefore only touches that module.
+pub trait SceneRegistry {
+    /// Called before the scenes of module `name` are added.
+    fn begin_module(&mut self, name: &'static str) {
+        let _ = name;
+    }
+
+    /// Register scene `S`.
+    fn add<S: VelloScene>(&mut self);
+}
+
+/// The `register` function of a scene module.
+type RegisterFn<G> = fn(&mut G);
+
+/// Hand the scenes of every scene module to `scenes`, in listing order.
+fn register_all<G: SceneRegistry>(scenes: &mut G) {
+    let modules: [(&str, RegisterFn<G>); 18] = [
+        ("filled_rects", filled_rects::register),
+        ("isolated_shapes", isolated_shapes::register),
+        ("filters", filters::register),
+        ("combined_layers", combined_layers::register),
+        ("blend_layers", blend_layers::register),
+        ("layer_stack", layer_stack::register),
+        ("images", images::register),
+        ("generated", generated::register),
+        ("paints", paints::register),
+        ("scroll", scroll::register),
+        ("winding", winding::register),
+        ("strokes", strokes::register),
+        ("complex_paths", complex_paths::register),
+        ("tiny_shapes", tiny_shapes::register),
+        ("animated", animated::register),
+        ("text", text::register),
+        ("masks", masks::register),
+        ("shadows", shadows::register),
+    ];
+    for (name, register) in modules {
+        scenes.begin_module(name);
+        register(scenes);
+    }
+}
+
+/// A registered scene, with its setup and draw functions for renderer `R`.
+///
+/// Dispatch goes through plain function pointers, so a benchmark looks the
+/// scene up once with [`find_scene`] and then draws it without any per-frame
+/// name matching.
+pub struct SceneRegistration<R> {
+    pub info: VelloSceneInfo,
+    /// See [`VelloScene::HAS_WARMUP_PROXY`].
+    pub has_warmup_proxy: bool,
+    /// See [`VelloScene::PER_ITERATION`].
+    pub per_iteration: bool,
+    /// See [`VelloScene::CPU_ONLY`].
+    pub cpu_only: bool,
+    /// See [`VelloScene::ANIMATED`].
+    pub animated: bool,
+    /// See [`VelloScene::ELEMENTS`].
+    pub elements: Option<u64>,
+    /// Takes the scene's info, so that ad hoc generated scenes can recover
+    /// their parameters from the name.
+    setup: fn(&mut R, &VelloSceneInfo) -> Box<dyn Any>,
+    draw_frame: fn(&dyn Any, &mut R, FrameKind, u64),
+}
+
+impl<R: Renderer> SceneRegistration<R> {
+    fn of<S: VelloScene>() -> Self {
+        Self {
+            info: S::info(),
+            has_warmup_proxy: S::HAS_WARMUP_PROXY,
+            per_iteration: S::PER_ITERATION,
+            cpu_only: S::CPU_ONLY,
+            animated: S::ANIMATED,
+            elements: S::ELEMENTS,
+            setup: |r, _info| Box::new(S::setup(r)),
+            draw_frame: draw_frame_of::<S, R>,
+        }
+    }
+
+    /// An ad hoc generated scene. Only setup depends on the parameters, so
+    /// drawing is shared with the registered generated scenes.
+    fn generated(params: GeneratedParams) -> Self {
+        Self {
+            info: params.info(),
+            has_warmup_proxy: false,
+            per_iteration: false,
+            cpu_only: false,
+            animated: false,
+            elements: None,
+            setup: |r, info| {
+                let params = GeneratedParams::parse(info.name).expect("generated scene name");
+                Box::new(generated::setup(params, r))
+            },
+            draw_frame: draw_frame_of::<Generated<0, 0>, R>,
+        }
+    }
+
+    /// A counted image scene with a count chosen at runtime, see
+    /// [`SceneParams`].
+    fn parameterized(family: &'static str, params: SceneParams) -> Self {
+        Self {
+            info: VelloSceneInfo {
+                name: generated::intern(params.name(family)),
+                width: COUNTED_SCENE_WIDTH,
+                height: COUNTED_SCENE_HEIGHT,
+            },
+            has_warmup_proxy: false,
+            per_iteration: false,
+            cpu_only: false,
+            animated: false,
+            elements: None,
+            setup: |r, info| {
+                let (family, params) =
+                    SceneParams::parse(info.name).expect("parameterized scene name");
+                Box::new(images::setup_parameterized(r, family, params))
+            },
+            draw_frame: |state, r, _frame, _frame_index| {
+                let state = state
+                    .downcast_ref::<ParameterizedState>()
+                    .expect("state type mismatch");
+                images::draw_parameterized(state, r);
+            },
+        }
+    }
+
+    /// Run the scene's setup. The returned state must be passed to
+    /// [`draw`](Self::draw) and [`draw_frame`](Self::draw_frame).
+    pub fn setup(&self, r: &mut R) -> Box<dyn Any> {
+        (self.setup)(r, &self.info)
+    }
+
+    /// Draw the first frame of the scene in full.
+    pub fn draw(&self, state: &dyn Any, r: &mut R) {
+        (self.draw_frame)(state, r, FrameKind::Full, 0);
+    }
+
+    /// Draw frame `frame_index` of the scene, or its warm-up proxy for
+    /// [`FrameKind::Proxy`]. Scenes without a proxy always draw in full.
+    pub fn draw_frame(&self, state: &dyn Any, r: &mut R, frame: FrameKind, frame_index: u64) {
+        (self.draw_frame)(state, r, frame, frame_index);
+    }
+}
+
+fn draw_frame_of<S: VelloScene, R: Renderer>(
+    state: &dyn Any,
+    r: &mut R,
+    frame: FrameKind,
+    frame_index: u64,
+) {
+    let state = state
+        .downcast_ref::<S::State>()
+        .expect("state type mismatch");
+    match frame {
+        FrameKind::Proxy => S::draw_warmup_proxy(state, r),
+        FrameKind::Full => S::draw_frame(state, r, frame_index),
+    }
+}
+
+/// All registered scenes for renderer `R`, in listing order.
+pub fn scene_registrations<R: Renderer>() -> Vec<SceneRegistration<R>> {
+    struct Collect<R>(Vec<SceneRegistration<R>>);
+
+    impl<R: Renderer> SceneRegistry for Collect<R> {
+        fn add<S: VelloScene>(&mut self) {
+            self.0.push(SceneRegistration::of::<S>());
+        }
+    }
+
+    let mut collect = Collect(Vec::new());
+    register_all(&mut collect);
+    collect.0
+}
+
+/// The scene called `name`, for renderer `R`.
 ///
-/// This macro generates generic dispatch functions that work with any
-/// backend implementing [`Renderer`]:
-/// - `get_vello_scenes()` — list of all scene metadata
-/// - `setup_scene<R>()` — run setup for a scene by name
-/// - `draw_scene<R>()` — draw a scene by name with pre-computed state
-macro_rules! register_vello_scenes {
-    ($(($name_str:expr, $scene:ty)),* $(,)?) => {
-        /// Get metadata for all registered vello scenes.
-        pub fn get_vello_scenes() -> Vec<VelloSceneInfo> {
-            vec![$(<$scene as VelloScene>::info()),*]
+/// Besides the registered scenes, this accepts any well-formed
+/// `generated_<seed>_<budget>` name (see [`GeneratedParams`]), so generated
+/// scenes can be run for arbitrary seeds without registering them, and
+/// likewise any `<family>?count=<count>` name of a counted image scene (see
+/// [`SceneParams`]).
+pub fn find_scene<R: Renderer>(name: &str) -> Option<SceneRegistration<R>> {
+    struct Find<'a, R> {
+        name: &'a str,
+        found: Option<SceneRegistration<R>>,
+    }
+
+    impl<R: Renderer> SceneRegistry for Find<'_, R> {
+        fn add<S: VelloScene>(&mut self) {
+            if self.found.is_none() && S::info().name == self.name {
+                self.found = Some(SceneRegistration::of::<S>());
+            }
         }
+    }
+
+    let mut find = Find { name, found: None };
+    register_all(&mut find);
+    find.found
+        .or_else(|| GeneratedParams::parse(name).map(SceneRegistration::generated))
+        .or_else(|| {
+            SceneParams::parse(name)
+                .map(|(family, params)| SceneRegistration::parameterized(family, params))
+        })
+}
+
+/// Draws frame `frame_index` of a scene into a recording, given the state
+/// from the scene's setup.
+pub type RecordFrameFn = for<'a, 'b> fn(&dyn Any, &mut RecorderRenderer<'a, 'b>, u64);
+
+/// The draw function of the scene called `name` for [`RecorderRenderer`],
+/// accepting the same names as [`find_scene`].
+///
+/// A [`SceneRegistration`] is for one renderer type, but a
+/// [`RecorderRenderer`] borrows the recorder of a single
+/// [`Renderer::record`] call, so its type differs from call to call. The
+/// state comes from the scene's setup on the renderer the recording is for.
+pub fn find_scene_recorder(name: &str) -> Option<RecordFrameFn> {
+    struct Find<'a> {
+        name: &'a str,
+        found: Option<RecordFrameFn>,
+    }
 
-        /// Run setup for a scene by name using any [`Renderer`] backend.
-        /// Returns a boxed state that must be passed to [`draw_scene`].
-        pub fn setup_scene<R: Renderer>(
-            name: &str,
-            r: &mut R,
-        ) -> Option<Box<dyn std::any::Any>> {
-            match name {
-                $($name_str => {
-                    let state = <$scene as VelloScene>::setup(r);
-                    Some(Box::new(state))
-                }),*
-                _ => None,
+    impl SceneRegistry for Find<'_> {
+        fn add<S: VelloScene>(&mut self) {
+            if self.found.is_none() && S::info().name == self.name {
+                let draw: RecordFrameFn = |state, r, frame_index| {
+                    draw_frame_of::<S, _>(state, r, FrameKind::Full, frame_index);
+                };
+                self.found = Some(draw);
             }
         }
+    }
 
-        /// Draw a scene by name using any [`Renderer`] backend with
-        /// pre-computed state from [`setup_scene`].
-        pub fn draw_scene<R: Renderer>(
-            name: &str,
-            state: &dyn std::any::Any,
-            r: &mut R,
-        ) {
-            match name {
-                $($name_str => {
+    let mut find = Find { name, found: None };
+    register_all(&mut find);
+    find.found
+        .or_else(|| {
+            GeneratedParams::parse(name).map(|_| -> RecordFrameFn {
+                |state, r, frame_index| {
+                    draw_frame_of::<Generated<0, 0>, _>(state, r, FrameKind::Full, frame_index);
+                }
+            })
+        })
+        .or_else(|| {
+            SceneParams::parse(name).map(|_| -> RecordFrameFn {
+                |state, r, _frame_index| {
                     let state = state
-                        .downcast_ref::<<$scene as VelloScene>::State>()
+                        .downcast_ref::<ParameterizedState>()
                         .expect("state type mismatch");
-                    <$scene as VelloScene>::draw(state, r);
-                }),*
-                _ => panic!("unknown vello scene: {name}"),
+                    images::draw_parameterized(state, r);
+                }
+            })
+        })
+}
+
+/// Renderer-independent facts about a registered scene.
+struct SceneSummary {
+    module: &'static str,
+    info: VelloSceneInfo,
+    has_warmup_proxy: bool,
+    per_iteration: bool,
+    cpu_only: bool,
+    recordable: bool,
+    tags: Vec<&'static str>,
+}
+
+/// Summaries of all registered scenes, collected once.
+fn summaries() -> &'static [SceneSummary] {
+    struct Collect {
+        module: &'static str,
+        scenes: Vec<SceneSummary>,
+    }
+
+    impl SceneRegistry for Collect {
+        fn begin_module(&mut self, name: &'static str) {
+            self.module = name;
+        }
+
+        fn add<S: VelloScene>(&mut self) {
+            self.scenes.push(SceneSummary {
+                module: self.module,
+                info: S::info(),
+                has_warmup_proxy: S::HAS_WARMUP_PROXY,
+                per_iteration: S::PER_ITERATION,
+                cpu_only: S::CPU_ONLY,
+                recordable: S::RECORDABLE,
+                tags: tags_of::<S>(self.module),
+            });
+        }
+    }
+
+    static SUMMARIES: OnceLock<Vec<SceneSummary>> = OnceLock::new();
+    SUMMARIES.get_or_init(|| {
+        let mut collect = Collect {
+            module: "",
+            scenes: Vec::new(),
+        };
+        register_all(&mut collect);
+        collect.scenes
+    })
+}
+
+fn summary(name: &str) -> Option<&'static SceneSummary> {
+    summaries().iter().find(|s| s.info.name == name)
+}
+
+/// Tags of scene `S`, registered by `module`, see [`scene_tags`].
+fn tags_of<S: VelloScene>(module: &'static str) -> Vec<&'static str> {
+    let mut tags = vec![module];
+    if S::ANIMATED {
+        tags.push("animated");
+    }
+    if let Some(elements) = S::ELEMENTS {
+        tags.extend(count_tags(elements));
+    }
+    tags.extend(S::TAGS);
+    let mut unique = Vec::with_capacity(tags.len());
+    for tag in tags {
+        if !unique.contains(&tag) {
+            unique.push(tag);
+        }
+    }
+    unique
+}
+
+/// `small` for scenes of at most 100 elements, `large` for at least 10 000.
+pub(super) const fn count_tags(count: u64) -> &'static [&'static str] {
+    if count <= 100 {
+        &["small"]
+    } else if count >= 10_000 {
+        &["large"]
+    } else {
+        &[]
+    }
+}
+
+/// Tags of the scene called `name`, for selecting its benchmarks by content
+/// (see [`BenchmarkInfo::tags`](crate::registry::BenchmarkInfo::tags)): the
+/// module registering it, e.g. `images` or `text`, `animated` for
+/// [`ANIMATED`](VelloScene::ANIMATED) scenes, `small` or `large` for scenes
+/// of at most 100 or at least 10 000 [`ELEMENTS`](VelloScene::ELEMENTS), and
+/// its own [`TAGS`](VelloScene::TAGS).
+///
+/// Ad hoc generated and parameterized scenes are tagged like the registered
+/// ones of their module. Empty for unknown scenes.
+pub fn scene_tags(name: &str) -> Vec<&'static str> {
+    if let Some(summary) = summary(name) {
+        return summary.tags.clone();
+    }
+    if GeneratedParams::parse(name).is_some() {
+        return vec!["generated"];
+    }
+    match SceneParams::parse(name) {
+        Some((_, params)) => std::iter::once("images")
+            .chain(count_tags(params.count.into()).iter().copied())
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Problems with the scene registration: modules listed in `register_all` that
+/// register no scene (most likely a forgotten `add`), and scene names used
+/// more than once. Empty when everything is registered correctly.
+pub fn scene_registration_problems() -> Vec<String> {
+    struct Modules(Vec<(&'static str, usize)>);
+
+    impl SceneRegistry for Modules {
+        fn begin_module(&mut self, name: &'static str) {
+            self.0.push((name, 0));
+        }
+
+        fn add<S: VelloScene>(&mut self) {
+            if let Some((_, count)) = self.0.last_mut() {
+                *count += 1;
             }
         }
-    };
-}
-
-// Register all scenes here.
-register_vello_scenes!(
-    // Vector-only
-    ("filled_rects", FilledRects),
-    // Tiled flowers
-    ("tiled_flowers_100", TiledFlowers100),
-    ("tiled_flowers_300", TiledFlowers300),
-    ("tiled_flowers_1000", TiledFlowers1000),
-    ("tiled_flowers_10000", TiledFlowers10000),
-    // Overlapping images (opaque)
-    ("overlapping_images_100", OverlappingImages100),
-    ("overlapping_images_1000", OverlappingImages1000),
-    ("overlapping_images_10000", OverlappingImages10000),
-    // Clipped image cards
-    ("clipped_image_cards_100", ClippedImageCards100),
-    ("clipped_image_cards_1000", ClippedImageCards1000),
-    ("clipped_image_cards_10000", ClippedImageCards10000),
-    // Large overlapping images (opaque, no alpha)
-    ("large_overlapping_images_100", LargeOverlappingImages100),
-    ("large_overlapping_images_1000", LargeOverlappingImages1000),
-    ("large_overlapping_images_10000", LargeOverlappingImages10000),
-    // Rotated images
-    ("rotated_images_100", RotatedImages100),
-    ("rotated_images_1000", RotatedImages1000),
-    ("rotated_images_10000", RotatedImages10000),
-    // Image cards with SVG-style borders
-    ("image_cards_with_borders_100", ImageCardsWithBorders100),
-    ("image_cards_with_borders_1000", ImageCardsWithBorders1000),
-    ("image_cards_with_borders_10000", ImageCardsWithBorders10000),
-    // Mixed image and vector
-    ("mixed_image_and_vector_100", MixedImageAndVector100),
-    ("mixed_image_and_vector_1000", MixedImageAndVector1000),
-    ("mixed_image_and_vector_10000", MixedImageAndVector10000),
-    // Paths and images — 100 random SVG paths then 1 image, repeated
-    ("paths_and_images_100", PathsAndImages100),
-);
+    }
+
+    let mut modules = Modules(Vec::new());
+    register_all(&mut modules);
+
+    let mut problems: Vec<String> = modules
+        .0
+        .iter()
+        .filter(|(_, count)| *count == 0)
+        .map(|(name, _)| format!("scene module `{name}` registers no scenes"))
+        .collect();
+
+    let scenes = summaries();
+    for (i, scene) in scenes.iter().enumerate() {
+        if let Some(first) = scenes[..i].iter().find(|s| s.info.name == scene.info.name) {
+            problems.push(format!(
+                "scene `{}` is registered by both `{}` and `{}`",
+                scene.info.name, first.module, scene.module
+            ));
+        }
+    }
+
+    problems
+}
+
+/// Get metadata for all registered vello scenes.
+pub fn get_vello_scenes() -> Vec<VelloSceneInfo> {
+    summaries().iter().map(|s| s.info.clone()).collect()
+}
+
+/// Run setup for a scene by name using any [`Renderer`] backend.
+/// Returns a boxed state that must be passed to [`draw_scene`].
+pub fn setup_scene<R: Renderer>(name: &str, r: &mut R) -> Option<Box<dyn Any>> {
+    Some(find_scene::<R>(name)?.setup(r))
+}
+
+/// Draw a scene by name using any [`Renderer`] backend with pre-computed
+/// state from [`setup_scene`].
+pub fn draw_scene<R: Renderer>(name: &str, state: &dyn Any, r: &mut R) {
+    find_scene::<R>(name)
+        .unwrap_or_else(|| panic!("unknown vello scene: {name}"))
+        .draw(state, r);
+}
+
+/// Whether a scene draws a lighter proxy for [`FrameKind::Proxy`] frames.
+/// Returns `false` for unknown scenes.
+pub fn has_warmup_proxy(name: &str) -> bool {
+    summary(name).is_some_and(|s| s.has_warmup_proxy)
+}
+
+/// Whether a scene is timed per iteration (see [`VelloScene::PER_ITERATION`]).
+/// Returns `false` for unknown scenes.
+pub fn times_per_iteration(name: &str) -> bool {
+    summary(name).is_some_and(|s| s.per_iteration)
+}
+
+/// Whether a scene only renders on Vello CPU (see [`VelloScene::CPU_ONLY`]).
+/// Returns `false` for unknown scenes.
+pub fn is_cpu_only(name: &str) -> bool {
+    summary(name).is_some_and(|s| s.cpu_only)
+}
+
+/// Whether a scene can be recorded (see [`VelloScene::RECORDABLE`]). Returns
+/// `false` for scenes that aren't registered, such as ad hoc generated ones,
+/// since nothing declares which operations they use.
+pub fn is_recordable(name: &str) -> bool {
+    summary(name).is_some_and(|s| s.recordable)
+}
+
+/// Draw frame `frame_index` of a scene by name, or its warm-up proxy for
+/// [`FrameKind::Proxy`]. Looks the scene up on every call; benchmark loops
+/// should use [`find_scene`] once instead.
+pub fn draw_scene_frame<R: Renderer>(
+    name: &str,
+    state: &dyn Any,
+    r: &mut R,
+    frame: FrameKind,
+    frame_index: u64,
+) {
+    find_scene::<R>(name)
+        .unwrap_or_else(|| panic!("unknown vello scene: {name}"))
+        .draw_frame(state, r, frame, frame_index);
+}
@@ -0,0 +1,96 @@
+//! Many small rects, each with its own solid color.
+//!
+//! Every draw sets a new paint, so this is the scene where the cost of
+//! `set_paint` shows most. `unique_paints_10000` builds each color in the
+//! draw loop and passes it unconverted, as most scenes do;
+//! `unique_paints_10000_preconverted` converts all of them to
+//! [`PaintType`]s during setup. See also the `set_paint_*` benchmarks.
+
+use super::{SceneRegistry, VelloScene, VelloSceneInfo};
+use crate::renderer::Renderer;
+use vello_common::color::{AlphaColor, Srgb};
+use vello_common::kurbo::Rect;
+use vello_common::paint::PaintType;
+
+/// Number of rects (and paints).
+const COUNT: u32 = 10_000;
+
+/// Rects per row; the grid is square.
+const COLUMNS: u32 = 100;
+
+/// Color of rect `i`, varying across the grid so no two are equal.
+fn color(i: u32) -> AlphaColor<Srgb> {
+    let col = (i % COLUMNS) as f32 / COLUMNS as f32;
+    let row = (i / COLUMNS) as f32 / (COUNT / COLUMNS) as f32;
+    AlphaColor::new([col, row, 1.0 - col * row, 0.9])
+}
+
+/// Bounds of rect `i` on a `width`×`height` canvas.
+fn cell(i: u32, width: f64, height: f64) -> Rect {
+    let rows = COUNT / COLUMNS;
+    let w = width / f64::from(COLUMNS);
+    let h = height / f64::from(rows);
+    let x = f64::from(i % COLUMNS) * w;
+    let y = f64::from(i / COLUMNS) * h;
+    Rect::new(x, y, x + w, y + h)
+}
+
+/// Colors built and converted in the draw loop.
+pub struct UniquePaints10000;
+
+impl VelloScene for UniquePaints10000 {
+    type State = ();
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "unique_paints_10000",
+            width: 1024,
+            height: 768,
+        }
+    }
+
+    fn setup<R: Renderer>(_r: &mut R) -> Self::State {}
+
+    fn draw<R: Renderer>(_state: &Self::State, r: &mut R) {
+        let width = f64::from(r.width());
+        let height = f64::from(r.height());
+        for i in 0..COUNT {
+            r.set_paint(color(i));
+            r.fill_rect(&cell(i, width, height));
+        }
+    }
+}
+
+/// The same rects and colors as [`UniquePaints10000`], with the paints
+/// converted during setup.
+pub struct UniquePaints10000Preconverted;
+
+impl VelloScene for UniquePaints10000Preconverted {
+    type State = Vec<PaintType>;
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "unique_paints_10000_preconverted",
+            width: 1024,
+            height: 768,
+        }
+    }
+
+    fn setup<R: Renderer>(_r: &mut R) -> Self::State {
+        (0..COUNT).map(|i| color(i).into()).collect()
+    }
+
+    fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
+        let width = f64::from(r.width());
+        let height = f64::from(r.height());
+        for (i, paint) in (0..COUNT).zip(state) {
+            r.set_paint(paint.clone());
+            r.fill_rect(&cell(i, width, height));
+        }
+    }
+}
+
+pub(super) fn register(scenes: &mut impl SceneRegistry) {
+    scenes.add::<UniquePaints10000>();
+    scenes.add::<UniquePaints10000Preconverted>();
+}
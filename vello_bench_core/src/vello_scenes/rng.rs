@@ -0,0 +1,34 @@
+//! Deterministic pseudo-random numbers for the hand-written scenes.
+//!
+//! A scene's content must not change between runs or platforms, so scenes
+//! that place things "randomly" draw from a [`Lcg`] with a fixed seed rather
+//! than from `rand`, whose algorithms may change between versions. The
+//! generated scenes are the exception: their seed is part of their name (see
+//! [`GeneratedParams`](super::GeneratedParams)).
+
+/// A 64-bit linear congruential generator with Knuth's MMIX constants.
+pub(super) struct Lcg(u64);
+
+impl Lcg {
+    pub(super) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn step(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1);
+        self.0
+    }
+
+    /// A number in `[0, 1)` from the top 31 bits of the next state.
+    pub(super) fn next_f64(&mut self) -> f64 {
+        (self.step() >> 33) as f64 / (1u64 << 31) as f64
+    }
+
+    /// A number in `[0, 1)` from the top 24 bits of the next state.
+    pub(super) fn next_f32(&mut self) -> f32 {
+        (self.step() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
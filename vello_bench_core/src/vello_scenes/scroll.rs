@@ -0,0 +1,301 @@
+//! Scrolling through a page of cards with images and text.
+//!
+//! The page is four viewports tall and scrolls by [`SCROLL_STEP`] pixels per
+//! frame, wrapping around at the end. `recorded_scroll` records the page once
+//! during setup and replays the recording under a new translation each frame,
+//! the cheapest way to scroll; `rebuilt_scroll` draws the page immediate-mode
+//! every frame. Both draw the page with [`draw_page`], so they render the same
+//! pixels, and both are timed per iteration to capture the distribution of
+//! frame times during the scroll.
+//!
+//! How close `recorded_scroll` gets to free depends on how much work the
+//! backend can skip when replaying a prepared recording under a translation.
+//! As it makes a recording itself, it isn't
+//! [`RECORDABLE`](VelloScene::RECORDABLE).
+
+use std::sync::Arc;
+
+use super::generated::FONT_BYTES;
+use super::images::{ImageGridState, setup_image_grid};
+use super::{SceneRegistry, VelloScene, VelloSceneInfo};
+use crate::renderer::Renderer;
+use vello_common::glyph::Glyph;
+use vello_common::kurbo::{Affine, Rect, RoundedRect};
+use vello_common::paint::{Image, PaintType};
+use vello_common::peniko::color::palette;
+use vello_common::peniko::{Blob, FontData, ImageSampler};
+use vello_common::recording::{Recorder, Recording};
+
+const WIDTH: u16 = 1024;
+const HEIGHT: u16 = 768;
+
+/// Height of the whole page.
+const PAGE_HEIGHT: f64 = HEIGHT as f64 * 4.0;
+
+/// Height of one card, including the gap below it.
+const CARD_PITCH: f64 = 256.0;
+
+/// Pixels scrolled per frame.
+const SCROLL_STEP: f64 = 12.0;
+
+/// Glyphs per line of body text.
+const LINE_GLYPHS: u32 = 64;
+
+/// Lines of body text per card.
+const BODY_LINES: u32 = 6;
+
+/// Translation of frame `frame_index`: the page scrolled down by
+/// [`SCROLL_STEP`] per frame, back at the top after its last viewport.
+fn scroll_transform(frame_index: u64) -> Affine {
+    let range = PAGE_HEIGHT - f64::from(HEIGHT);
+    let offset = (frame_index as f64 * SCROLL_STEP) % range;
+    Affine::translate((0.0, -offset))
+}
+
+/// The drawing operations the page needs, so that it can be drawn both to a
+/// [`Renderer`] and into a [`Recorder`].
+trait PageCanvas {
+    fn set_transform(&mut self, transform: Affine);
+    fn set_paint(&mut self, paint: PaintType);
+    fn fill_rect(&mut self, rect: &Rect);
+    fn fill_rounded_rect(&mut self, rect: &RoundedRect);
+    fn fill_glyphs(&mut self, font: &FontData, font_size: f32, glyphs: &[Glyph]);
+}
+
+impl<R: Renderer> PageCanvas for R {
+    fn set_transform(&mut self, transform: Affine) {
+        Renderer::set_transform(self, transform);
+    }
+
+    fn set_paint(&mut self, paint: PaintType) {
+        Renderer::set_paint(self, paint);
+    }
+
+    fn fill_rect(&mut self, rect: &Rect) {
+        Renderer::fill_rect(self, rect);
+    }
+
+    fn fill_rounded_rect(&mut self, rect: &RoundedRect) {
+        Renderer::fill_path(self, &rect.to_path(0.1));
+    }
+
+    fn fill_glyphs(&mut self, font: &FontData, font_size: f32, glyphs: &[Glyph]) {
+        self.glyph_run(font)
+            .font_size(font_size)
+            .fill_glyphs(glyphs.iter().copied());
+    }
+}
+
+impl PageCanvas for Recorder<'_> {
+    fn set_transform(&mut self, transform: Affine) {
+        Recorder::set_transform(self, transform);
+    }
+
+    fn set_paint(&mut self, paint: PaintType) {
+        Recorder::set_paint(self, paint);
+    }
+
+    fn fill_rect(&mut self, rect: &Rect) {
+        Recorder::fill_rect(self, rect);
+    }
+
+    fn fill_rounded_rect(&mut self, rect: &RoundedRect) {
+        Recorder::fill_path(self, &rect.to_path(0.1));
+    }
+
+    fn fill_glyphs(&mut self, font: &FontData, font_size: f32, glyphs: &[Glyph]) {
+        self.glyph_run(font)
+            .font_size(font_size)
+            .fill_glyphs(glyphs.iter().copied());
+    }
+}
+
+/// Resources of the page: the card image and the text font.
+pub struct PageState {
+    image: ImageGridState,
+    font: FontData,
+}
+
+impl PageState {
+    fn new<R: Renderer>(r: &mut R) -> Self {
+        Self {
+            image: setup_image_grid(r),
+            font: FontData::new(Blob::new(Arc::new(FONT_BYTES)), 0),
+        }
+    }
+}
+
+/// A line of `count` glyphs starting at `(x, y)`. There is no shaping; the
+/// glyph IDs cycle through the letters of DejaVu Sans Mono, offset by `seed`
+/// so that lines differ.
+fn text_line(seed: u32, count: u32, x: f32, y: f32, font_size: f32) -> Vec<Glyph> {
+    let advance = font_size * 0.6;
+    (0..count)
+        .map(|i| Glyph {
+            id: 36 + (seed * 7 + i * 11) % 52,
+            x: x + i as f32 * advance,
+            y,
+        })
+        .collect()
+}
+
+/// Draw the page under `origin`: a column of cards, each with an image, a
+/// heading and a few lines of body text.
+fn draw_page(page: &PageState, canvas: &mut impl PageCanvas, origin: Affine) {
+    let cards = (PAGE_HEIGHT / CARD_PITCH) as u32;
+    let margin = 24.0;
+    let thumb = CARD_PITCH - 3.0 * margin;
+    let img_w = f64::from(page.image.img_w);
+    let img_h = f64::from(page.image.img_h);
+
+    canvas.set_transform(origin);
+    canvas.set_paint(palette::css::WHITE_SMOKE.into());
+    canvas.fill_rect(&Rect::new(0.0, 0.0, f64::from(WIDTH), PAGE_HEIGHT));
+
+    for card in 0..cards {
+        let top = f64::from(card) * CARD_PITCH + margin;
+        let card_rect = Rect::new(
+            margin,
+            top,
+            f64::from(WIDTH) - margin,
+            top + CARD_PITCH - margin,
+        );
+
+        canvas.set_transform(origin);
+        canvas.set_paint(palette::css::WHITE.into());
+        canvas.fill_rounded_rect(&RoundedRect::from_rect(card_rect, 12.0));
+
+        // Thumbnail, scaled to a square.
+        let thumb_x = card_rect.x0 + margin;
+        let thumb_y = card_rect.y0 + margin;
+        canvas.set_transform(
+            origin
+                * Affine::translate((thumb_x, thumb_y))
+                * Affine::scale_non_uniform(thumb / img_w, thumb / img_h),
+        );
+        canvas.set_paint(
+            Image {
+                image: page.image.image_source.clone(),
+                sampler: ImageSampler::default(),
+            }
+            .into(),
+        );
+        canvas.fill_rect(&Rect::new(0.0, 0.0, img_w, img_h));
+
+        // Heading and body text to the right of the thumbnail.
+        canvas.set_transform(origin);
+        let text_x = (thumb_x + thumb + margin) as f32;
+        let heading_y = (thumb_y + 28.0) as f32;
+        canvas.set_paint(palette::css::BLACK.into());
+        canvas.fill_glyphs(
+            &page.font,
+            24.0,
+            &text_line(card, LINE_GLYPHS / 2, text_x, heading_y, 24.0),
+        );
+        canvas.set_paint(palette::css::DIM_GRAY.into());
+        for line in 0..BODY_LINES {
+            let y = heading_y + 36.0 + line as f32 * 22.0;
+            canvas.fill_glyphs(
+                &page.font,
+                14.0,
+                &text_line(card * BODY_LINES + line, LINE_GLYPHS, text_x, y, 14.0),
+            );
+        }
+
+        // Accent bar along the bottom of the card.
+        canvas.set_paint(palette::css::ROYAL_BLUE.into());
+        canvas.fill_rect(&Rect::new(
+            text_x.into(),
+            card_rect.y1 - margin - 6.0,
+            card_rect.x1 - margin,
+            card_rect.y1 - margin,
+        ));
+    }
+
+    canvas.set_transform(Affine::IDENTITY);
+}
+
+/// The page recorded once, replayed under the scroll translation.
+pub struct RecordedScroll;
+
+/// A prepared recording of the page. Keeps the page resources alive, since the
+/// recording refers to them.
+pub struct RecordedScrollState {
+    _page: PageState,
+    recording: Recording,
+}
+
+impl VelloScene for RecordedScroll {
+    type State = RecordedScrollState;
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "recorded_scroll",
+            width: WIDTH,
+            height: HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(r: &mut R) -> Self::State {
+        let page = PageState::new(r);
+        let mut recording = Recording::new();
+        r.record(&mut recording, |recorder| {
+            draw_page(&page, recorder, Affine::IDENTITY);
+        });
+        r.prepare_recording(&mut recording);
+        RecordedScrollState {
+            _page: page,
+            recording,
+        }
+    }
+
+    fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
+        Self::draw_frame(state, r, 0);
+    }
+
+    fn draw_frame<R: Renderer>(state: &Self::State, r: &mut R, frame_index: u64) {
+        r.set_transform(scroll_transform(frame_index));
+        r.execute_recording(&state.recording);
+        r.set_transform(Affine::IDENTITY);
+    }
+
+    const PER_ITERATION: bool = true;
+    const ANIMATED: bool = true;
+    const RECORDABLE: bool = false;
+}
+
+/// Control for [`RecordedScroll`]: the same page and scroll, drawn
+/// immediate-mode every frame.
+pub struct RebuiltScroll;
+
+impl VelloScene for RebuiltScroll {
+    type State = PageState;
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "rebuilt_scroll",
+            width: WIDTH,
+            height: HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(r: &mut R) -> Self::State {
+        PageState::new(r)
+    }
+
+    fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
+        Self::draw_frame(state, r, 0);
+    }
+
+    fn draw_frame<R: Renderer>(state: &Self::State, r: &mut R, frame_index: u64) {
+        draw_page(state, r, scroll_transform(frame_index));
+    }
+
+    const PER_ITERATION: bool = true;
+    const ANIMATED: bool = true;
+}
+
+pub(super) fn register(scenes: &mut impl SceneRegistry) {
+    scenes.add::<RecordedScroll>();
+    scenes.add::<RebuiltScroll>();
+}
@@ -0,0 +1,63 @@
+//! Box shadows drawn with [`fill_blurred_rounded_rect`](Renderer::fill_blurred_rounded_rect).
+//!
+//! - `blurred_rounded_rects_500`: 500 cards, each over a blurred rounded
+//!   rect offset below it, as in a page of CSS `box-shadow`s.
+//!
+//! Vello Hybrid doesn't implement blurred rounded rects, so the scene is
+//! [`CPU_ONLY`](VelloScene::CPU_ONLY). Once it does, the flag can go. The
+//! recorder doesn't hold them either, so it isn't
+//! [`RECORDABLE`](VelloScene::RECORDABLE).
+
+use super::layer_stack::{draw_backdrop, grid_cell};
+use super::{SceneRegistry, VelloScene, VelloSceneInfo};
+use crate::renderer::Renderer;
+use vello_common::kurbo::{RoundedRect, Shape, Vec2};
+use vello_common::peniko::color::palette;
+
+/// Cards of `blurred_rounded_rects_500`.
+const SHADOWED_CARDS: u32 = 500;
+
+/// 500 white cards in a grid, each casting a blurred shadow.
+pub struct BlurredRoundedRects500;
+
+impl VelloScene for BlurredRoundedRects500 {
+    type State = ();
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "blurred_rounded_rects_500",
+            width: 1024,
+            height: 768,
+        }
+    }
+
+    fn setup<R: Renderer>(_r: &mut R) -> Self::State {}
+
+    fn draw<R: Renderer>(_state: &Self::State, r: &mut R) {
+        draw_backdrop(r);
+
+        for i in 0..SHADOWED_CARDS {
+            let cell = grid_cell(r, SHADOWED_CARDS, i);
+            let size = cell.width().min(cell.height());
+            let card = cell.inset(-size * 0.15);
+            let radius = size * 0.12;
+
+            r.set_paint(palette::css::BLACK.with_alpha(0.35));
+            r.fill_blurred_rounded_rect(
+                &(card + Vec2::new(0.0, size * 0.06)),
+                radius as f32,
+                (size * 0.08) as f32,
+            );
+            r.set_paint(palette::css::WHITE);
+            r.fill_path(&RoundedRect::from_rect(card, radius).to_path(0.1));
+        }
+    }
+
+    const CPU_ONLY: bool = true;
+    const RECORDABLE: bool = false;
+    const ELEMENTS: Option<u64> = Some(SHADOWED_CARDS as u64);
+}
+
+pub(super) fn register(scenes: &mut impl SceneRegistry) {
+    scenes.add::<BlurredRoundedRects500>();
+}
@@ -0,0 +1,231 @@
+//! Stroked paths with non-default [`Stroke`] styles.
+//!
+//! Stroke expansion cost depends heavily on joins, caps and dashing, which
+//! the other scenes leave at their defaults. Setup builds the paths and their
+//! styles, so drawing only sets the stroke and strokes each path:
+//!
+//! - `stroked_paths_dashed_1000`: 1000 wavy paths of 12 cubic segments,
+//!   dashed with one of [`DASH_PATTERNS`], with miter joins at varying miter
+//!   limits and butt or square caps.
+//! - `stroked_paths_round_joins_1000`: the same paths, undashed, with round
+//!   joins and caps at varying widths.
+//! - `hairline_grid_5000`: a 50×50 grid of cells, each stroking its top and
+//!   left edge 0.5 px wide.
+
+use std::f64::consts::TAU;
+
+use super::layer_stack::draw_backdrop;
+use super::{SceneRegistry, VelloScene, VelloSceneInfo};
+use crate::renderer::Renderer;
+use vello_common::color::{AlphaColor, Srgb};
+use vello_common::kurbo::{Affine, BezPath, Cap, Join, Point, Stroke, Vec2};
+use vello_common::peniko::color::palette;
+
+const WIDTH: u16 = 1024;
+const HEIGHT: u16 = 768;
+
+/// Paths of the `stroked_paths_*` scenes.
+const STROKED_PATHS: u32 = 1000;
+
+/// Cubic segments per path of the `stroked_paths_*` scenes.
+const WAVE_SEGMENTS: u32 = 12;
+
+/// Dash arrays of `stroked_paths_dashed_1000`, cycled through by path.
+const DASH_PATTERNS: [&[f64]; 4] = [
+    &[8.0, 4.0],
+    &[2.0, 3.0],
+    &[12.0, 3.0, 2.0, 3.0],
+    &[1.0, 1.0],
+];
+
+/// Rows and columns of `hairline_grid_5000`, each cell stroking two edges.
+const HAIRLINE_CELLS: u32 = 50;
+
+const COLORS: [AlphaColor<Srgb>; 5] = [
+    palette::css::CRIMSON,
+    palette::css::SEA_GREEN,
+    palette::css::ROYAL_BLUE,
+    palette::css::DARK_ORANGE,
+    palette::css::DARK_ORCHID,
+];
+
+/// A path with the stroke style and color it is drawn with.
+struct StrokedPath {
+    path: BezPath,
+    stroke: Stroke,
+    color: AlphaColor<Srgb>,
+}
+
+/// The paths of a stroke scene, laid out during setup.
+pub struct StrokeState {
+    paths: Vec<StrokedPath>,
+}
+
+fn draw_stroked_paths<R: Renderer>(state: &StrokeState, r: &mut R) {
+    draw_backdrop(r);
+
+    for stroked in &state.paths {
+        r.set_stroke(stroked.stroke.clone());
+        r.set_paint(stroked.color);
+        r.stroke_path(&stroked.path);
+    }
+}
+
+/// Path `i` of the `stroked_paths_*` scenes: a wave of [`WAVE_SEGMENTS`]
+/// cubics about 300 px long, placed and rotated differently for every `i`.
+fn wavy_path(i: u32, width: f64, height: f64) -> BezPath {
+    let start = Point::new(f64::from(i * 97) % width, f64::from(i * 53) % height);
+    // Rotating by the golden angle spreads the directions evenly.
+    let angle = f64::from(i) * TAU * 0.381_966;
+    let transform = Affine::translate(start.to_vec2()) * Affine::rotate(angle);
+
+    let step = 25.0;
+    let amplitude = 6.0 + f64::from(i % 5) * 4.0;
+    let mut path = BezPath::new();
+    path.move_to(transform * Point::ORIGIN);
+    for segment in 0..WAVE_SEGMENTS {
+        let x = f64::from(segment) * step;
+        // Alternate up and down, so consecutive segments meet at a corner.
+        let dy = if segment % 2 == 0 {
+            amplitude
+        } else {
+            -amplitude
+        };
+        path.curve_to(
+            transform * Point::new(x + step * 0.3, dy),
+            transform * Point::new(x + step * 0.7, dy),
+            transform * Point::new(x + step, dy * 0.2),
+        );
+    }
+    path
+}
+
+/// 1000 wavy paths, each dashed with one of [`DASH_PATTERNS`].
+pub struct StrokedPathsDashed1000;
+
+impl VelloScene for StrokedPathsDashed1000 {
+    type State = StrokeState;
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "stroked_paths_dashed_1000",
+            width: WIDTH,
+            height: HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(r: &mut R) -> Self::State {
+        let (width, height) = (f64::from(r.width()), f64::from(r.height()));
+        let paths = (0..STROKED_PATHS)
+            .map(|i| {
+                let dashes = DASH_PATTERNS[i as usize % DASH_PATTERNS.len()];
+                let stroke = Stroke::new(1.5 + f64::from(i % 3))
+                    .with_join(Join::Miter)
+                    .with_miter_limit([1.5, 4.0, 10.0][i as usize % 3])
+                    .with_caps(if i % 2 == 0 { Cap::Butt } else { Cap::Square })
+                    .with_dashes(f64::from(i % 7), dashes.iter().copied());
+                StrokedPath {
+                    path: wavy_path(i, width, height),
+                    stroke,
+                    color: COLORS[i as usize % COLORS.len()],
+                }
+            })
+            .collect();
+        StrokeState { paths }
+    }
+
+    fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
+        draw_stroked_paths(state, r);
+    }
+
+    const ELEMENTS: Option<u64> = Some(STROKED_PATHS as u64);
+}
+
+/// 1000 wavy paths with round joins and caps, 2 to 10 px wide.
+pub struct StrokedPathsRoundJoins1000;
+
+impl VelloScene for StrokedPathsRoundJoins1000 {
+    type State = StrokeState;
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "stroked_paths_round_joins_1000",
+            width: WIDTH,
+            height: HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(r: &mut R) -> Self::State {
+        let (width, height) = (f64::from(r.width()), f64::from(r.height()));
+        let paths = (0..STROKED_PATHS)
+            .map(|i| StrokedPath {
+                path: wavy_path(i, width, height),
+                stroke: Stroke::new(2.0 + f64::from(i % 5) * 2.0)
+                    .with_join(Join::Round)
+                    .with_caps(Cap::Round),
+                color: COLORS[i as usize % COLORS.len()],
+            })
+            .collect();
+        StrokeState { paths }
+    }
+
+    fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
+        draw_stroked_paths(state, r);
+    }
+
+    const ELEMENTS: Option<u64> = Some(STROKED_PATHS as u64);
+}
+
+/// 5000 hairline segments forming a 50×50 grid.
+pub struct HairlineGrid5000;
+
+impl VelloScene for HairlineGrid5000 {
+    type State = StrokeState;
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "hairline_grid_5000",
+            width: WIDTH,
+            height: HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(r: &mut R) -> Self::State {
+        let cell_w = f64::from(r.width()) / f64::from(HAIRLINE_CELLS);
+        let cell_h = f64::from(r.height()) / f64::from(HAIRLINE_CELLS);
+        let stroke = Stroke::new(0.5);
+
+        let mut paths = Vec::new();
+        for row in 0..HAIRLINE_CELLS {
+            for col in 0..HAIRLINE_CELLS {
+                let corner = Point::new(f64::from(col) * cell_w, f64::from(row) * cell_h);
+                for end in [
+                    corner + Vec2::new(cell_w, 0.0),
+                    corner + Vec2::new(0.0, cell_h),
+                ] {
+                    let mut path = BezPath::new();
+                    path.move_to(corner);
+                    path.line_to(end);
+                    paths.push(StrokedPath {
+                        path,
+                        stroke: stroke.clone(),
+                        color: palette::css::BLACK,
+                    });
+                }
+            }
+        }
+        StrokeState { paths }
+    }
+
+    fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
+        draw_stroked_paths(state, r);
+    }
+
+    const ELEMENTS: Option<u64> = Some(2 * (HAIRLINE_CELLS * HAIRLINE_CELLS) as u64);
+}
+
+pub(super) fn register(scenes: &mut impl SceneRegistry) {
+    scenes.add::<StrokedPathsDashed1000>();
+    scenes.add::<StrokedPathsRoundJoins1000>();
+    scenes.add::<HairlineGrid5000>();
+}
@@ -0,0 +1,295 @@
+//! Text drawn as glyph runs of the embedded DejaVu Sans Mono.
+//!
+//! There is no shaping: printable ASCII maps to glyph IDs directly (see
+//! [`ascii_glyphs`]) and the font is monospaced, so setup lays the text out
+//! by advancing a fixed fraction of the font size per character. Drawing
+//! only builds and fills the glyph runs, as an application does once its
+//! text layout is cached.
+//!
+//! - `text_paragraphs_1000`: 1000 short lines of body text in columns, each
+//!   line its own run, on a 1920×1080 canvas.
+//! - `text_large_glyphs`: a few hundred display glyphs from 72 to 200 px,
+//!   where glyph outlines rather than their count dominate.
+//! - `text_transformed`: lines rotated around the canvas center and scaled,
+//!   so no glyph is axis-aligned.
+
+use std::f64::consts::TAU;
+use std::sync::Arc;
+
+use super::generated::FONT_BYTES;
+use super::rng::Lcg;
+use super::{SceneRegistry, VelloScene, VelloSceneInfo};
+use crate::renderer::Renderer;
+use vello_common::color::{AlphaColor, Srgb};
+use vello_common::glyph::Glyph;
+use vello_common::kurbo::Affine;
+use vello_common::peniko::color::palette;
+use vello_common::peniko::{Blob, FontData};
+
+const WIDTH: u16 = 1024;
+const HEIGHT: u16 = 768;
+
+/// Advance of every DejaVu Sans Mono glyph as a fraction of the font size
+/// (1233 of 2048 units).
+const ADVANCE: f32 = 0.602;
+
+/// Glyph ID of a printable ASCII character in DejaVu Sans Mono is its code
+/// point minus this.
+const ASCII_GLYPH_OFFSET: u32 = 29;
+
+/// The running text, split into lines at word boundaries.
+const TEXT: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod \
+    tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis nostrud \
+    exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat.";
+
+/// Glyphs of `text` from `(x, y)` on, at `font_size`. Characters outside
+/// printable ASCII get the `.notdef` glyph.
+fn ascii_glyphs(text: &str, x: f32, y: f32, font_size: f32) -> Vec<Glyph> {
+    text.chars()
+        .enumerate()
+        .map(|(i, c)| Glyph {
+            id: if (' '..='~').contains(&c) {
+                u32::from(c) - ASCII_GLYPH_OFFSET
+            } else {
+                0
+            },
+            x: x + i as f32 * font_size * ADVANCE,
+            y,
+        })
+        .collect()
+}
+
+/// Line `index` of the running text: whole words of [`TEXT`], starting at a
+/// different one per line, up to `max_chars` characters.
+fn latin_line(index: usize, max_chars: usize) -> String {
+    let words = TEXT.split(' ');
+    let skip = index * 7 % words.clone().count();
+    let mut line = String::new();
+    for word in words.cycle().skip(skip) {
+        if !line.is_empty() {
+            if line.len() + 1 + word.len() > max_chars {
+                break;
+            }
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    line
+}
+
+/// A glyph run, laid out during setup.
+struct TextRun {
+    transform: Affine,
+    font_size: f32,
+    color: AlphaColor<Srgb>,
+    glyphs: Vec<Glyph>,
+}
+
+/// The font and the runs of a text scene.
+pub struct TextState {
+    font: FontData,
+    runs: Vec<TextRun>,
+}
+
+impl TextState {
+    fn new(runs: Vec<TextRun>) -> Self {
+        Self {
+            font: FontData::new(Blob::new(Arc::new(FONT_BYTES)), 0),
+            runs,
+        }
+    }
+}
+
+fn draw_runs<R: Renderer>(state: &TextState, r: &mut R) {
+    for run in &state.runs {
+        r.set_transform(run.transform);
+        r.set_paint(run.color);
+        r.glyph_run(&state.font)
+            .font_size(run.font_size)
+            .fill_glyphs(run.glyphs.iter().copied());
+    }
+    r.set_transform(Affine::IDENTITY);
+}
+
+/// Lines of `text_paragraphs_1000`.
+const PARAGRAPH_LINES: usize = 1000;
+
+/// Columns the paragraph lines are split into.
+const PARAGRAPH_COLUMNS: usize = 12;
+
+const PARAGRAPH_FONT_SIZE: f32 = 10.0;
+const PARAGRAPH_LINE_HEIGHT: f32 = 12.0;
+
+/// 1000 lines of body text in 12 columns, one glyph run per line.
+pub struct TextParagraphs1000;
+
+impl VelloScene for TextParagraphs1000 {
+    type State = TextState;
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "text_paragraphs_1000",
+            width: 1920,
+            height: 1080,
+        }
+    }
+
+    fn setup<R: Renderer>(r: &mut R) -> Self::State {
+        let margin = 16.0;
+        let column_width = f32::from(r.width()) / PARAGRAPH_COLUMNS as f32;
+        let max_chars = ((column_width - margin) / (PARAGRAPH_FONT_SIZE * ADVANCE)) as usize;
+        let lines_per_column = PARAGRAPH_LINES.div_ceil(PARAGRAPH_COLUMNS);
+
+        let runs = (0..PARAGRAPH_LINES)
+            .map(|line| {
+                let x = (line / lines_per_column) as f32 * column_width + margin / 2.0;
+                let y = margin + (line % lines_per_column + 1) as f32 * PARAGRAPH_LINE_HEIGHT;
+                TextRun {
+                    transform: Affine::IDENTITY,
+                    font_size: PARAGRAPH_FONT_SIZE,
+                    color: palette::css::BLACK,
+                    glyphs: ascii_glyphs(&latin_line(line, max_chars), x, y, PARAGRAPH_FONT_SIZE),
+                }
+            })
+            .collect();
+        TextState::new(runs)
+    }
+
+    fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
+        draw_runs(state, r);
+    }
+
+    const ELEMENTS: Option<u64> = Some(PARAGRAPH_LINES as u64);
+}
+
+/// Font sizes of `text_large_glyphs`, one run each.
+const LARGE_SIZES: [f32; 6] = [72.0, 96.0, 120.0, 144.0, 176.0, 200.0];
+
+/// Glyphs per size in `text_large_glyphs`.
+const LARGE_GLYPHS_PER_SIZE: usize = 50;
+
+/// 300 letters at display sizes, scattered over the canvas in translucent
+/// colors.
+pub struct TextLargeGlyphs;
+
+impl VelloScene for TextLargeGlyphs {
+    type State = TextState;
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "text_large_glyphs",
+            width: WIDTH,
+            height: HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(r: &mut R) -> Self::State {
+        let colors = [
+            palette::css::CRIMSON,
+            palette::css::ROYAL_BLUE,
+            palette::css::SEA_GREEN,
+            palette::css::DARK_ORANGE,
+            palette::css::MEDIUM_PURPLE,
+            palette::css::TEAL,
+        ];
+        let width = f32::from(r.width());
+        let height = f32::from(r.height());
+
+        let mut rng = Lcg::new(2024);
+
+        let runs = LARGE_SIZES
+            .iter()
+            .zip(colors)
+            .enumerate()
+            .map(|(run, (&font_size, color))| {
+                let glyphs = (0..LARGE_GLYPHS_PER_SIZE)
+                    .map(|i| {
+                        let letter = (run * LARGE_GLYPHS_PER_SIZE + i) % 26;
+                        Glyph {
+                            id: u32::from(b'A') + letter as u32 - ASCII_GLYPH_OFFSET,
+                            x: rng.next_f32() * (width - font_size * ADVANCE),
+                            y: font_size * 0.8 + rng.next_f32() * (height - font_size * 0.8),
+                        }
+                    })
+                    .collect();
+                TextRun {
+                    transform: Affine::IDENTITY,
+                    font_size,
+                    color: color.with_alpha(0.6),
+                    glyphs,
+                }
+            })
+            .collect();
+        TextState::new(runs)
+    }
+
+    fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
+        draw_runs(state, r);
+    }
+
+    const ELEMENTS: Option<u64> = Some((LARGE_SIZES.len() * LARGE_GLYPHS_PER_SIZE) as u64);
+}
+
+/// Runs of `text_transformed`.
+const TRANSFORMED_RUNS: usize = 120;
+
+/// Text of every run of `text_transformed`.
+const PANGRAM: &str = "The quick brown fox jumps over the lazy dog";
+
+/// 120 lines radiating from the canvas center, each rotated by its own angle
+/// and scaled by 0.4 to 1.6.
+pub struct TextTransformed;
+
+impl VelloScene for TextTransformed {
+    type State = TextState;
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: "text_transformed",
+            width: WIDTH,
+            height: HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(r: &mut R) -> Self::State {
+        let center = (f64::from(r.width()) / 2.0, f64::from(r.height()) / 2.0);
+        let font_size = 16.0;
+        let glyphs = ascii_glyphs(PANGRAM, 0.0, 0.0, font_size);
+
+        let runs = (0..TRANSFORMED_RUNS)
+            .map(|i| {
+                let angle = TAU * i as f64 / TRANSFORMED_RUNS as f64;
+                // Scales in an order unrelated to the angle.
+                let scale =
+                    0.4 + 1.2 * ((i * 37) % TRANSFORMED_RUNS) as f64 / TRANSFORMED_RUNS as f64;
+                let color = if i % 2 == 0 {
+                    palette::css::MIDNIGHT_BLUE
+                } else {
+                    palette::css::DARK_RED
+                };
+                TextRun {
+                    transform: Affine::translate(center)
+                        * Affine::rotate(angle)
+                        * Affine::scale(scale)
+                        * Affine::translate((48.0, 0.0)),
+                    font_size,
+                    color,
+                    glyphs: glyphs.clone(),
+                }
+            })
+            .collect();
+        TextState::new(runs)
+    }
+
+    fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
+        draw_runs(state, r);
+    }
+
+    const ELEMENTS: Option<u64> = Some(TRANSFORMED_RUNS as u64);
+}
+
+pub(super) fn register(scenes: &mut impl SceneRegistry) {
+    scenes.add::<TextParagraphs1000>();
+    scenes.add::<TextLargeGlyphs>();
+    scenes.add::<TextTransformed>();
+}
@@ -0,0 +1,108 @@
+//! Many shapes only a few pixels across, where antialiasing dominates.
+//!
+//! Nearly every pixel a tiny shape touches is an edge pixel, so drawing it
+//! with and without antialiasing shows what antialiasing costs:
+//!
+//! - `tiny_shapes_20000_aa`: 10 000 rects and 10 000 circles under 4 px,
+//!   antialiased.
+//! - `tiny_shapes_20000_aliased`: the same shapes with
+//!   [`ALIASED_THRESHOLD`] as the aliasing threshold.
+//!
+//! The two must render differently on every backend, see
+//! [`check_effect_sanity`](crate::golden::check_effect_sanity). The recorder
+//! has no aliasing threshold, so neither is
+//! [`RECORDABLE`](VelloScene::RECORDABLE).
+
+use super::layer_stack::draw_backdrop;
+use super::rng::Lcg;
+use super::{SceneRegistry, VelloScene, VelloSceneInfo};
+use crate::renderer::Renderer;
+use vello_common::kurbo::{BezPath, Circle, Rect, Shape};
+use vello_common::peniko::color::palette;
+
+/// Shapes of each kind.
+const SHAPES_PER_KIND: u32 = 10_000;
+
+/// Aliasing threshold of `tiny_shapes_20000_aliased`: pixels at least half
+/// covered are drawn in full, so shapes keep about their size.
+const ALIASED_THRESHOLD: Option<u8> = Some(128);
+
+/// The shapes of the tiny shape scenes, placed during setup.
+pub struct TinyShapesState {
+    rects: Vec<Rect>,
+    circles: Vec<BezPath>,
+}
+
+/// 20 000 rects and circles of 1 to 4 px scattered over the canvas at
+/// fractional positions, antialiased or aliased.
+pub struct TinyShapes20000<const ALIASED: bool>;
+
+impl<const ALIASED: bool> VelloScene for TinyShapes20000<ALIASED> {
+    type State = TinyShapesState;
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: if ALIASED {
+                "tiny_shapes_20000_aliased"
+            } else {
+                "tiny_shapes_20000_aa"
+            },
+            width: 1024,
+            height: 768,
+        }
+    }
+
+    fn setup<R: Renderer>(r: &mut R) -> Self::State {
+        let width = f64::from(r.width());
+        let height = f64::from(r.height());
+        // Both variants draw the same shapes.
+        let mut rng = Lcg::new(20_000);
+
+        let rects = (0..SHAPES_PER_KIND)
+            .map(|_| {
+                let x = rng.next_f64() * (width - 4.0);
+                let y = rng.next_f64() * (height - 4.0);
+                let w = 1.0 + 3.0 * rng.next_f64();
+                let h = 1.0 + 3.0 * rng.next_f64();
+                Rect::new(x, y, x + w, y + h)
+            })
+            .collect();
+        let circles = (0..SHAPES_PER_KIND)
+            .map(|_| {
+                let center = (
+                    2.0 + rng.next_f64() * (width - 4.0),
+                    2.0 + rng.next_f64() * (height - 4.0),
+                );
+                Circle::new(center, 0.5 + 1.5 * rng.next_f64()).to_path(0.1)
+            })
+            .collect();
+        TinyShapesState { rects, circles }
+    }
+
+    fn draw<R: Renderer>(state: &Self::State, r: &mut R) {
+        draw_backdrop(r);
+
+        let colors = [
+            palette::css::CRIMSON,
+            palette::css::ROYAL_BLUE,
+            palette::css::SEA_GREEN,
+            palette::css::BLACK,
+        ];
+        r.set_aliasing_threshold(if ALIASED { ALIASED_THRESHOLD } else { None });
+        for (i, (rect, circle)) in state.rects.iter().zip(&state.circles).enumerate() {
+            r.set_paint(colors[i % colors.len()]);
+            r.fill_rect(rect);
+            r.set_paint(colors[(i + 1) % colors.len()]);
+            r.fill_path(circle);
+        }
+        r.set_aliasing_threshold(None);
+    }
+
+    const RECORDABLE: bool = false;
+    const ELEMENTS: Option<u64> = Some(2 * SHAPES_PER_KIND as u64);
+}
+
+pub(super) fn register(scenes: &mut impl SceneRegistry) {
+    scenes.add::<TinyShapes20000<false>>();
+    scenes.add::<TinyShapes20000<true>>();
+}
@@ -0,0 +1,230 @@
+//! Self-overlapping geometry, where fill rules and winding numbers matter.
+//!
+//! These are the scenes where CPU and hybrid strip generation are most likely
+//! to disagree by more than antialiasing noise, so they are part of the
+//! targeted parity check (see
+//! [`check_winding_parity`](crate::golden::check_winding_parity)).
+//!
+//! - `fill_rule_nonzero` / `fill_rule_evenodd`: the same grid of star
+//!   polygons, overlapping rings and nested subpaths of either orientation,
+//!   filled under each rule.
+//! - `long_path_nonzero` / `long_path_evenodd`: a single path of
+//!   [`LONG_PATH_SEGMENTS`] line segments tracing a curve that crosses itself
+//!   thousands of times.
+//!
+//! All paints are opaque, so any difference between backends is a
+//! difference in coverage.
+
+use std::f64::consts::TAU;
+
+use super::{SceneRegistry, VelloScene, VelloSceneInfo};
+use crate::renderer::Renderer;
+use vello_common::kurbo::{Affine, BezPath, Point, Vec2};
+use vello_common::peniko::Fill;
+use vello_common::peniko::color::palette;
+
+const WIDTH: u16 = 1024;
+const HEIGHT: u16 = 768;
+
+/// `(points, step)` of the star polygons `{points/step}`.
+const STARS: &[(u32, u32)] = &[(5, 2), (7, 2), (7, 3), (8, 3), (9, 4), (11, 4)];
+
+/// Segments of the path of `long_path_*`.
+pub const LONG_PATH_SEGMENTS: u32 = 10_000;
+
+fn fill_rule(even_odd: bool) -> Fill {
+    if even_odd {
+        Fill::EvenOdd
+    } else {
+        Fill::NonZero
+    }
+}
+
+/// Append the star polygon `{points/step}` around the origin: `points`
+/// vertices on a circle of `radius`, each connected to the one `step` ahead.
+/// Its inner regions have winding numbers up to `step`.
+fn star_polygon(path: &mut BezPath, points: u32, step: u32, radius: f64, rotation: f64) {
+    let vertex = |i: u32| {
+        let angle = rotation + TAU * f64::from(i * step % points) / f64::from(points);
+        Point::new(radius * angle.sin(), -radius * angle.cos())
+    };
+    path.move_to(vertex(0));
+    for i in 1..points {
+        path.line_to(vertex(i));
+    }
+    path.close_path();
+}
+
+/// Append a circle of `radius` around `center` out of four cubic arcs, in
+/// clockwise (in y-down coordinates) or counter-clockwise direction.
+fn circle(path: &mut BezPath, center: Point, radius: f64, clockwise: bool) {
+    // Control point distance for a quarter circle.
+    const K: f64 = 0.552_284_749_8;
+    let dir = if clockwise { 1.0 } else { -1.0 };
+    let at = |angle: f64| center + radius * Vec2::new(angle.cos(), angle.sin());
+    let tangent = |angle: f64| radius * K * dir * Vec2::new(-angle.sin(), angle.cos());
+
+    path.move_to(at(0.0));
+    for quarter in 0..4 {
+        let a0 = dir * f64::from(quarter) * TAU / 4.0;
+        let a1 = dir * f64::from(quarter + 1) * TAU / 4.0;
+        path.curve_to(at(a0) + tangent(a0), at(a1) - tangent(a1), at(a1));
+    }
+    path.close_path();
+}
+
+/// Grid of self-overlapping shapes, filled with the non-zero or even-odd
+/// rule. Each cell holds one of:
+///
+/// - a star polygon from [`STARS`];
+/// - two copies of such a star, rotated against each other, in one path;
+/// - three overlapping circles of the same orientation in one path;
+/// - two or three nested circles, alternating in orientation per row, so that
+///   the inner ones are holes under both rules or only under even-odd.
+pub struct FillRuleShapes<const EVEN_ODD: bool>;
+
+impl<const EVEN_ODD: bool> VelloScene for FillRuleShapes<EVEN_ODD> {
+    type State = ();
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: if EVEN_ODD {
+                "fill_rule_evenodd"
+            } else {
+                "fill_rule_nonzero"
+            },
+            width: WIDTH,
+            height: HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(_r: &mut R) -> Self::State {}
+
+    fn draw<R: Renderer>(_state: &Self::State, r: &mut R) {
+        let colors = [
+            palette::css::CRIMSON,
+            palette::css::SEA_GREEN,
+            palette::css::ROYAL_BLUE,
+            palette::css::DARK_ORANGE,
+        ];
+
+        let cols = 6u16;
+        let rows = 4u16;
+        let cell_w = f64::from(r.width()) / f64::from(cols);
+        let cell_h = f64::from(r.height()) / f64::from(rows);
+        let size = cell_w.min(cell_h) * 0.42;
+
+        r.set_fill_rule(fill_rule(EVEN_ODD));
+        for row in 0..rows {
+            for col in 0..cols {
+                let idx = usize::from(row * cols + col);
+                // Fractional offsets so that edges never align with pixels.
+                let cx = (f64::from(col) + 0.5) * cell_w + 0.31;
+                let cy = (f64::from(row) + 0.5) * cell_h + 0.57;
+                let (points, step) = STARS[idx / 4 % STARS.len()];
+
+                let mut path = BezPath::new();
+                match idx % 4 {
+                    0 => star_polygon(&mut path, points, step, size, 0.0),
+                    1 => {
+                        star_polygon(&mut path, points, step, size, 0.0);
+                        star_polygon(&mut path, points, step, size * 0.8, 0.35);
+                    }
+                    2 => {
+                        for i in 0..3 {
+                            let angle = TAU * f64::from(i) / 3.0;
+                            let offset = size * 0.35 * Vec2::new(angle.sin(), -angle.cos());
+                            circle(&mut path, offset.to_point(), size * 0.6, true);
+                        }
+                    }
+                    _ => {
+                        let alternate = row % 2 == 1;
+                        circle(&mut path, Point::ORIGIN, size, true);
+                        circle(
+                            &mut path,
+                            Point::new(0.1 * size, 0.0),
+                            size * 0.6,
+                            alternate,
+                        );
+                        if col % 2 == 1 {
+                            circle(
+                                &mut path,
+                                Point::new(0.15 * size, 0.05 * size),
+                                size * 0.25,
+                                true,
+                            );
+                        }
+                    }
+                }
+
+                r.set_paint(colors[idx % colors.len()]);
+                r.set_transform(Affine::translate((cx, cy)));
+                r.fill_path(&path);
+            }
+        }
+        r.set_transform(Affine::IDENTITY);
+        r.set_fill_rule(Fill::NonZero);
+    }
+}
+
+/// One closed path of [`LONG_PATH_SEGMENTS`] line segments tracing a
+/// hypotrochoid that winds around its center many times, filled with the
+/// non-zero or even-odd rule. Winding numbers vary across the whole canvas,
+/// and nearly every row of tiles holds many crossing edges.
+pub struct LongPath<const EVEN_ODD: bool>;
+
+impl<const EVEN_ODD: bool> VelloScene for LongPath<EVEN_ODD> {
+    type State = BezPath;
+
+    fn info() -> VelloSceneInfo {
+        VelloSceneInfo {
+            name: if EVEN_ODD {
+                "long_path_evenodd"
+            } else {
+                "long_path_nonzero"
+            },
+            width: WIDTH,
+            height: HEIGHT,
+        }
+    }
+
+    fn setup<R: Renderer>(r: &mut R) -> Self::State {
+        // Rolling circle of radius 23 inside one of radius 60; the curve
+        // closes after 23 turns.
+        const BIG: f64 = 60.0;
+        const SMALL: f64 = 23.0;
+        const PEN: f64 = 38.0;
+        const TURNS: f64 = 23.0;
+
+        let center = Point::new(f64::from(r.width()) / 2.0, f64::from(r.height()) / 2.0);
+        let scale = f64::from(r.width().min(r.height())) * 0.48 / (BIG - SMALL + PEN);
+        let point = |i: u32| {
+            let t = TAU * TURNS * f64::from(i) / f64::from(LONG_PATH_SEGMENTS);
+            let x = (BIG - SMALL) * t.cos() + PEN * ((BIG - SMALL) / SMALL * t).cos();
+            let y = (BIG - SMALL) * t.sin() - PEN * ((BIG - SMALL) / SMALL * t).sin();
+            center + Vec2::new(x * scale + 0.23, y * scale + 0.41)
+        };
+
+        let mut path = BezPath::new();
+        path.move_to(point(0));
+        for i in 1..LONG_PATH_SEGMENTS {
+            path.line_to(point(i));
+        }
+        path.close_path();
+        path
+    }
+
+    fn draw<R: Renderer>(path: &Self::State, r: &mut R) {
+        r.set_fill_rule(fill_rule(EVEN_ODD));
+        r.set_paint(palette::css::MIDNIGHT_BLUE);
+        r.fill_path(path);
+        r.set_fill_rule(Fill::NonZero);
+    }
+}
+
+pub(super) fn register(scenes: &mut impl SceneRegistry) {
+    scenes.add::<FillRuleShapes<false>>();
+    scenes.add::<FillRuleShapes<true>>();
+    scenes.add::<LongPath<false>>();
+    scenes.add::<LongPath<true>>();
+}
@@ -0,0 +1,58 @@
+//! The GPU watchdog against a hung GPU, simulated with a limit far below what
+//! a heavy scene takes.
+//!
+//! This is an integration test, so that it runs in a process of its own: the
+//! limit is global, and lowering it would fail the GPU unit tests running
+//! alongside.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::time::Duration;
+
+use anyrender::PaintScene;
+use vello_bench_core::gpu_watchdog::set_gpu_timeout;
+use vello_bench_core::registry::try_run_benchmark_by_id_from;
+use vello_bench_core::renderer::gpu_adapter_info;
+use vello_bench_core::scenes::TestSceneSource;
+use vello_bench_core::{BenchRunner, BenchmarkOutcome, Level};
+use vello_common::kurbo::{Affine, Rect};
+use vello_common::peniko::Fill;
+use vello_common::peniko::color::palette;
+
+#[test]
+fn absurdly_low_limit_fails_a_heavy_scene() {
+    if gpu_adapter_info().is_none() {
+        eprintln!("Skipped: no GPU adapter");
+        return;
+    }
+    // 100,000 overlapping translucent rects covering a 2048×2048 target.
+    let source = TestSceneSource::empty().with_scene("heavy", 2048, 2048, |scene| {
+        for i in 0..100_000 {
+            let x = f64::from(i % 256) * 8.0;
+            let y = f64::from(i / 256 % 256) * 8.0;
+            let rect = Rect::new(x, y, x + 64.0, y + 64.0);
+            let color = palette::css::STEEL_BLUE.with_alpha(0.25);
+            scene.fill(Fill::NonZero, Affine::IDENTITY, color, None, &rect);
+        }
+    });
+
+    set_gpu_timeout(Duration::from_nanos(1));
+    let outcome = try_run_benchmark_by_id_from(
+        &source,
+        &BenchRunner::new(0, 5),
+        "scene_hybrid/heavy",
+        Level::new(),
+    )
+    .expect("the scene exists");
+
+    match outcome {
+        BenchmarkOutcome::Failed { id, error, adapter } => {
+            assert_eq!(id, "scene_hybrid/heavy");
+            assert!(error.starts_with("GPU timeout"), "{error}");
+            assert!(adapter.is_some());
+        }
+        BenchmarkOutcome::Completed(result) => {
+            panic!("Completed within the limit: {:?}", result.statistics)
+        }
+    }
+}
@@ -0,0 +1,47 @@
+//! `vello_bench run --isolate` against a benchmark that panics: the panic
+//! takes down only the child process running it, and the run reports it as
+//! failed instead of aborting.
+//!
+//! This is an integration test, so that it can run the built binary, which
+//! is what spawns the child processes.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::process::Command;
+
+use vello_bench_core::ResultFile;
+
+#[test]
+fn isolated_panic_fails_only_its_benchmark() {
+    let output = Command::new(env!("CARGO_BIN_EXE_vello_bench"))
+        .args([
+            "run",
+            "--filter",
+            "name:no_such_benchmark",
+            "--include-panicking-benchmark",
+            "--isolate",
+            "--warmup",
+            "0",
+            "--iterations",
+            "1",
+        ])
+        // Keep the panic message within the stderr tail the error quotes.
+        .env("RUST_BACKTRACE", "0")
+        .output()
+        .expect("failed to start vello_bench");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert_eq!(output.status.code(), Some(1), "{stderr}");
+    assert!(stderr.contains("[1/1] debug/panic ... FAILED"), "{stderr}");
+    assert!(
+        stderr.contains("Benchmark process exited with status 101"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("debug/panic always panics"), "{stderr}");
+    assert!(stderr.contains("1 of 1 benchmarks failed"), "{stderr}");
+
+    // The run still writes its result file, without the failed benchmark.
+    let file: ResultFile = serde_json::from_slice(&output.stdout).expect("result file on stdout");
+    assert!(file.results.is_empty());
+    assert!(!file.partial);
+}
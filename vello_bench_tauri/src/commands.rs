@@ -4,18 +4,22 @@ use std::fs;
 use std::path::PathBuf;
 use std::sync::LazyLock;
 use tokio::sync::Mutex;
+use vello_bench_core::benchmarks::vello_cpu::render_mode_from_suffix;
+use vello_bench_core::data::AssetEntry;
+use vello_bench_core::memory::MemorySample;
 use vello_bench_core::{
-    available_level_infos, level_from_suffix, BenchRunner, BenchmarkInfo, BenchmarkResult,
-    SimdLevelInfo,
+    available_level_infos, level_from_suffix, BenchRunner, BenchmarkAvailability, BenchmarkOutcome,
+    BenchmarkResult, EnvironmentInfo, Platform, RunConfig, SimdLevelInfo,
 };
 
 /// Mutex to ensure only one benchmark runs at a time.
 static BENCHMARK_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
 
-/// Get list of available benchmarks.
+/// Get list of benchmarks. Those that can't run natively carry an
+/// `unavailable_reason`.
 #[tauri::command]
-pub fn list_benchmarks() -> Vec<BenchmarkInfo> {
-    vello_bench_core::get_benchmark_list()
+pub fn list_benchmarks() -> Vec<BenchmarkAvailability> {
+    vello_bench_core::get_benchmark_availability(&[Platform::Native])
 }
 
 /// Get available SIMD levels.
@@ -24,14 +28,49 @@ pub fn get_simd_levels() -> Vec<SimdLevelInfo> {
     available_level_infos()
 }
 
+/// List all embedded assets with their sizes.
+#[tauri::command]
+pub fn get_asset_manifest() -> Vec<AssetEntry> {
+    vello_bench_core::data::asset_manifest().to_vec()
+}
+
+/// Describe the machine the native benchmarks run on, for exported result
+/// files.
+#[tauri::command]
+pub fn get_environment(simd_level: String) -> EnvironmentInfo {
+    EnvironmentInfo::capture(level_from_suffix(&simd_level))
+}
+
+/// Describe how the UI configured a run, with the `VELLO_BENCH_*` environment
+/// variables of the app, for exported result files.
+#[tauri::command]
+pub fn get_run_config(warmup: u64, iterations: u64, filter: Option<String>) -> RunConfig {
+    RunConfig {
+        filter,
+        ..RunConfig::capture(&BenchRunner::new(warmup, iterations))
+    }
+}
+
+/// Sample the memory usage of the app after benchmark `after` finished.
+/// Only called between benchmarks.
+#[tauri::command]
+pub fn sample_memory(after: String) -> MemorySample {
+    vello_bench_core::memory::sample_memory(&after)
+}
+
 /// Run a single benchmark (async, runs in background thread).
+///
+/// `render_mode` (`"speed"` or `"quality"`) optionally forces the render mode
+/// of a `vello_cpu` benchmark. A benchmark whose GPU work hangs is reported as
+/// a failed outcome instead of blocking the suite.
 #[tauri::command]
 pub async fn run_benchmark(
     id: String,
     simd_level: String,
     warmup: u64,
     iterations: u64,
-) -> Option<BenchmarkResult> {
+    render_mode: Option<String>,
+) -> Option<BenchmarkOutcome> {
     // Acquire lock to ensure only one benchmark runs at a time
     let _guard = BENCHMARK_LOCK.lock().await;
 
@@ -39,7 +78,31 @@ pub async fn run_benchmark(
     tokio::task::spawn_blocking(move || {
         let level = level_from_suffix(&simd_level);
         let runner = BenchRunner::new(warmup, iterations);
-        vello_bench_core::run_benchmark_by_id(&runner, &id, level)
+        match render_mode.as_deref() {
+            None => vello_bench_core::try_run_benchmark_by_id(&runner, &id, level),
+            Some(mode) => {
+                let mode = render_mode_from_suffix(mode)?;
+                vello_bench_core::registry::run_benchmark_with_render_mode(
+                    &runner, &id, level, mode,
+                )
+                .map(BenchmarkOutcome::Completed)
+            }
+        }
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// Time a single iteration of a benchmark without warm-up, for quick
+/// previews. The result is marked as a single-shot estimate.
+#[tauri::command]
+pub async fn estimate_benchmark(id: String, simd_level: String) -> Option<BenchmarkResult> {
+    let _guard = BENCHMARK_LOCK.lock().await;
+
+    tokio::task::spawn_blocking(move || {
+        let level = level_from_suffix(&simd_level);
+        vello_bench_core::run_benchmark_by_id(&BenchRunner::single_shot(), &id, level)
     })
     .await
     .ok()
@@ -183,7 +246,8 @@ pub async fn screenshot(scene_name: String, category: String) -> Option<Screensh
             }
             _ => None,
         }?;
-        let rgba_base64 = base64::engine::general_purpose::STANDARD.encode(&result.rgba);
+        // The UI draws the pixels as `ImageData`, which expects straight alpha.
+        let rgba_base64 = base64::engine::general_purpose::STANDARD.encode(result.straight_rgba());
         Some(ScreenshotResponse {
             width: result.width,
             height: result.height,
@@ -12,7 +12,12 @@ fn main() {
         .invoke_handler(tauri::generate_handler![
             commands::list_benchmarks,
             commands::get_simd_levels,
+            commands::get_asset_manifest,
+            commands::get_environment,
+            commands::get_run_config,
+            commands::sample_memory,
             commands::run_benchmark,
+            commands::estimate_benchmark,
             commands::screenshot,
             commands::save_reference,
             commands::list_references,
@@ -3,7 +3,10 @@
 #![allow(missing_docs, reason = "Not needed for benchmarks")]
 #![cfg(target_arch = "wasm32")]
 
-use vello_bench_core::{BenchRunner, available_level_infos};
+use vello_bench_core::{
+    BenchRunner, BenchmarkAvailability, CancellationToken, OutlierPolicy, StabilityTarget,
+    SyncMode, TimeBudget, available_level_infos,
+};
 use wasm_bindgen::prelude::*;
 
 /// Initialize the WASM module.
@@ -12,13 +15,231 @@ pub fn init() {
     console_error_panic_hook::set_once();
 }
 
-/// List all available benchmarks.
+/// List all benchmarks, each with a `measures` description of its timed
+/// span, its `tags` for building suites, e.g. `["cpu", "scene", "images"]`,
+/// and its `estimated_cost` (`"micro"` to `"very_slow"`). Those that can't
+/// run in the browser carry an `unavailable_reason`.
 #[wasm_bindgen]
 pub fn list_benchmarks() -> JsValue {
-    let benchmarks = vello_bench_core::get_benchmark_list();
+    with_benchmark_list(|benchmarks| serde_wasm_bindgen::to_value(benchmarks).unwrap())
+}
+
+/// Only the `id`, `category`, `name`, `tags` and `estimated_cost` of every
+/// benchmark, for populating the UI before the full metadata is needed.
+/// Fetch the rest per category with [`get_category_details`].
+///
+/// Built from the plain benchmark list, so it neither computes nor waits for
+/// the availability of [`list_benchmarks`].
+#[wasm_bindgen]
+pub fn list_benchmarks_fast() -> JsValue {
+    serde_wasm_bindgen::to_value(&fast_benchmark_list()).unwrap()
+}
+
+/// An entry of [`list_benchmarks_fast`].
+#[derive(serde::Serialize)]
+struct FastEntry {
+    id: String,
+    category: String,
+    name: String,
+    tags: Vec<&'static str>,
+    estimated_cost: vello_bench_core::registry::CostClass,
+}
+
+fn fast_benchmark_list() -> Vec<FastEntry> {
+    vello_bench_core::get_benchmark_list()
+        .into_iter()
+        .map(|info| FastEntry {
+            id: info.id,
+            category: info.category,
+            name: info.name,
+            tags: info.tags,
+            estimated_cost: info.estimated_cost,
+        })
+        .collect()
+}
+
+/// The entries of [`list_benchmarks`] in `category`.
+#[wasm_bindgen]
+pub fn get_category_details(category: &str) -> JsValue {
+    with_benchmark_list(|benchmarks| {
+        let details: Vec<&BenchmarkAvailability> = benchmarks
+            .iter()
+            .filter(|b| b.info.category == category)
+            .collect();
+        serde_wasm_bindgen::to_value(&details).unwrap()
+    })
+}
+
+thread_local! {
+    /// The benchmark list with availability, computed on first use. Cleared by
+    /// [`invalidate_benchmark_list`].
+    static BENCHMARK_LIST: RefCell<Option<Vec<BenchmarkAvailability>>> =
+        const { RefCell::new(None) };
+}
+
+/// Call `f` with the cached benchmark list, computing it first if needed.
+fn with_benchmark_list<T>(f: impl FnOnce(&[BenchmarkAvailability]) -> T) -> T {
+    use vello_bench_core::Platform;
+
+    BENCHMARK_LIST.with(|list| {
+        let mut list = list.borrow_mut();
+        let benchmarks = list.get_or_insert_with(|| {
+            vello_bench_core::get_benchmark_availability(&[
+                Platform::WasmMainThread,
+                Platform::WasmWorker,
+            ])
+        });
+        f(benchmarks)
+    })
+}
+
+/// Drop the cached benchmark list, so the next listing reflects changes in
+/// what is registered or available.
+fn invalidate_benchmark_list() {
+    BENCHMARK_LIST.with(|list| list.borrow_mut().take());
+}
+
+/// Which categories run on which platforms, and what they need there.
+#[wasm_bindgen]
+pub fn get_support_matrix() -> JsValue {
+    serde_wasm_bindgen::to_value(vello_bench_core::support_matrix()).unwrap()
+}
+
+/// Problems with the benchmark registry, such as duplicate IDs or zero-sized
+/// scenes, as an array of messages (see
+/// `vello_bench_core::registry::validate`). Empty if there are none.
+#[wasm_bindgen]
+pub fn validate_registry() -> Vec<String> {
+    match vello_bench_core::registry::validate() {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors.iter().map(ToString::to_string).collect(),
+    }
+}
+
+/// IDs of the benchmarks matching a filter expression, e.g.
+/// `category:scene_cpu AND NOT name:*10000` (see `vello_bench_core::Filter`).
+/// Throws the parse error, which points at the offending column, if the
+/// expression is invalid.
+#[wasm_bindgen]
+pub fn filter_benchmark_ids(filter: &str) -> Result<JsValue, JsValue> {
+    let ids: Vec<String> = vello_bench_core::get_benchmark_list_filtered(filter)
+        .map_err(|e| JsValue::from_str(&e))?
+        .into_iter()
+        .map(|info| info.id)
+        .collect();
+    Ok(serde_wasm_bindgen::to_value(&ids).unwrap())
+}
+
+/// The benchmarks whose ID matches `pattern`, as `[{ id, category, name,
+/// measures }]`: a glob over the whole ID if it contains `*` or `?`, e.g.
+/// `scene_*/tiled*`, a substring of it otherwise (see
+/// `vello_bench_core::registry::filter_benchmarks`). Empty if nothing
+/// matches.
+#[wasm_bindgen]
+pub fn filter_benchmarks(pattern: &str) -> JsValue {
+    let benchmarks = vello_bench_core::filter_benchmarks(pattern);
     serde_wasm_bindgen::to_value(&benchmarks).unwrap()
 }
 
+/// Run every benchmark matching `pattern` (see [`filter_benchmarks`]) with
+/// `warmup` and `iterations` (or the time budget of [`set_time_budget`]),
+/// returning an array of results in list order. `level` works as in
+/// [`run_benchmark`]. Like there, WebGL benchmarks are left out; run them
+/// with [`run_hybrid_benchmark`] and [`run_vello_hybrid_benchmark`].
+#[wasm_bindgen]
+pub fn run_benchmarks_matching(
+    pattern: &str,
+    warmup: u32,
+    iterations: u32,
+    level: Option<String>,
+) -> Result<JsValue, JsValue> {
+    let level = parse_level(level.as_deref())?;
+    let runner = new_runner(warmup, iterations);
+    let mut results = vello_bench_core::run_benchmarks_matching(&runner, pattern, level);
+    for result in &mut results {
+        attach_delta(result);
+    }
+    Ok(serde_wasm_bindgen::to_value(&results).unwrap())
+}
+
+/// Run every benchmark of `category`, each measured for the time budget
+/// `calibration_ms` and `measurement_ms` (see [`set_time_budget`]), returning
+/// an array of results in list order, the same as running them one by one.
+/// Renderers are set up once for the whole category where the backend allows
+/// it (see `vello_bench_core::registry::run_category`). The WebGL categories
+/// `scene_hybrid` and `vello_hybrid` render on the canvas of [`init_hybrid`]
+/// and throw if it wasn't called; like [`run_hybrid_benchmark`], their array
+/// holds a failed outcome for a benchmark that hit the GPU timeout. Empty
+/// for an unknown category.
+#[wasm_bindgen]
+pub fn run_category(
+    category: &str,
+    calibration_ms: u32,
+    measurement_ms: u32,
+) -> Result<JsValue, JsValue> {
+    let runner = configure_runner(BenchRunner::with_time_budget(
+        calibration_ms.into(),
+        measurement_ms.into(),
+    ));
+    let level = fearless_simd::Level::new();
+    let run_webgl: fn(&str, &BenchRunner, fearless_simd::Level) -> JsValue = match category {
+        "scene_hybrid" => run_hybrid_with,
+        "vello_hybrid" => |id, runner, _| run_vello_hybrid_with(id, runner),
+        _ => {
+            let mut results = vello_bench_core::run_category(&runner, category, level);
+            for result in &mut results {
+                attach_delta(result);
+            }
+            return Ok(serde_wasm_bindgen::to_value(&results).unwrap());
+        }
+    };
+
+    if HYBRID_STATE.with(|s| s.borrow().is_empty()) {
+        return Err(JsValue::from_str(&format!(
+            "{category} needs a canvas; call init_hybrid first"
+        )));
+    }
+    let results = js_sys::Array::new();
+    for info in vello_bench_core::get_benchmark_list() {
+        if info.category != category {
+            continue;
+        }
+        let outcome = run_webgl(&info.id, &runner, level);
+        if !outcome.is_null() {
+            results.push(&outcome);
+        }
+    }
+    Ok(results.into())
+}
+
+/// CPU and hybrid benchmarks of the generated scene with `seed` and `budget`
+/// (see `vello_bench_core::vello_scenes::GeneratedParams`), which run like
+/// listed ones even for seeds that aren't registered. The scene itself is
+/// called `generated_<seed>_<budget>` for screenshots. Throws if the budget
+/// is out of range.
+#[wasm_bindgen]
+pub fn generated_benchmarks(seed: u64, budget: u32) -> Result<JsValue, JsValue> {
+    use vello_bench_core::vello_scenes::GeneratedParams;
+
+    let params = GeneratedParams::new(seed, budget).map_err(|e| JsValue::from_str(&e))?;
+    let benchmarks = vello_bench_core::generated_benchmarks(params);
+    Ok(serde_wasm_bindgen::to_value(&benchmarks).unwrap())
+}
+
+/// Estimated milliseconds to run the benchmarks `ids` with the time budget
+/// `calibration_ms` and `measurement_ms` (see [`set_time_budget`]), for
+/// showing an ETA. Only good to within a factor of a few; see
+/// `vello_bench_core::registry::estimate_suite_duration`.
+#[wasm_bindgen]
+pub fn estimate_suite_duration(ids: Vec<String>, calibration_ms: u32, measurement_ms: u32) -> f64 {
+    let ms = vello_bench_core::registry::estimate_suite_duration(
+        &ids,
+        calibration_ms.into(),
+        measurement_ms.into(),
+    );
+    ms as f64
+}
+
 /// Get available SIMD levels for this platform.
 #[wasm_bindgen]
 pub fn get_simd_levels() -> JsValue {
@@ -26,6 +247,22 @@ pub fn get_simd_levels() -> JsValue {
     serde_wasm_bindgen::to_value(&level_info).unwrap()
 }
 
+/// List all embedded assets with their sizes.
+#[wasm_bindgen]
+pub fn get_asset_manifest() -> JsValue {
+    let manifest = vello_bench_core::data::asset_manifest();
+    serde_wasm_bindgen::to_value(manifest).unwrap()
+}
+
+/// Embedded scenes that failed to load, as `[{ name, version, message }]`.
+/// `version` is the archive format version, or `null` if it couldn't be
+/// read. Their benchmarks are missing from the list.
+#[wasm_bindgen]
+pub fn get_scene_load_errors() -> JsValue {
+    let errors = vello_bench_core::scenes::scene_load_errors();
+    serde_wasm_bindgen::to_value(errors).unwrap()
+}
+
 /// Check if SIMD128 is available.
 #[wasm_bindgen]
 pub fn has_simd128() -> bool {
@@ -40,12 +277,163 @@ pub fn has_simd128() -> bool {
 }
 
 /// Run a single benchmark by ID.
+///
+/// `render_mode` (`"speed"` or `"quality"`) optionally forces the render mode
+/// of a `vello_cpu` or `scene_cpu` benchmark. Returns null if the benchmark
+/// does not support it and throws an `Error` for an unknown mode.
+///
+/// With `max_ms`, the benchmark is measured in batches of `iterations` until
+/// the batch means vary by less than 2%, or for at most about `max_ms`; the
+/// result's `statistics.iterations` is the number measured.
+///
+/// `level` forces a SIMD level by its suffix as listed by `get_simd_levels`,
+/// e.g. `"scalar"` for the fallback path; the result's `simd_variant` names
+/// it. Without it, the best level runs. Throws an `Error` for a level this
+/// browser doesn't support.
+#[wasm_bindgen]
+pub fn run_benchmark(
+    id: &str,
+    warmup: u32,
+    iterations: u32,
+    render_mode: Option<String>,
+    max_ms: Option<u32>,
+    level: Option<String>,
+) -> Result<JsValue, JsValue> {
+    let level = parse_level(level.as_deref())?;
+    let render_mode = parse_render_mode(render_mode.as_deref())?;
+    Ok(run_benchmark_at(
+        id,
+        warmup,
+        iterations,
+        render_mode,
+        max_ms,
+        level,
+    ))
+}
+
+/// The SIMD level with suffix `name`, the best one if there is none, or a JS
+/// `Error` if it isn't available.
+fn parse_level(name: Option<&str>) -> Result<fearless_simd::Level, JsValue> {
+    match name {
+        Some(name) => vello_bench_core::simd::available_level_from_suffix(name)
+            .map_err(|e| js_sys::Error::new(&e).into()),
+        None => Ok(fearless_simd::Level::new()),
+    }
+}
+
+/// The render mode with suffix `name`, or a JS `Error` naming the valid
+/// ones.
+fn parse_render_mode(name: Option<&str>) -> Result<Option<vello_cpu::RenderMode>, JsValue> {
+    use vello_bench_core::benchmarks::vello_cpu::{
+        RENDER_MODES, render_mode_from_suffix, render_mode_suffix,
+    };
+
+    let Some(name) = name else {
+        return Ok(None);
+    };
+    render_mode_from_suffix(name).map(Some).ok_or_else(|| {
+        let modes: Vec<&str> = RENDER_MODES.into_iter().map(render_mode_suffix).collect();
+        let message = format!(
+            "Unknown render mode: {name} (expected one of {})",
+            modes.join(", ")
+        );
+        js_sys::Error::new(&message).into()
+    })
+}
+
+/// [`run_benchmark`] at `level`.
+fn run_benchmark_at(
+    id: &str,
+    warmup: u32,
+    iterations: u32,
+    render_mode: Option<vello_cpu::RenderMode>,
+    max_ms: Option<u32>,
+    level: fearless_simd::Level,
+) -> JsValue {
+    use vello_bench_core::runner::DEFAULT_STABLE_CV;
+
+    let mut runner = new_runner(warmup, iterations);
+    runner.until_stable = max_ms.map(|max_ms| StabilityTarget {
+        max_cv: DEFAULT_STABLE_CV,
+        max_ms: max_ms.into(),
+    });
+
+    let result = match render_mode {
+        None => vello_bench_core::run_benchmark_by_id(&runner, id, level),
+        Some(mode) => {
+            vello_bench_core::registry::run_benchmark_with_render_mode(&runner, id, level, mode)
+        }
+    };
+
+    match result {
+        Some(mut result) => {
+            attach_delta(&mut result);
+            serde_wasm_bindgen::to_value(&result).unwrap()
+        }
+        None => JsValue::NULL,
+    }
+}
+
+/// Run benchmark `id` at every SIMD level this browser supports, each
+/// measured for the time budget `calibration_ms` and `measurement_ms` (see
+/// [`set_time_budget`]). Returns an array with one result per level, best
+/// first, all with the same `timestamp_ms`; it is empty for an unknown ID.
+/// Benchmarks that ignore the SIMD level run once.
+#[wasm_bindgen]
+pub fn run_benchmark_all_levels(id: &str, calibration_ms: u32, measurement_ms: u32) -> JsValue {
+    let runner = configure_runner(BenchRunner::with_time_budget(
+        calibration_ms.into(),
+        measurement_ms.into(),
+    ));
+    let mut results = vello_bench_core::run_benchmark_all_levels(&runner, id);
+    for result in &mut results {
+        attach_delta(result);
+    }
+    serde_wasm_bindgen::to_value(&results).unwrap()
+}
+
+/// [`run_benchmark`] at the best SIMD level, calling `callback` with the
+/// progress of the run as
+/// `{ phase, completed, total, elapsed_ns }`. `phase` is `"warmup"` or
+/// `"measurement"`, `total` is missing where the iteration count isn't known
+/// in advance. The callback runs when a phase starts and ends, and at most
+/// every 100 ms in between.
 #[wasm_bindgen]
-pub fn run_benchmark(id: &str, warmup: u32, iterations: u32) -> JsValue {
-    use fearless_simd::Level;
+pub fn run_benchmark_with_progress(
+    id: &str,
+    warmup: u32,
+    iterations: u32,
+    render_mode: Option<String>,
+    max_ms: Option<u32>,
+    callback: js_sys::Function,
+) -> Result<JsValue, JsValue> {
+    let render_mode = parse_render_mode(render_mode.as_deref())?;
+    Ok(vello_bench_core::runner::with_progress(
+        move |event| {
+            let event = serde_wasm_bindgen::to_value(&event).unwrap();
+            let _ = callback.call1(&JsValue::NULL, &event);
+        },
+        || {
+            run_benchmark_at(
+                id,
+                warmup,
+                iterations,
+                render_mode,
+                max_ms,
+                fearless_simd::Level::new(),
+            )
+        },
+    ))
+}
 
-    let runner = BenchRunner::new(warmup.into(), iterations.into());
-    let level = Level::new();
+/// Time a single iteration of a benchmark without warm-up.
+///
+/// The result is marked as a single-shot estimate and must not be used as a
+/// comparison baseline. Returns null if the benchmark is not found.
+#[wasm_bindgen]
+pub fn estimate_benchmark(id: &str) -> JsValue {
+    let runner = BenchRunner::single_shot();
+    let level = fearless_simd::Level::new();
 
     match vello_bench_core::run_benchmark_by_id(&runner, id, level) {
         Some(result) => serde_wasm_bindgen::to_value(&result).unwrap(),
@@ -53,6 +441,49 @@ pub fn run_benchmark(id: &str, warmup: u32, iterations: u32) -> JsValue {
     }
 }
 
+/// Drop the scenes kept deserialized between runs of `scene_cpu` benchmarks
+/// (see `vello_bench_core::scene_cache`), with their decoded images. Call
+/// when done with a scene to give the memory back to later runs; the next
+/// run of it deserializes it again.
+#[wasm_bindgen]
+pub fn clear_scene_cache() {
+    vello_bench_core::scene_cache::clear_scene_cache();
+}
+
+/// Sample the memory usage of this WASM instance after benchmark `after`
+/// finished. Only call between benchmarks, never inside a timed region.
+///
+/// Records the linear memory size and, where the browser exposes it (only
+/// Chromium does), `performance.memory.usedJSHeapSize`.
+#[wasm_bindgen]
+pub fn sample_memory(after: &str) -> JsValue {
+    use vello_bench_core::memory::MemorySample;
+    use wasm_bindgen::JsCast;
+
+    let get = |target: &JsValue, key: &str| js_sys::Reflect::get(target, &key.into()).ok();
+
+    let wasm_memory_bytes = wasm_bindgen::memory()
+        .dyn_into::<js_sys::WebAssembly::Memory>()
+        .ok()
+        .and_then(|memory| get(&memory.buffer(), "byteLength"))
+        .and_then(|v| v.as_f64())
+        .map(|v| v as u64);
+    let js_heap_bytes = get(&js_sys::global(), "performance")
+        .and_then(|performance| get(&performance, "memory"))
+        .and_then(|memory| get(&memory, "usedJSHeapSize"))
+        .and_then(|v| v.as_f64())
+        .map(|v| v as u64);
+
+    let sample = MemorySample {
+        after: after.to_string(),
+        timestamp_ms: js_sys::Date::now() as u64,
+        wasm_memory_bytes,
+        js_heap_bytes,
+        rss_bytes: None,
+    };
+    serde_wasm_bindgen::to_value(&sample).unwrap()
+}
+
 // ---------------------------------------------------------------------------
 // Hybrid WebGL benchmarks — run on the main thread, not in a Web Worker
 // ---------------------------------------------------------------------------
@@ -62,9 +493,12 @@ use std::cell::RefCell;
 use anyrender_vello_hybrid::{WebGlRenderContext, WebGlScenePainter};
 
 thread_local! {
-    static HYBRID_STATE: RefCell<Option<HybridState>> = const { RefCell::new(None) };
+    /// One entry per initialized canvas. The first entry is the primary target
+    /// used by all single-canvas benchmarks and screenshots.
+    static HYBRID_STATE: RefCell<Vec<HybridState>> = const { RefCell::new(Vec::new()) };
 }
 
+/// A canvas and the WebGL renderer bound to its context.
 struct HybridState {
     renderer: vello_hybrid::WebGlRenderer,
     canvas: web_sys::HtmlCanvasElement,
@@ -76,15 +510,405 @@ struct HybridState {
 pub fn init_hybrid(canvas: web_sys::HtmlCanvasElement) -> bool {
     let renderer = vello_hybrid::WebGlRenderer::new(&canvas);
     HYBRID_STATE.with(|s| {
-        *s.borrow_mut() = Some(HybridState { renderer, canvas });
+        *s.borrow_mut() = vec![HybridState { renderer, canvas }];
+    });
+    invalidate_benchmark_list();
+    true
+}
+
+/// Initialize one hybrid WebGL renderer per canvas for the multi-canvas
+/// benchmark. Replaces any previously initialized canvases; the first canvas
+/// becomes the primary target for single-canvas benchmarks. Returns the number
+/// of canvases initialized.
+#[wasm_bindgen]
+pub fn init_hybrid_multi(canvases: js_sys::Array) -> u32 {
+    use wasm_bindgen::JsCast;
+
+    let targets: Vec<HybridState> = canvases
+        .iter()
+        .filter_map(|c| c.dyn_into::<web_sys::HtmlCanvasElement>().ok())
+        .map(|canvas| HybridState {
+            renderer: vello_hybrid::WebGlRenderer::new(&canvas),
+            canvas,
+        })
+        .collect();
+    let count = targets.len() as u32;
+
+    HYBRID_STATE.with(|s| {
+        *s.borrow_mut() = targets;
+    });
+    invalidate_benchmark_list();
+    count
+}
+
+/// Check whether this browser is fit for benchmarking, as
+/// `{ checks: [{ kind, status, message }] }` (see `vello_bench_core::health`).
+/// `skip` is an array of check names to leave out, e.g. `["gpu"]`. The GPU
+/// check reports whether the hybrid WebGL renderer was initialized, so call
+/// this after `init_hybrid`.
+#[wasm_bindgen]
+pub fn health_check(skip: JsValue) -> Result<JsValue, JsValue> {
+    use vello_bench_core::health::{HealthCheck, HealthCheckKind, HealthStatus};
+    use vello_bench_core::registry::Platform;
+
+    let names: Vec<String> = if skip.is_undefined() || skip.is_null() {
+        Vec::new()
+    } else {
+        serde_wasm_bindgen::from_value(skip).map_err(|e| JsValue::from_str(&e.to_string()))?
+    };
+    let skip = names
+        .iter()
+        .map(|name| {
+            HealthCheckKind::from_name(name)
+                .ok_or_else(|| JsValue::from_str(&format!("Unknown check: {name}")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut report = vello_bench_core::registry::health_check(
+        &[Platform::WasmMainThread, Platform::WasmWorker],
+        &skip,
+    );
+    if !skip.contains(&HealthCheckKind::Gpu) {
+        let canvases = HYBRID_STATE.with(|s| s.borrow().len());
+        report.set(if canvases > 0 {
+            HealthCheck::new(
+                HealthCheckKind::Gpu,
+                HealthStatus::Pass,
+                "WebGL2 initialized",
+            )
+        } else {
+            HealthCheck::new(
+                HealthCheckKind::Gpu,
+                HealthStatus::Warn,
+                "WebGL2 is not initialized; hybrid benchmarks are unavailable",
+            )
+        });
+    }
+    Ok(serde_wasm_bindgen::to_value(&report).unwrap())
+}
+
+// ---------------------------------------------------------------------------
+// GPU watchdog — `gl.finish()` cannot be interrupted, so check how long it
+// took afterwards and fail the benchmark if it exceeded the GPU timeout.
+// Fences are polled, and given up on once the timeout has passed.
+// ---------------------------------------------------------------------------
+
+use vello_bench_core::gpu_watchdog::{GpuTimeout, gpu_timeout};
+
+thread_local! {
+    /// Set when a `gl.finish()` exceeded the GPU timeout. Once set, further
+    /// frames skip all GPU work so the benchmark finishes quickly; the
+    /// timeout is reported and cleared by [`result_or_timeout`].
+    static GPU_TIMED_OUT: RefCell<Option<GpuTimeout>> = const { RefCell::new(None) };
+}
+
+/// Whether a GPU timeout has been recorded for the current benchmark.
+pub(crate) fn gpu_timed_out() -> bool {
+    GPU_TIMED_OUT.with(|t| t.borrow().is_some())
+}
+
+/// Call `gl.finish()` and record a GPU timeout if it took longer than
+/// [`gpu_timeout`].
+fn finish_with_watchdog(gl: &web_sys::WebGl2RenderingContext) {
+    let start = js_sys::Date::now();
+    gl.finish();
+    let elapsed_ms = js_sys::Date::now() - start;
+
+    let timeout = gpu_timeout();
+    if elapsed_ms > timeout.as_millis() as f64 {
+        record_gpu_timeout(gl, timeout);
+    }
+}
+
+fn record_gpu_timeout(gl: &web_sys::WebGl2RenderingContext, timeout: std::time::Duration) {
+    let adapter = gl
+        .get_parameter(web_sys::WebGl2RenderingContext::RENDERER)
+        .ok()
+        .and_then(|v| v.as_string());
+    GPU_TIMED_OUT.with(|t| {
+        t.borrow_mut()
+            .get_or_insert(GpuTimeout { timeout, adapter });
+    });
+}
+
+/// Sync a frame as `sync_mode` says: [`finish_with_watchdog`] for
+/// [`SyncMode::Finish`], or a [`GpuFence`] to wait for with
+/// [`SyncMode::Fence`].
+pub(crate) fn sync_with_watchdog(
+    gl: &web_sys::WebGl2RenderingContext,
+    sync_mode: SyncMode,
+) -> Option<GpuFence> {
+    match sync_mode {
+        SyncMode::Finish => {
+            finish_with_watchdog(gl);
+            None
+        }
+        SyncMode::Fence => GpuFence::insert(gl),
+    }
+}
+
+/// A fence after the commands of a frame, for [`SyncMode::Fence`]. The frame
+/// is finished once [`Self::wait`] returns.
+pub struct GpuFence {
+    gl: web_sys::WebGl2RenderingContext,
+    sync: web_sys::WebGlSync,
+}
+
+impl GpuFence {
+    /// Insert a fence after the commands issued to `gl` so far and flush
+    /// them. `None` if the context is lost.
+    fn insert(gl: &web_sys::WebGl2RenderingContext) -> Option<Self> {
+        use web_sys::WebGl2RenderingContext as Gl;
+
+        let sync = gl.fence_sync(Gl::SYNC_GPU_COMMANDS_COMPLETE, 0)?;
+        gl.flush();
+        Some(Self {
+            gl: gl.clone(),
+            sync,
+        })
+    }
+
+    /// Wait until the GPU has passed the fence, polling it with
+    /// `clientWaitSync` once per task: browsers only update a fence's status
+    /// between tasks, so this includes the latency of the event loop. Gives
+    /// up and records a GPU timeout after [`gpu_timeout`].
+    pub async fn wait(self) {
+        use web_sys::WebGl2RenderingContext as Gl;
+
+        let start = js_sys::Date::now();
+        let timeout = gpu_timeout();
+        while self.gl.client_wait_sync_with_u32(&self.sync, 0, 0) == Gl::TIMEOUT_EXPIRED {
+            if js_sys::Date::now() - start > timeout.as_millis() as f64 {
+                record_gpu_timeout(&self.gl, timeout);
+                break;
+            }
+            next_task().await;
+        }
+        self.gl.delete_sync(Some(&self.sync));
+    }
+}
+
+/// Wait for `fence`, if there is one.
+async fn wait_for_fence(fence: Option<GpuFence>) {
+    if let Some(fence) = fence {
+        fence.wait().await;
+    }
+}
+
+/// Serialize `result` with its delta (see [`enable_result_deltas`]), or a
+/// failed outcome if a GPU timeout was recorded while it was measured. Clears
+/// the recorded timeout.
+fn result_or_timeout(result: &mut vello_bench_core::BenchmarkResult) -> JsValue {
+    match GPU_TIMED_OUT.with(|t| t.borrow_mut().take()) {
+        Some(timeout) => {
+            let outcome = vello_bench_core::BenchmarkOutcome::Failed {
+                id: result.id.clone(),
+                error: timeout.to_string(),
+                adapter: timeout.adapter,
+            };
+            serde_wasm_bindgen::to_value(&outcome).unwrap()
+        }
+        None => {
+            attach_delta(result);
+            serde_wasm_bindgen::to_value(result).unwrap()
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Result deltas — opt-in comparison with the last result of a benchmark on
+// this machine. The page persists the priors (e.g. in IndexedDB) and hands
+// them back on the next visit.
+// ---------------------------------------------------------------------------
+
+use vello_bench_core::result::delta::{DeltaTracker, MemoryPriorStore, PriorEntry};
+
+thread_local! {
+    /// Set by [`enable_result_deltas`].
+    static DELTAS: RefCell<Option<DeltaTracker>> = const { RefCell::new(None) };
+}
+
+/// Attach a `delta` to the results of all `run_*benchmark` functions from
+/// now on: `{ prior_mean_ns, change_pct, prior_timestamp }` against the last
+/// result of the same benchmark (ID, SIMD variant and render mode) measured
+/// in an environment with the same coarse fields as `environment`, an
+/// `EnvironmentInfo`. `change_pct` is positive when slower. Keeps priors
+/// loaded or recorded before. Returns false if `environment` is invalid.
+#[wasm_bindgen]
+pub fn enable_result_deltas(environment: JsValue) -> bool {
+    let Ok(environment) =
+        serde_wasm_bindgen::from_value::<vello_bench_core::EnvironmentInfo>(environment)
+    else {
+        return false;
+    };
+    DELTAS.with(|deltas| {
+        let mut deltas = deltas.borrow_mut();
+        let store = deltas
+            .take()
+            .map(DeltaTracker::into_store)
+            .unwrap_or_default();
+        *deltas = Some(DeltaTracker::new(&environment, store));
     });
     true
 }
 
+/// Stop attaching deltas and forget all priors.
+#[wasm_bindgen]
+pub fn disable_result_deltas() {
+    DELTAS.with(|deltas| deltas.borrow_mut().take());
+}
+
+/// Replace the priors with `entries`, as returned by
+/// [`export_prior_results`] in an earlier session. Returns false if deltas
+/// are not enabled or `entries` is invalid.
+#[wasm_bindgen]
+pub fn load_prior_results(entries: JsValue) -> bool {
+    let Ok(entries) = serde_wasm_bindgen::from_value::<Vec<PriorEntry>>(entries) else {
+        return false;
+    };
+    DELTAS.with(|deltas| match deltas.borrow_mut().as_mut() {
+        Some(tracker) => {
+            *tracker.store_mut() = MemoryPriorStore::from_entries(entries);
+            true
+        }
+        None => false,
+    })
+}
+
+/// The priors including every result measured since, as
+/// `[{ id, simd_variant, render_mode?, fingerprint, mean_ns, timestamp_ms }]`
+/// for the page to persist, or null if deltas are not enabled.
+#[wasm_bindgen]
+pub fn export_prior_results() -> JsValue {
+    DELTAS.with(|deltas| match deltas.borrow().as_ref() {
+        Some(tracker) => serde_wasm_bindgen::to_value(&tracker.store().entries()).unwrap(),
+        None => JsValue::NULL,
+    })
+}
+
+/// Set the delta of `result` if deltas are enabled, see
+/// [`enable_result_deltas`].
+fn attach_delta(result: &mut vello_bench_core::BenchmarkResult) {
+    DELTAS.with(|deltas| {
+        if let Some(tracker) = deltas.borrow_mut().as_mut() {
+            tracker.apply(result);
+        }
+    });
+}
+
+/// Set the GPU timeout in milliseconds (default 10 s).
+#[wasm_bindgen]
+pub fn set_gpu_timeout_ms(ms: u32) {
+    vello_bench_core::gpu_watchdog::set_gpu_timeout(std::time::Duration::from_millis(ms.into()));
+}
+
+/// Attach the names of the `performance.mark()` entries each run emits to its
+/// result, as `marks: { names, omitted? }` (off by default).
+#[wasm_bindgen]
+pub fn set_record_marks(record: bool) {
+    vello_bench_core::marks::set_record_marks(record);
+}
+
+use std::cell::Cell;
+
+thread_local! {
+    /// Set by [`set_sample_iterations`].
+    static SAMPLE_ITERATIONS: Cell<bool> = const { Cell::new(false) };
+    /// Set by [`set_time_budget`].
+    static TIME_BUDGET: Cell<Option<TimeBudget>> = const { Cell::new(None) };
+    /// Set by [`set_outlier_trim`].
+    static OUTLIER_POLICY: Cell<OutlierPolicy> = const { Cell::new(OutlierPolicy::Keep) };
+}
+
+/// Keep the time of every measured iteration in `statistics.samples` of each
+/// result, for histograms (off by default). Benchmarks otherwise timed as a
+/// whole are then timed per iteration; micro-benchmarks never are. A result
+/// keeps at most 100,000 samples, beyond that a random subset.
+#[wasm_bindgen]
+pub fn set_sample_iterations(enabled: bool) {
+    SAMPLE_ITERATIONS.with(|sample| sample.set(enabled));
+}
+
+/// Measure benchmarks for a time instead of a number of iterations (off by
+/// default). While set, the `warmup` and `iterations` arguments of
+/// `run_benchmark`, `run_hybrid_benchmark` and the other `run_*` functions
+/// are ignored: each run calls the benchmark for about `calibration_ms` to
+/// estimate an iteration's cost, then measures as many iterations as fit
+/// `measurement_ms`, at least one. The result's `statistics.iterations` is the
+/// count it picked. Pass 0 for `measurement_ms` to go back to iteration
+/// counts.
+#[wasm_bindgen]
+pub fn set_time_budget(calibration_ms: u32, measurement_ms: u32) {
+    let budget = (measurement_ms > 0).then_some(TimeBudget {
+        calibration_ms: calibration_ms.into(),
+        measurement_ms: measurement_ms.into(),
+    });
+    TIME_BUDGET.with(|b| b.set(budget));
+}
+
+/// Leave iterations that are outliers out of each result's `statistics`
+/// (off by default), e.g. WebGL frames that stalled on a shader compile.
+/// Iterations more than `k` interquartile ranges beyond the quartiles are
+/// removed; 1.5 is the usual choice. The result then has the statistics of all
+/// iterations in `raw_statistics` and the count in `outliers_removed`. Pass 0
+/// to keep every iteration.
+#[wasm_bindgen]
+pub fn set_outlier_trim(k: f64) {
+    let policy = if k > 0.0 {
+        OutlierPolicy::Trim { k }
+    } else {
+        OutlierPolicy::Keep
+    };
+    OUTLIER_POLICY.with(|p| p.set(policy));
+}
+
+/// A runner for `warmup` and `iterations`, or for the budget of
+/// [`set_time_budget`] if one is set, that samples iterations if
+/// [`set_sample_iterations`] is on and removes outliers if
+/// [`set_outlier_trim`] is.
+fn new_runner(warmup: u32, iterations: u32) -> BenchRunner {
+    configure_runner(match TIME_BUDGET.with(Cell::get) {
+        Some(budget) => BenchRunner::with_time_budget(budget.calibration_ms, budget.measurement_ms),
+        None => BenchRunner::new(warmup.into(), iterations.into()),
+    })
+}
+
+/// `runner`, sampling iterations and removing outliers as set by
+/// [`set_sample_iterations`] and [`set_outlier_trim`].
+fn configure_runner(mut runner: BenchRunner) -> BenchRunner {
+    runner.sample_iterations = SAMPLE_ITERATIONS.with(Cell::get);
+    runner.outlier_policy = OUTLIER_POLICY.with(Cell::get);
+    runner
+}
+
+/// Names of the `performance.mark()` entries of the last benchmark run in
+/// this instance, as `{ names, omitted? }`, whether or not they are recorded
+/// in results. Pass each name to `performance.getEntriesByName` to get the
+/// entries. Runs on a worker mark that worker's timeline, so ask the instance
+/// that ran the benchmark.
+#[wasm_bindgen]
+pub fn get_last_run_marks() -> JsValue {
+    serde_wasm_bindgen::to_value(&vello_bench_core::marks::last_run_marks()).unwrap()
+}
+
+/// How long the last image scene setup in this instance spent decoding the
+/// splash-flower JPEG and premultiplying it, as `{ decode_ns, convert_ns }`,
+/// or `null` if no image scene was set up yet.
+#[wasm_bindgen]
+pub fn get_last_image_setup_timing() -> JsValue {
+    match vello_bench_core::vello_scenes::images::last_image_setup_timing() {
+        Some(timing) => serde_wasm_bindgen::to_value(&timing).unwrap(),
+        None => JsValue::NULL,
+    }
+}
+
 /// Deserialize the scene with a [`WebGlRenderContext`], registering images
 /// directly in the WebGL backend format. Pending GPU uploads will be flushed
 /// lazily by the scene painter on first use.
-fn deserialize_scene_webgl(
+///
+/// Public, together with [`render_hybrid_frame`], for rendering scenes outside
+/// of a benchmark (see `vello_bench_core::harness`).
+pub fn deserialize_scene_webgl(
     item: &vello_bench_core::scenes::SceneItem,
 ) -> (anyrender::Scene, WebGlRenderContext) {
     let mut ctx = WebGlRenderContext::new();
@@ -99,58 +923,201 @@ fn deserialize_scene_webgl(
 // Screenshots — render a scene once and return pixel data for verification
 // ---------------------------------------------------------------------------
 
+use vello_bench_core::screenshot::{AlphaMode, Matte, PixmapPool, ScreenshotResult};
+
+thread_local! {
+    /// Pixmaps and buffers reused across CPU screenshots, so that taking one
+    /// per scene doesn't grow the WASM memory by a pixmap each time.
+    static SCREENSHOT_POOL: RefCell<PixmapPool> = RefCell::new(PixmapPool::new());
+}
+
 /// Render a scene via the CPU renderer and return the pixel data.
 /// Returns a JS object `{ width, height, data: Uint8ClampedArray }` with
 /// non-premultiplied RGBA8 pixels, compatible with `ImageData`.
+///
+/// `matte` optionally composites the render over an opaque background for
+/// docs: `"checkerboard"`, `"checkerboard:CELL"` or `"#rrggbb"` (see
+/// [`Matte::from_spec`]). Returns null for an invalid matte. The other
+/// screenshot exports take the same option.
 #[wasm_bindgen]
-pub fn screenshot_cpu(scene_name: &str) -> JsValue {
-    let result = match vello_bench_core::screenshot::render_scene_cpu(
-        scene_name,
-        fearless_simd::Level::new(),
-    ) {
-        Some(r) => r,
-        None => return JsValue::NULL,
-    };
+pub fn screenshot_cpu(
+    scene_name: &str,
+    matte: Option<String>,
+    level: Option<String>,
+) -> Result<JsValue, JsValue> {
+    let level = parse_level(level.as_deref())?;
+    Ok(pooled_screenshot(matte.as_deref(), |pool| {
+        vello_bench_core::screenshot::render_scene_cpu_in(scene_name, level, pool)
+    }))
+}
 
+/// Take a screenshot with `render` from the [`SCREENSHOT_POOL`] and convert it
+/// with [`matted_screenshot_to_js`], returning its buffers to the pool
+/// afterwards.
+fn pooled_screenshot(
+    matte: Option<&str>,
+    render: impl FnOnce(&mut PixmapPool) -> Option<ScreenshotResult>,
+) -> JsValue {
+    SCREENSHOT_POOL.with(|pool| {
+        let mut pool = pool.borrow_mut();
+        let Some(shot) = render(&mut pool) else {
+            return JsValue::NULL;
+        };
+        let js = matted_screenshot_to_js(&shot, matte);
+        pool.recycle(shot);
+        js
+    })
+}
+
+/// [`screenshot_to_js`] after compositing over `matte`, if given. Null for an
+/// invalid matte.
+fn matted_screenshot_to_js(shot: &ScreenshotResult, matte: Option<&str>) -> JsValue {
+    match matte.map(Matte::from_spec).transpose() {
+        Ok(None) => screenshot_to_js(shot),
+        Ok(Some(matte)) => screenshot_to_js(&shot.composite_over(matte)),
+        Err(_) => JsValue::NULL,
+    }
+}
+
+/// Convert a screenshot to `{ width, height, data: Uint8ClampedArray }` with
+/// non-premultiplied RGBA8 pixels, compatible with `ImageData`.
+fn screenshot_to_js(result: &ScreenshotResult) -> JsValue {
     let obj = js_sys::Object::new();
     js_sys::Reflect::set(&obj, &"width".into(), &result.width.into()).unwrap();
     js_sys::Reflect::set(&obj, &"height".into(), &result.height.into()).unwrap();
 
-    let clamped = js_sys::Uint8ClampedArray::from(result.rgba.as_slice());
+    let clamped = js_sys::Uint8ClampedArray::from(&*result.straight_rgba());
     js_sys::Reflect::set(&obj, &"data".into(), &clamped).unwrap();
 
     obj.into()
 }
 
+/// Read back the primary hybrid canvas.
+///
+/// `readPixels` returns the drawing buffer as rendered, i.e. premultiplied
+/// regardless of the context's `premultipliedAlpha` attribute (which only
+/// affects how the browser composites the canvas), with the bottom row
+/// first.
+fn read_hybrid_canvas() -> Option<ScreenshotResult> {
+    use web_sys::WebGl2RenderingContext;
+
+    HYBRID_STATE.with(|state_cell| {
+        let mut targets = state_cell.borrow_mut();
+        let state = targets.first_mut()?;
+        let width = state.canvas.width();
+        let height = state.canvas.height();
+
+        let mut pixels = vec![0_u8; (width * height * 4) as usize];
+        state
+            .renderer
+            .gl_context()
+            .read_pixels_with_opt_u8_array(
+                0,
+                0,
+                width as i32,
+                height as i32,
+                WebGl2RenderingContext::RGBA,
+                WebGl2RenderingContext::UNSIGNED_BYTE,
+                Some(&mut pixels),
+            )
+            .ok()?;
+
+        let row_bytes = (width * 4) as usize;
+        let rgba = pixels
+            .chunks_exact(row_bytes)
+            .rev()
+            .flatten()
+            .copied()
+            .collect();
+
+        Some(ScreenshotResult {
+            width,
+            height,
+            rgba,
+            alpha_mode: AlphaMode::Premultiplied,
+        })
+    })
+}
+
 /// Ensure the canvas matches the requested dimensions.
 ///
 /// If a resize is needed, `set_width`/`set_height` resets the WebGL context,
 /// invalidating all compiled shaders and uploaded textures. In that case we
 /// re-create the [`WebGlRenderer`] so it picks up the fresh GL context.
-fn ensure_canvas_size(state: &mut HybridState, width: u32, height: u32) {
+///
+/// Fails if the size exceeds the context's max texture size, or if the
+/// browser gave the canvas a smaller drawing buffer than requested, which it
+/// does instead of failing. Rendering would then silently cover only part of
+/// the scene.
+fn ensure_canvas_size(state: &mut HybridState, width: u32, height: u32) -> Result<(), String> {
+    use web_sys::WebGl2RenderingContext as Gl;
+
+    let max_size = state
+        .renderer
+        .gl_context()
+        .get_parameter(Gl::MAX_TEXTURE_SIZE)
+        .ok()
+        .and_then(|v| v.as_f64());
+    if let Some(max_size) = max_size.filter(|&max| f64::from(width.max(height)) > max) {
+        return Err(format!(
+            "A {width}×{height} canvas exceeds the WebGL max texture size of {max_size}"
+        ));
+    }
+
     if state.canvas.width() != width || state.canvas.height() != height {
         state.canvas.set_width(width);
         state.canvas.set_height(height);
         state.renderer = vello_hybrid::WebGlRenderer::new(&state.canvas);
     }
+
+    let gl = state.renderer.gl_context();
+    let (buffer_width, buffer_height) = (gl.drawing_buffer_width(), gl.drawing_buffer_height());
+    if (buffer_width, buffer_height) != (width as i32, height as i32) {
+        return Err(format!(
+            "WebGL clamped the {width}×{height} canvas to a {buffer_width}×{buffer_height} \
+             drawing buffer"
+        ));
+    }
+    Ok(())
 }
 
-/// Render a single hybrid frame: build the scene, render via WebGL, and sync.
+/// Serialize a failed outcome of benchmark `id`, for failures before it ran.
+fn failed_outcome(id: &str, error: String) -> JsValue {
+    let outcome = vello_bench_core::BenchmarkOutcome::Failed {
+        id: id.to_string(),
+        error,
+        adapter: None,
+    };
+    serde_wasm_bindgen::to_value(&outcome).unwrap()
+}
+
+/// Render a single hybrid frame: reset, build the scene, render via WebGL,
+/// and sync as `sync_mode` says. With [`SyncMode::Fence`] the frame is only
+/// finished once the returned fence has been waited for.
 ///
 /// Shared by both `render_hybrid_once` (screenshot) and `run_hybrid_benchmark`
-/// (hot loop) to ensure the same codepath.
-fn render_hybrid_frame(
+/// (hot loop) to ensure the same codepath. Per-frame state is reset at the
+/// start of the frame, matching the native backends. Does nothing once a GPU
+/// timeout has been recorded for the current benchmark.
+pub fn render_hybrid_frame(
     renderer: &mut vello_hybrid::WebGlRenderer,
     ctx: &mut WebGlRenderContext,
     scene: &anyrender::Scene,
     hybrid_scene: &mut vello_hybrid::Scene,
     render_size: &vello_hybrid::RenderSize,
-) {
+    sync_mode: SyncMode,
+) -> Option<GpuFence> {
     use anyrender::PaintScene;
     use vello_common::kurbo::Affine;
 
+    // The GPU already hung during this benchmark; don't queue more work.
+    if gpu_timed_out() {
+        return None;
+    }
+
     {
         let mut painter = WebGlScenePainter::new(ctx, renderer, hybrid_scene);
+        painter.reset();
         painter.append_scene(scene.clone(), Affine::IDENTITY);
     }
 
@@ -158,13 +1125,12 @@ fn render_hybrid_frame(
         .render(hybrid_scene, render_size)
         .expect("WebGL render failed");
 
-    renderer.gl_context().finish();
-    hybrid_scene.reset();
+    sync_with_watchdog(renderer.gl_context(), sync_mode)
 }
 
 /// Render a scene once via the WebGL hybrid renderer.
-/// After calling this, the hybrid canvas contains the rendered output.
-/// The JS side can then use `canvas.toDataURL()` to capture a screenshot.
+/// After calling this, the hybrid canvas contains the rendered output; see
+/// [`screenshot_hybrid`] for reading it back.
 /// Returns true if rendering succeeded, false otherwise.
 #[wasm_bindgen]
 pub fn render_hybrid_once(scene_name: &str) -> bool {
@@ -178,13 +1144,15 @@ pub fn render_hybrid_once(scene_name: &str) -> bool {
     let height = item.height as u32;
 
     HYBRID_STATE.with(|state_cell| {
-        let mut state_opt = state_cell.borrow_mut();
-        let state = match state_opt.as_mut() {
+        let mut targets = state_cell.borrow_mut();
+        let state = match targets.first_mut() {
             Some(s) => s,
             None => return false,
         };
 
-        ensure_canvas_size(state, width, height);
+        if ensure_canvas_size(state, width, height).is_err() {
+            return false;
+        }
 
         let (scene, mut ctx) = deserialize_scene_webgl(item);
 
@@ -197,21 +1165,90 @@ pub fn render_hybrid_once(scene_name: &str) -> bool {
             &scene,
             &mut hybrid_scene,
             &render_size,
+            SyncMode::Finish,
         );
 
         true
     })
 }
 
-/// Run a hybrid scene benchmark on the main thread using WebGL.
-/// Returns the benchmark result as a JsValue, or null if the benchmark
-/// was not found or hybrid is not initialized.
+/// Render a scene once via the WebGL hybrid renderer and return the pixel
+/// data like [`screenshot_cpu`], converted to straight alpha. Returns null on
+/// failure.
 #[wasm_bindgen]
-pub fn run_hybrid_benchmark(id: &str, warmup: u32, iterations: u32) -> JsValue {
-    // Only handle scene_hybrid/ benchmarks
-    let scene_name = match id.strip_prefix("scene_hybrid/") {
-        Some(name) => name,
-        None => return JsValue::NULL,
+pub fn screenshot_hybrid(scene_name: &str, matte: Option<String>) -> JsValue {
+    if !render_hybrid_once(scene_name) {
+        return JsValue::NULL;
+    }
+    read_hybrid_canvas().map_or(JsValue::NULL, |shot| {
+        matted_screenshot_to_js(&shot, matte.as_deref())
+    })
+}
+
+/// Stops a run of [`run_hybrid_benchmark`], [`run_hybrid_benchmark_async`]
+/// or [`run_vello_hybrid_benchmark`] it was passed to. Use a new handle for
+/// every run.
+///
+/// The run checks the handle between iterations and returns early with
+/// `cancelled: true` and the statistics of the iterations measured so far.
+/// The synchronous runs block the main thread, so there the handle can only
+/// be cancelled by code that gets to run before they end; the page can
+/// cancel [`run_hybrid_benchmark_async`] from an event handler.
+#[wasm_bindgen]
+pub struct JsCancellationHandle(CancellationToken);
+
+#[wasm_bindgen]
+impl JsCancellationHandle {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self(CancellationToken::new())
+    }
+
+    pub fn cancel(&self) {
+        self.0.cancel();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+}
+
+impl Default for JsCancellationHandle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Run a hybrid scene benchmark on the main thread using WebGL.
+/// Returns the benchmark result as a JsValue, or null if the benchmark
+/// was not found or hybrid is not initialized. Pass a
+/// [`JsCancellationHandle`] as `cancel` to be able to stop the run.
+///
+/// Frames are synced with [`SyncMode::Finish`]; a fence can only be waited
+/// for by [`run_hybrid_benchmark_async`].
+///
+/// `level` forces the SIMD level of the CPU-side scene encoding as in
+/// [`run_benchmark`]; the WebGL renderer keeps the level it was created with.
+#[wasm_bindgen]
+pub fn run_hybrid_benchmark(
+    id: &str,
+    warmup: u32,
+    iterations: u32,
+    cancel: Option<&JsCancellationHandle>,
+    level: Option<String>,
+) -> Result<JsValue, JsValue> {
+    let level = parse_level(level.as_deref())?;
+    let mut runner = new_runner(warmup, iterations);
+    runner.cancellation = cancel.map(|handle| handle.0.clone());
+    Ok(run_hybrid_with(id, &runner, level))
+}
+
+/// [`run_hybrid_benchmark`] measured by `runner`.
+fn run_hybrid_with(id: &str, runner: &BenchRunner, level: fearless_simd::Level) -> JsValue {
+    // Only handle scene_hybrid/ benchmarks
+    let scene_name = match id.strip_prefix("scene_hybrid/") {
+        Some(name) => name,
+        None => return JsValue::NULL,
     };
 
     let scenes = vello_bench_core::scenes::get_scenes();
@@ -224,23 +1261,32 @@ pub fn run_hybrid_benchmark(id: &str, warmup: u32, iterations: u32) -> JsValue {
     let height = item.height as u32;
 
     HYBRID_STATE.with(|state_cell| {
-        let mut state_opt = state_cell.borrow_mut();
-        let state = match state_opt.as_mut() {
+        let mut targets = state_cell.borrow_mut();
+        let state = match targets.first_mut() {
             Some(s) => s,
             None => return JsValue::NULL,
         };
 
-        ensure_canvas_size(state, width, height);
+        if let Err(error) = ensure_canvas_size(state, width, height) {
+            return failed_outcome(id, error);
+        }
 
         let (scene, mut ctx) = deserialize_scene_webgl(item);
 
         let render_size = vello_hybrid::RenderSize { width, height };
-        let mut hybrid_scene = vello_hybrid::Scene::new(item.width, item.height);
+        let mut hybrid_scene = vello_hybrid::Scene::new_with(
+            item.width,
+            item.height,
+            vello_hybrid::RenderSettings {
+                level,
+                ..Default::default()
+            },
+        );
 
-        let runner = BenchRunner::new(warmup.into(), iterations.into());
-        let simd_variant = vello_bench_core::simd::level_suffix(fearless_simd::Level::new());
+        let simd_variant = vello_bench_core::simd::level_suffix(level);
+        GPU_TIMED_OUT.with(|t| t.take());
 
-        let result = runner.run_with_frame_wait(
+        let mut result = runner.run_with_frame_wait(
             id,
             "scene_hybrid",
             scene_name,
@@ -253,11 +1299,254 @@ pub fn run_hybrid_benchmark(id: &str, warmup: u32, iterations: u32) -> JsValue {
                     &scene,
                     &mut hybrid_scene,
                     &render_size,
+                    SyncMode::Finish,
                 );
             },
         );
+        result.sync_mode = Some(SyncMode::Finish);
 
-        serde_wasm_bindgen::to_value(&result).unwrap()
+        result_or_timeout(&mut result)
+    })
+}
+
+/// [`run_hybrid_benchmark`], but waits for `requestAnimationFrame` between
+/// iterations instead of blocking the main thread for the whole run, and
+/// resolves to the result. The page stays responsive while it runs, so an
+/// abort button can cancel it through `cancel`. Until it resolves, the other
+/// hybrid functions find no initialized canvas.
+///
+/// `sync_mode` is `"finish"` (the default) or `"fence"`, see [`SyncMode`];
+/// the result is null for any other. With `"fence"`, time budgets and
+/// stability targets don't apply.
+#[wasm_bindgen]
+pub fn run_hybrid_benchmark_async(
+    id: String,
+    warmup: u32,
+    iterations: u32,
+    cancel: Option<&JsCancellationHandle>,
+    sync_mode: Option<String>,
+) -> js_sys::Promise {
+    let cancellation = cancel.map(|handle| handle.0.clone());
+    wasm_bindgen_futures::future_to_promise(async move {
+        let Some(sync_mode) = parse_sync_mode(sync_mode.as_deref()) else {
+            return Ok(JsValue::NULL);
+        };
+        Ok(hybrid_benchmark_async(&id, warmup, iterations, cancellation, sync_mode).await)
+    })
+}
+
+/// The [`SyncMode`] named `name`, [`SyncMode::Finish`] if there is none.
+fn parse_sync_mode(name: Option<&str>) -> Option<SyncMode> {
+    name.map_or(Some(SyncMode::default()), SyncMode::from_name)
+}
+
+async fn hybrid_benchmark_async(
+    id: &str,
+    warmup: u32,
+    iterations: u32,
+    cancellation: Option<CancellationToken>,
+    sync_mode: SyncMode,
+) -> JsValue {
+    let scene_name = match id.strip_prefix("scene_hybrid/") {
+        Some(name) => name,
+        None => return JsValue::NULL,
+    };
+
+    let scenes = vello_bench_core::scenes::get_scenes();
+    let item = match scenes.iter().find(|s| s.name == scene_name) {
+        Some(item) => item,
+        None => return JsValue::NULL,
+    };
+
+    let width = item.width as u32;
+    let height = item.height as u32;
+
+    let mut targets = TakenHybridState::take();
+    let state = match targets.0.first_mut() {
+        Some(s) => s,
+        None => return JsValue::NULL,
+    };
+
+    if let Err(error) = ensure_canvas_size(state, width, height) {
+        return failed_outcome(id, error);
+    }
+
+    let (scene, mut ctx) = deserialize_scene_webgl(item);
+
+    let render_size = vello_hybrid::RenderSize { width, height };
+    let mut hybrid_scene = vello_hybrid::Scene::new(item.width, item.height);
+
+    let mut runner = new_runner(warmup, iterations);
+    runner.cancellation = cancellation;
+    let simd_variant = vello_bench_core::simd::level_suffix(fearless_simd::Level::new());
+    GPU_TIMED_OUT.with(|t| t.take());
+
+    let mut frame = |sync_mode| {
+        render_hybrid_frame(
+            &mut state.renderer,
+            &mut ctx,
+            &scene,
+            &mut hybrid_scene,
+            &render_size,
+            sync_mode,
+        )
+    };
+    let mut result = match sync_mode {
+        SyncMode::Finish => {
+            runner
+                .run_with_frame_wait_async(
+                    id,
+                    "scene_hybrid",
+                    scene_name,
+                    simd_variant,
+                    #[inline(always)]
+                    || {
+                        frame(SyncMode::Finish);
+                    },
+                )
+                .await
+        }
+        SyncMode::Fence => {
+            runner
+                .run_with_async_frames(id, "scene_hybrid", scene_name, simd_variant, false, |_| {
+                    wait_for_fence(frame(SyncMode::Fence))
+                })
+                .await
+        }
+    };
+    result.sync_mode = Some(sync_mode);
+
+    result_or_timeout(&mut result)
+}
+
+/// The canvases of [`HYBRID_STATE`], taken out for an async run that keeps
+/// using them across frames. Dropping it puts them back, unless the page
+/// initialized new canvases in the meantime.
+struct TakenHybridState(Vec<HybridState>);
+
+impl TakenHybridState {
+    fn take() -> Self {
+        Self(HYBRID_STATE.with(|s| std::mem::take(&mut *s.borrow_mut())))
+    }
+}
+
+impl Drop for TakenHybridState {
+    fn drop(&mut self) {
+        let targets = std::mem::take(&mut self.0);
+        HYBRID_STATE.with(|s| {
+            let mut current = s.borrow_mut();
+            if current.is_empty() {
+                *current = targets;
+            }
+        });
+    }
+}
+
+use vello_bench_core::result::multi_canvas::CanvasOverhead;
+
+/// Run a `scene_hybrid/` scene on every canvas initialized via
+/// [`init_hybrid_multi`], rendering to each canvas in turn within a single
+/// iteration.
+///
+/// Each canvas gets its own deserialized scene and hybrid scene during the
+/// untimed setup. Right before the round robin, the same scene is measured on
+/// the first canvas alone, with the same renderer and settings. The result
+/// is reported under the `hybrid_multi` category as
+/// `hybrid_multi/<scene>_x<canvases>` with the canvas count as throughput
+/// and, as `canvas_overhead`, what the round robin takes beyond the canvas
+/// count times the single-canvas frame (see [`CanvasOverhead`]).
+/// Returns null if the scene is not found or no canvases are initialized.
+#[wasm_bindgen]
+pub fn run_hybrid_multi_benchmark(id: &str, warmup: u32, iterations: u32) -> JsValue {
+    let scene_name = match id.strip_prefix("scene_hybrid/") {
+        Some(name) => name,
+        None => return JsValue::NULL,
+    };
+
+    let scenes = vello_bench_core::scenes::get_scenes();
+    let item = match scenes.iter().find(|s| s.name == scene_name) {
+        Some(item) => item,
+        None => return JsValue::NULL,
+    };
+
+    let width = item.width as u32;
+    let height = item.height as u32;
+    let render_size = vello_hybrid::RenderSize { width, height };
+
+    HYBRID_STATE.with(|state_cell| {
+        let mut targets = state_cell.borrow_mut();
+        if targets.is_empty() {
+            return JsValue::NULL;
+        }
+
+        // Untimed per-canvas setup.
+        for state in targets.iter_mut() {
+            if let Err(error) = ensure_canvas_size(state, width, height) {
+                return failed_outcome(id, error);
+            }
+        }
+        let mut frames: Vec<_> = targets
+            .iter_mut()
+            .map(|state| {
+                let (scene, ctx) = deserialize_scene_webgl(item);
+                let hybrid_scene = vello_hybrid::Scene::new(item.width, item.height);
+                (&mut state.renderer, ctx, scene, hybrid_scene)
+            })
+            .collect();
+        let canvas_count = frames.len();
+
+        let name = format!("{scene_name}_x{canvas_count}");
+        let runner = new_runner(warmup, iterations);
+        let simd_variant = vello_bench_core::simd::level_suffix(fearless_simd::Level::new());
+        GPU_TIMED_OUT.with(|t| t.take());
+
+        let single = runner.run_with_frame_wait(
+            id,
+            "scene_hybrid",
+            scene_name,
+            simd_variant,
+            #[inline(always)]
+            || {
+                let (renderer, ctx, scene, hybrid_scene) = &mut frames[0];
+                render_hybrid_frame(
+                    renderer,
+                    ctx,
+                    scene,
+                    hybrid_scene,
+                    &render_size,
+                    SyncMode::Finish,
+                );
+            },
+        );
+
+        let mut result = runner.run_with_frame_wait(
+            &format!("hybrid_multi/{name}"),
+            "hybrid_multi",
+            &name,
+            simd_variant,
+            #[inline(always)]
+            || {
+                for (renderer, ctx, scene, hybrid_scene) in &mut frames {
+                    render_hybrid_frame(
+                        renderer,
+                        ctx,
+                        scene,
+                        hybrid_scene,
+                        &render_size,
+                        SyncMode::Finish,
+                    );
+                }
+            },
+        );
+        result.throughput = Some(vello_bench_core::Throughput::Elements(canvas_count as u64));
+        result.sync_mode = Some(SyncMode::Finish);
+        result.canvas_overhead = CanvasOverhead::new(
+            canvas_count as u32,
+            single.statistics.mean_ns,
+            result.statistics.mean_ns,
+        );
+
+        result_or_timeout(&mut result)
     })
 }
 
@@ -272,35 +1561,50 @@ mod webgl_renderer;
 // Programmatic vello scene benchmarks / screenshots — WebGL hybrid backend
 // ---------------------------------------------------------------------------
 
-use vello_bench_core::vello_scenes::{draw_scene, get_vello_scenes, setup_scene};
+use vello_bench_core::vello_scenes::find_scene;
 
 /// Run a programmatic vello scene benchmark via the WebGL hybrid renderer.
-/// Returns the benchmark result as a JsValue, or null if not found.
+/// Returns the benchmark result as a JsValue, or null if not found. Pass a
+/// [`JsCancellationHandle`] as `cancel` to be able to stop the run. Frames
+/// are synced with [`SyncMode::Finish`]; see
+/// [`run_vello_hybrid_benchmark_async`] for fences.
 #[wasm_bindgen]
 pub fn run_vello_hybrid_benchmark(
     id: &str,
     warmup: u32,
     iterations: u32,
+    cancel: Option<&JsCancellationHandle>,
 ) -> JsValue {
+    let mut runner = new_runner(warmup, iterations);
+    runner.cancellation = cancel.map(|handle| handle.0.clone());
+    run_vello_hybrid_with(id, &runner)
+}
+
+/// [`run_vello_hybrid_benchmark`] measured by `runner`.
+fn run_vello_hybrid_with(id: &str, runner: &BenchRunner) -> JsValue {
     let scene_name = match id.strip_prefix("vello_hybrid/") {
         Some(name) => name,
         None => return JsValue::NULL,
     };
 
-    let scenes = get_vello_scenes();
-    let info = match scenes.iter().find(|s| s.name == scene_name) {
-        Some(info) => info,
+    let registration = match find_scene::<webgl_renderer::WebGlHybridRenderer>(scene_name)
+        .filter(|s| !s.cpu_only)
+    {
+        Some(registration) => registration,
         None => return JsValue::NULL,
     };
+    let info = &registration.info;
 
     HYBRID_STATE.with(|state_cell| {
-        let mut state_opt = state_cell.borrow_mut();
-        let state = match state_opt.as_mut() {
+        let mut targets = state_cell.borrow_mut();
+        let state = match targets.first_mut() {
             Some(s) => s,
             None => return JsValue::NULL,
         };
 
-        ensure_canvas_size(state, info.width.into(), info.height.into());
+        if let Err(error) = ensure_canvas_size(state, info.width.into(), info.height.into()) {
+            return failed_outcome(id, error);
+        }
 
         let mut hybrid = webgl_renderer::WebGlHybridRenderer::from_state(
             info.width,
@@ -309,23 +1613,173 @@ pub fn run_vello_hybrid_benchmark(
         );
 
         // Setup phase — image uploads etc. (not timed).
-        let scene_state =
-            setup_scene(scene_name, &mut hybrid).expect("vello scene not found in setup");
+        let scene_state = registration.setup(&mut hybrid);
 
-        let runner = BenchRunner::new(warmup.into(), iterations.into());
         let simd_variant = vello_bench_core::simd::level_suffix(fearless_simd::Level::new());
+        GPU_TIMED_OUT.with(|t| t.take());
 
-        let result = runner.run_with_frame_wait(
+        let mut frame_index = 0;
+        let mut result = runner.run_with_frame_wait_and_warmup_proxy(
             id,
             "vello_hybrid",
             scene_name,
             simd_variant,
+            registration.has_warmup_proxy,
             #[inline(always)]
-            || {
-                draw_scene(scene_name, scene_state.as_ref(), &mut hybrid);
+            |frame| {
+                registration.draw_frame(scene_state.as_ref(), &mut hybrid, frame, frame_index);
+                frame_index += 1;
                 hybrid.render_and_sync();
             },
         );
+        result.throughput = registration
+            .elements
+            .map(vello_bench_core::Throughput::Elements);
+        result.animated = registration.animated;
+        result.sync_mode = Some(SyncMode::Finish);
+
+        result_or_timeout(&mut result)
+    })
+}
+
+/// [`run_vello_hybrid_benchmark`] with frames synced as `sync_mode` says,
+/// `"finish"` or `"fence"` (see [`SyncMode`]). Resolves to the result, or
+/// null if the benchmark or sync mode is not found. Every frame, of the
+/// warm-up too, is timed until it is synced, and time budgets and stability
+/// targets don't apply. Until it resolves, the other hybrid functions find
+/// no initialized canvas.
+#[wasm_bindgen]
+pub fn run_vello_hybrid_benchmark_async(
+    id: String,
+    warmup: u32,
+    iterations: u32,
+    cancel: Option<&JsCancellationHandle>,
+    sync_mode: String,
+) -> js_sys::Promise {
+    let cancellation = cancel.map(|handle| handle.0.clone());
+    wasm_bindgen_futures::future_to_promise(async move {
+        let Some(sync_mode) = SyncMode::from_name(&sync_mode) else {
+            return Ok(JsValue::NULL);
+        };
+        Ok(vello_hybrid_benchmark_async(&id, warmup, iterations, cancellation, sync_mode).await)
+    })
+}
+
+async fn vello_hybrid_benchmark_async(
+    id: &str,
+    warmup: u32,
+    iterations: u32,
+    cancellation: Option<CancellationToken>,
+    sync_mode: SyncMode,
+) -> JsValue {
+    let scene_name = match id.strip_prefix("vello_hybrid/") {
+        Some(name) => name,
+        None => return JsValue::NULL,
+    };
+
+    let registration = match find_scene::<webgl_renderer::WebGlHybridRenderer>(scene_name)
+        .filter(|s| !s.cpu_only)
+    {
+        Some(registration) => registration,
+        None => return JsValue::NULL,
+    };
+    let info = &registration.info;
+
+    let mut targets = TakenHybridState::take();
+    let state = match targets.0.first_mut() {
+        Some(s) => s,
+        None => return JsValue::NULL,
+    };
+
+    if let Err(error) = ensure_canvas_size(state, info.width.into(), info.height.into()) {
+        return failed_outcome(id, error);
+    }
+
+    let mut hybrid = webgl_renderer::WebGlHybridRenderer::from_state(
+        info.width,
+        info.height,
+        &mut state.renderer,
+    );
+    hybrid.set_sync_mode(sync_mode);
+
+    // Setup phase — image uploads etc. (not timed).
+    let scene_state = registration.setup(&mut hybrid);
+
+    let mut runner = new_runner(warmup, iterations);
+    runner.cancellation = cancellation;
+    let simd_variant = vello_bench_core::simd::level_suffix(fearless_simd::Level::new());
+    GPU_TIMED_OUT.with(|t| t.take());
+
+    let mut frame_index = 0;
+    let mut result = runner
+        .run_with_async_frames(
+            id,
+            "vello_hybrid",
+            scene_name,
+            simd_variant,
+            registration.has_warmup_proxy,
+            |frame| {
+                registration.draw_frame(scene_state.as_ref(), &mut hybrid, frame, frame_index);
+                frame_index += 1;
+                wait_for_fence(hybrid.render_and_sync())
+            },
+        )
+        .await;
+    result.throughput = registration
+        .elements
+        .map(vello_bench_core::Throughput::Elements);
+    result.animated = registration.animated;
+    result.sync_mode = Some(sync_mode);
+
+    result_or_timeout(&mut result)
+}
+
+/// Run a `hybrid_internals/` benchmark via the WebGL hybrid renderer.
+/// Returns the benchmark result as a JsValue, or null if not found.
+#[wasm_bindgen]
+pub fn run_hybrid_internals_benchmark(id: &str, warmup: u32, iterations: u32) -> JsValue {
+    use vello_bench_core::benchmarks::hybrid_internals::{self, HEIGHT, WIDTH};
+    use vello_bench_core::vello_scenes::images::{draw_overlapping_images, setup_image_grid};
+
+    let Some(name) = id.strip_prefix("hybrid_internals/") else {
+        return JsValue::NULL;
+    };
+    let Some(count) = hybrid_internals::reset_command_count(name) else {
+        return JsValue::NULL;
+    };
+
+    HYBRID_STATE.with(|state_cell| {
+        let mut targets = state_cell.borrow_mut();
+        let state = match targets.first_mut() {
+            Some(s) => s,
+            None => return JsValue::NULL,
+        };
+
+        if let Err(error) = ensure_canvas_size(state, WIDTH.into(), HEIGHT.into()) {
+            return failed_outcome(id, error);
+        }
+
+        let mut hybrid =
+            webgl_renderer::WebGlHybridRenderer::from_state(WIDTH, HEIGHT, &mut state.renderer);
+
+        // Setup phase — image upload (not timed).
+        let image_state = setup_image_grid(&mut hybrid);
+
+        let runner = new_runner(warmup, iterations);
+        let simd_variant = vello_bench_core::simd::level_suffix(fearless_simd::Level::new());
+
+        let mut result = runner.run_with_setup(
+            id,
+            "hybrid_internals",
+            name,
+            simd_variant,
+            &mut hybrid,
+            |hybrid| draw_overlapping_images(&image_state, hybrid, count),
+            #[inline(always)]
+            |hybrid| hybrid.reset(),
+        );
+        result.throughput = Some(vello_bench_core::Throughput::Elements(count.into()));
+        attach_delta(&mut result);
 
         serde_wasm_bindgen::to_value(&result).unwrap()
     })
@@ -336,20 +1790,24 @@ pub fn run_vello_hybrid_benchmark(
 /// Returns true on success.
 #[wasm_bindgen]
 pub fn render_vello_hybrid_once(scene_name: &str) -> bool {
-    let scenes = get_vello_scenes();
-    let info = match scenes.iter().find(|s| s.name == scene_name) {
-        Some(info) => info,
+    let registration = match find_scene::<webgl_renderer::WebGlHybridRenderer>(scene_name)
+        .filter(|s| !s.cpu_only)
+    {
+        Some(registration) => registration,
         None => return false,
     };
+    let info = &registration.info;
 
     HYBRID_STATE.with(|state_cell| {
-        let mut state_opt = state_cell.borrow_mut();
-        let state = match state_opt.as_mut() {
+        let mut targets = state_cell.borrow_mut();
+        let state = match targets.first_mut() {
             Some(s) => s,
             None => return false,
         };
 
-        ensure_canvas_size(state, info.width.into(), info.height.into());
+        if ensure_canvas_size(state, info.width.into(), info.height.into()).is_err() {
+            return false;
+        }
 
         let mut hybrid = webgl_renderer::WebGlHybridRenderer::from_state(
             info.width,
@@ -357,33 +1815,423 @@ pub fn render_vello_hybrid_once(scene_name: &str) -> bool {
             &mut state.renderer,
         );
 
-        let scene_state =
-            setup_scene(scene_name, &mut hybrid).expect("vello scene not found");
-        draw_scene(scene_name, scene_state.as_ref(), &mut hybrid);
+        let scene_state = registration.setup(&mut hybrid);
+        registration.draw(scene_state.as_ref(), &mut hybrid);
         hybrid.render_and_sync();
 
         true
     })
 }
 
+// ---------------------------------------------------------------------------
+// Frame budget demo — largest scene count that renders within a budget
+// ---------------------------------------------------------------------------
+
+use vello_bench_core::frame_budget::{CountSearch, probe_frame_ms};
+use vello_bench_core::vello_scenes::{
+    COUNTED_SCENE_HEIGHT, COUNTED_SCENE_WIDTH, ImageGridState, counted_draw_fn, setup_image_grid,
+};
+
+/// Find the largest count of the counted scene `family` (e.g.
+/// `"tiled_flowers"`) that renders within `budget_ms` per frame on `backend`:
+/// `"cpu"`, or `"hybrid"` for WebGL (main thread only, after
+/// [`init_hybrid`]). Resolves to `{ count, time_ms, probes }`, see
+/// [`BudgetResult`](vello_bench_core::frame_budget::BudgetResult).
+///
+/// Yields to the event loop between probes, so the page stays responsive
+/// while it searches.
+#[wasm_bindgen]
+pub async fn find_max_count_for_budget(
+    family: String,
+    budget_ms: f64,
+    backend: String,
+) -> Result<JsValue, JsValue> {
+    let mut search = CountSearch::new(budget_ms).map_err(|e| JsValue::from_str(&e))?;
+    let mut cpu = match backend.as_str() {
+        "cpu" => Some(CpuBudgetTarget::new(&family)?),
+        "hybrid" => None,
+        _ => {
+            return Err(JsValue::from_str(&format!(
+                "Unknown backend: {backend} (expected \"cpu\" or \"hybrid\")"
+            )));
+        }
+    };
+
+    GPU_TIMED_OUT.with(|t| t.take());
+    while let Some(count) = search.next_count() {
+        let time_ms = match &mut cpu {
+            Some(cpu) => cpu.probe(count),
+            None => probe_hybrid(&family, count)?,
+        };
+        if let Some(timeout) = GPU_TIMED_OUT.with(|t| t.take()) {
+            return Err(JsValue::from_str(&timeout.to_string()));
+        }
+        search.record(count, time_ms);
+        yield_to_event_loop().await;
+    }
+
+    serde_wasm_bindgen::to_value(&search.result()).map_err(JsValue::from)
+}
+
+/// Vello CPU state kept across the probes of a budget search.
+struct CpuBudgetTarget {
+    ctx: vello_cpu::RenderContext,
+    pixmap: vello_cpu::Pixmap,
+    images: ImageGridState,
+    draw: vello_bench_core::vello_scenes::CountedDrawFn<vello_cpu::RenderContext>,
+}
+
+impl CpuBudgetTarget {
+    fn new(family: &str) -> Result<Self, JsValue> {
+        use vello_bench_core::renderer::Renderer;
+
+        let draw = counted_draw_fn(family).ok_or_else(|| unknown_family(family))?;
+        let mut ctx: vello_cpu::RenderContext = Renderer::new(
+            COUNTED_SCENE_WIDTH,
+            COUNTED_SCENE_HEIGHT,
+            0,
+            fearless_simd::Level::new(),
+            vello_cpu::RenderMode::default(),
+        );
+        let images = setup_image_grid(&mut ctx);
+        Ok(Self {
+            ctx,
+            pixmap: vello_cpu::Pixmap::new(COUNTED_SCENE_WIDTH, COUNTED_SCENE_HEIGHT),
+            images,
+            draw,
+        })
+    }
+
+    fn probe(&mut self, count: u32) -> f64 {
+        let pixmap = &mut self.pixmap;
+        probe_frame_ms(&mut self.ctx, &self.images, self.draw, count, |ctx| {
+            ctx.flush();
+            ctx.render_to_pixmap(pixmap);
+        })
+    }
+}
+
+/// Median frame time of `count` elements of `family` on the primary hybrid
+/// canvas. The canvas can't stay borrowed across the search's yields, so
+/// every probe uploads the image again (untimed).
+fn probe_hybrid(family: &str, count: u32) -> Result<f64, JsValue> {
+    HYBRID_STATE.with(|state_cell| {
+        let mut targets = state_cell.borrow_mut();
+        let state = targets
+            .first_mut()
+            .ok_or_else(|| JsValue::from_str("Hybrid renderer is not initialized"))?;
+
+        ensure_canvas_size(
+            state,
+            COUNTED_SCENE_WIDTH.into(),
+            COUNTED_SCENE_HEIGHT.into(),
+        )
+        .map_err(|e| JsValue::from_str(&e))?;
+
+        let mut hybrid = webgl_renderer::WebGlHybridRenderer::from_state(
+            COUNTED_SCENE_WIDTH,
+            COUNTED_SCENE_HEIGHT,
+            &mut state.renderer,
+        );
+        let draw = counted_draw_fn(family).ok_or_else(|| unknown_family(family))?;
+        let images = setup_image_grid(&mut hybrid);
+
+        let frame_ms = probe_frame_ms(&mut hybrid, &images, draw, count, |hybrid| {
+            hybrid.render_and_sync();
+        });
+        Ok(frame_ms)
+    })
+}
+
+fn unknown_family(family: &str) -> JsValue {
+    JsValue::from_str(&format!("Unknown scene family: {family}"))
+}
+
+/// Resolve in a new task, through a `MessageChannel`. Unlike
+/// [`yield_to_event_loop`], repeated calls aren't clamped to 4 ms.
+async fn next_task() {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        let Ok(channel) = web_sys::MessageChannel::new() else {
+            let _ = resolve.call0(&JsValue::NULL);
+            return;
+        };
+        channel.port1().set_onmessage(Some(&resolve));
+        let _ = channel.port2().post_message(&JsValue::NULL);
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
+
+/// Resolve after a `setTimeout(0)`, letting the browser handle input and
+/// paint in between. Works in both Window and Worker contexts.
+async fn yield_to_event_loop() {
+    use wasm_bindgen::JsCast;
+
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        let global = js_sys::global();
+        let set_timeout = js_sys::Reflect::get(&global, &JsValue::from_str("setTimeout"))
+            .ok()
+            .and_then(|f| f.dyn_into::<js_sys::Function>().ok());
+        match set_timeout {
+            Some(set_timeout) => {
+                let _ = set_timeout.call2(&global, &resolve, &JsValue::from(0));
+            }
+            None => {
+                let _ = resolve.call0(&JsValue::NULL);
+            }
+        }
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
+
 /// Render a programmatic vello scene via CPU and return pixel data.
 /// Returns a JS object `{ width, height, data: Uint8ClampedArray }`.
 #[wasm_bindgen]
-pub fn screenshot_vello_cpu(scene_name: &str) -> JsValue {
-    let result = match vello_bench_core::screenshot::render_vello_scene_cpu(
-        scene_name,
-        fearless_simd::Level::new(),
-    ) {
-        Some(r) => r,
-        None => return JsValue::NULL,
+pub fn screenshot_vello_cpu(scene_name: &str, matte: Option<String>) -> JsValue {
+    pooled_screenshot(matte.as_deref(), |pool| {
+        vello_bench_core::screenshot::render_vello_scene_cpu_in(
+            scene_name,
+            fearless_simd::Level::new(),
+            pool,
+        )
+    })
+}
+
+/// Render a programmatic vello scene once via the WebGL hybrid renderer and
+/// return the pixel data like [`screenshot_vello_cpu`], converted to straight
+/// alpha. Returns null on failure.
+#[wasm_bindgen]
+pub fn screenshot_vello_hybrid(scene_name: &str, matte: Option<String>) -> JsValue {
+    if !render_vello_hybrid_once(scene_name) {
+        return JsValue::NULL;
+    }
+    read_hybrid_canvas().map_or(JsValue::NULL, |shot| {
+        matted_screenshot_to_js(&shot, matte.as_deref())
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Frame pacing — vsync statistics of presented frames
+// ---------------------------------------------------------------------------
+
+/// Analyze the pacing of frames presented at `timestamps_ms`, e.g. the
+/// `requestAnimationFrame` timestamps of a continuously rendering loop.
+/// Returns a `FramePacing` object: the detected refresh rate, the share of
+/// frames on time, counts of frames that took 2×, 3× or more refresh
+/// periods, and the longest stall, see
+/// [`FramePacing`](vello_bench_core::frame_pacing::FramePacing).
+/// Throws if the timestamps aren't increasing or there are fewer than three.
+#[wasm_bindgen]
+pub fn analyze_frame_pacing(timestamps_ms: &[f64]) -> Result<JsValue, JsValue> {
+    let pacing = vello_bench_core::frame_pacing::FramePacing::from_timestamps(timestamps_ms)
+        .map_err(|e| JsValue::from_str(&e))?;
+    serde_wasm_bindgen::to_value(&pacing).map_err(JsValue::from)
+}
+
+// ---------------------------------------------------------------------------
+// Result anonymization, aggregation and encoding
+// ---------------------------------------------------------------------------
+
+/// Anonymize a JSON-encoded `ResultFile` with the strictest policy before
+/// upload. Returns the anonymized JSON, or `undefined` if parsing fails.
+#[wasm_bindgen]
+pub fn anonymize_results(json: &str) -> Option<String> {
+    use vello_bench_core::result::{AnonymizePolicy, ResultFile, anonymize};
+
+    let mut file: ResultFile = serde_json::from_str(json).ok()?;
+    anonymize(&mut file, AnonymizePolicy::default());
+    serde_json::to_string(&file).ok()
+}
+
+/// Aggregate a JSON array of `ResultFile`s into an `AggregateReport`.
+/// Returns the report as JSON, or `undefined` if parsing fails.
+#[wasm_bindgen]
+pub fn aggregate_results(json: &str) -> Option<String> {
+    use vello_bench_core::result::{ResultFile, aggregate};
+
+    let files: Vec<ResultFile> = serde_json::from_str(json).ok()?;
+    serde_json::to_string(&aggregate(&files)).ok()
+}
+
+/// Encode a JSON-encoded `ResultFile` in the compact binary format for
+/// upload. Returns `undefined` if parsing fails.
+#[wasm_bindgen]
+pub fn export_results_binary(json: &str) -> Option<Vec<u8>> {
+    use vello_bench_core::result::ResultFile;
+
+    let file: ResultFile = serde_json::from_str(json).ok()?;
+    Some(file.to_bytes())
+}
+
+/// Decode a `ResultFile` in either the binary or the JSON format and return
+/// it as JSON. Returns `undefined` if parsing fails.
+#[wasm_bindgen]
+pub fn import_results(bytes: &[u8]) -> Option<String> {
+    use vello_bench_core::result::ResultFile;
+
+    let file = ResultFile::parse(bytes).ok()?;
+    serde_json::to_string(&file).ok()
+}
+
+/// The environment of this instance, with `label` unless it is empty: the
+/// SIMD level, user agent, core count and crate version (see
+/// `EnvironmentInfo::capture`).
+fn captured_environment(label: &str) -> vello_bench_core::EnvironmentInfo {
+    vello_bench_core::EnvironmentInfo {
+        label: (!label.is_empty()).then(|| label.to_string()),
+        ..vello_bench_core::EnvironmentInfo::capture(fearless_simd::Level::new())
+    }
+}
+
+/// The `EnvironmentInfo` of this instance, with `label` unless it is empty,
+/// for result files exported by the UI.
+#[wasm_bindgen]
+pub fn capture_environment(label: &str) -> JsValue {
+    serde_wasm_bindgen::to_value(&captured_environment(label)).unwrap()
+}
+
+/// A `ResultFile` of `results`, an array of benchmark results run in this
+/// instance, as pretty-printed JSON for archiving the run. The environment
+/// is that of [`capture_environment`]. The run starts at the earliest
+/// result's `timestamp_ms` and finishes now. Throws if `results` aren't
+/// benchmark results.
+#[wasm_bindgen]
+pub fn export_suite_report(results: JsValue, label: &str) -> Result<String, JsValue> {
+    use vello_bench_core::result::ResultFile;
+
+    let results: Vec<vello_bench_core::BenchmarkResult> =
+        serde_wasm_bindgen::from_value(results)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse results: {e}")))?;
+    let finished_ms = js_sys::Date::now() as u64;
+    let environment = captured_environment(label);
+
+    let file = ResultFile {
+        schema_version: ResultFile::SCHEMA_VERSION,
+        started_ms: results
+            .iter()
+            .map(|result| result.timestamp_ms)
+            .min()
+            .unwrap_or(finished_ms),
+        finished_ms,
+        environment,
+        results,
+        memory_timeline: Vec::new(),
+        partial: false,
+        config: None,
+        health: None,
     };
+    file.to_json().map_err(|e| JsValue::from_str(&e))
+}
 
-    let obj = js_sys::Object::new();
-    js_sys::Reflect::set(&obj, &"width".into(), &result.width.into()).unwrap();
-    js_sys::Reflect::set(&obj, &"height".into(), &result.height.into()).unwrap();
+// ---------------------------------------------------------------------------
+// Reference results
+// ---------------------------------------------------------------------------
 
-    let clamped = js_sys::Uint8ClampedArray::from(result.rgba.as_slice());
-    js_sys::Reflect::set(&obj, &"data".into(), &clamped).unwrap();
+/// Names of the reference result files embedded in the crate, e.g.
+/// `"desktop_reference"`.
+#[wasm_bindgen]
+pub fn list_references() -> JsValue {
+    serde_wasm_bindgen::to_value(&vello_bench_core::reference::reference_names()).unwrap()
+}
 
-    obj.into()
+/// The embedded reference result file `name` as JSON, or `undefined` if there
+/// is none.
+#[wasm_bindgen]
+pub fn get_reference_results(name: &str) -> Option<String> {
+    let file = vello_bench_core::reference::get_reference_results(name)?;
+    serde_json::to_string(&file).ok()
+}
+
+/// Compare two JSON-encoded `ResultFile`s of the same machine, e.g. a run of
+/// a pull request against one of `main`. Returns the `ComparisonReport` as
+/// JSON: per benchmark and SIMD variant the ratio and change in percent,
+/// flagged `regression` or `improvement` beyond `threshold` percent, and the
+/// benchmarks only one file has (see
+/// `vello_bench_core::result::compare::compare_reports`). Throws if either
+/// file doesn't parse.
+#[wasm_bindgen]
+pub fn compare_reports_json(
+    baseline_json: &str,
+    candidate_json: &str,
+    threshold: f64,
+) -> Result<String, JsValue> {
+    use vello_bench_core::result::ResultFile;
+    use vello_bench_core::result::compare::compare_reports;
+
+    let baseline = ResultFile::from_json(baseline_json)
+        .map_err(|e| JsValue::from_str(&format!("Baseline: {e}")))?;
+    let candidate = ResultFile::from_json(candidate_json)
+        .map_err(|e| JsValue::from_str(&format!("Candidate: {e}")))?;
+    let report = compare_reports(&baseline, &candidate, threshold);
+    Ok(serde_json::to_string(&report).unwrap())
+}
+
+/// Compare a JSON-encoded `ResultFile` against the embedded reference `name`.
+/// Returns the `Comparison` as JSON: the ratio to the reference per
+/// benchmark, their geometric mean, both environments for labeling, and
+/// `config_warnings` if the runs were configured differently.
+/// Throws if the results don't parse or the reference doesn't exist.
+#[wasm_bindgen]
+pub fn compare_to_reference(json: &str, name: &str) -> Result<String, JsValue> {
+    use vello_bench_core::result::ResultFile;
+
+    let file: ResultFile = serde_json::from_str(json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse results: {e}")))?;
+    let comparison = vello_bench_core::reference::compare_to_reference(&file, name)
+        .map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_json::to_string(&comparison).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vello_bench_core::Platform;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn is_cached() -> bool {
+        BENCHMARK_LIST.with(|list| list.borrow().is_some())
+    }
+
+    fn cached_json() -> String {
+        with_benchmark_list(|benchmarks| serde_json::to_string(benchmarks).unwrap())
+    }
+
+    #[wasm_bindgen_test]
+    fn cached_and_fresh_lists_match() {
+        let cached = cached_json();
+        assert!(is_cached());
+        assert_eq!(cached_json(), cached);
+
+        let fresh = vello_bench_core::get_benchmark_availability(&[
+            Platform::WasmMainThread,
+            Platform::WasmWorker,
+        ]);
+        assert_eq!(serde_json::to_string(&fresh).unwrap(), cached);
+    }
+
+    #[wasm_bindgen_test]
+    fn invalidation_recomputes_the_list() {
+        let before = cached_json();
+        invalidate_benchmark_list();
+        assert!(!is_cached());
+        assert_eq!(cached_json(), before);
+        assert!(is_cached());
+    }
+
+    #[wasm_bindgen_test]
+    fn fast_list_skips_the_availability() {
+        invalidate_benchmark_list();
+        let fast = fast_benchmark_list();
+        assert!(!is_cached());
+
+        with_benchmark_list(|benchmarks| {
+            assert_eq!(fast.len(), benchmarks.len());
+            for (entry, full) in fast.iter().zip(benchmarks) {
+                assert_eq!(entry.id, full.info.id);
+                assert_eq!(entry.category, full.info.category);
+                assert_eq!(entry.tags, full.info.tags);
+            }
+        });
+    }
 }
@@ -8,6 +8,8 @@
 use std::cell::RefCell;
 use std::sync::Arc;
 
+use vello_bench_core::SyncMode;
+use vello_bench_core::readback::{check_pixmap_size, copy_unpadded_rows};
 use vello_bench_core::renderer::Renderer;
 use vello_common::filter_effects::Filter;
 use vello_common::glyph::GlyphRunBuilder;
@@ -20,6 +22,8 @@ use vello_common::recording::{Recordable, Recorder, Recording};
 use vello_cpu::RenderMode;
 use vello_hybrid::Scene;
 
+use crate::GpuFence;
+
 /// A wrapper around a borrowed [`vello_hybrid::WebGlRenderer`] and a
 /// [`Scene`] that implements [`Renderer`].
 ///
@@ -28,6 +32,7 @@ use vello_hybrid::Scene;
 pub(crate) struct WebGlHybridRenderer<'a> {
     scene: Scene,
     renderer: RefCell<&'a mut vello_hybrid::WebGlRenderer>,
+    sync_mode: SyncMode,
 }
 
 impl<'a> WebGlHybridRenderer<'a> {
@@ -41,12 +46,33 @@ impl<'a> WebGlHybridRenderer<'a> {
         Self {
             scene,
             renderer: RefCell::new(renderer),
+            sync_mode: SyncMode::default(),
         }
     }
 
-    /// Render the current scene via WebGL and sync. Used in the benchmark
-    /// hot loop (no pixel readback).
-    pub fn render_and_sync(&mut self) {
+    /// Set how [`Self::render_and_sync`] waits for the GPU.
+    pub fn set_sync_mode(&mut self, sync_mode: SyncMode) {
+        self.sync_mode = sync_mode;
+    }
+
+    /// Discard everything drawn into the scene without rendering it.
+    pub fn reset(&mut self) {
+        self.scene.reset();
+    }
+
+    /// Render the current scene via WebGL and sync as set by
+    /// [`Self::set_sync_mode`]. Used in the benchmark hot loop (no pixel
+    /// readback). With [`SyncMode::Fence`] the frame is only finished once
+    /// the returned fence has been waited for.
+    ///
+    /// Skips rendering once a GPU timeout has been recorded; see
+    /// [`crate::sync_with_watchdog`].
+    pub fn render_and_sync(&mut self) -> Option<GpuFence> {
+        if crate::gpu_timed_out() {
+            self.scene.reset();
+            return None;
+        }
+
         let render_size = vello_hybrid::RenderSize {
             width: self.scene.width().into(),
             height: self.scene.height().into(),
@@ -57,8 +83,10 @@ impl<'a> WebGlHybridRenderer<'a> {
             .render(&self.scene, &render_size)
             .expect("WebGL render failed");
 
-        self.renderer.borrow_mut().gl_context().finish();
+        let fence =
+            crate::sync_with_watchdog(self.renderer.borrow_mut().gl_context(), self.sync_mode);
         self.scene.reset();
+        fence
     }
 }
 
@@ -189,10 +217,17 @@ impl Renderer for WebGlHybridRenderer<'_> {
     }
 
     fn render_to_pixmap(&self, pixmap: &mut Pixmap) {
+        if let Err(e) = self.try_render_to_pixmap(pixmap) {
+            panic!("{e}");
+        }
+    }
+
+    fn try_render_to_pixmap(&self, pixmap: &mut Pixmap) -> Result<(), String> {
         use web_sys::WebGl2RenderingContext;
 
         let width = self.scene.width();
         let height = self.scene.height();
+        check_pixmap_size(pixmap, width, height)?;
 
         let render_size = vello_hybrid::RenderSize {
             width: width.into(),
@@ -202,11 +237,14 @@ impl Renderer for WebGlHybridRenderer<'_> {
         let mut renderer = self.renderer.borrow_mut();
         renderer
             .render(&self.scene, &render_size)
-            .expect("WebGL render failed");
+            .map_err(|e| format!("WebGL render failed: {e:?}"))?;
 
         let gl = renderer.gl_context();
 
-        let mut pixels = vec![0_u8; (width as usize) * (height as usize) * 4];
+        // RGBA rows are a multiple of 4 bytes, so with the default
+        // `PACK_ALIGNMENT` of 4 they come back without padding.
+        let row_bytes = usize::from(width) * 4;
+        let mut pixels = vec![0_u8; row_bytes * usize::from(height)];
         gl.read_pixels_with_opt_u8_array(
             0,
             0,
@@ -216,10 +254,15 @@ impl Renderer for WebGlHybridRenderer<'_> {
             WebGl2RenderingContext::UNSIGNED_BYTE,
             Some(&mut pixels),
         )
-        .unwrap();
+        .map_err(|e| format!("readPixels failed: {e:?}"))?;
 
-        let pixmap_data = pixmap.data_as_u8_slice_mut();
-        pixmap_data.copy_from_slice(&pixels);
+        copy_unpadded_rows(
+            &pixels,
+            row_bytes,
+            width.into(),
+            height.into(),
+            pixmap.data_as_u8_slice_mut(),
+        )
     }
 
     fn width(&self) -> u16 {